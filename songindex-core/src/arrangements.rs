@@ -0,0 +1,267 @@
+//! Arrangements: "Wonderwall (easy)" and "Wonderwall (fingerstyle)" as
+//! versions of one piece (`werke`). Artist and key belong to the piece and
+//! are kept the same on every arrangement; tags, notes and files stay with
+//! each arrangement.
+
+use crate::db::{query_rows, LogErr};
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+
+/// The piece a group of arrangements belongs to.
+#[derive(Debug, Clone)]
+pub struct Work {
+    pub id: i64,
+    pub titel: String,
+    pub artist: Option<String>,
+    pub tonart: Option<String>,
+}
+
+/// One song of a piece, for the detail panel.
+#[derive(Debug, Clone)]
+pub struct Arrangement {
+    pub id: i64,
+    pub titel: String,
+}
+
+/// The title without trailing "(easy)" or "[Live]" parts, which usually name
+/// the arrangement rather than the piece.
+pub fn base_title(titel: &str) -> String {
+    let mut rest = titel.trim();
+    while let Some(open) = rest
+        .strip_suffix(')')
+        .and_then(|r| r.rfind('('))
+        .or_else(|| rest.strip_suffix(']').and_then(|r| r.rfind('[')))
+    {
+        if open == 0 {
+            break;
+        }
+        rest = rest[..open].trim_end();
+    }
+    rest.trim_end_matches([' ', '-', '\u{2013}']).to_string()
+}
+
+pub fn works(conn: &Connection) -> HashMap<i64, Work> {
+    query_rows(
+        conn,
+        "SELECT id, titel, artist, tonart FROM werke",
+        [],
+        |row| {
+            Ok(Work {
+                id: row.get(0)?,
+                titel: row.get(1)?,
+                artist: row.get(2)?,
+                tonart: row.get(3)?,
+            })
+        },
+    )
+    .into_iter()
+    .map(|w| (w.id, w))
+    .collect()
+}
+
+/// Every arrangement of the piece `song_id` belongs to, itself included.
+/// Empty if it stands alone.
+pub fn arrangements(conn: &Connection, song_id: i64) -> Vec<Arrangement> {
+    query_rows(
+        conn,
+        "SELECT s.id, s.titel FROM songs s
+         WHERE s.werk_id = (SELECT werk_id FROM songs WHERE id = ?1)
+           AND s.deleted_at IS NULL AND s.ignoriert = 0
+         ORDER BY s.titel",
+        params![song_id],
+        |row| {
+            Ok(Arrangement {
+                id: row.get(0)?,
+                titel: row.get(1)?,
+            })
+        },
+    )
+}
+
+/// Songs that look like other arrangements of `song_id`: the same title
+/// without the parts in brackets, and no other artist.
+pub fn suggestions(conn: &Connection, song_id: i64) -> Vec<Arrangement> {
+    let Ok((titel, artist, werk_id)) = conn.query_row(
+        "SELECT titel, artist, werk_id FROM songs WHERE id = ?1",
+        params![song_id],
+        |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<i64>>(2)?,
+            ))
+        },
+    ) else {
+        return Vec::new();
+    };
+    let base = base_title(&titel).to_lowercase();
+    query_rows(
+        conn,
+        "SELECT id, titel, artist FROM songs
+         WHERE id != ?1 AND (?2 IS NULL OR werk_id IS NOT ?2)
+           AND deleted_at IS NULL AND ignoriert = 0
+           AND titel LIKE ?3 || '%' ORDER BY titel",
+        params![song_id, werk_id, base],
+        |row| {
+            Ok((
+                Arrangement {
+                    id: row.get(0)?,
+                    titel: row.get(1)?,
+                },
+                row.get::<_, Option<String>>(2)?,
+            ))
+        },
+    )
+    .into_iter()
+    .filter(|(other, other_artist)| {
+        base_title(&other.titel).to_lowercase() == base
+            && (artist.is_none() || other_artist.is_none() || *other_artist == artist)
+    })
+    .map(|(other, _)| other)
+    .collect()
+}
+
+/// Make `other_id` an arrangement of the same piece as `song_id`, creating
+/// the piece from `song_id` if it has none yet. If `other_id` belonged to
+/// another piece, all of its arrangements come along. Returns the piece.
+pub fn add_arrangement(conn: &Connection, song_id: i64, other_id: i64) -> Option<i64> {
+    if song_id == other_id {
+        return None;
+    }
+    let werk_of = |id: i64| -> Option<i64> {
+        conn.query_row(
+            "SELECT werk_id FROM songs WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )
+        .ok()
+        .flatten()
+    };
+    conn.execute_batch("BEGIN").log_err();
+    let werk_id = match werk_of(song_id) {
+        Some(id) => id,
+        None => {
+            conn.execute(
+                "INSERT INTO werke (titel, artist, tonart)
+                 SELECT ?1, COALESCE(s.artist, o.artist), COALESCE(s.tonart, o.tonart)
+                 FROM songs s, songs o WHERE s.id = ?2 AND o.id = ?3",
+                params![base_title(&song_titel(conn, song_id)), song_id, other_id],
+            )
+            .log_err();
+            let id = conn.last_insert_rowid();
+            conn.execute(
+                "UPDATE songs SET werk_id = ?1 WHERE id = ?2",
+                params![id, song_id],
+            )
+            .log_err();
+            id
+        }
+    };
+    match werk_of(other_id) {
+        Some(old) if old == werk_id => {}
+        Some(old) => {
+            conn.execute(
+                "UPDATE werke SET artist = COALESCE(artist, (SELECT artist FROM werke WHERE id = ?2)),
+                                  tonart = COALESCE(tonart, (SELECT tonart FROM werke WHERE id = ?2))
+                 WHERE id = ?1",
+                params![werk_id, old],
+            )
+            .log_err();
+            conn.execute(
+                "UPDATE songs SET werk_id = ?1 WHERE werk_id = ?2",
+                params![werk_id, old],
+            )
+            .log_err();
+            conn.execute("DELETE FROM werke WHERE id = ?1", params![old])
+                .log_err();
+        }
+        None => {
+            conn.execute(
+                "UPDATE werke SET artist = COALESCE(artist, (SELECT artist FROM songs WHERE id = ?2)),
+                                  tonart = COALESCE(tonart, (SELECT tonart FROM songs WHERE id = ?2))
+                 WHERE id = ?1",
+                params![werk_id, other_id],
+            )
+            .log_err();
+            conn.execute(
+                "UPDATE songs SET werk_id = ?1 WHERE id = ?2",
+                params![werk_id, other_id],
+            )
+            .log_err();
+        }
+    }
+    sync(conn, werk_id);
+    conn.execute_batch("COMMIT").log_err();
+    Some(werk_id)
+}
+
+/// Take a song out of its piece. A piece left with one arrangement is
+/// dissolved.
+pub fn remove_arrangement(conn: &Connection, song_id: i64) {
+    conn.execute(
+        "UPDATE songs SET werk_id = NULL WHERE id = ?1",
+        params![song_id],
+    )
+    .log_err();
+    prune(conn);
+}
+
+/// After the artist or key of `song_id` changed: give the piece and its
+/// other arrangements the same values. Does nothing for songs on their own.
+pub fn share_from(conn: &Connection, song_id: i64) {
+    let werk_id: Option<i64> = conn
+        .query_row(
+            "SELECT werk_id FROM songs WHERE id = ?1",
+            params![song_id],
+            |row| row.get(0),
+        )
+        .ok()
+        .flatten();
+    let Some(werk_id) = werk_id else {
+        return;
+    };
+    conn.execute(
+        "UPDATE werke SET artist = (SELECT artist FROM songs WHERE id = ?2),
+                          tonart = (SELECT tonart FROM songs WHERE id = ?2)
+         WHERE id = ?1",
+        params![werk_id, song_id],
+    )
+    .log_err();
+    sync(conn, werk_id);
+}
+
+/// Copy the piece's artist and key to all of its arrangements.
+fn sync(conn: &Connection, werk_id: i64) {
+    conn.execute(
+        "UPDATE songs SET artist = (SELECT artist FROM werke WHERE id = ?1),
+                          tonart = (SELECT tonart FROM werke WHERE id = ?1)
+         WHERE werk_id = ?1
+           AND (artist IS NOT (SELECT artist FROM werke WHERE id = ?1)
+                OR tonart IS NOT (SELECT tonart FROM werke WHERE id = ?1))",
+        params![werk_id],
+    )
+    .log_err();
+}
+
+/// Dissolve pieces with fewer than two arrangements left, e.g. after songs
+/// were purged or merged. Returns how many were removed.
+pub fn prune(conn: &Connection) -> usize {
+    let lonely = "SELECT id FROM werke w
+                  WHERE (SELECT COUNT(*) FROM songs WHERE werk_id = w.id) < 2";
+    conn.execute(
+        &format!("UPDATE songs SET werk_id = NULL WHERE werk_id IN ({lonely})"),
+        [],
+    )
+    .log_err();
+    conn.execute(&format!("DELETE FROM werke WHERE id IN ({lonely})"), [])
+        .unwrap_or(0)
+}
+
+fn song_titel(conn: &Connection, song_id: i64) -> String {
+    conn.query_row(
+        "SELECT titel FROM songs WHERE id = ?1",
+        params![song_id],
+        |row| row.get(0),
+    )
+    .unwrap_or_default()
+}
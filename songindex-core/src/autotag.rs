@@ -0,0 +1,354 @@
+use crate::config::{AutoTagRule, RuleScope};
+use crate::db::{get_or_create_tag, query_rows, LogErr};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+const RULES_VERSION: u32 = 1;
+
+/// A tag as `(kategorie, wert)`.
+pub type Tag = (String, String);
+
+/// Whether `rule` matches a song at `rel_path`: below `unter`, with the
+/// pattern in the part of the path its scope names.
+fn matches(rule: &AutoTagRule, rel_path: &str) -> bool {
+    let unter = rule.unter.trim().trim_matches('/');
+    if !unter.is_empty()
+        && !rel_path
+            .strip_prefix(unter)
+            .is_some_and(|rest| rest.starts_with('/'))
+    {
+        return false;
+    }
+    let pattern = rule.pattern.as_str();
+    let contains = |text: &str| contains(rule, text, pattern);
+    let mut folders: Vec<&str> = rel_path.split('/').collect();
+    folders.pop();
+    match rule.bereich {
+        RuleScope::Pfad => contains(rel_path),
+        RuleScope::Ordner => folders.iter().any(|folder| contains(folder)),
+        RuleScope::Elternordner => folders.last().is_some_and(|folder| contains(folder)),
+        RuleScope::Ebene => folders
+            .get(rule.ebene.max(1) as usize - 1)
+            .is_some_and(|folder| contains(folder)),
+    }
+}
+
+/// `text` contains `pattern`, honoring the rule's case and whole-word flags.
+fn contains(rule: &AutoTagRule, text: &str, pattern: &str) -> bool {
+    let (text, pattern): (Cow<str>, Cow<str>) = if rule.ohne_gross_klein {
+        (text.to_lowercase().into(), pattern.to_lowercase().into())
+    } else {
+        (text.into(), pattern.into())
+    };
+    if !rule.ganzes_wort {
+        return text.contains(pattern.as_ref());
+    }
+    text.match_indices(pattern.as_ref()).any(|(i, m)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + m.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// Tags the rules give a song at `rel_path`: every rule that matches (see
+/// [`matches`]), in list order, plus `instrument:Akustik-Gitarre` in the guitar
+/// folder when no rule set an instrument. A matching rule with `ersetzen`
+/// drops the tags earlier rules set in its category; one with `stoppen` ends
+/// the evaluation.
+pub fn infer_tags(rel_path: &str, rules: &[AutoTagRule]) -> Vec<Tag> {
+    evaluate(rel_path, rules).0
+}
+
+/// [`infer_tags`] plus the categories a matching `ersetzen` rule claimed.
+/// Re-tagging replaces every tag in those, hand-set ones included.
+fn evaluate(rel_path: &str, rules: &[AutoTagRule]) -> (Vec<Tag>, BTreeSet<String>) {
+    let mut tags: Vec<Tag> = Vec::new();
+    let mut replaced = BTreeSet::new();
+    for rule in rules {
+        let kategorie = rule.kategorie.trim();
+        let wert = rule.wert.trim();
+        if rule.pattern.is_empty() || kategorie.is_empty() || wert.is_empty() {
+            continue;
+        }
+        if !matches(rule, rel_path) {
+            continue;
+        }
+        if rule.ersetzen {
+            tags.retain(|(k, _)| k != kategorie);
+            replaced.insert(kategorie.to_string());
+        }
+        if !tags.iter().any(|(k, w)| k == kategorie && w == wert) {
+            tags.push((kategorie.to_string(), wert.to_string()));
+        }
+        if rule.stoppen {
+            break;
+        }
+    }
+
+    if rel_path.contains("00 gitarre") && !tags.iter().any(|(k, _)| k == "instrument") {
+        tags.push(("instrument".to_string(), "Akustik-Gitarre".to_string()));
+    }
+
+    (tags, replaced)
+}
+
+/// A song with its current tags, split by whether a rule set them.
+pub struct TaggedSong {
+    pub id: i64,
+    pub titel: String,
+    pub dateipfad: String,
+    auto: BTreeSet<Tag>,
+    manual: BTreeSet<Tag>,
+}
+
+/// Songs in the library (not trashed, not ignored) with their tags, loaded once
+/// so the preview can be recomputed on every keystroke.
+pub fn load_songs(conn: &Connection) -> Vec<TaggedSong> {
+    let mut songs: Vec<TaggedSong> = Vec::new();
+    let mut index = HashMap::new();
+    let rows = query_rows(
+        conn,
+        "SELECT id, titel, dateipfad FROM songs
+         WHERE deleted_at IS NULL AND ignoriert = 0
+         ORDER BY titel COLLATE NOCASE",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    );
+    for (id, titel, dateipfad) in rows {
+        index.insert(id, songs.len());
+        songs.push(TaggedSong {
+            id,
+            titel,
+            dateipfad,
+            auto: BTreeSet::new(),
+            manual: BTreeSet::new(),
+        });
+    }
+
+    let rows = query_rows(
+        conn,
+        "SELECT st.song_id, t.kategorie, t.wert, st.auto_generated
+         FROM song_tags st JOIN tags t ON t.id = st.tag_id",
+        [],
+        |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<bool>>(3)?.unwrap_or(false),
+            ))
+        },
+    );
+    for (song_id, kategorie, wert, auto) in rows {
+        if let Some(&i) = index.get(&song_id) {
+            let song = &mut songs[i];
+            if auto {
+                song.auto.insert((kategorie, wert));
+            } else {
+                song.manual.insert((kategorie, wert));
+            }
+        }
+    }
+    songs
+}
+
+/// What re-tagging would do to one song.
+pub struct RetagChange {
+    pub song_id: i64,
+    pub titel: String,
+    pub dateipfad: String,
+    /// Tags the rules give that the song does not have yet.
+    pub gained: Vec<Tag>,
+    /// Tags a rule set earlier that no rule gives any more, and hand-set tags
+    /// in a category an `ersetzen` rule claims. Other hand-set tags stay.
+    pub lost: Vec<Tag>,
+}
+
+/// Songs whose tags would change if `rules` were applied to the whole library.
+pub fn preview(songs: &[TaggedSong], rules: &[AutoTagRule]) -> Vec<RetagChange> {
+    songs
+        .iter()
+        .filter_map(|song| {
+            let (tags, replaced) = evaluate(&song.dateipfad, rules);
+            let inferred: BTreeSet<Tag> = tags.into_iter().collect();
+            let gained: Vec<Tag> = inferred
+                .iter()
+                .filter(|t| !song.auto.contains(*t) && !song.manual.contains(*t))
+                .cloned()
+                .collect();
+            let lost: Vec<Tag> = song
+                .auto
+                .iter()
+                .chain(song.manual.iter().filter(|(k, _)| replaced.contains(k)))
+                .filter(|t| !inferred.contains(*t))
+                .cloned()
+                .collect();
+            (!gained.is_empty() || !lost.is_empty()).then(|| RetagChange {
+                song_id: song.id,
+                titel: song.titel.clone(),
+                dateipfad: song.dateipfad.clone(),
+                gained,
+                lost,
+            })
+        })
+        .collect()
+}
+
+/// Apply a preview in one transaction. Returns the number of songs changed,
+/// 0 if the transaction could not be committed.
+pub fn apply(conn: &Connection, changes: &[RetagChange]) -> usize {
+    conn.execute_batch("BEGIN").log_err();
+    for change in changes {
+        for (kategorie, wert) in &change.gained {
+            let Ok(tag_id) = get_or_create_tag(conn, kategorie, wert) else {
+                continue;
+            };
+            conn.execute(
+                "INSERT OR IGNORE INTO song_tags (song_id, tag_id, auto_generated) VALUES (?1, ?2, 1)",
+                params![change.song_id, tag_id],
+            )
+            .log_err();
+        }
+        for (kategorie, wert) in &change.lost {
+            conn.execute(
+                "DELETE FROM song_tags WHERE song_id = ?1
+                 AND tag_id = (SELECT id FROM tags WHERE kategorie = ?2 AND wert = ?3)",
+                params![change.song_id, kategorie, wert],
+            )
+            .log_err();
+        }
+    }
+    conn.execute(
+        "DELETE FROM tags WHERE angeheftet = 0 AND id NOT IN (SELECT DISTINCT tag_id FROM song_tags)",
+        [],
+    )
+    .log_err();
+    if conn.execute_batch("COMMIT").log_err().is_none() {
+        conn.execute_batch("ROLLBACK").log_err();
+        return 0;
+    }
+    changes.len()
+}
+
+/// A rule list as exported for someone else, folder defaults included if
+/// they are still in the list.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RuleFile {
+    pub version: u32,
+    pub regeln: Vec<AutoTagRule>,
+}
+
+/// How imported rules join the current list.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum RuleMerge {
+    /// The imported rules replace the whole list.
+    Ersetzen,
+    /// Imported rules go after the current ones, even if they repeat one.
+    Anhaengen,
+    /// Like `Anhaengen`, minus rules the list already has (see [`same_rule`]).
+    #[default]
+    OhneDoppelte,
+}
+
+impl RuleMerge {
+    pub const ALL: [RuleMerge; 3] = [
+        RuleMerge::OhneDoppelte,
+        RuleMerge::Anhaengen,
+        RuleMerge::Ersetzen,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RuleMerge::Ersetzen => "Liste ersetzen",
+            RuleMerge::Anhaengen => "Anh\u{00E4}ngen",
+            RuleMerge::OhneDoppelte => "Doppelte \u{00FC}berspringen",
+        }
+    }
+}
+
+/// Two rules count as the same when they look for the same pattern in the
+/// same place and give the same tag; the case, word, replace and stop flags
+/// don't matter.
+fn same_rule(a: &AutoTagRule, b: &AutoTagRule) -> bool {
+    a.pattern == b.pattern
+        && a.bereich == b.bereich
+        && (a.bereich != RuleScope::Ebene || a.ebene == b.ebene)
+        && a.unter.trim().trim_matches('/') == b.unter.trim().trim_matches('/')
+        && a.kategorie.trim() == b.kategorie.trim()
+        && a.wert.trim() == b.wert.trim()
+}
+
+fn is_toml(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"))
+}
+
+/// Write `rules` to `path`, as TOML for a `.toml` file and JSON otherwise.
+/// Returns the number of rules written.
+pub fn export_rules(rules: &[AutoTagRule], path: &Path) -> Result<usize, String> {
+    let file = RuleFile {
+        version: RULES_VERSION,
+        regeln: rules.to_vec(),
+    };
+    let text = if is_toml(path) {
+        toml::to_string_pretty(&file).map_err(|e| e.to_string())?
+    } else {
+        serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?
+    };
+    std::fs::write(path, text).map_err(|e| e.to_string())?;
+    Ok(file.regeln.len())
+}
+
+/// Rules from a file written by [`export_rules`], without empty patterns.
+pub fn read_rules(path: &Path) -> Result<Vec<AutoTagRule>, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let file: RuleFile = if is_toml(path) {
+        toml::from_str(&content).map_err(|e| e.to_string())?
+    } else {
+        serde_json::from_str(&content).map_err(|e| e.to_string())?
+    };
+    if file.version > RULES_VERSION {
+        return Err(format!(
+            "Unbekannte Version {} (unterst\u{00FC}tzt: {RULES_VERSION})",
+            file.version
+        ));
+    }
+    Ok(file
+        .regeln
+        .into_iter()
+        .filter(|r| !r.pattern.is_empty())
+        .collect())
+}
+
+/// Merge `imported` into `rules` the way `mode` says. Returns the number of
+/// rules taken over.
+pub fn merge_rules(
+    rules: &mut Vec<AutoTagRule>,
+    imported: Vec<AutoTagRule>,
+    mode: RuleMerge,
+) -> usize {
+    match mode {
+        RuleMerge::Ersetzen => {
+            *rules = imported;
+            rules.len()
+        }
+        RuleMerge::Anhaengen => {
+            let count = imported.len();
+            rules.extend(imported);
+            count
+        }
+        RuleMerge::OhneDoppelte => {
+            let mut count = 0;
+            for rule in imported {
+                if !rules.iter().any(|r| same_rule(r, &rule)) {
+                    rules.push(rule);
+                    count += 1;
+                }
+            }
+            count
+        }
+    }
+}
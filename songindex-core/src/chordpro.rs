@@ -0,0 +1,284 @@
+use crate::filetype::SongMeta;
+
+/// Chord suffix building blocks, longest first so "maj" wins over "m".
+const CHORD_SUFFIXES: &[&str] = &[
+    "maj", "min", "dim", "aug", "sus", "add", "m", "M", "+", "-", "°", "ø", "#", "b", "(", ")",
+];
+
+/// Returns true if `token` looks like a chord symbol, e.g. `C`, `Am7`, `F#m7b5`, `G/B`, `Dsus4`.
+pub fn is_chord(token: &str) -> bool {
+    let (chord, bass) = match token.split_once('/') {
+        Some((c, b)) => (c, Some(b)),
+        None => (token, None),
+    };
+    if let Some(bass) = bass {
+        if !is_root(bass) {
+            return false;
+        }
+    }
+
+    let mut chars = chord.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    if !('A'..='G').contains(&first) {
+        return false;
+    }
+    let mut rest = chars.as_str();
+    if let Some(r) = rest.strip_prefix('#').or_else(|| rest.strip_prefix('b')) {
+        rest = r;
+    }
+
+    while !rest.is_empty() {
+        if let Some(r) = rest.strip_prefix(|c: char| c.is_ascii_digit()) {
+            rest = r;
+            continue;
+        }
+        match CHORD_SUFFIXES.iter().find(|s| rest.starts_with(*s)) {
+            Some(s) => rest = &rest[s.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+fn is_root(s: &str) -> bool {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) if ('A'..='G').contains(&c) => matches!(chars.as_str(), "" | "#" | "b"),
+        _ => false,
+    }
+}
+
+fn is_chord_line(line: &str) -> bool {
+    let mut tokens = line
+        .split_whitespace()
+        .filter(|t| !matches!(*t, "|" | "||" | "/" | "-" | "N.C."))
+        .peekable();
+    tokens.peek().is_some() && tokens.all(is_chord)
+}
+
+/// Merge a chord line into the lyric line below it, placing `[Chord]` at the
+/// column the chord was written above.
+fn merge_chords(chord_line: &str, lyric_line: &str) -> String {
+    let mut lyric: Vec<char> = lyric_line.chars().collect();
+    let mut chords: Vec<(usize, &str)> = Vec::new();
+    let mut col = 0;
+    for part in chord_line.split(' ') {
+        if !part.is_empty() && is_chord(part) {
+            chords.push((col, part));
+        }
+        col += part.chars().count() + 1;
+    }
+
+    if let Some(&(last_col, _)) = chords.last() {
+        while lyric.len() < last_col {
+            lyric.push(' ');
+        }
+    }
+
+    let mut out = String::new();
+    let mut next = chords.iter().peekable();
+    for (i, c) in lyric.iter().enumerate() {
+        while let Some(&(_, chord)) = next.next_if(|&&(pos, _)| pos == i) {
+            out.push_str(&format!("[{chord}]"));
+        }
+        out.push(*c);
+    }
+    for (_, chord) in next {
+        out.push_str(&format!("[{chord}]"));
+    }
+    out.trim_end().to_string()
+}
+
+/// Convert plain "chords over lyrics" text (as found in messages or on tab sites)
+/// into ChordPro. Text that already contains ChordPro directives is kept as-is.
+pub fn from_chords_over_lyrics(text: &str) -> String {
+    if text.lines().any(|l| l.trim_start().starts_with('{')) {
+        return text.trim_end().to_string();
+    }
+
+    let lines: Vec<&str> = text.lines().map(|l| l.trim_end()).collect();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim();
+
+        if trimmed.starts_with('[')
+            && trimmed.ends_with(']')
+            && !is_chord(&trimmed[1..trimmed.len() - 1])
+        {
+            out.push(format!("{{comment: {}}}", &trimmed[1..trimmed.len() - 1]));
+        } else if is_chord_line(line) {
+            match lines.get(i + 1) {
+                Some(next) if !next.trim().is_empty() && !is_chord_line(next) => {
+                    out.push(merge_chords(line, next));
+                    i += 1;
+                }
+                _ => out.push(
+                    line.split_whitespace()
+                        .map(|t| {
+                            if is_chord(t) {
+                                format!("[{t}]")
+                            } else {
+                                t.to_string()
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                ),
+            }
+        } else {
+            out.push(line.to_string());
+        }
+        i += 1;
+    }
+
+    out.join("\n").trim().to_string()
+}
+
+/// Build a complete ChordPro document with title/artist header.
+pub fn build_document(titel: &str, artist: &str, body: &str) -> String {
+    if body.contains("{title") || body.contains("{t:") {
+        return format!("{}\n", body.trim_end());
+    }
+    let mut doc = format!("{{title: {}}}\n", titel.trim());
+    if !artist.trim().is_empty() {
+        doc.push_str(&format!("{{artist: {}}}\n", artist.trim()));
+    }
+    doc.push('\n');
+    doc.push_str(&from_chords_over_lyrics(body));
+    doc.push('\n');
+    doc
+}
+
+/// Split `{name: value}` into the lowercased name and the trimmed value.
+fn directive(line: &str) -> Option<(String, &str)> {
+    let inner = line.trim().strip_prefix('{')?.strip_suffix('}')?;
+    let (name, value) = match inner.find([':', ' ']) {
+        Some(idx) => (&inner[..idx], inner[idx + 1..].trim()),
+        None => (inner, ""),
+    };
+    Some((name.trim().to_lowercase(), value))
+}
+
+/// Read `{title}`/`{t}`, `{artist}`, `{key}` and `{capo}`; the first value of
+/// each wins. Empty values are ignored.
+pub fn parse_metadata(text: &str) -> SongMeta {
+    let mut meta = SongMeta::default();
+    for (name, value) in text.lines().filter_map(directive) {
+        if value.is_empty() {
+            continue;
+        }
+        let slot = match name.as_str() {
+            "title" | "t" => &mut meta.title,
+            "artist" => &mut meta.artist,
+            "key" => &mut meta.key,
+            "capo" => {
+                meta.capo = meta.capo.or(value.parse().ok());
+                continue;
+            }
+            _ => continue,
+        };
+        slot.get_or_insert_with(|| value.to_string());
+    }
+    meta
+}
+
+/// One line of a chart prepared for display.
+pub enum ChartLine {
+    /// Lyrics as `(chord, text)` segments; the chord sits above the start of its text.
+    Lyrics(Vec<(Option<String>, String)>),
+    /// Section start (`{start_of_chorus}` …) with its label.
+    Section(String),
+    /// `{comment}` and friends.
+    Comment(String),
+    /// Shown as-is in monospace: `{start_of_tab}` blocks and plain text sheets.
+    Verbatim(String),
+    Blank,
+}
+
+fn lyric_segments(line: &str) -> Vec<(Option<String>, String)> {
+    let mut segments = vec![(None, String::new())];
+    let mut rest = line;
+    while let Some(open) = rest.find('[') {
+        let Some(close) = rest[open..].find(']') else {
+            break;
+        };
+        if let Some((_, text)) = segments.last_mut() {
+            text.push_str(&rest[..open]);
+        }
+        let chord = rest[open + 1..open + close].to_string();
+        segments.push((Some(chord), String::new()));
+        rest = &rest[open + close + 1..];
+    }
+    if let Some((_, text)) = segments.last_mut() {
+        text.push_str(rest);
+    }
+    if segments[0].1.is_empty() && segments.len() > 1 {
+        segments.remove(0);
+    }
+    segments
+}
+
+/// Parse a ChordPro document into display lines. Metadata directives are
+/// dropped (they are shown separately); unknown directives are ignored.
+pub fn parse_chart(text: &str) -> Vec<ChartLine> {
+    let mut lines = Vec::new();
+    let mut in_tab = false;
+    for line in text.lines() {
+        if let Some((name, value)) = directive(line) {
+            let label = |default: &str| {
+                if value.is_empty() {
+                    default.to_string()
+                } else {
+                    value.to_string()
+                }
+            };
+            match name.as_str() {
+                "start_of_chorus" | "soc" => lines.push(ChartLine::Section(label("Refrain"))),
+                "start_of_verse" | "sov" => lines.push(ChartLine::Section(label("Strophe"))),
+                "start_of_bridge" | "sob" => lines.push(ChartLine::Section(label("Bridge"))),
+                "start_of_tab" | "sot" => {
+                    in_tab = true;
+                    if !value.is_empty() {
+                        lines.push(ChartLine::Section(value.to_string()));
+                    }
+                }
+                "end_of_tab" | "eot" => in_tab = false,
+                "chorus" => lines.push(ChartLine::Section(label("Refrain"))),
+                "comment" | "c" | "comment_italic" | "ci" | "comment_box" | "cb" => {
+                    lines.push(ChartLine::Comment(value.to_string()))
+                }
+                _ => {}
+            }
+            continue;
+        }
+        if line.trim_start().starts_with('#') {
+            continue;
+        }
+        if in_tab {
+            lines.push(ChartLine::Verbatim(line.to_string()));
+        } else if line.trim().is_empty() {
+            lines.push(ChartLine::Blank);
+        } else {
+            lines.push(ChartLine::Lyrics(lyric_segments(line.trim_end())));
+        }
+    }
+    lines
+}
+
+/// Display lines for a plain text or Markdown sheet: no parsing, so chord
+/// lines keep their alignment over the lyrics.
+pub fn plain_chart(text: &str) -> Vec<ChartLine> {
+    text.lines()
+        .map(|line| {
+            if line.trim().is_empty() {
+                ChartLine::Blank
+            } else {
+                ChartLine::Verbatim(line.trim_end().to_string())
+            }
+        })
+        .collect()
+}
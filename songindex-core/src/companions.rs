@@ -0,0 +1,504 @@
+//! Companion files: the tab, lyrics sheet or extra recordings that belong to
+//! a song next to its main file (`song_dateien`). A file joins a song by hand
+//! or from the matcher's suggestions; from then on the scanner no longer
+//! indexes it as a song of its own.
+
+use crate::db::{query_rows, LogErr};
+use crate::filetype::FileType;
+use crate::scanner::{is_audio_file, nfc, parse_filename, ScanOptions};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// What a file is for, stored in `song_dateien.rolle`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Role {
+    /// Sheet music: PDF, MusicXML, a scan.
+    Noten,
+    Tabs,
+    /// Chord chart, e.g. ChordPro.
+    Akkorde,
+    /// Lyrics sheet.
+    Text,
+    /// Recording or backing track.
+    Audio,
+}
+
+impl Role {
+    pub const ALL: [Role; 5] = [
+        Role::Noten,
+        Role::Tabs,
+        Role::Akkorde,
+        Role::Text,
+        Role::Audio,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::Noten => "noten",
+            Role::Tabs => "tabs",
+            Role::Akkorde => "akkorde",
+            Role::Text => "text",
+            Role::Audio => "audio",
+        }
+    }
+
+    /// Inverse of `as_str()`.
+    pub fn from_stored(s: &str) -> Option<Role> {
+        Role::ALL.into_iter().find(|r| r.as_str() == s)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Role::Noten => "Noten",
+            Role::Tabs => "Tabs",
+            Role::Akkorde => "Akkorde",
+            Role::Text => "Text",
+            Role::Audio => "Audio",
+        }
+    }
+
+    /// Role of a file from words in its name ("Tab", "Lyrics", "Akkorde"),
+    /// otherwise from its type.
+    pub fn guess(rel_path: &str) -> Role {
+        let path = Path::new(rel_path);
+        if is_audio_file(path) {
+            return Role::Audio;
+        }
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        for word in name.split(|c: char| !c.is_alphanumeric()) {
+            match word {
+                "tab" | "tabs" | "tabulatur" => return Role::Tabs,
+                "text" | "lyrics" | "songtext" => return Role::Text,
+                "akkorde" | "chords" => return Role::Akkorde,
+                _ => {}
+            }
+        }
+        match FileType::from_path(path) {
+            Some(FileType::GuitarPro) => Role::Tabs,
+            Some(FileType::ChordPro) => Role::Akkorde,
+            Some(FileType::Text) => Role::Text,
+            Some(FileType::Audio) => Role::Audio,
+            _ => Role::Noten,
+        }
+    }
+}
+
+/// A file that belongs to a song besides its main file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Companion {
+    /// Relative to the music folder.
+    pub dateipfad: String,
+    pub rolle: Role,
+}
+
+pub fn companions(conn: &Connection, song_id: i64) -> Vec<Companion> {
+    query_rows(
+        conn,
+        "SELECT dateipfad, rolle FROM song_dateien WHERE song_id = ?1 ORDER BY rolle, dateipfad",
+        params![song_id],
+        |row| {
+            Ok(Companion {
+                dateipfad: row.get(0)?,
+                rolle: Role::from_stored(&row.get::<_, String>(1)?).unwrap_or(Role::Noten),
+            })
+        },
+    )
+}
+
+/// Companions of every song, keyed by song id, in the order of [`companions`].
+pub fn all_companions(conn: &Connection) -> HashMap<i64, Vec<Companion>> {
+    let rows = query_rows(
+        conn,
+        "SELECT song_id, dateipfad, rolle FROM song_dateien ORDER BY rolle, dateipfad",
+        [],
+        |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                Companion {
+                    dateipfad: row.get(1)?,
+                    rolle: Role::from_stored(&row.get::<_, String>(2)?).unwrap_or(Role::Noten),
+                },
+            ))
+        },
+    );
+    let mut companions: HashMap<i64, Vec<Companion>> = HashMap::new();
+    for (song_id, companion) in rows {
+        companions.entry(song_id).or_default().push(companion);
+    }
+    companions
+}
+
+/// Paths of all companion files, which the scanner leaves alone.
+pub fn companion_paths(conn: &Connection) -> HashSet<String> {
+    query_rows(conn, "SELECT dateipfad FROM song_dateien", [], |row| {
+        row.get(0)
+    })
+    .into_iter()
+    .collect()
+}
+
+pub fn is_companion(conn: &Connection, rel_path: &str) -> bool {
+    conn.query_row(
+        "SELECT 1 FROM song_dateien WHERE dateipfad = ?1",
+        params![rel_path],
+        |_| Ok(()),
+    )
+    .is_ok()
+}
+
+/// Attach files to a song in one transaction. A file that is a song of its
+/// own is merged into it: tags, links, notes, history and its own companions
+/// move over, then its row is deleted. The first audio file becomes the
+/// song's backing track if it has none. Returns how many files were attached.
+pub fn add_companions(conn: &Connection, song_id: i64, files: &[(String, Role)]) -> usize {
+    let Ok(own_path) = conn.query_row(
+        "SELECT dateipfad FROM songs WHERE id = ?1",
+        params![song_id],
+        |row| row.get::<_, String>(0),
+    ) else {
+        return 0;
+    };
+    conn.execute_batch("BEGIN").log_err();
+    let mut added = 0;
+    for (rel_path, rolle) in files {
+        if *rel_path == own_path {
+            continue;
+        }
+        let other = conn
+            .query_row(
+                "SELECT id FROM songs WHERE dateipfad = ?1",
+                params![rel_path],
+                |row| row.get::<_, i64>(0),
+            )
+            .ok();
+        if let Some(other) = other {
+            merge_song(conn, song_id, other);
+        }
+        let inserted = conn
+            .execute(
+                "INSERT OR REPLACE INTO song_dateien (dateipfad, song_id, rolle) VALUES (?1, ?2, ?3)",
+                params![rel_path, song_id, rolle.as_str()],
+            )
+            .is_ok();
+        if inserted {
+            added += 1;
+            if *rolle == Role::Audio {
+                conn.execute(
+                    "UPDATE songs SET audio_pfad = ?2, has_audio = 1
+                     WHERE id = ?1 AND audio_pfad IS NULL",
+                    params![song_id, rel_path],
+                )
+                .log_err();
+            }
+        }
+    }
+    conn.execute_batch("COMMIT").log_err();
+    added
+}
+
+/// Move everything of song `other` to `song_id` and delete `other`. Fields
+/// the song already has are kept.
+fn merge_song(conn: &Connection, song_id: i64, other: i64) {
+    let other_audio: Option<String> = conn
+        .query_row(
+            "SELECT audio_pfad FROM songs WHERE id = ?1 AND COALESCE(dateityp, 'pdf') != 'audio'",
+            params![other],
+            |row| row.get(0),
+        )
+        .ok()
+        .flatten();
+    for column in [
+        "artist",
+        "notizen",
+        "tonart",
+        "bpm",
+        "kapo",
+        "jahr",
+        "bewertung",
+        "repertoire_seit",
+        "werk_id",
+    ] {
+        conn.execute(
+            &format!(
+                "UPDATE songs SET {column} = COALESCE({column}, (SELECT {column} FROM songs WHERE id = ?2))
+                 WHERE id = ?1"
+            ),
+            params![song_id, other],
+        )
+        .log_err();
+    }
+    for sql in [
+        "UPDATE songs SET favorit = MAX(favorit, (SELECT favorit FROM songs WHERE id = ?2))
+         WHERE id = ?1",
+        "UPDATE OR IGNORE song_tags SET song_id = ?1 WHERE song_id = ?2",
+        "UPDATE song_links SET song_id = ?1 WHERE song_id = ?2",
+        "UPDATE song_events SET song_id = ?1 WHERE song_id = ?2",
+        "UPDATE practice_log SET song_id = ?1 WHERE song_id = ?2",
+        "UPDATE song_dateien SET song_id = ?1 WHERE song_id = ?2",
+        "UPDATE OR IGNORE song_folgen SET song_id = ?1 WHERE song_id = ?2",
+        "UPDATE OR IGNORE song_folgen SET vorher_id = ?1 WHERE vorher_id = ?2",
+        "DELETE FROM song_folgen
+         WHERE (song_id = ?1 AND vorher_id = ?1) OR song_id = ?2 OR vorher_id = ?2",
+        "DELETE FROM songs WHERE id = ?2",
+    ] {
+        conn.execute(sql, params![song_id, other]).log_err();
+    }
+    if let Some(audio) = other_audio {
+        conn.execute(
+            "INSERT OR IGNORE INTO song_dateien (dateipfad, song_id, rolle)
+             SELECT ?2, ?1, 'audio' WHERE (SELECT audio_pfad FROM songs WHERE id = ?1) IS NOT ?2",
+            params![song_id, audio],
+        )
+        .log_err();
+        conn.execute(
+            "UPDATE songs SET audio_pfad = ?2, has_audio = 1 WHERE id = ?1 AND audio_pfad IS NULL",
+            params![song_id, audio],
+        )
+        .log_err();
+    }
+}
+
+/// Detach a file from its song. If it was the backing track, the next audio
+/// companion takes over. Returns the song it belonged to; the caller indexes
+/// the file again (`scanner::add_single_file`) to make it a song of its own.
+pub fn remove_companion(conn: &Connection, rel_path: &str) -> Option<i64> {
+    let song_id: i64 = conn
+        .query_row(
+            "SELECT song_id FROM song_dateien WHERE dateipfad = ?1",
+            params![rel_path],
+            |row| row.get(0),
+        )
+        .ok()?;
+    conn.execute(
+        "DELETE FROM song_dateien WHERE dateipfad = ?1",
+        params![rel_path],
+    )
+    .log_err();
+    conn.execute(
+        "UPDATE songs SET audio_pfad = (
+             SELECT dateipfad FROM song_dateien WHERE song_id = ?1 AND rolle = 'audio'
+             ORDER BY dateipfad LIMIT 1)
+         WHERE id = ?1 AND audio_pfad = ?2",
+        params![song_id, rel_path],
+    )
+    .log_err();
+    conn.execute(
+        "UPDATE songs SET has_audio = audio_pfad IS NOT NULL WHERE id = ?1",
+        params![song_id],
+    )
+    .log_err();
+    Some(song_id)
+}
+
+pub fn set_role(conn: &Connection, rel_path: &str, rolle: Role) {
+    conn.execute(
+        "UPDATE song_dateien SET rolle = ?2 WHERE dateipfad = ?1",
+        params![rel_path, rolle.as_str()],
+    )
+    .log_err();
+}
+
+/// Drop companions whose file is gone. Returns how many were removed.
+pub fn remove_missing(conn: &Connection, base_dir: &Path) -> usize {
+    let mut removed = 0;
+    for rel_path in companion_paths(conn) {
+        if !base_dir.join(&rel_path).exists() && remove_companion(conn, &rel_path).is_some() {
+            removed += 1;
+        }
+    }
+    removed
+}
+
+/// Title reduced for matching: lowercase letters and digits only, without
+/// words that name the kind of file ("Tab", "Lyrics", "Playback").
+pub fn match_key(titel: &str) -> String {
+    const ROLE_WORDS: &[&str] = &[
+        "tab",
+        "tabs",
+        "tabulatur",
+        "text",
+        "lyrics",
+        "songtext",
+        "akkorde",
+        "chords",
+        "noten",
+        "sheet",
+        "playback",
+        "backing",
+        "track",
+        "audio",
+        "demo",
+        "kopie",
+    ];
+    titel
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty() && !ROLE_WORDS.contains(w))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A file the matcher would attach to a song.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub dateipfad: String,
+    pub rolle: Role,
+    /// Set if the file is a song of its own now, which would be merged.
+    pub song_id: Option<i64>,
+}
+
+/// Files that seem to belong together: one song and the files the matcher
+/// would attach to it.
+#[derive(Debug, Clone)]
+pub struct Group {
+    pub song_id: i64,
+    pub titel: String,
+    pub artist: Option<String>,
+    pub dateipfad: String,
+    pub dateien: Vec<Candidate>,
+}
+
+/// Preference for the main file of a group: sheet music first, audio last.
+fn main_file_rank(dateityp: &str) -> usize {
+    let order = [
+        FileType::Pdf,
+        FileType::MusicXml,
+        FileType::ChordPro,
+        FileType::GuitarPro,
+        FileType::Text,
+        FileType::Image,
+        FileType::Audio,
+    ];
+    order
+        .iter()
+        .position(|t| t.as_str() == dateityp)
+        .unwrap_or(order.len())
+}
+
+/// Songs and loose audio files with the same title (see [`match_key`]), no
+/// matter in which folder. A title whose files name different artists is
+/// left out, and so is a group without a chart. The song that keeps its row
+/// is the one that already has companions, else the best main file type.
+/// Audio files are found on disk, except ignored ones and those already used
+/// as backing tracks or companions.
+pub fn suggest(conn: &Connection, base_dir: &Path, options: &ScanOptions) -> Vec<Group> {
+    struct Entry {
+        song_id: Option<i64>,
+        titel: String,
+        artist: Option<String>,
+        dateipfad: String,
+        dateityp: String,
+        has_companions: bool,
+    }
+
+    let songs: Vec<Entry> = query_rows(
+        conn,
+        "SELECT s.id, s.titel, s.artist, s.dateipfad, COALESCE(s.dateityp, 'pdf'),
+                EXISTS (SELECT 1 FROM song_dateien WHERE song_id = s.id)
+         FROM songs s WHERE s.deleted_at IS NULL AND s.ignoriert = 0",
+        [],
+        |row| {
+            Ok(Entry {
+                song_id: Some(row.get(0)?),
+                titel: row.get(1)?,
+                artist: row.get(2)?,
+                dateipfad: row.get(3)?,
+                dateityp: row.get(4)?,
+                has_companions: row.get(5)?,
+            })
+        },
+    );
+    let mut known: HashSet<String> = companion_paths(conn);
+    known.extend(query_rows(
+        conn,
+        "SELECT dateipfad FROM songs UNION SELECT audio_pfad FROM songs WHERE audio_pfad IS NOT NULL",
+        [],
+        |row| row.get::<_, String>(0),
+    ));
+    let loose_audio = WalkDir::new(base_dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| !e.path().starts_with(base_dir.join("songindex")) && is_audio_file(e.path()))
+        .filter_map(|e| {
+            let rel_path = nfc(e.path().strip_prefix(base_dir).ok()?.to_string_lossy());
+            if known.contains(&rel_path)
+                || options.is_ignored(&rel_path)
+                || rel_path.split('/').any(|c| c.starts_with('.'))
+            {
+                return None;
+            }
+            let name = nfc(e.file_name().to_string_lossy());
+            let (titel, artist) = parse_filename(&name);
+            Some(Entry {
+                song_id: None,
+                titel,
+                artist,
+                dateipfad: rel_path,
+                dateityp: FileType::Audio.as_str().to_string(),
+                has_companions: false,
+            })
+        });
+
+    let mut by_title: BTreeMap<String, Vec<Entry>> = BTreeMap::new();
+    for entry in songs.into_iter().chain(loose_audio) {
+        let key = match_key(&entry.titel);
+        if !key.is_empty() {
+            by_title.entry(key).or_default().push(entry);
+        }
+    }
+
+    let mut groups = Vec::new();
+    for (_, mut entries) in by_title {
+        if entries.len() < 2 {
+            continue;
+        }
+        let artists: HashSet<String> = entries
+            .iter()
+            .filter_map(|e| e.artist.as_deref())
+            .map(|a| a.trim().to_lowercase())
+            .filter(|a| !a.is_empty())
+            .collect();
+        if artists.len() > 1 {
+            continue;
+        }
+        entries.sort_by_key(|e| {
+            (
+                e.song_id.is_none(),
+                !e.has_companions,
+                main_file_rank(&e.dateityp),
+                e.dateipfad.len(),
+            )
+        });
+        let main = entries.remove(0);
+        let Some(song_id) = main
+            .song_id
+            .filter(|_| main.dateityp != FileType::Audio.as_str())
+        else {
+            continue;
+        };
+        groups.push(Group {
+            song_id,
+            titel: main.titel,
+            artist: main
+                .artist
+                .or_else(|| entries.iter().find_map(|e| e.artist.clone())),
+            dateipfad: main.dateipfad,
+            dateien: entries
+                .into_iter()
+                .map(|e| Candidate {
+                    rolle: Role::guess(&e.dateipfad),
+                    dateipfad: e.dateipfad,
+                    song_id: e.song_id,
+                })
+                .collect(),
+        });
+    }
+    groups.sort_by_key(|g| g.titel.to_lowercase());
+    groups
+}
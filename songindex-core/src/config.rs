@@ -0,0 +1,665 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+#[derive(Serialize, Deserialize)]
+pub struct Config {
+    pub music_dir: PathBuf,
+    /// Folder with blank chart templates; defaults to `vorlagen/` in the data dir.
+    #[serde(default)]
+    pub template_dir: Option<PathBuf>,
+    /// The library file; relative paths are relative to the data dir.
+    /// Defaults to `songindex.db` in the data dir.
+    #[serde(default)]
+    pub database: Option<PathBuf>,
+    /// How many days of daily metadata snapshots to keep.
+    #[serde(default = "default_snapshot_retention_days")]
+    pub snapshot_retention_days: u32,
+    /// Show the daily warm-up set when the app starts.
+    #[serde(default = "default_true")]
+    pub warmup_on_startup: bool,
+    /// `technik` values the warm-up draws from; empty means all of them.
+    #[serde(default)]
+    pub warmup_techniken: Vec<String>,
+    /// Days until a "kann ich" song is due for its first review.
+    #[serde(default = "default_review_interval_days")]
+    pub review_interval_days: u32,
+    /// Optional encrypted cloud backup of the database.
+    #[serde(default)]
+    pub backup: BackupConfig,
+    /// Set once the starter tag packs have been offered on a library without tags.
+    #[serde(default)]
+    pub tag_packs_offered: bool,
+    /// Memory budget for decoded thumbnails in the grid view.
+    #[serde(default = "default_thumbnail_cache_mb")]
+    pub thumbnail_cache_mb: u32,
+    /// Memory budget for parsed charts kept for the chart viewer.
+    #[serde(default = "default_chart_cache_mb")]
+    pub chart_cache_mb: u32,
+    /// Index audio files without a matching chart as songs of their own.
+    #[serde(default)]
+    pub audio_entries: bool,
+    /// Player volume, 0.0–1.0, before the per-song gain.
+    #[serde(default = "default_volume")]
+    pub volume: f32,
+    /// Glob patterns relative to the music folder the scanner skips,
+    /// e.g. `**/Archiv/**` or `*_old.pdf`.
+    #[serde(default)]
+    pub ignore_patterns: Vec<String>,
+    /// Above this many songs the app suggests excluding large folders;
+    /// 0 turns the warning off.
+    #[serde(default = "default_scan_warn_limit")]
+    pub scan_warn_limit: usize,
+    /// Language of the UI (i18n.rs).
+    #[serde(default)]
+    pub language: Language,
+    /// How dates and numbers are written (format.rs).
+    #[serde(default)]
+    pub locale: Locale,
+    /// What a double-click or Enter on a song does, per view.
+    #[serde(default)]
+    pub open_actions: OpenActions,
+    /// App per file extension (lowercase, without the dot), e.g.
+    /// `gp5 = "Guitar Pro 8"`; other files open in the system default.
+    #[serde(default)]
+    pub open_with: BTreeMap<String, String>,
+    /// Tags given to newly indexed songs by folder names (autotag.rs).
+    #[serde(default = "default_auto_tag_rules")]
+    pub auto_tag_rules: Vec<AutoTagRule>,
+    /// Background full rescan, for changes the watcher missed while the
+    /// machine was asleep.
+    #[serde(default)]
+    pub rescan: RescanSchedule,
+    /// Public repertoire site generated on demand (site.rs).
+    #[serde(default)]
+    pub website: WebsiteConfig,
+    /// Markdown notes per song for an Obsidian vault (obsidian.rs).
+    #[serde(default)]
+    pub obsidian: ObsidianConfig,
+    /// Read-only browser view of the library for the local network (lan.rs).
+    #[serde(default)]
+    pub lan: LanConfig,
+    /// Colors that replace the built-in ones (`palette` in the app).
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    /// Running on the generated demo library (demo.rs): `music_dir` points
+    /// at a temp folder, so nothing is written back to `config.toml`.
+    #[serde(skip)]
+    pub demo: bool,
+}
+
+/// A song whose path relative to the music folder contains `pattern` in the
+/// part given by `bereich` gets the tag `kategorie:wert`. Rules are checked in
+/// list order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AutoTagRule {
+    pub pattern: String,
+    pub kategorie: String,
+    pub wert: String,
+    #[serde(default)]
+    pub bereich: RuleScope,
+    /// Folder depth for `RuleScope::Ebene`, 1 = top level of the music folder.
+    #[serde(default = "default_rule_ebene")]
+    pub ebene: u32,
+    /// Only songs below this folder (relative to the music folder); empty = all.
+    #[serde(default)]
+    pub unter: String,
+    /// Match regardless of upper and lower case.
+    #[serde(default)]
+    pub ohne_gross_klein: bool,
+    /// The pattern must not continue a word: no letter or digit right
+    /// before or after it, so "Blues" does not match "Bluestone".
+    #[serde(default)]
+    pub ganzes_wort: bool,
+    /// Replace what earlier rules set in this category.
+    #[serde(default)]
+    pub ersetzen: bool,
+    /// Skip the remaining rules once this one matched.
+    #[serde(default)]
+    pub stoppen: bool,
+}
+
+/// Which part of a song's path an auto-tag rule looks at.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleScope {
+    /// The whole relative path, file name included.
+    #[default]
+    Pfad,
+    /// Any folder name, not the file name.
+    Ordner,
+    /// Only the folder the file is in.
+    Elternordner,
+    /// Only the folder at depth `ebene`.
+    Ebene,
+}
+
+impl RuleScope {
+    pub const ALL: [RuleScope; 4] = [
+        RuleScope::Pfad,
+        RuleScope::Ordner,
+        RuleScope::Elternordner,
+        RuleScope::Ebene,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RuleScope::Pfad => "Ganzer Pfad",
+            RuleScope::Ordner => "Ein Ordner",
+            RuleScope::Elternordner => "Direkter Ordner",
+            RuleScope::Ebene => "Ordnerebene",
+        }
+    }
+}
+
+/// UI language. Strings without a translation stay German.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    De,
+    En,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::De, Language::En];
+
+    /// The language's own name, so it can be found in either UI.
+    pub fn label(self) -> &'static str {
+        match self {
+            Language::De => "Deutsch",
+            Language::En => "English",
+        }
+    }
+}
+
+/// Date and number format.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    /// 17.10.2026, 50.000, "vor 3 Tagen"
+    #[default]
+    De,
+    /// Oct 17, 2026, 50,000, "3 days ago"
+    En,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::De, Locale::En];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Locale::De => "Deutsch (17.10.2026)",
+            Locale::En => "English (Oct 17, 2026)",
+        }
+    }
+}
+
+/// Default action for a song on double-click or Enter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OpenAction {
+    #[default]
+    Datei,
+    /// Start or stop the backing track; songs without one open the file.
+    Audio,
+    /// Select the song, which shows the detail panel.
+    Details,
+}
+
+impl OpenAction {
+    pub const ALL: [OpenAction; 3] = [OpenAction::Datei, OpenAction::Audio, OpenAction::Details];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OpenAction::Datei => "Datei \u{00F6}ffnen",
+            OpenAction::Audio => "Audio abspielen",
+            OpenAction::Details => "Details zeigen",
+        }
+    }
+}
+
+/// `OpenAction` for each view, e.g. audio in the grid used on stage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct OpenActions {
+    #[serde(default)]
+    pub liste: OpenAction,
+    #[serde(default)]
+    pub tabelle: OpenAction,
+    #[serde(default)]
+    pub raster: OpenAction,
+}
+
+fn default_rule_ebene() -> u32 {
+    1
+}
+
+/// Style and instrument rules look at whole words in folder names only, so
+/// "Bluestone" in a file name does not count; the artist rule matches file
+/// names. Also what "Standardregeln" in the rule editor merges back in.
+pub fn default_auto_tag_rules() -> Vec<AutoTagRule> {
+    let folder = |pattern: &str, kategorie: &str, wert: &str| AutoTagRule {
+        pattern: pattern.to_string(),
+        kategorie: kategorie.to_string(),
+        wert: wert.to_string(),
+        bereich: RuleScope::Ordner,
+        ebene: default_rule_ebene(),
+        ohne_gross_klein: true,
+        ganzes_wort: true,
+        ..Default::default()
+    };
+    vec![
+        folder("E-Gitarre", "instrument", "E-Gitarre"),
+        folder("Zupfen", "technik", "Fingerpicking"),
+        folder("Anfaenger", "schwierigkeit", "Anf\u{00E4}nger"),
+        folder("Kinderlieder", "schwierigkeit", "Anf\u{00E4}nger"),
+        folder("Kinderlieder", "stil", "Kinderlieder"),
+        folder("Moderne Popsongs", "stil", "Pop"),
+        folder("Mundart", "stil", "Mundart"),
+        folder("Weihnachtssongs", "stil", "Weihnachten"),
+        folder("Christmas", "stil", "Weihnachten"),
+        folder("Worship", "stil", "Worship"),
+        folder("Blues", "stil", "Blues"),
+        folder("Jazz", "stil", "Jazz"),
+        folder("Solos", "technik", "Solo"),
+        folder("Klassisch", "stil", "Klassik"),
+        folder("Ukulele", "instrument", "Ukulele"),
+        AutoTagRule {
+            bereich: RuleScope::Pfad,
+            ..folder("The Beatles", "artist", "The Beatles")
+        },
+        folder("Bossa", "stil", "Bossa Nova"),
+        folder("Samba", "stil", "Bossa Nova"),
+    ]
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct ObsidianConfig {
+    /// Folder inside the vault the notes are written to.
+    #[serde(default)]
+    pub ordner: Option<PathBuf>,
+    /// Rewrite the notes in the background whenever the library changes.
+    #[serde(default)]
+    pub aktuell_halten: bool,
+}
+
+/// Colors as `#rrggbb`; anything unset or unreadable keeps the default.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThemeConfig {
+    /// Buttons, selection and highlights.
+    #[serde(default)]
+    pub akzent: Option<String>,
+    /// Window background; panels, cards, buttons and borders are shades of it.
+    #[serde(default)]
+    pub hintergrund: Option<String>,
+    /// Tag chips per category, e.g. `stil = "#3a7dbe"`.
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LanConfig {
+    /// Serve the library while the app runs; stays on across restarts.
+    #[serde(default)]
+    pub aktiv: bool,
+    #[serde(default = "default_lan_port")]
+    pub port: u16,
+}
+
+impl Default for LanConfig {
+    fn default() -> Self {
+        Self {
+            aktiv: false,
+            port: default_lan_port(),
+        }
+    }
+}
+
+fn default_lan_port() -> u16 {
+    8080
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "modus", rename_all = "lowercase")]
+pub enum RescanSchedule {
+    #[default]
+    Aus,
+    /// Every `stunden` hours after the last scan.
+    Intervall { stunden: u32 },
+    /// Once a day, at the first check after `stunde` o'clock (local time),
+    /// so a machine that sleeps at night catches up in the morning.
+    Nachts { stunde: u32 },
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WebsiteConfig {
+    pub titel: String,
+    /// Shown under the title, e.g. a note for prospective students.
+    #[serde(default)]
+    pub einleitung: String,
+    /// Folder the site is written to.
+    #[serde(default)]
+    pub ausgabe: Option<PathBuf>,
+    /// Only songs marked "kann ich".
+    #[serde(default = "default_true")]
+    pub nur_repertoire: bool,
+    /// Tag categories shown on the site; tags of other categories stay private.
+    #[serde(default = "default_website_kategorien")]
+    pub kategorien: Vec<String>,
+}
+
+impl Default for WebsiteConfig {
+    fn default() -> Self {
+        Self {
+            titel: "Repertoire".to_string(),
+            einleitung: String::new(),
+            ausgabe: None,
+            nur_repertoire: true,
+            kategorien: default_website_kategorien(),
+        }
+    }
+}
+
+fn default_website_kategorien() -> Vec<String> {
+    vec!["stil".to_string(), "schwierigkeit".to_string()]
+}
+
+/// The passwords live in the system keychain; config.toml only names their
+/// keychain accounts (`*_ref`).
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BackupConfig {
+    pub enabled: bool,
+    pub target: BackupTarget,
+    /// Keychain account of the passphrase the uploaded snapshots are
+    /// encrypted with.
+    #[serde(default = "default_passphrase_ref")]
+    pub passphrase_ref: String,
+    /// Plain-text passphrase of older configs; moved to the keychain on
+    /// startup, kept only while that fails.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub passphrase: String,
+    #[serde(default = "default_backup_interval_hours")]
+    pub interval_hours: u32,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum BackupTarget {
+    /// Collection URL, e.g. `https://cloud.example.com/remote.php/dav/files/me/songindex`.
+    WebDav {
+        url: String,
+        username: String,
+        #[serde(default = "default_password_ref")]
+        password_ref: String,
+        /// Plain text from older configs, see [`BackupConfig::passphrase`].
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        password: String,
+    },
+    /// S3-compatible endpoint, addressed path-style (`endpoint/bucket/key`).
+    S3 {
+        endpoint: String,
+        bucket: String,
+        region: String,
+        access_key: String,
+        #[serde(default = "default_secret_key_ref")]
+        secret_key_ref: String,
+        /// Plain text from older configs, see [`BackupConfig::passphrase`].
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        secret_key: String,
+    },
+}
+
+impl BackupTarget {
+    pub fn webdav() -> Self {
+        BackupTarget::WebDav {
+            url: String::new(),
+            username: String::new(),
+            password_ref: default_password_ref(),
+            password: String::new(),
+        }
+    }
+
+    pub fn s3() -> Self {
+        BackupTarget::S3 {
+            endpoint: String::new(),
+            bucket: String::new(),
+            region: "us-east-1".to_string(),
+            access_key: String::new(),
+            secret_key_ref: default_secret_key_ref(),
+            secret_key: String::new(),
+        }
+    }
+
+    /// Keychain account of the WebDAV password or the S3 secret key.
+    pub fn secret_ref(&self) -> &str {
+        match self {
+            BackupTarget::WebDav { password_ref, .. } => password_ref,
+            BackupTarget::S3 { secret_key_ref, .. } => secret_key_ref,
+        }
+    }
+}
+
+impl BackupConfig {
+    /// Plain-text secrets left in config.toml by older versions, by keychain
+    /// account, cleared here. The caller stores them and saves the config.
+    pub fn take_plain_secrets(&mut self) -> Vec<(String, String)> {
+        let mut secrets = Vec::new();
+        if !self.passphrase.is_empty() {
+            secrets.push((
+                self.passphrase_ref.clone(),
+                std::mem::take(&mut self.passphrase),
+            ));
+        }
+        let secret_ref = self.target.secret_ref().to_string();
+        let (BackupTarget::WebDav {
+            password: secret, ..
+        }
+        | BackupTarget::S3 {
+            secret_key: secret, ..
+        }) = &mut self.target;
+        if !secret.is_empty() {
+            secrets.push((secret_ref, std::mem::take(secret)));
+        }
+        secrets
+    }
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target: BackupTarget::webdav(),
+            passphrase_ref: default_passphrase_ref(),
+            passphrase: String::new(),
+            interval_hours: default_backup_interval_hours(),
+        }
+    }
+}
+
+fn default_passphrase_ref() -> String {
+    "backup-passphrase".to_string()
+}
+
+fn default_password_ref() -> String {
+    "backup-webdav-password".to_string()
+}
+
+fn default_secret_key_ref() -> String {
+    "backup-s3-secret-key".to_string()
+}
+
+fn default_backup_interval_hours() -> u32 {
+    24
+}
+
+fn default_snapshot_retention_days() -> u32 {
+    30
+}
+
+fn default_review_interval_days() -> u32 {
+    14
+}
+
+fn default_thumbnail_cache_mb() -> u32 {
+    128
+}
+
+fn default_chart_cache_mb() -> u32 {
+    16
+}
+
+fn default_scan_warn_limit() -> usize {
+    10_000
+}
+
+fn default_volume() -> f32 {
+    1.0
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            music_dir: PathBuf::new(),
+            template_dir: None,
+            database: None,
+            snapshot_retention_days: default_snapshot_retention_days(),
+            warmup_on_startup: true,
+            warmup_techniken: Vec::new(),
+            review_interval_days: default_review_interval_days(),
+            backup: BackupConfig::default(),
+            tag_packs_offered: false,
+            thumbnail_cache_mb: default_thumbnail_cache_mb(),
+            chart_cache_mb: default_chart_cache_mb(),
+            audio_entries: false,
+            volume: default_volume(),
+            ignore_patterns: Vec::new(),
+            scan_warn_limit: default_scan_warn_limit(),
+            language: Language::default(),
+            locale: Locale::default(),
+            open_actions: OpenActions::default(),
+            open_with: BTreeMap::new(),
+            auto_tag_rules: default_auto_tag_rules(),
+            rescan: RescanSchedule::default(),
+            website: WebsiteConfig::default(),
+            obsidian: ObsidianConfig::default(),
+            lan: LanConfig::default(),
+            theme: ThemeConfig::default(),
+            demo: false,
+        }
+    }
+}
+
+impl Config {
+    pub fn database_path(&self) -> PathBuf {
+        data_dir().join(self.database.as_deref().unwrap_or(DATABASE_FILE.as_ref()))
+    }
+
+    pub fn template_dir(&self) -> PathBuf {
+        self.template_dir
+            .clone()
+            .unwrap_or_else(|| data_dir().join("vorlagen"))
+    }
+
+    pub fn thumbnail_cache_bytes(&self) -> usize {
+        self.thumbnail_cache_mb as usize * 1024 * 1024
+    }
+
+    pub fn chart_cache_bytes(&self) -> usize {
+        self.chart_cache_mb as usize * 1024 * 1024
+    }
+}
+
+pub const DATABASE_FILE: &str = "songindex.db";
+/// A folder of this name next to the app switches on portable mode.
+pub const PORTABLE_DIR: &str = "songindex-daten";
+
+/// The folder the executable is in; for a macOS app bundle, the folder the
+/// `.app` is in.
+pub fn executable_dir() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let dir = exe.parent()?;
+    let bundle = dir
+        .ancestors()
+        .nth(2)
+        .filter(|b| dir.ends_with("Contents/MacOS") && b.extension().is_some_and(|e| e == "app"));
+    Some(match bundle {
+        Some(bundle) => bundle.parent()?.to_path_buf(),
+        None => dir.to_path_buf(),
+    })
+}
+
+/// `songindex-daten` next to the app, if it exists: config, library and
+/// caches then travel with the app, e.g. on a USB stick.
+pub fn portable_dir() -> Option<PathBuf> {
+    static DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+    DIR.get_or_init(|| {
+        executable_dir()
+            .map(|dir| dir.join(PORTABLE_DIR))
+            .filter(|dir| dir.is_dir())
+    })
+    .clone()
+}
+
+/// The portable folder if there is one, else
+/// `~/Library/Application Support/songindex`, created on first use. Without
+/// a home directory this falls back to the working directory; writing there
+/// then fails and is reported by whoever writes.
+pub fn data_dir() -> PathBuf {
+    let dir = portable_dir().unwrap_or_else(|| {
+        dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("songindex")
+    });
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        log::warn!("Cannot create data directory {}: {e}", dir.display());
+    }
+    dir
+}
+
+pub fn config_path() -> PathBuf {
+    data_dir().join("config.toml")
+}
+
+/// The settings in `config.toml`, or None if there is no such file yet. A
+/// file that cannot be read or parsed, e.g. after a typo in a hand edit, is
+/// an error naming the problem.
+pub fn read_config() -> Result<Option<Config>, String> {
+    let path = config_path();
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("{}: {e}", path.display())),
+    };
+    toml::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// [`read_config`] for callers that only need valid settings; a broken file
+/// is logged and treated as none.
+pub fn load_config() -> Option<Config> {
+    read_config()
+        .map_err(|e| log::error!("Cannot load config: {e}"))
+        .ok()
+        .flatten()
+}
+
+/// Does nothing in demo mode, which must not replace the real music folder.
+/// Refuses to replace a `config.toml` that does not parse: it holds the
+/// user's settings until they fix it.
+pub fn save_config(config: &Config) -> std::io::Result<()> {
+    if config.demo {
+        return Ok(());
+    }
+    if let Err(e) = read_config() {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, e));
+    }
+    let content = toml::to_string_pretty(config)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    std::fs::write(config_path(), content)
+}
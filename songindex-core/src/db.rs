@@ -0,0 +1,1672 @@
+use crate::arrangements::share_from;
+use crate::companions::{all_companions, companions, Companion};
+use crate::filetype::FileType;
+use rusqlite::{params, Connection, Params, Row};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Song {
+    pub id: i64,
+    pub titel: String,
+    pub artist: Option<String>,
+    pub dateipfad: String,
+    pub dateiname: String,
+    pub has_audio: bool,
+    pub audio_pfad: Option<String>,
+    pub created_at: String,
+    /// Marked as "kann ich" (part of the performance repertoire).
+    pub kann_ich: bool,
+    /// Free-form Markdown notes (teaching notes, tricky bars, arrangement ideas).
+    pub notizen: Option<String>,
+    /// 1–5 stars, 0 = not rated.
+    pub bewertung: i64,
+    pub favorit: bool,
+    pub tonart: Option<String>,
+    pub bpm: Option<i64>,
+    pub kapo: Option<i64>,
+    /// `FileType::as_str()` of the song file.
+    pub dateityp: String,
+    /// Left out of the public repertoire site (site.rs).
+    pub privat: bool,
+    /// Days since the file was last opened from the app, `None` if never.
+    pub zuletzt_geoeffnet: Option<i64>,
+    /// Opens and audio plays recorded in `song_events`.
+    pub nutzungen: i64,
+    /// Days since the last entry in `practice_log`, `None` if never practiced.
+    pub zuletzt_geuebt: Option<i64>,
+    /// App this song opens in, overriding `Config::open_with` (open_with.rs).
+    pub oeffnen_mit: Option<String>,
+    /// Year of the original release.
+    pub jahr: Option<i64>,
+    /// Page count of a PDF, read on scan and by the text job.
+    pub seiten: Option<i64>,
+    pub tags: Vec<TagInfo>,
+    /// Lesson videos, recordings, tab pages and notes elsewhere (`song_links`).
+    pub links: Vec<SongLink>,
+    /// Tabs, lyrics sheets and recordings that belong to the song (`song_dateien`).
+    pub dateien: Vec<Companion>,
+    /// The piece this is one arrangement of (`werke`, arrangements.rs).
+    pub werk_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagInfo {
+    pub id: i64,
+    pub kategorie: String,
+    pub wert: String,
+    pub auto_generated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagGroup {
+    pub kategorie: String,
+    pub tags: Vec<TagEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagEntry {
+    pub id: i64,
+    pub wert: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    pub total_songs: i64,
+    pub songs_with_audio: i64,
+    pub untagged_songs: i64,
+    pub practice_seconds_week: i64,
+    pub review_due: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
+pub enum SortMode {
+    Title,
+    Artist,
+    Recent,
+    Untagged,
+    Rating,
+    LastOpened,
+    MostUsed,
+    /// Easiest first by the difficulty scale; songs without a ranked
+    /// `schwierigkeit` tag last.
+    Difficulty,
+}
+
+impl SortMode {
+    pub fn label(&self) -> &str {
+        match self {
+            SortMode::Title => "Titel",
+            SortMode::Artist => "Artist",
+            SortMode::Recent => "Neueste zuerst",
+            SortMode::Untagged => "Ohne Tags zuerst",
+            SortMode::Rating => "Bewertung",
+            SortMode::LastOpened => "Zuletzt ge\u{00F6}ffnet",
+            SortMode::MostUsed => "Am h\u{00E4}ufigsten verwendet",
+            SortMode::Difficulty => "Schwierigkeit",
+        }
+    }
+
+    pub fn all() -> &'static [SortMode] {
+        &[
+            SortMode::Title,
+            SortMode::Artist,
+            SortMode::Recent,
+            SortMode::Untagged,
+            SortMode::Rating,
+            SortMode::LastOpened,
+            SortMode::MostUsed,
+            SortMode::Difficulty,
+        ]
+    }
+}
+
+/// How selected tags are combined: within one category, and across categories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MatchMode {
+    Any,
+    All,
+}
+
+impl MatchMode {
+    pub fn label(&self) -> &str {
+        match self {
+            MatchMode::Any => "ODER",
+            MatchMode::All => "UND",
+        }
+    }
+
+    pub fn toggled(self) -> Self {
+        match self {
+            MatchMode::Any => MatchMode::All,
+            MatchMode::All => MatchMode::Any,
+        }
+    }
+}
+
+/// Selected tag filters: one group of tag ids per category with its own mode,
+/// combined with `mode`.
+pub struct TagFilter {
+    pub groups: Vec<(Vec<i64>, MatchMode)>,
+    pub mode: MatchMode,
+}
+
+impl TagFilter {
+    pub fn none() -> Self {
+        Self {
+            groups: Vec::new(),
+            mode: MatchMode::All,
+        }
+    }
+}
+
+/// Toggles and value ranges from the filter accordion. Ranges are inclusive.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExtraFilters {
+    pub has_audio: bool,
+    pub untagged: bool,
+    pub favorites: bool,
+    pub bpm: Option<(i64, i64)>,
+    pub kapo_max: Option<i64>,
+    /// Range of difficulty ranks (`tags.rang`).
+    #[serde(default)]
+    pub schwierigkeit: Option<(i64, i64)>,
+    /// Songs must also match the rule of this smart list.
+    #[serde(default)]
+    pub smart_list: Option<i64>,
+}
+
+/// Timestamps are stored as RFC 3339 in UTC ("2026-10-17T06:30:00Z"): they
+/// sort as text and mean the same instant after a time zone or DST change.
+/// SQL that shows them or compares them with calendar days converts to local
+/// time with the helpers below.
+const STORED_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+/// SQL expression for the current time in the stored format.
+pub const NOW: &str = "strftime('%Y-%m-%dT%H:%M:%SZ', 'now')";
+
+/// SQL expression for a stored timestamp as local time ("2026-10-17 08:30:00").
+pub fn local_time(expr: &str) -> String {
+    format!("datetime({expr}, 'localtime')")
+}
+
+/// SQL expression for the local calendar day of a stored timestamp.
+pub fn local_date(expr: &str) -> String {
+    format!("date({expr}, 'localtime')")
+}
+
+/// SQL expression turning a local time or date into the stored format, e.g.
+/// the start of a local day for range comparisons.
+pub fn from_local(expr: &str) -> String {
+    format!("strftime('{STORED_FORMAT}', {expr}, 'utc')")
+}
+
+/// SQL expression for the current time shifted by an SQLite modifier, e.g.
+/// `"-30 days"`, in the stored format.
+pub fn now_shifted(modifier: &str) -> String {
+    format!("strftime('{STORED_FORMAT}', 'now', '{modifier}')")
+}
+
+/// Create the schema and migrate older libraries. Only creating the base
+/// tables can fail; later migrations are best effort.
+pub fn init_db(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS songs (
+            id INTEGER PRIMARY KEY,
+            titel TEXT NOT NULL,
+            artist TEXT,
+            dateipfad TEXT NOT NULL UNIQUE,
+            dateiname TEXT NOT NULL,
+            has_audio INTEGER DEFAULT 0,
+            audio_pfad TEXT,
+            created_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+            updated_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+        );
+
+        CREATE TABLE IF NOT EXISTS tags (
+            id INTEGER PRIMARY KEY,
+            kategorie TEXT NOT NULL,
+            wert TEXT NOT NULL,
+            UNIQUE(kategorie, wert)
+        );
+
+        CREATE TABLE IF NOT EXISTS song_tags (
+            song_id INTEGER REFERENCES songs(id) ON DELETE CASCADE,
+            tag_id INTEGER REFERENCES tags(id) ON DELETE CASCADE,
+            auto_generated INTEGER DEFAULT 0,
+            PRIMARY KEY (song_id, tag_id)
+        );
+
+        CREATE TABLE IF NOT EXISTS practice_log (
+            id INTEGER PRIMARY KEY,
+            song_id INTEGER REFERENCES songs(id) ON DELETE CASCADE,
+            started_at TEXT NOT NULL,
+            seconds INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_songs_dateipfad ON songs(dateipfad);
+        CREATE INDEX IF NOT EXISTS idx_songs_titel ON songs(titel);
+        CREATE INDEX IF NOT EXISTS idx_tags_kategorie ON tags(kategorie);
+        CREATE INDEX IF NOT EXISTS idx_practice_log_started ON practice_log(started_at);
+        CREATE INDEX IF NOT EXISTS idx_practice_log_song ON practice_log(song_id);
+        CREATE INDEX IF NOT EXISTS idx_songs_audio_pfad ON songs(audio_pfad);
+        CREATE INDEX IF NOT EXISTS idx_song_tags_tag ON song_tags(tag_id);
+        ",
+    )?;
+
+    // Columns added after the first release; fails harmlessly if they exist.
+    add_column(conn, "songs", "repertoire_seit TEXT");
+    add_column(conn, "songs", "wiederholung_intervall INTEGER");
+    add_column(conn, "songs", "letzte_wiederholung TEXT");
+    add_column(conn, "songs", "notizen TEXT");
+    add_column(conn, "songs", "bewertung INTEGER");
+    add_column(conn, "songs", "favorit INTEGER NOT NULL DEFAULT 0");
+    // Key and capo used to be plain tags; take them over once when the columns appear.
+    if add_column(conn, "songs", "tonart TEXT") {
+        conn.execute(
+            "UPDATE songs SET tonart = (
+                SELECT t.wert FROM song_tags st JOIN tags t ON t.id = st.tag_id
+                WHERE st.song_id = songs.id AND t.kategorie = 'tonart' LIMIT 1)",
+            [],
+        )
+        .ok();
+    }
+    add_column(conn, "songs", "bpm INTEGER");
+    if add_column(conn, "songs", "kapo INTEGER") {
+        conn.execute(
+            "UPDATE songs SET kapo = (
+                SELECT CAST(t.wert AS INTEGER) FROM song_tags st JOIN tags t ON t.id = st.tag_id
+                WHERE st.song_id = songs.id AND t.kategorie = 'kapo' AND t.wert GLOB '[0-9]*' LIMIT 1)",
+            [],
+        )
+        .ok();
+    }
+    if add_column(conn, "songs", "dateityp TEXT") {
+        backfill_file_types(conn);
+    }
+    // Results of the background job queue (jobs.rs).
+    add_column(conn, "songs", "volltext TEXT");
+    add_column(conn, "songs", "datei_hash TEXT");
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS erledigte_jobs (
+            dateipfad TEXT NOT NULL,
+            art TEXT NOT NULL,
+            mtime INTEGER NOT NULL,
+            PRIMARY KEY (dateipfad, art)
+        );",
+    )
+    .ok();
+    add_column(conn, "songs", "privat INTEGER NOT NULL DEFAULT 0");
+    // Set when the file disappears; the row and its tags stay in the Papierkorb.
+    add_column(conn, "songs", "deleted_at TEXT");
+    // Files in the tree that are not songs (invoices, method-book pages).
+    add_column(conn, "songs", "ignoriert INTEGER NOT NULL DEFAULT 0");
+    // Playback gain in dB for backing tracks that are much louder or quieter.
+    add_column(conn, "songs", "pegel_db REAL NOT NULL DEFAULT 0");
+    // Saved A–B loop of the audio file, in seconds.
+    add_column(conn, "songs", "schleife_von REAL");
+    add_column(conn, "songs", "schleife_bis REAL");
+    // Playback pitch shift in semitones, e.g. to match a capo or a singer.
+    add_column(conn, "songs", "halbtoene INTEGER NOT NULL DEFAULT 0");
+    // App for this song's file instead of the one for its extension.
+    add_column(conn, "songs", "oeffnen_mit TEXT");
+    // Year the song first came out, e.g. from a MusicBrainz lookup.
+    add_column(conn, "songs", "jahr INTEGER");
+    // Page count of PDFs (filetype::pdf_metadata()). Text jobs recorded
+    // before it did not count pages, so they run once more.
+    if add_column(conn, "songs", "seiten INTEGER") {
+        conn.execute("DELETE FROM erledigte_jobs WHERE art = 'text'", [])
+            .ok();
+    }
+    // Piece this song is one arrangement of (arrangements.rs).
+    add_column(
+        conn,
+        "songs",
+        "werk_id INTEGER REFERENCES werke(id) ON DELETE SET NULL",
+    );
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS song_events (
+            id INTEGER PRIMARY KEY,
+            song_id INTEGER REFERENCES songs(id) ON DELETE CASCADE,
+            art TEXT NOT NULL,
+            zeitpunkt TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+        );
+        CREATE INDEX IF NOT EXISTS idx_song_events_song ON song_events(song_id, art);",
+    )
+    .ok();
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS tag_nutzung (
+            tag_id INTEGER REFERENCES tags(id) ON DELETE CASCADE,
+            zeitpunkt TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+        );
+        CREATE INDEX IF NOT EXISTS idx_tag_nutzung_tag ON tag_nutzung(tag_id);",
+    )
+    .ok();
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS song_links (
+            id INTEGER PRIMARY KEY,
+            song_id INTEGER REFERENCES songs(id) ON DELETE CASCADE,
+            label TEXT NOT NULL,
+            url TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_song_links_song ON song_links(song_id);",
+    )
+    .ok();
+    // Songs the scanner moved to the Papierkorb, kept after purging (removal_log.rs).
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS entfernte_songs (
+            id INTEGER PRIMARY KEY,
+            dateipfad TEXT NOT NULL,
+            titel TEXT NOT NULL,
+            artist TEXT,
+            notizen TEXT,
+            tags TEXT NOT NULL,
+            zeitpunkt TEXT NOT NULL,
+            wartet INTEGER NOT NULL DEFAULT 0
+        );
+        CREATE INDEX IF NOT EXISTS idx_entfernte_songs_pfad ON entfernte_songs(dateipfad);",
+    )
+    .ok();
+    // Saved search rules shown as lists of their own (smart lists).
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS smart_listen (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            regel TEXT NOT NULL
+        );",
+    )
+    .ok();
+    // Learning paths: learn `vorher_id` before `song_id` (progression.rs).
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS song_folgen (
+            song_id INTEGER NOT NULL REFERENCES songs(id) ON DELETE CASCADE,
+            vorher_id INTEGER NOT NULL REFERENCES songs(id) ON DELETE CASCADE,
+            PRIMARY KEY (song_id, vorher_id)
+        );
+        CREATE INDEX IF NOT EXISTS idx_song_folgen_vorher ON song_folgen(vorher_id);",
+    )
+    .ok();
+    // Tabs, lyrics sheets and recordings kept with a song (companions.rs).
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS song_dateien (
+            dateipfad TEXT PRIMARY KEY,
+            song_id INTEGER NOT NULL REFERENCES songs(id) ON DELETE CASCADE,
+            rolle TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_song_dateien_song ON song_dateien(song_id);",
+    )
+    .ok();
+    // Pieces grouping arrangements of the same song (arrangements.rs).
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS werke (
+            id INTEGER PRIMARY KEY,
+            titel TEXT NOT NULL,
+            artist TEXT,
+            tonart TEXT
+        );",
+    )
+    .ok();
+    // Window geometry and list state for the next launch, as JSON per key.
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS ui_state (
+            schluessel TEXT PRIMARY KEY,
+            wert TEXT NOT NULL
+        );",
+    )
+    .ok();
+    // Pinned tags (e.g. from an imported taxonomy) survive orphan cleanup.
+    add_column(conn, "tags", "angeheftet INTEGER NOT NULL DEFAULT 0");
+    // Position of a `schwierigkeit` value on the difficulty scale; NULL for
+    // other categories and values not placed yet.
+    if add_column(conn, "tags", "rang INTEGER") {
+        let mut levels: Vec<(i64, String)> = query_rows(
+            conn,
+            "SELECT id, wert FROM tags WHERE kategorie = 'schwierigkeit' ORDER BY wert",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        );
+        // Known values first, so the others end up after them.
+        levels.sort_by_key(|(_, wert)| {
+            DEFAULT_DIFFICULTY
+                .iter()
+                .position(|d| d.eq_ignore_ascii_case(wert))
+                .unwrap_or(DEFAULT_DIFFICULTY.len())
+        });
+        for (id, wert) in levels {
+            place_difficulty(conn, id, &wert);
+        }
+    }
+    migrate_timestamps(conn);
+    Ok(())
+}
+
+/// Rewrite timestamps from before UTC storage. `created_at`/`updated_at` came
+/// from CURRENT_TIMESTAMP (UTC without a zone), everything else was local
+/// time. Rows already ending in "Z" are left alone, so this is safe on every
+/// start. Old tables keep their old column defaults, which is why inserts
+/// always pass [`NOW`] explicitly.
+fn migrate_timestamps(conn: &Connection) {
+    let columns = [
+        ("songs", "created_at", false),
+        ("songs", "updated_at", false),
+        ("songs", "deleted_at", true),
+        ("songs", "repertoire_seit", true),
+        ("songs", "letzte_wiederholung", true),
+        ("song_events", "zeitpunkt", true),
+        ("tag_nutzung", "zeitpunkt", true),
+        ("practice_log", "started_at", true),
+    ];
+    for (table, column, local) in columns {
+        let converted = if local {
+            from_local(column)
+        } else {
+            format!("strftime('{STORED_FORMAT}', {column})")
+        };
+        // strftime() yields NULL for unparsable text; keep such values as they are.
+        conn.execute(
+            &format!(
+                "UPDATE {table} SET {column} = COALESCE({converted}, {column})
+                 WHERE {column} IS NOT NULL AND {column} NOT LIKE '%Z'"
+            ),
+            [],
+        )
+        .ok();
+    }
+}
+
+/// Songs indexed before file types were stored get theirs from the extension.
+fn backfill_file_types(conn: &Connection) {
+    let songs: Vec<(i64, String)> =
+        query_rows(conn, "SELECT id, dateipfad FROM songs", [], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        });
+    for (id, dateipfad) in songs {
+        let file_type = FileType::from_path(Path::new(&dateipfad)).unwrap_or(FileType::Pdf);
+        conn.execute(
+            "UPDATE songs SET dateityp = ?1 WHERE id = ?2",
+            params![file_type.as_str(), id],
+        )
+        .ok();
+    }
+}
+
+/// All rows of a query that could be read. A query that fails is logged and
+/// yields no rows, so a damaged library shows empty lists instead of crashing.
+pub fn query_rows<T, P: Params>(
+    conn: &Connection,
+    sql: &str,
+    params: P,
+    f: impl FnMut(&Row<'_>) -> rusqlite::Result<T>,
+) -> Vec<T> {
+    let rows = conn.prepare(sql).and_then(|mut stmt| {
+        let rows = stmt.query_map(params, f)?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    });
+    rows.unwrap_or_else(|e| {
+        log::warn!("Query failed: {e}");
+        Vec::new()
+    })
+}
+
+/// `.ok()` for writes: a failed statement is logged instead of dropped, so
+/// the frontend can show it.
+pub trait LogErr<T> {
+    fn log_err(self) -> Option<T>;
+}
+
+impl<T> LogErr<T> for rusqlite::Result<T> {
+    fn log_err(self) -> Option<T> {
+        self.map_err(|e| log::warn!("Database write failed: {e}"))
+            .ok()
+    }
+}
+
+/// Returns true if the column was added, false if it already existed.
+fn add_column(conn: &Connection, table: &str, definition: &str) -> bool {
+    conn.execute(&format!("ALTER TABLE {table} ADD COLUMN {definition}"), [])
+        .is_ok()
+}
+
+pub fn get_or_create_tag(conn: &Connection, kategorie: &str, wert: &str) -> rusqlite::Result<i64> {
+    let created = conn.execute(
+        "INSERT OR IGNORE INTO tags (kategorie, wert) VALUES (?1, ?2)",
+        params![kategorie, wert],
+    )? > 0;
+    let id = conn.query_row(
+        "SELECT id FROM tags WHERE kategorie = ?1 AND wert = ?2",
+        params![kategorie, wert],
+        |row| row.get(0),
+    )?;
+    if created && kategorie == "schwierigkeit" {
+        place_difficulty(conn, id, wert);
+    }
+    Ok(id)
+}
+
+pub fn song_exists(conn: &Connection, song_id: i64) -> bool {
+    conn.query_row("SELECT 1 FROM songs WHERE id = ?1", params![song_id], |_| Ok(()))
+        .is_ok()
+}
+
+pub fn get_song_tags(conn: &Connection, song_id: i64) -> Vec<TagInfo> {
+    query_rows(
+        conn,
+        "SELECT t.id, t.kategorie, t.wert, st.auto_generated
+         FROM tags t
+         JOIN song_tags st ON t.id = st.tag_id
+         WHERE st.song_id = ?1
+         ORDER BY t.kategorie, t.wert",
+        params![song_id],
+        |row| {
+            Ok(TagInfo {
+                id: row.get(0)?,
+                kategorie: row.get(1)?,
+                wert: row.get(2)?,
+                auto_generated: row.get::<_, i64>(3)? != 0,
+            })
+        },
+    )
+}
+
+pub fn query_songs(
+    conn: &Connection,
+    search: &str,
+    tag_filter: &TagFilter,
+    extras: &ExtraFilters,
+    sort: &SortMode,
+) -> Vec<Song> {
+    select_songs(conn, search, tag_filter, extras, sort, None)
+}
+
+/// One song, if it still exists and matches the given filters.
+pub fn query_song(
+    conn: &Connection,
+    id: i64,
+    search: &str,
+    tag_filter: &TagFilter,
+    extras: &ExtraFilters,
+) -> Option<Song> {
+    select_songs(conn, search, tag_filter, extras, &SortMode::Title, Some(id))
+        .into_iter()
+        .next()
+}
+
+fn select_songs(
+    conn: &Connection,
+    search: &str,
+    tag_filter: &TagFilter,
+    extras: &ExtraFilters,
+    sort: &SortMode,
+    only: Option<i64>,
+) -> Vec<Song> {
+    puffin::profile_scope!("DB: Songs");
+    let mut sql = format!(
+        "SELECT DISTINCT s.id, s.titel, s.artist, s.dateipfad, s.dateiname, s.has_audio, s.audio_pfad,
+                {created_at},
+                s.repertoire_seit IS NOT NULL, s.notizen, COALESCE(s.bewertung, 0), s.favorit,
+                s.tonart, s.bpm, s.kapo, COALESCE(s.dateityp, 'pdf'), s.privat,
+                CAST(julianday(date('now', 'localtime')) - julianday({opened}) AS INTEGER),
+                (SELECT COUNT(*) FROM song_events WHERE song_id = s.id),
+                CAST(julianday(date('now', 'localtime')) - julianday({practiced}) AS INTEGER),
+                s.oeffnen_mit, s.jahr, s.werk_id, s.seiten
+         FROM songs s
+         LEFT JOIN song_tags st ON s.id = st.song_id
+         LEFT JOIN tags t ON st.tag_id = t.id
+         WHERE s.deleted_at IS NULL AND s.ignoriert = 0",
+        created_at = local_time("s.created_at"),
+        opened = local_date(
+            "(SELECT MAX(zeitpunkt) FROM song_events WHERE song_id = s.id AND art = 'geoeffnet')"
+        ),
+        practiced = local_date("(SELECT MAX(started_at) FROM practice_log WHERE song_id = s.id)"),
+    );
+    let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+
+    crate::query::push_clauses(&crate::query::parse(search), &mut sql, &mut param_values);
+    // A deleted smart list no longer filters.
+    if let Some(regel) = extras.smart_list.and_then(|id| smart_list_rule(conn, id)) {
+        crate::query::push_clauses(&crate::query::parse(&regel), &mut sql, &mut param_values);
+    }
+
+    let mut group_clauses = Vec::new();
+    for (ids, mode) in &tag_filter.groups {
+        if ids.is_empty() {
+            continue;
+        }
+        let ph: Vec<String> = ids
+            .iter()
+            .enumerate()
+            .map(|(i, _)| format!("?{}", param_values.len() + i + 1))
+            .collect();
+        let ph = ph.join(",");
+        group_clauses.push(match mode {
+            MatchMode::Any => {
+                format!("s.id IN (SELECT song_id FROM song_tags WHERE tag_id IN ({ph}))")
+            }
+            MatchMode::All => format!(
+                "s.id IN (
+                    SELECT song_id FROM song_tags WHERE tag_id IN ({ph})
+                    GROUP BY song_id
+                    HAVING COUNT(DISTINCT tag_id) = {}
+                )",
+                ids.len()
+            ),
+        });
+        for &id in ids {
+            param_values.push(Box::new(id));
+        }
+    }
+    if !group_clauses.is_empty() {
+        let joiner = match tag_filter.mode {
+            MatchMode::Any => " OR ",
+            MatchMode::All => " AND ",
+        };
+        sql.push_str(&format!(" AND ({})", group_clauses.join(joiner)));
+    }
+
+    if extras.has_audio {
+        sql.push_str(" AND s.has_audio = 1");
+    }
+
+    if extras.favorites {
+        sql.push_str(" AND s.favorit = 1");
+    }
+
+    if let Some((min, max)) = extras.bpm {
+        sql.push_str(&format!(" AND s.bpm BETWEEN {min} AND {max}"));
+    }
+
+    if let Some(max) = extras.kapo_max {
+        sql.push_str(&format!(" AND COALESCE(s.kapo, 0) <= {max}"));
+    }
+
+    if let Some((min, max)) = extras.schwierigkeit {
+        sql.push_str(&format!(
+            " AND EXISTS (SELECT 1 FROM song_tags st JOIN tags t ON t.id = st.tag_id
+                          WHERE st.song_id = s.id AND t.kategorie = 'schwierigkeit'
+                            AND t.rang BETWEEN {min} AND {max})"
+        ));
+    }
+
+    if extras.untagged {
+        sql.push_str(
+            " AND s.id NOT IN (SELECT DISTINCT song_id FROM song_tags WHERE auto_generated = 0)",
+        );
+    }
+
+    if let Some(id) = only {
+        sql.push_str(&format!(" AND s.id = {id}"));
+    }
+
+    let order = match sort {
+        SortMode::Artist => "ORDER BY COALESCE(s.artist, 'zzz'), s.titel",
+        SortMode::Recent => "ORDER BY s.created_at DESC",
+        SortMode::Untagged => {
+            "ORDER BY (SELECT COUNT(*) FROM song_tags WHERE song_id = s.id) ASC, s.titel"
+        }
+        SortMode::Rating => "ORDER BY s.favorit DESC, COALESCE(s.bewertung, 0) DESC, s.titel",
+        SortMode::LastOpened => {
+            "ORDER BY (SELECT MAX(zeitpunkt) FROM song_events
+                       WHERE song_id = s.id AND art = 'geoeffnet') IS NULL,
+                      (SELECT MAX(zeitpunkt) FROM song_events
+                       WHERE song_id = s.id AND art = 'geoeffnet') DESC, s.titel"
+        }
+        SortMode::MostUsed => {
+            "ORDER BY (SELECT COUNT(*) FROM song_events WHERE song_id = s.id) DESC, s.titel"
+        }
+        SortMode::Difficulty => {
+            "ORDER BY COALESCE((SELECT MIN(t.rang) FROM song_tags st JOIN tags t ON t.id = st.tag_id
+                                WHERE st.song_id = s.id AND t.kategorie = 'schwierigkeit'),
+                               1e9), s.titel"
+        }
+        SortMode::Title => "ORDER BY s.titel",
+    };
+    sql.push_str(&format!(" {order}"));
+
+    let params_refs: Vec<&dyn rusqlite::types::ToSql> =
+        param_values.iter().map(|p| p.as_ref()).collect();
+
+    let mut songs: Vec<Song> = query_rows(conn, &sql, params_refs.as_slice(), |row| {
+        Ok(Song {
+            id: row.get(0)?,
+            titel: row.get(1)?,
+            artist: row.get(2)?,
+            dateipfad: row.get(3)?,
+            dateiname: row.get(4)?,
+            has_audio: row.get::<_, i64>(5)? != 0,
+            audio_pfad: row.get(6)?,
+            created_at: row.get::<_, Option<String>>(7)?.unwrap_or_default(),
+            kann_ich: row.get(8)?,
+            notizen: row.get(9)?,
+            bewertung: row.get(10)?,
+            favorit: row.get::<_, i64>(11)? != 0,
+            tonart: row.get(12)?,
+            bpm: row.get(13)?,
+            kapo: row.get(14)?,
+            dateityp: row.get(15)?,
+            privat: row.get::<_, i64>(16)? != 0,
+            zuletzt_geoeffnet: row.get(17)?,
+            nutzungen: row.get(18)?,
+            zuletzt_geuebt: row.get(19)?,
+            oeffnen_mit: row.get(20)?,
+            jahr: row.get(21)?,
+            tags: Vec::new(),
+            links: Vec::new(),
+            dateien: Vec::new(),
+            werk_id: row.get(22)?,
+            seiten: row.get(23)?,
+        })
+    });
+
+    if only.is_some() {
+        for song in &mut songs {
+            song.tags = get_song_tags(conn, song.id);
+            song.links = get_song_links(conn, song.id);
+            song.dateien = companions(conn, song.id);
+        }
+    } else {
+        // One query for all tags; one per song takes seconds on large libraries.
+        let mut tags = all_song_tags(conn);
+        let mut links = all_song_links(conn);
+        let mut dateien = all_companions(conn);
+        for song in &mut songs {
+            song.tags = tags.remove(&song.id).unwrap_or_default();
+            song.links = links.remove(&song.id).unwrap_or_default();
+            song.dateien = dateien.remove(&song.id).unwrap_or_default();
+        }
+    }
+
+    songs
+}
+
+/// Tags of every song, keyed by song id, in the order of [`get_song_tags`].
+pub fn all_song_tags(conn: &Connection) -> HashMap<i64, Vec<TagInfo>> {
+    let rows = query_rows(
+        conn,
+        "SELECT st.song_id, t.id, t.kategorie, t.wert, st.auto_generated
+         FROM tags t
+         JOIN song_tags st ON t.id = st.tag_id
+         ORDER BY t.kategorie, t.wert",
+        [],
+        |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                TagInfo {
+                    id: row.get(1)?,
+                    kategorie: row.get(2)?,
+                    wert: row.get(3)?,
+                    auto_generated: row.get::<_, i64>(4)? != 0,
+                },
+            ))
+        },
+    );
+    let mut tags: HashMap<i64, Vec<TagInfo>> = HashMap::new();
+    for (song_id, tag) in rows {
+        tags.entry(song_id).or_default().push(tag);
+    }
+    tags
+}
+
+pub fn update_song(conn: &Connection, id: i64, titel: &str, artist: &str) {
+    conn.execute(
+        &format!("UPDATE songs SET titel = ?1, updated_at = {NOW} WHERE id = ?2"),
+        params![titel, id],
+    )
+    .log_err();
+
+    let artist_val: Option<&str> = if artist.is_empty() { None } else { Some(artist) };
+    conn.execute(
+        &format!("UPDATE songs SET artist = ?1, updated_at = {NOW} WHERE id = ?2"),
+        params![artist_val, id],
+    )
+    .log_err();
+    share_from(conn, id);
+}
+
+pub fn update_song_notes(conn: &Connection, id: i64, notizen: &str) {
+    let notizen_val: Option<&str> = if notizen.trim().is_empty() { None } else { Some(notizen) };
+    conn.execute(
+        &format!("UPDATE songs SET notizen = ?1, updated_at = {NOW} WHERE id = ?2"),
+        params![notizen_val, id],
+    )
+    .log_err();
+}
+
+pub fn update_song_music(
+    conn: &Connection,
+    id: i64,
+    tonart: Option<&str>,
+    bpm: Option<i64>,
+    kapo: Option<i64>,
+) {
+    conn.execute(
+        &format!(
+            "UPDATE songs SET tonart = ?1, bpm = ?2, kapo = ?3, updated_at = {NOW}
+             WHERE id = ?4"
+        ),
+        params![tonart, bpm, kapo, id],
+    )
+    .log_err();
+    share_from(conn, id);
+}
+
+pub fn set_song_year(conn: &Connection, id: i64, jahr: Option<i64>) {
+    conn.execute(
+        &format!("UPDATE songs SET jahr = ?1, updated_at = {NOW} WHERE id = ?2"),
+        params![jahr, id],
+    )
+    .log_err();
+}
+
+pub fn set_rating(conn: &Connection, id: i64, bewertung: i64) {
+    let value: Option<i64> = (1..=5).contains(&bewertung).then_some(bewertung);
+    conn.execute(
+        &format!("UPDATE songs SET bewertung = ?1, updated_at = {NOW} WHERE id = ?2"),
+        params![value, id],
+    )
+    .log_err();
+}
+
+pub fn set_private(conn: &Connection, id: i64, privat: bool) {
+    conn.execute(
+        &format!("UPDATE songs SET privat = ?1, updated_at = {NOW} WHERE id = ?2"),
+        params![privat, id],
+    )
+    .log_err();
+}
+
+pub fn song_open_with(conn: &Connection, id: i64) -> Option<String> {
+    conn.query_row(
+        "SELECT oeffnen_mit FROM songs WHERE id = ?1",
+        params![id],
+        |row| row.get(0),
+    )
+    .ok()
+    .flatten()
+}
+
+/// Empty means "by extension" and is stored as NULL.
+pub fn set_song_open_with(conn: &Connection, id: i64, app: &str) {
+    let app = app.trim();
+    conn.execute(
+        &format!("UPDATE songs SET oeffnen_mit = ?1, updated_at = {NOW} WHERE id = ?2"),
+        params![(!app.is_empty()).then_some(app), id],
+    )
+    .log_err();
+}
+
+/// Link from a song to something outside the library: a lesson video on
+/// YouTube, the recording on Spotify, a tab page on Ultimate Guitar, or a
+/// lesson plan in Obsidian (`obsidian://…`), Notion or Google Drive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SongLink {
+    pub id: i64,
+    pub label: String,
+    pub url: String,
+}
+
+pub fn get_song_links(conn: &Connection, song_id: i64) -> Vec<SongLink> {
+    query_rows(
+        conn,
+        "SELECT id, label, url FROM song_links WHERE song_id = ?1 ORDER BY id",
+        params![song_id],
+        |row| {
+            Ok(SongLink {
+                id: row.get(0)?,
+                label: row.get(1)?,
+                url: row.get(2)?,
+            })
+        },
+    )
+}
+
+/// Links of every song, keyed by song id, in the order of [`get_song_links`].
+pub fn all_song_links(conn: &Connection) -> HashMap<i64, Vec<SongLink>> {
+    let rows = query_rows(
+        conn,
+        "SELECT song_id, id, label, url FROM song_links ORDER BY id",
+        [],
+        |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                SongLink {
+                    id: row.get(1)?,
+                    label: row.get(2)?,
+                    url: row.get(3)?,
+                },
+            ))
+        },
+    );
+    let mut links: HashMap<i64, Vec<SongLink>> = HashMap::new();
+    for (song_id, link) in rows {
+        links.entry(song_id).or_default().push(link);
+    }
+    links
+}
+
+pub fn add_song_link(conn: &Connection, song_id: i64, label: &str, url: &str) {
+    conn.execute(
+        "INSERT INTO song_links (song_id, label, url) VALUES (?1, ?2, ?3)",
+        params![song_id, label, url],
+    )
+    .log_err();
+}
+
+pub fn remove_song_link(conn: &Connection, id: i64) {
+    conn.execute("DELETE FROM song_links WHERE id = ?1", params![id])
+        .log_err();
+}
+
+/// A saved search rule in the search box syntax, shown as a list of its own
+/// that follows the library as it changes.
+#[derive(Debug, Clone)]
+pub struct SmartList {
+    pub id: i64,
+    pub name: String,
+    pub regel: String,
+}
+
+pub fn smart_lists(conn: &Connection) -> Vec<SmartList> {
+    query_rows(
+        conn,
+        "SELECT id, name, regel FROM smart_listen ORDER BY name COLLATE NOCASE",
+        [],
+        |row| {
+            Ok(SmartList {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                regel: row.get(2)?,
+            })
+        },
+    )
+}
+
+fn smart_list_rule(conn: &Connection, id: i64) -> Option<String> {
+    conn.query_row(
+        "SELECT regel FROM smart_listen WHERE id = ?1",
+        params![id],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+/// Insert a new smart list (`id` is `None`) or update one; returns its id.
+pub fn save_smart_list(conn: &Connection, id: Option<i64>, name: &str, regel: &str) -> Option<i64> {
+    match id {
+        Some(id) => {
+            conn.execute(
+                "UPDATE smart_listen SET name = ?2, regel = ?3 WHERE id = ?1",
+                params![id, name, regel],
+            )
+            .ok()?;
+            Some(id)
+        }
+        None => {
+            conn.execute(
+                "INSERT INTO smart_listen (name, regel) VALUES (?1, ?2)",
+                params![name, regel],
+            )
+            .ok()?;
+            Some(conn.last_insert_rowid())
+        }
+    }
+}
+
+pub fn delete_smart_list(conn: &Connection, id: i64) {
+    conn.execute("DELETE FROM smart_listen WHERE id = ?1", params![id])
+        .log_err();
+}
+
+/// A value the UI stored for the next launch.
+pub fn get_ui_state(conn: &Connection, schluessel: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT wert FROM ui_state WHERE schluessel = ?1",
+        params![schluessel],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+pub fn set_ui_state(conn: &Connection, schluessel: &str, wert: &str) {
+    conn.execute(
+        "INSERT INTO ui_state (schluessel, wert) VALUES (?1, ?2)
+         ON CONFLICT(schluessel) DO UPDATE SET wert = excluded.wert",
+        params![schluessel, wert],
+    )
+    .log_err();
+}
+
+/// Hide a file that is not a song from the list, stats and all windows.
+pub fn set_ignored(conn: &Connection, id: i64, ignoriert: bool) {
+    conn.execute(
+        &format!("UPDATE songs SET ignoriert = ?1, updated_at = {NOW} WHERE id = ?2"),
+        params![ignoriert, id],
+    )
+    .log_err();
+}
+
+/// Ignored files as (id, titel, dateipfad), by path.
+pub fn ignored_songs(conn: &Connection) -> Vec<(i64, String, String)> {
+    query_rows(
+        conn,
+        "SELECT id, titel, dateipfad FROM songs
+         WHERE ignoriert = 1 AND deleted_at IS NULL
+         ORDER BY dateipfad",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )
+}
+
+/// A song whose file has disappeared, kept with its tags until purged.
+pub struct TrashedSong {
+    pub id: i64,
+    pub titel: String,
+    pub artist: Option<String>,
+    pub dateipfad: String,
+    pub deleted_at: String,
+    pub tag_count: i64,
+}
+
+pub fn trashed_songs(conn: &Connection) -> Vec<TrashedSong> {
+    let sql = format!(
+        "SELECT s.id, s.titel, s.artist, s.dateipfad, {},
+                (SELECT COUNT(*) FROM song_tags WHERE song_id = s.id)
+         FROM songs s
+         WHERE s.deleted_at IS NOT NULL
+         ORDER BY s.deleted_at DESC, s.titel",
+        local_time("s.deleted_at")
+    );
+    query_rows(conn, &sql, [], |row| {
+        Ok(TrashedSong {
+            id: row.get(0)?,
+            titel: row.get(1)?,
+            artist: row.get(2)?,
+            dateipfad: row.get(3)?,
+            deleted_at: row.get(4)?,
+            tag_count: row.get(5)?,
+        })
+    })
+}
+
+/// Back into the list. If the file is still missing, the next scan moves it
+/// to the Papierkorb again.
+pub fn restore_song(conn: &Connection, id: i64) {
+    conn.execute("UPDATE songs SET deleted_at = NULL WHERE id = ?1", params![id])
+        .log_err();
+}
+
+/// Delete a trashed song for good, with its tags, practice log and events.
+/// `None` empties the whole Papierkorb.
+pub fn purge_songs(conn: &Connection, id: Option<i64>) {
+    match id {
+        Some(id) => conn.execute(
+            "DELETE FROM songs WHERE id = ?1 AND deleted_at IS NOT NULL",
+            params![id],
+        ),
+        None => conn.execute("DELETE FROM songs WHERE deleted_at IS NOT NULL", []),
+    }
+    .log_err();
+    conn.execute(
+        "DELETE FROM tags WHERE angeheftet = 0 AND id NOT IN (SELECT DISTINCT tag_id FROM song_tags)",
+        [],
+    )
+    .log_err();
+}
+
+/// Something done with a song's files, recorded in `song_events` for the
+/// "zuletzt geöffnet" and usage sort modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SongEvent {
+    Opened,
+    Played,
+}
+
+impl SongEvent {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SongEvent::Opened => "geoeffnet",
+            SongEvent::Played => "abgespielt",
+        }
+    }
+}
+
+pub fn log_song_event(conn: &Connection, song_id: i64, event: SongEvent) {
+    conn.execute(
+        &format!("INSERT INTO song_events (song_id, art, zeitpunkt) VALUES (?1, ?2, {NOW})"),
+        params![song_id, event.as_str()],
+    )
+    .log_err();
+}
+
+pub fn song_id_by_path(conn: &Connection, dateipfad: &str) -> Option<i64> {
+    conn.query_row(
+        "SELECT id FROM songs WHERE dateipfad = ?1",
+        params![dateipfad],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+pub fn song_gain_db(conn: &Connection, id: i64) -> f32 {
+    conn.query_row(
+        "SELECT pegel_db FROM songs WHERE id = ?1",
+        params![id],
+        |row| row.get::<_, f64>(0),
+    )
+    .map_or(0.0, |db| db as f32)
+}
+
+pub fn set_song_gain_db(conn: &Connection, id: i64, pegel_db: f32) {
+    conn.execute(
+        "UPDATE songs SET pegel_db = ?1 WHERE id = ?2",
+        params![f64::from(pegel_db), id],
+    )
+    .log_err();
+}
+
+pub fn song_semitones(conn: &Connection, id: i64) -> i32 {
+    conn.query_row(
+        "SELECT halbtoene FROM songs WHERE id = ?1",
+        params![id],
+        |row| row.get(0),
+    )
+    .unwrap_or(0)
+}
+
+pub fn set_song_semitones(conn: &Connection, id: i64, halbtoene: i32) {
+    conn.execute(
+        "UPDATE songs SET halbtoene = ?1 WHERE id = ?2",
+        params![halbtoene, id],
+    )
+    .log_err();
+}
+
+/// Saved A–B loop (start, end) in seconds.
+pub fn song_loop(conn: &Connection, id: i64) -> Option<(f32, f32)> {
+    conn.query_row(
+        "SELECT schleife_von, schleife_bis FROM songs WHERE id = ?1",
+        params![id],
+        |row| Ok((row.get::<_, Option<f64>>(0)?, row.get::<_, Option<f64>>(1)?)),
+    )
+    .ok()
+    .and_then(|(von, bis)| Some((von? as f32, bis? as f32)))
+}
+
+pub fn set_song_loop(conn: &Connection, id: i64, schleife: Option<(f32, f32)>) {
+    let (von, bis) = schleife
+        .map(|(von, bis)| (f64::from(von), f64::from(bis)))
+        .unzip();
+    conn.execute(
+        "UPDATE songs SET schleife_von = ?1, schleife_bis = ?2 WHERE id = ?3",
+        params![von, bis, id],
+    )
+    .log_err();
+}
+
+pub fn set_favorite(conn: &Connection, id: i64, favorit: bool) {
+    conn.execute(
+        &format!("UPDATE songs SET favorit = ?1, updated_at = {NOW} WHERE id = ?2"),
+        params![favorit, id],
+    )
+    .log_err();
+}
+
+pub fn add_tag_to_song(conn: &Connection, song_id: i64, kategorie: &str, wert: &str) {
+    let Ok(tag_id) = get_or_create_tag(conn, kategorie, wert) else {
+        return;
+    };
+    conn.execute(
+        "INSERT OR IGNORE INTO song_tags (song_id, tag_id, auto_generated) VALUES (?1, ?2, 0)",
+        params![song_id, tag_id],
+    )
+    .log_err();
+}
+
+/// Add a tag by hand from the UI and remember it for the quick picks.
+pub fn apply_tag(conn: &Connection, song_id: i64, kategorie: &str, wert: &str) {
+    add_tag_to_song(conn, song_id, kategorie, wert);
+    conn.execute(
+        &format!(
+            "INSERT INTO tag_nutzung (tag_id, zeitpunkt)
+             SELECT id, {NOW} FROM tags WHERE kategorie = ?1 AND wert = ?2"
+        ),
+        params![kategorie, wert],
+    )
+    .log_err();
+}
+
+/// Hand-applied tags for the tag modal's quick picks: the most recently used
+/// ones and the most used ones overall, each at most `limit`.
+pub fn quick_pick_tags(conn: &Connection, limit: usize) -> (Vec<TagInfo>, Vec<TagInfo>) {
+    let query = |order: &str| {
+        let sql = format!(
+            "SELECT t.id, t.kategorie, t.wert FROM tag_nutzung n
+             JOIN tags t ON t.id = n.tag_id
+             GROUP BY t.id
+             ORDER BY {order}, t.wert
+             LIMIT {limit}"
+        );
+        query_rows(conn, &sql, [], |row| {
+            Ok(TagInfo {
+                id: row.get(0)?,
+                kategorie: row.get(1)?,
+                wert: row.get(2)?,
+                auto_generated: false,
+            })
+        })
+    };
+    (query("MAX(n.zeitpunkt) DESC"), query("COUNT(*) DESC"))
+}
+
+pub fn remove_tag_from_song(conn: &Connection, song_id: i64, tag_id: i64) {
+    conn.execute(
+        "DELETE FROM song_tags WHERE song_id = ?1 AND tag_id = ?2",
+        params![song_id, tag_id],
+    )
+    .log_err();
+
+    conn.execute(
+        "DELETE FROM tags WHERE id = ?1 AND angeheftet = 0
+         AND id NOT IN (SELECT DISTINCT tag_id FROM song_tags)",
+        params![tag_id],
+    )
+    .log_err();
+}
+
+pub fn find_tag(conn: &Connection, kategorie: &str, wert: &str) -> Option<i64> {
+    conn.query_row(
+        "SELECT id FROM tags WHERE kategorie = ?1 AND wert = ?2",
+        params![kategorie, wert],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+/// Move all song assignments from `from_id` to `into_id` and delete `from_id`.
+pub fn merge_tags(conn: &Connection, from_id: i64, into_id: i64) {
+    if from_id == into_id {
+        return;
+    }
+    conn.execute(
+        "INSERT OR IGNORE INTO song_tags (song_id, tag_id, auto_generated)
+         SELECT song_id, ?2, auto_generated FROM song_tags WHERE tag_id = ?1",
+        params![from_id, into_id],
+    )
+    .log_err();
+    delete_tag(conn, from_id);
+}
+
+/// Rename a tag value for all songs. If the new value already exists in the
+/// category, the two tags are merged.
+pub fn rename_tag(conn: &Connection, tag_id: i64, wert: &str) {
+    move_tag(conn, tag_id, None, wert);
+}
+
+/// Move a tag to another category, merging with an existing tag of the same value there.
+pub fn set_tag_category(conn: &Connection, tag_id: i64, kategorie: &str) {
+    let wert: Option<String> = conn
+        .query_row("SELECT wert FROM tags WHERE id = ?1", params![tag_id], |row| {
+            row.get(0)
+        })
+        .ok();
+    if let Some(wert) = wert {
+        move_tag(conn, tag_id, Some(kategorie), &wert);
+    }
+}
+
+fn move_tag(conn: &Connection, tag_id: i64, kategorie: Option<&str>, wert: &str) {
+    let kategorie: String = match kategorie {
+        Some(k) => k.to_string(),
+        None => match conn.query_row(
+            "SELECT kategorie FROM tags WHERE id = ?1",
+            params![tag_id],
+            |row| row.get(0),
+        ) {
+            Ok(k) => k,
+            Err(_) => return,
+        },
+    };
+
+    match find_tag(conn, &kategorie, wert) {
+        Some(existing) if existing != tag_id => merge_tags(conn, tag_id, existing),
+        Some(_) => {}
+        None => {
+            // A value moved out of `schwierigkeit` leaves the scale, one
+            // moved in is placed on it.
+            let moved_in = conn
+                .query_row(
+                    "SELECT kategorie != ?1 FROM tags WHERE id = ?2",
+                    params![kategorie, tag_id],
+                    |row| row.get(0),
+                )
+                .unwrap_or(false);
+            conn.execute(
+                "UPDATE tags SET rang = CASE WHEN kategorie = ?1 THEN rang END,
+                                 kategorie = ?1, wert = ?2
+                 WHERE id = ?3",
+                params![kategorie, wert, tag_id],
+            )
+            .log_err();
+            if moved_in && kategorie == "schwierigkeit" {
+                place_difficulty(conn, tag_id, wert);
+            }
+        }
+    }
+}
+
+/// The usual difficulty values, easiest first; where a new one of these goes
+/// on the scale. The "Stufen" window reorders it.
+const DEFAULT_DIFFICULTY: &[&str] = &[
+    "Anf\u{00E4}nger",
+    "Leicht",
+    "Mittel",
+    "Fortgeschritten",
+    "Schwer",
+    "Profi",
+];
+
+/// Put a new `schwierigkeit` value on the scale: one of `DEFAULT_DIFFICULTY`
+/// right before the first harder one already there, anything else last.
+fn place_difficulty(conn: &Connection, id: i64, wert: &str) {
+    let before = DEFAULT_DIFFICULTY
+        .iter()
+        .skip_while(|d| !d.eq_ignore_ascii_case(wert))
+        .skip(1)
+        .filter_map(|harder| {
+            conn.query_row(
+                "SELECT rang FROM tags WHERE kategorie = 'schwierigkeit' AND wert = ?1",
+                params![harder],
+                |row| row.get::<_, Option<i64>>(0),
+            )
+            .ok()
+            .flatten()
+        })
+        .min();
+    match before {
+        Some(rang) => {
+            conn.execute(
+                "UPDATE tags SET rang = rang + 1 WHERE kategorie = 'schwierigkeit' AND rang >= ?1",
+                params![rang],
+            )
+            .log_err();
+            conn.execute("UPDATE tags SET rang = ?1 WHERE id = ?2", params![rang, id])
+                .log_err();
+        }
+        None => {
+            conn.execute(
+                "UPDATE tags SET rang = (SELECT COALESCE(MAX(rang), 0) + 1 FROM tags
+                                         WHERE kategorie = 'schwierigkeit')
+                 WHERE id = ?1",
+                params![id],
+            )
+            .log_err();
+        }
+    }
+}
+
+/// A `schwierigkeit` value and its place on the difficulty scale.
+#[derive(Debug, Clone)]
+pub struct DifficultyLevel {
+    pub id: i64,
+    pub wert: String,
+    /// 1 = easiest; `None` until the value is placed on the scale.
+    pub rang: Option<i64>,
+}
+
+/// All `schwierigkeit` values, easiest first, unranked ones last.
+pub fn difficulty_levels(conn: &Connection) -> Vec<DifficultyLevel> {
+    query_rows(
+        conn,
+        "SELECT id, wert, rang FROM tags WHERE kategorie = 'schwierigkeit'
+         ORDER BY rang IS NULL, rang, wert COLLATE NOCASE",
+        [],
+        |row| {
+            Ok(DifficultyLevel {
+                id: row.get(0)?,
+                wert: row.get(1)?,
+                rang: row.get(2)?,
+            })
+        },
+    )
+}
+
+/// Rank the given tags 1, 2, … in order; other `schwierigkeit` values
+/// leave the scale.
+pub fn set_difficulty_order(conn: &Connection, ids: &[i64]) {
+    let Ok(tx) = conn.unchecked_transaction() else {
+        return;
+    };
+    tx.execute(
+        "UPDATE tags SET rang = NULL WHERE kategorie = 'schwierigkeit'",
+        [],
+    )
+    .log_err();
+    for (i, id) in ids.iter().enumerate() {
+        tx.execute(
+            "UPDATE tags SET rang = ?1 WHERE id = ?2 AND kategorie = 'schwierigkeit'",
+            params![i as i64 + 1, id],
+        )
+        .log_err();
+    }
+    if let Err(e) = tx.commit() {
+        log::warn!("Failed to store difficulty order: {e}");
+    }
+}
+
+/// Remove a tag from all songs and delete it.
+pub fn delete_tag(conn: &Connection, tag_id: i64) {
+    conn.execute("DELETE FROM song_tags WHERE tag_id = ?1", params![tag_id])
+        .log_err();
+    conn.execute("DELETE FROM tags WHERE id = ?1", params![tag_id])
+        .log_err();
+}
+
+pub fn get_all_tags(conn: &Connection) -> Vec<TagGroup> {
+    puffin::profile_scope!("DB: Tags");
+    let rows: Vec<(i64, String, String, i64)> = query_rows(
+        conn,
+        "SELECT t.id, t.kategorie, t.wert, COUNT(s.id) as cnt
+         FROM tags t
+         LEFT JOIN song_tags st ON t.id = st.tag_id
+         LEFT JOIN songs s ON s.id = st.song_id AND s.deleted_at IS NULL AND s.ignoriert = 0
+         GROUP BY t.id
+         ORDER BY t.kategorie, t.rang IS NULL, t.rang, cnt DESC, t.wert",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+    );
+
+    let mut groups: HashMap<String, Vec<TagEntry>> = HashMap::new();
+    for (id, kategorie, wert, count) in rows {
+        groups
+            .entry(kategorie)
+            .or_default()
+            .push(TagEntry { id, wert, count });
+    }
+
+    let category_order = [
+        "instrument",
+        "schwierigkeit",
+        "stil",
+        "technik",
+        "artist",
+        "stimmung",
+        "kapo",
+        "tonart",
+    ];
+
+    let mut result: Vec<TagGroup> = Vec::new();
+    for cat in &category_order {
+        if let Some(tags) = groups.remove(*cat) {
+            result.push(TagGroup {
+                kategorie: cat.to_string(),
+                tags,
+            });
+        }
+    }
+    for (kategorie, tags) in groups {
+        result.push(TagGroup { kategorie, tags });
+    }
+
+    result
+}
+
+pub fn get_stats(conn: &Connection) -> Stats {
+    puffin::profile_scope!("DB: Statistik");
+    let total_songs: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM songs WHERE deleted_at IS NULL AND ignoriert = 0",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+
+    let untagged_songs: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM songs
+             WHERE deleted_at IS NULL AND ignoriert = 0
+               AND id NOT IN (SELECT DISTINCT song_id FROM song_tags)",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+
+    let songs_with_audio: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM songs
+             WHERE has_audio = 1 AND deleted_at IS NULL AND ignoriert = 0",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+
+    // Week starts on Monday: 'weekday 0' jumps to the coming Sunday (or stays on it).
+    let practice_seconds_week: i64 = conn
+        .query_row(
+            &format!(
+                "SELECT COALESCE(SUM(seconds), 0) FROM practice_log WHERE started_at >= {}",
+                from_local("date('now', 'localtime', 'weekday 0', '-6 days')")
+            ),
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+
+    Stats {
+        total_songs,
+        songs_with_audio,
+        untagged_songs,
+        practice_seconds_week,
+        review_due: crate::repertoire::review_queue(conn).len() as i64,
+    }
+}
+
+/// Songs added per local calendar month (`2026-10`), oldest first. Months
+/// without new songs between the first and the last are included as 0.
+pub fn songs_added_per_month(conn: &Connection) -> Vec<(String, i64)> {
+    let rows: Vec<(String, i64)> = query_rows(
+        conn,
+        "SELECT strftime('%Y-%m', created_at, 'localtime') AS monat, COUNT(*)
+         FROM songs
+         WHERE deleted_at IS NULL AND ignoriert = 0 AND created_at IS NOT NULL
+         GROUP BY monat
+         ORDER BY monat",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    );
+    let parse = |monat: &str| -> Option<(i32, u32)> {
+        let (jahr, monat) = monat.split_once('-')?;
+        Some((jahr.parse().ok()?, monat.parse().ok()?))
+    };
+    let (Some(first), Some(last)) = (
+        rows.first().and_then(|(m, _)| parse(m)),
+        rows.last().and_then(|(m, _)| parse(m)),
+    ) else {
+        return rows;
+    };
+    let mut months = Vec::new();
+    let (mut jahr, mut monat) = first;
+    while (jahr, monat) <= last {
+        let key = format!("{jahr:04}-{monat:02}");
+        let count = rows.iter().find(|(m, _)| *m == key).map_or(0, |(_, n)| *n);
+        months.push((key, count));
+        (jahr, monat) = if monat == 12 {
+            (jahr + 1, 1)
+        } else {
+            (jahr, monat + 1)
+        };
+    }
+    months
+}
+
+/// Per value of `kategorie`: (wert, songs, songs with audio), most songs first.
+pub fn audio_coverage(conn: &Connection, kategorie: &str) -> Vec<(String, i64, i64)> {
+    query_rows(
+        conn,
+        "SELECT t.wert, COUNT(*) AS cnt, SUM(s.has_audio = 1)
+         FROM tags t
+         JOIN song_tags st ON st.tag_id = t.id
+         JOIN songs s ON s.id = st.song_id AND s.deleted_at IS NULL AND s.ignoriert = 0
+         WHERE t.kategorie = ?1
+         GROUP BY t.id
+         ORDER BY cnt DESC, t.wert",
+        params![kategorie],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )
+}
+
+/// The `limit` tags on the most songs across all categories, as
+/// (kategorie, wert, songs).
+pub fn most_used_tags(conn: &Connection, limit: usize) -> Vec<(String, String, i64)> {
+    query_rows(
+        conn,
+        "SELECT t.kategorie, t.wert, COUNT(*) AS cnt
+         FROM tags t
+         JOIN song_tags st ON st.tag_id = t.id
+         JOIN songs s ON s.id = st.song_id AND s.deleted_at IS NULL AND s.ignoriert = 0
+         GROUP BY t.id
+         ORDER BY cnt DESC, t.kategorie, t.wert
+         LIMIT ?1",
+        params![limit as i64],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )
+}
+
+/// Songs never opened from the app (no `geoeffnet` event), as
+/// (titel, artist, dateipfad), longest in the library first.
+pub fn never_opened_songs(conn: &Connection) -> Vec<(String, Option<String>, String)> {
+    query_rows(
+        conn,
+        "SELECT titel, artist, dateipfad FROM songs
+         WHERE deleted_at IS NULL AND ignoriert = 0
+           AND id NOT IN (
+               SELECT song_id FROM song_events WHERE art = 'geoeffnet' AND song_id IS NOT NULL
+           )
+         ORDER BY created_at, titel",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )
+}
+
+/// Record a finished practice session that started `seconds` ago.
+pub fn log_practice(conn: &Connection, song_id: i64, seconds: i64) {
+    conn.execute(
+        &format!(
+            "INSERT INTO practice_log (song_id, started_at, seconds)
+             VALUES (?1, strftime('{STORED_FORMAT}', 'now', ?2), ?3)"
+        ),
+        params![song_id, format!("-{seconds} seconds"), seconds],
+    )
+    .log_err();
+}
+
+/// Total practice time of one song across all sessions.
+pub fn get_practice_seconds(conn: &Connection, song_id: i64) -> i64 {
+    conn.query_row(
+        "SELECT COALESCE(SUM(seconds), 0) FROM practice_log WHERE song_id = ?1",
+        params![song_id],
+        |row| row.get(0),
+    )
+    .unwrap_or(0)
+}
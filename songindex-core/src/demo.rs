@@ -0,0 +1,476 @@
+use crate::db::{
+    add_tag_to_song, log_practice, set_favorite, set_rating, song_id_by_path, update_song_music,
+    update_song_notes,
+};
+use crate::repertoire::set_repertoire;
+use rusqlite::Connection;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Marks a folder as generated here, so it may be wiped and rewritten.
+const MARKER: &str = ".songindex-demo";
+const NOTEN: &str = "00 gitarre/0. Songs/1. Noten";
+const AUDIOS: &str = "00 gitarre/0. Songs/2. Audios";
+
+/// Charts of the demo library: path relative to the music folder and the
+/// chord lines printed on the page. Folder names hit the default auto-tag
+/// rules (Anfaenger, Kinderlieder, Zupfen, Blues, E-Gitarre, Ukulele …);
+/// all songs are traditional or in the public domain.
+const CHARTS: &[(&str, &str, &[&str])] = &[
+    (
+        NOTEN,
+        "Anfaenger/Traditional - Amazing Grace.pdf",
+        &["G G7 C G", "G Em A D", "G G7 C G", "Em D G"],
+    ),
+    (
+        NOTEN,
+        "Anfaenger/Traditional - Oh Susanna.pdf",
+        &["C G C", "C G C", "F C G", "C G C"],
+    ),
+    (
+        NOTEN,
+        "Anfaenger/Traditional - When the Saints Go Marching In.pdf",
+        &["G", "G D", "G G7 C Cm", "G D G"],
+    ),
+    (
+        NOTEN,
+        "Kinderlieder/Traditional - Alle meine Entchen.pdf",
+        &["D A D", "D A D"],
+    ),
+    (
+        NOTEN,
+        "Kinderlieder/Traditional - H\u{00E4}nschen klein.pdf",
+        &["G D G", "D G", "G D G"],
+    ),
+    (
+        NOTEN,
+        "Kinderlieder/Traditional - Fr\u{00E8}re Jacques.pdf",
+        &["C G C", "C G C"],
+    ),
+    (
+        NOTEN,
+        "Zupfen/Traditional - Scarborough Fair.pdf",
+        &["Am G Am", "C Am D Am", "C G F", "Am G Am"],
+    ),
+    (
+        NOTEN,
+        "Zupfen/Traditional - Greensleeves.pdf",
+        &["Am C G Em", "Am E", "C G Em", "Am E Am"],
+    ),
+    (
+        NOTEN,
+        "Blues/Traditional - Trouble in Mind.pdf",
+        &["A A7 D Dm", "A E7 A E7"],
+    ),
+    (
+        NOTEN,
+        "Blues/Traditional - Careless Love.pdf",
+        &["C G7 C C7", "F Fm C G7 C"],
+    ),
+    (
+        NOTEN,
+        "Weihnachtssongs/Franz Xaver Gruber - Stille Nacht.pdf",
+        &["D A7 D", "G D", "A7 D"],
+    ),
+    (
+        NOTEN,
+        "Weihnachtssongs/James Lord Pierpont - Jingle Bells.pdf",
+        &["G C", "A7 D", "G C", "D G"],
+    ),
+    (
+        NOTEN,
+        "Klassisch/Francisco T\u{00E1}rrega - L\u{00E1}grima.pdf",
+        &["E B7 E", "Em B7 Em"],
+    ),
+    (
+        NOTEN,
+        "Traditional - House of the Rising Sun.pdf",
+        &["Am C D F", "Am C E E", "Am C D F", "Am E Am E"],
+    ),
+    (
+        NOTEN,
+        "Traditional - Danny Boy.pdf",
+        &["C C7 F C", "Am D7 G7", "C C7 F Fm", "C G7 C"],
+    ),
+    (
+        "E-Gitarre",
+        "Blues/Traditional - St. James Infirmary.pdf",
+        &["Am E7 Am", "Dm E7 Am"],
+    ),
+    (
+        "E-Gitarre",
+        "Solos/Traditional - Shenandoah.pdf",
+        &["D G D", "Bm G D", "A D"],
+    ),
+    (
+        "Mundart",
+        "Traditional - Vo Luz\u{00E4}rn g\u{00E4}g W\u{00E4}ggis zue.pdf",
+        &["C G7 C", "C G7 C"],
+    ),
+];
+
+/// ChordPro charts, with key and capo in the file.
+const CHORDPRO: &[(&str, &str)] = &[
+    (
+        "E-Gitarre/Traditional - Wayfaring Stranger.cho",
+        "{title: Wayfaring Stranger}\n{artist: Traditional}\n{key: Am}\n{capo: 2}\n\n\
+         {start_of_verse}\nI am a [Am]poor wayfaring stranger\n\
+         While [Dm]traveling through this world of [Am]woe\n{end_of_verse}\n",
+    ),
+    (
+        "Ukulele/Traditional - Kumbaya.cho",
+        "{title: Kumbaya}\n{artist: Traditional}\n{key: C}\n\n\
+         {start_of_chorus}\n[C]Kumbaya, my Lord, [F]kum[C]baya\n\
+         [C]Kumbaya, my Lord, [G]kumbaya\n{end_of_chorus}\n",
+    ),
+];
+
+/// Plain text sheets: the first line is the title.
+const TEXT: &[(&str, &str)] = &[(
+    "Ukulele/Kinderlieder/Kuckuck.txt",
+    "Kuckuck, Kuckuck, ruft's aus dem Wald\n\nC        G7       C\nKuckuck, Kuckuck, ruft's aus dem Wald\n",
+)];
+
+/// Backing tracks; the scanner pairs them with songs by title.
+const AUDIO: &[&str] = &["Amazing Grace.wav", "House of the Rising Sun.wav"];
+
+/// Song details the scanner cannot know, so every view has something to
+/// show: path, key, BPM, capo, stars, favorite, repertoire, minutes
+/// practiced, extra tag and notes.
+struct Seed {
+    pfad: &'static str,
+    tonart: Option<&'static str>,
+    bpm: Option<i64>,
+    kapo: Option<i64>,
+    bewertung: i64,
+    favorit: bool,
+    kann_ich: bool,
+    minuten: i64,
+    tag: Option<(&'static str, &'static str)>,
+    notizen: &'static str,
+}
+
+const SEEDS: &[Seed] = &[
+    Seed {
+        pfad: "00 gitarre/0. Songs/1. Noten/Anfaenger/Traditional - Amazing Grace.pdf",
+        tonart: Some("G"),
+        bpm: Some(80),
+        kapo: None,
+        bewertung: 5,
+        favorit: true,
+        kann_ich: true,
+        minuten: 45,
+        tag: Some(("technik", "Akkorde")),
+        notizen: "## Ablauf\n- Intro: 4 Takte G\n- **3/4-Takt**, Schlag auf 1 betonen",
+    },
+    Seed {
+        pfad: "00 gitarre/0. Songs/1. Noten/Traditional - House of the Rising Sun.pdf",
+        tonart: Some("Am"),
+        bpm: Some(116),
+        kapo: None,
+        bewertung: 4,
+        favorit: true,
+        kann_ich: false,
+        minuten: 30,
+        tag: Some(("technik", "Fingerpicking")),
+        notizen:
+            "Arpeggio: Bass, 3, 2, 1, 2, 3. Den F-Dur-Barr\u{00E9} zuerst als Fmaj7 \u{00FC}ben.",
+    },
+    Seed {
+        pfad: "00 gitarre/0. Songs/1. Noten/Zupfen/Traditional - Scarborough Fair.pdf",
+        tonart: Some("Am"),
+        bpm: Some(96),
+        kapo: Some(2),
+        bewertung: 3,
+        favorit: false,
+        kann_ich: false,
+        minuten: 20,
+        tag: None,
+        notizen: "",
+    },
+    Seed {
+        pfad: "00 gitarre/0. Songs/1. Noten/Blues/Traditional - Trouble in Mind.pdf",
+        tonart: Some("A"),
+        bpm: Some(72),
+        kapo: None,
+        bewertung: 4,
+        favorit: false,
+        kann_ich: true,
+        minuten: 15,
+        tag: Some(("technik", "Riff")),
+        notizen: "Shuffle-Feel, Turnaround in Takt 11\u{2013}12.",
+    },
+    Seed {
+        pfad: "00 gitarre/0. Songs/1. Noten/Kinderlieder/Traditional - Alle meine Entchen.pdf",
+        tonart: Some("D"),
+        bpm: Some(100),
+        kapo: None,
+        bewertung: 2,
+        favorit: false,
+        kann_ich: true,
+        minuten: 0,
+        tag: None,
+        notizen: "",
+    },
+    Seed {
+        pfad: "E-Gitarre/Solos/Traditional - Shenandoah.pdf",
+        tonart: Some("D"),
+        bpm: Some(68),
+        kapo: None,
+        bewertung: 0,
+        favorit: false,
+        kann_ich: false,
+        minuten: 10,
+        tag: Some(("schwierigkeit", "Fortgeschritten")),
+        notizen: "",
+    },
+];
+
+/// Where the window's demo mode puts the library; rewritten on every start.
+pub fn demo_dir() -> PathBuf {
+    std::env::temp_dir().join("songindex-demo")
+}
+
+/// The demo library's own database: a hidden file in the demo folder, which
+/// the scanner skips, so the real library stays untouched.
+pub fn database_path(dir: &Path) -> PathBuf {
+    dir.join(".songindex-demo.db")
+}
+
+/// Write the demo library into `dir` and return the number of files. A
+/// folder from an earlier run is replaced; any other non-empty folder is
+/// refused, so a mistyped path cannot wipe real files.
+pub fn create_library(dir: &Path) -> io::Result<usize> {
+    if dir.join(MARKER).exists() {
+        std::fs::remove_dir_all(dir)?;
+    } else if dir
+        .read_dir()
+        .is_ok_and(|mut entries| entries.next().is_some())
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} ist nicht leer", dir.display()),
+        ));
+    }
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(dir.join(MARKER), "")?;
+
+    let mut count = 0;
+    let mut write = |rel: &str, content: &[u8]| -> io::Result<()> {
+        let path = dir.join(rel);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, content)?;
+        count += 1;
+        Ok(())
+    };
+    for (folder, rel, chords) in CHARTS {
+        let name = rel.rsplit('/').next().unwrap_or(rel);
+        let stem = name.trim_end_matches(".pdf");
+        let (artist, titel) = stem.split_once(" - ").unwrap_or(("", stem));
+        write(
+            &format!("{folder}/{rel}"),
+            &chart_pdf(titel, artist, chords),
+        )?;
+    }
+    for (rel, text) in CHORDPRO.iter().chain(TEXT) {
+        write(rel, text.as_bytes())?;
+    }
+    for name in AUDIO {
+        write(&format!("{AUDIOS}/{name}"), &silent_wav())?;
+    }
+    Ok(count)
+}
+
+/// Fill in key, ratings, repertoire, practice time and notes for the
+/// scanned demo library (see [`SEEDS`]).
+pub fn seed_metadata(conn: &Connection, review_interval_days: u32) {
+    for seed in SEEDS {
+        let Some(id) = song_id_by_path(conn, seed.pfad) else {
+            log::warn!("Demo: {} nicht im Index", seed.pfad);
+            continue;
+        };
+        update_song_music(conn, id, seed.tonart, seed.bpm, seed.kapo);
+        set_rating(conn, id, seed.bewertung);
+        set_favorite(conn, id, seed.favorit);
+        if seed.kann_ich {
+            set_repertoire(conn, id, true, review_interval_days);
+        }
+        if seed.minuten > 0 {
+            log_practice(conn, id, seed.minuten * 60);
+        }
+        if let Some((kategorie, wert)) = seed.tag {
+            add_tag_to_song(conn, id, kategorie, wert);
+        }
+        update_song_notes(conn, id, seed.notizen);
+    }
+}
+
+/// One A4 page with the title, artist and chord lines as real text, so the
+/// full-text index and thumbnails have something to work with.
+fn chart_pdf(titel: &str, artist: &str, chords: &[&str]) -> Vec<u8> {
+    let mut content = format!("BT /F1 22 Tf 56 770 Td ({}) Tj ET\n", pdf_text(titel));
+    content.push_str(&format!(
+        "BT /F1 12 Tf 56 748 Td ({}) Tj ET\n",
+        pdf_text(artist)
+    ));
+    for (i, line) in chords.iter().enumerate() {
+        let y = 700 - i * 28;
+        content.push_str(&format!(
+            "BT /F1 14 Tf 56 {y} Td ({}) Tj ET\n",
+            pdf_text(line)
+        ));
+    }
+    let content = content.into_bytes();
+
+    let objects: [Vec<u8>; 5] = [
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+        b"<< /Type /Page /Parent 2 0 R /MediaBox [0 0 595 842] \
+          /Resources << /Font << /F1 4 0 R >> >> /Contents 5 0 R >>"
+            .to_vec(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_vec(),
+        [
+            format!("<< /Length {} >>\nstream\n", content.len()).into_bytes(),
+            content,
+            b"\nendstream".to_vec(),
+        ]
+        .concat(),
+    ];
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::new();
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend(format!("{} 0 obj\n", i + 1).into_bytes());
+        pdf.extend(object);
+        pdf.extend(b"\nendobj\n");
+    }
+    let xref = pdf.len();
+    pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+    for offset in offsets {
+        pdf.extend(format!("{offset:010} 00000 n \n").into_bytes());
+    }
+    pdf.extend(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        )
+        .into_bytes(),
+    );
+    pdf
+}
+
+/// A PDF string literal body in WinAnsi (Latin-1 for the letters used here).
+fn pdf_text(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{c}"),
+            c if (c as u32) < 0x80 => c.to_string(),
+            c if (c as u32) < 0x100 => format!("\\{:03o}", c as u32),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
+/// One second of silence, 8 kHz mono 16 bit.
+fn silent_wav() -> Vec<u8> {
+    const RATE: u32 = 8000;
+    let data_len = RATE * 2;
+    let mut wav = b"RIFF".to_vec();
+    wav.extend((36 + data_len).to_le_bytes());
+    wav.extend(b"WAVEfmt ");
+    wav.extend(16u32.to_le_bytes());
+    wav.extend(1u16.to_le_bytes()); // PCM
+    wav.extend(1u16.to_le_bytes()); // mono
+    wav.extend(RATE.to_le_bytes());
+    wav.extend((RATE * 2).to_le_bytes());
+    wav.extend(2u16.to_le_bytes());
+    wav.extend(16u16.to_le_bytes());
+    wav.extend(b"data");
+    wav.extend(data_len.to_le_bytes());
+    wav.resize(wav.len() + data_len as usize, 0);
+    wav
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::db::{init_db, query_songs, ExtraFilters, SortMode, TagFilter};
+    use crate::scanner::{scan_directory, ScanOptions};
+
+    #[test]
+    fn demo_library_scans_and_seeds() {
+        let dir = std::env::temp_dir().join(format!("songindex-demo-test-{}", std::process::id()));
+        let files = create_library(&dir).unwrap();
+        assert_eq!(
+            files,
+            CHARTS.len() + CHORDPRO.len() + TEXT.len() + AUDIO.len()
+        );
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        let report = scan_directory(&conn, &dir, &ScanOptions::from(&Config::default()));
+        seed_metadata(&conn, 14);
+        let songs = query_songs(
+            &conn,
+            "",
+            &TagFilter::none(),
+            &ExtraFilters::default(),
+            &SortMode::Title,
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // The backing tracks belong to songs and are no entries of their own.
+        let song_files = CHARTS.len() + CHORDPRO.len() + TEXT.len();
+        assert_eq!(report.added.len(), song_files);
+        assert_eq!(songs.len(), song_files);
+        let song = |pfad: &str| songs.iter().find(|s| s.dateipfad == pfad).unwrap();
+        let has_tag = |pfad: &str, kategorie: &str, wert: &str| {
+            song(pfad)
+                .tags
+                .iter()
+                .any(|t| t.kategorie == kategorie && t.wert == wert)
+        };
+
+        for seed in SEEDS {
+            let s = song(seed.pfad);
+            assert_eq!(s.tonart.as_deref(), seed.tonart, "{}", seed.pfad);
+            assert_eq!(s.bpm, seed.bpm, "{}", seed.pfad);
+            assert_eq!(s.kapo, seed.kapo, "{}", seed.pfad);
+            assert_eq!(s.bewertung, seed.bewertung, "{}", seed.pfad);
+            assert_eq!(s.favorit, seed.favorit, "{}", seed.pfad);
+            assert_eq!(s.kann_ich, seed.kann_ich, "{}", seed.pfad);
+            if let Some((kategorie, wert)) = seed.tag {
+                assert!(has_tag(seed.pfad, kategorie, wert), "{}", seed.pfad);
+            }
+        }
+
+        // Folder rules, and key and capo from inside a ChordPro file.
+        assert!(has_tag(
+            &format!("{NOTEN}/Kinderlieder/Traditional - Alle meine Entchen.pdf"),
+            "stil",
+            "Kinderlieder"
+        ));
+        assert!(has_tag(
+            "E-Gitarre/Solos/Traditional - Shenandoah.pdf",
+            "instrument",
+            "E-Gitarre"
+        ));
+        let stranger = song("E-Gitarre/Traditional - Wayfaring Stranger.cho");
+        assert_eq!(stranger.tonart.as_deref(), Some("Am"));
+        assert_eq!(stranger.kapo, Some(2));
+        assert_eq!(
+            song("Ukulele/Kinderlieder/Kuckuck.txt").titel,
+            "Kuckuck, Kuckuck, ruft's aus dem Wald"
+        );
+        assert!(
+            song(&format!(
+                "{NOTEN}/Anfaenger/Traditional - Amazing Grace.pdf"
+            ))
+            .has_audio
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use crate::chordpro::parse_metadata;
+use crate::transpose::Key;
+use std::io::Read;
+use std::path::Path;
+
+/// Kind of song file, stored in `songs.dateityp`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileType {
+    Pdf,
+    ChordPro,
+    GuitarPro,
+    MusicXml,
+    /// Plain text or Markdown lyric/chord sheet.
+    Text,
+    /// Photo or scan of a sheet (JPEG, PNG, HEIC).
+    Image,
+    /// Backing track without a chart, indexed with `ScanOptions::audio_entries`.
+    Audio,
+}
+
+impl FileType {
+    pub fn from_path(path: &Path) -> Option<FileType> {
+        let ext = path.extension()?.to_string_lossy().to_lowercase();
+        match ext.as_str() {
+            "pdf" => Some(FileType::Pdf),
+            "cho" | "chopro" | "crd" | "pro" => Some(FileType::ChordPro),
+            "gp3" | "gp4" | "gp5" | "gpx" => Some(FileType::GuitarPro),
+            "musicxml" | "mxl" => Some(FileType::MusicXml),
+            "txt" | "md" => Some(FileType::Text),
+            "jpg" | "jpeg" | "png" | "heic" => Some(FileType::Image),
+            "mp3" | "wav" | "m4a" | "aif" | "aiff" | "flac" => Some(FileType::Audio),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FileType::Pdf => "pdf",
+            FileType::ChordPro => "chordpro",
+            FileType::GuitarPro => "guitarpro",
+            FileType::MusicXml => "musicxml",
+            FileType::Text => "text",
+            FileType::Image => "image",
+            FileType::Audio => "audio",
+        }
+    }
+
+    /// Inverse of `as_str()`.
+    pub fn from_stored(s: &str) -> Option<FileType> {
+        [
+            FileType::Pdf,
+            FileType::ChordPro,
+            FileType::GuitarPro,
+            FileType::MusicXml,
+            FileType::Text,
+            FileType::Image,
+            FileType::Audio,
+        ]
+        .into_iter()
+        .find(|t| t.as_str() == s)
+    }
+
+    /// Short name for the badge on song cards.
+    pub fn label(self) -> &'static str {
+        match self {
+            FileType::Pdf => "PDF",
+            FileType::ChordPro => "ChordPro",
+            FileType::GuitarPro => "Guitar Pro",
+            FileType::MusicXml => "MusicXML",
+            FileType::Text => "Text",
+            FileType::Image => "Bild",
+            FileType::Audio => "Audio",
+        }
+    }
+}
+
+/// Metadata read from inside a song file; anything missing falls back to the
+/// file name.
+#[derive(Debug, Default)]
+pub struct SongMeta {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub key: Option<String>,
+    pub capo: Option<i64>,
+    /// Page count of a PDF.
+    pub pages: Option<i64>,
+}
+
+pub fn read_metadata(path: &Path, file_type: FileType) -> SongMeta {
+    match file_type {
+        // A broken PDF must not take the scan down with it.
+        FileType::Pdf => std::panic::catch_unwind(|| lopdf::Document::load(path))
+            .ok()
+            .and_then(|doc| doc.ok())
+            .map(|doc| pdf_metadata(&doc))
+            .unwrap_or_default(),
+        FileType::Image | FileType::Audio => SongMeta::default(),
+        FileType::ChordPro => std::fs::read_to_string(path)
+            .map(|text| parse_metadata(&text))
+            .unwrap_or_default(),
+        FileType::GuitarPro => std::fs::read(path)
+            .ok()
+            .and_then(|data| guitar_pro_metadata(&data))
+            .unwrap_or_default(),
+        FileType::MusicXml => read_musicxml(path)
+            .map(|xml| musicxml_metadata(&xml))
+            .unwrap_or_default(),
+        FileType::Text => SongMeta {
+            title: std::fs::read_to_string(path)
+                .ok()
+                .and_then(|text| text_title(&text)),
+            ..Default::default()
+        },
+    }
+}
+
+/// Page count and the Title and Author entries of the Info dictionary.
+/// Titles that name the file or the program that wrote it ("Microsoft Word -
+/// Lied.docx", "Untitled") are left out, so the file name wins.
+pub fn pdf_metadata(doc: &lopdf::Document) -> SongMeta {
+    let info = doc
+        .trailer
+        .get(b"Info")
+        .and_then(|info| match info {
+            lopdf::Object::Reference(id) => doc.get_dictionary(*id),
+            other => other.as_dict(),
+        })
+        .ok();
+    let entry = |key: &[u8]| {
+        info.and_then(|info| info.get(key).ok())
+            .and_then(|value| lopdf::decode_text_string(value).ok())
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+    };
+    let title = entry(b"Title").filter(|t| !is_generated_title(t));
+    SongMeta {
+        title,
+        artist: entry(b"Author"),
+        pages: Some(doc.get_pages().len() as i64),
+        ..Default::default()
+    }
+}
+
+fn is_generated_title(title: &str) -> bool {
+    let lower = title.to_lowercase();
+    const PROGRAMS: [&str; 4] = [
+        "microsoft word - ",
+        "microsoft excel - ",
+        "untitled",
+        "unbenannt",
+    ];
+    PROGRAMS.iter().any(|p| lower.starts_with(p))
+        || Path::new(&lower).extension().is_some_and(|ext| {
+            [
+                "pdf", "doc", "docx", "odt", "pages", "mscz", "sib", "musx", "txt",
+            ]
+            .contains(&ext.to_str().unwrap_or(""))
+        })
+}
+
+/// First non-empty line of a text sheet, without Markdown heading marks.
+fn text_title(text: &str) -> Option<String> {
+    text.lines()
+        .map(|l| l.trim().trim_start_matches('#').trim())
+        .find(|l| !l.is_empty())
+        .map(str::to_string)
+}
+
+/// Guitar Pro 3–5 header: a version string padded to 30 bytes, then title,
+/// subtitle and artist as length-prefixed Latin-1 strings. GP6 (`.gpx`) files
+/// are compressed containers and are not read.
+fn guitar_pro_metadata(data: &[u8]) -> Option<SongMeta> {
+    let version_len = *data.first()? as usize;
+    let version = String::from_utf8_lossy(data.get(1..1 + version_len.min(30))?);
+    if !version.starts_with("FICHIER GUITAR PRO") {
+        return None;
+    }
+
+    let mut pos = 31;
+    let mut next = || {
+        // Int32 field size, then a byte with the string length.
+        let size = i32::from_le_bytes(data.get(pos..pos + 4)?.try_into().ok()?);
+        let len = *data.get(pos + 4)? as usize;
+        let bytes = data.get(pos + 5..pos + 5 + len)?;
+        pos += 4 + size.max(1) as usize;
+        let text: String = bytes.iter().map(|&b| b as char).collect();
+        Some(Some(text.trim().to_string()).filter(|t| !t.is_empty()))
+    };
+    let title = next()?;
+    let _subtitle = next()?;
+    let artist = next()?;
+    Some(SongMeta {
+        title,
+        artist,
+        ..Default::default()
+    })
+}
+
+/// The score document of a `.musicxml` file or a compressed `.mxl` archive.
+fn read_musicxml(path: &Path) -> Option<String> {
+    let is_archive = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("mxl"));
+    if !is_archive {
+        return std::fs::read_to_string(path).ok();
+    }
+
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path).ok()?).ok()?;
+    // META-INF/container.xml names the root file; older archives just hold one score.
+    let root = zip_entry(&mut archive, "META-INF/container.xml")
+        .and_then(|container| attribute(&container, "rootfile", "full-path"))
+        .or_else(|| {
+            archive
+                .file_names()
+                .find(|n| {
+                    !n.starts_with("META-INF/") && (n.ends_with(".xml") || n.ends_with(".musicxml"))
+                })
+                .map(str::to_string)
+        })?;
+    zip_entry(&mut archive, &root)
+}
+
+fn zip_entry(archive: &mut zip::ZipArchive<std::fs::File>, name: &str) -> Option<String> {
+    let mut text = String::new();
+    archive.by_name(name).ok()?.read_to_string(&mut text).ok()?;
+    Some(text)
+}
+
+fn musicxml_metadata(xml: &str) -> SongMeta {
+    let title = element_text(xml, "work-title").or_else(|| element_text(xml, "movement-title"));
+    let artist = element_text(xml, "creator type=\"composer\"")
+        .or_else(|| element_text(xml, "creator type=\"lyricist\""));
+    // First key signature: fifths above/below C, `mode` minor shifts to the relative minor.
+    let key = element_text(xml, "fifths")
+        .and_then(|f| f.parse::<i64>().ok())
+        .map(|fifths| {
+            let minor = element_text(xml, "mode").is_some_and(|m| m == "minor");
+            let c = Key { root: 0, minor };
+            c.transposed(fifths * 7 + if minor { 9 } else { 0 }).name()
+        });
+    SongMeta {
+        title,
+        artist,
+        key,
+        ..Default::default()
+    }
+}
+
+/// Text of the first `<tag …>text</tag>`; `open` may include attributes.
+fn element_text(xml: &str, open: &str) -> Option<String> {
+    let name = open.split(' ').next()?;
+    let start = xml.find(&format!("<{open}"))?;
+    let content_start = start + xml[start..].find('>')? + 1;
+    let content_end = content_start + xml[content_start..].find(&format!("</{name}>"))?;
+    let text = unescape(xml[content_start..content_end].trim());
+    (!text.is_empty()).then_some(text)
+}
+
+fn attribute(xml: &str, element: &str, name: &str) -> Option<String> {
+    let start = xml.find(&format!("<{element}"))?;
+    let tag = &xml[start..start + xml[start..].find('>')?];
+    let value_start = tag.find(&format!("{name}=\""))? + name.len() + 2;
+    let value_end = value_start + tag[value_start..].find('"')?;
+    Some(unescape(&tag[value_start..value_end]))
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,26 @@
+//! The library behind Songindex without the window: configuration, the
+//! SQLite index and the scanner that keeps it in step with the music folder.
+//! Frontends (the egui app, the CLI) open a connection, call
+//! [`db::init_db`] and then use the query and scan functions directly; the
+//! app then hands the connection to a [`worker::Db`] that its threads share.
+//!
+//! Nothing here panics on I/O or SQL errors or prints to the terminal: setup
+//! returns `Result`, queries that fail come back empty, and progress goes to
+//! the [`log`] facade for the frontend to route.
+
+pub mod arrangements;
+pub mod autotag;
+pub mod chordpro;
+pub mod companions;
+pub mod config;
+pub mod db;
+pub mod demo;
+pub mod filetype;
+pub mod maintenance;
+pub mod progression;
+pub mod query;
+pub mod removal_log;
+pub mod repertoire;
+pub mod scanner;
+pub mod transpose;
+pub mod worker;
@@ -0,0 +1,125 @@
+//! Upkeep of the SQLite file: compacting, WAL checkpoint, integrity check
+//! and leftover rows. Unlike the rest of the crate these return their errors,
+//! since the user asked for them and wants to see what went wrong.
+
+use rusqlite::{params, Connection};
+
+/// File size and unused space, in bytes.
+pub struct Size {
+    pub total: i64,
+    pub free: i64,
+}
+
+pub fn size(conn: &Connection) -> rusqlite::Result<Size> {
+    let pragma =
+        |name: &str| conn.query_row(&format!("PRAGMA {name}"), [], |row| row.get::<_, i64>(0));
+    let page_size = pragma("page_size")?;
+    Ok(Size {
+        total: pragma("page_count")? * page_size,
+        free: pragma("freelist_count")? * page_size,
+    })
+}
+
+/// Rewrite the file without unused pages. Returns the size before and after.
+pub fn vacuum(conn: &Connection) -> rusqlite::Result<(i64, i64)> {
+    let before = size(conn)?.total;
+    conn.execute_batch("VACUUM")?;
+    Ok((before, size(conn)?.total))
+}
+
+/// Result of `PRAGMA wal_checkpoint`, in pages.
+pub struct Checkpoint {
+    /// Another connection kept part of the log from being written back.
+    pub busy: bool,
+    pub log: i64,
+    pub written: i64,
+}
+
+/// Write the WAL back into the database file and truncate it. The page
+/// counts come from a full checkpoint first; truncating reports zeros.
+pub fn checkpoint(conn: &Connection) -> rusqlite::Result<Checkpoint> {
+    let run = |mode: &str| {
+        conn.query_row(&format!("PRAGMA wal_checkpoint({mode})"), [], |row| {
+            Ok(Checkpoint {
+                busy: row.get::<_, i64>(0)? != 0,
+                log: row.get(1)?,
+                written: row.get(2)?,
+            })
+        })
+    };
+    let full = run("FULL")?;
+    let truncate = run("TRUNCATE")?;
+    Ok(Checkpoint {
+        busy: full.busy || truncate.busy,
+        ..full
+    })
+}
+
+/// Problems `PRAGMA integrity_check` reports; empty if the file is sound.
+pub fn integrity_check(conn: &Connection) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    let problems: Vec<String> = rows.collect::<rusqlite::Result<_>>()?;
+    Ok(problems.into_iter().filter(|p| p != "ok").collect())
+}
+
+/// Rows that point at nothing, and tags no song uses.
+#[derive(Default)]
+pub struct OrphanReport {
+    /// Rows per table whose song or tag no longer exists, e.g. `song_tags`
+    /// entries from before foreign keys were enforced.
+    pub dangling: Vec<(String, i64)>,
+    /// Tags without songs that are not pinned; normal cleanup removes them.
+    pub unused_tags: i64,
+}
+
+impl OrphanReport {
+    pub fn is_empty(&self) -> bool {
+        self.dangling.is_empty() && self.unused_tags == 0
+    }
+}
+
+/// `(table, rowid)` of every row `PRAGMA foreign_key_check` reports.
+fn dangling_rows(conn: &Connection) -> rusqlite::Result<Vec<(String, i64)>> {
+    let mut stmt = conn.prepare("PRAGMA foreign_key_check")?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+pub fn orphan_report(conn: &Connection) -> rusqlite::Result<OrphanReport> {
+    let mut report = OrphanReport::default();
+    for (table, _) in dangling_rows(conn)? {
+        match report.dangling.iter_mut().find(|(t, _)| *t == table) {
+            Some((_, count)) => *count += 1,
+            None => report.dangling.push((table, 1)),
+        }
+    }
+    report.unused_tags = conn.query_row(
+        "SELECT COUNT(*) FROM tags WHERE angeheftet = 0
+         AND id NOT IN (SELECT DISTINCT tag_id FROM song_tags)",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(report)
+}
+
+/// Delete what `orphan_report()` finds, in one transaction. Returns the
+/// number of rows removed.
+pub fn remove_orphans(conn: &Connection) -> rusqlite::Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    let mut removed = 0;
+    for (table, rowid) in dangling_rows(&tx)? {
+        // Table names come from SQLite itself, not from user input.
+        removed += tx.execute(
+            &format!("DELETE FROM \"{table}\" WHERE rowid = ?1"),
+            params![rowid],
+        )?;
+    }
+    removed += tx.execute(
+        "DELETE FROM tags WHERE angeheftet = 0
+         AND id NOT IN (SELECT DISTINCT tag_id FROM song_tags)",
+        [],
+    )?;
+    tx.commit()?;
+    Ok(removed)
+}
@@ -0,0 +1,185 @@
+//! Learning paths: "learn this before that" links between songs
+//! (`song_folgen`), and the songs that are next once everything before them
+//! is in the repertoire.
+
+use crate::db::{query_rows, LogErr};
+use rusqlite::{params, Connection};
+
+/// Links are followed at most this far from a song.
+const MAX_DEPTH: i64 = 32;
+
+/// A song on a path around the selected one.
+#[derive(Debug, Clone)]
+pub struct PathSong {
+    pub id: i64,
+    pub titel: String,
+    pub artist: Option<String>,
+    pub kann_ich: bool,
+    /// Linked to the selected song itself rather than through another one.
+    pub direkt: bool,
+}
+
+/// What comes before and after a song, one group per step. A song reachable
+/// over paths of different length sits at its farthest step, so each group
+/// only depends on the groups before it.
+#[derive(Debug, Clone, Default)]
+pub struct Chain {
+    /// Farthest first, ending with the songs right before.
+    pub vorher: Vec<Vec<PathSong>>,
+    /// Right after first.
+    pub danach: Vec<Vec<PathSong>>,
+}
+
+impl Chain {
+    pub fn is_empty(&self) -> bool {
+        self.vorher.is_empty() && self.danach.is_empty()
+    }
+}
+
+/// A song whose prerequisites are all in the repertoire while it isn't yet.
+#[derive(Debug, Clone)]
+pub struct NextSong {
+    pub id: i64,
+    pub titel: String,
+    pub artist: Option<String>,
+    pub dateipfad: String,
+    /// Titles of the songs right before it.
+    pub nach: Vec<String>,
+}
+
+/// Learn `vorher_id` before `song_id`. Returns false if that would make a
+/// song its own prerequisite.
+pub fn add_prerequisite(conn: &Connection, song_id: i64, vorher_id: i64) -> bool {
+    if song_id == vorher_id || reaches(conn, song_id, vorher_id) {
+        return false;
+    }
+    conn.execute(
+        "INSERT OR IGNORE INTO song_folgen (song_id, vorher_id) VALUES (?1, ?2)",
+        params![song_id, vorher_id],
+    )
+    .is_ok()
+}
+
+pub fn remove_prerequisite(conn: &Connection, song_id: i64, vorher_id: i64) {
+    conn.execute(
+        "DELETE FROM song_folgen WHERE song_id = ?1 AND vorher_id = ?2",
+        params![song_id, vorher_id],
+    )
+    .log_err();
+}
+
+/// Whether `to` comes somewhere after `from`.
+fn reaches(conn: &Connection, from: i64, to: i64) -> bool {
+    conn.query_row(
+        &format!(
+            "WITH RECURSIVE danach(id, tiefe) AS (
+                 SELECT song_id, 1 FROM song_folgen WHERE vorher_id = ?1
+                 UNION
+                 SELECT f.song_id, d.tiefe + 1 FROM song_folgen f
+                 JOIN danach d ON f.vorher_id = d.id WHERE d.tiefe < {MAX_DEPTH}
+             )
+             SELECT EXISTS (SELECT 1 FROM danach WHERE id = ?2)"
+        ),
+        params![from, to],
+        |row| row.get(0),
+    )
+    .unwrap_or(false)
+}
+
+pub fn chain(conn: &Connection, song_id: i64) -> Chain {
+    let mut vorher = steps(conn, song_id, "song_id", "vorher_id");
+    vorher.reverse();
+    Chain {
+        vorher,
+        danach: steps(conn, song_id, "vorher_id", "song_id"),
+    }
+}
+
+/// Songs reached from `song_id` by following `from` → `to` links, grouped
+/// by their farthest step, nearest group first. Trashed and ignored songs
+/// are left out.
+fn steps(conn: &Connection, song_id: i64, from: &str, to: &str) -> Vec<Vec<PathSong>> {
+    let rows: Vec<(i64, PathSong)> = query_rows(
+        conn,
+        &format!(
+            "WITH RECURSIVE weg(id, tiefe) AS (
+                 SELECT {to}, 1 FROM song_folgen WHERE {from} = ?1
+                 UNION
+                 SELECT f.{to}, w.tiefe + 1 FROM song_folgen f
+                 JOIN weg w ON f.{from} = w.id WHERE w.tiefe < {MAX_DEPTH}
+             )
+             SELECT MAX(w.tiefe), s.id, s.titel, s.artist, s.repertoire_seit IS NOT NULL,
+                    EXISTS (SELECT 1 FROM song_folgen WHERE {from} = ?1 AND {to} = s.id)
+             FROM weg w JOIN songs s ON s.id = w.id
+             WHERE s.id != ?1 AND s.deleted_at IS NULL AND s.ignoriert = 0
+             GROUP BY s.id
+             ORDER BY MAX(w.tiefe), s.titel"
+        ),
+        params![song_id],
+        |row| {
+            Ok((
+                row.get(0)?,
+                PathSong {
+                    id: row.get(1)?,
+                    titel: row.get(2)?,
+                    artist: row.get(3)?,
+                    kann_ich: row.get(4)?,
+                    direkt: row.get(5)?,
+                },
+            ))
+        },
+    );
+    let mut groups: Vec<Vec<PathSong>> = Vec::new();
+    let mut current = 0;
+    for (tiefe, song) in rows {
+        if tiefe != current || groups.is_empty() {
+            groups.push(Vec::new());
+            current = tiefe;
+        }
+        if let Some(group) = groups.last_mut() {
+            group.push(song);
+        }
+    }
+    groups
+}
+
+/// What to learn next: songs outside the repertoire whose prerequisites are
+/// all in it, easiest first by the difficulty scale. Trashed or ignored
+/// prerequisites don't hold a song back.
+pub fn next_songs(conn: &Connection) -> Vec<NextSong> {
+    let mut songs: Vec<NextSong> = query_rows(
+        conn,
+        "SELECT s.id, s.titel, s.artist, s.dateipfad FROM songs s
+         WHERE s.repertoire_seit IS NULL AND s.deleted_at IS NULL AND s.ignoriert = 0
+           AND EXISTS (SELECT 1 FROM song_folgen f WHERE f.song_id = s.id)
+           AND NOT EXISTS (
+               SELECT 1 FROM song_folgen f JOIN songs v ON v.id = f.vorher_id
+               WHERE f.song_id = s.id AND v.repertoire_seit IS NULL
+                 AND v.deleted_at IS NULL AND v.ignoriert = 0
+           )
+         ORDER BY COALESCE((SELECT MIN(t.rang) FROM song_tags st JOIN tags t ON t.id = st.tag_id
+                            WHERE st.song_id = s.id AND t.kategorie = 'schwierigkeit'),
+                           1e9), s.titel",
+        [],
+        |row| {
+            Ok(NextSong {
+                id: row.get(0)?,
+                titel: row.get(1)?,
+                artist: row.get(2)?,
+                dateipfad: row.get(3)?,
+                nach: Vec::new(),
+            })
+        },
+    );
+    for song in &mut songs {
+        song.nach = query_rows(
+            conn,
+            "SELECT v.titel FROM song_folgen f JOIN songs v ON v.id = f.vorher_id
+             WHERE f.song_id = ?1 AND v.deleted_at IS NULL AND v.ignoriert = 0
+             ORDER BY v.titel",
+            params![song.id],
+            |row| row.get(0),
+        );
+    }
+    songs
+}
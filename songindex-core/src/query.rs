@@ -0,0 +1,300 @@
+use crate::db::now_shifted;
+use rusqlite::types::ToSql;
+
+/// Categories that can be used directly as a search field, e.g. `stil:Jazz`.
+const CATEGORY_FIELDS: &[&str] = &[
+    "instrument",
+    "schwierigkeit",
+    "stil",
+    "technik",
+    "stimmung",
+];
+
+#[derive(Debug)]
+pub enum Filter {
+    /// Plain word or quoted phrase: title, artist, file name or notes contain it.
+    Text(String),
+    Titel(String),
+    Artist(String),
+    Datei(String),
+    Notiz(String),
+    /// Text layer of the PDF, extracted in the background (jobs.rs).
+    Inhalt(String),
+    /// `tag:Blues` — any tag with this value, regardless of category.
+    Tag(String),
+    /// `stil:Blues` — a tag with this category and value.
+    Kategorie(String, String),
+    HasAudio,
+    HasTags,
+    Repertoire,
+    Favorit,
+    /// Excluded from the public repertoire site.
+    Privat,
+    Tonart(String),
+    /// Inclusive bounds, e.g. `bpm:60-90` or `kapo:<=2`.
+    Bpm(Option<i64>, Option<i64>),
+    Kapo(Option<i64>, Option<i64>),
+    /// `seiten:<=2` — page count of a PDF.
+    Seiten(Option<i64>, Option<i64>),
+    /// `gespielt:30` — opened, audio played or practiced in the last 30 days.
+    Gespielt(i64),
+}
+
+#[derive(Debug)]
+pub struct Term {
+    pub filter: Filter,
+    pub negated: bool,
+}
+
+/// Split on whitespace, keeping double-quoted parts together. Quotes are
+/// removed, so `artist:"The Beatles"` becomes `artist:The Beatles`.
+fn tokenize(input: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    for c in input.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// `60-90`, `>=100`, `<3`, `2` → inclusive (min, max). Numbers that do not
+/// fit, `>` the largest one included, give None: the term is searched as
+/// text and matches nothing.
+fn parse_range(value: &str) -> Option<(Option<i64>, Option<i64>)> {
+    let num = |s: &str| s.trim().parse::<i64>().ok();
+    if let Some(v) = value.strip_prefix(">=") {
+        Some((Some(num(v)?), None))
+    } else if let Some(v) = value.strip_prefix("<=") {
+        Some((None, Some(num(v)?)))
+    } else if let Some(v) = value.strip_prefix('>') {
+        Some((Some(num(v)?.checked_add(1)?), None))
+    } else if let Some(v) = value.strip_prefix('<') {
+        Some((None, Some(num(v)?.checked_sub(1)?)))
+    } else if let Some((a, b)) = value.split_once('-') {
+        Some((Some(num(a)?), Some(num(b)?)))
+    } else {
+        let n = num(value)?;
+        Some((Some(n), Some(n)))
+    }
+}
+
+fn parse_filter(token: &str) -> Filter {
+    let Some((field, value)) = token.split_once(':') else {
+        return Filter::Text(token.to_string());
+    };
+    let field = field.to_lowercase();
+    let value = value.trim();
+    if value.is_empty() {
+        return Filter::Text(token.to_string());
+    }
+    match field.as_str() {
+        "titel" | "title" => Filter::Titel(value.to_string()),
+        "artist" => Filter::Artist(value.to_string()),
+        "datei" | "file" => Filter::Datei(value.to_string()),
+        "notiz" | "notizen" | "note" => Filter::Notiz(value.to_string()),
+        "inhalt" | "text" => Filter::Inhalt(value.to_string()),
+        "tonart" | "key" => Filter::Tonart(value.to_string()),
+        "bpm" | "tempo" => match parse_range(value) {
+            Some((min, max)) => Filter::Bpm(min, max),
+            None => Filter::Text(token.to_string()),
+        },
+        "kapo" | "capo" => match parse_range(value) {
+            Some((min, max)) => Filter::Kapo(min, max),
+            None => Filter::Text(token.to_string()),
+        },
+        "seiten" | "pages" => match parse_range(value) {
+            Some((min, max)) => Filter::Seiten(min, max),
+            None => Filter::Text(token.to_string()),
+        },
+        "gespielt" | "played" => match value.parse() {
+            Ok(days) if days >= 0 => Filter::Gespielt(days),
+            _ => Filter::Text(token.to_string()),
+        },
+        "tag" => match value.split_once(':') {
+            Some((k, w)) if !w.is_empty() => Filter::Kategorie(k.to_lowercase(), w.to_string()),
+            _ => Filter::Tag(value.to_string()),
+        },
+        "has" | "hat" => match value.to_lowercase().as_str() {
+            "audio" => Filter::HasAudio,
+            "tags" | "tag" => Filter::HasTags,
+            _ => Filter::Text(token.to_string()),
+        },
+        "is" | "ist" => match value.to_lowercase().as_str() {
+            "repertoire" | "kannich" => Filter::Repertoire,
+            "favorit" | "favorite" => Filter::Favorit,
+            "privat" | "private" => Filter::Privat,
+            _ => Filter::Text(token.to_string()),
+        },
+        k if CATEGORY_FIELDS.contains(&k) => Filter::Kategorie(k.to_string(), value.to_string()),
+        _ => Filter::Text(token.to_string()),
+    }
+}
+
+/// Parse the search box. Anything that isn't a known `field:value` is plain
+/// text, so titles containing a colon still work. A leading `-` negates a term.
+pub fn parse(input: &str) -> Vec<Term> {
+    tokenize(input)
+        .into_iter()
+        .map(|token| match token.strip_prefix('-') {
+            Some(rest) if !rest.is_empty() => Term {
+                filter: parse_filter(rest),
+                negated: true,
+            },
+            _ => Term {
+                filter: parse_filter(&token),
+                negated: false,
+            },
+        })
+        .collect()
+}
+
+fn like(value: &str) -> Box<dyn ToSql> {
+    Box::new(format!("%{}%", value.to_lowercase()))
+}
+
+fn tag_exists(condition: &str) -> String {
+    format!(
+        "EXISTS (SELECT 1 FROM song_tags qst JOIN tags qt ON qt.id = qst.tag_id
+                 WHERE qst.song_id = s.id AND {condition})"
+    )
+}
+
+fn range_clause(column: &str, min: Option<i64>, max: Option<i64>) -> String {
+    match (min, max) {
+        (Some(min), Some(max)) => format!("{column} BETWEEN {min} AND {max}"),
+        (Some(min), None) => format!("{column} >= {min}"),
+        (None, Some(max)) => format!("{column} <= {max}"),
+        (None, None) => "1=1".to_string(),
+    }
+}
+
+/// Append one `AND ...` clause per term to a query over `songs s`.
+pub fn push_clauses(terms: &[Term], sql: &mut String, params: &mut Vec<Box<dyn ToSql>>) {
+    for term in terms {
+        let n = params.len() + 1;
+        let clause = match &term.filter {
+            Filter::Text(v) => {
+                params.push(like(v));
+                format!(
+                    "(LOWER(s.titel) LIKE ?{n} OR LOWER(COALESCE(s.artist, '')) LIKE ?{n}
+                      OR LOWER(s.dateiname) LIKE ?{n} OR LOWER(COALESCE(s.notizen, '')) LIKE ?{n})"
+                )
+            }
+            Filter::Titel(v) => {
+                params.push(like(v));
+                format!("LOWER(s.titel) LIKE ?{n}")
+            }
+            Filter::Artist(v) => {
+                params.push(like(v));
+                format!("LOWER(COALESCE(s.artist, '')) LIKE ?{n}")
+            }
+            Filter::Datei(v) => {
+                params.push(like(v));
+                format!("LOWER(s.dateipfad) LIKE ?{n}")
+            }
+            Filter::Notiz(v) => {
+                params.push(like(v));
+                format!("LOWER(COALESCE(s.notizen, '')) LIKE ?{n}")
+            }
+            Filter::Inhalt(v) => {
+                params.push(like(v));
+                format!("LOWER(COALESCE(s.volltext, '')) LIKE ?{n}")
+            }
+            Filter::Tag(v) => {
+                params.push(Box::new(v.to_lowercase()));
+                tag_exists(&format!("LOWER(qt.wert) = ?{n}"))
+            }
+            Filter::Kategorie(k, v) => {
+                params.push(Box::new(k.clone()));
+                params.push(Box::new(v.to_lowercase()));
+                tag_exists(&format!(
+                    "qt.kategorie = ?{n} AND LOWER(qt.wert) = ?{}",
+                    n + 1
+                ))
+            }
+            Filter::HasAudio => "s.has_audio = 1".to_string(),
+            Filter::HasTags => tag_exists("qst.auto_generated = 0"),
+            Filter::Repertoire => "s.repertoire_seit IS NOT NULL".to_string(),
+            Filter::Favorit => "s.favorit = 1".to_string(),
+            Filter::Privat => "s.privat = 1".to_string(),
+            Filter::Tonart(v) => {
+                params.push(Box::new(v.to_lowercase()));
+                format!("LOWER(s.tonart) = ?{n}")
+            }
+            Filter::Bpm(min, max) => range_clause("s.bpm", *min, *max),
+            // Songs without a capo value count as capo 0.
+            Filter::Kapo(min, max) => range_clause("COALESCE(s.kapo, 0)", *min, *max),
+            Filter::Seiten(min, max) => range_clause("s.seiten", *min, *max),
+            Filter::Gespielt(days) => {
+                let since = now_shifted(&format!("-{days} days"));
+                format!(
+                    "(EXISTS (SELECT 1 FROM song_events qe
+                              WHERE qe.song_id = s.id AND qe.zeitpunkt >= {since})
+                      OR EXISTS (SELECT 1 FROM practice_log qp
+                                 WHERE qp.song_id = s.id AND qp.started_at >= {since}))"
+                )
+            }
+        };
+        // A comparison with NULL (no bpm, key or page count) is NULL, and NOT
+        // NULL would drop the song; a negated term keeps it.
+        if term.negated {
+            sql.push_str(&format!(" AND NOT COALESCE(({clause}), 0)"));
+        } else {
+            sql.push_str(&format!(" AND {clause}"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::{init_db, query_songs, ExtraFilters, SortMode, TagFilter};
+    use rusqlite::Connection;
+
+    #[test]
+    fn negated_range_keeps_songs_without_a_value() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        for (titel, bpm, tonart) in [
+            ("Schnell", Some(110), Some("G")),
+            ("Langsam", Some(80), None),
+            ("Ohne", None, None),
+        ] {
+            conn.execute(
+                "INSERT INTO songs (titel, dateipfad, dateiname, bpm, tonart)
+                 VALUES (?1, ?1 || '.pdf', ?1 || '.pdf', ?2, ?3)",
+                rusqlite::params![titel, bpm, tonart],
+            )
+            .unwrap();
+        }
+        let titles = |search: &str| {
+            let mut titles: Vec<String> = query_songs(
+                &conn,
+                search,
+                &TagFilter::none(),
+                &ExtraFilters::default(),
+                &SortMode::Title,
+            )
+            .into_iter()
+            .map(|song| song.titel)
+            .collect();
+            titles.sort();
+            titles
+        };
+
+        assert_eq!(titles("bpm:100-120"), ["Schnell"]);
+        assert_eq!(titles("-bpm:100-120"), ["Langsam", "Ohne"]);
+        assert_eq!(titles("-tonart:G"), ["Langsam", "Ohne"]);
+    }
+}
@@ -0,0 +1,217 @@
+use crate::db::{add_tag_to_song, local_time, query_rows, LogErr, NOW};
+use crate::scanner::{add_single_file, ScanOptions};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+
+/// A song the scanner moved to the Papierkorb, as it was at that moment. The
+/// entry outlives purging the song, so its metadata can be brought back.
+pub struct RemovedSong {
+    pub id: i64,
+    pub dateipfad: String,
+    pub titel: String,
+    pub artist: Option<String>,
+    pub tags: Vec<(String, String)>,
+    pub zeitpunkt: String,
+    /// Restore was requested while the file was missing.
+    pub wartet: bool,
+    /// A song with this path is in the list again.
+    pub zurueck: bool,
+}
+
+pub enum Restored {
+    Now,
+    /// The file is missing; the next scan that finds it restores the metadata.
+    WhenFileReturns,
+}
+
+/// Log a song before the scanner moves it to the Papierkorb. An earlier entry
+/// for the same path that nobody asked to restore is replaced, so a network
+/// drive that drops out now and then does not fill the log.
+pub fn record(conn: &Connection, song_id: i64) {
+    let Ok((dateipfad, titel, artist, notizen)) = conn.query_row(
+        "SELECT dateipfad, titel, artist, notizen FROM songs WHERE id = ?1",
+        params![song_id],
+        |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        },
+    ) else {
+        return;
+    };
+    let tags: Vec<(String, String)> = query_rows(
+        conn,
+        "SELECT t.kategorie, t.wert FROM song_tags st
+         JOIN tags t ON t.id = st.tag_id
+         WHERE st.song_id = ?1
+         ORDER BY t.kategorie, t.wert",
+        params![song_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    );
+    conn.execute(
+        "DELETE FROM entfernte_songs WHERE dateipfad = ?1 AND wartet = 0",
+        params![dateipfad],
+    )
+    .log_err();
+    conn.execute(
+        &format!(
+            "INSERT INTO entfernte_songs (dateipfad, titel, artist, notizen, tags, zeitpunkt)
+             VALUES (?1, ?2, ?3, ?4, ?5, {NOW})"
+        ),
+        params![
+            dateipfad,
+            titel,
+            artist,
+            notizen,
+            serde_json::to_string(&tags).unwrap_or_default()
+        ],
+    )
+    .log_err();
+}
+
+/// Newest first.
+pub fn entries(conn: &Connection) -> Vec<RemovedSong> {
+    let sql = format!(
+        "SELECT e.id, e.dateipfad, e.titel, e.artist, e.tags, {}, e.wartet,
+                EXISTS(SELECT 1 FROM songs s
+                       WHERE s.dateipfad = e.dateipfad AND s.deleted_at IS NULL)
+         FROM entfernte_songs e
+         ORDER BY e.zeitpunkt DESC, e.id DESC",
+        local_time("e.zeitpunkt")
+    );
+    query_rows(conn, &sql, [], |row| {
+        let tags: String = row.get(4)?;
+        Ok(RemovedSong {
+            id: row.get(0)?,
+            dateipfad: row.get(1)?,
+            titel: row.get(2)?,
+            artist: row.get(3)?,
+            tags: serde_json::from_str(&tags).unwrap_or_default(),
+            zeitpunkt: row.get(5)?,
+            wartet: row.get(6)?,
+            zurueck: row.get(7)?,
+        })
+    })
+}
+
+/// Bring a logged song back with its title, artist, notes and tags: right
+/// away if the file is there, otherwise once a scan finds it again.
+pub fn restore(
+    conn: &Connection,
+    base_dir: &Path,
+    options: &ScanOptions,
+    entry_id: i64,
+) -> Restored {
+    let dateipfad: Option<String> = conn
+        .query_row(
+            "SELECT dateipfad FROM entfernte_songs WHERE id = ?1",
+            params![entry_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .ok()
+        .flatten();
+    let Some(dateipfad) = dateipfad else {
+        return Restored::Now;
+    };
+    conn.execute(
+        "UPDATE entfernte_songs SET wartet = 1 WHERE id = ?1",
+        params![entry_id],
+    )
+    .log_err();
+    let path = base_dir.join(&dateipfad);
+    if !path.is_file() {
+        return Restored::WhenFileReturns;
+    }
+    // Indexing the file (or taking it out of the Papierkorb) applies the
+    // pending restore; an excluded file waits like a missing one.
+    add_single_file(conn, base_dir, &path, options);
+    if pending(conn, entry_id) {
+        Restored::WhenFileReturns
+    } else {
+        Restored::Now
+    }
+}
+
+fn pending(conn: &Connection, entry_id: i64) -> bool {
+    conn.query_row(
+        "SELECT wartet FROM entfernte_songs WHERE id = ?1",
+        params![entry_id],
+        |row| row.get(0),
+    )
+    .unwrap_or(false)
+}
+
+/// Apply a pending restore to the song indexed at `dateipfad`. Called by the
+/// scanner for every song it inserts or takes out of the Papierkorb; true if
+/// there was one.
+pub fn apply_pending(conn: &Connection, dateipfad: &str) -> bool {
+    let song_id: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM songs WHERE dateipfad = ?1 AND deleted_at IS NULL",
+            params![dateipfad],
+            |row| row.get(0),
+        )
+        .optional()
+        .ok()
+        .flatten();
+    let Some(song_id) = song_id else {
+        return false;
+    };
+    let entry = conn
+        .query_row(
+            "SELECT id, titel, artist, notizen, tags FROM entfernte_songs
+             WHERE dateipfad = ?1 AND wartet = 1
+             ORDER BY zeitpunkt DESC LIMIT 1",
+            params![dateipfad],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, String>(4)?,
+                ))
+            },
+        )
+        .optional()
+        .ok()
+        .flatten();
+    let Some((entry_id, titel, artist, notizen, tags)) = entry else {
+        return false;
+    };
+    // Notes written since the song came back win over the logged ones.
+    conn.execute(
+        &format!(
+            "UPDATE songs SET titel = ?1, artist = ?2, notizen = COALESCE(notizen, ?3),
+                              updated_at = {NOW}
+             WHERE id = ?4"
+        ),
+        params![titel, artist, notizen, song_id],
+    )
+    .log_err();
+    let tags: Vec<(String, String)> = serde_json::from_str(&tags).unwrap_or_default();
+    for (kategorie, wert) in tags {
+        add_tag_to_song(conn, song_id, &kategorie, &wert);
+    }
+    conn.execute(
+        "UPDATE entfernte_songs SET wartet = 0 WHERE dateipfad = ?1",
+        params![dateipfad],
+    )
+    .log_err();
+    log::info!("Restored metadata of {dateipfad} from removal log entry {entry_id}");
+    true
+}
+
+/// Drop the entries of songs that are back in the list.
+pub fn clear_returned(conn: &Connection) {
+    conn.execute(
+        "DELETE FROM entfernte_songs
+         WHERE dateipfad IN (SELECT dateipfad FROM songs WHERE deleted_at IS NULL)",
+        [],
+    )
+    .log_err();
+}
@@ -0,0 +1,113 @@
+use crate::db::{query_rows, LogErr, NOW};
+use rusqlite::{params, Connection};
+
+/// Upper bound for the review interval, however often a song was reviewed.
+const MAX_INTERVAL_DAYS: i64 = 180;
+
+#[derive(Debug, Clone)]
+pub struct RepertoireSong {
+    pub id: i64,
+    pub titel: String,
+    pub artist: Option<String>,
+    pub dateipfad: String,
+    pub interval_days: i64,
+    pub days_since: f64,
+}
+
+impl RepertoireSong {
+    /// 100% right after practice, 50% when the interval has passed, then further down.
+    pub fn freshness(&self) -> f64 {
+        0.5f64.powf(self.days_since / self.interval_days.max(1) as f64)
+    }
+
+    pub fn is_due(&self) -> bool {
+        self.days_since >= self.interval_days as f64
+    }
+}
+
+/// Mark a song as "kann ich" (starting at `base_interval` days) or drop it from
+/// the repertoire again.
+pub fn set_repertoire(conn: &Connection, song_id: i64, on: bool, base_interval: u32) {
+    if on {
+        conn.execute(
+            &format!(
+                "UPDATE songs SET repertoire_seit = {NOW},
+                                  wiederholung_intervall = ?2,
+                                  letzte_wiederholung = NULL
+                 WHERE id = ?1 AND repertoire_seit IS NULL"
+            ),
+            params![song_id, base_interval],
+        )
+    } else {
+        conn.execute(
+            "UPDATE songs SET repertoire_seit = NULL, wiederholung_intervall = NULL,
+                              letzte_wiederholung = NULL
+             WHERE id = ?1",
+            params![song_id],
+        )
+    }
+    .log_err();
+}
+
+/// All repertoire songs with the days since they were last reviewed or
+/// practiced (whatever happened last). Both sides are UTC, so the fraction of
+/// a day is exact across time zone changes.
+pub fn repertoire_songs(conn: &Connection) -> Vec<RepertoireSong> {
+    query_rows(
+        conn,
+        "SELECT s.id, s.titel, s.artist, s.dateipfad, s.wiederholung_intervall,
+                julianday('now') - julianday(MAX(
+                    s.repertoire_seit,
+                    COALESCE(s.letzte_wiederholung, ''),
+                    COALESCE((SELECT MAX(started_at) FROM practice_log WHERE song_id = s.id), '')
+                ))
+         FROM songs s
+         WHERE s.repertoire_seit IS NOT NULL AND s.deleted_at IS NULL AND s.ignoriert = 0",
+        [],
+        |row| {
+            Ok(RepertoireSong {
+                id: row.get(0)?,
+                titel: row.get(1)?,
+                artist: row.get(2)?,
+                dateipfad: row.get(3)?,
+                interval_days: row.get::<_, Option<i64>>(4)?.unwrap_or(14),
+                days_since: row.get::<_, Option<f64>>(5)?.unwrap_or(0.0),
+            })
+        },
+    )
+}
+
+/// Due songs, most overdue (relative to their interval) first.
+pub fn review_queue(conn: &Connection) -> Vec<RepertoireSong> {
+    let mut due: Vec<RepertoireSong> = repertoire_songs(conn)
+        .into_iter()
+        .filter(|s| s.is_due())
+        .collect();
+    due.sort_by(|a, b| a.freshness().total_cmp(&b.freshness()));
+    due
+}
+
+/// Record a review. A confident review doubles the interval (up to
+/// `MAX_INTERVAL_DAYS`); a shaky one starts over at `base_interval`.
+pub fn mark_reviewed(conn: &Connection, song_id: i64, confident: bool, base_interval: u32) {
+    let interval: i64 = if confident {
+        conn.query_row(
+            "SELECT COALESCE(wiederholung_intervall, ?2) FROM songs WHERE id = ?1",
+            params![song_id, base_interval],
+            |row| row.get(0),
+        )
+        .map(|i: i64| (i * 2).min(MAX_INTERVAL_DAYS))
+        .unwrap_or(i64::from(base_interval))
+    } else {
+        i64::from(base_interval)
+    };
+    conn.execute(
+        &format!(
+            "UPDATE songs SET letzte_wiederholung = {NOW},
+                              wiederholung_intervall = ?2
+             WHERE id = ?1"
+        ),
+        params![song_id, interval],
+    )
+    .log_err();
+}
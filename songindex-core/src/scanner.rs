@@ -0,0 +1,1517 @@
+use crate::arrangements;
+use crate::autotag::{infer_tags, Tag};
+use crate::companions::{self, companion_paths, is_companion};
+use crate::config::{load_config, AutoTagRule, Config, RescanSchedule};
+use crate::db::{get_or_create_tag, get_song_tags, query_rows, LogErr, NOW};
+use crate::filetype::{read_metadata, FileType, SongMeta};
+use crate::removal_log;
+use crate::worker::Db;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use rusqlite::{params, Connection};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard};
+use std::time::{Duration, Instant, SystemTime};
+use unicode_normalization::UnicodeNormalization;
+use walkdir::WalkDir;
+
+/// Normalize a lossy string from the filesystem to NFC form.
+/// macOS stores filenames in NFD (decomposed), which causes combining
+/// characters (e.g. "a" + U+0308) to render as boxes in egui.
+pub fn nfc(s: std::borrow::Cow<'_, str>) -> String {
+    s.nfc().collect()
+}
+
+/// File extensions the scanner indexes as songs: PDFs, ChordPro charts,
+/// Guitar Pro tabs, MusicXML scores, plain text/Markdown sheets and image scans.
+pub const SONG_EXTENSIONS: &[&str] = &[
+    "pdf", "cho", "chopro", "crd", "pro", "gp3", "gp4", "gp5", "gpx", "musicxml", "mxl", "txt",
+    "md", "jpg", "jpeg", "png", "heic",
+];
+
+/// Audio files: companions of a song (`audio_pfad`), or entries of their own
+/// with `ScanOptions::audio_entries`.
+pub const AUDIO_EXTENSIONS: &[&str] = &["mp3", "wav", "m4a", "aif", "aiff", "flac"];
+
+pub fn is_song_file(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| SONG_EXTENSIONS.contains(&ext.to_string_lossy().to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+pub fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| AUDIO_EXTENSIONS.contains(&ext.to_string_lossy().to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Scanner settings from `Config`, shared with the watcher thread so changes in
+/// the settings apply without a restart.
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    /// Index audio files that are no song's companion as songs of type `audio`.
+    pub audio_entries: bool,
+    /// `Config::ignore_patterns`; invalid patterns are left out.
+    pub ignore: GlobSet,
+    /// `Config::auto_tag_rules`, applied to songs when they are first indexed.
+    pub auto_tags: Vec<AutoTagRule>,
+}
+
+impl From<&Config> for ScanOptions {
+    fn from(config: &Config) -> Self {
+        Self {
+            audio_entries: config.audio_entries,
+            ignore: ignore_set(&config.ignore_patterns).0,
+            auto_tags: config.auto_tag_rules.clone(),
+        }
+    }
+}
+
+impl ScanOptions {
+    /// Whether a path relative to the music folder matches an ignore pattern.
+    pub fn is_ignored(&self, rel_path: &str) -> bool {
+        self.ignore.is_match(rel_path)
+    }
+}
+
+/// Compile ignore patterns, case-insensitive like the macOS file system. `*`
+/// also matches `/`, so `*_old.pdf` applies in every folder. Returns the set
+/// of valid patterns and an error message per invalid one.
+pub fn ignore_set(patterns: &[String]) -> (GlobSet, Vec<String>) {
+    let mut builder = GlobSetBuilder::new();
+    let mut errors = Vec::new();
+    for pattern in patterns.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        match GlobBuilder::new(pattern).case_insensitive(true).build() {
+            Ok(glob) => {
+                builder.add(glob);
+            }
+            Err(e) => errors.push(format!("{pattern}: {}", e.kind())),
+        }
+    }
+    (builder.build().unwrap_or_default(), errors)
+}
+
+/// Whether an audio file is already the companion of a song file.
+fn is_companion_audio(conn: &Connection, rel_path: &str) -> bool {
+    conn.query_row(
+        "SELECT COUNT(*) FROM songs
+         WHERE audio_pfad = ?1 AND dateityp != 'audio' AND deleted_at IS NULL",
+        params![rel_path],
+        |row| row.get::<_, i64>(0),
+    )
+    .unwrap_or(0)
+        > 0
+}
+
+pub fn parse_filename(filename: &str) -> (String, Option<String>) {
+    let stem = match filename.rfind('.') {
+        Some(idx)
+            if SONG_EXTENSIONS
+                .iter()
+                .chain(AUDIO_EXTENSIONS)
+                .any(|ext| filename[idx + 1..].eq_ignore_ascii_case(ext)) =>
+        {
+            &filename[..idx]
+        }
+        _ => filename,
+    };
+    let name = stem.trim_end_matches(" Kopie").trim();
+
+    if let Some(idx) = name.find(" - ") {
+        let artist = name[..idx].trim().to_string();
+        let titel = name[idx + 3..].trim().to_string();
+        if !artist.is_empty() && !titel.is_empty() {
+            return (titel, Some(artist));
+        }
+    }
+    if let Some(idx) = name.find(" – ") {
+        let artist = name[..idx].trim().to_string();
+        let titel = name[idx + "\u{2013}".len() + 2..].trim().to_string();
+        if !artist.is_empty() && !titel.is_empty() {
+            return (titel, Some(artist));
+        }
+    }
+
+    (name.to_string(), None)
+}
+
+/// Recordings in the audio folder, as (lowercased file stem, path relative
+/// to the music folder), for matching new songs by title. Read once per scan.
+struct AudioDir(Vec<(String, String)>);
+
+impl AudioDir {
+    fn read(base_dir: &Path) -> AudioDir {
+        let audio_dir = base_dir.join("00 gitarre/0. Songs/2. Audios");
+        if !audio_dir.exists() {
+            return AudioDir(Vec::new());
+        }
+        let files = WalkDir::new(&audio_dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| is_audio_file(e.path()))
+            .filter_map(|e| {
+                let stem = nfc(e.path().file_stem()?.to_string_lossy()).to_lowercase();
+                let rel = nfc(e.path().strip_prefix(base_dir).ok()?.to_string_lossy());
+                Some((stem, rel))
+            })
+            .collect();
+        AudioDir(files)
+    }
+
+    fn find(&self, song_title: &str) -> Option<String> {
+        let title_lower = song_title.to_lowercase();
+        self.0
+            .iter()
+            .find(|(stem, _)| stem.contains(&title_lower))
+            .map(|(_, rel)| rel.clone())
+    }
+}
+
+/// A new song's row as far as it comes from the file. Title and artist come
+/// from the file name unless the file itself names them (ChordPro directives,
+/// Guitar Pro header, MusicXML work title, PDF Info dictionary, first line of
+/// a text sheet); ChordPro and MusicXML can also set key and capo, PDFs their
+/// page count. A standalone audio entry is its own audio track. Read before
+/// the insert, so a scan does it outside the database worker.
+struct NewSong {
+    filename: String,
+    file_type: FileType,
+    titel: String,
+    artist: Option<String>,
+    meta: SongMeta,
+    audio_match: Option<String>,
+}
+
+impl NewSong {
+    fn read(path: &Path, rel_path: &str, audio_dir: &AudioDir) -> NewSong {
+        let filename = nfc(path.file_name().unwrap_or_default().to_string_lossy());
+        let file_type = FileType::from_path(path).unwrap_or(FileType::Pdf);
+        let mut meta = read_metadata(path, file_type);
+        let (mut titel, mut artist) = parse_filename(&filename);
+        if let Some(title) = meta.title.take() {
+            titel = title;
+        }
+        if meta.artist.is_some() {
+            artist = meta.artist.take();
+        }
+        let audio_match = if file_type == FileType::Audio {
+            Some(rel_path.to_string())
+        } else {
+            audio_dir.find(&titel)
+        };
+        NewSong {
+            filename,
+            file_type,
+            titel,
+            artist,
+            meta,
+            audio_match,
+        }
+    }
+}
+
+/// Insert a new song with auto-tags.
+fn insert_song(conn: &Connection, rel_path: &str, song: NewSong, options: &ScanOptions) {
+    let NewSong {
+        filename,
+        file_type,
+        titel,
+        artist,
+        meta,
+        audio_match,
+    } = song;
+    let has_audio = audio_match.is_some();
+
+    let inserted = conn.execute(
+        &format!(
+            "INSERT INTO songs (titel, artist, dateipfad, dateiname, has_audio, audio_pfad, tonart, kapo,
+                                dateityp, seiten, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, {NOW}, {NOW})"
+        ),
+        params![titel, artist, rel_path, filename, has_audio, audio_match, meta.key, meta.capo, file_type.as_str(), meta.pages],
+    );
+    if inserted.log_err().is_none() {
+        return;
+    }
+
+    let song_id: i64 = conn.last_insert_rowid();
+
+    let tags = infer_tags(rel_path, &options.auto_tags);
+    for (kategorie, wert) in tags {
+        let Ok(tag_id) = get_or_create_tag(conn, &kategorie, &wert) else {
+            continue;
+        };
+        conn.execute(
+            "INSERT OR IGNORE INTO song_tags (song_id, tag_id, auto_generated) VALUES (?1, ?2, 1)",
+            params![song_id, tag_id],
+        )
+        .log_err();
+    }
+    removal_log::apply_pending(conn, rel_path);
+}
+
+/// Index the whole music folder and report what changed.
+pub fn scan_directory(conn: &Connection, base_dir: &Path, options: &ScanOptions) -> ScanReport {
+    puffin::profile_scope!("Scan");
+    let before = library_state(conn);
+    let mut found_paths: HashSet<String> = HashSet::new();
+    // Audio entries come after all song files, which may claim them as companions.
+    let mut audio_files = Vec::new();
+    // Files that belong to another song are not songs of their own.
+    let companion_files = companion_paths(conn);
+    // One transaction for the whole scan; committing per insert dominates on
+    // large libraries.
+    conn.execute_batch("BEGIN").log_err();
+
+    let audio_dir = AudioDir::read(base_dir);
+
+    for (path, rel_path, audio_entry) in scan_candidates(base_dir, options, &companion_files) {
+        if audio_entry {
+            audio_files.push((path, rel_path));
+            continue;
+        }
+        let new_song = || NewSong::read(&path, &rel_path, &audio_dir);
+        index_found(conn, &rel_path, false, new_song, options);
+        found_paths.insert(rel_path);
+    }
+
+    for (path, rel_path) in audio_files {
+        let new_song = || NewSong::read(&path, &rel_path, &audio_dir);
+        if index_found(conn, &rel_path, true, new_song, options).is_some() {
+            found_paths.insert(rel_path);
+        }
+    }
+
+    finish_scan(conn, base_dir, &found_paths);
+    conn.execute_batch("COMMIT").log_err();
+    ScanReport::between(&before, &library_state(conn))
+}
+
+/// A song in the list before or after a scan.
+struct IndexedSong {
+    dateipfad: String,
+    /// Has a backing track; audio entries of their own do not count.
+    audio: bool,
+}
+
+fn library_state(conn: &Connection) -> HashMap<i64, IndexedSong> {
+    query_rows(
+        conn,
+        "SELECT id, dateipfad, has_audio AND COALESCE(dateityp, 'pdf') != 'audio'
+         FROM songs WHERE deleted_at IS NULL",
+        [],
+        |row| {
+            Ok((
+                row.get(0)?,
+                IndexedSong {
+                    dateipfad: row.get(1)?,
+                    audio: row.get(2)?,
+                },
+            ))
+        },
+    )
+    .into_iter()
+    .collect()
+}
+
+/// A song in a [`ScanReport`].
+#[derive(Debug, Clone)]
+pub struct ReportedSong {
+    pub id: i64,
+    pub dateipfad: String,
+}
+
+/// A removed song and a new one with the same file name, most likely the
+/// same file in another folder.
+#[derive(Debug, Clone)]
+pub struct MovedSong {
+    /// The old entry, now in the Papierkorb with its tags and notes.
+    pub from: ReportedSong,
+    pub to: ReportedSong,
+}
+
+/// What a scan changed, sorted by path.
+#[derive(Debug, Clone, Default)]
+pub struct ScanReport {
+    /// Songs in the list before the scan.
+    pub known: usize,
+    pub added: Vec<ReportedSong>,
+    /// Moved to the Papierkorb.
+    pub removed: Vec<ReportedSong>,
+    /// Pairs taken out of `added` and `removed`.
+    pub moved: Vec<MovedSong>,
+    /// Songs that have a backing track now and did not have one before, new
+    /// songs included.
+    pub audio: Vec<ReportedSong>,
+}
+
+impl ScanReport {
+    fn between(
+        before: &HashMap<i64, IndexedSong>,
+        after: &HashMap<i64, IndexedSong>,
+    ) -> ScanReport {
+        let song = |id: i64, song: &IndexedSong| ReportedSong {
+            id,
+            dateipfad: song.dateipfad.clone(),
+        };
+        let mut added: Vec<ReportedSong> = after
+            .iter()
+            .filter(|(id, _)| !before.contains_key(id))
+            .map(|(id, s)| song(*id, s))
+            .collect();
+        let mut removed: Vec<ReportedSong> = before
+            .iter()
+            .filter(|(id, _)| !after.contains_key(id))
+            .map(|(id, s)| song(*id, s))
+            .collect();
+        let mut audio: Vec<ReportedSong> = after
+            .iter()
+            .filter(|(id, s)| s.audio && !before.get(id).is_some_and(|b| b.audio))
+            .map(|(id, s)| song(*id, s))
+            .collect();
+
+        let mut moved: Vec<MovedSong> = pair_moves(
+            removed.iter().map(|s| s.dateipfad.as_str()),
+            added.iter().map(|s| s.dateipfad.as_str()),
+        )
+        .into_iter()
+        .map(|(from, to)| MovedSong {
+            from: removed[from].clone(),
+            to: added[to].clone(),
+        })
+        .collect();
+        let moved_ids: HashSet<i64> = moved.iter().flat_map(|m| [m.from.id, m.to.id]).collect();
+        removed.retain(|s| !moved_ids.contains(&s.id));
+        added.retain(|s| !moved_ids.contains(&s.id));
+
+        added.sort_by(|a, b| a.dateipfad.cmp(&b.dateipfad));
+        removed.sort_by(|a, b| a.dateipfad.cmp(&b.dateipfad));
+        moved.sort_by(|a, b| a.to.dateipfad.cmp(&b.to.dateipfad));
+        audio.sort_by(|a, b| a.dateipfad.cmp(&b.dateipfad));
+        ScanReport {
+            known: before.len(),
+            added,
+            removed,
+            moved,
+            audio,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.moved.is_empty()
+            && self.audio.is_empty()
+    }
+}
+
+/// Song files and, with audio entries on, audio files under `base_dir`, as
+/// `(path, rel_path, audio_entry)`. Ignored paths and companion files are
+/// left out.
+fn scan_candidates<'a>(
+    base_dir: &'a Path,
+    options: &'a ScanOptions,
+    companion_files: &'a HashSet<String>,
+) -> impl Iterator<Item = (PathBuf, String, bool)> + 'a {
+    WalkDir::new(base_dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(move |entry| {
+            let path = entry.path();
+            if path.starts_with(base_dir.join("songindex")) {
+                return None;
+            }
+            if path
+                .components()
+                .any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+            {
+                return None;
+            }
+
+            let audio_entry = options.audio_entries && is_audio_file(path);
+            if !is_song_file(path) && !audio_entry {
+                return None;
+            }
+
+            let rel_path = nfc(path.strip_prefix(base_dir).ok()?.to_string_lossy());
+            // Skipped paths count as gone, so matching songs move to the
+            // Papierkorb and come back if the pattern is removed.
+            if options.is_ignored(&rel_path) || companion_files.contains(&rel_path) {
+                return None;
+            }
+            Some((path.to_path_buf(), rel_path, audio_entry))
+        })
+}
+
+/// Bring back or insert the song for a file a scan found; `new_song` gives
+/// the row of a file the library does not know. `Some(true)` if it is new,
+/// `None` for an audio file that turned out to be a song's backing track.
+fn index_found(
+    conn: &Connection,
+    rel_path: &str,
+    audio_entry: bool,
+    new_song: impl FnOnce() -> NewSong,
+    options: &ScanOptions,
+) -> Option<bool> {
+    if audio_entry && is_companion_audio(conn, rel_path) {
+        return None;
+    }
+    if restore_if_known(conn, rel_path) {
+        return Some(false);
+    }
+    insert_song(conn, rel_path, new_song(), options);
+    Some(true)
+}
+
+/// The end of a scan that saw `found_paths`: songs whose file was not among
+/// them go to the Papierkorb, then companions, pieces and unused tags are
+/// tidied up.
+fn finish_scan(conn: &Connection, base_dir: &Path, found_paths: &HashSet<String>) {
+    // If this fails nothing is moved to the Papierkorb, which is the safe side.
+    let db_songs: Vec<(i64, String)> = query_rows(
+        conn,
+        "SELECT id, dateipfad FROM songs WHERE deleted_at IS NULL",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    );
+
+    for (id, path) in db_songs {
+        if !found_paths.contains(&path) {
+            removal_log::record(conn, id);
+            conn.execute(
+                &format!("UPDATE songs SET deleted_at = {NOW} WHERE id = ?1"),
+                params![id],
+            )
+            .log_err();
+        }
+    }
+
+    companions::remove_missing(conn, base_dir);
+    arrangements::prune(conn);
+
+    conn.execute(
+        "DELETE FROM tags WHERE angeheftet = 0 AND id NOT IN (SELECT DISTINCT tag_id FROM song_tags)",
+        [],
+    )
+    .log_err();
+}
+
+/// A removed and an added path with the same file name (ignoring case) most
+/// likely name the same file in another folder. Only names that occur once on
+/// each side count. Returns pairs of indexes into `removed` and `added`.
+fn pair_moves<'a>(
+    removed: impl Iterator<Item = &'a str>,
+    added: impl Iterator<Item = &'a str>,
+) -> Vec<(usize, usize)> {
+    let file_name = |path: &str| path.rsplit('/').next().unwrap_or_default().to_lowercase();
+    let mut names: HashMap<String, (Vec<usize>, Vec<usize>)> = HashMap::new();
+    for (i, path) in removed.enumerate() {
+        names.entry(file_name(path)).or_default().0.push(i);
+    }
+    for (i, path) in added.enumerate() {
+        names.entry(file_name(path)).or_default().1.push(i);
+    }
+    names
+        .into_values()
+        .filter(|(from, to)| from.len() == 1 && to.len() == 1)
+        .map(|(from, to)| (from[0], to[0]))
+        .collect()
+}
+
+/// A song that [`preview_scan`] found in another folder.
+#[derive(Debug, Clone)]
+pub struct PreviewMove {
+    pub from: ReportedSong,
+    /// The path the file seems to have now.
+    pub to: String,
+    /// Tags the rules would give the new entry that the song does not have.
+    pub gained: Vec<Tag>,
+    /// Tags of the song, hand-set ones included, that the new entry would
+    /// not get. They stay with the old entry in the Papierkorb.
+    pub lost: Vec<Tag>,
+}
+
+/// What a scan would do, worked out without writing anything.
+#[derive(Debug, Clone, Default)]
+pub struct ScanPreview {
+    /// Songs in the list now.
+    pub known: usize,
+    /// Files that would become new songs, with the tags the rules give them.
+    pub added: Vec<(String, Vec<Tag>)>,
+    /// Songs in the Papierkorb whose file is there again.
+    pub restored: Vec<ReportedSong>,
+    /// Songs that would go to the Papierkorb.
+    pub removed: Vec<ReportedSong>,
+    /// Pairs taken out of `added` and `removed`, see [`pair_moves`].
+    pub moved: Vec<PreviewMove>,
+}
+
+impl ScanPreview {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.restored.is_empty()
+            && self.removed.is_empty()
+            && self.moved.is_empty()
+    }
+}
+
+/// What [`scan_directory`] would do if `base_dir` were the music folder, e.g.
+/// a reorganized copy of it. Walks the folder outside the database worker
+/// and only reads from the library.
+pub fn preview_scan(db: &Db, base_dir: &Path, options: &ScanOptions) -> ScanPreview {
+    let Some(companion_files) = db.call(|conn| companion_paths(conn)) else {
+        return ScanPreview::default();
+    };
+    let candidates: Vec<(String, bool)> = scan_candidates(base_dir, options, &companion_files)
+        .map(|(_, rel_path, audio_entry)| (rel_path, audio_entry))
+        .collect();
+    let rules = options.auto_tags.clone();
+    db.call(move |conn| {
+        let before = library_state(conn);
+        let active: HashMap<&str, i64> = before
+            .iter()
+            .map(|(id, s)| (s.dateipfad.as_str(), *id))
+            .collect();
+        let trashed: HashMap<String, i64> = query_rows(
+            conn,
+            "SELECT dateipfad, id FROM songs WHERE deleted_at IS NOT NULL",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .into_iter()
+        .collect();
+
+        let mut found = HashSet::new();
+        let mut added = Vec::new();
+        let mut restored = Vec::new();
+        for (rel_path, audio_entry) in candidates {
+            if audio_entry && is_companion_audio(conn, &rel_path) {
+                continue;
+            }
+            if let Some(id) = active.get(rel_path.as_str()) {
+                found.insert(*id);
+            } else if let Some(id) = trashed.get(&rel_path) {
+                restored.push(ReportedSong {
+                    id: *id,
+                    dateipfad: rel_path,
+                });
+            } else {
+                let tags = infer_tags(&rel_path, &rules);
+                added.push((rel_path, tags));
+            }
+        }
+        let mut removed: Vec<ReportedSong> = before
+            .iter()
+            .filter(|(id, _)| !found.contains(*id))
+            .map(|(id, s)| ReportedSong {
+                id: *id,
+                dateipfad: s.dateipfad.clone(),
+            })
+            .collect();
+
+        let pairs = pair_moves(
+            removed.iter().map(|s| s.dateipfad.as_str()),
+            added.iter().map(|(path, _)| path.as_str()),
+        );
+        let mut moved: Vec<PreviewMove> = pairs
+            .iter()
+            .map(|&(from, to)| {
+                let from = removed[from].clone();
+                let (to, inferred) = added[to].clone();
+                let current: Vec<Tag> = get_song_tags(conn, from.id)
+                    .into_iter()
+                    .map(|t| (t.kategorie, t.wert))
+                    .collect();
+                PreviewMove {
+                    gained: inferred
+                        .iter()
+                        .filter(|t| !current.contains(t))
+                        .cloned()
+                        .collect(),
+                    lost: current
+                        .iter()
+                        .filter(|t| !inferred.contains(t))
+                        .cloned()
+                        .collect(),
+                    from,
+                    to,
+                }
+            })
+            .collect();
+        let (moved_from, moved_to): (HashSet<usize>, HashSet<usize>) = pairs.into_iter().unzip();
+        let mut i = 0..;
+        removed.retain(|_| !moved_from.contains(&i.next().unwrap_or_default()));
+        let mut i = 0..;
+        added.retain(|_| !moved_to.contains(&i.next().unwrap_or_default()));
+
+        added.sort();
+        restored.sort_by(|a, b| a.dateipfad.cmp(&b.dateipfad));
+        removed.sort_by(|a, b| a.dateipfad.cmp(&b.dateipfad));
+        moved.sort_by(|a, b| a.to.cmp(&b.to));
+        ScanPreview {
+            known: before.len(),
+            added,
+            restored,
+            removed,
+            moved,
+        }
+    })
+    .unwrap_or_default()
+}
+
+/// Files indexed per database request in [`scan_in_batches`]; between two
+/// batches the window and the watcher get their turn.
+const SCAN_BATCH: usize = 200;
+
+/// Scans in the background as the window sees them: how far the running one
+/// is, and what the last one changed.
+#[derive(Debug, Clone, Default)]
+pub struct ScanProgress {
+    /// Song and audio files looked at so far.
+    pub files: usize,
+    /// Songs that were not in the library before.
+    pub added: usize,
+    /// A scan is running; another one is not started meanwhile.
+    pub running: bool,
+    /// Report of the last scan, until the window takes it.
+    pub report: Option<ScanReport>,
+}
+
+pub type ScanStatus = Arc<Mutex<ScanProgress>>;
+
+/// [`scan_in_batches`] on a background thread, so the window can open at
+/// once and stays responsive. Does nothing if a scan is already running.
+pub fn start_scan(
+    db: Db,
+    base_dir: PathBuf,
+    options: ScanOptions,
+    status: ScanStatus,
+    notify_tx: std::sync::mpsc::Sender<Changed>,
+    then: impl FnOnce(&mut Connection) + Send + 'static,
+) {
+    if !begin_scan(&status) {
+        return;
+    }
+    std::thread::spawn(move || {
+        scan_in_batches(&db, &base_dir, &options, &status, &notify_tx, then);
+    });
+}
+
+/// Mark a scan as running, unless one already is.
+fn begin_scan(status: &ScanStatus) -> bool {
+    let mut progress = lock(status);
+    if progress.running {
+        return false;
+    }
+    *progress = ScanProgress {
+        running: true,
+        ..ScanProgress::default()
+    };
+    true
+}
+
+/// [`scan_directory`] without occupying the database worker for the whole
+/// scan, after [`begin_scan`]. The folder is walked outside the worker and the
+/// files are indexed in batches, each its own transaction followed by a
+/// signal on `notify_tx`, so the list fills while the scan runs. Songs are
+/// only moved to the Papierkorb at the end, once the whole folder was seen;
+/// `then` runs on the worker right after that, and the report goes to
+/// `status`.
+fn scan_in_batches(
+    db: &Db,
+    base_dir: &Path,
+    options: &ScanOptions,
+    status: &ScanStatus,
+    notify_tx: &std::sync::mpsc::Sender<Changed>,
+    then: impl FnOnce(&mut Connection) + Send + 'static,
+) {
+    puffin::profile_scope!("Scan");
+    let base_dir = Arc::new(base_dir.to_path_buf());
+    let options = Arc::new(options.clone());
+    let Some((before, companion_files, known)) = db.call(|conn| {
+        (
+            library_state(conn),
+            companion_paths(conn),
+            known_paths(conn),
+        )
+    }) else {
+        return abort_scan(status);
+    };
+    let mut audio_dir = None;
+    let mut found_paths = HashSet::new();
+    let mut audio_files = Vec::new();
+    let mut batch = Vec::new();
+    // False once the worker lost a batch.
+    let mut index = |batch: Vec<(PathBuf, String, bool)>| {
+        // Files the library does not know are read here, so the worker only
+        // writes their rows.
+        let batch: Vec<_> = batch
+            .into_iter()
+            .map(|(path, rel_path, audio_entry)| {
+                let new = (!known.contains(&rel_path)).then(|| {
+                    let audio_dir = audio_dir.get_or_insert_with(|| AudioDir::read(&base_dir));
+                    NewSong::read(&path, &rel_path, audio_dir)
+                });
+                (path, rel_path, audio_entry, new)
+            })
+            .collect();
+        let (base_dir, options) = (base_dir.clone(), options.clone());
+        let Some((found, added)) = db.call(move |conn| {
+            conn.execute_batch("BEGIN").log_err();
+            let mut found = Vec::new();
+            let mut added = 0;
+            for (path, rel_path, audio_entry, new) in batch {
+                // Known when the scan started but gone since: read it here.
+                let new_song = || {
+                    new.unwrap_or_else(|| {
+                        NewSong::read(&path, &rel_path, &AudioDir::read(&base_dir))
+                    })
+                };
+                if let Some(new) = index_found(conn, &rel_path, audio_entry, new_song, &options) {
+                    added += usize::from(new);
+                    found.push(rel_path);
+                }
+            }
+            conn.execute_batch("COMMIT").log_err();
+            (found, added)
+        }) else {
+            return false;
+        };
+        lock(status).added += added;
+        found_paths.extend(found);
+        let _ = notify_tx.send(Changed::Files(Vec::new()));
+        true
+    };
+
+    for candidate in scan_candidates(&base_dir, &options, &companion_files) {
+        lock(status).files += 1;
+        // Audio entries come after all song files, like in `scan_directory`.
+        if candidate.2 {
+            audio_files.push(candidate);
+            continue;
+        }
+        batch.push(candidate);
+        if batch.len() == SCAN_BATCH && !index(std::mem::take(&mut batch)) {
+            return abort_scan(status);
+        }
+    }
+    batch.extend(audio_files);
+    while !batch.is_empty() {
+        let rest = batch.split_off(batch.len().min(SCAN_BATCH));
+        if !index(std::mem::replace(&mut batch, rest)) {
+            return abort_scan(status);
+        }
+    }
+
+    let base = base_dir.to_path_buf();
+    let report = db.call(move |conn| {
+        conn.execute_batch("BEGIN").log_err();
+        finish_scan(conn, &base, &found_paths);
+        conn.execute_batch("COMMIT").log_err();
+        then(conn);
+        ScanReport::between(&before, &library_state(conn))
+    });
+    let mut progress = lock(status);
+    progress.running = false;
+    progress.report = report;
+    drop(progress);
+    let _ = notify_tx.send(Changed::Library);
+}
+
+/// End a scan whose request the worker lost (it logged why). Nothing is moved
+/// to the Papierkorb, since the folder was not seen in full.
+fn abort_scan(status: &ScanStatus) {
+    log::warn!("Scan aborted");
+    lock(status).running = false;
+}
+
+/// The folders with the most songs in the library, see [`largest_folders_of`].
+pub fn largest_folders(conn: &Connection, limit: usize) -> Vec<(String, usize)> {
+    let rows = query_rows(
+        conn,
+        "SELECT dateipfad FROM songs WHERE deleted_at IS NULL",
+        [],
+        |row| row.get::<_, String>(0),
+    );
+    largest_folders_of(rows.iter().map(String::as_str), limit)
+}
+
+/// The folders with the most of `paths` in them, as (folder, count), largest
+/// first. Only the top two folder levels are counted, which is where large
+/// archives usually split up; a folder is left out if one of its subfolders
+/// holds all of its songs.
+pub fn largest_folders_of<'a>(
+    paths: impl IntoIterator<Item = &'a str>,
+    limit: usize,
+) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for path in paths {
+        let folders: Vec<&str> = path.split('/').collect();
+        for depth in 1..folders.len().min(3) {
+            *counts.entry(folders[..depth].join("/")).or_default() += 1;
+        }
+    }
+    let mut folders: Vec<(String, usize)> = counts
+        .iter()
+        .filter(|(folder, count)| {
+            !counts.iter().any(|(other, other_count)| {
+                other_count == *count
+                    && other
+                        .strip_prefix(folder.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'))
+            })
+        })
+        .map(|(folder, count)| (folder.clone(), *count))
+        .collect();
+    folders.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    folders.truncate(limit);
+    folders
+}
+
+/// Index a single file if it is a song file inside `base_dir` and not yet known.
+pub fn add_single_file(conn: &Connection, base_dir: &Path, file_path: &Path, options: &ScanOptions) {
+    let audio_entry = options.audio_entries && is_audio_file(file_path);
+    if !is_song_file(file_path) && !audio_entry {
+        return;
+    }
+
+    if file_path.starts_with(base_dir.join("songindex")) {
+        return;
+    }
+
+    let rel_path = match file_path.strip_prefix(base_dir) {
+        Ok(r) => nfc(r.to_string_lossy()),
+        Err(_) => return,
+    };
+
+    if options.is_ignored(&rel_path) {
+        return;
+    }
+
+    if is_companion(conn, &rel_path)
+        || audio_entry && is_companion_audio(conn, &rel_path)
+        || restore_if_known(conn, &rel_path)
+    {
+        return;
+    }
+
+    let song = NewSong::read(file_path, &rel_path, &AudioDir::read(base_dir));
+    insert_song(conn, &rel_path, song, options);
+    log::info!("Added: {rel_path}");
+}
+
+fn remove_single_file(conn: &Connection, base_dir: &Path, file_path: &Path) {
+    let rel_path = match file_path.strip_prefix(base_dir) {
+        Ok(r) => nfc(r.to_string_lossy()),
+        Err(_) => return,
+    };
+
+    if companions::remove_companion(conn, &rel_path).is_some() {
+        log::info!("Companion removed: {rel_path}");
+        return;
+    }
+    let Some(song_id) = conn
+        .query_row(
+            "SELECT id FROM songs WHERE dateipfad = ?1 AND deleted_at IS NULL",
+            params![rel_path],
+            |row| row.get::<_, i64>(0),
+        )
+        .ok()
+    else {
+        return;
+    };
+    removal_log::record(conn, song_id);
+    conn.execute(
+        &format!("UPDATE songs SET deleted_at = {NOW} WHERE id = ?1"),
+        params![song_id],
+    )
+    .log_err();
+    log::info!("Removed: {rel_path}");
+}
+
+/// True if the path is already indexed. A song in the Papierkorb whose file
+/// is back (e.g. a network drive was remounted) is restored with its tags.
+fn restore_if_known(conn: &Connection, rel_path: &str) -> bool {
+    let exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) FROM songs WHERE dateipfad = ?1",
+            params![rel_path],
+            |row| row.get::<_, i64>(0),
+        )
+        .unwrap_or(0)
+        > 0;
+    if exists {
+        let restored = conn
+            .execute(
+                "UPDATE songs SET deleted_at = NULL WHERE dateipfad = ?1 AND deleted_at IS NOT NULL",
+                params![rel_path],
+            )
+            .unwrap_or(0);
+        if restored > 0 {
+            removal_log::apply_pending(conn, rel_path);
+        }
+    }
+    exists
+}
+
+/// The background threads share the options and watcher status with the
+/// frontend; a panic while someone else held them must not stop the threads.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Quiet time after the last file event before a batch is processed.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+/// Upper bound for one batch, so a long copy still shows progress.
+const MAX_BATCH_WAIT: Duration = Duration::from_secs(5);
+
+/// Apply a batch of changed paths: each path is added or removed depending on
+/// whether it still exists, so a file created and deleted within one batch is a
+/// no-op. Song files go first so audio can be matched to its companion PDF.
+fn apply_watcher_batch(
+    conn: &Connection,
+    base_dir: &Path,
+    options: &ScanOptions,
+    batch: BTreeSet<std::path::PathBuf>,
+) -> Vec<String> {
+    puffin::profile_scope!("Watcher");
+    let (audio, songs): (Vec<_>, Vec<_>) = batch.into_iter().partition(|p| is_audio_file(p));
+    let mut changed = Vec::new();
+    conn.execute_batch("BEGIN").log_err();
+    for path in songs.iter().chain(&audio) {
+        if path.exists() {
+            add_single_file(conn, base_dir, path, options);
+            if let Ok(rel) = path.strip_prefix(base_dir) {
+                changed.push(nfc(rel.to_string_lossy()));
+            }
+        } else {
+            remove_single_file(conn, base_dir, path);
+        }
+    }
+    conn.execute_batch("COMMIT").log_err();
+    log::info!("Watcher: {} changes applied", songs.len() + audio.len());
+    changed
+}
+
+/// Sent to the window after the index changed, saying which files may need
+/// their background jobs (text, hash).
+pub enum Changed {
+    /// Files the watcher or a reconcile added or saw change, relative to the
+    /// music folder; none for a scan batch.
+    Files(Vec<String>),
+    /// A scan is done: any file may have changed.
+    Library,
+}
+
+/// How often the supervisor checks that the music folder is still there.
+const HEALTH_CHECK: Duration = Duration::from_secs(10);
+/// First and longest wait before re-creating a failed watcher.
+const RETRY_MIN: Duration = Duration::from_secs(2);
+const RETRY_MAX: Duration = Duration::from_secs(5 * 60);
+
+/// State of the file watcher, shown in the header while it is down.
+#[derive(Clone)]
+pub enum WatcherHealth {
+    Active,
+    /// Watching failed, e.g. because the volume was disconnected. The next
+    /// attempt to re-create the watcher is at `retry_at`.
+    Down {
+        error: String,
+        attempts: u32,
+        retry_at: Instant,
+    },
+}
+
+pub type WatcherStatus = Arc<Mutex<WatcherHealth>>;
+
+/// Watch `base_dir` recursively, sending song and audio paths to `tx` and
+/// watcher errors to `error_tx`.
+fn create_watcher(
+    base_dir: &Path,
+    options: Arc<RwLock<ScanOptions>>,
+    tx: std::sync::mpsc::Sender<std::path::PathBuf>,
+    error_tx: std::sync::mpsc::Sender<String>,
+) -> notify::Result<RecommendedWatcher> {
+    let base_dir_notify = base_dir.to_path_buf();
+    let mut watcher: RecommendedWatcher =
+        notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
+            let event = match res {
+                Ok(event) => event,
+                Err(e) => {
+                    let _ = error_tx.send(e.to_string());
+                    return;
+                }
+            };
+            match event.kind {
+                EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) => {
+                    for path in event.paths {
+                        if path.starts_with(base_dir_notify.join("songindex")) {
+                            continue;
+                        }
+                        let options = read(&options);
+                        let ignored = path.strip_prefix(&base_dir_notify).is_ok_and(|r| {
+                            options.is_ignored(&nfc(r.to_string_lossy()))
+                        });
+                        let audio_entry = is_audio_file(&path) && options.audio_entries;
+                        drop(options);
+                        if (is_song_file(&path) || audio_entry) && !ignored {
+                            let _ = tx.send(path);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        })?;
+    watcher.watch(base_dir, RecursiveMode::Recursive)?;
+    Ok(watcher)
+}
+
+/// Start the file watcher on background threads and return its status.
+/// Events are collected until the folder is quiet for [`DEBOUNCE`], then applied in one
+/// transaction; `notify_tx` gets one signal per batch so the UI refreshes once.
+///
+/// A supervisor thread owns the watcher. When it reports an error or the folder
+/// disappears, the watcher is dropped and re-created with exponential backoff
+/// ([`RETRY_MIN`] doubling up to [`RETRY_MAX`]); once it is back, the library is
+/// reconciled with the disk to pick up what changed in between.
+pub fn start_watcher(
+    db: Db,
+    base_dir: std::path::PathBuf,
+    options: Arc<RwLock<ScanOptions>>,
+    notify_tx: std::sync::mpsc::Sender<Changed>,
+) -> WatcherStatus {
+    let (tx, rx) = std::sync::mpsc::channel::<std::path::PathBuf>();
+
+    let db_thread = db.clone();
+    let base_dir_thread = base_dir.clone();
+    let options_thread = options.clone();
+    let notify_tx_thread = notify_tx.clone();
+    std::thread::spawn(move || {
+        while let Ok(first) = rx.recv() {
+            let mut batch = BTreeSet::from([first]);
+            let started = Instant::now();
+            while started.elapsed() < MAX_BATCH_WAIT {
+                match rx.recv_timeout(DEBOUNCE) {
+                    Ok(path) => {
+                        batch.insert(path);
+                    }
+                    Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => break,
+                }
+            }
+            let base_dir = base_dir_thread.clone();
+            let options = read(&options_thread).clone();
+            let changed = db_thread
+                .call(move |conn| apply_watcher_batch(conn, &base_dir, &options, batch))
+                .unwrap_or_default();
+            let _ = notify_tx_thread.send(Changed::Files(changed));
+        }
+    });
+
+    let status = Arc::new(Mutex::new(WatcherHealth::Active));
+    let status_thread = status.clone();
+    std::thread::spawn(move || {
+        let (error_tx, error_rx) = std::sync::mpsc::channel::<String>();
+        let mut watcher: Option<RecommendedWatcher> = None;
+        let mut attempts = 0u32;
+        loop {
+            let error = if watcher.is_some() {
+                match error_rx.recv_timeout(HEALTH_CHECK) {
+                    Ok(error) => Some(error),
+                    Err(_) if !base_dir.is_dir() => {
+                        Some(format!("{} ist nicht erreichbar", base_dir.display()))
+                    }
+                    Err(_) => {
+                        // Healthy for a whole check, so the next failure starts
+                        // the backoff from the beginning.
+                        attempts = 0;
+                        None
+                    }
+                }
+            } else {
+                match create_watcher(&base_dir, options.clone(), tx.clone(), error_tx.clone()) {
+                    Ok(new) => {
+                        watcher = Some(new);
+                        // Drop errors the old watcher sent before it was dropped.
+                        while error_rx.try_recv().is_ok() {}
+                        let was_down = attempts > 0;
+                        *lock(&status_thread) = WatcherHealth::Active;
+                        if was_down {
+                            log::info!("Watcher: watching again, reconciling");
+                            let result = reconcile(&db, &base_dir, &options);
+                            if result.added + result.removed > 0 {
+                                let _ = notify_tx.send(Changed::Files(result.changed));
+                            }
+                        }
+                        None
+                    }
+                    Err(e) => Some(e.to_string()),
+                }
+            };
+
+            let Some(error) = error else {
+                continue;
+            };
+            watcher = None;
+            attempts += 1;
+            let delay = RETRY_MIN
+                .saturating_mul(1 << (attempts - 1).min(16))
+                .min(RETRY_MAX);
+            log::warn!("Watcher: {error}; retry {attempts} in {}s", delay.as_secs());
+            *lock(&status_thread) = WatcherHealth::Down {
+                error,
+                attempts,
+                retry_at: Instant::now() + delay,
+            };
+            std::thread::sleep(delay);
+        }
+    });
+
+    status
+}
+
+/// Background thread for the scheduled full rescan (`Config::rescan`). The
+/// startup scan counts as the first run; the schedule is re-read every round
+/// so changes in Einstellungen apply without a restart. Wall-clock time is
+/// used because it keeps counting while the machine sleeps. Scans run in
+/// batches like the one at startup; each report goes to `status` for the
+/// window.
+pub fn start_rescan_scheduler(
+    db: Db,
+    options: Arc<RwLock<ScanOptions>>,
+    status: ScanStatus,
+    notify_tx: std::sync::mpsc::Sender<Changed>,
+) {
+    std::thread::spawn(move || {
+        let today = |conn: &mut Connection| -> (String, u32) {
+            conn.query_row(
+                "SELECT date('now', 'localtime'),
+                        CAST(strftime('%H', 'now', 'localtime') AS INTEGER)",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap_or_default()
+        };
+        let mut last_scan = SystemTime::now();
+        let mut last_date = db.call(today).unwrap_or_default().0;
+        loop {
+            std::thread::sleep(Duration::from_secs(5 * 60));
+
+            let Some(config) = load_config() else {
+                continue;
+            };
+            let Some((date, hour)) = db.call(today) else {
+                continue;
+            };
+            let due = match config.rescan {
+                RescanSchedule::Aus => false,
+                RescanSchedule::Intervall { stunden } => last_scan
+                    .elapsed()
+                    .is_ok_and(|e| e.as_secs() >= u64::from(stunden.max(1)) * 3600),
+                RescanSchedule::Nachts { stunde } => date != last_date && hour >= stunde,
+            };
+            if !due {
+                continue;
+            }
+
+            // A scan started from the window counts as this run.
+            if begin_scan(&status) {
+                log::info!("Scheduled rescan");
+                let options = read(&options).clone();
+                scan_in_batches(&db, &config.music_dir, &options, &status, &notify_tx, |_| {});
+            }
+            last_scan = SystemTime::now();
+            last_date = date;
+        }
+    });
+}
+
+/// Songs that appeared or disappeared during [`reconcile`].
+#[derive(Default)]
+pub struct Reconciliation {
+    pub added: usize,
+    pub removed: usize,
+    /// Files it added or updated, for [`Changed::Files`].
+    pub changed: Vec<String>,
+}
+
+/// Paths of all songs, including those in the Papierkorb.
+fn known_paths(conn: &Connection) -> HashSet<String> {
+    query_rows(conn, "SELECT dateipfad FROM songs", [], |row| row.get(0))
+        .into_iter()
+        .collect()
+}
+
+fn active_paths(conn: &Connection) -> HashSet<String> {
+    query_rows(
+        conn,
+        "SELECT dateipfad FROM songs WHERE deleted_at IS NULL",
+        [],
+        |row| row.get(0),
+    )
+    .into_iter()
+    .collect()
+}
+
+/// Quick catch-up for watcher events that were lost: walks the folder without
+/// occupying the database worker, then applies only the differences like a
+/// watcher batch. Known files are not touched, so this is much cheaper than
+/// [`scan_directory`].
+pub fn reconcile(db: &Db, base_dir: &Path, options: &RwLock<ScanOptions>) -> Reconciliation {
+    let on_disk: HashSet<String> = {
+        let options = read(options);
+        WalkDir::new(base_dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| !e.path().starts_with(base_dir.join("songindex")))
+            .filter(|e| {
+                !e.path()
+                    .components()
+                    .any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+            })
+            .filter(|e| {
+                is_song_file(e.path()) || options.audio_entries && is_audio_file(e.path())
+            })
+            .filter_map(|e| {
+                e.path()
+                    .strip_prefix(base_dir)
+                    .ok()
+                    .map(|r| nfc(r.to_string_lossy()))
+            })
+            .filter(|rel_path| !options.is_ignored(rel_path))
+            .collect()
+    };
+
+    let Some((before, companion_files)) =
+        db.call(|conn| (active_paths(conn), companion_paths(conn)))
+    else {
+        return Reconciliation::default();
+    };
+    // Companion files are on disk but never songs: those in `song_dateien`
+    // are left out here, backing tracks are skipped by the batch.
+    let on_disk: HashSet<String> = on_disk.difference(&companion_files).cloned().collect();
+    let batch: BTreeSet<std::path::PathBuf> = on_disk
+        .symmetric_difference(&before)
+        .map(|rel_path| base_dir.join(rel_path))
+        .collect();
+    if batch.is_empty() {
+        return Reconciliation {
+            added: 0,
+            removed: 0,
+            changed: Vec::new(),
+        };
+    }
+    let base_dir = base_dir.to_path_buf();
+    let options = read(options).clone();
+    let Some((changed, after)) = db.call(move |conn| {
+        let changed = apply_watcher_batch(conn, &base_dir, &options, batch);
+        (changed, active_paths(conn))
+    }) else {
+        return Reconciliation::default();
+    };
+    Reconciliation {
+        added: after.difference(&before).count(),
+        removed: before.difference(&after).count(),
+        changed,
+    }
+}
+
+/// Background thread that notices when the machine slept: the wall clock
+/// jumps ahead of the thread's own sleep, which stops counting in sleep. Then
+/// FSEvents may have been dropped, so [`reconcile`] runs and any changes are
+/// reported on `report_tx` (and the list refreshed via `notify_tx`).
+pub fn start_wake_reconciler(
+    db: Db,
+    options: Arc<RwLock<ScanOptions>>,
+    notify_tx: std::sync::mpsc::Sender<Changed>,
+    report_tx: std::sync::mpsc::Sender<Reconciliation>,
+) {
+    const TICK: Duration = Duration::from_secs(15);
+    const WAKE_GAP: Duration = Duration::from_secs(90);
+    std::thread::spawn(move || {
+        let mut last = SystemTime::now();
+        loop {
+            std::thread::sleep(TICK);
+            let now = SystemTime::now();
+            let slept = now.duration_since(last).is_ok_and(|gap| gap >= WAKE_GAP);
+            last = now;
+            if !slept {
+                continue;
+            }
+            let Some(config) = load_config() else {
+                continue;
+            };
+            log::info!("Wake from sleep, reconciling");
+            let mut result = reconcile(&db, &config.music_dir, &options);
+            if result.added + result.removed > 0 {
+                let _ = notify_tx.send(Changed::Files(std::mem::take(&mut result.changed)));
+                let _ = report_tx.send(result);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{
+        get_all_tags, init_db, query_songs, ExtraFilters, MatchMode, SortMode, TagFilter,
+    };
+
+    const FOLDERS: usize = 500;
+    const PER_FOLDER: usize = 100;
+
+    /// The library the large-library numbers in CLAUDE.md were measured on:
+    /// 50 000 PDFs in 500 folders, every tenth folder under `Blues/`. Run with
+    /// `cargo test --release -p songindex-core -- --ignored` for timings.
+    #[test]
+    #[ignore = "writes 50 000 files"]
+    fn large_library() {
+        let dir = std::env::temp_dir().join(format!("songindex-large-test-{}", std::process::id()));
+        let folder = |d: usize| {
+            if d.is_multiple_of(10) {
+                format!("Blues/Artist {d}")
+            } else {
+                format!("Artist {d}")
+            }
+        };
+        for d in 0..FOLDERS {
+            let path = dir.join(folder(d));
+            std::fs::create_dir_all(&path).unwrap();
+            for i in 0..PER_FOLDER {
+                std::fs::write(
+                    path.join(format!("Artist {d} - Song {i}.pdf")),
+                    b"%PDF-1.4\n",
+                )
+                .unwrap();
+            }
+        }
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        let options = ScanOptions::from(&Config::default());
+        let list = |search: &str, tag_filter: &TagFilter| {
+            query_songs(
+                &conn,
+                search,
+                tag_filter,
+                &ExtraFilters::default(),
+                &SortMode::Title,
+            )
+        };
+
+        let start = Instant::now();
+        let report = scan_directory(&conn, &dir, &options);
+        eprintln!("first scan: {:?}", start.elapsed());
+        assert_eq!(report.known, 0);
+        assert_eq!(report.added.len(), FOLDERS * PER_FOLDER);
+
+        let start = Instant::now();
+        let report = scan_directory(&conn, &dir, &options);
+        eprintln!("rescan: {:?}", start.elapsed());
+        assert_eq!(report.known, FOLDERS * PER_FOLDER);
+        assert!(report.is_empty());
+
+        let start = Instant::now();
+        let songs = list("", &TagFilter::none());
+        eprintln!("list query: {:?}", start.elapsed());
+        assert_eq!(songs.len(), FOLDERS * PER_FOLDER);
+        assert_eq!(
+            list("artist:\"Artist 499\"", &TagFilter::none()).len(),
+            PER_FOLDER
+        );
+        let blues = get_all_tags(&conn)
+            .into_iter()
+            .find(|g| g.kategorie == "stil")
+            .and_then(|g| g.tags.into_iter().find(|t| t.wert == "Blues"))
+            .unwrap();
+        assert_eq!(blues.count as usize, FOLDERS / 10 * PER_FOLDER);
+        let by_tag = TagFilter {
+            groups: vec![(vec![blues.id], MatchMode::Any)],
+            mode: MatchMode::All,
+        };
+        assert_eq!(list("", &by_tag).len(), FOLDERS / 10 * PER_FOLDER);
+
+        // One folder deleted, one renamed.
+        std::fs::remove_dir_all(dir.join(folder(1))).unwrap();
+        std::fs::rename(dir.join(folder(2)), dir.join("Umbenannt")).unwrap();
+        let report = scan_directory(&conn, &dir, &options);
+        assert_eq!(report.removed.len(), PER_FOLDER);
+        assert_eq!(report.moved.len(), PER_FOLDER);
+        assert!(report.added.is_empty());
+        assert_eq!(
+            list("", &TagFilter::none()).len(),
+            (FOLDERS - 1) * PER_FOLDER
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// The scan the window runs: song files in several batches on the
+    /// database worker, the Papierkorb only once the whole folder was seen.
+    #[test]
+    fn batched_scan() {
+        const ARTISTS: usize = 3;
+        const SONGS: usize = SCAN_BATCH * 2 + 50;
+        let dir = std::env::temp_dir().join(format!("songindex-batch-test-{}", std::process::id()));
+        let folder = |i: usize| dir.join(format!("Artist {}", i % ARTISTS));
+        for i in 0..SONGS {
+            std::fs::create_dir_all(folder(i)).unwrap();
+            std::fs::write(
+                folder(i).join(format!("Artist {} - Song {i}.pdf", i % ARTISTS)),
+                b"%PDF-1.4\n",
+            )
+            .unwrap();
+        }
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        let db = Db::spawn(conn);
+        let options = ScanOptions::from(&Config::default());
+        let status = ScanStatus::default();
+        let (notify_tx, notify_rx) = std::sync::mpsc::channel();
+        let scan = || {
+            assert!(begin_scan(&status));
+            scan_in_batches(&db, &dir, &options, &status, &notify_tx, |_| {});
+            let mut progress = lock(&status);
+            assert!(!progress.running);
+            progress.report.take().unwrap()
+        };
+
+        let report = scan();
+        assert_eq!(report.known, 0);
+        assert_eq!(report.added.len(), SONGS);
+        assert_eq!(lock(&status).added, SONGS);
+        // One signal per batch, then one for the whole library.
+        let signals: Vec<Changed> = notify_rx.try_iter().collect();
+        assert_eq!(signals.len(), SONGS.div_ceil(SCAN_BATCH) + 1);
+        assert!(matches!(signals.last(), Some(Changed::Library)));
+
+        let report = scan();
+        assert_eq!(report.known, SONGS);
+        assert!(report.is_empty());
+
+        std::fs::remove_dir_all(folder(1)).unwrap();
+        std::fs::rename(folder(2), dir.join("Umbenannt")).unwrap();
+        let report = scan();
+        assert_eq!(report.removed.len(), SONGS / ARTISTS);
+        assert_eq!(report.moved.len(), SONGS / ARTISTS);
+        assert!(report.added.is_empty());
+        let count = db.call(|conn| {
+            query_songs(
+                conn,
+                "",
+                &TagFilter::none(),
+                &ExtraFilters::default(),
+                &SortMode::Title,
+            )
+            .len()
+        });
+        assert_eq!(count, Some(SONGS - SONGS / ARTISTS));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,135 @@
+/// Pitch classes with sharps and with flats; index 0 is C.
+const SHARP_NAMES: [&str; 12] = [
+    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+];
+const FLAT_NAMES: [&str; 12] = [
+    "C", "Db", "D", "Eb", "E", "F", "Gb", "G", "Ab", "A", "Bb", "B",
+];
+
+/// Keys a guitarist plays with open chord shapes, used for capo suggestions.
+const OPEN_MAJOR: [u8; 5] = [0, 2, 4, 7, 9]; // C D E G A
+const OPEN_MINOR: [u8; 3] = [2, 4, 9]; // Dm Em Am
+
+/// Highest capo position worth suggesting.
+pub const MAX_CAPO: i64 = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    /// Pitch class, 0 = C.
+    pub root: u8,
+    pub minor: bool,
+}
+
+impl Key {
+    /// Parse keys as written in charts and tags: `G`, `F#m`, `Bb`, `Es`, `Fis`,
+    /// `H`, `a-Moll`, `D-Dur`, `E minor`. A lone lowercase letter is minor,
+    /// as in German notation.
+    pub fn parse(input: &str) -> Option<Key> {
+        let input = input.trim();
+        let mut chars = input.chars();
+        let letter = chars.next()?;
+        let mut root: i32 = match letter.to_ascii_uppercase() {
+            'C' => 0,
+            'D' => 2,
+            'E' => 4,
+            'F' => 5,
+            'G' => 7,
+            'A' => 9,
+            'B' | 'H' => 11,
+            _ => return None,
+        };
+        let mut rest = chars.as_str();
+        let german_flat = if matches!(letter, 'A' | 'a' | 'E' | 'e') {
+            "s"
+        } else {
+            "es"
+        };
+        for (accidental, shift) in [
+            ("#", 1),
+            ("\u{266F}", 1),
+            ("b", -1),
+            ("\u{266D}", -1),
+            ("is", 1),
+            (german_flat, -1),
+        ] {
+            if let Some(r) = rest.strip_prefix(accidental) {
+                root += shift;
+                rest = r;
+                break;
+            }
+        }
+
+        let rest = rest.trim_start_matches(['-', ' ']).to_lowercase();
+        let minor = match rest.as_str() {
+            "" => letter.is_lowercase(),
+            "m" | "min" | "moll" | "minor" => true,
+            "dur" | "maj" | "major" => false,
+            _ => return None,
+        };
+        Some(Key {
+            root: root.rem_euclid(12) as u8,
+            minor,
+        })
+    }
+
+    pub fn transposed(self, semitones: i64) -> Key {
+        Key {
+            root: (self.root as i64 + semitones).rem_euclid(12) as u8,
+            minor: self.minor,
+        }
+    }
+
+    /// Semitones to move up from `self` to `other` (0–11).
+    pub fn distance_to(self, other: Key) -> i64 {
+        (other.root as i64 - self.root as i64).rem_euclid(12)
+    }
+
+    /// Conventional spelling: flats for F, Bb, Eb, …, Gb and their relative minors.
+    pub fn name(self) -> String {
+        let relative_major = if self.minor {
+            (self.root + 3) % 12
+        } else {
+            self.root
+        };
+        let names = if [5, 10, 3, 8, 1, 6].contains(&relative_major) {
+            &FLAT_NAMES
+        } else {
+            &SHARP_NAMES
+        };
+        let suffix = if self.minor { "m" } else { "" };
+        format!("{}{suffix}", names[self.root as usize])
+    }
+
+    /// All twelve keys of the same mode, starting at C.
+    pub fn all(minor: bool) -> impl Iterator<Item = Key> {
+        (0..12).map(move |root| Key { root, minor })
+    }
+}
+
+/// Key that sounds when the chart's `played` key is fingered with a capo.
+pub fn sounding_key(played: Key, kapo: i64) -> Key {
+    played.transposed(kapo)
+}
+
+/// Ways to reach `target` with open chord shapes: `(shapes, capo)`, lowest
+/// capo first.
+pub fn capo_suggestions(target: Key) -> Vec<(Key, i64)> {
+    let shapes: &[u8] = if target.minor {
+        &OPEN_MINOR
+    } else {
+        &OPEN_MAJOR
+    };
+    let mut suggestions: Vec<(Key, i64)> = shapes
+        .iter()
+        .map(|&root| {
+            let shape = Key {
+                root,
+                minor: target.minor,
+            };
+            (shape, shape.distance_to(target))
+        })
+        .filter(|(_, kapo)| *kapo <= MAX_CAPO)
+        .collect();
+    suggestions.sort_by_key(|(_, kapo)| *kapo);
+    suggestions
+}
@@ -0,0 +1,140 @@
+//! The database worker: one thread owns the connection and runs requests in
+//! the order they arrive. Everyone else — the window, the watcher, scans,
+//! background jobs — holds a [`Db`] handle and sends closures instead of
+//! locking a shared connection, so nobody holds the connection across file
+//! reads, and writes from the window return at once.
+//!
+//! Requests are plain closures with a typed answer: [`Db::send`] for writes
+//! nobody waits for, [`Db::request`] for an answer to poll later (the
+//! receiver stays empty until it is there, and [`Db::on_answer`] says when),
+//! [`Db::call`] to wait for it. The window never calls, since anything may
+//! queue behind a scan batch; threads of their own may. A request must not
+//! call back into its own [`Db`]: the worker would wait for itself.
+
+use rusqlite::Connection;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, OnceLock};
+
+type Request = Box<dyn FnOnce(&mut Connection) + Send>;
+type Wake = Box<dyn Fn() + Send + Sync>;
+
+/// Handle to the database worker; cheap to clone and to send to threads.
+#[derive(Clone)]
+pub struct Db {
+    tx: Sender<Request>,
+    wake: Arc<OnceLock<Wake>>,
+}
+
+impl Db {
+    /// Start the worker thread with `conn`. It runs until the last handle
+    /// is dropped.
+    pub fn spawn(mut conn: Connection) -> Db {
+        let (tx, rx) = channel::<Request>();
+        std::thread::spawn(move || {
+            for request in rx {
+                // A request that panics loses its answer (its caller's
+                // receiver disconnects), not the worker. A transaction it
+                // left open is rolled back, or every later write would end
+                // up in it and never be committed.
+                if catch_unwind(AssertUnwindSafe(|| request(&mut conn))).is_err() {
+                    log::error!("Database request failed");
+                    if !conn.is_autocommit() {
+                        if let Err(e) = conn.execute_batch("ROLLBACK") {
+                            log::error!("Rollback after failed request: {e}");
+                        }
+                    }
+                }
+            }
+        });
+        Db {
+            tx,
+            wake: Arc::default(),
+        }
+    }
+
+    /// Run `wake` after each answer of [`Db::request`] has arrived, e.g. to
+    /// repaint the window that polls for it. Can be set once.
+    pub fn on_answer(&self, wake: impl Fn() + Send + Sync + 'static) {
+        if self.wake.set(Box::new(wake)).is_err() {
+            log::warn!("Database answer hook already set");
+        }
+    }
+
+    /// Queue `f` without waiting for it, e.g. a write from the window; its
+    /// answer is dropped. Later requests see its changes.
+    pub fn send<T>(&self, f: impl FnOnce(&mut Connection) -> T + Send + 'static) {
+        // Only fails once the worker is gone, i.e. while shutting down.
+        self.tx
+            .send(Box::new(move |conn: &mut Connection| {
+                f(conn);
+            }))
+            .ok();
+    }
+
+    /// Queue `f`; its answer arrives on the returned receiver, followed by
+    /// the [`Db::on_answer`] hook.
+    pub fn request<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&mut Connection) -> T + Send + 'static,
+    ) -> Receiver<T> {
+        let (tx, rx) = channel();
+        let wake = self.wake.clone();
+        self.send(move |conn| {
+            tx.send(f(conn)).ok();
+            if let Some(wake) = wake.get() {
+                wake();
+            }
+        });
+        rx
+    }
+
+    /// Run `f` on the worker and wait for its answer; `None` if `f` panicked
+    /// (the worker logs it) or the worker is gone. Not for the window's
+    /// thread: it would freeze behind whatever is queued.
+    pub fn call<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&mut Connection) -> T + Send + 'static,
+    ) -> Option<T> {
+        let (tx, rx) = channel();
+        self.send(move |conn| {
+            tx.send(f(conn)).ok();
+        });
+        rx.recv().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn panic_rolls_back_open_transaction() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE t (x INTEGER)").unwrap();
+        let db = Db::spawn(conn);
+
+        let answer = db.call(|conn| {
+            conn.execute_batch("BEGIN; INSERT INTO t VALUES (1)")
+                .unwrap();
+            panic!("request fails inside its transaction");
+        });
+        assert_eq!(answer, None::<()>);
+
+        // A later transaction of its own starts and commits.
+        db.call(|conn| {
+            assert!(conn.is_autocommit());
+            conn.execute_batch("BEGIN; INSERT INTO t VALUES (2); COMMIT")
+                .unwrap();
+        })
+        .unwrap();
+        let rows: Vec<i64> = db
+            .call(|conn| {
+                let mut stmt = conn.prepare("SELECT x FROM t").unwrap();
+                let rows = stmt.query_map([], |r| r.get(0)).unwrap();
+                rows.map(Result::unwrap).collect()
+            })
+            .unwrap();
+        assert_eq!(rows, [2]);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::arrangements::share_from;
+use crate::autotag::infer_tags;
+use crate::config::AutoTagRule;
+use crate::db::{LogErr, NOW};
+use rusqlite::{params, Connection};
+use std::collections::BTreeMap;
+
+/// A folder needs at least this many PDFs to count as an artist folder.
+const MIN_PDFS: usize = 3;
+
+/// A folder that looks like it is named after an artist.
+pub struct ArtistSuggestion {
+    /// Folder path relative to the music folder.
+    pub ordner: String,
+    /// The folder name, suggested as artist.
+    pub artist: String,
+    /// Songs in the folder without an artist, as (id, titel).
+    pub songs: Vec<(i64, String)>,
+}
+
+/// Folders whose PDFs mostly lack the "Artist - Titel" separator, so the
+/// folder name is the best guess for their artist. Only songs directly in the
+/// folder count. Skipped are numbered folders ("0. Songs", "00 gitarre") and
+/// folders a non-artist auto-tag rule already knows, e.g. genre folders.
+pub fn suggest(conn: &Connection, rules: &[AutoTagRule]) -> Vec<ArtistSuggestion> {
+    struct Folder {
+        pdfs: usize,
+        without_separator: usize,
+        songs: Vec<(i64, String)>,
+    }
+
+    let mut folders: BTreeMap<String, Folder> = BTreeMap::new();
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, titel, artist, dateipfad, dateiname, dateityp FROM songs
+             WHERE deleted_at IS NULL AND ignoriert = 0
+             ORDER BY titel COLLATE NOCASE",
+        )
+        .unwrap();
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, Option<String>>(5)?,
+            ))
+        })
+        .unwrap()
+        .filter_map(|r| r.ok());
+    for (id, titel, artist, dateipfad, dateiname, dateityp) in rows {
+        let Some((ordner, _)) = dateipfad.rsplit_once('/') else {
+            continue;
+        };
+        let folder = folders.entry(ordner.to_string()).or_insert(Folder {
+            pdfs: 0,
+            without_separator: 0,
+            songs: Vec::new(),
+        });
+        if dateityp.as_deref() == Some("pdf") {
+            folder.pdfs += 1;
+            if !dateiname.contains(" - ") && !dateiname.contains(" \u{2013} ") {
+                folder.without_separator += 1;
+            }
+        }
+        if artist.is_none_or(|a| a.trim().is_empty()) {
+            folder.songs.push((id, titel));
+        }
+    }
+
+    let other_rules: Vec<AutoTagRule> = rules
+        .iter()
+        .filter(|r| r.kategorie.trim() != "artist")
+        .cloned()
+        .collect();
+    folders
+        .into_iter()
+        .filter_map(|(ordner, folder)| {
+            let name = ordner.rsplit('/').next().unwrap_or_default().trim();
+            if name.is_empty()
+                || name.starts_with(|c: char| c.is_ascii_digit())
+                || folder.songs.is_empty()
+                || folder.pdfs < MIN_PDFS
+                || folder.without_separator * 4 < folder.pdfs * 3
+                || !infer_tags(&format!("{name}/_"), &other_rules).is_empty()
+            {
+                return None;
+            }
+            Some(ArtistSuggestion {
+                artist: name.to_string(),
+                ordner,
+                songs: folder.songs,
+            })
+        })
+        .collect()
+}
+
+/// Set `artist` on the given songs in one transaction.
+pub fn apply(conn: &Connection, artist: &str, song_ids: &[i64]) {
+    conn.execute_batch("BEGIN").log_err();
+    for id in song_ids {
+        conn.execute(
+            &format!("UPDATE songs SET artist = ?1, updated_at = {NOW} WHERE id = ?2"),
+            params![artist, id],
+        )
+        .log_err();
+        share_from(conn, *id);
+    }
+    conn.execute_batch("COMMIT").log_err();
+}
@@ -0,0 +1,318 @@
+use crate::config::{data_dir, load_config, save_config, BackupConfig, BackupTarget, Config};
+use crate::i18n::{tr, tr_arg};
+use crate::keychain;
+use crate::worker::Db;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use hmac::{Hmac, Mac};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// File header of encrypted backups: magic, then 16 byte salt and 12 byte nonce.
+const MAGIC: &[u8] = b"SIDXBAK1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+/// Wait before the scheduler retries a failed backup; doubles with every
+/// failure in a row, up to the backup interval.
+const RETRY_AFTER: u64 = 15 * 60;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BackupState {
+    pub last_success: Option<u64>,
+    pub last_file: Option<String>,
+    pub last_error: Option<String>,
+    /// Last backup run, successful or not.
+    #[serde(default)]
+    pub last_attempt: Option<u64>,
+    /// Failed runs since the last success.
+    #[serde(default)]
+    pub failures: u32,
+}
+
+impl BackupState {
+    /// Whether the scheduler should back up now: the interval has passed
+    /// since the last success, and after failures the retry wait since the
+    /// last attempt, so a wrong password or a missing network does not
+    /// upload a snapshot every round.
+    fn is_due(&self, interval: u64, now: u64) -> bool {
+        let since = |time: Option<u64>| time.map(|t| now.saturating_sub(t));
+        let retry_after = RETRY_AFTER
+            .saturating_mul(1 << self.failures.saturating_sub(1).min(16))
+            .min(interval);
+        since(self.last_success).is_none_or(|s| s >= interval)
+            && (self.failures == 0 || since(self.last_attempt).is_none_or(|s| s >= retry_after))
+    }
+}
+
+fn state_path() -> PathBuf {
+    data_dir().join("backup_state.json")
+}
+
+pub fn load_state() -> BackupState {
+    std::fs::read_to_string(state_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &BackupState) {
+    if let Ok(json) = serde_json::to_string_pretty(state) {
+        if let Err(e) = std::fs::write(state_path(), json) {
+            log::warn!("Cannot save backup state: {e}");
+        }
+    }
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// UTC date and time for a unix timestamp as (YYYYMMDD, HHMMSS).
+pub(crate) fn utc_parts(secs: u64) -> (String, String) {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Civil-from-days (Howard Hinnant), valid for the proleptic Gregorian calendar.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+
+    (
+        format!("{y:04}{m:02}{d:02}"),
+        format!("{:02}{:02}{:02}", rem / 3600, (rem / 60) % 60, rem % 60),
+    )
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key, String> {
+    let mut key = [0u8; 32];
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| e.to_string())?;
+    Ok(Key::from(key))
+}
+
+pub fn encrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, data)
+        .map_err(|_| tr("Verschl\u{00FC}sselung fehlgeschlagen").to_string())?;
+
+    let mut out = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+pub fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    let header = MAGIC.len() + SALT_LEN + NONCE_LEN;
+    if data.len() < header || !data.starts_with(MAGIC) {
+        return Err(tr("Keine Songindex-Sicherung").to_string());
+    }
+    let salt = &data[MAGIC.len()..MAGIC.len() + SALT_LEN];
+    let nonce = Nonce::from_slice(&data[MAGIC.len() + SALT_LEN..header]);
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, salt)?);
+    cipher
+        .decrypt(nonce, &data[header..])
+        .map_err(|_| tr("Falsches Passwort oder besch\u{00E4}digte Datei").to_string())
+}
+
+/// Consistent copy of the database via `VACUUM INTO`, encrypted with the passphrase.
+pub fn create_encrypted_snapshot(conn: &Connection, passphrase: &str) -> Result<Vec<u8>, String> {
+    let tmp = data_dir().join("backup-tmp.db");
+    std::fs::remove_file(&tmp).ok();
+    conn.execute("VACUUM INTO ?1", params![tmp.to_string_lossy()])
+        .map_err(|e| e.to_string())?;
+    let data = std::fs::read(&tmp).map_err(|e| e.to_string());
+    std::fs::remove_file(&tmp).ok();
+    encrypt(&data?, passphrase)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn hmac_sha256(key: &[u8], msg: &str) -> Vec<u8> {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(msg.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn upload_webdav(
+    url: &str,
+    username: &str,
+    password: &str,
+    name: &str,
+    body: &[u8],
+) -> Result<(), String> {
+    let target = format!("{}/{}", url.trim_end_matches('/'), name);
+    let auth = base64::engine::general_purpose::STANDARD.encode(format!("{username}:{password}"));
+    ureq::put(&target)
+        .set("Authorization", &format!("Basic {auth}"))
+        .set("Content-Type", "application/octet-stream")
+        .send_bytes(body)
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Path-style PUT to an S3-compatible endpoint, signed with AWS Signature V4.
+fn upload_s3(
+    endpoint: &str,
+    bucket: &str,
+    region: &str,
+    access_key: &str,
+    secret_key: &str,
+    name: &str,
+    body: &[u8],
+) -> Result<(), String> {
+    let endpoint = endpoint.trim_end_matches('/');
+    let host = endpoint
+        .split_once("://")
+        .map(|(_, h)| h)
+        .unwrap_or(endpoint);
+    let (date, time) = utc_parts(unix_now());
+    let amz_date = format!("{date}T{time}Z");
+    let uri = format!("/{bucket}/{name}");
+    let payload_hash = hex(&Sha256::digest(body));
+
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "PUT\n{uri}\n\nhost:{host}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{amz_date}\n\n{signed_headers}\n{payload_hash}"
+    );
+    let scope = format!("{date}/{region}/s3/aws4_request");
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let k_date = hmac_sha256(format!("AWS4{secret_key}").as_bytes(), &date);
+    let k_region = hmac_sha256(&k_date, region);
+    let k_service = hmac_sha256(&k_region, "s3");
+    let k_signing = hmac_sha256(&k_service, "aws4_request");
+    let signature = hex(&hmac_sha256(&k_signing, &string_to_sign));
+
+    ureq::put(&format!("{endpoint}{uri}"))
+        .set("x-amz-date", &amz_date)
+        .set("x-amz-content-sha256", &payload_hash)
+        .set(
+            "Authorization",
+            &format!(
+                "AWS4-HMAC-SHA256 Credential={access_key}/{scope}, SignedHeaders={signed_headers}, Signature={signature}"
+            ),
+        )
+        .send_bytes(body)
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Create an encrypted snapshot and upload it to the configured target.
+/// Records the outcome in `backup_state.json` and returns the remote file name.
+pub fn run_backup(db: &Db, cfg: &BackupConfig) -> Result<String, String> {
+    let result = (|| {
+        let Some(passphrase) = keychain::get(&cfg.passphrase_ref) else {
+            return Err(tr("Kein Backup-Passwort gesetzt").to_string());
+        };
+        let secret = keychain::get(cfg.target.secret_ref()).unwrap_or_default();
+        let data = db
+            .call(move |conn| create_encrypted_snapshot(conn, &passphrase))
+            .ok_or(tr("Snapshot konnte nicht erstellt werden"))??;
+        let (date, time) = utc_parts(unix_now());
+        let name = format!("songindex-{date}-{time}.db.enc");
+        match &cfg.target {
+            BackupTarget::WebDav { url, username, .. } => {
+                upload_webdav(url, username, &secret, &name, &data)?
+            }
+            BackupTarget::S3 {
+                endpoint,
+                bucket,
+                region,
+                access_key,
+                ..
+            } => upload_s3(endpoint, bucket, region, access_key, &secret, &name, &data)?,
+        }
+        Ok(name)
+    })();
+
+    let mut state = load_state();
+    state.last_attempt = Some(unix_now());
+    match &result {
+        Ok(name) => {
+            state.last_success = Some(unix_now());
+            state.failures = 0;
+            state.last_file = Some(name.clone());
+            state.last_error = None;
+            log::info!("Backup uploaded as {name}");
+            crate::ui::notify(
+                crate::ui::Level::Info,
+                tr_arg("Backup hochgeladen: {}", name),
+            );
+        }
+        Err(e) => {
+            state.last_error = Some(e.clone());
+            state.failures += 1;
+            log::warn!("Backup failed: {e}");
+        }
+    }
+    save_state(&state);
+    result
+}
+
+/// Move backup passwords that older versions kept in config.toml to the
+/// keychain. They stay in the config until all of them are stored.
+pub fn migrate_secrets(config: &mut Config) {
+    let mut backup = config.backup.clone();
+    let secrets = backup.take_plain_secrets();
+    if secrets.is_empty() {
+        return;
+    }
+    for (account, secret) in &secrets {
+        if let Err(e) = keychain::set(account, secret) {
+            log::warn!("Cannot move the backup password {account} to the keychain: {e}");
+            return;
+        }
+    }
+    log::info!("Moved {} backup passwords to the keychain", secrets.len());
+    config.backup = backup;
+    if let Err(e) = save_config(config) {
+        log::warn!("Failed to write config: {e}");
+    }
+}
+
+/// Background thread that uploads a backup whenever the configured interval has
+/// passed, waiting longer after each failure ([`BackupState::is_due`]). The
+/// config is re-read every round so changes in Settings apply without a
+/// restart.
+pub fn start_scheduler(db: Db) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_secs(5 * 60));
+
+        let Some(cfg) = load_config().map(|c| c.backup) else {
+            continue;
+        };
+        if !cfg.enabled {
+            continue;
+        }
+        let interval = u64::from(cfg.interval_hours.max(1)) * 3600;
+        if load_state().is_due(interval, unix_now()) {
+            run_backup(&db, &cfg).ok();
+        }
+    });
+}
@@ -0,0 +1,315 @@
+use crate::config::{data_dir, executable_dir, load_config, read_config, Config, PORTABLE_DIR};
+use crate::db::{
+    add_tag_to_song, find_tag, query_songs, remove_tag_from_song, song_id_by_path, ExtraFilters,
+    MatchMode, Song, SortMode, TagFilter,
+};
+use crate::i18n::{set_language, tr, tr_arg, tr_args};
+use crate::scanner::{nfc, scan_directory, ScanOptions};
+use rusqlite::Connection;
+use std::io::Write;
+use std::path::Path;
+
+/// Translated like any other string; the English text is in the i18n table.
+pub(crate) const USAGE: &str = "Songindex ohne Fenster, z.\u{00A0}B. f\u{00FC}r Skripte und cron:
+
+  songindex scan
+      Musikordner neu einlesen.
+  songindex list [--tag kategorie=wert]... [--suche text] [--sort titel|artist|neu] [--json]
+      Songs auflisten: Pfad, Titel und Artist, durch Tabs getrennt.
+  songindex tag add|remove <pfad> <kategorie> <wert>
+      Tag setzen oder entfernen. Der Pfad ist relativ zum Musikordner oder absolut.
+  songindex export [--tag kategorie=wert]... [--suche text] [--csv]
+      Songs mit allen Feldern und Tags als JSON (oder CSV) ausgeben.
+  songindex demo <ordner>
+      Demo-Bibliothek in einen leeren Ordner schreiben, z.\u{00A0}B. als Testdaten.
+  songindex portable
+      Ordner songindex-daten neben dem Programm anlegen und Einstellungen und
+      Bibliothek hineinkopieren (bei geschlossenem Fenster). Liegt der Ordner
+      dort, arbeitet Songindex nur damit, z.\u{00A0}B. vom USB-Stick.
+  songindex --demo
+      Das Fenster mit einer frischen Demo-Bibliothek starten; Musikordner und
+      Bibliothek bleiben unber\u{00FC}hrt.
+
+Ohne Befehl startet das Fenster.
+";
+
+/// Run a subcommand against the same library as the window and return the
+/// exit code. `None` starts the window: no arguments, or the process serial
+/// number older macOS versions pass to apps opened from the Finder.
+pub fn run(args: &[String]) -> Option<i32> {
+    let (command, rest) = args.split_first()?;
+    if command.starts_with("-psn_") {
+        return None;
+    }
+    // A broken config is reported by the command that needs it.
+    if let Ok(Some(config)) = read_config() {
+        set_language(config.language);
+    }
+    let result = match command.as_str() {
+        "help" | "--help" | "-h" => {
+            print!("{}", tr(USAGE));
+            Ok(())
+        }
+        "scan" => open_library().map(|(config, conn)| scan(&config, &conn)),
+        "list" => parse_options(rest).and_then(|options| list(&open_library()?.1, &options)),
+        "export" => parse_options(rest).and_then(|options| export(&open_library()?.1, &options)),
+        "tag" => open_library().and_then(|(config, conn)| tag(&config, &conn, rest)),
+        "demo" => demo(rest),
+        "portable" => portable(),
+        _ => Err(tr_arg("Unbekannter Befehl \u{201E}{}\u{201C}", command)),
+    };
+    Some(match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!(
+                "songindex: {}",
+                tr_arg("{} (songindex help zeigt alle Befehle)", e)
+            );
+            1
+        }
+    })
+}
+
+fn open_library() -> Result<(Config, Connection), String> {
+    let config = read_config()?.filter(|c| c.music_dir.is_dir()).ok_or(tr(
+        "Kein Musikordner eingerichtet; das Programm einmal ohne Befehl starten",
+    ))?;
+    let path = config.database_path();
+    if !path.parent().is_some_and(Path::is_dir) {
+        return Err(tr_arg(
+            "Ordner der Bibliothek {} nicht gefunden",
+            path.display(),
+        ));
+    }
+    let conn = crate::open_database(&config).ok_or(tr("Bibliothek nicht entsperrt"))?;
+    Ok((config, conn))
+}
+
+fn demo(args: &[String]) -> Result<(), String> {
+    let [dir] = args else {
+        return Err(tr("Aufruf: songindex demo <ordner>").to_string());
+    };
+    let count = crate::demo::create_library(Path::new(dir)).map_err(|e| e.to_string())?;
+    println!("{}", tr_args("{} Dateien in {}", &[&count, dir]));
+    Ok(())
+}
+
+/// Copy the files of the data dir (config, library, curriculum) into a new
+/// `songindex-daten` next to the app. Caches and snapshots stay behind.
+fn portable() -> Result<(), String> {
+    let dir = executable_dir()
+        .ok_or(tr("Programmordner unbekannt"))?
+        .join(PORTABLE_DIR);
+    if dir.exists() {
+        return Err(tr_arg("{} gibt es schon", dir.display()));
+    }
+    let source = data_dir();
+    std::fs::create_dir(&dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    let mut count = 0;
+    let entries = std::fs::read_dir(&source).map_err(|e| e.to_string())?;
+    for entry in entries.flatten() {
+        if entry.file_type().is_ok_and(|t| t.is_file()) {
+            std::fs::copy(entry.path(), dir.join(entry.file_name()))
+                .map_err(|e| format!("{}: {e}", entry.path().display()))?;
+            count += 1;
+        }
+    }
+    println!(
+        "{}",
+        tr_args("{} Dateien nach {} kopiert", &[&count, &dir.display()])
+    );
+    if let Some(database) = load_config().and_then(|c| c.database) {
+        if database.is_absolute() {
+            println!(
+                "{}",
+                tr_arg(
+                    "Die Bibliothek liegt unter {} und wurde nicht kopiert",
+                    database.display()
+                )
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Flags shared by `list` and `export`.
+struct Options {
+    tags: Vec<(String, String)>,
+    suche: String,
+    sort: SortMode,
+    json: bool,
+    csv: bool,
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        tags: Vec::new(),
+        suche: String::new(),
+        sort: SortMode::Title,
+        json: false,
+        csv: false,
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| tr_arg("{} braucht einen Wert", arg))
+        };
+        match arg.as_str() {
+            "--tag" => {
+                let tag = value()?;
+                let (kategorie, wert) = tag
+                    .split_once('=')
+                    .ok_or_else(|| tr_arg("--tag {}: erwartet kategorie=wert", tag))?;
+                options.tags.push((kategorie.to_string(), wert.to_string()));
+            }
+            "--suche" => options.suche = value()?.clone(),
+            "--sort" => {
+                options.sort = match value()?.as_str() {
+                    "titel" => SortMode::Title,
+                    "artist" => SortMode::Artist,
+                    "neu" => SortMode::Recent,
+                    other => return Err(tr_arg("--sort {}: titel, artist oder neu", other)),
+                }
+            }
+            "--json" => options.json = true,
+            "--csv" => options.csv = true,
+            other => return Err(tr_arg("Unbekannte Option \u{201E}{}\u{201C}", other)),
+        }
+    }
+    Ok(options)
+}
+
+/// Songs in the list with the given tags (all of them) and search text.
+fn matching_songs(conn: &Connection, options: &Options) -> Result<Vec<Song>, String> {
+    let mut groups = Vec::new();
+    for (kategorie, wert) in &options.tags {
+        let id = find_tag(conn, kategorie, wert)
+            .ok_or_else(|| tr_args("Den Tag {}={} gibt es nicht", &[kategorie, wert]))?;
+        groups.push((vec![id], MatchMode::Any));
+    }
+    let tag_filter = TagFilter {
+        groups,
+        mode: MatchMode::All,
+    };
+    Ok(query_songs(
+        conn,
+        &options.suche,
+        &tag_filter,
+        &ExtraFilters::default(),
+        &options.sort,
+    ))
+}
+
+fn scan(config: &Config, conn: &Connection) {
+    let report = scan_directory(conn, &config.music_dir, &ScanOptions::from(config));
+    if !report.is_empty() {
+        println!(
+            "{}",
+            tr_args(
+                "{} neu, {} entfernt, {} verschoben, {} mit neuem Audio",
+                &[
+                    &report.added.len(),
+                    &report.removed.len(),
+                    &report.moved.len(),
+                    &report.audio.len(),
+                ],
+            )
+        );
+    }
+    let count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM songs WHERE deleted_at IS NULL AND ignoriert = 0",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+    println!("{}", tr_arg("{} Songs im Index", count));
+}
+
+fn list(conn: &Connection, options: &Options) -> Result<(), String> {
+    if options.json {
+        return export(conn, options);
+    }
+    let songs = matching_songs(conn, options)?;
+    // Write errors mean the reader went away (`| head`); stop quietly.
+    let mut out = std::io::stdout().lock();
+    for song in songs {
+        let artist = song.artist.unwrap_or_default();
+        if writeln!(out, "{}\t{}\t{artist}", song.dateipfad, song.titel).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn export(conn: &Connection, options: &Options) -> Result<(), String> {
+    let songs = matching_songs(conn, options)?;
+    let mut out = std::io::stdout().lock();
+    if !options.csv {
+        serde_json::to_writer_pretty(&mut out, &songs).map_err(|e| e.to_string())?;
+        return writeln!(out).map_err(|e| e.to_string());
+    }
+    let mut writer = csv::Writer::from_writer(out);
+    writer
+        .write_record([
+            "titel",
+            "artist",
+            "dateipfad",
+            "tonart",
+            "bpm",
+            "kapo",
+            "tags",
+        ])
+        .map_err(|e| e.to_string())?;
+    for song in songs {
+        let tags: Vec<String> = song
+            .tags
+            .iter()
+            .map(|t| format!("{}={}", t.kategorie, t.wert))
+            .collect();
+        writer
+            .write_record([
+                song.titel,
+                song.artist.unwrap_or_default(),
+                song.dateipfad,
+                song.tonart.unwrap_or_default(),
+                song.bpm.map(|b| b.to_string()).unwrap_or_default(),
+                song.kapo.map(|k| k.to_string()).unwrap_or_default(),
+                tags.join("; "),
+            ])
+            .map_err(|e| e.to_string())?;
+    }
+    writer.flush().map_err(|e| e.to_string())
+}
+
+fn tag(config: &Config, conn: &Connection, args: &[String]) -> Result<(), String> {
+    let [action, path, kategorie, wert] = args else {
+        return Err(tr("tag erwartet add|remove <pfad> <kategorie> <wert>").to_string());
+    };
+    let song_id = song_for_path(conn, &config.music_dir, path)?;
+    match action.as_str() {
+        "add" => add_tag_to_song(conn, song_id, kategorie, wert),
+        "remove" => {
+            let tag_id = find_tag(conn, kategorie, wert)
+                .ok_or_else(|| tr_args("Den Tag {}={} gibt es nicht", &[kategorie, wert]))?;
+            remove_tag_from_song(conn, song_id, tag_id);
+        }
+        other => return Err(tr_arg("tag {}: add oder remove", other)),
+    }
+    Ok(())
+}
+
+/// A path relative to the music folder, or any path to a file inside it.
+fn song_for_path(conn: &Connection, music_dir: &Path, path: &str) -> Result<i64, String> {
+    if let Some(id) = song_id_by_path(conn, &nfc(path.into())) {
+        return Ok(id);
+    }
+    let absolute = std::fs::canonicalize(path).ok();
+    let music_dir = std::fs::canonicalize(music_dir).unwrap_or_else(|_| music_dir.to_path_buf());
+    absolute
+        .as_deref()
+        .and_then(|p| p.strip_prefix(&music_dir).ok())
+        .and_then(|rel| song_id_by_path(conn, &nfc(rel.to_string_lossy())))
+        .ok_or_else(|| tr_arg("{} ist nicht im Index", path))
+}
@@ -0,0 +1,131 @@
+use crate::config::data_dir;
+use rusqlite::{params, Connection};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+const DEFAULT_CURRICULUM: &str = r#"# Lehrplan für den Abdeckungsbericht (Statistik → Lehrplan).
+#
+# Jede Fertigkeit zählt die Songs, die ALLE ihre Tags tragen ("kategorie:wert"),
+# getrennt nach den Stufen (Werte der Kategorie "schwierigkeit").
+# Zellen mit weniger als `min_songs` Songs gelten als Lücke.
+
+stufen = ["Anfänger", "Mittel", "Fortgeschritten"]
+min_songs = 3
+
+[[fertigkeit]]
+name = "Fingerpicking"
+tags = ["technik:Fingerpicking"]
+
+[[fertigkeit]]
+name = "Solospiel"
+tags = ["technik:Solo"]
+
+[[fertigkeit]]
+name = "Blues"
+tags = ["stil:Blues"]
+
+[[fertigkeit]]
+name = "Bossa Nova"
+tags = ["stil:Bossa Nova"]
+
+[[fertigkeit]]
+name = "E-Gitarre Solo"
+tags = ["instrument:E-Gitarre", "technik:Solo"]
+"#;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Curriculum {
+    pub stufen: Vec<String>,
+    #[serde(default = "default_min_songs")]
+    pub min_songs: i64,
+    #[serde(default)]
+    pub fertigkeit: Vec<Skill>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Skill {
+    pub name: String,
+    pub tags: Vec<String>,
+}
+
+fn default_min_songs() -> i64 {
+    3
+}
+
+pub struct CoverageRow {
+    pub skill: String,
+    /// Song count per level, in the order of `Curriculum::stufen`.
+    pub counts: Vec<i64>,
+}
+
+pub fn curriculum_path() -> PathBuf {
+    data_dir().join("lehrplan.toml")
+}
+
+/// Load the curriculum, writing the example file first if there is none.
+pub fn load_curriculum() -> Result<Curriculum, String> {
+    let path = curriculum_path();
+    if !path.exists() {
+        std::fs::write(&path, DEFAULT_CURRICULUM).map_err(|e| e.to_string())?;
+    }
+    let content = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    toml::from_str(&content).map_err(|e| e.to_string())
+}
+
+/// Number of songs carrying all tags in `tags` (each "kategorie:wert") plus
+/// `schwierigkeit=stufe`.
+fn count_songs(conn: &Connection, tags: &[String], stufe: &str) -> i64 {
+    let mut required: Vec<(String, String)> = tags
+        .iter()
+        .filter_map(|t| t.split_once(':'))
+        .map(|(k, w)| (k.trim().to_string(), w.trim().to_string()))
+        .collect();
+    required.push(("schwierigkeit".to_string(), stufe.to_string()));
+
+    let mut sql =
+        String::from("SELECT COUNT(*) FROM songs s WHERE s.deleted_at IS NULL AND s.ignoriert = 0");
+    let mut values: Vec<String> = Vec::new();
+    for (kategorie, wert) in &required {
+        let n = values.len();
+        sql.push_str(&format!(
+            " AND EXISTS (SELECT 1 FROM song_tags st JOIN tags t ON t.id = st.tag_id
+                          WHERE st.song_id = s.id AND t.kategorie = ?{} AND t.wert = ?{})",
+            n + 1,
+            n + 2
+        ));
+        values.push(kategorie.clone());
+        values.push(wert.clone());
+    }
+    conn.query_row(&sql, rusqlite::params_from_iter(values.iter()), |row| {
+        row.get(0)
+    })
+    .unwrap_or(0)
+}
+
+pub fn coverage(conn: &Connection, curriculum: &Curriculum) -> Vec<CoverageRow> {
+    curriculum
+        .fertigkeit
+        .iter()
+        .map(|skill| CoverageRow {
+            skill: skill.name.clone(),
+            counts: curriculum
+                .stufen
+                .iter()
+                .map(|stufe| count_songs(conn, &skill.tags, stufe))
+                .collect(),
+        })
+        .collect()
+}
+
+/// Songs tagged with a level that isn't one of the curriculum levels would be
+/// invisible in the report; callers can warn about them.
+pub fn unknown_levels(conn: &Connection, curriculum: &Curriculum) -> Vec<String> {
+    let mut stmt = conn
+        .prepare("SELECT wert FROM tags WHERE kategorie = ?1 ORDER BY wert")
+        .unwrap();
+    stmt.query_map(params!["schwierigkeit"], |row| row.get::<_, String>(0))
+        .unwrap()
+        .filter_map(|r| r.ok())
+        .filter(|w| !curriculum.stufen.contains(w))
+        .collect()
+}
@@ -0,0 +1,180 @@
+use crate::i18n::tr;
+use crate::keychain;
+use rusqlite::Connection;
+use std::io::Read;
+use std::path::Path;
+
+/// Keychain account of the library passphrase.
+const KEYCHAIN_ACCOUNT: &str = "songindex.db";
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+
+/// A database file that exists but doesn't start with the plain SQLite header
+/// is a SQLCipher file.
+pub fn is_encrypted(path: &Path) -> bool {
+    let Ok(file) = std::fs::File::open(path) else {
+        return false;
+    };
+    let mut header = Vec::with_capacity(SQLITE_HEADER.len());
+    if file
+        .take(SQLITE_HEADER.len() as u64)
+        .read_to_end(&mut header)
+        .is_err()
+    {
+        return false;
+    }
+    !header.is_empty() && header != SQLITE_HEADER
+}
+
+pub fn stored_passphrase() -> Option<String> {
+    keychain::get(KEYCHAIN_ACCOUNT)
+}
+
+pub fn store_passphrase(passphrase: &str) -> Result<(), String> {
+    keychain::set(KEYCHAIN_ACCOUNT, passphrase)
+}
+
+pub fn forget_passphrase() {
+    keychain::delete(KEYCHAIN_ACCOUNT);
+}
+
+/// Native password dialog via `osascript`; `None` if the user cancels.
+fn prompt_passphrase(message: &str) -> Option<String> {
+    let output = std::process::Command::new("osascript")
+        .args([
+            "-e",
+            "on run argv",
+            "-e",
+            "text returned of (display dialog (item 1 of argv) default answer \"\" \
+             with hidden answer with title \"Songindex\" with icon caution)",
+            "-e",
+            "end run",
+            message,
+        ])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .trim_end_matches('\n')
+            .to_string(),
+    )
+}
+
+/// Set the SQLCipher key and check that it actually decrypts the file.
+pub fn apply_key(conn: &Connection, passphrase: &str) -> bool {
+    conn.pragma_update(None, "key", passphrase).is_ok()
+        && conn
+            .query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| {
+                row.get::<_, i64>(0)
+            })
+            .is_ok()
+}
+
+/// Open an encrypted database, using the keychain entry if there is one and
+/// otherwise asking for the passphrase. Returns `None` if the user gives up.
+pub fn unlock(path: &Path) -> Option<Connection> {
+    if let Some(passphrase) = stored_passphrase() {
+        let conn = Connection::open(path).ok()?;
+        if apply_key(&conn, &passphrase) {
+            return Some(conn);
+        }
+        log::warn!("Keychain passphrase rejected");
+    }
+
+    let mut message = tr("Passwort f\u{00FC}r die Songindex-Bibliothek:");
+    loop {
+        let passphrase = prompt_passphrase(message)?;
+        let conn = Connection::open(path).ok()?;
+        if apply_key(&conn, &passphrase) {
+            return Some(conn);
+        }
+        message = tr("Falsches Passwort. Bitte erneut versuchen:");
+    }
+}
+
+/// Re-encrypt the open plain database into a SQLCipher file at the same path
+/// and swap `conn` over to the encrypted copy. On an error after the swap
+/// began, the plain file is put back and `conn` reopened on it.
+pub fn encrypt_database(
+    conn: &mut Connection,
+    path: &Path,
+    passphrase: &str,
+) -> Result<(), String> {
+    let tmp = path.with_extension("db.encrypting");
+    std::fs::remove_file(&tmp).ok();
+    conn.execute(
+        "ATTACH DATABASE ?1 AS encrypted KEY ?2",
+        rusqlite::params![tmp.to_string_lossy(), passphrase],
+    )
+    .map_err(|e| e.to_string())?;
+    let exported = conn
+        .query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))
+        .map_err(|e| e.to_string());
+    conn.execute_batch("DETACH DATABASE encrypted").ok();
+    if let Err(e) = exported {
+        std::fs::remove_file(&tmp).ok();
+        return Err(e);
+    }
+
+    // Close the plain connection before replacing the file underneath it.
+    let plain = std::mem::replace(
+        conn,
+        Connection::open_in_memory().map_err(|e| e.to_string())?,
+    );
+    if let Err((plain, e)) = plain.close() {
+        *conn = plain;
+        std::fs::remove_file(&tmp).ok();
+        return Err(e.to_string());
+    }
+    for suffix in ["-wal", "-shm"] {
+        let mut sidecar = path.as_os_str().to_owned();
+        sidecar.push(suffix);
+        std::fs::remove_file(sidecar).ok();
+    }
+
+    // The plain file stays next to it until the encrypted one opens.
+    let backup = path.with_extension("db.plain");
+    let swapped = std::fs::rename(path, &backup)
+        .map_err(|e| e.to_string())
+        .and_then(|()| {
+            std::fs::rename(&tmp, path).map_err(|e| {
+                std::fs::rename(&backup, path).ok();
+                e.to_string()
+            })
+        });
+    if let Err(e) = swapped {
+        std::fs::remove_file(&tmp).ok();
+        reopen(conn, path, None)?;
+        return Err(e);
+    }
+
+    match reopen(conn, path, Some(passphrase)) {
+        Ok(()) => {
+            std::fs::remove_file(&backup).ok();
+            Ok(())
+        }
+        Err(e) => {
+            std::fs::remove_file(path).ok();
+            std::fs::rename(&backup, path).map_err(|e| e.to_string())?;
+            reopen(conn, path, None)?;
+            Err(e)
+        }
+    }
+}
+
+/// Open `path` into `conn`, with the SQLCipher key if there is one.
+fn reopen(conn: &mut Connection, path: &Path, passphrase: Option<&str>) -> Result<(), String> {
+    let reopened = Connection::open(path).map_err(|e| e.to_string())?;
+    if passphrase.is_some_and(|p| !apply_key(&reopened, p)) {
+        return Err(
+            tr("Verschl\u{00FC}sselte Datenbank l\u{00E4}sst sich nicht \u{00F6}ffnen").to_string(),
+        );
+    }
+    reopened
+        .execute_batch("PRAGMA journal_mode=WAL; PRAGMA foreign_keys=ON;")
+        .ok();
+    *conn = reopened;
+    Ok(())
+}
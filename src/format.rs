@@ -0,0 +1,180 @@
+use crate::config::Locale;
+use rusqlite::Connection;
+
+const MONTHS_EN: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Dates and numbers as shown in the UI, in the configured locale. Keeps
+/// today's date, so relative phrases ("vor 3 Tagen") agree across a frame.
+/// The default, standing in until the first `load`, knows no date.
+#[derive(Clone, Copy, Default)]
+pub struct Formatter {
+    locale: Locale,
+    /// Local date as days since 1970-01-01.
+    today: i64,
+}
+
+impl Formatter {
+    /// Today from SQLite's local time, like the date comparisons in queries.
+    pub fn load(conn: &Connection, locale: Locale) -> Self {
+        let today: String = conn
+            .query_row("SELECT date('now', 'localtime')", [], |row| row.get(0))
+            .unwrap_or_default();
+        Self {
+            locale,
+            today: day_number(&today).unwrap_or(0),
+        }
+    }
+
+    /// The date part of an SQLite timestamp ("2026-10-17 08:30:00"), e.g.
+    /// "17.10.2026". Anything unparsable is returned unchanged.
+    pub fn date(&self, timestamp: &str) -> String {
+        let Some((y, m, d)) = parse_date(timestamp) else {
+            return timestamp.to_string();
+        };
+        match self.locale {
+            Locale::De => format!("{d:02}.{m:02}.{y}"),
+            Locale::En => format!("{} {d}, {y}", MONTHS_EN[m as usize - 1]),
+        }
+    }
+
+    /// Today's date, formatted like [`date`](Self::date).
+    pub fn today(&self) -> String {
+        let (y, m, d) = civil_date(self.today);
+        self.date(&format!("{y:04}-{m:02}-{d:02}"))
+    }
+
+    /// How long ago a timestamp's date was, e.g. "gestern" or "vor 3 Wochen".
+    pub fn relative(&self, timestamp: &str) -> String {
+        match day_number(timestamp) {
+            Some(day) => self.days_ago(self.today - day),
+            None => timestamp.to_string(),
+        }
+    }
+
+    /// "heute", "gestern", then days, weeks, months and years.
+    pub fn days_ago(&self, days: i64) -> String {
+        let (n, unit) = match days {
+            ..=0 => return self.pick("heute", "today").to_string(),
+            1 => return self.pick("gestern", "yesterday").to_string(),
+            2..=13 => (days, Unit::Day),
+            14..=59 => (days / 7, Unit::Week),
+            60..=729 => (days / 30, Unit::Month),
+            _ => (days / 365, Unit::Year),
+        };
+        self.ago(n, unit)
+    }
+
+    /// Like [`days_ago`](Self::days_ago), with minutes and hours for the
+    /// first day; for Unix timestamps, which have no local date.
+    pub fn seconds_ago(&self, seconds: u64) -> String {
+        let seconds = seconds as i64;
+        match seconds {
+            ..=59 => self.pick("gerade eben", "just now").to_string(),
+            60..=3599 => self.ago(seconds / 60, Unit::Minute),
+            3600..=86_399 => self.ago(seconds / 3600, Unit::Hour),
+            _ => self.days_ago(seconds / 86_400),
+        }
+    }
+
+    /// Whole number with thousands separators: "50.000" or "50,000".
+    pub fn number(&self, n: i64) -> String {
+        let separator = self.pick(".", ",");
+        let digits = n.unsigned_abs().to_string();
+        let mut out = String::new();
+        if n < 0 {
+            out.push('-');
+        }
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                out.push_str(separator);
+            }
+            out.push(c);
+        }
+        out
+    }
+
+    fn pick<'a>(&self, de: &'a str, en: &'a str) -> &'a str {
+        match self.locale {
+            Locale::De => de,
+            Locale::En => en,
+        }
+    }
+
+    fn ago(&self, n: i64, unit: Unit) -> String {
+        let (one, many) = match (self.locale, unit) {
+            (Locale::De, Unit::Minute) => ("Minute", "Minuten"),
+            (Locale::De, Unit::Hour) => ("Stunde", "Stunden"),
+            (Locale::De, Unit::Day) => ("Tag", "Tagen"),
+            (Locale::De, Unit::Week) => ("Woche", "Wochen"),
+            (Locale::De, Unit::Month) => ("Monat", "Monaten"),
+            (Locale::De, Unit::Year) => ("Jahr", "Jahren"),
+            (Locale::En, Unit::Minute) => ("minute", "minutes"),
+            (Locale::En, Unit::Hour) => ("hour", "hours"),
+            (Locale::En, Unit::Day) => ("day", "days"),
+            (Locale::En, Unit::Week) => ("week", "weeks"),
+            (Locale::En, Unit::Month) => ("month", "months"),
+            (Locale::En, Unit::Year) => ("year", "years"),
+        };
+        let word = if n == 1 { one } else { many };
+        match self.locale {
+            Locale::De => format!("vor {n} {word}"),
+            Locale::En => format!("{n} {word} ago"),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Unit {
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+/// Year, month and day of a timestamp starting with "YYYY-MM-DD".
+fn parse_date(timestamp: &str) -> Option<(i64, u32, u32)> {
+    let date = timestamp.get(..10)?;
+    let mut parts = date.split('-');
+    let y = parts.next()?.parse().ok()?;
+    let m = parts
+        .next()?
+        .parse()
+        .ok()
+        .filter(|m| (1..=12).contains(m))?;
+    let d = parts
+        .next()?
+        .parse()
+        .ok()
+        .filter(|d| (1..=31).contains(d))?;
+    Some((y, m, d))
+}
+
+/// Days since 1970-01-01 of a timestamp's date (proleptic Gregorian).
+fn day_number(timestamp: &str) -> Option<i64> {
+    let (y, m, d) = parse_date(timestamp)?;
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (i64::from(m) + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + i64::from(d) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Some(era * 146_097 + doe - 719_468)
+}
+
+/// Year, month and day of a day number; the inverse of [`day_number`].
+fn civil_date(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    (y, m, d)
+}
@@ -0,0 +1,112 @@
+use crate::db::TagGroup;
+
+/// Category for `#wert` tokens whose value does not exist as a tag yet.
+pub const FALLBACK_CATEGORY: &str = "sonstiges";
+
+const MAX_COMPLETIONS: usize = 8;
+
+/// One `#kategorie/wert` or `#wert` token. Underscores stand for spaces, so
+/// `#stil/Bossa_Nova` becomes the value "Bossa Nova".
+#[derive(Debug, PartialEq)]
+pub struct Hashtag {
+    /// Lowercased, as categories are stored.
+    pub kategorie: Option<String>,
+    pub wert: String,
+}
+
+/// All hashtags in the input; words without `#` and empty tags are ignored.
+pub fn parse(input: &str) -> Vec<Hashtag> {
+    input
+        .split_whitespace()
+        .filter_map(|word| word.strip_prefix('#'))
+        .filter_map(|token| {
+            let (kategorie, wert) = match token.split_once('/') {
+                Some((kategorie, wert)) => (Some(kategorie.trim().to_lowercase()), wert),
+                None => (None, token),
+            };
+            let wert = wert.replace('_', " ").trim().to_string();
+            if wert.is_empty() || kategorie.as_deref() == Some("") {
+                return None;
+            }
+            Some(Hashtag { kategorie, wert })
+        })
+        .collect()
+}
+
+/// Category and value to store. A bare `#wert` takes the category of an
+/// existing tag with that value (case-insensitive), otherwise
+/// [`FALLBACK_CATEGORY`]. Existing values keep their spelling.
+pub fn resolve(tags: &[TagGroup], hashtag: &Hashtag) -> (String, String) {
+    let existing = tags
+        .iter()
+        .filter(|g| hashtag.kategorie.as_ref().is_none_or(|k| *k == g.kategorie))
+        .flat_map(|g| g.tags.iter().map(move |t| (&g.kategorie, &t.wert)))
+        .find(|(_, wert)| wert.to_lowercase() == hashtag.wert.to_lowercase());
+    match (existing, &hashtag.kategorie) {
+        (Some((kategorie, wert)), _) => (kategorie.clone(), wert.clone()),
+        (None, Some(kategorie)) => (kategorie.clone(), hashtag.wert.clone()),
+        (None, None) => (FALLBACK_CATEGORY.to_string(), hashtag.wert.clone()),
+    }
+}
+
+/// Completions for the hashtag being typed at the end of `input`: matching
+/// categories (`#stil/`) and existing tags (`#stil/Blues`), most used first.
+pub fn completions(input: &str, tags: &[TagGroup]) -> Vec<String> {
+    let Some(partial) = current_token(input) else {
+        return Vec::new();
+    };
+    let partial = partial.to_lowercase();
+    let mut found: Vec<(i64, String)> = Vec::new();
+    match partial.split_once('/') {
+        Some((kategorie, wert)) => {
+            for group in tags.iter().filter(|g| g.kategorie == kategorie) {
+                for tag in &group.tags {
+                    if tag.wert.to_lowercase().starts_with(wert) {
+                        found.push((tag.count, token(&group.kategorie, &tag.wert)));
+                    }
+                }
+            }
+        }
+        None => {
+            for group in tags {
+                if group.kategorie.starts_with(&partial) {
+                    found.push((i64::MAX, format!("#{}/", group.kategorie)));
+                }
+                for tag in &group.tags {
+                    if !partial.is_empty() && tag.wert.to_lowercase().starts_with(&partial) {
+                        found.push((tag.count, token(&group.kategorie, &tag.wert)));
+                    }
+                }
+            }
+        }
+    }
+    found.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    found
+        .into_iter()
+        .map(|(_, completion)| completion)
+        .take(MAX_COMPLETIONS)
+        .collect()
+}
+
+/// Replace the hashtag being typed with `completion`. A finished tag gets a
+/// trailing space so the next one can follow right away.
+pub fn complete(input: &str, completion: &str) -> String {
+    let before = input.trim_end_matches(|c: char| !c.is_whitespace());
+    let mut out = format!("{before}{completion}");
+    if !completion.ends_with('/') {
+        out.push(' ');
+    }
+    out
+}
+
+/// Text after the `#` of the last word, if that word is an unfinished hashtag.
+fn current_token(input: &str) -> Option<&str> {
+    if input.ends_with(char::is_whitespace) {
+        return None;
+    }
+    input.split_whitespace().next_back()?.strip_prefix('#')
+}
+
+fn token(kategorie: &str, wert: &str) -> String {
+    format!("#{kategorie}/{}", wert.replace(' ', "_"))
+}
@@ -0,0 +1,191 @@
+use crate::db::Song;
+use crate::i18n::tr_arg;
+use std::fmt::Write;
+use std::path::Path;
+
+/// How song titles link to their files in an exported page.
+pub enum Links {
+    None,
+    /// Relative to the page, for a copy saved at the top of the music folder.
+    Relative,
+    /// Prefixed with a base URL, e.g. a shared drive folder.
+    Base(String),
+}
+
+/// Standalone repertoire page: one table row per song with artist, key and
+/// tags, styled inline and filterable with a small search box, so it works
+/// from a shared drive without any other files. With `print`, the page is
+/// light, numbered and without the search box, for paper or an email to the
+/// band; either way it prints in black on white.
+pub fn repertoire_page(
+    titel: &str,
+    stand: &str,
+    songs: &[Song],
+    links: &Links,
+    print: bool,
+) -> String {
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"de\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{titel}</title>\n<style>{STYLE}{extra}</style>\n</head>\n<body>\n\
+         <header>\n<h1>{titel}</h1>\n<p class=\"meta\">{count} Songs \u{00B7} Stand {stand}</p>\n",
+        titel = escape(titel),
+        count = songs.len(),
+        stand = escape(stand),
+        extra = if print { PRINT_STYLE } else { "" },
+    );
+    if !print {
+        html.push_str(
+            "<input id=\"suche\" type=\"search\" placeholder=\"Suchen\u{2026}\" autofocus>\n",
+        );
+    }
+    html.push_str("</header>\n<table>\n<thead><tr>");
+    if print {
+        html.push_str("<th class=\"nr\">Nr.</th>");
+    }
+    html.push_str(
+        "<th>Titel</th><th>Artist</th><th>Tonart</th><th>Tags</th></tr></thead>\n<tbody>\n",
+    );
+    for (i, song) in songs.iter().enumerate() {
+        let title = escape(&song.titel);
+        let title = match link_target(song, links) {
+            Some(href) => format!("<a href=\"{}\">{title}</a>", escape(&href)),
+            None => title,
+        };
+        let key = match (&song.tonart, song.kapo.filter(|k| *k > 0)) {
+            (Some(tonart), Some(kapo)) => format!("{} (Kapo {kapo})", escape(tonart)),
+            (Some(tonart), None) => escape(tonart),
+            (None, _) => String::new(),
+        };
+        let tags: String = song
+            .tags
+            .iter()
+            .filter(|t| t.kategorie != "artist")
+            .map(|t| {
+                format!(
+                    "<span class=\"tag\" title=\"{}\">{}</span>",
+                    escape(&t.kategorie),
+                    escape(&t.wert)
+                )
+            })
+            .collect();
+        let nr = if print {
+            format!("<td class=\"nr\">{}</td>", i + 1)
+        } else {
+            String::new()
+        };
+        let _ = writeln!(
+            html,
+            "<tr>{nr}<td>{title}</td><td>{}</td><td>{key}</td><td>{tags}</td></tr>",
+            escape(song.artist.as_deref().unwrap_or("")),
+        );
+    }
+    html.push_str("</tbody>\n</table>\n");
+    if !print {
+        html.push_str("<script>");
+        html.push_str(SCRIPT);
+        html.push_str("</script>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+pub fn export_repertoire(
+    path: &Path,
+    titel: &str,
+    stand: &str,
+    songs: &[Song],
+    links: &Links,
+    print: bool,
+) -> Result<(), String> {
+    std::fs::write(path, repertoire_page(titel, stand, songs, links, print))
+        .map_err(|e| tr_arg("Datei konnte nicht geschrieben werden: {}", e))
+}
+
+fn link_target(song: &Song, links: &Links) -> Option<String> {
+    let path = url_path(&song.dateipfad);
+    match links {
+        Links::None => None,
+        Links::Relative => Some(path),
+        Links::Base(base) => Some(format!("{}/{path}", base.trim_end_matches('/'))),
+    }
+}
+
+/// Percent-encode a relative path for use in a URL, keeping the slashes.
+pub fn url_path(path: &str) -> String {
+    let mut out = String::new();
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                out.push(byte as char)
+            }
+            _ => {
+                let _ = write!(out, "%{byte:02X}");
+            }
+        }
+    }
+    out
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const STYLE: &str = "
+body { font-family: -apple-system, 'Helvetica Neue', sans-serif; margin: 0; padding: 24px;
+  background: #16141a; color: #f2eee6; }
+header { margin-bottom: 16px; }
+h1 { font-size: 24px; margin: 0 0 4px; }
+.meta { color: #afaaa2; margin: 0 0 12px; }
+input { font-size: 15px; padding: 6px 10px; width: 100%; max-width: 360px; border-radius: 6px;
+  border: 1px solid #34303e; background: #121016; color: inherit; }
+table { border-collapse: collapse; width: 100%; }
+th { text-align: left; color: #afaaa2; font-weight: 600; font-size: 13px; padding: 6px 8px;
+  border-bottom: 1px solid #34303e; }
+td { padding: 6px 8px; border-bottom: 1px solid #26242e; vertical-align: top; }
+tr:hover td { background: #201e26; }
+a { color: #ebb43c; text-decoration: none; }
+a:hover { text-decoration: underline; }
+.tag { display: inline-block; font-size: 12px; padding: 1px 7px; margin: 0 4px 3px 0;
+  border-radius: 9px; background: #34303e; color: #afaaa2; }
+@media print {
+  body { background: #fff; color: #000; padding: 0; }
+  .meta, th { color: #444; }
+  input { display: none; }
+  th { border-bottom: 1px solid #000; }
+  td { border-bottom: 1px solid #ccc; }
+  tr { break-inside: avoid; }
+  tr:hover td { background: none; }
+  a { color: inherit; }
+  .tag { background: none; border: 1px solid #999; color: #444; }
+}
+";
+
+/// Light layout for the printable variant, on top of [`STYLE`].
+const PRINT_STYLE: &str = "
+body { background: #fff; color: #111; max-width: 960px; }
+.meta, th { color: #555; }
+th { border-bottom: 1px solid #333; }
+td { border-bottom: 1px solid #ddd; }
+tr:hover td { background: none; }
+a { color: #8a5a00; }
+.nr { width: 2.5em; color: #777; text-align: right; padding-right: 12px; }
+.tag { background: #eee; color: #444; }
+";
+
+const SCRIPT: &str = "
+const suche = document.getElementById('suche');
+const rows = Array.from(document.querySelectorAll('tbody tr'));
+suche.addEventListener('input', () => {
+  const words = suche.value.toLowerCase().split(/\\s+/).filter(w => w);
+  for (const row of rows) {
+    const text = row.textContent.toLowerCase();
+    row.style.display = words.every(w => text.includes(w)) ? '' : 'none';
+  }
+});
+";
@@ -0,0 +1,812 @@
+//! UI strings in the configured language. The German text is the key: a
+//! string missing from a table shows up in German instead of breaking.
+
+use crate::config::Language;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+static LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+/// Switch the language for every string looked up from now on.
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+fn language() -> Language {
+    match LANGUAGE.load(Ordering::Relaxed) {
+        1 => Language::En,
+        _ => Language::De,
+    }
+}
+
+/// `de` in the current language.
+pub fn tr(de: &str) -> &str {
+    match language() {
+        Language::De => de,
+        Language::En => {
+            static TABLE: OnceLock<HashMap<&str, &str>> = OnceLock::new();
+            TABLE
+                .get_or_init(|| EN.iter().copied().collect())
+                .get(de)
+                .copied()
+                .unwrap_or(de)
+        }
+    }
+}
+
+/// [`tr`] for a template with one `{}`, filled in after translating.
+pub fn tr_arg(de: &str, arg: impl Display) -> String {
+    tr(de).replacen("{}", &arg.to_string(), 1)
+}
+
+/// [`tr`] for a template with several `{}`, filled in order.
+pub fn tr_args(de: &str, args: &[&dyn Display]) -> String {
+    let mut out = String::new();
+    for (i, part) in tr(de).split("{}").enumerate() {
+        if let Some(arg) = i.checked_sub(1).and_then(|i| args.get(i)) {
+            out.push_str(&arg.to_string());
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+/// German → English. Templates keep their `{}` placeholder.
+const EN: &[(&str, &str)] = &[
+    ("Favorit entfernen", "Remove from favorites"),
+    ("Als Favorit markieren", "Mark as favorite"),
+    ("Einstellungen", "Settings"),
+    ("Statistik", "Statistics"),
+    ("Hintergrundjobs: Vorschaubilder, PDF-Text, Pr\u{00FC}fsummen", "Background jobs: thumbnails, PDF text, checksums"),
+    ("min ge\u{00FC}bt diese Woche", "min practiced this week"),
+    ("Scanne\u{2026} {} Dateien", "Scanning\u{2026} {} files"),
+    ("Scan abgeschlossen, keine \u{00C4}nderungen", "Scan finished, no changes"),
+    ("{} neue Songs bisher; die Liste f\u{00FC}llt sich, w\u{00E4}hrend der Scan l\u{00E4}uft", "{} new songs so far; the list fills while the scan runs"),
+    ("ohne Tags", "untagged"),
+    ("mit Audio", "with audio"),
+    ("Suche nach Titel oder Artist...", "Search by title or artist..."),
+    ("Neues Chart aus Vorlage\u{2026}", "New chart from template\u{2026}"),
+    ("Aus Zwischenablage importieren\u{2026}", "Import from clipboard\u{2026}"),
+    ("Mehrere Tags dieser Kategorie: mindestens einer (ODER) oder alle (UND)", "Several tags in this category: at least one (OR) or all (AND)"),
+    ("Extras:", "Extras:"),
+    ("Nur mit Audio", "Audio only"),
+    ("Ohne Tags", "Untagged"),
+    ("Nur Favoriten", "Favorites only"),
+    ("Kategorien verkn\u{00FC}pfen:", "Combine categories:"),
+    ("Songs m\u{00FC}ssen die Filter aller Kategorien erf\u{00FC}llen (UND) oder mindestens einer (ODER)", "Songs must match the filters of all categories (AND) or of at least one (OR)"),
+    ("Werte:", "Values:"),
+    ("Kapo bis", "Capo up to"),
+    ("\u{00DC}be-Session\u{2026}", "Practice session\u{2026}"),
+    ("Aufw\u{00E4}rmen\u{2026}", "Warm-up\u{2026}"),
+    ("Metronom\u{2026}", "Metronome\u{2026}"),
+    ("\u{00DC}bernimmt das Tempo des ausgew\u{00E4}hlten Songs", "Uses the tempo of the selected song"),
+    ("Wiederholen", "Review"),
+    ("Tags vergeben\u{2026}", "Tag songs\u{2026}"),
+    ("Als HTML exportieren\u{2026}", "Export as HTML\u{2026}"),
+    ("Als PDF-Mappe exportieren\u{2026}", "Export as PDF binder\u{2026}"),
+    ("PDFs der Liste zu einer Datei zusammenf\u{00FC}gen, z.B. f\u{00FC}r einen Auftritt", "Merge the PDFs in the list into one file, e.g. for a gig"),
+    ("\u{25B6} Alle abspielen", "\u{25B6} Play all"),
+    ("Audio aller Songs der Liste nacheinander abspielen", "Play the audio of every song in the list in turn"),
+    ("Sortierung", "Sort"),
+    ("Titel", "Title"),
+    ("Neueste zuerst", "Newest first"),
+    ("Ohne Tags zuerst", "Untagged first"),
+    ("Bewertung", "Rating"),
+    ("Zuletzt ge\u{00F6}ffnet", "Last opened"),
+    ("Am h\u{00E4}ufigsten verwendet", "Most used"),
+    ("ODER", "OR"),
+    ("UND", "AND"),
+    ("\u{2630} Liste", "\u{2630} List"),
+    ("\u{25A6} Tabelle", "\u{25A6} Table"),
+    ("\u{25A3} Raster", "\u{25A3} Grid"),
+    ("Datei \u{00F6}ffnen", "Open file"),
+    ("Audio abspielen", "Play audio"),
+    ("Details zeigen", "Show details"),
+    ("Schwierigkeit", "Difficulty"),
+    ("Stil", "Style"),
+    ("Technik", "Technique"),
+    ("Stimmung", "Tuning"),
+    ("Kapo", "Capo"),
+    ("Tonart", "Key"),
+    ("Musikordner:", "Music folder:"),
+    ("Ordner \u{00E4}ndern", "Change folder"),
+    ("Musikordner ausw\u{00E4}hlen", "Choose music folder"),
+    ("Audiodateien ohne Chart als eigene Eintr\u{00E4}ge", "List audio files without a chart as entries of their own"),
+    ("Automatisch neu scannen:", "Rescan automatically:"),
+    ("Nie", "Never"),
+    ("Alle N Stunden", "Every N hours"),
+    ("T\u{00E4}glich ab", "Daily from"),
+    (" Uhr", ":00"),
+    ("Sprache:", "Language:"),
+    ("Datum und Zahlen:", "Dates and numbers:"),
+    ("Doppelklick und Enter:", "Double-click and Enter:"),
+    ("Liste", "List"),
+    ("Tabelle", "Table"),
+    ("Raster", "Grid"),
+    ("Speicher f\u{00FC}r Vorschaubilder:", "Thumbnail cache:"),
+    ("Tags verwalten\u{2026}", "Manage tags\u{2026}"),
+    ("CSV importieren\u{2026}", "Import CSV\u{2026}"),
+    ("Zeitmaschine\u{2026}", "Time machine\u{2026}"),
+    ("\u{00D6}ffentliche Seite\u{2026}", "Public site\u{2026}"),
+    ("Im WLAN freigeben\u{2026}", "Share on the local network\u{2026}"),
+    ("\u{00D6}ffnen mit\u{2026}", "Open with\u{2026}"),
+    ("Papierkorb\u{2026}", "Trash\u{2026}"),
+    ("Entfernte Songs\u{2026}", "Removed songs\u{2026}"),
+    ("Ignoriert\u{2026}", "Ignored\u{2026}"),
+    ("Ausschl\u{00FC}sse\u{2026}", "Exclusions\u{2026}"),
+    ("Auto-Tag-Regeln\u{2026}", "Auto-tag rules\u{2026}"),
+    ("Artist-Ordner\u{2026}", "Artist folders\u{2026}"),
+    ("Zusammengeh\u{00F6}rige Dateien\u{2026}", "Related files\u{2026}"),
+    ("Verschl\u{00FC}sselung\u{2026}", "Encryption\u{2026}"),
+    ("Farben\u{2026}", "Colors\u{2026}"),
+    ("Speicherort\u{2026}", "Storage location\u{2026}"),
+    ("Scan-Vorschau\u{2026}", "Scan preview\u{2026}"),
+    ("Wartung\u{2026}", "Maintenance\u{2026}"),
+    ("Protokoll anzeigen\u{2026}", "Show log\u{2026}"),
+    ("Als N\u{00E4}chstes\u{2026}", "Up next\u{2026}"),
+    (
+        "Songs, deren Lernpfad bis hierher als \u{201E}Kann ich\u{201C} markiert ist",
+        "Songs whose learning path up to them is marked \u{201C}Can play\u{201D}",
+    ),
+    ("Dateien", "Files"),
+    ("Noten", "Sheet music"),
+    ("Akkorde", "Chords"),
+    ("\u{00D6}ffnen", "Open"),
+    ("Vom Song l\u{00F6}sen \u{2014} wieder ein eigener Song", "Detach \u{2014} make it a song of its own again"),
+    ("+ Datei\u{2026}", "+ File\u{2026}"),
+    (
+        "Tab, Liedtext oder Aufnahme aus dem Musikordner dazulegen",
+        "Add a tab, lyrics sheet or recording from the music folder",
+    ),
+    ("Passende suchen", "Find matching"),
+    ("Dateien mit gleichem Titel in anderen Ordnern", "Files with the same title in other folders"),
+    ("Nichts Passendes gefunden.", "Nothing matching found."),
+    ("Die Datei liegt nicht im Musikordner.", "The file is not inside the music folder."),
+    ("Lernpfad", "Learning path"),
+    ("Kann ich", "Can play"),
+    ("Noch nicht gelernt", "Not learned yet"),
+    ("Verkn\u{00FC}pfung entfernen", "Remove from path"),
+    ("+ Davor", "+ Before"),
+    ("+ Danach", "+ After"),
+    ("Einen Song, der vorher gelernt werden sollte", "A song to learn before this one"),
+    ("Einen Song, der auf diesen aufbaut", "A song that builds on this one"),
+    ("Davor lernen: Song suchen\u{2026}", "Learn before: search song\u{2026}"),
+    ("Danach lernen: Song suchen\u{2026}", "Learn after: search song\u{2026}"),
+    (
+        "Geht nicht: der Song liegt schon auf der anderen Seite des Pfads.",
+        "Not possible: that song is already on the other side of the path.",
+    ),
+    ("Fassungen", "Arrangements"),
+    ("{} Fassungen", "{} arrangements"),
+    ("Fassungen zeigen", "Show arrangements"),
+    ("Fassungen zuklappen", "Collapse arrangements"),
+    ("\u{00C4}hnlich:", "Similar:"),
+    ("Als Fassung dazunehmen", "Add as an arrangement"),
+    ("+ Fassung", "+ Arrangement"),
+    ("Ein anderes Arrangement desselben Songs", "Another arrangement of the same song"),
+    ("Aus Gruppe l\u{00F6}sen", "Remove from group"),
+    (
+        "Wieder als eigenst\u{00E4}ndiger Song f\u{00FC}hren",
+        "Make it a song of its own again",
+    ),
+    ("Fassung: Song suchen\u{2026}", "Arrangement: search song\u{2026}"),
+    ("Listen:", "Lists:"),
+    ("Neue smarte Liste aus der aktuellen Suche", "New smart list from the current search"),
+    ("Bearbeiten\u{2026}", "Edit\u{2026}"),
+    ("L\u{00F6}schen", "Delete"),
+    ("Tag hinzuf\u{00FC}gen", "Add tag"),
+    ("Zuletzt:", "Recent:"),
+    ("H\u{00E4}ufig:", "Frequent:"),
+    ("Kategorie:", "Category:"),
+    ("Wert:", "Value:"),
+    ("Hinzuf\u{00FC}gen", "Add"),
+    ("Vorhandene Tags:", "Existing tags:"),
+    ("Song bearbeiten", "Edit song"),
+    ("Titel:", "Title:"),
+    ("Tonart:", "Key:"),
+    ("Kapo:", "Capo:"),
+    ("Jahr:", "Year:"),
+    ("leer = nach Dateiendung", "empty = by file extension"),
+    ("W\u{00E4}hlen\u{2026}", "Choose\u{2026}"),
+    ("Kann ich (Repertoire)", "I can play it (repertoire)"),
+    ("Privat (nicht auf der \u{00F6}ffentlichen Seite)", "Private (not on the public site)"),
+    ("Notizen (Markdown):", "Notes (Markdown):"),
+    ("\u{00D6}ffnen mit:", "Open with:"),
+    ("Speichern", "Save"),
+    ("Abbrechen", "Cancel"),
+    ("Tag entfernen?", "Remove tag?"),
+    ("Entfernen", "Remove"),
+    ("Keine Songs gefunden.", "No songs found."),
+    ("Repertoire (kann ich)", "Repertoire (I can play it)"),
+    ("Automatisch \u{2014} Klick zum Entfernen", "Automatic \u{2014} click to remove"),
+    ("Manuell \u{2014} Klick zum Entfernen", "Manual \u{2014} click to remove"),
+    ("Bearbeiten", "Edit"),
+    ("\u{23F1} \u{00DC}ben", "\u{23F1} Practice"),
+    ("Hinzugef\u{00FC}gt", "Added"),
+    ("Schlie\u{00DF}en", "Close"),
+    ("Link entfernen", "Remove link"),
+    ("Link zu Lernvideo, Aufnahme, Tab-Seite, Unterrichtsplan oder Notiz", "Link to a lesson video, recording, tab page, lesson plan or note"),
+    ("Bezeichnung", "Label"),
+    ("obsidian://\u{2026} oder https://\u{2026}", "obsidian://\u{2026} or https://\u{2026}"),
+    ("Anzeigen", "View"),
+    ("Artist, Jahr, Tonart und Stil online nachschlagen", "Look up artist, year, key and style online"),
+    ("Ignorieren", "Ignore"),
+    ("Kein Song \u{2014} aus Liste und Statistik ausblenden", "Not a song \u{2014} hide it from the list and statistics"),
+    ("Notizen", "Notes"),
+    ("Keine Notizen. \u{00DC}ber \u{201E}Bearbeiten\u{201C} hinzuf\u{00FC}gen.", "No notes. Add some with \u{201C}Edit\u{201D}."),
+    ("Im Finder zeigen", "Show in Finder"),
+    ("Im Explorer zeigen", "Show in Explorer"),
+    ("Im Dateimanager zeigen", "Show in file manager"),
+    ("{} Songs gefunden", "{} songs found"),
+    ("Tag \u{201E}{}\u{201C} entfernen?", "Remove tag \u{201C}{}\u{201D}?"),
+    ("\u{00B7} hinzugef\u{00FC}gt {}", "\u{00B7} added {}"),
+    ("\u{00B7} zuletzt ge\u{00F6}ffnet {}", "\u{00B7} last opened {}"),
+    ("{}\u{00D7} verwendet", "used {}\u{00D7}"),
+    ("Hinzugef\u{00FC}gt {}", "Added {}"),
+    (" \u{00B7} zuletzt ge\u{00FC}bt {}", " \u{00B7} last practiced {}"),
+    ("Hinzugef\u{00FC}gt am {}", "Added on {}"),
+    ("Bild", "Image"),
+    ("1 Seite", "1 page"),
+    ("{} Seiten", "{} pages"),
+    (
+        "Freitext sucht in Titel, Artist, Dateiname und Notizen.\n\
+         artist:\"The Beatles\"   titel:yesterday   datei:gitarre   notiz:barr\u{00E9}\n\
+         inhalt:Refrain (Text im PDF)\n\
+         tag:Blues   stil:Jazz   tag:technik:Solo\n\
+         tonart:G   bpm:60-90   bpm:>=120   kapo:<=2   seiten:<=2\n\
+         has:audio   has:tags   is:repertoire   is:favorit   is:privat\n\
+         gespielt:30 (in den letzten 30 Tagen ge\u{00F6}ffnet, geh\u{00F6}rt oder ge\u{00FC}bt)\n\
+         Ein vorangestelltes - schlie\u{00DF}t aus, z.B. -tag:Solo oder -gespielt:30",
+        "Free text searches title, artist, file name and notes.\n\
+         artist:\"The Beatles\"   titel:yesterday   datei:guitar   notiz:barr\u{00E9}\n\
+         inhalt:chorus (text in the PDF)\n\
+         tag:Blues   stil:Jazz   tag:technik:Solo\n\
+         tonart:G   bpm:60-90   bpm:>=120   kapo:<=2   pages:<=2\n\
+         has:audio   has:tags   is:repertoire   is:favorit   is:privat\n\
+         played:30 (opened, listened to or practiced in the last 30 days)\n\
+         A leading - excludes, e.g. -tag:Solo or -played:30",
+    ),
+    // Player bar
+    ("Weiter", "Resume"),
+    ("Vorheriger Song", "Previous song"),
+    ("N\u{00E4}chster Song", "Next song"),
+    ("Pegel dieses Songs, z.\u{00A0}B. f\u{00FC}r zu laute Backing-Tracks", "Level of this song, e.g. for backing tracks that are too loud"),
+    ("Lautst\u{00E4}rke", "Volume"),
+    ("Halbt\u{00F6}ne", "Semitones"),
+    ("Tempo \u{2013} die Tonh\u{00F6}he bleibt gleich. Doppelklick setzt auf 100\u{00A0}% zur\u{00FC}ck.", "Tempo \u{2013} the pitch stays the same. Double-click resets to 100\u{00A0}%."),
+    ("Tonh\u{00F6}he in Halbt\u{00F6}nen \u{2013} das Tempo bleibt gleich. Wird mit dem Song gespeichert, Doppelklick setzt zur\u{00FC}ck.", "Pitch in semitones \u{2013} the tempo stays the same. Saved with the song; double-click resets it."),
+    ("Merken", "Keep"),
+    ("Schleife mit dem Song speichern", "Save the loop with the song"),
+    ("Schleife aufheben", "Clear the loop"),
+    ("Schleifenende an der aktuellen Stelle", "Loop end at the current position"),
+    ("Schleifenanfang an der aktuellen Stelle", "Loop start at the current position"),
+    // Scan report
+    ("Scan-Bericht", "Scan report"),
+    ("{} neu \u{00B7} {} entfernt \u{00B7} {} verschoben \u{00B7} {} mit neuem Audio", "{} new \u{00B7} {} removed \u{00B7} {} moved \u{00B7} {} with new audio"),
+    ("\u{26A0} {} von {} Songs sind nicht mehr an ihrem Platz. Wurde ein Ordner umbenannt oder ein Laufwerk getrennt? Die alten Eintr\u{00E4}ge liegen mit Tags und Notizen im Papierkorb.", "\u{26A0} {} of {} songs are no longer where they were. Was a folder renamed or a drive disconnected? The old entries are in the trash with their tags and notes."),
+    ("Neu", "New"),
+    ("Entfernt", "Removed"),
+    ("Verschoben", "Moved"),
+    ("Gleicher Dateiname in einem anderen Ordner. Der neue Eintrag beginnt ohne Tags; der alte liegt im Papierkorb.", "Same file name in another folder. The new entry starts without tags; the old one is in the trash."),
+    ("In der Liste zeigen", "Show in the list"),
+    ("Neues Audio", "New audio"),
+    ("Papierkorb \u{00F6}ffnen", "Open the trash"),
+    // Main window, remaining
+    ("Kapo {}", "Capo {}"),
+    ("{} von 5", "{} of 5"),
+    ("Backing-Tracks, die zu keinem Song geh\u{00F6}ren, erscheinen mit Typ \u{201E}Audio\u{201C} in der Liste.", "Backing tracks that belong to no song show up in the list with type \u{201C}Audio\u{201D}."),
+    ("F\u{00E4}ngt \u{00C4}nderungen auf, die der Datei-W\u{00E4}chter w\u{00E4}hrend des Ruhezustands verpasst hat.", "Catches changes the file watcher missed while the computer was asleep."),
+    ("#stil/Blues #kapo/2 #anf\u{00E4}nger", "#stil/Blues #kapo/2 #beginner"),
+    ("z.B. ## Knifflige Takte\n- T. 12: Barr\u{00E9} vorbereiten", "e.g. ## Tricky bars\n- Bar 12: prepare the barr\u{00E9}"),
+    // Auto-tag rules
+    ("Auto-Tag-Regeln", "Auto-tag rules"),
+    ("Enth\u{00E4}lt der Pfad eines neuen Songs das Muster, bekommt er den Tag. Ohne \u{201E}A=a\u{201C} z\u{00E4}hlt die Gro\u{00DF}-/Kleinschreibung. Die Regeln werden von oben nach unten gepr\u{00FC}ft.", "A new song whose path contains the pattern gets the tag. Without \u{201C}A=a\u{201D} the case matters. The rules are checked from top to bottom."),
+    ("Muster", "Pattern"),
+    ("Wort", "Word"),
+    ("Suchen in", "Look in"),
+    ("Nur unter", "Only under"),
+    ("Kategorie", "Category"),
+    ("Wert", "Value"),
+    ("Ersetzen", "Replace"),
+    ("Stopp", "Stop"),
+    ("Fr\u{00FC}her pr\u{00FC}fen", "Check earlier"),
+    ("Sp\u{00E4}ter pr\u{00FC}fen", "Check later"),
+    ("Gro\u{00DF}-/Kleinschreibung egal", "Ignore case"),
+    ("Nur als ganzes Wort: \u{201E}Blues\u{201C} passt nicht auf \u{201E}Bluestone\u{201C}", "Whole word only: \u{201C}Blues\u{201D} does not match \u{201C}Bluestone\u{201D}"),
+    ("Ordner", "Folder"),
+    ("Nur Songs in diesem Ordner (relativ zum Musikordner)", "Only songs in this folder (relative to the music folder)"),
+    ("Ersetzt Tags dieser Kategorie aus fr\u{00FC}heren Regeln, beim Neu-Taggen auch von Hand gesetzte", "Replaces tags of this category from earlier rules, and when retagging also those set by hand"),
+    ("Trifft die Regel zu, werden die folgenden nicht mehr gepr\u{00FC}ft", "If the rule matches, the following ones are not checked"),
+    ("Regel l\u{00F6}schen", "Delete rule"),
+    ("+ Regel", "+ Rule"),
+    ("Exportieren\u{2026}", "Export\u{2026}"),
+    ("Die Regeln als TOML- oder JSON-Datei speichern, z.B. zum Weitergeben", "Save the rules as a TOML or JSON file, e.g. to share them"),
+    ("Auto-Tag-Regeln exportieren", "Export auto-tag rules"),
+    ("auto-tag-regeln.toml", "auto-tag-rules.toml"),
+    ("{} Regeln exportiert", "{} rules exported"),
+    ("Export fehlgeschlagen: {}", "Export failed: {}"),
+    ("Importieren\u{2026}", "Import\u{2026}"),
+    ("Regeln aus einer exportierten Datei \u{00FC}bernehmen", "Take over rules from an exported file"),
+    ("Auto-Tag-Regeln importieren", "Import auto-tag rules"),
+    ("Regeln", "Rules"),
+    ("Import fehlgeschlagen: {}", "Import failed: {}"),
+    ("Standardregeln", "Default rules"),
+    ("Die mitgelieferten Ordner-Regeln (E-Gitarre, Blues, Kinderlieder \u{2026}) \u{00FC}bernehmen", "Take over the bundled folder rules (electric guitar, blues, children\u{2019}s songs \u{2026})"),
+    ("Liste ersetzen", "Replace list"),
+    ("Anh\u{00E4}ngen", "Append"),
+    ("Doppelte \u{00FC}berspringen", "Skip duplicates"),
+    ("Wie Import und Standardregeln zur Liste kommen. Doppelt ist eine Regel mit gleichem Muster, Bereich und Tag.", "How imported and default rules join the list. A duplicate is a rule with the same pattern, scope and tag."),
+    ("{} Regeln \u{00FC}bernommen \u{2013} \u{201E}Speichern\u{201C} macht sie wirksam", "{} rules taken over \u{2013} \u{201C}Save\u{201D} puts them into effect"),
+    ("Neu taggen w\u{00FC}rde nichts \u{00E4}ndern", "Retagging would change nothing"),
+    ("Neu taggen w\u{00FC}rde 1 Song \u{00E4}ndern:", "Retagging would change 1 song:"),
+    ("Neu taggen w\u{00FC}rde {} Songs \u{00E4}ndern:", "Retagging would change {} songs:"),
+    ("Speichern und neu taggen", "Save and retag"),
+    ("Speichern gilt f\u{00FC}r neu gefundene Songs. Neu taggen \u{00E4}ndert nur Tags, die eine Regel vergeben hat; von Hand gesetzte bleiben, au\u{00DF}er eine Regel mit \u{201E}Ersetzen\u{201C} trifft zu.", "Saving applies to newly found songs. Retagging only changes tags a rule gave; those set by hand stay unless a rule with \u{201C}Replace\u{201D} matches."),
+    ("Ganzer Pfad", "Whole path"),
+    ("Ein Ordner", "Any folder"),
+    ("Direkter Ordner", "Parent folder"),
+    ("Ordnerebene", "Folder level"),
+    ("Ganzer Pfad: auch im Dateinamen. Ein Ordner: irgendein Ordnername. Direkter Ordner: nur der Ordner der Datei. Ordnerebene: nur der Ordner in dieser Tiefe (1 = oberste Ebene).", "Whole path: also in the file name. Any folder: any folder name. Parent folder: only the folder of the file. Folder level: only the folder at this depth (1 = top level)."),
+    // Maintenance
+    ("Wartung", "Maintenance"),
+    ("Bibliothek: {}, davon ungenutzt {}", "Library: {}, of which unused {}"),
+    ("Komprimieren", "Compact"),
+    ("VACUUM: schreibt die Datei ohne ungenutzten Platz neu. Dauert bei gro\u{00DF}en Bibliotheken einige Sekunden.", "VACUUM: rewrites the file without unused space. Takes a few seconds for large libraries."),
+    ("WAL zur\u{00FC}ckschreiben", "Write back WAL"),
+    ("Schreibt das Write-Ahead-Log in die Datei zur\u{00FC}ck und leert es, z.\u{00A0}B. vor dem Kopieren der Datei von Hand.", "Writes the write-ahead log back into the file and empties it, e.g. before copying the file by hand."),
+    ("Integrit\u{00E4}t pr\u{00FC}fen", "Check integrity"),
+    ("PRAGMA integrity_check: sucht besch\u{00E4}digte Seiten und Indizes.", "PRAGMA integrity_check: looks for damaged pages and indexes."),
+    ("Verwaiste Eintr\u{00E4}ge suchen", "Find orphaned entries"),
+    ("Tag-Zuordnungen, \u{00DC}bungszeiten, Links und Ereignisse ohne Song oder Tag sowie Tags ohne Songs.", "Tag assignments, practice times, links and events without a song or tag, and tags without songs."),
+    ("Keine verwaisten Eintr\u{00E4}ge.", "No orphaned entries."),
+    ("{} ohne Song oder Tag", "{} without song or tag"),
+    ("{} ohne Songs", "{} without songs"),
+    ("Komprimiert: {} \u{2192} {}", "Compacted: {} \u{2192} {}"),
+    ("Komprimieren fehlgeschlagen: {}", "Compacting failed: {}"),
+    ("Die Bibliothek nutzt kein WAL.", "The library does not use a WAL."),
+    ("WAL nur teilweise zur\u{00FC}ckgeschrieben ({} von {} Seiten); ein Hintergrundjob liest gerade. Sp\u{00E4}ter nochmal versuchen.", "WAL only partly written back ({} of {} pages); a background job is reading. Try again later."),
+    ("WAL zur\u{00FC}ckgeschrieben: {} Seiten.", "WAL written back: {} pages."),
+    ("Checkpoint fehlgeschlagen: {}", "Checkpoint failed: {}"),
+    ("Integrit\u{00E4}tspr\u{00FC}fung: keine Fehler.", "Integrity check: no errors."),
+    ("Integrit\u{00E4}tspr\u{00FC}fung: {} Fehler. Ein Backup aus der Zeitmaschine oder dem Cloud-Backup zur\u{00FC}ckspielen.", "Integrity check: {} errors. Restore a backup from the time machine or the cloud backup."),
+    ("\n\u{2026} und {} weitere", "\n\u{2026} and {} more"),
+    ("Integrit\u{00E4}tspr\u{00FC}fung fehlgeschlagen: {}", "Integrity check failed: {}"),
+    ("{} verwaiste Eintr\u{00E4}ge gefunden.", "{} orphaned entries found."),
+    ("Suche fehlgeschlagen: {}", "Search failed: {}"),
+    ("{} verwaiste Eintr\u{00E4}ge entfernt.", "{} orphaned entries removed."),
+    ("Entfernen fehlgeschlagen: {}", "Removing failed: {}"),
+    // Statistics
+    ("\u{00DC}bersicht", "Overview"),
+    ("Tag-Kombinationen", "Tag combinations"),
+    ("Lehrplan-Abdeckung\u{2026}", "Curriculum coverage\u{2026}"),
+    ("Neu berechnen", "Recalculate"),
+    ("Songs pro Wert", "Songs per value"),
+    ("{}: {} Songs", "{}: {} songs"),
+    ("Neu pro Monat", "New per month"),
+    ("Audio-Abdeckung pro Stil", "Audio coverage per style"),
+    ("{}: {} von {} mit Audio", "{}: {} of {} with audio"),
+    ("{}: {} von {} ohne Audio", "{}: {} of {} without audio"),
+    ("H\u{00E4}ufigste Tags", "Most used tags"),
+    ("{}: {}, {} Songs", "{}: {}, {} songs"),
+    ("Nie ge\u{00F6}ffnet ({})", "Never opened ({})"),
+    ("Z\u{00E4}hlt nur \u{00D6}ffnen aus Songindex heraus. Klick \u{00F6}ffnet den Song.", "Only counts opening from Songindex. Click to open the song."),
+    ("F\u{00FC}r diese Kategorien gibt es noch keine Tags.", "There are no tags in these categories yet."),
+    ("Rot umrandet: Kombinationen ohne einen einzigen Song.", "Outlined in red: combinations without a single song."),
+    ("Noch keine Daten.", "No data yet."),
+    ("{} \u{00D7} {}: {} Songs", "{} \u{00D7} {}: {} songs"),
+    // Scan preview
+    ("Scan-Vorschau", "Scan preview"),
+    ("Zeigt, was ein Scan dieses Ordners an der Bibliothek \u{00E4}ndern w\u{00FC}rde. Es wird nichts gespeichert.", "Shows what a scan of this folder would change in the library. Nothing is saved."),
+    ("Anderer Ordner\u{2026}", "Other folder\u{2026}"),
+    ("Ordner wird durchsucht\u{2026}", "Searching the folder\u{2026}"),
+    ("Ein Scan w\u{00FC}rde nichts \u{00E4}ndern.", "A scan would change nothing."),
+    ("{} neu \u{00B7} {} zur\u{00FC}ck \u{00B7} {} entfernt \u{00B7} {} verschoben", "{} new \u{00B7} {} back \u{00B7} {} removed \u{00B7} {} moved"),
+    ("{} von {} Songs k\u{00E4}men mit Tags und Notizen in den Papierkorb.", "{} of {} songs would go to the trash with their tags and notes."),
+    ("Zur\u{00FC}ck aus dem Papierkorb", "Back from the trash"),
+    ("Gleicher Dateiname in einem anderen Ordner. Der neue Eintrag bekommt nur die Tags der Regeln.", "Same file name in another folder. The new entry only gets the tags from the rules."),
+    ("Ordner f\u{00FC}r die Vorschau ausw\u{00E4}hlen", "Choose a folder for the preview"),
+    // Tag manager
+    ("Tags verwalten", "Manage tags"),
+    ("Filter:", "Filter:"),
+    ("Vorlagen\u{2026}", "Templates\u{2026}"),
+    ("Stufen\u{2026}", "Levels\u{2026}"),
+    ("Reihenfolge der Schwierigkeitswerte f\u{00FC}r Sortierung und Filter", "Order of the difficulty values for sorting and filtering"),
+    ("Schema importieren\u{2026}", "Import schema\u{2026}"),
+    ("Kategorien, Werte und Farben aus einer exportierten Datei \u{00FC}bernehmen", "Take over categories, values and colors from an exported file"),
+    ("Tag-Schema importieren", "Import tag schema"),
+    ("{} neue Tags importiert", "{} new tags imported"),
+    ("Schema exportieren\u{2026}", "Export schema\u{2026}"),
+    ("Kategorien, Werte und Farben ohne Songs speichern, z.B. zum Weitergeben", "Save categories, values and colors without songs, e.g. to share them"),
+    ("Tag-Schema exportieren", "Export tag schema"),
+    ("{} Tags exportiert", "{} tags exported"),
+    ("Umbenennen", "Rename"),
+    ("Ziel w\u{00E4}hlen", "Choose target"),
+    ("Zusammenf\u{00FC}hren", "Merge"),
+    ("Alle Songs dieses Tags einem anderen Tag zuweisen", "Move all songs of this tag to another tag"),
+    ("Tag l\u{00F6}schen?", "Delete tag?"),
+    ("Tag \u{201E}{}\u{201C} bei {} Songs entfernen und l\u{00F6}schen?", "Remove tag \u{201C}{}\u{201D} from {} songs and delete it?"),
+    // Cloud backup
+    ("Cloud-Backup", "Cloud backup"),
+    ("Cloud-Backup\u{2026}", "Cloud backup\u{2026}"),
+    ("Hochgeladen als {}", "Uploaded as {}"),
+    ("Automatisch sichern", "Back up automatically"),
+    ("S3-kompatibel", "S3 compatible"),
+    ("Benutzer:", "User:"),
+    ("Passwort:", "Password:"),
+    ("Verschl\u{00FC}sselung:", "Encryption:"),
+    ("Intervall:", "Interval:"),
+    ("Ohne das Verschl\u{00FC}sselungspasswort lassen sich die Sicherungen nicht wiederherstellen.", "Without the encryption password the backups cannot be restored."),
+    ("Letzte Sicherung {}: {}", "Last backup {}: {}"),
+    ("Letzte Sicherung: {}", "Last backup: {}"),
+    ("Noch keine Sicherung hochgeladen.", "No backup uploaded yet."),
+    ("Letzter Fehler: {}", "Last error: {}"),
+    ("Jetzt sichern", "Back up now"),
+    ("Backup entschl\u{00FC}sseln\u{2026}", "Decrypt backup\u{2026}"),
+    ("Gespeichert.", "Saved."),
+    ("Sicherung ausw\u{00E4}hlen", "Choose a backup"),
+    ("Entschl\u{00FC}sselte Datenbank speichern", "Save the decrypted database"),
+    ("Gespeichert unter {}", "Saved to {}"),
+    ("Verschl\u{00FC}sselung fehlgeschlagen", "Encryption failed"),
+    ("Keine Songindex-Sicherung", "Not a Songindex backup"),
+    ("Falsches Passwort oder besch\u{00E4}digte Datei", "Wrong password or damaged file"),
+    ("Kein Backup-Passwort gesetzt", "No backup password set"),
+    ("Snapshot konnte nicht erstellt werden", "The snapshot could not be created"),
+    ("Backup hochgeladen: {}", "Backup uploaded: {}"),
+    // Storage location
+    ("Speicherort", "Storage location"),
+    ("Portabel: Einstellungen, Bibliothek und Caches liegen im Ordner \u{201E}songindex-daten\u{201C} neben dem Programm.", "Portable: settings, library and caches are in the folder \u{201C}songindex-daten\u{201D} next to the program."),
+    ("Einstellungen und Caches liegen im Datenordner. F\u{00FC}r den Betrieb vom USB-Stick legt \u{201E}songindex portable\u{201C} im Terminal den Ordner \u{201E}songindex-daten\u{201C} neben dem Programm an.", "Settings and caches are in the data folder. To run from a USB stick, \u{201C}songindex portable\u{201D} in the terminal creates the folder \u{201C}songindex-daten\u{201D} next to the program."),
+    ("Datenordner", "Data folder"),
+    ("Bibliothek", "Library"),
+    ("In der Demo-Bibliothek nicht verf\u{00FC}gbar.", "Not available in the demo library."),
+    ("Verschieben\u{2026}", "Move\u{2026}"),
+    ("Kopiert die Bibliothek an den neuen Ort und beendet Songindex; beim n\u{00E4}chsten Start wird die Kopie ge\u{00F6}ffnet. Die alte Datei bleibt liegen.", "Copies the library to the new place and quits Songindex; the next start opens the copy. The old file stays where it is."),
+    ("Bibliothek verschieben", "Move library"),
+    ("Songindex-Bibliothek", "Songindex library"),
+    ("Andere \u{00F6}ffnen\u{2026}", "Open another\u{2026}"),
+    ("Eine vorhandene Bibliothek ab dem n\u{00E4}chsten Start verwenden.", "Use an existing library from the next start on."),
+    ("Bibliothek \u{00F6}ffnen", "Open library"),
+    ("Standardort", "Default location"),
+    ("Ab dem n\u{00E4}chsten Start wieder {} im Datenordner verwenden.", "Use {} in the data folder again from the next start on."),
+    ("Ab dem n\u{00E4}chsten Start: {}", "From the next start on: {}"),
+    ("Jetzt beenden", "Quit now"),
+    ("{} gibt es schon; bitte einen anderen Namen w\u{00E4}hlen.", "{} already exists; please choose another name."),
+    ("Kopieren fehlgeschlagen: {}", "Copying failed: {}"),
+    // Windows
+    ("Artist-Ordner", "Artist folders"),
+    ("Ordner mit mehreren PDFs ohne \u{201E}Artist - Titel\u{201C} im Dateinamen sind vermutlich nach dem Artist benannt. Der Ordnername wird als Artist f\u{00FC}r die Songs darin ohne Artist vorgeschlagen.", "Folders with several PDFs without \u{201C}Artist - Title\u{201D} in the file name are probably named after the artist. The folder name is suggested as the artist for the songs in it that have none."),
+    ("Keine Vorschl\u{00E4}ge.", "No suggestions."),
+    ("Artist \u{00FC}bernehmen", "Apply artist"),
+    ("Aus Zwischenablage importieren", "Import from clipboard"),
+    ("Ordner:", "Folder:"),
+    ("Akkorde / Text:", "Chords / lyrics:"),
+    ("Erneut einf\u{00FC}gen", "Paste again"),
+    ("Zusammengeh\u{00F6}rige Dateien", "Related files"),
+    ("Dateien mit gleichem Titel in verschiedenen Ordnern, z.\u{00A0}B. Noten, Tab und Aufnahme eines Songs. Angehakte Dateien werden dem Song oben zugeordnet; waren sie eigene Songs, gehen deren Tags, Links und Notizen mit.", "Files with the same title in different folders, e.g. sheet music, tab and recording of one song. Checked files are attached to the song above; if they were songs of their own, their tags, links and notes come along."),
+    ("Bisher ein eigener Song; er wird mit diesem zusammengelegt.", "Currently a song of its own; it will be merged into this one."),
+    ("Lehrplan-Abdeckung", "Curriculum coverage"),
+    ("Lehrplan bearbeiten\u{2026}", "Edit curriculum\u{2026}"),
+    ("Neu laden", "Reload"),
+    ("Als PDF exportieren\u{2026}", "Export as PDF\u{2026}"),
+    ("Abdeckungsbericht speichern", "Save coverage report"),
+    ("Fertigkeit", "Skill"),
+    ("Schwierigkeitsstufen", "Difficulty levels"),
+    ("Vom leichtesten zum schwersten. Die Reihenfolge gilt f\u{00FC}r die Sortierung \u{201E}Schwierigkeit\u{201C} und den Filter; Werte ohne Stufe kommen dort zuletzt.", "From easiest to hardest. The order applies to the \u{201C}Difficulty\u{201D} sorting and the filter; values without a level come last there."),
+    ("Noch keine Werte in der Kategorie Schwierigkeit.", "No values in the difficulty category yet."),
+    ("Aus der Skala nehmen", "Remove from the scale"),
+    ("Einordnen", "Add to scale"),
+    ("Als schwerste Stufe anh\u{00E4}ngen", "Append as the hardest level"),
+    ("Bibliothek verschl\u{00FC}sseln", "Encrypt library"),
+    ("Die Datenbank ist mit SQLCipher verschl\u{00FC}sselt.", "The database is encrypted with SQLCipher."),
+    ("Das Passwort liegt im Schl\u{00FC}sselbund; beim Start wird nicht gefragt.", "The password is stored in the keychain; it is not asked for at startup."),
+    ("Aus Schl\u{00FC}sselbund entfernen", "Remove from keychain"),
+    ("Das Passwort wird bei jedem Start abgefragt.", "The password is asked for at every start."),
+    ("Ohne das Passwort l\u{00E4}sst sich die Bibliothek nicht mehr \u{00F6}ffnen.", "Without the password the library can no longer be opened."),
+    ("Wiederholen:", "Repeat:"),
+    ("Im Schl\u{00FC}sselbund speichern", "Save in keychain"),
+    ("Verschl\u{00FC}sseln", "Encrypt"),
+    ("Als HTML exportieren", "Export as HTML"),
+    ("Zum Drucken oder Verschicken", "For printing or sending"),
+    ("Helles Layout mit Nummern, ohne Suchfeld", "Light layout with numbers, without search field"),
+    ("Titel mit den Dateien verlinken", "Link titles to the files"),
+    ("Basis-URL:", "Base URL:"),
+    ("leer = relativ zum Musikordner", "empty = relative to the music folder"),
+    ("Speichern\u{2026}", "Save\u{2026}"),
+    ("Repertoire-Seite speichern", "Save repertoire page"),
+    ("Ausschl\u{00FC}sse", "Exclusions"),
+    ("Ein Muster pro Zeile, relativ zum Musikordner. * passt auf beliebige Zeichen (auch in Unterordnern), ** auf beliebig viele Ordner.", "One pattern per line, relative to the music folder. * matches any characters (also in subfolders), ** any number of folders."),
+    ("**/Archiv/**\n*_old.pdf", "**/Archive/**\n*_old.pdf"),
+    ("\u{00DC}bernehmen und neu scannen", "Apply and rescan"),
+    ("Ausgeschlossene Songs landen im Papierkorb und kommen mit ihren Tags zur\u{00FC}ck, sobald das Muster entfernt wird.", "Excluded songs go to the trash and come back with their tags once the pattern is removed."),
+    ("Ignorierte Dateien", "Ignored files"),
+    ("Dateien, die keine Songs sind. Sie tauchen weder in der Liste noch in der Statistik auf, bleiben aber beim Scan erkannt.", "Files that are not songs. They appear neither in the list nor in the statistics, but the scan still recognizes them."),
+    ("Keine ignorierten Dateien.", "No ignored files."),
+    ("Einpassen", "Fit"),
+    ("Bild wird vorbereitet\u{2026}", "Preparing image\u{2026}"),
+    ("PDFs werden zusammengef\u{00FC}gt\u{2026}", "Merging PDFs\u{2026}"),
+    ("Bild konnte nicht umgewandelt werden.", "The image could not be converted."),
+    ("CSV-Datei ausw\u{00E4}hlen", "Choose CSV file"),
+    ("CSV importieren", "Import CSV"),
+    ("Schliessen", "Close"),
+    ("Importieren", "Import"),
+    ("Titel- oder Dateiname-Spalte n\u{00F6}tig", "A title or file name column is required"),
+    ("Im WLAN freigeben", "Share on Wi-Fi"),
+    ("Sch\u{00FC}ler im selben WLAN k\u{00F6}nnen die Bibliothek im Browser durchsuchen und PDFs und Audio \u{00F6}ffnen, ohne etwas zu installieren. Nur lesen; als privat markierte Songs, Notizen und Bewertungen bleiben drau\u{00DF}en.", "Students on the same Wi-Fi can search the library in the browser and open PDFs and audio without installing anything. Read only; songs marked private, notes and ratings stay out."),
+    ("Freigabe an", "Sharing on"),
+    ("Kopieren", "Copy"),
+    ("Diese Adresse im Browser des Handys eingeben.", "Enter this address in the phone\u{2019}s browser."),
+    ("Gro\u{00DF}e Bibliothek", "Large library"),
+    ("Nicht mehr warnen", "Don\u{2019}t warn again"),
+    ("Ausschlie\u{00DF}en legt ein Muster unter \u{201E}Ausschl\u{00FC}sse\u{2026}\u{201C} an und scannt neu; die Songs landen im Papierkorb.", "Exclude adds a pattern under \u{201C}Exclusions\u{2026}\u{201D} and rescans; the songs go to the trash."),
+    ("Als N\u{00E4}chstes", "Up next"),
+    ("Songs, bei denen alles davor im Lernpfad als \u{201E}Kann ich\u{201C} markiert ist, leichteste zuerst.", "Songs whose predecessors in the learning path are all marked \u{201C}Can play\u{201D}, easiest first."),
+    ("Gerade nichts bereit. Lernpfade entstehen im Detailbereich eines Songs mit \u{201E}+ Davor\u{201C} und \u{201E}+ Danach\u{201C}.", "Nothing ready right now. Learning paths are built in a song\u{2019}s detail pane with \u{201C}+ Before\u{201D} and \u{201C}+ After\u{201D}."),
+    ("Ins Repertoire aufnehmen", "Add to repertoire"),
+    ("Protokoll", "Log"),
+    ("Was Scan, \u{00DC}berwachung, Player und Hintergrundaufgaben gemeldet haben, neueste unten. Zeiten in UTC.", "What the scan, watcher, player and background tasks reported, newest at the bottom. Times in UTC."),
+    ("Alles", "Everything"),
+    ("Warnungen und Fehler", "Warnings and errors"),
+    ("Nur Fehler", "Errors only"),
+    ("Filtern, z.\u{202F}B. Watcher", "Filter, e.g. watcher"),
+    ("Keine Eintr\u{00E4}ge.", "No entries."),
+    ("Metronom", "Metronome"),
+    ("Im Takt klicken, um das Tempo zu messen", "Click in time to measure the tempo"),
+    ("Takt", "Meter"),
+    ("Betonung auf 1", "Accent on 1"),
+    ("Sucht den Song online bei musicbrainz.org. \u{00DC}bernommen wird nur, was unten angehakt ist.", "Looks the song up online at musicbrainz.org. Only what is checked below is applied."),
+    ("Suchen", "Search"),
+    ("Nichts gefunden.", "Nothing found."),
+    ("Tonart und Stil werden geladen\u{2026}", "Loading key and style\u{2026}"),
+    ("Genres bei MusicBrainz, meistgenanntes zuerst", "Genres on MusicBrainz, most named first"),
+    ("\u{00DC}bernehmen", "Apply"),
+    ("Neues Chart anlegen", "Create new chart"),
+    ("Vorlage:", "Template:"),
+    ("Anlegen", "Create"),
+    ("\u{00C4}ndern", "Change"),
+    ("Zielordner ausw\u{00E4}hlen", "Choose target folder"),
+    ("Obsidian-Export", "Obsidian export"),
+    ("Eine Markdown-Notiz pro Song mit Tags, Tonart und Links im Frontmatter und einem Link auf die Noten \u{2013} zum Verlinken aus deinem Unterrichts-Vault. Die Notizen geh\u{00F6}ren Songindex und werden beim Abgleich \u{00FC}berschrieben.", "One Markdown note per song with tags, key and links in the front matter and a link to the sheet music \u{2013} for linking from your teaching vault. The notes belong to Songindex and are overwritten when syncing."),
+    ("Ordner im Obsidian-Vault", "Folder in the Obsidian vault"),
+    ("Automatisch aktuell halten", "Keep up to date automatically"),
+    ("Gleicht die Notizen im Hintergrund ab, sobald sich die Bibliothek \u{00E4}ndert.", "Syncs the notes in the background whenever the library changes."),
+    ("Jetzt exportieren", "Export now"),
+    ("\u{00D6}ffnen mit", "Open with"),
+    ("Welches Programm eine Dateiendung \u{00F6}ffnet, z.\u{00A0}B. .gp in Guitar Pro und .pdf in forScore. Ohne Eintrag gilt die Vorgabe von macOS; einzelne Songs lassen sich unter \u{201E}Bearbeiten\u{201C} abweichend einstellen.", "Which program opens a file extension, e.g. .gp in Guitar Pro and .pdf in forScore. Without an entry the macOS default applies; single songs can be set differently under \u{201C}Edit\u{201D}."),
+    ("+ Zuordnung", "+ Mapping"),
+    ("Als PDF-Mappe exportieren", "Export as PDF book"),
+    ("Deckblatt mit Songs und Tonarten", "Cover page with songs and keys"),
+    ("PDF-Mappe speichern", "Save PDF book"),
+    ("\u{23F1} \u{00DC}ben:", "\u{23F1} Practice:"),
+    ("Zur\u{00FC}cksetzen", "Reset"),
+    ("Entfernte Songs", "Removed songs"),
+    ("Jeder Song, den der Scan wegen einer fehlenden Datei entfernt hat, mit seinen Tags \u{2013} auch nachdem der Papierkorb geleert wurde. \u{201E}Wiederherstellen\u{201C} holt Titel, Artist, Notizen und Tags zur\u{00FC}ck, sobald die Datei wieder am selben Ort liegt.", "Every song the scan removed because its file was missing, with its tags \u{2013} even after the trash was emptied. \u{201C}Restore\u{201D} brings back title, artist, notes and tags once the file is back in the same place."),
+    ("Noch keine Songs entfernt.", "No songs removed yet."),
+    ("Zur\u{00FC}ckgekehrte ausblenden", "Hide returned ones"),
+    ("Erstes Wiederholungsintervall f\u{00FC}r neue \u{201E}Kann ich\u{201C}-Songs", "First review interval for new \u{201C}Can play\u{201D} songs"),
+    ("Intervall zur\u{00FC}cksetzen", "Reset interval"),
+    ("Intervall verdoppeln", "Double interval"),
+    ("\u{00DC}be-Session", "Practice session"),
+    ("Am wenigsten ge\u{00FC}bte Songs zuerst", "Least practiced songs first"),
+    ("\u{25B6} Starten", "\u{25B6} Start"),
+    ("\u{23ED} Weiter", "\u{23ED} Next"),
+    ("Beenden", "Finish"),
+    ("Session beendet.", "Session finished."),
+    ("Eine gespeicherte Suche, die als eigene Liste erscheint und sich mit der Bibliothek aktualisiert. Zum Beispiel: schwierigkeit:Anf\u{00E4}nger has:audio -gespielt:30", "A saved search that appears as a list of its own and updates with the library. For example: schwierigkeit:Anf\u{00E4}nger has:audio -gespielt:30"),
+    ("z.\u{00A0}B. Wieder mal spielen", "e.g. Play again soon"),
+    ("Regel", "Rule"),
+    ("Wirklich l\u{00F6}schen?", "Really delete?"),
+    ("Ja", "Yes"),
+    ("Nein", "No"),
+    ("Zeitmaschine", "Time machine"),
+    ("Noch keine Snapshots vorhanden.", "No snapshots yet."),
+    ("Stand vom:", "State of:"),
+    ("Einzelner Song", "Single song"),
+    ("Tag-Kategorie", "Tag category"),
+    ("Snapshot konnte nicht gelesen werden.", "The snapshot could not be read."),
+    ("Suche:", "Search:"),
+    ("Diesen Song wiederherstellen", "Restore this song"),
+    ("Die Datei ist nicht mehr im Index.", "The file is no longer in the index."),
+    ("Kategorie wiederherstellen", "Restore category"),
+    ("Tag-Vorlagen", "Tag templates"),
+    ("Statt mit einer leeren Tag-Liste zu beginnen, k\u{00F6}nnen Kategorien und Werte aus einer Vorlage \u{00FC}bernommen werden. Songs werden dabei nicht ver\u{00E4}ndert.", "Instead of starting with an empty tag list, categories and values can be taken from a template. Songs are not changed."),
+    ("Farben", "Colors"),
+    ("\u{00C4}nderungen wirken sofort. Die Schrift ist hell, der Hintergrund sollte also dunkel bleiben; Fl\u{00E4}chen und R\u{00E4}nder sind Abstufungen davon.", "Changes apply immediately. The text is light, so the background should stay dark; surfaces and borders are shades of it."),
+    ("Standard", "Default"),
+    ("Transponieren", "Transpose"),
+    ("Zieltonart:", "Target key:"),
+    ("Papierkorb", "Trash"),
+    ("Songs, deren Datei verschwunden ist. Tags und Notizen bleiben erhalten; taucht die Datei wieder auf, ist der Song automatisch zur\u{00FC}ck.", "Songs whose file has disappeared. Tags and notes are kept; if the file shows up again, the song is back automatically."),
+    ("Der Papierkorb ist leer.", "The trash is empty."),
+    ("Papierkorb leeren\u{2026}", "Empty trash\u{2026}"),
+    ("Endg\u{00FC}ltig l\u{00F6}schen", "Delete permanently"),
+    ("Fehlt die Datei noch, landet der Song beim n\u{00E4}chsten Scan wieder hier.", "If the file is still missing, the song lands here again at the next scan."),
+    ("Tags vergeben", "Assign tags"),
+    ("Noch keine Tags vorhanden \u{2014} lege zuerst ein paar im Tag-Dialog an.", "No tags yet \u{2014} create a few in the tag dialog first."),
+    ("Tasten 1\u{2013}0 und Buchstaben schalten Tags um \u{00B7} Enter/\u{2192} weiter \u{00B7} \u{2190} zur\u{00FC}ck \u{00B7} Esc schlie\u{00DF}t", "Keys 1\u{2013}0 and letters toggle tags \u{00B7} Enter/\u{2192} next \u{00B7} \u{2190} back \u{00B7} Esc closes"),
+    ("Aufw\u{00E4}rmen heute", "Warm-up today"),
+    ("Keine Songs mit \u{201E}Technik\u{201C}-Tags gefunden.", "No songs with \u{201C}technique\u{201D} tags found."),
+    ("Optionen", "Options"),
+    ("Beim Start anzeigen", "Show at startup"),
+    ("Techniken (keine Auswahl = alle):", "Techniques (none selected = all):"),
+    ("\u{26A0} \u{00DC}berwachung unterbrochen", "\u{26A0} Watching interrupted"),
+    ("\u{00D6}ffentliche Seite", "Public page"),
+    ("Eine kleine durchsuchbare Website deiner Songs, z.\u{00A0}B. f\u{00FC}r neue Sch\u{00FC}ler. Als privat markierte Songs, Dateien, Notizen und Bewertungen bleiben drau\u{00DF}en.", "A small searchable website of your songs, e.g. for new students. Songs marked private, files, notes and ratings stay out."),
+    ("Einleitung:", "Introduction:"),
+    ("Nur Repertoire (\u{201E}kann ich\u{201C})", "Repertoire only (\u{201C}can play\u{201D})"),
+    ("Sichtbare Tag-Kategorien:", "Visible tag categories:"),
+    ("Ordner f\u{00FC}r die Website", "Folder for the website"),
+    ("Seite erzeugen", "Generate page"),
+    ("1 Song", "1 song"),
+    ("{} Songs", "{} songs"),
+    ("Caches", "Caches"),
+    ("Eintr\u{00E4}ge", "Entries"),
+    ("Speicher", "Memory"),
+    ("Trefferquote", "Hit rate"),
+    ("Vorschaubilder", "Thumbnails"),
+    ("Texturen", "Textures"),
+    ("Datei konnte nicht gelesen werden: {}", "Could not read the file: {}"),
+    ("\u{201E}{}\u{201C} existiert bereits.", "\u{201C}{}\u{201D} already exists."),
+    ("{} Dateien zuordnen", "Attach {} files"),
+    ("Lehrplan-Abdeckung.pdf", "Curriculum coverage.pdf"),
+    ("lehrplan.toml ist fehlerhaft: {}", "lehrplan.toml is invalid: {}"),
+    ("{} L\u{00FC}cken (weniger als {} Songs).", "{} gaps (fewer than {} songs)."),
+    ("Nicht im Lehrplan: Schwierigkeit {}", "Not in the curriculum: difficulty {}"),
+    ("Rot: weniger als {} Songs pro Fertigkeit und Stufe", "Red: fewer than {} songs per skill and level"),
+    ("Einkaufsliste", "Shopping list"),
+    ("\u{2022} {} ({}): {} weitere Songs", "\u{2022} {} ({}): {} more songs"),
+    ("{} Songs zugeordnet, {} Tags gesetzt.", "{} songs matched, {} tags set."),
+    ("{} Zeilen ohne passenden Song", "{} rows without a matching song"),
+    ("{} Zeilen gefunden. Spalten zuordnen:", "{} rows found. Map the columns:"),
+    ("Dateiname", "File name"),
+    ("Der Scan hat {} Songs gefunden. Ist ein ganzes Notenarchiv dabei, das nicht in den Index geh\u{00F6}rt, l\u{00E4}sst es sich hier ausschlie\u{00DF}en.", "The scan found {} songs. If a whole sheet music archive is among them that does not belong in the index, it can be excluded here."),
+    ("Ausschlie\u{00DF}en", "Exclude"),
+    ("nach {}", "after {}"),
+    ("\u{2713} Kann ich", "\u{2713} Can play"),
+    ("Zeigen", "Show"),
+    ("Aktualisieren", "Refresh"),
+    ("Vom Song \u{00FC}bernehmen ({} BPM)", "Use the song\u{2019}s tempo ({} BPM)"),
+    ("\u{25A0} Stopp", "\u{25A0} Stop"),
+    ("\u{25B6} Start", "\u{25B6} Start"),
+    ("Wieder anzeigen", "Show again"),
+    ("Wiederherstellen", "Restore"),
+    ("Weiter \u{2192}", "Next \u{2192}"),
+    ("\u{2190} Zur\u{00FC}ck", "\u{2190} Back"),
+    ("Wackelt", "Shaky"),
+    ("\u{2713} Sitzt", "\u{2713} Solid"),
+    ("Bisher", "Current"),
+    ("Jahr", "Year"),
+    ("Stil-Tag", "Style tag"),
+    ("Keine Vorlagen in {}", "No templates in {}"),
+    ("Der Ordner muss im Musikordner liegen.", "The folder must be inside the music folder."),
+    ("{} Songs \u{00B7} {} Notizen geschrieben, {} entfernt", "{} songs \u{00B7} {} notes written, {} removed"),
+    ("F\u{00FC}gt die PDFs der aktuellen Liste ({} Songs) in dieser Reihenfolge zu einer Datei zusammen.", "Merges the PDFs of the current list ({} songs) into one file in this order."),
+    ("Nr.", "No."),
+    ("Gespeichert unter {}. {} Dateien nicht lesbar (fehlt, besch\u{00E4}digt oder verschl\u{00FC}sselt) und ausgelassen.", "Saved to {}. {} files could not be read (missing, damaged or encrypted) and were left out."),
+    ("(insgesamt {})", "({} in total)"),
+    ("{} \u{00B7} {} Tags \u{00B7} entfernt {}", "{} \u{00B7} {} tags \u{00B7} removed {}"),
+    ("wieder da", "back again"),
+    ("wartet auf die Datei", "waiting for the file"),
+    ("Wiederhergestellt.", "Restored."),
+    ("Die Datei fehlt noch; der Song kommt mit seinen Tags zur\u{00FC}ck, sobald sie wieder da ist.", "The file is still missing; the song comes back with its tags once it is there again."),
+    ("F\u{00E4}llig", "Due"),
+    ("Ganzes Repertoire", "Whole repertoire"),
+    (" Tage", " days"),
+    ("Noch keine Songs als \u{201E}Kann ich\u{201C} markiert (Bearbeiten).", "No songs marked \u{201C}Can play\u{201D} yet (Edit)."),
+    ("Nichts f\u{00E4}llig \u{2014} das Repertoire sitzt.", "Nothing due \u{2014} the repertoire is solid."),
+    ("Zuletzt {}, Intervall {} Tage", "Last {}, interval {} days"),
+    ("Aus der aktuellen Liste ({} Songs):", "From the current list ({} songs):"),
+    ("Song {} von {}", "Song {} of {}"),
+    ("noch {}", "{} left"),
+    ("Als N\u{00E4}chstes: {}", "Up next: {}"),
+    ("{} ge\u{00FC}bt, im \u{00DC}be-Log eingetragen.", "{} practiced, added to the practice log."),
+    ("Smarte Liste bearbeiten", "Edit smart list"),
+    ("Neue smarte Liste", "New smart list"),
+    ("Passt gerade auf {} Songs.", "Currently matches {} songs."),
+    ("Aktuell", "Current"),
+    ("w\u{00E4}hlen", "choose"),
+    ("{} Songs haben heute andere \u{201E}{}\u{201C}-Tags.", "{} songs have different \u{201C}{}\u{201D} tags today."),
+    ("\u{201E}{}\u{201C} auf Stand {} zur\u{00FC}ckgesetzt.", "\u{201C}{}\u{201D} reset to the state of {}."),
+    ("{} bei {} Songs auf Stand {} zur\u{00FC}ckgesetzt.", "{} reset for {} songs to the state of {}."),
+    ("{} neue Tags angelegt", "{} new tags created"),
+    ("Gitarrenunterricht", "Guitar lessons"),
+    ("Stufen, Spieltechniken und Stile f\u{00FC}r den Einzel- und Gruppenunterricht", "Levels, playing techniques and styles for private and group lessons"),
+    ("Worship-Band", "Worship band"),
+    ("Besetzung, Stimmungen und Tonarten f\u{00FC}r die Gottesdienstplanung", "Line-up, tunings and keys for service planning"),
+    ("Klassische Gitarre", "Classical guitar"),
+    ("Epochen und Techniken f\u{00FC}r klassisches Repertoire", "Periods and techniques for classical repertoire"),
+    ("Akzent", "Accent"),
+    ("Hintergrund", "Background"),
+    ("{} gegriffen klingt in {}", "{} fingered sounds in {}"),
+    ("Mit den Griffen in {}: Kapo {}", "With the {} shapes: capo {}"),
+    ("{}-Griffe ohne Kapo", "{} shapes without capo"),
+    ("{}-Griffe, Kapo {}", "{} shapes, capo {}"),
+    ("{} Songs endg\u{00FC}ltig l\u{00F6}schen?", "Delete {} songs permanently?"),
+    ("Fertig \u{2014} alle {} Songs durchgesehen.", "Done \u{2014} all {} songs reviewed."),
+    ("{} neu", "{} new"),
+    ("Ordner mit neuen Songs", "Folders with new songs"),
+    ("{} von {}", "{} of {}"),
+    ("{} entfernt", "{} removed"),
+    ("Nach dem Ruhezustand abgeglichen: {}", "Synced after sleep: {}"),
+    ("Neuer Versuch l\u{00E4}uft\u{2026}", "Retrying\u{2026}"),
+    ("Neuer Versuch in {}\u{00A0}s (Versuch {})", "Retrying in {}\u{00A0}s (attempt {})"),
+    ("\u{00C4}nderungen im Musikordner werden gerade nicht erkannt.", "Changes in the music folder are currently not detected."),
+    ("{} Songs nach {} geschrieben", "{} songs written to {}"),
+    ("{} Songs \u{00F6}ffentlich", "{} songs public"),
+    ("Exportiert die aktuelle Liste ({} Songs) mit Artist, Tonart und Tags.", "Exports the current list ({} songs) with artist, key and tags."),
+    ("{} indizierte Songs passen auf die Muster", "{} indexed songs match the patterns"),
+    (" Songs", " songs"),
+    // Errors and startup dialogs
+    ("Schl\u{00FC}sselbund nicht verf\u{00FC}gbar", "Keychain not available"),
+    ("Schl\u{00FC}sselbund: {}", "Keychain: {}"),
+    ("Einstellungen fehlerhaft", "Settings invalid"),
+    (
+        "config.toml enth\u{00E4}lt einen Fehler und wurde nicht ver\u{00E4}ndert:\n\n{}\n\n\
+         Bitte die Datei korrigieren oder umbenennen und Songindex neu starten.",
+        "config.toml contains an error and was left unchanged:\n\n{}\n\n\
+         Please fix or rename the file and restart Songindex.",
+    ),
+    ("Passwort f\u{00FC}r die Songindex-Bibliothek:", "Password for the Songindex library:"),
+    ("Falsches Passwort. Bitte erneut versuchen:", "Wrong password. Please try again:"),
+    ("Verschl\u{00FC}sselte Datenbank l\u{00E4}sst sich nicht \u{00F6}ffnen", "The encrypted database cannot be opened"),
+    ("Datei konnte nicht geschrieben werden: {}", "Could not write the file: {}"),
+    ("Port {} ist nicht verf\u{00FC}gbar: {}", "Port {} is not available: {}"),
+    ("Die Bibliothek konnte nicht gelesen werden", "The library could not be read"),
+    ("Ordner konnte nicht angelegt werden: {}", "Could not create the folder: {}"),
+    ("{} konnte nicht geschrieben werden: {}", "Could not write {}: {}"),
+    ("Keine lesbaren PDFs", "No readable PDFs"),
+    ("Audioausgabe nicht verf\u{00FC}gbar: {}", "Audio output not available: {}"),
+    ("Keine Audioausgabe auf diesem System", "No audio output on this system"),
+    ("Format nicht lesbar: {}", "Format not readable: {}"),
+    ("Bibliothek nicht gefunden", "Library not found"),
+    ("Der Ordner der Bibliothek fehlt:\n{}\n\nIst das Laufwerk verbunden? Der Speicherort steht als database in config.toml.", "The library\u{2019}s folder is missing:\n{}\n\nIs the drive connected? The location is set as database in config.toml."),
+    ("Musikordner w\u{00E4}hlen\u{2026}", "Choose music folder\u{2026}"),
+    ("Demo-Bibliothek", "Demo library"),
+    ("Willkommen bei Songindex", "Welcome to Songindex"),
+    ("W\u{00E4}hle den Ordner mit deinen Noten. Oder probiere Songindex zuerst mit einer kleinen Demo-Bibliothek aus: Beispiel-Songs mit Tags, Tonarten und Notizen in einem tempor\u{00E4}ren Ordner; deine Dateien bleiben unber\u{00FC}hrt.", "Choose the folder with your sheet music. Or try Songindex first with a small demo library: sample songs with tags, keys and notes in a temporary folder; your files stay untouched."),
+    // Command line
+    (crate::cli::USAGE, "Songindex without a window, e.g. for scripts and cron:
+
+  songindex scan
+      Read the music folder again.
+  songindex list [--tag kategorie=wert]... [--suche text] [--sort titel|artist|neu] [--json]
+      List songs: path, title and artist, separated by tabs.
+  songindex tag add|remove <pfad> <kategorie> <wert>
+      Set or remove a tag. The path is relative to the music folder or absolute.
+  songindex export [--tag kategorie=wert]... [--suche text] [--csv]
+      Print songs with all fields and tags as JSON (or CSV).
+  songindex demo <ordner>
+      Write the demo library into an empty folder, e.g. as test data.
+  songindex portable
+      Create the folder songindex-daten next to the program and copy settings
+      and library into it (with the window closed). While the folder is there,
+      Songindex only uses it, e.g. from a USB stick.
+  songindex --demo
+      Start the window with a fresh demo library; music folder and library
+      stay untouched.
+
+Without a command the window starts.
+"),
+    ("Unbekannter Befehl \u{201E}{}\u{201C}", "Unknown command \u{201C}{}\u{201D}"),
+    ("{} (songindex help zeigt alle Befehle)", "{} (songindex help shows all commands)"),
+    ("Kein Musikordner eingerichtet; das Programm einmal ohne Befehl starten", "No music folder set up; start the program once without a command"),
+    ("Ordner der Bibliothek {} nicht gefunden", "Folder of the library {} not found"),
+    ("Bibliothek nicht entsperrt", "Library not unlocked"),
+    ("Aufruf: songindex demo <ordner>", "Usage: songindex demo <ordner>"),
+    ("{} Dateien in {}", "{} files in {}"),
+    ("Programmordner unbekannt", "Program folder unknown"),
+    ("{} gibt es schon", "{} already exists"),
+    ("{} Dateien nach {} kopiert", "Copied {} files to {}"),
+    ("Die Bibliothek liegt unter {} und wurde nicht kopiert", "The library is at {} and was not copied"),
+    ("{} braucht einen Wert", "{} needs a value"),
+    ("--tag {}: erwartet kategorie=wert", "--tag {}: expected kategorie=wert"),
+    ("--sort {}: titel, artist oder neu", "--sort {}: titel, artist or neu"),
+    ("Unbekannte Option \u{201E}{}\u{201C}", "Unknown option \u{201C}{}\u{201D}"),
+    ("Den Tag {}={} gibt es nicht", "There is no tag {}={}"),
+    ("{} neu, {} entfernt, {} verschoben, {} mit neuem Audio", "{} new, {} removed, {} moved, {} with new audio"),
+    ("{} Songs im Index", "{} songs in the index"),
+    ("tag erwartet add|remove <pfad> <kategorie> <wert>", "tag expects add|remove <pfad> <kategorie> <wert>"),
+    ("tag {}: add oder remove", "tag {}: add or remove"),
+    ("{} ist nicht im Index", "{} is not in the index"),
+];
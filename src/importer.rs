@@ -0,0 +1,199 @@
+use crate::arrangements::share_from;
+use crate::db::{add_tag_to_song, LogErr, NOW};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+
+/// What a CSV column is mapped to during import.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnTarget {
+    Ignore,
+    Titel,
+    Artist,
+    Dateiname,
+    Tag(String),
+}
+
+#[derive(Clone)]
+pub struct CsvData {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+#[derive(Debug, Default)]
+pub struct ImportResult {
+    pub matched: usize,
+    pub tags_added: usize,
+    pub unmatched: Vec<String>,
+}
+
+/// Read a CSV file, detecting `;` (Excel with German locale) or `,` as delimiter.
+pub fn read_csv(path: &Path) -> Result<CsvData, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let content = content.trim_start_matches('\u{feff}');
+
+    let first_line = content.lines().next().unwrap_or_default();
+    let delimiter = if first_line.matches(';').count() > first_line.matches(',').count() {
+        b';'
+    } else {
+        b','
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|h| h.trim().to_string())
+        .collect();
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| e.to_string())?;
+        let row: Vec<String> = record.iter().map(|v| v.trim().to_string()).collect();
+        if row.iter().all(|v| v.is_empty()) {
+            continue;
+        }
+        rows.push(row);
+    }
+
+    Ok(CsvData { headers, rows })
+}
+
+/// Guess a sensible mapping for a column based on its header name.
+pub fn guess_target(header: &str) -> ColumnTarget {
+    let h = header.to_lowercase();
+    match h.as_str() {
+        "titel" | "title" | "song" | "name" => ColumnTarget::Titel,
+        "artist" | "interpret" | "künstler" | "band" => ColumnTarget::Artist,
+        "datei" | "dateiname" | "file" | "filename" | "pdf" => ColumnTarget::Dateiname,
+        "schwierigkeit" | "difficulty" | "level" | "niveau" => {
+            ColumnTarget::Tag("schwierigkeit".to_string())
+        }
+        "tonart" | "key" => ColumnTarget::Tag("tonart".to_string()),
+        "stil" | "genre" | "style" => ColumnTarget::Tag("stil".to_string()),
+        "technik" | "technique" => ColumnTarget::Tag("technik".to_string()),
+        "instrument" => ColumnTarget::Tag("instrument".to_string()),
+        "stimmung" | "tuning" => ColumnTarget::Tag("stimmung".to_string()),
+        "kapo" | "capo" => ColumnTarget::Tag("kapo".to_string()),
+        _ => ColumnTarget::Ignore,
+    }
+}
+
+fn find_song(
+    conn: &Connection,
+    dateiname: Option<&str>,
+    titel: Option<&str>,
+    artist: Option<&str>,
+) -> Option<i64> {
+    if let Some(name) = dateiname.filter(|n| !n.is_empty()) {
+        let name = name.to_lowercase();
+        let found: Option<i64> = conn
+            .query_row(
+                "SELECT id FROM songs
+                 WHERE (LOWER(dateiname) = ?1 OR LOWER(dateiname) = ?1 || '.pdf')
+                   AND deleted_at IS NULL",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()
+            .ok()
+            .flatten();
+        if found.is_some() {
+            return found;
+        }
+    }
+
+    let titel = titel.filter(|t| !t.is_empty())?;
+    match artist.filter(|a| !a.is_empty()) {
+        Some(artist) => conn
+            .query_row(
+                "SELECT id FROM songs
+                 WHERE LOWER(titel) = LOWER(?1) AND LOWER(artist) = LOWER(?2)
+                   AND deleted_at IS NULL",
+                params![titel, artist],
+                |row| row.get(0),
+            )
+            .optional()
+            .ok()
+            .flatten(),
+        None => conn
+            .query_row(
+                "SELECT id FROM songs WHERE LOWER(titel) = LOWER(?1) AND deleted_at IS NULL",
+                params![titel],
+                |row| row.get(0),
+            )
+            .optional()
+            .ok()
+            .flatten(),
+    }
+}
+
+/// Apply the mapped CSV rows to the library in a single transaction.
+/// Rows are matched by filename first, then by title + artist. Songs matched by
+/// filename also take over the mapped title/artist values.
+pub fn import_rows(conn: &Connection, data: &CsvData, mapping: &[ColumnTarget]) -> ImportResult {
+    let mut result = ImportResult::default();
+
+    let column = |target: &ColumnTarget| mapping.iter().position(|t| t == target);
+    let titel_col = column(&ColumnTarget::Titel);
+    let artist_col = column(&ColumnTarget::Artist);
+    let dateiname_col = column(&ColumnTarget::Dateiname);
+
+    conn.execute_batch("BEGIN").log_err();
+
+    for row in &data.rows {
+        let cell = |col: Option<usize>| col.and_then(|c| row.get(c)).map(|v| v.as_str());
+        let titel = cell(titel_col);
+        let artist = cell(artist_col);
+        let dateiname = cell(dateiname_col);
+
+        let Some(song_id) = find_song(conn, dateiname, titel, artist) else {
+            let label = dateiname
+                .filter(|d| !d.is_empty())
+                .or(titel)
+                .unwrap_or("(leere Zeile)");
+            result.unmatched.push(label.to_string());
+            continue;
+        };
+        result.matched += 1;
+
+        if dateiname.is_some_and(|d| !d.is_empty()) {
+            if let Some(titel) = titel.filter(|t| !t.is_empty()) {
+                conn.execute(
+                    &format!("UPDATE songs SET titel = ?1, updated_at = {NOW} WHERE id = ?2"),
+                    params![titel, song_id],
+                )
+                .log_err();
+            }
+            if let Some(artist) = artist.filter(|a| !a.is_empty()) {
+                conn.execute(
+                    &format!("UPDATE songs SET artist = ?1, updated_at = {NOW} WHERE id = ?2"),
+                    params![artist, song_id],
+                )
+                .log_err();
+                share_from(conn, song_id);
+            }
+        }
+
+        for (i, target) in mapping.iter().enumerate() {
+            let ColumnTarget::Tag(kategorie) = target else {
+                continue;
+            };
+            let Some(value) = row.get(i) else {
+                continue;
+            };
+            for wert in value.split(',').map(str::trim).filter(|w| !w.is_empty()) {
+                add_tag_to_song(conn, song_id, kategorie, wert);
+                result.tags_added += 1;
+            }
+        }
+    }
+
+    conn.execute_batch("COMMIT").log_err();
+
+    result
+}
@@ -0,0 +1,375 @@
+use crate::db::{query_rows, LogErr};
+use crate::thumbnails::{
+    preview_path, preview_uri, render_preview, render_thumbnail, thumbnail_path, thumbnail_uri,
+};
+use crate::waveform::{render_waveform, waveform_path};
+use crate::worker::Db;
+use eframe::egui;
+use rusqlite::{params, Connection};
+use sha2::{Digest, Sha256};
+use std::collections::{HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::UNIX_EPOCH;
+
+/// Upper bound for worker threads; jobs are I/O heavy and should not starve the UI.
+const MAX_WORKERS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JobKind {
+    /// First-page thumbnail (see thumbnails.rs); cached on disk.
+    Thumbnail,
+    /// PNG copy of a JPEG/HEIC scan for the image viewer; cached on disk.
+    Preview,
+    /// Text layer of a PDF or the content of a text sheet, stored in
+    /// `songs.volltext` for `inhalt:` searches; for PDFs also the page count
+    /// in `songs.seiten`.
+    Text,
+    /// SHA-256 of the file, stored in `songs.datei_hash`.
+    Hash,
+    /// Peak levels of an audio file for the player bar; cached on disk.
+    Waveform,
+}
+
+impl JobKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            JobKind::Thumbnail => "thumbnail",
+            JobKind::Preview => "preview",
+            JobKind::Text => "text",
+            JobKind::Hash => "hash",
+            JobKind::Waveform => "waveform",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    /// Needed for something on screen; runs before background work.
+    Visible,
+    Background,
+}
+
+struct Job {
+    kind: JobKind,
+    dateipfad: String,
+    source: PathBuf,
+}
+
+#[derive(Default)]
+struct Queue {
+    visible: VecDeque<Job>,
+    background: VecDeque<Job>,
+    queued: HashSet<(JobKind, String)>,
+    running: usize,
+    /// Jobs finished since the queue was last idle, for the progress indicator.
+    done: usize,
+}
+
+impl Queue {
+    fn pop(&mut self) -> Option<Job> {
+        self.visible
+            .pop_front()
+            .or_else(|| self.background.pop_front())
+    }
+
+    fn pending(&self) -> usize {
+        self.visible.len() + self.background.len()
+    }
+}
+
+/// Shared background job system: a bounded pool of workers takes jobs from a
+/// two-level priority queue. Completed text and hash jobs are recorded in
+/// `erledigte_jobs` with the file's modification time, so they only run again
+/// when the file changes.
+#[derive(Clone)]
+pub struct JobQueue {
+    shared: Arc<(Mutex<Queue>, Condvar)>,
+}
+
+impl JobQueue {
+    pub fn start(db: Db, ctx: egui::Context) -> Self {
+        let queue = JobQueue {
+            shared: Arc::new((Mutex::new(Queue::default()), Condvar::new())),
+        };
+        let workers = std::thread::available_parallelism()
+            .map_or(1, |n| n.get() / 2)
+            .clamp(1, MAX_WORKERS);
+        for _ in 0..workers {
+            let queue = queue.clone();
+            let db = db.clone();
+            let ctx = ctx.clone();
+            std::thread::spawn(move || queue.work(&db, &ctx));
+        }
+        queue
+    }
+
+    /// Queue a job unless it is already waiting; a visible request moves a
+    /// waiting background job to the front.
+    pub fn push(&self, kind: JobKind, dateipfad: &str, source: PathBuf, priority: Priority) {
+        let (lock, cvar) = &*self.shared;
+        let mut queue = lock.lock().unwrap();
+        let key = (kind, dateipfad.to_string());
+        if !queue.queued.insert(key) {
+            if priority == Priority::Visible {
+                if let Some(pos) = queue
+                    .background
+                    .iter()
+                    .position(|j| j.kind == kind && j.dateipfad == dateipfad)
+                {
+                    let job = queue.background.remove(pos).unwrap();
+                    queue.visible.push_back(job);
+                }
+            }
+            return;
+        }
+        let job = Job {
+            kind,
+            dateipfad: dateipfad.to_string(),
+            source,
+        };
+        match priority {
+            Priority::Visible => queue.visible.push_back(job),
+            Priority::Background => queue.background.push_back(job),
+        }
+        cvar.notify_one();
+    }
+
+    /// Queue text and hash jobs for songs that have none recorded for the
+    /// file's current modification time.
+    /// Runs on a thread of its own: the worker only answers one query, the
+    /// files are looked at outside it.
+    pub fn enqueue_library(&self, db: &Db, base_dir: PathBuf) {
+        self.enqueue(db, base_dir, None);
+    }
+
+    /// [`enqueue_library`](Self::enqueue_library) for some files only, e.g.
+    /// a watcher batch.
+    pub fn enqueue_files(&self, db: &Db, base_dir: PathBuf, paths: Vec<String>) {
+        if !paths.is_empty() {
+            self.enqueue(db, base_dir, Some(paths));
+        }
+    }
+
+    fn enqueue(&self, db: &Db, base_dir: PathBuf, only: Option<Vec<String>>) {
+        let (queue, db) = (self.clone(), db.clone());
+        std::thread::spawn(move || {
+            let Some(files) = db.call(move |conn| library_files(conn, only.as_deref())) else {
+                return;
+            };
+            for file in files {
+                let source = base_dir.join(&file.dateipfad);
+                let Some(mtime) = modified(&source) else {
+                    continue;
+                };
+                let is_done =
+                    |kind: JobKind| file.done.contains(&(kind.as_str().to_string(), mtime));
+
+                let has_text = file.dateityp == "pdf" || file.dateityp == "text";
+                if has_text && !is_done(JobKind::Text) {
+                    queue.push(
+                        JobKind::Text,
+                        &file.dateipfad,
+                        source.clone(),
+                        Priority::Background,
+                    );
+                }
+                if !is_done(JobKind::Hash) {
+                    queue.push(JobKind::Hash, &file.dateipfad, source, Priority::Background);
+                }
+            }
+        });
+    }
+
+    /// Whether a job is waiting or running.
+    pub fn is_pending(&self, kind: JobKind, dateipfad: &str) -> bool {
+        let queue = self.shared.0.lock().unwrap();
+        queue.queued.contains(&(kind, dateipfad.to_string()))
+    }
+
+    /// `(finished, total)` while jobs are waiting or running.
+    pub fn progress(&self) -> Option<(usize, usize)> {
+        let queue = self.shared.0.lock().unwrap();
+        let open = queue.pending() + queue.running;
+        (open > 0).then_some((queue.done, queue.done + open))
+    }
+
+    fn work(&self, db: &Db, ctx: &egui::Context) {
+        let (lock, cvar) = &*self.shared;
+        loop {
+            let job = {
+                let mut queue = lock.lock().unwrap();
+                loop {
+                    if let Some(job) = queue.pop() {
+                        queue.running += 1;
+                        break job;
+                    }
+                    queue = cvar.wait(queue).unwrap();
+                }
+            };
+
+            // Taken before the run, so a change during it runs the job again.
+            let mtime = modified(&job.source);
+            // A broken file must not take the worker down with it.
+            let result = std::panic::catch_unwind(|| run_job(&job));
+            let (kind, dateipfad) = (job.kind, job.dateipfad.clone());
+            let output = match result {
+                Ok(Some(output)) => Some(output),
+                // Recorded like a result, so an encrypted or broken file is
+                // not read again on every start until it changes.
+                _ => matches!(kind, JobKind::Text | JobKind::Hash).then_some(JobOutput::Failed),
+            };
+            if let Some(output) = output {
+                db.call(move |conn| store_result(conn, &job, output, mtime));
+            }
+            match kind {
+                JobKind::Thumbnail => ctx.forget_image(&thumbnail_uri(&dateipfad)),
+                JobKind::Preview => ctx.forget_image(&preview_uri(&dateipfad)),
+                JobKind::Text | JobKind::Hash | JobKind::Waveform => {}
+            }
+
+            let mut queue = lock.lock().unwrap();
+            queue.running -= 1;
+            queue.queued.remove(&(kind, dateipfad));
+            queue.done += 1;
+            if queue.pending() == 0 && queue.running == 0 {
+                queue.done = 0;
+            }
+            drop(queue);
+            ctx.request_repaint();
+        }
+    }
+}
+
+/// A song's file with the jobs recorded for it, for [`JobQueue::enqueue`].
+struct LibraryFile {
+    dateipfad: String,
+    dateityp: String,
+    /// `(art, mtime)` rows of `erledigte_jobs`.
+    done: Vec<(String, i64)>,
+}
+
+/// Indexed songs (or those among `only`) with their recorded jobs, in one
+/// query; the join gives a song one row per recorded job.
+fn library_files(conn: &Connection, only: Option<&[String]>) -> Vec<LibraryFile> {
+    let sql = format!(
+        "SELECT s.dateipfad, s.dateityp, e.art, e.mtime
+         FROM songs s LEFT JOIN erledigte_jobs e ON e.dateipfad = s.dateipfad
+         WHERE s.deleted_at IS NULL AND s.ignoriert = 0{}
+         ORDER BY s.titel, s.dateipfad",
+        if only.is_some() {
+            " AND s.dateipfad IN (SELECT value FROM json_each(?1))"
+        } else {
+            ""
+        }
+    );
+    let read = |row: &rusqlite::Row<'_>| -> rusqlite::Result<_> {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, Option<i64>>(3)?,
+        ))
+    };
+    let rows = match only {
+        Some(paths) => {
+            let paths = serde_json::to_string(paths).unwrap_or_default();
+            query_rows(conn, &sql, params![paths], read)
+        }
+        None => query_rows(conn, &sql, [], read),
+    };
+
+    let mut files: Vec<LibraryFile> = Vec::new();
+    for (dateipfad, dateityp, art, mtime) in rows {
+        if files.last().is_none_or(|f| f.dateipfad != dateipfad) {
+            files.push(LibraryFile {
+                dateipfad,
+                dateityp,
+                done: Vec::new(),
+            });
+        }
+        if let (Some(art), Some(mtime), Some(file)) = (art, mtime, files.last_mut()) {
+            file.done.push((art, mtime));
+        }
+    }
+    files
+}
+
+enum JobOutput {
+    /// Image or waveform written to its cache file.
+    Rendered,
+    /// Text and, for a PDF, its page count.
+    Text(String, Option<i64>),
+    Hash(String),
+    /// A text or hash job that could not read its file.
+    Failed,
+}
+
+fn run_job(job: &Job) -> Option<JobOutput> {
+    match job.kind {
+        JobKind::Thumbnail => render_thumbnail(&job.source, &thumbnail_path(&job.dateipfad))
+            .then_some(JobOutput::Rendered),
+        JobKind::Preview => render_preview(&job.source, &preview_path(&job.dateipfad))
+            .then_some(JobOutput::Rendered),
+        JobKind::Waveform => render_waveform(&job.source, &waveform_path(&job.dateipfad))
+            .then_some(JobOutput::Rendered),
+        JobKind::Text if !is_pdf(&job.source) => {
+            let text = std::fs::read_to_string(&job.source).ok()?;
+            Some(JobOutput::Text(text, None))
+        }
+        JobKind::Text => {
+            let doc = lopdf::Document::load(&job.source).ok()?;
+            let pages: Vec<u32> = doc.get_pages().keys().copied().collect();
+            // Scanned sheets have no text layer; store the empty result anyway
+            // so they are not retried.
+            let text = doc.extract_text(&pages).unwrap_or_default();
+            Some(JobOutput::Text(
+                text.split_whitespace().collect::<Vec<_>>().join(" "),
+                Some(pages.len() as i64),
+            ))
+        }
+        JobKind::Hash => {
+            let mut hasher = Sha256::new();
+            std::io::copy(&mut std::fs::File::open(&job.source).ok()?, &mut hasher).ok()?;
+            let hash = hasher.finalize();
+            Some(JobOutput::Hash(
+                hash.iter().map(|b| format!("{b:02x}")).collect(),
+            ))
+        }
+    }
+}
+
+/// Store a job's result and record it for the file's `mtime`, as taken
+/// before the job ran.
+fn store_result(conn: &Connection, job: &Job, output: JobOutput, mtime: Option<i64>) {
+    match output {
+        JobOutput::Rendered => return,
+        JobOutput::Failed => Ok(0),
+        JobOutput::Text(text, seiten) => conn.execute(
+            "UPDATE songs SET volltext = ?1, seiten = COALESCE(?2, seiten) WHERE dateipfad = ?3",
+            params![text, seiten, job.dateipfad],
+        ),
+        JobOutput::Hash(hash) => conn.execute(
+            "UPDATE songs SET datei_hash = ?1 WHERE dateipfad = ?2",
+            params![hash, job.dateipfad],
+        ),
+    }
+    .log_err();
+    if let Some(mtime) = mtime {
+        conn.execute(
+            "INSERT OR REPLACE INTO erledigte_jobs (dateipfad, art, mtime) VALUES (?1, ?2, ?3)",
+            params![job.dateipfad, job.kind.as_str(), mtime],
+        )
+        .log_err();
+    }
+}
+
+fn modified(path: &Path) -> Option<i64> {
+    let time = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    Some(time.duration_since(UNIX_EPOCH).ok()?.as_secs() as i64)
+}
+
+fn is_pdf(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("pdf"))
+}
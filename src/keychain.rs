@@ -0,0 +1,31 @@
+use crate::i18n::tr;
+
+/// Service name of all Songindex entries; the account tells them apart.
+const SERVICE: &str = "Songindex";
+
+fn entry(account: &str) -> Option<keyring::Entry> {
+    keyring::Entry::new(SERVICE, account).ok()
+}
+
+/// The secret stored under `account`, `None` if there is none.
+pub fn get(account: &str) -> Option<String> {
+    entry(account)?.get_password().ok()
+}
+
+/// Store `secret` under `account`; an empty secret removes the entry.
+pub fn set(account: &str, secret: &str) -> Result<(), String> {
+    if secret.is_empty() {
+        delete(account);
+        return Ok(());
+    }
+    entry(account)
+        .ok_or_else(|| tr("Schl\u{00FC}sselbund nicht verf\u{00FC}gbar").to_string())?
+        .set_password(secret)
+        .map_err(|e| e.to_string())
+}
+
+pub fn delete(account: &str) {
+    if let Some(entry) = entry(account) {
+        entry.delete_credential().ok();
+    }
+}
@@ -0,0 +1,380 @@
+use crate::db::all_song_tags;
+use crate::i18n::tr_args;
+use crate::site::STYLE;
+use crate::worker::Db;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::net::{IpAddr, TcpListener, TcpStream, UdpSocket};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// What the browser view lists; no paths, notes or ratings. Files are
+/// fetched by id.
+#[derive(Serialize)]
+struct LanSong {
+    id: i64,
+    titel: String,
+    artist: Option<String>,
+    tonart: Option<String>,
+    /// `songs.dateityp`; audio entries only get the audio link.
+    typ: String,
+    audio: bool,
+    tags: Vec<String>,
+}
+
+/// Read-only HTTP server for browsers in the local network: the song list
+/// with search and tag filters at `/`, the song's file at `/datei/<id>` and
+/// its backing track at `/audio/<id>`. Private songs are left out. Stops
+/// when dropped.
+pub struct LanServer {
+    port: u16,
+    stop: Arc<AtomicBool>,
+}
+
+impl LanServer {
+    pub fn start(db: Db, base_dir: PathBuf, port: u16) -> Result<Self, String> {
+        let listener = TcpListener::bind(("0.0.0.0", port))
+            .map_err(|e| tr_args("Port {} ist nicht verf\u{00FC}gbar: {}", &[&port, &e]))?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = stop.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stopped.load(Ordering::Relaxed) {
+                    break;
+                }
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                let db = db.clone();
+                let base_dir = base_dir.clone();
+                std::thread::spawn(move || handle(&stream, &db, &base_dir));
+            }
+        });
+        log::info!("LAN view on port {port}");
+        Ok(Self { port, stop })
+    }
+
+    /// Address to open on another device, e.g. `http://192.168.1.20:8080`.
+    pub fn url(&self) -> String {
+        let host = local_ip().map_or_else(|| "localhost".to_string(), |ip| ip.to_string());
+        format!("http://{host}:{}", self.port)
+    }
+}
+
+impl Drop for LanServer {
+    fn drop(&mut self) {
+        // The accept loop only sees the flag with the next connection.
+        self.stop.store(true, Ordering::Relaxed);
+        let _ = TcpStream::connect(("127.0.0.1", self.port));
+    }
+}
+
+/// The address outgoing traffic would use, which is the one other devices in
+/// the network reach. Connecting a UDP socket sends nothing.
+fn local_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip())
+}
+
+fn handle(stream: &TcpStream, db: &Db, base_dir: &Path) {
+    stream.set_read_timeout(Some(Duration::from_secs(10))).ok();
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        return;
+    }
+    let mut range = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).unwrap_or(0) == 0 || line.trim_end().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("range") {
+                range = Some(value.trim().to_string());
+            }
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or("/").split('?').next().unwrap_or("/");
+    let head = method == "HEAD";
+    let result = if method != "GET" && !head {
+        respond(
+            stream,
+            "405 Method Not Allowed",
+            "text/plain",
+            b"Nur lesen",
+            head,
+        )
+    } else if path == "/" {
+        let page = page(&db.call(|conn| songs(conn)).unwrap_or_default());
+        respond(
+            stream,
+            "200 OK",
+            "text/html; charset=utf-8",
+            page.as_bytes(),
+            head,
+        )
+    } else {
+        let path = path.to_string();
+        let file = db.call(move |conn| requested_file(conn, &path)).flatten();
+        match file.and_then(|rel| File::open(base_dir.join(&rel)).ok().map(|f| (rel, f))) {
+            Some((rel, file)) => send_file(stream, file, Path::new(&rel), range.as_deref(), head),
+            None => respond(
+                stream,
+                "404 Not Found",
+                "text/plain",
+                b"Nicht gefunden",
+                head,
+            ),
+        }
+    };
+    if let Err(e) = result {
+        log::info!("LAN view: {path}: {e}");
+    }
+}
+
+fn songs(conn: &Connection) -> Vec<LanSong> {
+    let mut tags = all_song_tags(conn);
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, titel, artist, tonart, COALESCE(dateityp, 'pdf'), audio_pfad IS NOT NULL
+             FROM songs WHERE privat = 0 AND ignoriert = 0 AND deleted_at IS NULL
+             ORDER BY titel COLLATE NOCASE",
+        )
+        .unwrap();
+    stmt.query_map([], |row| {
+        let id = row.get(0)?;
+        Ok(LanSong {
+            id,
+            titel: row.get(1)?,
+            artist: row.get(2)?,
+            tonart: row.get(3)?,
+            typ: row.get(4)?,
+            audio: row.get(5)?,
+            tags: tags
+                .remove(&id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|t| t.wert)
+                .collect(),
+        })
+    })
+    .unwrap()
+    .filter_map(|r| r.ok())
+    .collect()
+}
+
+/// Relative path behind `/datei/<id>` or `/audio/<id>`, if the song is listed.
+fn requested_file(conn: &Connection, path: &str) -> Option<String> {
+    let (column, id) = match path.strip_prefix("/datei/") {
+        Some(id) => ("dateipfad", id),
+        None => ("audio_pfad", path.strip_prefix("/audio/")?),
+    };
+    let id: i64 = id.parse().ok()?;
+    conn.query_row(
+        &format!(
+            "SELECT {column} FROM songs
+             WHERE id = ?1 AND privat = 0 AND ignoriert = 0 AND deleted_at IS NULL"
+        ),
+        params![id],
+        |row| row.get::<_, Option<String>>(0),
+    )
+    .ok()
+    .flatten()
+}
+
+fn respond(
+    mut stream: &TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+    head: bool,
+) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\
+         Cache-Control: no-cache\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    if !head {
+        stream.write_all(body)?;
+    }
+    Ok(())
+}
+
+/// The file, or the requested byte range of it. Safari on iPhones only
+/// plays audio from servers that answer range requests.
+fn send_file(
+    mut stream: &TcpStream,
+    mut file: File,
+    rel: &Path,
+    range: Option<&str>,
+    head: bool,
+) -> std::io::Result<()> {
+    let len = file.metadata()?.len();
+    let (status, start, end) = match range.and_then(|r| parse_range(r, len)) {
+        Some((start, end)) => ("206 Partial Content", start, end),
+        None => ("200 OK", 0, len.saturating_sub(1)),
+    };
+    let length = if len == 0 { 0 } else { end - start + 1 };
+    let content_range = if status.starts_with("206") {
+        format!("Content-Range: bytes {start}-{end}/{len}\r\n")
+    } else {
+        String::new()
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {}\r\nContent-Length: {length}\r\n\
+         Accept-Ranges: bytes\r\n{content_range}Connection: close\r\n\r\n",
+        content_type(rel)
+    )?;
+    if !head {
+        file.seek(SeekFrom::Start(start))?;
+        std::io::copy(&mut file.take(length), &mut stream)?;
+    }
+    Ok(())
+}
+
+/// First range of a `bytes=…` header as inclusive offsets into a file of
+/// `len` bytes. Anything unusable means the whole file.
+fn parse_range(header: &str, len: u64) -> Option<(u64, u64)> {
+    let spec = header.strip_prefix("bytes=")?.split(',').next()?.trim();
+    let (start, end) = spec.split_once('-')?;
+    let last = len.checked_sub(1)?;
+    if start.is_empty() {
+        let suffix: u64 = end.parse().ok()?;
+        return (suffix > 0).then(|| (len.saturating_sub(suffix), last));
+    }
+    let start: u64 = start.parse().ok()?;
+    let end = if end.is_empty() {
+        last
+    } else {
+        end.parse::<u64>().ok()?.min(last)
+    };
+    (start <= end).then_some((start, end))
+}
+
+fn content_type(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "pdf" => "application/pdf",
+        "mp3" => "audio/mpeg",
+        "m4a" => "audio/mp4",
+        "wav" => "audio/wav",
+        "aif" | "aiff" => "audio/aiff",
+        "flac" => "audio/flac",
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "heic" => "image/heic",
+        "txt" | "md" | "cho" | "chopro" | "crd" | "pro" => "text/plain; charset=utf-8",
+        "musicxml" => "application/vnd.recordare.musicxml+xml",
+        _ => "application/octet-stream",
+    }
+}
+
+fn page(songs: &[LanSong]) -> String {
+    // `</` would end the script element early.
+    let data = serde_json::to_string(songs)
+        .unwrap_or_else(|_| "[]".to_string())
+        .replace("</", "<\\/");
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"de\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>Songindex</title>\n<style>\n{STYLE}{LAN_STYLE}</style>\n</head>\n<body>\n\
+         <header>\n<h1>Songindex</h1>\n\
+         <input id=\"suche\" type=\"search\" placeholder=\"Titel, Artist, Tag\u{2026}\">\n\
+         <div id=\"filter\"></div>\n<p id=\"anzahl\" class=\"meta\"></p>\n</header>\n\
+         <ul id=\"songs\"></ul>\n\
+         <script id=\"daten\" type=\"application/json\">{data}</script>\n\
+         <script>\n{SCRIPT}</script>\n</body>\n</html>\n"
+    )
+}
+
+const LAN_STYLE: &str = ".links a { display: inline-block; font-size: 14px; padding: 4px 12px;
+  margin: 6px 6px 0 0; border-radius: 12px; background: #b48a30; color: #f2eee6;
+  text-decoration: none; }
+";
+
+const SCRIPT: &str = "const songs = JSON.parse(document.getElementById('daten').textContent);
+const suche = document.getElementById('suche');
+const liste = document.getElementById('songs');
+const anzahl = document.getElementById('anzahl');
+const aktiv = new Set();
+const MAX = 200;
+let nurAudio = false;
+
+function el(tag, cls, text) {
+  const e = document.createElement(tag);
+  if (cls) e.className = cls;
+  if (text) e.textContent = text;
+  return e;
+}
+
+function link(href, text) {
+  const a = el('a', '', text);
+  a.href = href;
+  a.target = '_blank';
+  return a;
+}
+
+const filter = document.getElementById('filter');
+const audio = el('button', '', '\\u25B6 Mit Audio');
+audio.onclick = () => {
+  nurAudio = !nurAudio;
+  audio.classList.toggle('aktiv');
+  zeigen();
+};
+filter.appendChild(audio);
+for (const wert of [...new Set(songs.flatMap(s => s.tags))].sort()) {
+  const b = el('button', '', wert);
+  b.onclick = () => {
+    aktiv.has(wert) ? aktiv.delete(wert) : aktiv.add(wert);
+    b.classList.toggle('aktiv');
+    zeigen();
+  };
+  filter.appendChild(b);
+}
+
+function zeigen() {
+  const woerter = suche.value.toLowerCase().split(/\\s+/).filter(w => w);
+  liste.replaceChildren();
+  let n = 0;
+  for (const s of songs) {
+    const text = [s.titel, s.artist, s.tonart, ...s.tags].join(' ').toLowerCase();
+    if (!woerter.every(w => text.includes(w))) continue;
+    if (nurAudio && !s.audio) continue;
+    if (![...aktiv].every(w => s.tags.includes(w))) continue;
+    if (++n > MAX) continue;
+    const li = el('li');
+    li.appendChild(el('span', 'titel', s.titel));
+    if (s.artist) li.appendChild(el('span', 'artist', ' \\u2014 ' + s.artist));
+    if (s.tonart) li.appendChild(el('span', 'tonart', ' \\u00b7 ' + s.tonart));
+    const links = el('div', 'links');
+    if (s.typ !== 'audio') {
+      links.appendChild(link('/datei/' + s.id, s.typ === 'pdf' ? 'PDF' : '\\u00d6ffnen'));
+    }
+    if (s.audio) links.appendChild(link('/audio/' + s.id, '\\u25B6 Audio'));
+    li.appendChild(links);
+    const tags = el('div');
+    for (const t of s.tags) tags.appendChild(el('span', 'tag', t));
+    li.appendChild(tags);
+    liste.appendChild(li);
+  }
+  anzahl.textContent = (n === songs.length ? n + ' Songs' : n + ' von ' + songs.length + ' Songs')
+    + (n > MAX ? ', die ersten ' + MAX + ' angezeigt' : '');
+}
+
+suche.addEventListener('input', zeigen);
+zeigen();
+";
@@ -0,0 +1,185 @@
+//! The log: everything logged through the `log` facade goes to stderr and
+//! to `songindex.log` in the data dir, one line per message with time, level
+//! and module. At [`MAX_BYTES`] the file is rotated; [`KEEP`] older ones stay
+//! (`songindex.log.1` is the newest). Warnings and errors also show up as
+//! toasts in the window.
+
+use crate::backup::{unix_now, utc_parts};
+use crate::config::data_dir;
+use crate::ui;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Mutex;
+
+pub const MAX_BYTES: u64 = 1024 * 1024;
+const KEEP: usize = 3;
+
+pub fn log_path() -> PathBuf {
+    data_dir().join("songindex.log")
+}
+
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{n}"));
+    PathBuf::from(name)
+}
+
+struct LogFile {
+    path: PathBuf,
+    file: File,
+    len: u64,
+}
+
+impl LogFile {
+    fn open(path: PathBuf) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let len = file.metadata()?.len();
+        Ok(LogFile { path, file, len })
+    }
+
+    fn write(&mut self, line: &str) -> std::io::Result<()> {
+        if self.len > 0 && self.len + line.len() as u64 > MAX_BYTES {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.len += line.len() as u64;
+        Ok(())
+    }
+
+    /// `songindex.log.2` becomes `.3`, `.1` becomes `.2`, the current file
+    /// `.1`; the oldest is dropped.
+    fn rotate(&mut self) -> std::io::Result<()> {
+        for n in (1..KEEP).rev() {
+            let from = rotated_path(&self.path, n);
+            if from.exists() {
+                std::fs::rename(&from, rotated_path(&self.path, n + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        *self = LogFile::open(self.path.clone())?;
+        Ok(())
+    }
+}
+
+struct Logger {
+    /// None until [`init`] opened the file, or if it cannot be written.
+    file: Mutex<Option<LogFile>>,
+}
+
+impl log::Log for Logger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Info
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        eprintln!("{}", record.args());
+        if let Some(file) = self.file.lock().unwrap().as_mut() {
+            let (date, time) = utc_parts(unix_now());
+            let line = format!(
+                "{}-{}-{}T{}:{}:{}Z {:<5} {}: {}\n",
+                &date[..4],
+                &date[4..6],
+                &date[6..],
+                &time[..2],
+                &time[2..4],
+                &time[4..],
+                record.level(),
+                module(record.target()),
+                record.args()
+            );
+            // Nowhere left to report it; stderr still has the message.
+            file.write(&line).ok();
+        }
+        match record.level() {
+            log::Level::Error => ui::notify(ui::Level::Error, record.args().to_string()),
+            log::Level::Warn => ui::notify(ui::Level::Warning, record.args().to_string()),
+            _ => {}
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(file) = self.file.lock().unwrap().as_mut() {
+            file.file.flush().ok();
+        }
+    }
+}
+
+static LOGGER: Logger = Logger {
+    file: Mutex::new(None),
+};
+
+/// "songindex_core::scanner" → "scanner".
+fn module(target: &str) -> &str {
+    target.rsplit("::").next().unwrap_or(target)
+}
+
+/// Install the logger. Messages before this only went nowhere, so call it
+/// first thing in `main`.
+pub fn init() {
+    let path = log_path();
+    match LogFile::open(path.clone()) {
+        Ok(file) => *LOGGER.file.lock().unwrap() = Some(file),
+        Err(e) => eprintln!("Songindex: cannot write {}: {e}", path.display()),
+    }
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(log::LevelFilter::Info);
+    }
+}
+
+/// One message of the log file.
+#[derive(Debug, Clone)]
+pub struct LogLine {
+    /// UTC, as written: `2026-10-17T06:30:00Z`.
+    pub zeit: String,
+    pub level: log::Level,
+    pub module: String,
+    pub text: String,
+}
+
+/// The last `limit` messages of the current and the previous log file,
+/// oldest first. Lines that do not start with a time and level (a message
+/// spanning several lines) belong to the message before.
+pub fn read_lines(limit: usize) -> Vec<LogLine> {
+    let path = log_path();
+    let mut lines: Vec<LogLine> = Vec::new();
+    for file in [rotated_path(&path, 1), path] {
+        let Ok(content) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        for raw in content.lines() {
+            match parse_line(raw) {
+                Some(line) => lines.push(line),
+                None => {
+                    if let Some(last) = lines.last_mut() {
+                        last.text.push('\n');
+                        last.text.push_str(raw);
+                    }
+                }
+            }
+        }
+    }
+    let skip = lines.len().saturating_sub(limit);
+    lines.split_off(skip)
+}
+
+fn parse_line(raw: &str) -> Option<LogLine> {
+    let (zeit, rest) = raw.split_once(' ')?;
+    if !zeit.ends_with('Z') {
+        return None;
+    }
+    let rest = rest.trim_start();
+    let (level, rest) = rest.split_once(' ')?;
+    let level = log::Level::from_str(level).ok()?;
+    let (module, text) = rest.trim_start().split_once(": ")?;
+    Some(LogLine {
+        zeit: zeit.to_string(),
+        level,
+        module: module.to_string(),
+        text: text.to_string(),
+    })
+}
@@ -0,0 +1,121 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+/// Least-recently-used cache bounded by an estimated byte size instead of an
+/// entry count, so a few large items and many small ones share one budget.
+pub struct LruCache<K, V> {
+    entries: HashMap<K, Entry<V>>,
+    /// Last-use tick → key; the first entry is the next to be evicted.
+    order: BTreeMap<u64, K>,
+    tick: u64,
+    bytes: usize,
+    limit: usize,
+    hits: u64,
+    misses: u64,
+}
+
+struct Entry<V> {
+    value: V,
+    bytes: usize,
+    tick: u64,
+}
+
+/// Snapshot for the cache overlay.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub bytes: usize,
+    pub limit: usize,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CacheStats {
+    /// Share of lookups answered from the cache, `None` before the first lookup.
+    pub fn hit_rate(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> LruCache<K, V> {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+            bytes: 0,
+            limit,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Look up an entry and mark it as recently used.
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        self.tick += 1;
+        let Some(entry) = self.entries.get_mut(key) else {
+            self.misses += 1;
+            return None;
+        };
+        self.hits += 1;
+        let key = self.order.remove(&entry.tick).expect("tick is tracked");
+        entry.tick = self.tick;
+        self.order.insert(self.tick, key);
+        Some(&entry.value)
+    }
+
+    /// Store an entry of roughly `bytes` size. Returns the keys evicted to stay
+    /// within the limit, so callers can release resources held elsewhere.
+    pub fn insert(&mut self, key: K, value: V, bytes: usize) -> Vec<K> {
+        self.remove(&key);
+        self.tick += 1;
+        self.order.insert(self.tick, key.clone());
+        self.entries.insert(
+            key,
+            Entry {
+                value,
+                bytes,
+                tick: self.tick,
+            },
+        );
+        self.bytes += bytes;
+        self.evict()
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let entry = self.entries.remove(key)?;
+        self.order.remove(&entry.tick);
+        self.bytes -= entry.bytes;
+        Some(entry.value)
+    }
+
+    /// Change the byte limit; returns the keys evicted by a lower limit.
+    pub fn set_limit(&mut self, limit: usize) -> Vec<K> {
+        self.limit = limit;
+        self.evict()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.entries.len(),
+            bytes: self.bytes,
+            limit: self.limit,
+            hits: self.hits,
+            misses: self.misses,
+        }
+    }
+
+    /// Drop least recently used entries until the size fits. The newest entry
+    /// always stays, even if it alone exceeds the limit.
+    fn evict(&mut self) -> Vec<K> {
+        let mut evicted = Vec::new();
+        while self.bytes > self.limit && self.entries.len() > 1 {
+            let (_, key) = self.order.pop_first().expect("order matches entries");
+            let entry = self.entries.remove(&key).expect("order matches entries");
+            self.bytes -= entry.bytes;
+            evicted.push(key);
+        }
+        evicted
+    }
+}
@@ -1,80 +1,300 @@
-mod config;
-mod db;
-mod scanner;
+mod artist_folders;
+mod backup;
+mod cli;
+mod curriculum;
+#[cfg(feature = "encryption")]
+mod encryption;
+mod format;
+mod hashtags;
+mod html;
+mod i18n;
+mod importer;
+mod jobs;
+mod keychain;
+mod lan;
+mod logging;
+mod lru;
+mod metronome;
+mod musicbrainz;
+mod obsidian;
+mod open_with;
+mod pdf;
+mod player;
+mod site;
+mod snapshot;
+mod stats;
+mod stretch;
+mod taxonomy;
+mod templates;
+mod thumbnails;
 mod ui;
+mod warmup;
+mod waveform;
 
-use config::{load_config, save_config, Config};
+// Core modules, re-exported so the frontend keeps using `crate::db` etc.
+use songindex_core::{
+    arrangements, autotag, chordpro, companions, config, db, demo, filetype, maintenance,
+    progression, removal_log, repertoire, scanner, transpose, worker,
+};
+
+use config::{read_config, save_config};
 use db::init_db;
 use eframe::egui;
-use notify::{RecursiveMode, Watcher};
+use i18n::{tr, tr_arg};
 use rusqlite::Connection;
-use scanner::{scan_directory, start_watcher};
-use std::sync::{Arc, Mutex};
-use ui::SongIndexApp;
+use scanner::{
+    start_rescan_scheduler, start_scan, start_wake_reconciler, start_watcher, ScanOptions, ScanStatus,
+};
+use std::sync::{Arc, RwLock};
+use ui::{saved_viewport, SongIndexApp};
+use worker::Db;
 
 fn main() {
-    let base_dir = match load_config() {
-        Some(cfg) if cfg.music_dir.is_dir() => cfg.music_dir,
-        _ => {
-            eprintln!("Songindex: no config found, opening folder picker...");
-            match rfd::FileDialog::new()
-                .set_title("Musikordner auswählen")
-                .pick_folder()
-            {
-                Some(dir) => {
-                    save_config(&Config {
-                        music_dir: dir.clone(),
-                    });
-                    dir
-                }
-                None => {
-                    eprintln!("Songindex: no folder selected, exiting.");
-                    return;
+    logging::init();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut demo = args.first().is_some_and(|a| a == "--demo");
+    if !demo {
+        if let Some(code) = cli::run(&args) {
+            std::process::exit(code);
+        }
+    }
+
+    let mut config = match read_config() {
+        Ok(config) => config.unwrap_or_default(),
+        Err(e) => {
+            // Starting with defaults would run the first-run dialog and
+            // overwrite the user's settings. The language setting is in the
+            // broken file, so this shows in the default language.
+            log::error!("Cannot load config, exiting: {e}");
+            rfd::MessageDialog::new()
+                .set_level(rfd::MessageLevel::Error)
+                .set_title(tr("Einstellungen fehlerhaft"))
+                .set_description(tr_arg(
+                    "config.toml enth\u{00E4}lt einen Fehler und wurde nicht ver\u{00E4}ndert:\n\n{}\n\n\
+                     Bitte die Datei korrigieren oder umbenennen und Songindex neu starten.",
+                    e,
+                ))
+                .show();
+            return;
+        }
+    };
+    i18n::set_language(config.language);
+    if !demo {
+        backup::migrate_secrets(&mut config);
+    }
+    if !demo && !config.music_dir.is_dir() {
+        log::info!("No config found, asking for a folder");
+        match first_run() {
+            FirstRun::Folder(dir) => {
+                config.music_dir = dir;
+                if let Err(e) = save_config(&config) {
+                    log::warn!("Failed to write config: {e}");
                 }
             }
+            FirstRun::Demo => demo = true,
+            FirstRun::Quit => {
+                log::info!("No folder selected, exiting");
+                return;
+            }
         }
-    };
+    }
+    if demo {
+        let dir = demo::demo_dir();
+        match demo::create_library(&dir) {
+            Ok(n) => log::info!("Demo library with {n} files in {}", dir.display()),
+            Err(e) => {
+                log::error!("Failed to create the demo library: {e}");
+                return;
+            }
+        }
+        config.music_dir = dir;
+        config.demo = true;
+    }
+    let base_dir = config.music_dir.clone();
 
-    eprintln!("Songindex: scanning {}", base_dir.display());
+    log::info!("Music folder: {}", base_dir.display());
 
-    let db_path = config::data_dir().join("songindex.db");
-    let conn = Connection::open(&db_path).expect("Failed to open database");
-    conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA foreign_keys=ON;")
-        .ok();
-    init_db(&conn);
+    let conn = if demo {
+        open_database_at(&demo::database_path(&base_dir))
+    } else {
+        let path = config.database_path();
+        // A library on an external drive that is not connected: say so
+        // instead of starting over with an empty one.
+        if !path.parent().is_some_and(|dir| dir.is_dir()) {
+            log::error!("Folder of {} not found, exiting", path.display());
+            rfd::MessageDialog::new()
+                .set_level(rfd::MessageLevel::Error)
+                .set_title(tr("Bibliothek nicht gefunden"))
+                .set_description(tr_arg(
+                    "Der Ordner der Bibliothek fehlt:\n{}\n\nIst das Laufwerk verbunden? \
+                     Der Speicherort steht als database in config.toml.",
+                    path.display(),
+                ))
+                .show();
+            return;
+        }
+        open_database(&config)
+    };
+    let Some(conn) = conn else {
+        log::info!("Library not unlocked, exiting");
+        return;
+    };
+
+    let db = Db::spawn(conn);
+    let (notify_tx, notify_rx) = std::sync::mpsc::channel();
 
-    eprintln!("Songindex: initial scan...");
-    scan_directory(&conn, &base_dir);
+    // The window opens right away; the list fills while this runs.
+    log::info!("Initial scan");
+    let scan_options = ScanOptions::from(&config);
+    let review_interval_days = config.review_interval_days;
+    let snapshot_retention_days = config.snapshot_retention_days;
+    let scan_status = ScanStatus::default();
+    start_scan(
+        db.clone(),
+        base_dir.clone(),
+        scan_options.clone(),
+        scan_status.clone(),
+        notify_tx.clone(),
+        move |conn| {
+            if demo {
+                demo::seed_metadata(conn, review_interval_days);
+            }
 
-    let song_count: i64 = conn
-        .query_row("SELECT COUNT(*) FROM songs", [], |row| row.get(0))
-        .unwrap_or(0);
-    eprintln!("Songindex: {} songs indexed", song_count);
+            let song_count: i64 = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM songs WHERE deleted_at IS NULL",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap_or(0);
+            log::info!("{song_count} songs indexed");
 
-    let db = Arc::new(Mutex::new(conn));
+            // The demo library is thrown away; keep it out of snapshots and backups.
+            if !demo {
+                snapshot::take_daily_snapshot(conn, snapshot_retention_days);
+            }
+        },
+    );
 
-    let (notify_tx, notify_rx) = std::sync::mpsc::channel();
+    let scan_options = Arc::new(RwLock::new(scan_options));
+    start_rescan_scheduler(
+        db.clone(),
+        scan_options.clone(),
+        scan_status.clone(),
+        notify_tx.clone(),
+    );
+    let (wake_tx, wake_rx) = std::sync::mpsc::channel();
+    start_wake_reconciler(db.clone(), scan_options.clone(), notify_tx.clone(), wake_tx);
+    let watcher_status = start_watcher(
+        db.clone(),
+        base_dir.clone(),
+        scan_options.clone(),
+        notify_tx.clone(),
+    );
 
-    let mut watcher = start_watcher(db.clone(), base_dir.clone(), notify_tx);
-    watcher
-        .watch(&base_dir, RecursiveMode::Recursive)
-        .expect("Failed to watch directory");
+    if !demo {
+        backup::start_scheduler(db.clone());
+        snapshot::start_scheduler(db.clone(), scan_status.clone());
+        obsidian::start_sync(db.clone(), base_dir.clone());
+    }
 
+    let title = if demo {
+        "Songindex \u{2013} Demo-Bibliothek"
+    } else {
+        "Songindex"
+    };
+    let viewport = egui::ViewportBuilder::default()
+        .with_title(title)
+        .with_inner_size([900.0, 700.0]);
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_title("Songindex")
-            .with_inner_size([900.0, 700.0]),
+        viewport: db
+            .call({
+                let viewport = viewport.clone();
+                move |conn| saved_viewport(conn, viewport)
+            })
+            .unwrap_or(viewport),
         ..Default::default()
     };
 
+    let worker = db.clone();
     eframe::run_native(
         "Songindex",
         options,
-        Box::new(move |_cc| {
-            // Keep watcher alive by moving it into the closure
-            let _watcher = watcher;
-            Ok(Box::new(SongIndexApp::new(db, base_dir, notify_rx)))
+        Box::new(move |cc| {
+            egui_extras::install_image_loaders(&cc.egui_ctx);
+            Ok(Box::new(SongIndexApp::new(
+                db,
+                config,
+                scan_options,
+                notify_rx,
+                watcher_status,
+                scan_status,
+                notify_tx,
+                wake_rx,
+                cc.egui_ctx.clone(),
+            )))
         }),
     )
     .expect("Failed to run eframe");
+    // Let the writes the window queued last (e.g. its state) finish.
+    worker.call(|_| ());
+}
+
+enum FirstRun {
+    Folder(std::path::PathBuf),
+    Demo,
+    Quit,
+}
+
+/// No music folder yet: pick one, or look around in the demo library first.
+fn first_run() -> FirstRun {
+    let pick = tr("Musikordner w\u{00E4}hlen\u{2026}");
+    let demo = tr("Demo-Bibliothek");
+    let choice = rfd::MessageDialog::new()
+        .set_title(tr("Willkommen bei Songindex"))
+        .set_description(tr(
+            "W\u{00E4}hle den Ordner mit deinen Noten. Oder probiere Songindex zuerst mit \
+             einer kleinen Demo-Bibliothek aus: Beispiel-Songs mit Tags, Tonarten und Notizen \
+             in einem tempor\u{00E4}ren Ordner; deine Dateien bleiben unber\u{00FC}hrt.",
+        ))
+        .set_buttons(rfd::MessageButtons::YesNoCancelCustom(
+            pick.to_string(),
+            demo.to_string(),
+            tr("Beenden").to_string(),
+        ))
+        .show();
+    match choice {
+        rfd::MessageDialogResult::Custom(c) if c == demo => FirstRun::Demo,
+        rfd::MessageDialogResult::Custom(c) if c == pick => {
+            match rfd::FileDialog::new()
+                .set_title(tr("Musikordner ausw\u{00E4}hlen"))
+                .pick_folder()
+            {
+                Some(dir) => FirstRun::Folder(dir),
+                None => FirstRun::Quit,
+            }
+        }
+        _ => FirstRun::Quit,
+    }
+}
+
+/// The library at `config.database_path()`, unlocked if encrypted and
+/// migrated. `None` if the passphrase prompt was cancelled.
+fn open_database(config: &config::Config) -> Option<Connection> {
+    open_database_at(&config.database_path())
+}
+
+fn open_database_at(db_path: &std::path::Path) -> Option<Connection> {
+    #[cfg(feature = "encryption")]
+    let conn = if encryption::is_encrypted(db_path) {
+        encryption::unlock(db_path)?
+    } else {
+        Connection::open(db_path).expect("Failed to open database")
+    };
+    #[cfg(not(feature = "encryption"))]
+    let conn = Connection::open(db_path).expect("Failed to open database");
+    conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA foreign_keys=ON;")
+        .ok();
+    init_db(&conn).expect("Failed to initialize database");
+    Some(conn)
 }
@@ -0,0 +1,148 @@
+use crate::player::{open_output, Output};
+use rodio::source::SeekError;
+use rodio::{ChannelCount, Sample, SampleRate, Sink, Source};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub const MIN_BPM: u32 = 20;
+pub const MAX_BPM: u32 = 300;
+
+const SAMPLE_RATE: u32 = 44_100;
+/// Length of one click; a short decaying sine.
+const CLICK_MS: u32 = 30;
+
+/// Settings shared between the metronome window and the audio thread.
+pub struct MetronomeControls {
+    bpm: AtomicU32,
+    /// Beats per bar (the time signature's numerator).
+    beats: AtomicU32,
+    /// Higher click on the first beat of each bar.
+    accent: AtomicBool,
+    /// Beats played since the start, for the beat indicator.
+    beat: AtomicU64,
+}
+
+impl MetronomeControls {
+    pub fn new(bpm: u32, beats: u32, accent: bool) -> Arc<Self> {
+        let controls = Arc::new(Self {
+            bpm: AtomicU32::new(0),
+            beats: AtomicU32::new(0),
+            accent: AtomicBool::new(accent),
+            beat: AtomicU64::new(0),
+        });
+        controls.set_bpm(bpm);
+        controls.set_beats(beats);
+        controls
+    }
+
+    pub fn set_bpm(&self, bpm: u32) {
+        self.bpm
+            .store(bpm.clamp(MIN_BPM, MAX_BPM), Ordering::Relaxed);
+    }
+
+    pub fn set_beats(&self, beats: u32) {
+        self.beats.store(beats.max(1), Ordering::Relaxed);
+    }
+
+    pub fn set_accent(&self, accent: bool) {
+        self.accent.store(accent, Ordering::Relaxed);
+    }
+
+    /// Position in the bar of the beat that sounded last, 0 = first beat.
+    pub fn beat_in_bar(&self) -> u32 {
+        let beat = self.beat.load(Ordering::Relaxed).saturating_sub(1);
+        (beat % u64::from(self.beats.load(Ordering::Relaxed))) as u32
+    }
+}
+
+/// Endless click track following [`MetronomeControls`]; tempo and time
+/// signature changes apply from the next beat.
+struct Clicks {
+    controls: Arc<MetronomeControls>,
+    /// Sample within the current beat.
+    pos: u32,
+    beat_len: u32,
+    frequency: f32,
+}
+
+impl Clicks {
+    fn new(controls: Arc<MetronomeControls>) -> Self {
+        Self {
+            controls,
+            pos: 0,
+            beat_len: 0,
+            frequency: 0.0,
+        }
+    }
+}
+
+impl Iterator for Clicks {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        if self.pos >= self.beat_len {
+            let controls = &self.controls;
+            let bpm = controls.bpm.load(Ordering::Relaxed);
+            let beats = u64::from(controls.beats.load(Ordering::Relaxed));
+            let beat = controls.beat.fetch_add(1, Ordering::Relaxed);
+            let accented = controls.accent.load(Ordering::Relaxed) && beat.is_multiple_of(beats);
+            self.frequency = if accented { 1760.0 } else { 1320.0 };
+            self.beat_len = SAMPLE_RATE * 60 / bpm;
+            self.pos = 0;
+        }
+        let t = self.pos as f32 / SAMPLE_RATE as f32;
+        self.pos += 1;
+        if self.pos > SAMPLE_RATE * CLICK_MS / 1000 {
+            return Some(0.0);
+        }
+        let envelope = (-t * 150.0).exp();
+        Some(0.6 * envelope * (2.0 * std::f32::consts::PI * self.frequency * t).sin())
+    }
+}
+
+impl Source for Clicks {
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> ChannelCount {
+        1
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+
+    fn try_seek(&mut self, _: Duration) -> Result<(), SeekError> {
+        Err(SeekError::NotSupported {
+            underlying_source: "Clicks",
+        })
+    }
+}
+
+/// A running metronome. Dropping it stops the clicks.
+pub struct Metronome {
+    _sink: Sink,
+    _output: Output,
+    pub controls: Arc<MetronomeControls>,
+}
+
+impl Metronome {
+    /// Start clicking on the default output; fails on builds without audio
+    /// output.
+    pub fn start(controls: Arc<MetronomeControls>) -> Result<Self, String> {
+        let (output, sink) = open_output()?;
+        controls.beat.store(0, Ordering::Relaxed);
+        sink.append(Clicks::new(controls.clone()));
+        Ok(Self {
+            _sink: sink,
+            _output: output,
+            controls,
+        })
+    }
+}
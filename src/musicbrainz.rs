@@ -0,0 +1,216 @@
+use crate::transpose::Key;
+use serde_json::Value;
+use std::cmp::Reverse;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const API: &str = "https://musicbrainz.org/ws/2";
+/// MusicBrainz asks every client to identify itself with a contact.
+const USER_AGENT: &str = concat!(
+    "songindex/",
+    env!("CARGO_PKG_VERSION"),
+    " ( https://github.com/derseitenschneider/songindex )"
+);
+/// At most one request per second, as the MusicBrainz rate limit asks.
+const MIN_INTERVAL: Duration = Duration::from_millis(1100);
+const SEARCH_LIMIT: usize = 25;
+const MAX_CANDIDATES: usize = 6;
+
+static LAST_REQUEST: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// A song as MusicBrainz knows it: one search hit, with the recordings of
+/// the same title and artist folded in so `jahr` is the earliest release.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub recording_id: String,
+    pub titel: String,
+    /// Artist credit as MusicBrainz spells it.
+    pub artist: String,
+    pub artist_id: Option<String>,
+    pub jahr: Option<i64>,
+    /// Search score, 0–100.
+    pub score: i64,
+}
+
+/// What a second round of requests finds for a candidate.
+#[derive(Debug, Clone, Default)]
+pub struct Details {
+    /// Key of the work the recording performs, e.g. `Em`.
+    pub tonart: Option<String>,
+    /// Genres of the recording, else of the artist; most votes first.
+    pub genres: Vec<String>,
+}
+
+/// GET `path` below the API as JSON, waiting out the rate limit first.
+fn get(path: &str, query: &[(&str, &str)]) -> Result<Value, String> {
+    {
+        let mut last = LAST_REQUEST.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(wait) = last.and_then(|t| MIN_INTERVAL.checked_sub(t.elapsed())) {
+            std::thread::sleep(wait);
+        }
+        *last = Some(Instant::now());
+    }
+    let mut request = ureq::get(&format!("{API}/{path}"))
+        .set("User-Agent", USER_AGENT)
+        .set("Accept", "application/json")
+        .timeout(Duration::from_secs(15));
+    for (name, value) in query {
+        request = request.query(name, value);
+    }
+    let body = request
+        .query("fmt", "json")
+        .call()
+        .map_err(|e| e.to_string())?
+        .into_string()
+        .map_err(|e| e.to_string())?;
+    serde_json::from_str(&body).map_err(|e| e.to_string())
+}
+
+/// A quoted Lucene phrase.
+fn phrase(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn year(date: &str) -> Option<i64> {
+    date.get(..4)?.parse().ok()
+}
+
+/// Recordings matching the title and, if given, the artist guess, best
+/// first. Versions of the same song (same title and artist) count once.
+pub fn search(titel: &str, artist: &str) -> Result<Vec<Candidate>, String> {
+    let mut query = format!("recording:{}", phrase(titel.trim()));
+    if !artist.trim().is_empty() {
+        query.push_str(&format!(" AND artist:{}", phrase(artist.trim())));
+    }
+    let limit = SEARCH_LIMIT.to_string();
+    let json = get("recording", &[("query", &query), ("limit", &limit)])?;
+
+    let mut candidates: Vec<Candidate> = Vec::new();
+    for recording in json["recordings"].as_array().into_iter().flatten() {
+        let credits = recording["artist-credit"].as_array();
+        let artist = credits
+            .into_iter()
+            .flatten()
+            .map(|c| {
+                let name = c["name"].as_str().unwrap_or_default();
+                format!("{name}{}", c["joinphrase"].as_str().unwrap_or_default())
+            })
+            .collect::<String>();
+        let candidate = Candidate {
+            recording_id: recording["id"].as_str().unwrap_or_default().to_string(),
+            titel: recording["title"].as_str().unwrap_or_default().to_string(),
+            artist_id: credits
+                .and_then(|c| c.first())
+                .and_then(|c| c["artist"]["id"].as_str())
+                .map(str::to_string),
+            artist,
+            jahr: recording["first-release-date"].as_str().and_then(year),
+            score: recording["score"].as_i64().unwrap_or_default(),
+        };
+        if candidate.recording_id.is_empty() {
+            continue;
+        }
+        match candidates.iter_mut().find(|c| {
+            c.titel.to_lowercase() == candidate.titel.to_lowercase()
+                && c.artist_id == candidate.artist_id
+        }) {
+            Some(existing) => {
+                // The earliest version is the original; keep the best score.
+                if candidate
+                    .jahr
+                    .is_some_and(|j| existing.jahr.is_none_or(|e| j < e))
+                {
+                    existing.jahr = candidate.jahr;
+                    existing.recording_id = candidate.recording_id;
+                }
+                existing.score = existing.score.max(candidate.score);
+            }
+            None => candidates.push(candidate),
+        }
+    }
+    candidates.sort_by_key(|c| Reverse(c.score));
+    candidates.truncate(MAX_CANDIDATES);
+    Ok(candidates)
+}
+
+/// Key and genres for a candidate: two to four requests, so a few seconds.
+pub fn details(candidate: &Candidate) -> Result<Details, String> {
+    let recording = get(
+        &format!("recording/{}", candidate.recording_id),
+        &[("inc", "genres+work-rels")],
+    )?;
+    let mut details = Details {
+        genres: genres(&recording),
+        ..Default::default()
+    };
+
+    let work_id = recording["relations"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|r| r["type"] == "performance")
+        .and_then(|r| r["work"]["id"].as_str());
+    if let Some(work_id) = work_id {
+        let work = get(&format!("work/{work_id}"), &[])?;
+        details.tonart = work["attributes"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|a| a["type"] == "Key")
+            .and_then(|a| a["value"].as_str())
+            .and_then(key_name);
+    }
+
+    if details.genres.is_empty() {
+        if let Some(ref artist_id) = candidate.artist_id {
+            let artist = get(&format!("artist/{artist_id}"), &[("inc", "genres")])?;
+            details.genres = genres(&artist);
+        }
+    }
+    Ok(details)
+}
+
+fn genres(entity: &Value) -> Vec<String> {
+    let mut genres: Vec<(i64, String)> = entity["genres"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|g| {
+            Some((
+                g["count"].as_i64().unwrap_or(0),
+                g["name"].as_str()?.to_string(),
+            ))
+        })
+        .collect();
+    genres.sort_by_key(|(count, _)| Reverse(*count));
+    genres.into_iter().map(|(_, name)| name).collect()
+}
+
+/// MusicBrainz writes keys as `F-sharp minor` or `B-flat major`; the
+/// library uses `F#m` and `Bb`.
+fn key_name(value: &str) -> Option<String> {
+    let value = value.replace("-sharp", "#").replace("-flat", "b");
+    Key::parse(&value).map(Key::name)
+}
+
+/// The `stil` value for a MusicBrainz genre: an existing value if one
+/// matches regardless of case, else the genre with each word capitalized.
+pub fn genre_tag(genre: &str, stile: &[String]) -> String {
+    if let Some(existing) = stile
+        .iter()
+        .find(|s| s.to_lowercase() == genre.to_lowercase())
+    {
+        return existing.clone();
+    }
+    genre
+        .split(' ')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
@@ -0,0 +1,298 @@
+use crate::config::load_config;
+use crate::html::url_path;
+use crate::i18n::{tr, tr_arg, tr_args};
+use crate::worker::Db;
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// First frontmatter line of every generated note; stale notes are only
+/// deleted if they start with it, so the user's own notes are never touched.
+const MARKER: &str = "---\nsongindex_id: ";
+
+/// What a note is built from, read in one go so the DB lock is short.
+struct NoteSong {
+    id: i64,
+    titel: String,
+    artist: Option<String>,
+    dateipfad: String,
+    audio_pfad: Option<String>,
+    tonart: Option<String>,
+    bpm: Option<i64>,
+    kapo: Option<i64>,
+    kann_ich: bool,
+    notizen: Option<String>,
+    tags: Vec<String>,
+    /// (label, url) from `song_links`.
+    links: Vec<(String, String)>,
+}
+
+pub struct SyncSummary {
+    pub total: usize,
+    pub written: usize,
+    pub removed: usize,
+}
+
+fn load_songs(conn: &Connection) -> Vec<NoteSong> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, titel, artist, dateipfad, audio_pfad, tonart, bpm, kapo,
+                    repertoire_seit IS NOT NULL, notizen
+             FROM songs WHERE deleted_at IS NULL AND ignoriert = 0
+             ORDER BY titel COLLATE NOCASE, id",
+        )
+        .unwrap();
+    let mut songs: Vec<NoteSong> = stmt
+        .query_map([], |row| {
+            Ok(NoteSong {
+                id: row.get(0)?,
+                titel: row.get(1)?,
+                artist: row.get(2)?,
+                dateipfad: row.get(3)?,
+                audio_pfad: row.get(4)?,
+                tonart: row.get(5)?,
+                bpm: row.get(6)?,
+                kapo: row.get(7)?,
+                kann_ich: row.get(8)?,
+                notizen: row.get(9)?,
+                tags: Vec::new(),
+                links: Vec::new(),
+            })
+        })
+        .unwrap()
+        .filter_map(|r| r.ok())
+        .collect();
+    let index: HashMap<i64, usize> = songs.iter().enumerate().map(|(i, s)| (s.id, i)).collect();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT st.song_id, t.kategorie, t.wert FROM song_tags st
+             JOIN tags t ON t.id = st.tag_id ORDER BY t.kategorie, t.wert",
+        )
+        .unwrap();
+    let tags = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })
+        .unwrap()
+        .filter_map(|r| r.ok());
+    for (song_id, kategorie, wert) in tags {
+        if let Some(&i) = index.get(&song_id) {
+            songs[i].tags.push(tag_name(&kategorie, &wert));
+        }
+    }
+
+    let mut stmt = conn
+        .prepare("SELECT song_id, label, url FROM song_links ORDER BY id")
+        .unwrap();
+    let links = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })
+        .unwrap()
+        .filter_map(|r| r.ok());
+    for (song_id, label, url) in links {
+        if let Some(&i) = index.get(&song_id) {
+            songs[i].links.push((label, url));
+        }
+    }
+    songs
+}
+
+/// Nested Obsidian tag `kategorie/wert`; characters Obsidian does not allow
+/// in tags become underscores, like spaces in the hashtag input.
+fn tag_name(kategorie: &str, wert: &str) -> String {
+    format!("{kategorie}/{wert}")
+        .chars()
+        .map(|c| match c {
+            c if c.is_alphanumeric() || matches!(c, '/' | '-' | '_') => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+/// "Titel – Artist", without characters that are not allowed in file names
+/// or break wiki links.
+fn note_name(song: &NoteSong) -> String {
+    let name = match song.artist {
+        Some(ref artist) => format!("{} \u{2013} {artist}", song.titel),
+        None => song.titel.clone(),
+    };
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' | '^' | '[' | ']' => '-',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect();
+    let name = name.trim().trim_start_matches('.');
+    if name.is_empty() {
+        format!("Song {}", song.id)
+    } else {
+        name.to_string()
+    }
+}
+
+fn yaml_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn file_url(base_dir: &Path, rel_path: &str) -> String {
+    format!(
+        "file://{}",
+        url_path(&base_dir.join(rel_path).to_string_lossy())
+    )
+}
+
+fn render(song: &NoteSong, base_dir: &Path) -> String {
+    let mut md = String::new();
+    let _ = writeln!(md, "{MARKER}{}", song.id);
+    let _ = writeln!(md, "titel: {}", yaml_string(&song.titel));
+    let _ = writeln!(md, "aliases:\n  - {}", yaml_string(&song.titel));
+    if let Some(ref artist) = song.artist {
+        let _ = writeln!(md, "artist: {}", yaml_string(artist));
+    }
+    if let Some(ref tonart) = song.tonart {
+        let _ = writeln!(md, "tonart: {}", yaml_string(tonart));
+    }
+    if let Some(bpm) = song.bpm {
+        let _ = writeln!(md, "bpm: {bpm}");
+    }
+    if let Some(kapo) = song.kapo {
+        let _ = writeln!(md, "kapo: {kapo}");
+    }
+    let _ = writeln!(md, "kann_ich: {}", song.kann_ich);
+    let _ = writeln!(md, "datei: {}", yaml_string(&song.dateipfad));
+    if song.tags.is_empty() {
+        md.push_str("tags: []\n");
+    } else {
+        md.push_str("tags:\n");
+        for tag in &song.tags {
+            let _ = writeln!(md, "  - {}", yaml_string(tag));
+        }
+    }
+    md.push_str("---\n\n");
+
+    let _ = writeln!(md, "# {}\n", song.titel);
+    let _ = write!(
+        md,
+        "[Noten \u{00F6}ffnen]({})",
+        file_url(base_dir, &song.dateipfad)
+    );
+    if let Some(ref audio) = song.audio_pfad {
+        let _ = write!(md, " \u{00B7} [Audio]({})", file_url(base_dir, audio));
+    }
+    md.push('\n');
+
+    if !song.links.is_empty() {
+        md.push_str("\n## Links\n\n");
+        for (label, url) in &song.links {
+            let _ = writeln!(md, "- [{label}](<{url}>)");
+        }
+    }
+    if let Some(ref notizen) = song.notizen {
+        let _ = writeln!(md, "\n## Notizen\n\n{}", notizen.trim_end());
+    }
+    md.push_str(
+        "\n%% Von Songindex erzeugt \u{2013} \u{00C4}nderungen an dieser Notiz werden beim \
+         n\u{00E4}chsten Abgleich \u{00FC}berschrieben. %%\n",
+    );
+    md
+}
+
+/// Write one note per song into `dir`. Unchanged notes are left alone (so
+/// Obsidian does not reindex them), and generated notes of songs that are
+/// gone or renamed are deleted.
+pub fn sync(db: &Db, base_dir: &Path, dir: &Path) -> Result<SyncSummary, String> {
+    let songs = db
+        .call(|conn| load_songs(conn))
+        .ok_or(tr("Die Bibliothek konnte nicht gelesen werden"))?;
+    std::fs::create_dir_all(dir)
+        .map_err(|e| tr_arg("Ordner konnte nicht angelegt werden: {}", e))?;
+
+    let mut names = HashSet::new();
+    let mut written = 0;
+    for song in &songs {
+        let mut name = note_name(song);
+        if !names.insert(name.to_lowercase()) {
+            name = format!("{name} ({})", song.id);
+            names.insert(name.to_lowercase());
+        }
+        let path = dir.join(format!("{name}.md"));
+        let content = render(song, base_dir);
+        if std::fs::read_to_string(&path).ok().as_deref() == Some(content.as_str()) {
+            continue;
+        }
+        std::fs::write(&path, content).map_err(|e| {
+            tr_args(
+                "{} konnte nicht geschrieben werden: {}",
+                &[&path.display(), &e],
+            )
+        })?;
+        written += 1;
+    }
+
+    let mut removed = 0;
+    let entries = std::fs::read_dir(dir).map_err(|e| e.to_string())?;
+    for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+        let stem = path.file_stem().map(|s| s.to_string_lossy().to_lowercase());
+        let stale = path.extension().is_some_and(|e| e == "md")
+            && stem.is_some_and(|s| !names.contains(&s))
+            && std::fs::read_to_string(&path).is_ok_and(|c| c.starts_with(MARKER));
+        if stale && std::fs::remove_file(&path).is_ok() {
+            removed += 1;
+        }
+    }
+
+    Ok(SyncSummary {
+        total: songs.len(),
+        written,
+        removed,
+    })
+}
+
+/// Background thread that keeps the vault folder current while
+/// `aktuell_halten` is on. Every write to the library bumps SQLite's
+/// `total_changes()` on the worker's connection, so a round without changes
+/// costs one query. The config is re-read every round.
+pub fn start_sync(db: Db, base_dir: PathBuf) {
+    std::thread::spawn(move || {
+        let mut last: Option<(PathBuf, i64)> = None;
+        loop {
+            std::thread::sleep(Duration::from_secs(10));
+
+            let Some(cfg) = load_config().map(|c| c.obsidian) else {
+                continue;
+            };
+            let (true, Some(dir)) = (cfg.aktuell_halten, cfg.ordner) else {
+                last = None;
+                continue;
+            };
+            let Some(changes) = db.call(|conn| {
+                conn.query_row("SELECT total_changes()", [], |row| row.get::<_, i64>(0))
+                    .unwrap_or(0)
+            }) else {
+                continue;
+            };
+            let state = Some((dir.clone(), changes));
+            if state == last {
+                continue;
+            }
+            match sync(&db, &base_dir, &dir) {
+                Ok(_) => last = state,
+                Err(e) => log::warn!("Obsidian: {e}"),
+            }
+        }
+    });
+}
@@ -0,0 +1,142 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The app a file opens in: the song's own choice, else the one configured
+/// for its extension. `None` means the system default.
+pub fn app_for<'a>(
+    mappings: &'a BTreeMap<String, String>,
+    song_app: Option<&'a str>,
+    path: &Path,
+) -> Option<&'a str> {
+    song_app.filter(|app| !app.trim().is_empty()).or_else(|| {
+        let ext = path.extension()?.to_string_lossy().to_lowercase();
+        mappings
+            .get(&ext)
+            .map(String::as_str)
+            .filter(|app| !app.trim().is_empty())
+    })
+}
+
+/// `open -a <app>` for a chosen app (a name like "Guitar Pro 8" or a path to
+/// the `.app`), plain `open` otherwise.
+pub fn open(path: &Path, app: Option<&str>) {
+    let mut command = std::process::Command::new("open");
+    if let Some(app) = app {
+        command.arg("-a").arg(app.trim());
+    }
+    if let Err(e) = command.arg(path).spawn() {
+        log::warn!("open {}: {e}", path.display());
+    }
+}
+
+/// A link in the default browser, or the app registered for its scheme
+/// (`spotify:`, `obsidian:`).
+pub fn open_url(url: &str) {
+    if let Err(e) = std::process::Command::new("open").arg(url).spawn() {
+        log::warn!("open {url}: {e}");
+    }
+}
+
+/// Menu label for [`reveal`], named after the platform's file manager.
+pub const REVEAL_LABEL: &str = if cfg!(target_os = "macos") {
+    "Im Finder zeigen"
+} else if cfg!(windows) {
+    "Im Explorer zeigen"
+} else {
+    "Im Dateimanager zeigen"
+};
+
+/// Show the file in the file manager with it selected. A file that is gone
+/// opens its folder instead, if that still exists.
+pub fn reveal(path: &Path) {
+    if !path.exists() {
+        match path.parent().filter(|dir| dir.is_dir()) {
+            Some(dir) => open_folder(dir),
+            None => log::warn!("reveal {}: not found", path.display()),
+        }
+        return;
+    }
+    if let Err(e) = reveal_command(path).spawn() {
+        log::warn!("reveal {}: {e}", path.display());
+    }
+}
+
+/// A folder in the file manager.
+pub fn open_folder(dir: &Path) {
+    let program = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+    if let Err(e) = std::process::Command::new(program).arg(dir).spawn() {
+        log::warn!("{program} {}: {e}", dir.display());
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn reveal_command(path: &Path) -> std::process::Command {
+    let mut command = std::process::Command::new("open");
+    command.arg("-R").arg(path);
+    command
+}
+
+#[cfg(windows)]
+fn reveal_command(path: &Path) -> std::process::Command {
+    use std::os::windows::process::CommandExt;
+    // Explorer wants the quotes around the path only, not the whole argument.
+    let mut command = std::process::Command::new("explorer");
+    command.raw_arg(format!("/select,\"{}\"", path.display()));
+    command
+}
+
+/// File managers that implement the freedesktop `FileManager1` interface
+/// (Nautilus, Dolphin, Nemo, Thunar…) select the file; without one the
+/// folder opens via `xdg-open`.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn reveal_command(path: &Path) -> std::process::Command {
+    let dir = path.parent().unwrap_or(path).to_path_buf();
+    let script = "dbus-send --session --print-reply --dest=org.freedesktop.FileManager1 \
+                  --type=method_call /org/freedesktop/FileManager1 \
+                  org.freedesktop.FileManager1.ShowItems \"array:string:$1\" string: \
+                  >/dev/null 2>&1 || xdg-open \"$2\"";
+    let mut command = std::process::Command::new("sh");
+    command
+        .arg("-c")
+        .arg(script)
+        .arg("sh")
+        .arg(file_uri(path))
+        .arg(dir);
+    command
+}
+
+/// `file://` URI with everything but unreserved characters and `/`
+/// percent-encoded; `dbus-send` would split an array at a bare comma.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn file_uri(path: &Path) -> String {
+    use std::os::unix::ffi::OsStrExt;
+    let mut uri = String::from("file://");
+    for &byte in path.as_os_str().as_bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    uri
+}
+
+/// Let the user pick an app bundle; the path works with `open -a`.
+pub fn pick_app() -> Option<String> {
+    rfd::FileDialog::new()
+        .set_directory("/Applications")
+        .add_filter("Programm", &["app"])
+        .pick_file()
+        .map(|path| path.to_string_lossy().into_owned())
+}
+
+/// [`open`] with the app configured for the file's extension.
+pub fn open_by_extension(path: &Path, mappings: &BTreeMap<String, String>) {
+    open(path, app_for(mappings, None, path));
+}
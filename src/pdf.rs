@@ -0,0 +1,247 @@
+use crate::i18n::tr;
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Document, Object, ObjectId, Stream, StringFormat};
+use std::path::{Path, PathBuf};
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 50.0;
+
+/// One line of a text report. Cells are placed at fixed x offsets from the
+/// left margin, which is enough for simple tables.
+pub struct ReportLine {
+    pub cells: Vec<(f32, String)>,
+    pub size: f32,
+    pub bold: bool,
+    pub highlight: bool,
+}
+
+impl ReportLine {
+    pub fn text(text: impl Into<String>, size: f32, bold: bool) -> Self {
+        Self {
+            cells: vec![(0.0, text.into())],
+            size,
+            bold,
+            highlight: false,
+        }
+    }
+
+    pub fn row(cells: Vec<(f32, String)>, bold: bool, highlight: bool) -> Self {
+        Self {
+            cells,
+            size: 10.0,
+            bold,
+            highlight,
+        }
+    }
+
+    pub fn blank() -> Self {
+        Self::text("", 6.0, false)
+    }
+}
+
+/// Standard PDF fonts use WinAnsiEncoding; map the few non-Latin-1 characters
+/// we use and replace everything else.
+fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            '\u{20AC}' => 0x80,
+            '\u{201E}' => 0x84,
+            '\u{2026}' => 0x85,
+            '\u{2013}' => 0x96,
+            '\u{2014}' => 0x97,
+            '\u{201C}' => 0x93,
+            '\u{201D}' => 0x94,
+            '\u{2022}' => 0x95,
+            c if (c as u32) < 0x100 => c as u8,
+            _ => b'?',
+        })
+        .collect()
+}
+
+fn font(doc: &mut Document, base: &str) -> ObjectId {
+    doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => base,
+        "Encoding" => "WinAnsiEncoding",
+    })
+}
+
+/// Write `lines` as an A4 document, starting a new page whenever the current one is full.
+pub fn write_report(path: &Path, lines: &[ReportLine]) -> Result<(), String> {
+    let mut doc = report_document(lines)?;
+    doc.compress();
+    doc.save(path).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn report_document(lines: &[ReportLine]) -> Result<Document, String> {
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+    let regular = font(&mut doc, "Helvetica");
+    let bold = font(&mut doc, "Helvetica-Bold");
+    let resources_id = doc.add_object(dictionary! {
+        "Font" => dictionary! { "F1" => regular, "F2" => bold },
+    });
+
+    let mut pages: Vec<Vec<Operation>> = vec![Vec::new()];
+    let mut y = PAGE_HEIGHT - MARGIN;
+    for line in lines {
+        let height = line.size * 1.45;
+        if y - height < MARGIN {
+            pages.push(Vec::new());
+            y = PAGE_HEIGHT - MARGIN;
+        }
+        y -= height;
+        let ops = pages.last_mut().unwrap();
+        let (r, g, b) = if line.highlight {
+            (0.75, 0.15, 0.15)
+        } else {
+            (0.0, 0.0, 0.0)
+        };
+        for (x, text) in &line.cells {
+            if text.is_empty() {
+                continue;
+            }
+            ops.push(Operation::new("BT", vec![]));
+            ops.push(Operation::new("rg", vec![r.into(), g.into(), b.into()]));
+            ops.push(Operation::new(
+                "Tf",
+                vec![if line.bold { "F2" } else { "F1" }.into(), line.size.into()],
+            ));
+            ops.push(Operation::new("Td", vec![(MARGIN + x).into(), y.into()]));
+            ops.push(Operation::new(
+                "Tj",
+                vec![Object::String(win_ansi(text), StringFormat::Literal)],
+            ));
+            ops.push(Operation::new("ET", vec![]));
+        }
+    }
+
+    let mut kids: Vec<Object> = Vec::new();
+    for operations in pages {
+        let content = Content { operations };
+        let content_id = doc.add_object(Stream::new(
+            dictionary! {},
+            content.encode().map_err(|e| e.to_string())?,
+        ));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Contents" => content_id,
+        });
+        kids.push(page_id.into());
+    }
+    let count = kids.len() as i64;
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => count,
+            "Resources" => resources_id,
+            "MediaBox" => vec![0.into(), 0.into(), PAGE_WIDTH.into(), PAGE_HEIGHT.into()],
+        }),
+    );
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    doc.trailer.set("Root", catalog_id);
+    Ok(doc)
+}
+
+/// Page attributes a page may inherit from its page tree nodes. They are
+/// copied onto the page, since merging drops the source's page tree.
+const INHERITED: [&[u8]; 4] = [b"Resources", b"MediaBox", b"CropBox", b"Rotate"];
+
+/// Concatenate the PDFs in `files` into one document at `path`, in order,
+/// after a cover made of `cover` if given. Files that cannot be read (missing,
+/// damaged or encrypted) are left out and returned.
+pub fn merge(
+    path: &Path,
+    cover: Option<&[ReportLine]>,
+    files: &[PathBuf],
+) -> Result<Vec<PathBuf>, String> {
+    let mut sources = Vec::new();
+    if let Some(lines) = cover {
+        sources.push(report_document(lines)?);
+    }
+    let mut skipped = Vec::new();
+    for file in files {
+        match Document::load(file) {
+            Ok(doc) if !doc.is_encrypted() && !doc.get_pages().is_empty() => sources.push(doc),
+            _ => skipped.push(file.clone()),
+        }
+    }
+    if sources.is_empty() {
+        return Err(tr("Keine lesbaren PDFs").to_string());
+    }
+
+    let mut out = Document::with_version("1.5");
+    let pages_id = out.new_object_id();
+    let mut kids: Vec<Object> = Vec::new();
+    for mut doc in sources {
+        doc.renumber_objects_with(out.max_id + 1);
+        for page_id in doc.get_pages().into_values() {
+            let mut inherited = Vec::new();
+            let mut node = doc.get_dictionary(page_id).ok();
+            while let Some(dict) = node {
+                for key in INHERITED {
+                    if let Ok(value) = dict.get(key) {
+                        if !inherited.iter().any(|(k, _)| *k == key) {
+                            inherited.push((key, value.clone()));
+                        }
+                    }
+                }
+                node = dict
+                    .get(b"Parent")
+                    .and_then(Object::as_reference)
+                    .and_then(|id| doc.get_dictionary(id))
+                    .ok();
+            }
+            if let Some(Object::Dictionary(page)) = doc.objects.get_mut(&page_id) {
+                for (key, value) in inherited {
+                    if !page.has(key) {
+                        page.set(key, value);
+                    }
+                }
+                page.set("Parent", pages_id);
+            }
+            kids.push(page_id.into());
+        }
+        out.max_id = out.max_id.max(doc.max_id);
+        // The source's catalog and page tree are replaced by the merged ones.
+        for (id, object) in doc.objects {
+            let node_type = object
+                .as_dict()
+                .and_then(|dict| dict.get(b"Type"))
+                .and_then(Object::as_name)
+                .unwrap_or_default();
+            if node_type != b"Catalog" && node_type != b"Pages" {
+                out.objects.insert(id, object);
+            }
+        }
+    }
+
+    let count = kids.len() as i64;
+    out.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => count,
+        }),
+    );
+    let catalog_id = out.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    out.trailer.set("Root", catalog_id);
+    // Outlines and other catalog entries of the sources are now unreferenced.
+    out.prune_objects();
+    out.compress();
+    out.save(path).map_err(|e| e.to_string())?;
+    Ok(skipped)
+}
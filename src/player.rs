@@ -0,0 +1,136 @@
+use crate::i18n::{tr, tr_arg};
+use crate::stretch::{Resample, StretchControls, TimeStretch};
+use rodio::{Decoder, Sink, Source};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Audio output device. Only macOS builds link an output backend (CoreAudio);
+/// elsewhere rodio just decodes and [`Player::open`] fails, so the UI falls
+/// back to the default app.
+#[cfg(target_os = "macos")]
+pub type Output = rodio::OutputStream;
+#[cfg(not(target_os = "macos"))]
+pub type Output = ();
+
+#[cfg(target_os = "macos")]
+pub fn open_output() -> Result<(Output, Sink), String> {
+    let mut stream = rodio::OutputStreamBuilder::open_default_stream()
+        .map_err(|e| tr_arg("Audioausgabe nicht verf\u{00FC}gbar: {}", e))?;
+    stream.log_on_drop(false);
+    let sink = Sink::connect_new(stream.mixer());
+    Ok((stream, sink))
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn open_output() -> Result<(Output, Sink), String> {
+    Err(tr("Keine Audioausgabe auf diesem System").to_string())
+}
+
+/// One audio file being played. Dropping it stops playback.
+pub struct Player {
+    sink: Sink,
+    _output: Output,
+    /// `None` if the file does not say (some MP3s without a Xing header).
+    total: Option<Duration>,
+    sample_rate: u32,
+    stretch: Arc<StretchControls>,
+}
+
+impl Player {
+    /// Decode `path` and start playing it right away at the given volume
+    /// (see [`Player::set_volume`]), tempo and pitch shift.
+    pub fn open(
+        path: &Path,
+        volume: f32,
+        gain_db: f32,
+        tempo: f32,
+        semitones: i32,
+    ) -> Result<Self, String> {
+        let file = std::fs::File::open(path).map_err(|e| {
+            format!(
+                "{} konnte nicht ge\u{00F6}ffnet werden: {e}",
+                path.display()
+            )
+        })?;
+        let source = Decoder::try_from(file).map_err(|e| tr_arg("Format nicht lesbar: {}", e))?;
+        let total = source.total_duration();
+        let sample_rate = source.sample_rate();
+        let (output, sink) = open_output()?;
+        let player = Self {
+            sink,
+            _output: output,
+            total,
+            sample_rate,
+            stretch: StretchControls::new(),
+        };
+        player.set_volume(volume, gain_db);
+        player.set_tempo(tempo);
+        player.set_semitones(semitones);
+        let stretched = TimeStretch::new(source, player.stretch.clone());
+        player
+            .sink
+            .append(Resample::new(stretched, player.stretch.clone()));
+        Ok(player)
+    }
+
+    /// `volume` 0.0–1.0 times the song's gain in dB.
+    pub fn set_volume(&self, volume: f32, gain_db: f32) {
+        self.sink.set_volume(volume * 10f32.powf(gain_db / 20.0));
+    }
+
+    /// Playback speed without changing the pitch, 1.0 = original.
+    pub fn set_tempo(&self, tempo: f32) {
+        self.stretch.set_tempo(tempo);
+    }
+
+    /// Transpose by `semitones` without changing the tempo.
+    pub fn set_semitones(&self, semitones: i32) {
+        self.stretch.set_semitones(semitones);
+    }
+
+    /// Position in the recording; unlike the sink's own position this does not
+    /// drift when the tempo is changed.
+    pub fn position(&self) -> Duration {
+        Duration::from_secs_f64(
+            self.stretch.position_frames() as f64 / f64::from(self.sample_rate.max(1)),
+        )
+    }
+
+    /// Repeat `start..end` of the recording until cleared with `None`.
+    pub fn set_loop(&self, range: Option<(Duration, Duration)>) {
+        let rate = f64::from(self.sample_rate);
+        self.stretch.set_loop(range.map(|(start, end)| {
+            (
+                (start.as_secs_f64() * rate) as u64,
+                (end.as_secs_f64() * rate) as u64,
+            )
+        }));
+    }
+
+    pub fn total(&self) -> Option<Duration> {
+        self.total
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.sink.is_paused()
+    }
+
+    pub fn toggle_pause(&self) {
+        if self.sink.is_paused() {
+            self.sink.play();
+        } else {
+            self.sink.pause();
+        }
+    }
+
+    pub fn seek(&self, position: Duration) {
+        let position = self.total.map_or(position, |total| position.min(total));
+        let _ = self.sink.try_seek(position);
+    }
+
+    /// The file played to its end.
+    pub fn is_finished(&self) -> bool {
+        self.sink.empty()
+    }
+}
@@ -0,0 +1,177 @@
+use crate::config::WebsiteConfig;
+use crate::db::get_song_tags;
+use crate::html::escape;
+use crate::i18n::{tr_arg, tr_args};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::path::Path;
+
+/// What the public site knows about a song; no file paths, notes or ratings.
+#[derive(Serialize)]
+pub struct PublicSong {
+    pub titel: String,
+    pub artist: Option<String>,
+    pub tonart: Option<String>,
+    pub tags: Vec<PublicTag>,
+}
+
+#[derive(Serialize)]
+pub struct PublicTag {
+    pub kategorie: String,
+    pub wert: String,
+}
+
+/// Songs not marked private (and, if configured, only the repertoire), with
+/// the tags of the public categories.
+pub fn public_songs(conn: &Connection, website: &WebsiteConfig) -> Vec<PublicSong> {
+    let mut sql = String::from(
+        "SELECT id, titel, artist, tonart FROM songs
+         WHERE privat = 0 AND ignoriert = 0 AND deleted_at IS NULL",
+    );
+    if website.nur_repertoire {
+        sql.push_str(" AND repertoire_seit IS NOT NULL");
+    }
+    sql.push_str(" ORDER BY titel COLLATE NOCASE");
+
+    let mut stmt = conn.prepare(&sql).unwrap();
+    let rows: Vec<(i64, String, Option<String>, Option<String>)> = stmt
+        .query_map([], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })
+        .unwrap()
+        .filter_map(|r| r.ok())
+        .collect();
+
+    rows.into_iter()
+        .map(|(id, titel, artist, tonart)| PublicSong {
+            titel,
+            artist,
+            tonart,
+            tags: get_song_tags(conn, id)
+                .into_iter()
+                .filter(|t| website.kategorien.contains(&t.kategorie))
+                .map(|t| PublicTag {
+                    kategorie: t.kategorie,
+                    wert: t.wert,
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Write the site into `dir`: `index.html` with the song list embedded as
+/// JSON, plus `style.css` and `suche.js` for client-side search and tag
+/// filters. Works from a plain web host or opened locally.
+pub fn generate(
+    dir: &Path,
+    website: &WebsiteConfig,
+    songs: &[PublicSong],
+    stand: &str,
+) -> Result<(), String> {
+    let write = |name: &str, content: &str| {
+        std::fs::write(dir.join(name), content)
+            .map_err(|e| tr_args("{} konnte nicht geschrieben werden: {}", &[&name, &e]))
+    };
+    std::fs::create_dir_all(dir)
+        .map_err(|e| tr_arg("Ordner konnte nicht angelegt werden: {}", e))?;
+
+    // `</` would end the script element early.
+    let data = serde_json::to_string(songs)
+        .map_err(|e| e.to_string())?
+        .replace("</", "<\\/");
+    let einleitung = if website.einleitung.trim().is_empty() {
+        String::new()
+    } else {
+        format!(
+            "<p class=\"intro\">{}</p>\n",
+            escape(website.einleitung.trim())
+        )
+    };
+    let index = format!(
+        "<!DOCTYPE html>\n<html lang=\"de\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{titel}</title>\n<link rel=\"stylesheet\" href=\"style.css\">\n</head>\n<body>\n\
+         <header>\n<h1>{titel}</h1>\n{einleitung}\
+         <input id=\"suche\" type=\"search\" placeholder=\"Titel, Artist, Stil\u{2026}\" autofocus>\n\
+         <div id=\"filter\"></div>\n<p id=\"anzahl\" class=\"meta\"></p>\n</header>\n\
+         <ul id=\"songs\"></ul>\n<footer class=\"meta\">Stand {stand}</footer>\n\
+         <script id=\"daten\" type=\"application/json\">{data}</script>\n\
+         <script src=\"suche.js\"></script>\n</body>\n</html>\n",
+        titel = escape(&website.titel),
+        stand = escape(stand),
+    );
+
+    write("index.html", &index)?;
+    write("style.css", STYLE)?;
+    write("suche.js", SCRIPT)
+}
+
+/// Also the base of the LAN view (lan.rs).
+pub const STYLE: &str = "body { font-family: -apple-system, 'Helvetica Neue', sans-serif; margin: 0 auto;
+  max-width: 760px; padding: 32px 20px; background: #16141a; color: #f2eee6; }
+h1 { font-size: 28px; margin: 0 0 8px; }
+.intro { color: #afaaa2; line-height: 1.5; margin: 0 0 16px; }
+.meta { color: #78736c; font-size: 13px; }
+input { font-size: 16px; padding: 8px 12px; width: 100%; box-sizing: border-box; border-radius: 8px;
+  border: 1px solid #34303e; background: #121016; color: inherit; }
+#filter { margin: 10px 0 0; }
+#filter button { font: inherit; font-size: 13px; padding: 2px 10px; margin: 0 6px 6px 0;
+  border-radius: 12px; border: 1px solid #34303e; background: none; color: #afaaa2; cursor: pointer; }
+#filter button.aktiv { background: #b48a30; border-color: #b48a30; color: #f2eee6; }
+ul { list-style: none; padding: 0; margin: 0; }
+li { padding: 10px 0; border-bottom: 1px solid #26242e; }
+.titel { font-weight: 600; }
+.artist, .tonart { color: #afaaa2; }
+.tag { display: inline-block; font-size: 12px; padding: 1px 7px; margin: 4px 4px 0 0;
+  border-radius: 9px; background: #34303e; color: #afaaa2; }
+";
+
+const SCRIPT: &str = "const songs = JSON.parse(document.getElementById('daten').textContent);
+const suche = document.getElementById('suche');
+const liste = document.getElementById('songs');
+const anzahl = document.getElementById('anzahl');
+const aktiv = new Set();
+
+function el(tag, cls, text) {
+  const e = document.createElement(tag);
+  if (cls) e.className = cls;
+  if (text) e.textContent = text;
+  return e;
+}
+
+const werte = [...new Set(songs.flatMap(s => s.tags.map(t => t.wert)))].sort();
+const filter = document.getElementById('filter');
+for (const wert of werte) {
+  const b = el('button', '', wert);
+  b.onclick = () => {
+    aktiv.has(wert) ? aktiv.delete(wert) : aktiv.add(wert);
+    b.classList.toggle('aktiv');
+    zeigen();
+  };
+  filter.appendChild(b);
+}
+
+function zeigen() {
+  const woerter = suche.value.toLowerCase().split(/\\s+/).filter(w => w);
+  liste.replaceChildren();
+  let n = 0;
+  for (const s of songs) {
+    const text = [s.titel, s.artist, s.tonart, ...s.tags.map(t => t.wert)].join(' ').toLowerCase();
+    if (!woerter.every(w => text.includes(w))) continue;
+    if (![...aktiv].every(w => s.tags.some(t => t.wert === w))) continue;
+    const li = el('li');
+    li.appendChild(el('span', 'titel', s.titel));
+    if (s.artist) li.appendChild(el('span', 'artist', ' \\u2014 ' + s.artist));
+    if (s.tonart) li.appendChild(el('span', 'tonart', ' \\u00b7 ' + s.tonart));
+    const tags = el('div');
+    for (const t of s.tags) tags.appendChild(el('span', 'tag', t.wert));
+    li.appendChild(tags);
+    liste.appendChild(li);
+    n++;
+  }
+  anzahl.textContent = n === songs.length ? n + ' Songs' : n + ' von ' + songs.length + ' Songs';
+}
+
+suche.addEventListener('input', zeigen);
+zeigen();
+";
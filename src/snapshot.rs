@@ -0,0 +1,349 @@
+use crate::config::{data_dir, load_config};
+use crate::db::{get_or_create_tag, get_song_tags, LogErr, NOW};
+use crate::scanner::ScanStatus;
+use crate::worker::Db;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::PoisonError;
+use std::time::Duration;
+
+/// Logical metadata of one song, keyed by its relative path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SongSnapshot {
+    pub dateipfad: String,
+    pub titel: String,
+    pub artist: Option<String>,
+    pub tags: Vec<TagSnapshot>,
+    /// `None` in snapshots written before these fields were recorded; a
+    /// restore then leaves them as they are.
+    #[serde(default)]
+    pub details: Option<SongDetails>,
+}
+
+/// Notes and playing details of a song.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct SongDetails {
+    #[serde(default)]
+    pub notizen: Option<String>,
+    #[serde(default)]
+    pub tonart: Option<String>,
+    #[serde(default)]
+    pub kapo: Option<i64>,
+    #[serde(default)]
+    pub bpm: Option<i64>,
+}
+
+impl SongDetails {
+    fn from_row(row: &rusqlite::Row, first: usize) -> rusqlite::Result<Self> {
+        Ok(SongDetails {
+            notizen: row.get(first)?,
+            tonart: row.get(first + 1)?,
+            kapo: row.get(first + 2)?,
+            bpm: row.get(first + 3)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagSnapshot {
+    pub kategorie: String,
+    pub wert: String,
+    pub auto_generated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub datum: String,
+    pub songs: Vec<SongSnapshot>,
+}
+
+pub fn snapshot_dir() -> PathBuf {
+    data_dir().join("snapshots")
+}
+
+fn today(conn: &Connection) -> String {
+    conn.query_row("SELECT date('now', 'localtime')", [], |row| row.get(0))
+        .unwrap_or_default()
+}
+
+pub fn collect_snapshot(conn: &Connection) -> Snapshot {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, dateipfad, titel, artist, notizen, tonart, kapo, bpm
+             FROM songs ORDER BY dateipfad",
+        )
+        .unwrap();
+    let rows: Vec<(i64, String, String, Option<String>, SongDetails)> = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                SongDetails::from_row(row, 4)?,
+            ))
+        })
+        .unwrap()
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut tag_stmt = conn
+        .prepare(
+            "SELECT t.kategorie, t.wert, st.auto_generated
+             FROM song_tags st JOIN tags t ON t.id = st.tag_id
+             WHERE st.song_id = ?1
+             ORDER BY t.kategorie, t.wert",
+        )
+        .unwrap();
+
+    let songs = rows
+        .into_iter()
+        .map(|(id, dateipfad, titel, artist, details)| {
+            let tags = tag_stmt
+                .query_map(params![id], |row| {
+                    Ok(TagSnapshot {
+                        kategorie: row.get(0)?,
+                        wert: row.get(1)?,
+                        auto_generated: row.get::<_, i64>(2)? != 0,
+                    })
+                })
+                .unwrap()
+                .filter_map(|r| r.ok())
+                .collect();
+            SongSnapshot {
+                dateipfad,
+                titel,
+                artist,
+                tags,
+                details: Some(details),
+            }
+        })
+        .collect();
+
+    Snapshot {
+        datum: today(conn),
+        songs,
+    }
+}
+
+/// Write today's snapshot if it doesn't exist yet and drop snapshots older
+/// than `retention_days`.
+pub fn take_daily_snapshot(conn: &Connection, retention_days: u32) {
+    let dir = snapshot_dir();
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        log::warn!("Cannot create snapshot folder {}: {e}", dir.display());
+        return;
+    }
+
+    let datum = today(conn);
+    let path = dir.join(format!("{datum}.json"));
+    if !path.exists() {
+        let snapshot = collect_snapshot(conn);
+        if let Ok(json) = serde_json::to_string(&snapshot) {
+            match std::fs::write(&path, json) {
+                Ok(()) => log::info!("Metadata snapshot {datum} written"),
+                Err(e) => log::warn!("Cannot write snapshot {}: {e}", path.display()),
+            }
+        }
+    }
+
+    let cutoff: String = conn
+        .query_row(
+            "SELECT date('now', 'localtime', ?1)",
+            params![format!("-{retention_days} days")],
+            |row| row.get(0),
+        )
+        .unwrap_or_default();
+    for datum in list_snapshots() {
+        if datum < cutoff {
+            let old = dir.join(format!("{datum}.json"));
+            if let Err(e) = std::fs::remove_file(&old) {
+                log::warn!("Cannot remove old snapshot {}: {e}", old.display());
+            }
+        }
+    }
+}
+
+/// Background thread that takes the day's snapshot when the date changes
+/// while the app stays open; the one on startup runs after the first scan.
+/// Skips rounds while a scan is running, so a snapshot never catches a half
+/// indexed library.
+pub fn start_scheduler(db: Db, scan_status: ScanStatus) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_secs(5 * 60));
+
+        let Some(retention_days) = load_config().map(|c| c.snapshot_retention_days) else {
+            continue;
+        };
+        if scan_status
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .running
+        {
+            continue;
+        }
+        db.call(move |conn| take_daily_snapshot(conn, retention_days));
+    });
+}
+
+/// Available snapshot dates (YYYY-MM-DD), newest first.
+pub fn list_snapshots() -> Vec<String> {
+    let mut dates: Vec<String> = std::fs::read_dir(snapshot_dir())
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| {
+                    e.file_name()
+                        .to_string_lossy()
+                        .strip_suffix(".json")
+                        .map(|s| s.to_string())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    dates.sort();
+    dates.reverse();
+    dates
+}
+
+pub fn load_snapshot(datum: &str) -> Option<Snapshot> {
+    let content = std::fs::read_to_string(snapshot_dir().join(format!("{datum}.json"))).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn song_id(conn: &Connection, dateipfad: &str) -> Option<i64> {
+    conn.query_row(
+        "SELECT id FROM songs WHERE dateipfad = ?1",
+        params![dateipfad],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+fn set_song_tags(conn: &Connection, song_id: i64, tags: &[TagSnapshot], kategorie: Option<&str>) {
+    match kategorie {
+        Some(k) => conn.execute(
+            "DELETE FROM song_tags WHERE song_id = ?1
+             AND tag_id IN (SELECT id FROM tags WHERE kategorie = ?2)",
+            params![song_id, k],
+        ),
+        None => conn.execute("DELETE FROM song_tags WHERE song_id = ?1", params![song_id]),
+    }
+    .log_err();
+
+    for tag in tags
+        .iter()
+        .filter(|t| kategorie.is_none_or(|k| t.kategorie == k))
+    {
+        let Ok(tag_id) = get_or_create_tag(conn, &tag.kategorie, &tag.wert) else {
+            continue;
+        };
+        conn.execute(
+            "INSERT OR IGNORE INTO song_tags (song_id, tag_id, auto_generated) VALUES (?1, ?2, ?3)",
+            params![song_id, tag_id, tag.auto_generated],
+        )
+        .log_err();
+    }
+}
+
+fn cleanup_orphan_tags(conn: &Connection) {
+    conn.execute(
+        "DELETE FROM tags WHERE angeheftet = 0 AND id NOT IN (SELECT DISTINCT tag_id FROM song_tags)",
+        [],
+    )
+    .log_err();
+}
+
+/// Roll back title, artist, notes, playing details and tags of a single song
+/// to the snapshot state.
+pub fn restore_song(conn: &Connection, song: &SongSnapshot) -> bool {
+    let Some(id) = song_id(conn, &song.dateipfad) else {
+        return false;
+    };
+    conn.execute(
+        &format!("UPDATE songs SET titel = ?1, artist = ?2, updated_at = {NOW} WHERE id = ?3"),
+        params![song.titel, song.artist, id],
+    )
+    .log_err();
+    if let Some(ref d) = song.details {
+        conn.execute(
+            "UPDATE songs SET notizen = ?1, tonart = ?2, kapo = ?3, bpm = ?4 WHERE id = ?5",
+            params![d.notizen, d.tonart, d.kapo, d.bpm, id],
+        )
+        .log_err();
+    }
+    set_song_tags(conn, id, &song.tags, None);
+    cleanup_orphan_tags(conn);
+    true
+}
+
+/// Roll back the tags of one category for every song that still exists.
+/// Returns the number of songs touched.
+pub fn restore_category(conn: &Connection, snapshot: &Snapshot, kategorie: &str) -> usize {
+    let mut restored = 0;
+    conn.execute_batch("BEGIN").log_err();
+    for song in &snapshot.songs {
+        if let Some(id) = song_id(conn, &song.dateipfad) {
+            set_song_tags(conn, id, &song.tags, Some(kategorie));
+            restored += 1;
+        }
+    }
+    cleanup_orphan_tags(conn);
+    conn.execute_batch("COMMIT").log_err();
+    restored
+}
+
+/// Current metadata of a song in the same shape as a snapshot entry, for previews.
+pub fn current_song(conn: &Connection, dateipfad: &str) -> Option<SongSnapshot> {
+    let (id, titel, artist, details): (i64, String, Option<String>, SongDetails) = conn
+        .query_row(
+            "SELECT id, titel, artist, notizen, tonart, kapo, bpm FROM songs WHERE dateipfad = ?1",
+            params![dateipfad],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    SongDetails::from_row(row, 3)?,
+                ))
+            },
+        )
+        .ok()?;
+    let tags = get_song_tags(conn, id)
+        .into_iter()
+        .map(|t| TagSnapshot {
+            kategorie: t.kategorie,
+            wert: t.wert,
+            auto_generated: t.auto_generated,
+        })
+        .collect();
+    Some(SongSnapshot {
+        dateipfad: dateipfad.to_string(),
+        titel,
+        artist,
+        tags,
+        details: Some(details),
+    })
+}
+
+/// Number of existing songs whose tags in `kategorie` differ from the snapshot.
+pub fn category_changes(conn: &Connection, snapshot: &Snapshot, kategorie: &str) -> usize {
+    let values = |tags: &[TagSnapshot]| {
+        let mut v: Vec<&str> = tags
+            .iter()
+            .filter(|t| t.kategorie == kategorie)
+            .map(|t| t.wert.as_str())
+            .collect();
+        v.sort();
+        v.into_iter().map(String::from).collect::<Vec<_>>()
+    };
+    snapshot
+        .songs
+        .iter()
+        .filter(|song| {
+            current_song(conn, &song.dateipfad)
+                .is_some_and(|current| values(&current.tags) != values(&song.tags))
+        })
+        .count()
+}
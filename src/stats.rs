@@ -0,0 +1,73 @@
+use rusqlite::{params, Connection};
+
+/// Song counts for every pair of tag values from two categories.
+pub struct Cooccurrence {
+    pub rows: Vec<String>,
+    pub columns: Vec<String>,
+    /// `counts[row][column]`
+    pub counts: Vec<Vec<i64>>,
+}
+
+impl Cooccurrence {
+    pub fn max(&self) -> i64 {
+        self.counts.iter().flatten().copied().max().unwrap_or(0)
+    }
+}
+
+fn category_values(conn: &Connection, kategorie: &str) -> Vec<String> {
+    let mut stmt = conn
+        .prepare("SELECT wert FROM tags WHERE kategorie = ?1 ORDER BY wert")
+        .unwrap();
+    stmt.query_map(params![kategorie], |row| row.get(0))
+        .unwrap()
+        .filter_map(|r| r.ok())
+        .collect()
+}
+
+/// Matrix of how many songs carry both `row_kategorie=X` and `col_kategorie=Y`.
+/// Includes every known value of both categories, so empty combinations show up as 0.
+pub fn tag_cooccurrence(
+    conn: &Connection,
+    row_kategorie: &str,
+    col_kategorie: &str,
+) -> Cooccurrence {
+    let rows = category_values(conn, row_kategorie);
+    let columns = category_values(conn, col_kategorie);
+    let mut counts = vec![vec![0; columns.len()]; rows.len()];
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT a.wert, b.wert, COUNT(DISTINCT sa.song_id)
+             FROM song_tags sa
+             JOIN songs s ON s.id = sa.song_id AND s.deleted_at IS NULL AND s.ignoriert = 0
+             JOIN tags a ON a.id = sa.tag_id AND a.kategorie = ?1
+             JOIN song_tags sb ON sb.song_id = sa.song_id
+             JOIN tags b ON b.id = sb.tag_id AND b.kategorie = ?2
+             GROUP BY a.wert, b.wert",
+        )
+        .unwrap();
+    let pairs = stmt
+        .query_map(params![row_kategorie, col_kategorie], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })
+        .unwrap()
+        .filter_map(|r| r.ok());
+    for (a, b, n) in pairs {
+        if let (Some(r), Some(c)) = (
+            rows.iter().position(|v| *v == a),
+            columns.iter().position(|v| *v == b),
+        ) {
+            counts[r][c] = n;
+        }
+    }
+
+    Cooccurrence {
+        rows,
+        columns,
+        counts,
+    }
+}
@@ -0,0 +1,455 @@
+use rodio::source::SeekError;
+use rodio::{ChannelCount, Sample, SampleRate, Source};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Slowest and fastest tempo the player offers.
+pub const MIN_TEMPO: f32 = 0.25;
+pub const MAX_TEMPO: f32 = 2.0;
+/// Largest pitch shift either way, in semitones.
+pub const MAX_SEMITONES: i32 = 12;
+
+/// Analysis frame length; 40 ms keeps guitar transients reasonably tight.
+const FRAME_MS: usize = 40;
+/// How far the stretcher may shift a frame to line up with the previous one.
+const TOLERANCE_MS: usize = 10;
+
+/// Settings shared between the player on the UI thread and the stretcher on
+/// the audio thread.
+pub struct StretchControls {
+    /// f32 bits; 1.0 is the original tempo.
+    tempo: AtomicU32,
+    /// f32 bits; frequency ratio of the pitch shift, 1.0 = original pitch.
+    pitch: AtomicU32,
+    /// Source frame currently being played, for the position display.
+    position: AtomicU64,
+    /// A–B loop as source frames; an end of 0 means no loop.
+    loop_start: AtomicU64,
+    loop_end: AtomicU64,
+}
+
+impl StretchControls {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            tempo: AtomicU32::new(1.0f32.to_bits()),
+            pitch: AtomicU32::new(1.0f32.to_bits()),
+            position: AtomicU64::new(0),
+            loop_start: AtomicU64::new(0),
+            loop_end: AtomicU64::new(0),
+        })
+    }
+
+    pub fn tempo(&self) -> f32 {
+        f32::from_bits(self.tempo.load(Ordering::Relaxed))
+    }
+
+    pub fn set_tempo(&self, tempo: f32) {
+        let tempo = tempo.clamp(MIN_TEMPO, MAX_TEMPO);
+        self.tempo.store(tempo.to_bits(), Ordering::Relaxed);
+    }
+
+    fn pitch_ratio(&self) -> f32 {
+        f32::from_bits(self.pitch.load(Ordering::Relaxed))
+    }
+
+    /// Shift the pitch by `semitones` without changing the tempo.
+    pub fn set_semitones(&self, semitones: i32) {
+        let semitones = semitones.clamp(-MAX_SEMITONES, MAX_SEMITONES);
+        let ratio = 2f32.powf(semitones as f32 / 12.0);
+        self.pitch.store(ratio.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn position_frames(&self) -> u64 {
+        self.position.load(Ordering::Relaxed)
+    }
+
+    /// Loop `start..end` (source frames); `None` or an empty range plays on.
+    pub fn set_loop(&self, frames: Option<(u64, u64)>) {
+        let (start, end) = frames.filter(|(a, b)| a < b).unwrap_or((0, 0));
+        self.loop_end.store(0, Ordering::Relaxed);
+        self.loop_start.store(start, Ordering::Relaxed);
+        self.loop_end.store(end, Ordering::Relaxed);
+    }
+
+    fn loop_frames(&self) -> Option<(u64, u64)> {
+        let end = self.loop_end.load(Ordering::Relaxed);
+        (end > 0).then(|| (self.loop_start.load(Ordering::Relaxed), end))
+    }
+}
+
+/// Changes the tempo of a source without changing its pitch (WSOLA:
+/// overlapping Hann-windowed frames, each shifted within a small tolerance to
+/// best continue the previous one). At tempo 1.0 the input passes through
+/// unchanged. With a pitch shift it stretches by the pitch ratio as well, and
+/// [`Resample`] after it brings the length back while raising or lowering
+/// the pitch.
+pub struct TimeStretch<S> {
+    inner: S,
+    controls: Arc<StretchControls>,
+    channels: usize,
+    sample_rate: SampleRate,
+    frame_len: usize,
+    hop: usize,
+    tolerance: u64,
+    window: Vec<f32>,
+    /// Interleaved input; `input_start` is the source frame of its first frame.
+    input: Vec<Sample>,
+    input_start: u64,
+    /// Where the next frame would start at exactly the requested tempo.
+    analysis_pos: f64,
+    /// Frame that seamlessly continues the last one taken.
+    natural: u64,
+    /// Second half of the last windowed frame, added to the next one.
+    tail: Vec<Sample>,
+    output: VecDeque<Sample>,
+    /// Nothing played since the start or a seek, so the first frame is taken
+    /// as is instead of fading in.
+    fresh: bool,
+    exhausted: bool,
+    done: bool,
+}
+
+impl<S: Source> TimeStretch<S> {
+    pub fn new(inner: S, controls: Arc<StretchControls>) -> Self {
+        let channels = usize::from(inner.channels()).max(1);
+        let sample_rate = inner.sample_rate();
+        let hop = (sample_rate as usize * FRAME_MS / 1000 / 2).max(1);
+        let frame_len = hop * 2;
+        // Periodic Hann: two windows half a frame apart add up to exactly 1.
+        let window = (0..frame_len)
+            .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / frame_len as f32).cos())
+            .collect();
+        Self {
+            inner,
+            controls,
+            channels,
+            sample_rate,
+            frame_len,
+            hop,
+            tolerance: (sample_rate as usize * TOLERANCE_MS / 1000) as u64,
+            window,
+            input: Vec::new(),
+            input_start: 0,
+            analysis_pos: 0.0,
+            natural: 0,
+            tail: vec![0.0; hop * channels],
+            output: VecDeque::new(),
+            fresh: true,
+            exhausted: false,
+            done: false,
+        }
+    }
+
+    fn input_end(&self) -> u64 {
+        self.input_start + (self.input.len() / self.channels) as u64
+    }
+
+    /// Read from the source until `frame` is buffered or the source ends.
+    fn fill_until(&mut self, frame: u64) {
+        while !self.exhausted && self.input_end() < frame {
+            for ch in 0..self.channels {
+                match self.inner.next() {
+                    Some(sample) => self.input.push(sample),
+                    None => {
+                        self.exhausted = true;
+                        // Complete the last frame so indexing stays aligned.
+                        if ch > 0 {
+                            self.input
+                                .resize(self.input.len() + self.channels - ch, 0.0);
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sample `ch` of source frame `frame`; silence outside the buffer.
+    fn sample(&self, frame: u64, ch: usize) -> Sample {
+        frame
+            .checked_sub(self.input_start)
+            .map(|i| i as usize * self.channels + ch)
+            .and_then(|i| self.input.get(i))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    fn mono(&self, frame: u64) -> Sample {
+        (0..self.channels).map(|ch| self.sample(frame, ch)).sum()
+    }
+
+    /// Start in `lo..=hi` whose first half best matches the natural
+    /// continuation, by normalized cross-correlation over every other frame.
+    fn best_start(&self, lo: u64, hi: u64) -> u64 {
+        let hop = self.hop as u64;
+        let target: Vec<Sample> = (0..hop)
+            .step_by(2)
+            .map(|i| self.mono(self.natural + i))
+            .collect();
+        let candidates: Vec<Sample> = (lo..hi + hop).map(|f| self.mono(f)).collect();
+        let mut best = (f32::MIN, lo);
+        for offset in 0..=(hi - lo) as usize {
+            let (mut corr, mut energy) = (0.0, 1e-9);
+            for (n, t) in target.iter().enumerate() {
+                let x = candidates[offset + 2 * n];
+                corr += x * t;
+                energy += x * x;
+            }
+            let score = corr / energy.sqrt();
+            if score > best.0 {
+                best = (score, lo + offset as u64);
+            }
+        }
+        best.1
+    }
+
+    /// Overlap-add one frame, producing `hop` frames of output. Returns false
+    /// once the source is used up.
+    fn step(&mut self) -> bool {
+        let tempo = self.controls.tempo() / self.controls.pitch_ratio();
+        let (lo, hi) = if tempo == 1.0 {
+            (self.natural, self.natural)
+        } else {
+            let nominal = self.analysis_pos.round() as u64;
+            (
+                nominal.saturating_sub(self.tolerance).max(self.input_start),
+                nominal + self.tolerance,
+            )
+        };
+        self.fill_until(hi.max(self.natural) + self.frame_len as u64);
+        if self.exhausted && lo >= self.input_end() {
+            // A loop end past the end of the file loops at the end instead.
+            if let Some((loop_start, _)) = self.controls.loop_frames() {
+                if lo > loop_start {
+                    self.jump(loop_start);
+                    return true;
+                }
+            }
+            self.output.extend(self.tail.iter().copied());
+            self.tail.fill(0.0);
+            return false;
+        }
+
+        let start = if lo == hi {
+            lo
+        } else {
+            self.best_start(lo, hi)
+        };
+        if self.fresh {
+            // Pretend a previous frame overlaps this one; with the windows
+            // adding up to 1 the first half passes through unchanged.
+            self.fresh = false;
+            for i in 0..self.hop {
+                for ch in 0..self.channels {
+                    self.tail[i * self.channels + ch] =
+                        (1.0 - self.window[i]) * self.sample(start + i as u64, ch);
+                }
+            }
+        }
+        for i in 0..self.hop {
+            for ch in 0..self.channels {
+                let frame = start + i as u64;
+                let idx = i * self.channels + ch;
+                self.output
+                    .push_back(self.tail[idx] + self.window[i] * self.sample(frame, ch));
+                self.tail[idx] =
+                    self.window[self.hop + i] * self.sample(frame + self.hop as u64, ch);
+            }
+        }
+        self.controls.position.store(start, Ordering::Relaxed);
+        let before = self.analysis_pos;
+        self.natural = start + self.hop as u64;
+        self.analysis_pos = if tempo == 1.0 {
+            self.natural as f64
+        } else {
+            self.analysis_pos + self.hop as f64 * f64::from(tempo)
+        };
+        // Only crossing the end jumps back, so seeking past it plays on.
+        if let Some((loop_start, loop_end)) = self.controls.loop_frames() {
+            if before < loop_end as f64 && self.analysis_pos >= loop_end as f64 {
+                self.jump(loop_start);
+                return true;
+            }
+        }
+
+        let keep_from = self
+            .natural
+            .min((self.analysis_pos as u64).saturating_sub(self.tolerance));
+        if keep_from > self.input_start {
+            let drop =
+                ((keep_from - self.input_start) as usize * self.channels).min(self.input.len());
+            self.input.drain(..drop);
+            self.input_start += (drop / self.channels) as u64;
+        }
+        true
+    }
+
+    /// Continue at `frame` (loop start). Unlike a seek the tail of the last
+    /// frame is kept, so the next frame crossfades into it without a click.
+    fn jump(&mut self, frame: u64) {
+        let pos = Duration::from_secs_f64(frame as f64 / f64::from(self.sample_rate.max(1)));
+        if self.inner.try_seek(pos).is_err() {
+            // The decoder cannot seek; play on instead of retrying every frame.
+            self.controls.set_loop(None);
+            return;
+        }
+        self.input.clear();
+        self.input_start = frame;
+        self.analysis_pos = frame as f64;
+        self.natural = frame;
+        self.exhausted = false;
+        self.controls.position.store(frame, Ordering::Relaxed);
+    }
+
+    fn reset(&mut self, frame: u64) {
+        self.input.clear();
+        self.input_start = frame;
+        self.analysis_pos = frame as f64;
+        self.natural = frame;
+        self.tail.fill(0.0);
+        self.output.clear();
+        self.fresh = true;
+        self.exhausted = false;
+        self.done = false;
+        self.controls.position.store(frame, Ordering::Relaxed);
+    }
+}
+
+impl<S: Source> Iterator for TimeStretch<S> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        while self.output.is_empty() && !self.done {
+            self.done = !self.step();
+        }
+        self.output.pop_front()
+    }
+}
+
+impl<S: Source> Source for TimeStretch<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.channels as ChannelCount
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+
+    /// `pos` is a position in the original recording, whatever the tempo.
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.inner.try_seek(pos)?;
+        self.reset((pos.as_secs_f64() * f64::from(self.sample_rate)) as u64);
+        Ok(())
+    }
+}
+
+/// Plays a source faster or slower by the pitch ratio in [`StretchControls`],
+/// which shifts its pitch; [`TimeStretch`] before it has already made it
+/// longer or shorter by the same ratio. Linear interpolation between frames.
+pub struct Resample<S> {
+    inner: S,
+    controls: Arc<StretchControls>,
+    channels: usize,
+    /// The two input frames the output lies between.
+    prev: Vec<Sample>,
+    next: Vec<Sample>,
+    /// Position between `prev` (0.0) and `next` (1.0).
+    frac: f32,
+    /// Current output frame and how much of it was handed out.
+    frame: Vec<Sample>,
+    frame_pos: usize,
+    done: bool,
+}
+
+impl<S: Source> Resample<S> {
+    pub fn new(inner: S, controls: Arc<StretchControls>) -> Self {
+        let channels = usize::from(inner.channels()).max(1);
+        Self {
+            inner,
+            controls,
+            channels,
+            prev: vec![0.0; channels],
+            next: vec![0.0; channels],
+            // Read two frames before the first output.
+            frac: 2.0,
+            frame: vec![0.0; channels],
+            frame_pos: channels,
+            done: false,
+        }
+    }
+
+    /// Move on to the next input frame; false at the end of the source.
+    fn advance(&mut self) -> bool {
+        std::mem::swap(&mut self.prev, &mut self.next);
+        for ch in 0..self.channels {
+            match self.inner.next() {
+                Some(sample) => self.next[ch] = sample,
+                None => return false,
+            }
+        }
+        true
+    }
+}
+
+impl<S: Source> Iterator for Resample<S> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        if self.frame_pos == self.channels {
+            if self.done {
+                return None;
+            }
+            while self.frac >= 1.0 {
+                if !self.advance() {
+                    self.done = true;
+                    return None;
+                }
+                self.frac -= 1.0;
+            }
+            for ch in 0..self.channels {
+                self.frame[ch] = self.prev[ch] + (self.next[ch] - self.prev[ch]) * self.frac;
+            }
+            self.frac += self.controls.pitch_ratio();
+            self.frame_pos = 0;
+        }
+        let sample = self.frame[self.frame_pos];
+        self.frame_pos += 1;
+        Some(sample)
+    }
+}
+
+impl<S: Source> Source for Resample<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.channels as ChannelCount
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.inner.try_seek(pos)?;
+        self.frac = 2.0;
+        self.frame_pos = self.channels;
+        self.done = false;
+        Ok(())
+    }
+}
@@ -0,0 +1,245 @@
+use crate::db::{get_or_create_tag, LogErr};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const TAXONOMY_VERSION: u32 = 1;
+
+/// The tag scheme without any songs: categories, their values and their
+/// colors. Tags have no hierarchy, aliases or descriptions, so a scheme
+/// carries none either.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Taxonomy {
+    pub version: u32,
+    pub kategorien: Vec<TaxonomyCategory>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaxonomyCategory {
+    pub name: String,
+    pub werte: Vec<String>,
+    /// Chip color as `#rrggbb` (`[theme.tags]` in the config); missing in
+    /// files exported before colors were.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub farbe: Option<String>,
+}
+
+/// A built-in scheme to start from instead of an empty tag list.
+pub struct StarterPack {
+    pub name: &'static str,
+    pub beschreibung: &'static str,
+    pub kategorien: &'static [(&'static str, &'static [&'static str])],
+}
+
+impl StarterPack {
+    pub fn taxonomy(&self) -> Taxonomy {
+        Taxonomy {
+            version: TAXONOMY_VERSION,
+            kategorien: self
+                .kategorien
+                .iter()
+                .map(|(name, werte)| TaxonomyCategory {
+                    name: name.to_string(),
+                    werte: werte.iter().map(|w| w.to_string()).collect(),
+                    farbe: None,
+                })
+                .collect(),
+        }
+    }
+}
+
+pub const STARTER_PACKS: &[StarterPack] = &[
+    StarterPack {
+        name: "Gitarrenunterricht",
+        beschreibung:
+            "Stufen, Spieltechniken und Stile f\u{00FC}r den Einzel- und Gruppenunterricht",
+        kategorien: &[
+            (
+                "schwierigkeit",
+                &["Anf\u{00E4}nger", "Mittel", "Fortgeschritten"],
+            ),
+            ("instrument", &["Akustik-Gitarre", "E-Gitarre", "Ukulele"]),
+            (
+                "technik",
+                &[
+                    "Akkorde",
+                    "Barr\u{00E9}",
+                    "Strumming",
+                    "Fingerpicking",
+                    "Powerchords",
+                    "Riff",
+                    "Solo",
+                ],
+            ),
+            (
+                "stil",
+                &[
+                    "Pop",
+                    "Rock",
+                    "Blues",
+                    "Folk",
+                    "Kinderlieder",
+                    "Weihnachten",
+                ],
+            ),
+        ],
+    },
+    StarterPack {
+        name: "Worship-Band",
+        beschreibung: "Besetzung, Stimmungen und Tonarten f\u{00FC}r die Gottesdienstplanung",
+        kategorien: &[
+            (
+                "instrument",
+                &[
+                    "Gesang",
+                    "Akustik-Gitarre",
+                    "E-Gitarre",
+                    "Bass",
+                    "Keys",
+                    "Schlagzeug",
+                ],
+            ),
+            ("stil", &["Worship", "Hymne", "Gospel"]),
+            (
+                "stimmung",
+                &["Ruhig", "Feierlich", "Fr\u{00F6}hlich", "Nachdenklich"],
+            ),
+            ("tonart", &["C", "D", "E", "F", "G", "A", "B"]),
+        ],
+    },
+    StarterPack {
+        name: "Klassische Gitarre",
+        beschreibung: "Epochen und Techniken f\u{00FC}r klassisches Repertoire",
+        kategorien: &[
+            (
+                "schwierigkeit",
+                &["Anf\u{00E4}nger", "Mittel", "Fortgeschritten"],
+            ),
+            ("instrument", &["Konzertgitarre"]),
+            (
+                "stil",
+                &["Renaissance", "Barock", "Klassik", "Romantik", "Moderne"],
+            ),
+            (
+                "technik",
+                &[
+                    "Arpeggio",
+                    "Tremolo",
+                    "Rasgueado",
+                    "Bindungen",
+                    "Flageolett",
+                    "Lagenspiel",
+                ],
+            ),
+        ],
+    },
+];
+
+/// Categories that are derived from file names and folders rather than
+/// chosen by hand; they don't belong to a shareable scheme.
+const SKIPPED_CATEGORIES: &[&str] = &["artist"];
+
+/// True once a tag was set by hand or imported; otherwise the scheme is still empty.
+pub fn has_own_tags(conn: &Connection) -> bool {
+    conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM tags WHERE angeheftet = 1)
+             OR EXISTS (SELECT 1 FROM song_tags WHERE auto_generated = 0)",
+        [],
+        |row| row.get(0),
+    )
+    .unwrap_or(true)
+}
+
+/// All categories and values, with the colors in `farben` (category →
+/// `#rrggbb`).
+pub fn current_taxonomy(conn: &Connection, farben: &BTreeMap<String, String>) -> Taxonomy {
+    let mut stmt = conn
+        .prepare("SELECT kategorie, wert FROM tags ORDER BY kategorie, rang IS NULL, rang, wert")
+        .unwrap();
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .unwrap()
+        .filter_map(|r| r.ok());
+
+    let mut kategorien: Vec<TaxonomyCategory> = Vec::new();
+    for (kategorie, wert) in rows {
+        if SKIPPED_CATEGORIES.contains(&kategorie.as_str()) {
+            continue;
+        }
+        match kategorien.last_mut() {
+            Some(last) if last.name == kategorie => last.werte.push(wert),
+            _ => kategorien.push(TaxonomyCategory {
+                farbe: farben.get(&kategorie).cloned(),
+                name: kategorie,
+                werte: vec![wert],
+            }),
+        }
+    }
+    Taxonomy {
+        version: TAXONOMY_VERSION,
+        kategorien,
+    }
+}
+
+pub fn export_taxonomy(
+    conn: &Connection,
+    path: &Path,
+    farben: &BTreeMap<String, String>,
+) -> Result<usize, String> {
+    let taxonomy = current_taxonomy(conn, farben);
+    let count = taxonomy.kategorien.iter().map(|k| k.werte.len()).sum();
+    let json = serde_json::to_string_pretty(&taxonomy).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())?;
+    Ok(count)
+}
+
+/// Create every tag of `taxonomy` that doesn't exist yet and pin it, so it
+/// stays available even before any song uses it. Returns the number of new tags.
+pub fn apply_taxonomy(conn: &Connection, taxonomy: &Taxonomy) -> usize {
+    let mut created = 0;
+    for kategorie in &taxonomy.kategorien {
+        let name = kategorie.name.trim().to_lowercase();
+        if name.is_empty() {
+            continue;
+        }
+        for wert in &kategorie.werte {
+            let wert = wert.trim();
+            if wert.is_empty() {
+                continue;
+            }
+            let exists: bool = conn
+                .query_row(
+                    "SELECT COUNT(*) > 0 FROM tags WHERE kategorie = ?1 AND wert = ?2",
+                    params![name, wert],
+                    |row| row.get(0),
+                )
+                .unwrap_or(false);
+            let Ok(id) = get_or_create_tag(conn, &name, wert) else {
+                continue;
+            };
+            conn.execute("UPDATE tags SET angeheftet = 1 WHERE id = ?1", params![id])
+                .log_err();
+            if !exists {
+                created += 1;
+            }
+        }
+    }
+    created
+}
+
+/// Read an exported scheme; apply it with [`apply_taxonomy`], its colors go
+/// to the config.
+pub fn read_taxonomy(path: &Path) -> Result<Taxonomy, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let taxonomy: Taxonomy = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    if taxonomy.version > TAXONOMY_VERSION {
+        return Err(format!(
+            "Unbekannte Version {} (unterst\u{00FC}tzt: {TAXONOMY_VERSION})",
+            taxonomy.version
+        ));
+    }
+    Ok(taxonomy)
+}
@@ -0,0 +1,98 @@
+use crate::scanner::is_song_file;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_CHORDPRO_TEMPLATE: &str = "{title: {{titel}}}
+{artist: {{artist}}}
+{key: }
+{capo: }
+
+{start_of_verse}
+[C]
+{end_of_verse}
+
+{start_of_chorus}
+[C]
+{end_of_chorus}
+";
+
+/// Make sure the template folder exists and contains at least one template,
+/// so "Neues Chart anlegen" works out of the box.
+pub fn ensure_template_dir(dir: &Path) {
+    if let Err(e) = std::fs::create_dir_all(dir) {
+        log::warn!("Cannot create template folder {}: {e}", dir.display());
+        return;
+    }
+    if list_templates(dir).is_empty() {
+        let path = dir.join("Leeres Chart.cho");
+        if let Err(e) = std::fs::write(&path, DEFAULT_CHORDPRO_TEMPLATE) {
+            log::warn!("Cannot write template {}: {e}", path.display());
+        }
+    }
+}
+
+pub fn list_templates(dir: &Path) -> Vec<PathBuf> {
+    let mut templates: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.is_file() && is_song_file(p))
+                .collect()
+        })
+        .unwrap_or_default();
+    templates.sort();
+    templates
+}
+
+/// Build the "Artist - Titel.ext" filename for a new chart, stripping characters
+/// that are not allowed in filenames.
+pub fn chart_filename(titel: &str, artist: &str, ext: &str) -> String {
+    let clean = |s: &str| -> String {
+        s.chars()
+            .filter(|c| !matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|'))
+            .collect::<String>()
+            .trim()
+            .to_string()
+    };
+    let titel = clean(titel);
+    let artist = clean(artist);
+    if artist.is_empty() {
+        format!("{titel}.{ext}")
+    } else {
+        format!("{artist} - {titel}.{ext}")
+    }
+}
+
+/// Copy `template` into `target_dir` as a new chart. Text templates get their
+/// `{{titel}}`/`{{artist}}` placeholders filled in; binary templates (PDF) are
+/// copied as-is. Returns the path of the created file.
+pub fn create_chart(
+    template: &Path,
+    target_dir: &Path,
+    titel: &str,
+    artist: &str,
+) -> Result<PathBuf, String> {
+    let ext = template
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_else(|| "pdf".to_string());
+    let target = target_dir.join(chart_filename(titel, artist, &ext));
+    if target.exists() {
+        return Err(format!(
+            "\u{201E}{}\u{201C} existiert bereits.",
+            target.file_name().unwrap_or_default().to_string_lossy()
+        ));
+    }
+
+    if ext == "pdf" {
+        std::fs::copy(template, &target).map_err(|e| e.to_string())?;
+    } else {
+        let content = std::fs::read_to_string(template).map_err(|e| e.to_string())?;
+        let content = content
+            .replace("{{titel}}", titel.trim())
+            .replace("{{artist}}", artist.trim());
+        std::fs::write(&target, content).map_err(|e| e.to_string())?;
+    }
+
+    Ok(target)
+}
@@ -0,0 +1,154 @@
+use crate::config::data_dir;
+use crate::filetype::FileType;
+use crate::jobs::{JobKind, JobQueue, Priority};
+use crate::lru::{CacheStats, LruCache};
+use eframe::egui;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Edge length of generated thumbnails in pixels.
+const THUMBNAIL_SIZE: u32 = 320;
+
+/// Upper estimate for one decoded thumbnail texture (RGBA).
+const THUMBNAIL_BYTES: usize = (THUMBNAIL_SIZE * THUMBNAIL_SIZE * 4) as usize;
+
+pub fn thumbnail_dir() -> PathBuf {
+    data_dir().join("thumbnails")
+}
+
+/// Cache file name for a song, a hash of its relative path.
+fn cache_name(dateipfad: &str) -> String {
+    let hash = Sha256::digest(dateipfad.as_bytes());
+    let name: String = hash[..10].iter().map(|b| format!("{b:02x}")).collect();
+    format!("{name}.png")
+}
+
+/// Cache file for a song, keyed by a hash of its relative path.
+pub fn thumbnail_path(dateipfad: &str) -> PathBuf {
+    thumbnail_dir().join(cache_name(dateipfad))
+}
+
+/// Full-size PNG copy of an image scan in a format egui cannot decode
+/// (JPEG, HEIC), shown by the image viewer.
+pub fn preview_path(dateipfad: &str) -> PathBuf {
+    data_dir().join("vorschau").join(cache_name(dateipfad))
+}
+
+pub fn preview_uri(dateipfad: &str) -> String {
+    format!("file://{}", preview_path(dateipfad).display())
+}
+
+/// Image URI the thumbnail is loaded and cached under by egui.
+pub fn thumbnail_uri(dateipfad: &str) -> String {
+    format!("file://{}", thumbnail_path(dateipfad).display())
+}
+
+/// A cached thumbnail is stale once the source file has been modified after it.
+pub fn is_fresh(thumb: &Path, source: &Path) -> bool {
+    let modified = |p: &Path| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+    match (modified(thumb), modified(source)) {
+        (Some(t), Some(s)) => t >= s,
+        (Some(_), None) => true,
+        _ => false,
+    }
+}
+
+/// Render the first page with Quick Look (`qlmanage`) into the cache.
+pub fn render_thumbnail(source: &Path, target: &Path) -> bool {
+    let tmp = thumbnail_dir().join("tmp");
+    if std::fs::create_dir_all(&tmp).is_err() {
+        return false;
+    }
+    let ok = std::process::Command::new("qlmanage")
+        .args(["-t", "-s", &THUMBNAIL_SIZE.to_string(), "-o"])
+        .arg(&tmp)
+        .arg(source)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success());
+    // qlmanage names its output "<file name>.png".
+    let mut produced = source.file_name().unwrap_or_default().to_os_string();
+    produced.push(".png");
+    ok && std::fs::rename(tmp.join(produced), target).is_ok()
+}
+
+/// Convert an image scan to PNG with `sips`, capped at 2400 px on the long side.
+pub fn render_preview(source: &Path, target: &Path) -> bool {
+    let Some(dir) = target.parent() else {
+        return false;
+    };
+    std::fs::create_dir_all(dir).is_ok()
+        && std::process::Command::new("sips")
+            .args(["-s", "format", "png", "-Z", "2400"])
+            .arg(source)
+            .arg("--out")
+            .arg(target)
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .is_ok_and(|s| s.success())
+}
+
+/// Disk cache of first-page thumbnails, rendered by the job queue so the UI
+/// never waits on `qlmanage`. Decoded textures are tracked in an LRU and
+/// released from egui once they exceed the configured memory limit.
+pub struct ThumbnailCache {
+    base_dir: PathBuf,
+    requested: HashSet<String>,
+    jobs: JobQueue,
+    loaded: LruCache<String, ()>,
+    ctx: egui::Context,
+}
+
+impl ThumbnailCache {
+    pub fn new(base_dir: PathBuf, jobs: JobQueue, limit: usize, ctx: egui::Context) -> Self {
+        Self {
+            base_dir,
+            requested: HashSet::new(),
+            jobs,
+            loaded: LruCache::new(limit),
+            ctx,
+        }
+    }
+
+    /// Path of an up-to-date thumbnail, or `None` while it is being generated
+    /// (or if the file type has none).
+    pub fn get(&mut self, dateipfad: &str) -> Option<PathBuf> {
+        let source = self.base_dir.join(dateipfad);
+        let thumb = thumbnail_path(dateipfad);
+        if is_fresh(&thumb, &source) {
+            let key = dateipfad.to_string();
+            if self.loaded.get(&key).is_none() {
+                let evicted = self.loaded.insert(key, (), THUMBNAIL_BYTES);
+                self.forget(evicted);
+            }
+            return Some(thumb);
+        }
+        let has_thumbnail = matches!(
+            FileType::from_path(&source),
+            Some(FileType::Pdf | FileType::Image)
+        );
+        // Asked for while drawing, so the tile is on screen.
+        if has_thumbnail && self.requested.insert(dateipfad.to_string()) {
+            self.jobs
+                .push(JobKind::Thumbnail, dateipfad, source, Priority::Visible);
+        }
+        None
+    }
+    pub fn set_limit(&mut self, limit: usize) {
+        let evicted = self.loaded.set_limit(limit);
+        self.forget(evicted);
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.loaded.stats()
+    }
+
+    fn forget(&self, evicted: Vec<String>) {
+        for dateipfad in evicted {
+            self.ctx.forget_image(&thumbnail_uri(&dateipfad));
+        }
+    }
+}
@@ -1,10 +1,83 @@
-use crate::config::{save_config, Config};
+use crate::config::{Config, Language, Locale, OpenAction, RescanSchedule};
 use crate::db::*;
+use crate::format::Formatter;
+use crate::hashtags;
+use crate::i18n::{tr, tr_arg};
+use crate::jobs::JobQueue;
+use crate::scanner::{Changed, Reconciliation, ScanOptions, ScanStatus, WatcherStatus};
+use crate::worker::Db;
 use eframe::egui;
 use rusqlite::Connection;
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, RwLock};
+
+mod arrangements;
+mod artist_folders;
+mod auto_tags;
+mod backup;
+mod cache_overlay;
+mod chart_viewer;
+mod clipboard_import;
+mod companions;
+mod coverage;
+mod detail;
+mod difficulty;
+#[cfg(feature = "encryption")]
+mod encryption;
+mod grid_view;
+mod html_export;
+mod ignore_patterns;
+mod ignored;
+mod image_viewer;
+mod import;
+mod lan;
+mod large_library;
+mod learning_path;
+mod list_view;
+mod log_viewer;
+mod maintenance;
+mod markdown;
+mod metronome;
+mod musicbrainz;
+mod new_chart;
+mod obsidian;
+mod open_action;
+mod open_with;
+mod pdf_export;
+mod pending;
+mod player;
+mod practice;
+mod profiler;
+mod refresh;
+mod removal_log;
+mod review;
+mod scan_preview;
+mod scan_progress;
+mod scan_report;
+mod scroll_memory;
+mod session;
+mod smart_lists;
+mod snapshots;
+mod stats;
+mod storage;
+mod table_view;
+mod tag_manager;
+mod tag_packs;
+mod theme;
+mod toasts;
+mod transpose;
+mod trash;
+mod triage;
+mod type_select;
+mod ui_state;
+mod wake;
+mod warmup;
+mod watcher;
+mod website;
+
+pub(crate) use toasts::{notify, Level};
+pub use ui_state::saved_viewport;
 
 const CATEGORY_ORDER: &[&str] = &[
     "instrument",
@@ -20,13 +93,26 @@ const CATEGORY_LABELS: &[(&str, &str)] = &[
     ("technik", "Technik"),
     ("stimmung", "Stimmung"),
     ("kapo", "Kapo"),
+    ("tonart", "Tonart"),
     ("artist", "Artist"),
 ];
 
+/// Chips per row ("Zuletzt", "Häufig") at the top of the tag modal.
+const QUICK_PICKS: usize = 6;
+
+const SEARCH_SYNTAX_HELP: &str = "Freitext sucht in Titel, Artist, Dateiname und Notizen.\n\
+    artist:\"The Beatles\"   titel:yesterday   datei:gitarre   notiz:barr\u{00E9}\n\
+    inhalt:Refrain (Text im PDF)\n\
+    tag:Blues   stil:Jazz   tag:technik:Solo\n\
+    tonart:G   bpm:60-90   bpm:>=120   kapo:<=2   seiten:<=2\n\
+    has:audio   has:tags   is:repertoire   is:favorit   is:privat\n\
+    gespielt:30 (in den letzten 30 Tagen ge\u{00F6}ffnet, geh\u{00F6}rt oder ge\u{00FC}bt)\n\
+    Ein vorangestelltes - schlie\u{00DF}t aus, z.B. -tag:Solo oder -gespielt:30";
+
 fn category_label(kategorie: &str) -> &str {
     for (k, l) in CATEGORY_LABELS {
         if *k == kategorie {
-            return l;
+            return tr(l);
         }
     }
     kategorie
@@ -34,25 +120,20 @@ fn category_label(kategorie: &str) -> &str {
 
 // --- Color Palette ---
 mod palette {
+    use crate::config::ThemeConfig;
     use eframe::egui::Color32;
+    use std::collections::BTreeMap;
+    use std::sync::RwLock;
 
-    pub const BG_DEEP: Color32 = Color32::from_rgb(22, 20, 26);
-    pub const BG_SURFACE: Color32 = Color32::from_rgb(32, 30, 38);
-    pub const BG_CARD: Color32 = Color32::from_rgb(38, 36, 46);
-    pub const BG_INPUT: Color32 = Color32::from_rgb(18, 16, 22);
-    pub const BG_HEADER: Color32 = Color32::from_rgb(26, 24, 32);
+    pub const DEFAULT_ACCENT: Color32 = Color32::from_rgb(235, 180, 60);
+    pub const DEFAULT_BACKGROUND: Color32 = Color32::from_rgb(22, 20, 26);
 
-    pub const ACCENT: Color32 = Color32::from_rgb(235, 180, 60);
-    pub const ACCENT_DIM: Color32 = Color32::from_rgb(180, 138, 48);
     pub const ACCENT_RED: Color32 = Color32::from_rgb(220, 75, 85);
 
     pub const TEXT_PRIMARY: Color32 = Color32::from_rgb(242, 238, 230);
     pub const TEXT_SECONDARY: Color32 = Color32::from_rgb(175, 170, 162);
     pub const TEXT_MUTED: Color32 = Color32::from_rgb(120, 115, 108);
 
-    pub const BORDER_SUBTLE: Color32 = Color32::from_rgb(52, 48, 62);
-    pub const BORDER_ACTIVE: Color32 = Color32::from_rgb(80, 75, 95);
-
     pub const TAG_INSTRUMENT: Color32 = Color32::from_rgb(38, 148, 88);
     pub const TAG_SCHWIERIGKEIT: Color32 = Color32::from_rgb(210, 135, 48);
     pub const TAG_STIL: Color32 = Color32::from_rgb(58, 125, 190);
@@ -60,14 +141,132 @@ mod palette {
     pub const TAG_ARTIST: Color32 = Color32::from_rgb(188, 118, 52);
     pub const TAG_STIMMUNG: Color32 = Color32::from_rgb(95, 142, 78);
     pub const TAG_KAPO: Color32 = Color32::from_rgb(132, 128, 148);
+    pub const TAG_TONART: Color32 = Color32::from_rgb(176, 92, 122);
 
     pub const AUDIO_GREEN: Color32 = Color32::from_rgb(85, 195, 130);
 
-    pub const BTN_BG: Color32 = Color32::from_rgb(48, 45, 58);
-    pub const BTN_HOVER: Color32 = Color32::from_rgb(62, 58, 74);
+    /// The colors `[theme]` in `config.toml` can change.
+    struct Theme {
+        accent: Color32,
+        background: Color32,
+        tags: BTreeMap<String, Color32>,
+    }
+
+    static THEME: RwLock<Theme> = RwLock::new(Theme {
+        accent: DEFAULT_ACCENT,
+        background: DEFAULT_BACKGROUND,
+        tags: BTreeMap::new(),
+    });
+
+    /// `#rrggbb` (the `#` may be left out); `None` for anything else.
+    pub fn parse(hex: &str) -> Option<Color32> {
+        let hex = hex.trim();
+        let hex = if hex.starts_with('#') {
+            hex.to_string()
+        } else {
+            format!("#{hex}")
+        };
+        Color32::from_hex(&hex).ok()
+    }
+
+    pub fn to_hex(color: Color32) -> String {
+        format!("#{:02x}{:02x}{:02x}", color.r(), color.g(), color.b())
+    }
+
+    /// Take the colors from the config; called at startup and by the
+    /// color settings on every change.
+    pub fn load(config: &ThemeConfig) {
+        let read = |value: &Option<String>, default| match value {
+            Some(hex) => parse(hex).unwrap_or_else(|| {
+                log::warn!("Ignoring color {hex:?} in [theme]: expected #rrggbb");
+                default
+            }),
+            None => default,
+        };
+        let mut theme = THEME.write().unwrap_or_else(|e| e.into_inner());
+        theme.accent = read(&config.akzent, DEFAULT_ACCENT);
+        theme.background = read(&config.hintergrund, DEFAULT_BACKGROUND);
+        theme.tags = config
+            .tags
+            .iter()
+            .filter_map(|(kategorie, hex)| match parse(hex) {
+                Some(color) => Some((kategorie.clone(), color)),
+                None => {
+                    log::warn!("Ignoring color {hex:?} for tag category {kategorie}");
+                    None
+                }
+            })
+            .collect();
+    }
+
+    fn theme<T>(f: impl FnOnce(&Theme) -> T) -> T {
+        f(&THEME.read().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    pub fn accent() -> Color32 {
+        theme(|t| t.accent)
+    }
+
+    pub fn accent_dim() -> Color32 {
+        let a = accent();
+        let dim = |c: u8| (c as f32 * 0.766).round() as u8;
+        Color32::from_rgb(dim(a.r()), dim(a.g()), dim(a.b()))
+    }
+
+    /// The configured color of a tag category, if there is one.
+    pub fn tag(kategorie: &str) -> Option<Color32> {
+        theme(|t| t.tags.get(kategorie).copied())
+    }
+
+    /// The background moved by `d` per channel: the surfaces and borders
+    /// keep their distance to it whatever its hue.
+    fn shade(d: [i16; 3]) -> Color32 {
+        let bg = theme(|t| t.background);
+        let add = |c: u8, d: i16| (c as i16 + d).clamp(0, 255) as u8;
+        Color32::from_rgb(add(bg.r(), d[0]), add(bg.g(), d[1]), add(bg.b(), d[2]))
+    }
+
+    pub fn bg_deep() -> Color32 {
+        theme(|t| t.background)
+    }
+
+    pub fn bg_surface() -> Color32 {
+        shade([10, 10, 12])
+    }
+
+    pub fn bg_card() -> Color32 {
+        shade([16, 16, 20])
+    }
+
+    pub fn bg_input() -> Color32 {
+        shade([-4, -4, -4])
+    }
+
+    pub fn bg_header() -> Color32 {
+        shade([4, 4, 6])
+    }
+
+    pub fn border_subtle() -> Color32 {
+        shade([30, 28, 36])
+    }
+
+    pub fn border_active() -> Color32 {
+        shade([58, 55, 69])
+    }
+
+    pub fn btn_bg() -> Color32 {
+        shade([26, 25, 32])
+    }
+
+    pub fn btn_hover() -> Color32 {
+        shade([40, 38, 48])
+    }
 }
 
 fn tag_color(kategorie: &str) -> egui::Color32 {
+    if let Some(color) = palette::tag(kategorie) {
+        return color;
+    }
     match kategorie {
         "instrument" => palette::TAG_INSTRUMENT,
         "schwierigkeit" => palette::TAG_SCHWIERIGKEIT,
@@ -76,6 +275,7 @@ fn tag_color(kategorie: &str) -> egui::Color32 {
         "artist" => palette::TAG_ARTIST,
         "stimmung" => palette::TAG_STIMMUNG,
         "kapo" => palette::TAG_KAPO,
+        "tonart" => palette::TAG_TONART,
         _ => palette::TEXT_MUTED,
     }
 }
@@ -85,12 +285,45 @@ struct TagModalState {
     song_titel: String,
     kategorie_idx: usize,
     wert: String,
+    /// Quick picks from `tag_nutzung`: last used and most used.
+    quick_picks: pending::Loaded<(Vec<TagInfo>, Vec<TagInfo>)>,
 }
 
 struct EditModalState {
     song_id: i64,
     titel: String,
     artist: String,
+    kann_ich: bool,
+    privat: bool,
+    notizen: String,
+    tonart: String,
+    bpm: String,
+    kapo: String,
+    jahr: String,
+    /// App for this song; empty opens it by extension (open_with.rs).
+    oeffnen_mit: String,
+    /// Tags to add on save, e.g. `#stil/Blues #kapo/2 #anf\u{00E4}nger` (hashtags.rs).
+    hashtags: String,
+}
+
+impl EditModalState {
+    fn from_song(song: &Song) -> Self {
+        let number = |n: Option<i64>| n.map(|n| n.to_string()).unwrap_or_default();
+        Self {
+            song_id: song.id,
+            titel: song.titel.clone(),
+            artist: song.artist.clone().unwrap_or_default(),
+            kann_ich: song.kann_ich,
+            privat: song.privat,
+            notizen: song.notizen.clone().unwrap_or_default(),
+            tonart: song.tonart.clone().unwrap_or_default(),
+            bpm: number(song.bpm),
+            kapo: number(song.kapo),
+            jahr: number(song.jahr),
+            oeffnen_mit: song.oeffnen_mit.clone().unwrap_or_default(),
+            hashtags: String::new(),
+        }
+    }
 }
 
 struct ConfirmRemoveTag {
@@ -100,192 +333,517 @@ struct ConfirmRemoveTag {
 }
 
 pub struct SongIndexApp {
-    db: Arc<Mutex<Connection>>,
+    db: Db,
+    config: Config,
+    /// Shared with the file watcher.
+    scan_options: Arc<RwLock<ScanOptions>>,
     base_dir: PathBuf,
-    watcher_rx: std::sync::mpsc::Receiver<()>,
+    watcher_rx: std::sync::mpsc::Receiver<Changed>,
+    watcher_status: WatcherStatus,
+    /// Progress of the running scan, reports of background scans.
+    scan_status: ScanStatus,
+    /// Signals scan batches like the watcher does, for scans started here.
+    scan_tx: std::sync::mpsc::Sender<Changed>,
+    /// A scan started from the menu runs; its report says so even if empty.
+    manual_scan: bool,
+    scan_preview: Option<scan_preview::ScanPreviewState>,
+    scan_report: Option<scan_report::ScanReportState>,
+    /// Changes the wake-from-sleep reconciliation found.
+    wake_rx: std::sync::mpsc::Receiver<Reconciliation>,
+    wake_notice: Option<wake::WakeNotice>,
+    /// Errors and events from anywhere in the app (`notify()`).
+    toasts: toasts::Toasts,
+    refresh: refresh::BackgroundRefresh,
+    jobs: JobQueue,
 
     // UI state
     search_text: String,
     active_filters: HashMap<String, HashSet<i64>>,
-    filter_audio: bool,
-    filter_untagged: bool,
+    category_modes: HashMap<String, MatchMode>,
+    category_join: MatchMode,
+    extras: ExtraFilters,
     sort_mode: SortMode,
+    view_mode: ViewMode,
+    table_sort: Option<(table_view::TableColumn, bool)>,
+    selected_song_id: Option<i64>,
+    type_select: type_select::TypeSelect,
+    /// Section the list scrolls to on the next frame (letter index click).
+    jump_to_letter: Option<char>,
+    scroll_memory: scroll_memory::ScrollMemory,
+    transpose: transpose::TransposeState,
+    scroll_to_selected: bool,
+    /// Last measured height of each card in the list view, for skipping
+    /// cards out of view.
+    card_heights: HashMap<i64, f32>,
+    /// Pieces whose arrangements are unfolded in the list view.
+    expanded_works: HashSet<i64>,
+    thumbnails: Option<crate::thumbnails::ThumbnailCache>,
 
     // Cached data
     songs: Vec<Song>,
     tags: Vec<TagGroup>,
+    /// The `schwierigkeit` values in scale order, reloaded with the tags.
+    difficulty_levels: Vec<DifficultyLevel>,
+    /// Pieces grouping arrangements, by id, reloaded with the list.
+    works: HashMap<i64, crate::arrangements::Work>,
+    smart_lists: Vec<SmartList>,
     stats: Stats,
+    /// Dates and numbers in the configured locale; today's date is
+    /// updated with the stats.
+    format: Formatter,
+    /// Tags and stats need a reload after single-song edits.
+    meta_stale: bool,
+    /// Startup data still loading; until it arrives only a spinner is shown.
+    startup: Option<std::sync::mpsc::Receiver<ui_state::Startup>>,
+    list_loads: refresh::ListLoads,
+    /// Songs to open once their app setting is loaded, in click order.
+    opening: Vec<std::sync::mpsc::Receiver<OpenRequest>>,
+    /// Background jobs were running last frame; when they finish the list is
+    /// reloaded for what they filled in (page counts of older PDFs).
+    jobs_running: bool,
 
     // Modals
     tag_modal: Option<TagModalState>,
     edit_modal: Option<EditModalState>,
     confirm_remove: Option<ConfirmRemoveTag>,
+    import_modal: Option<import::ImportModalState>,
+    new_chart_modal: Option<new_chart::NewChartModalState>,
+    clipboard_modal: Option<clipboard_import::ClipboardModalState>,
+    tag_manager: Option<tag_manager::TagManagerState>,
+    difficulty_window: bool,
+    snapshot_window: Option<snapshots::SnapshotWindowState>,
+    backup_window: Option<backup::BackupWindowState>,
+    #[cfg(feature = "encryption")]
+    encryption_modal: Option<encryption::EncryptionModalState>,
 
     // Settings
     show_settings: bool,
     filters_open: bool,
 
     // Audio playback
-    audio_process: Option<std::process::Child>,
-    audio_playing_song_id: Option<i64>,
+    now_playing: Option<player::NowPlaying>,
+    starting_playback: Option<player::StartingPlayback>,
+    /// Player tempo for this session; kept when the next song starts.
+    playback_tempo: f32,
+    play_queue: Option<player::PlayQueue>,
+    metronome: Option<metronome::MetronomeState>,
+
+    practice_timer: Option<practice::PracticeTimer>,
+    session: Option<session::SessionState>,
+    warmup_window: Option<warmup::WarmupWindowState>,
+    /// Warm-up that opens once loaded; `true` keeps it closed if it has no
+    /// songs, as on startup.
+    warmup_loading: Option<(std::sync::mpsc::Receiver<warmup::WarmupWindowState>, bool)>,
+    review_window: Option<review::ReviewWindowState>,
+    next_songs_window: Option<learning_path::NextSongsState>,
+    stats_window: Option<stats::StatsWindowState>,
+    coverage_window: Option<coverage::CoverageWindowState>,
+    tag_packs: Option<tag_packs::TagPacksState>,
+    chart_viewer: Option<chart_viewer::ChartViewerState>,
+    chart_cache: chart_viewer::ChartCache,
+    image_viewer: Option<image_viewer::ImageViewerState>,
+    html_export: Option<html_export::HtmlExportState>,
+    pdf_export: Option<pdf_export::PdfExportState>,
+    triage: Option<triage::TriageState>,
+    trash_window: Option<trash::TrashWindowState>,
+    removal_log: Option<removal_log::RemovalLogState>,
+    ignored_window: Option<ignored::IgnoredWindowState>,
+    log_viewer: Option<log_viewer::LogViewer>,
+    ignore_patterns: Option<ignore_patterns::IgnorePatternsState>,
+    auto_tag_rules: Option<auto_tags::AutoTagRulesState>,
+    artist_folders: Option<artist_folders::ArtistFoldersState>,
+    companion_groups: Option<companions::CompanionGroupsState>,
+    large_library: Option<large_library::LargeLibraryState>,
+    link_form: Option<detail::LinkForm>,
+    path_form: Option<learning_path::PathForm>,
+    companion_panel: Option<companions::CompanionPanel>,
+    /// Learning path of the selected song, reloaded with the list.
+    song_chain: Option<(i64, pending::Loaded<crate::progression::Chain>)>,
+    arrangement_form: Option<arrangements::ArrangementForm>,
+    /// Arrangements of the selected song, reloaded with the list.
+    song_arrangements: Option<(i64, pending::Loaded<arrangements::SongArrangements>)>,
+    /// Piece of a just added arrangement, to expand once it is there.
+    adding_arrangement: Option<std::sync::mpsc::Receiver<Option<i64>>>,
+    website_window: Option<website::WebsiteWindowState>,
+    obsidian_window: Option<obsidian::ObsidianWindowState>,
+    lan_window: Option<lan::LanWindowState>,
+    open_with_window: Option<open_with::OpenWithState>,
+    musicbrainz: Option<musicbrainz::MusicBrainzState>,
+    theme_window: Option<theme::ThemeState>,
+    storage_window: Option<storage::StorageState>,
+    maintenance_window: Option<maintenance::MaintenanceState>,
+    smart_list_editor: Option<smart_lists::SmartListEditor>,
+    /// Main window geometry of this frame, stored on exit.
+    window_state: Option<ui_state::WindowState>,
+    /// Running while `config.lan.aktiv`; dropping it stops the server.
+    lan_server: Option<crate::lan::LanServer>,
+    /// Cache statistics overlay, toggled with F12.
+    cache_overlay: bool,
+    /// Frames puffin recorded while the profiler (Shift+F12) is open.
+    profiler: Option<puffin::GlobalFrameView>,
 
     needs_refresh: bool,
 }
 
+/// A song file to open, with the app set for the song.
+struct OpenRequest {
+    rel_path: String,
+    song_id: Option<i64>,
+    song_app: Option<String>,
+}
+
 impl SongIndexApp {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        db: Arc<Mutex<Connection>>,
-        base_dir: PathBuf,
-        watcher_rx: std::sync::mpsc::Receiver<()>,
+        db: Db,
+        config: Config,
+        scan_options: Arc<RwLock<ScanOptions>>,
+        watcher_rx: std::sync::mpsc::Receiver<Changed>,
+        watcher_status: WatcherStatus,
+        scan_status: ScanStatus,
+        scan_tx: std::sync::mpsc::Sender<Changed>,
+        wake_rx: std::sync::mpsc::Receiver<Reconciliation>,
+        ctx: egui::Context,
     ) -> Self {
-        let (songs, tags, stats) = {
-            let conn = db.lock().unwrap();
-            let songs = query_songs(&conn, "", &[], false, false, &SortMode::Title);
-            let tags = get_all_tags(&conn);
-            let stats = get_stats(&conn);
-            (songs, tags, stats)
-        };
-
-        Self {
+        crate::i18n::set_language(config.language);
+        palette::load(&config.theme);
+        toasts::Toasts::attach(&ctx);
+        db.on_answer({
+            let ctx = ctx.clone();
+            move || ctx.request_repaint()
+        });
+        let jobs = JobQueue::start(db.clone(), ctx);
+        let chart_cache_limit = config.chart_cache_bytes();
+        let startup = ui_state::load_startup(&db, config.locale);
+
+        let mut app = Self {
             db,
-            base_dir,
+            base_dir: config.music_dir.clone(),
+            config,
+            scan_options,
             watcher_rx,
+            watcher_status,
+            scan_status,
+            scan_tx,
+            manual_scan: false,
+            scan_preview: None,
+            scan_report: None,
+            wake_rx,
+            wake_notice: None,
+            toasts: toasts::Toasts::default(),
+            refresh: Default::default(),
+            jobs,
             search_text: String::new(),
             active_filters: HashMap::new(),
-            filter_audio: false,
-            filter_untagged: false,
+            category_modes: HashMap::new(),
+            category_join: MatchMode::All,
+            extras: ExtraFilters::default(),
             sort_mode: SortMode::Title,
-            songs,
-            tags,
-            stats,
+            view_mode: ViewMode::List,
+            table_sort: None,
+            selected_song_id: None,
+            type_select: Default::default(),
+            jump_to_letter: None,
+            scroll_memory: Default::default(),
+            transpose: Default::default(),
+            scroll_to_selected: false,
+            card_heights: HashMap::new(),
+            expanded_works: HashSet::new(),
+            thumbnails: None,
+            songs: Vec::new(),
+            tags: Vec::new(),
+            difficulty_levels: Vec::new(),
+            works: HashMap::new(),
+            smart_lists: Vec::new(),
+            stats: Stats::default(),
+            format: Formatter::default(),
+            meta_stale: false,
+            startup: Some(startup),
+            list_loads: Default::default(),
+            opening: Vec::new(),
+            jobs_running: false,
             tag_modal: None,
             edit_modal: None,
             confirm_remove: None,
+            import_modal: None,
+            new_chart_modal: None,
+            clipboard_modal: None,
+            tag_manager: None,
+            difficulty_window: false,
+            snapshot_window: None,
+            backup_window: None,
+            #[cfg(feature = "encryption")]
+            encryption_modal: None,
             show_settings: false,
             filters_open: true,
-            audio_process: None,
-            audio_playing_song_id: None,
+            now_playing: None,
+            starting_playback: None,
+            playback_tempo: 1.0,
+            play_queue: None,
+            metronome: None,
+            practice_timer: None,
+            session: None,
+            warmup_window: None,
+            warmup_loading: None,
+            review_window: None,
+            next_songs_window: None,
+            stats_window: None,
+            coverage_window: None,
+            tag_packs: None,
+            chart_viewer: None,
+            chart_cache: crate::lru::LruCache::new(chart_cache_limit),
+            image_viewer: None,
+            html_export: None,
+            pdf_export: None,
+            triage: None,
+            trash_window: None,
+            removal_log: None,
+            ignored_window: None,
+            log_viewer: None,
+            ignore_patterns: None,
+            auto_tag_rules: None,
+            artist_folders: None,
+            companion_groups: None,
+            large_library: None,
+            link_form: None,
+            path_form: None,
+            companion_panel: None,
+            song_chain: None,
+            arrangement_form: None,
+            song_arrangements: None,
+            adding_arrangement: None,
+            website_window: None,
+            obsidian_window: None,
+            lan_window: None,
+            open_with_window: None,
+            musicbrainz: None,
+            theme_window: None,
+            storage_window: None,
+            maintenance_window: None,
+            smart_list_editor: None,
+            window_state: None,
+            lan_server: None,
+            cache_overlay: false,
+            profiler: None,
             needs_refresh: false,
+        };
+
+        if app.config.warmup_on_startup {
+            app.open_warmup_on_startup();
         }
+        if let Err(e) = app.apply_lan() {
+            log::warn!("LAN view not started: {e}");
+        }
+        app
     }
 
+    /// Reload the list with tags and stats; the answer is applied in
+    /// `poll_list_loads`.
     fn refresh_data(&mut self) {
-        let tag_ids: Vec<i64> = self
-            .active_filters
-            .values()
-            .flat_map(|s| s.iter().copied())
-            .collect();
+        self.refresh.invalidate();
+        self.reload_song_chain();
+        self.reload_song_arrangements();
+        let query = self.list_query();
+        let locale = self.config.locale;
+        let loads = &mut self.list_loads;
+        (loads.songs, loads.meta) = (None, None);
+        loads.song.clear();
+        loads.full = Some(self.db.request(move |conn| refresh::ListData {
+            songs: query(conn),
+            tags: get_all_tags(conn),
+            difficulty_levels: difficulty_levels(conn),
+            works: crate::arrangements::works(conn),
+            stats: get_stats(conn),
+            format: Formatter::load(conn, locale),
+        }));
+    }
 
-        let conn = self.db.lock().unwrap();
-        self.songs = query_songs(
-            &conn,
-            &self.search_text,
-            &tag_ids,
-            self.filter_audio,
-            self.filter_untagged,
-            &self.sort_mode,
+    /// The song list query for the current search, filters and sort, to run
+    /// on the database worker.
+    fn list_query(&self) -> impl FnOnce(&Connection) -> Vec<Song> + Send + 'static {
+        let (search, tag_filter, extras, sort_mode) = (
+            self.search_text.clone(),
+            self.tag_filter(),
+            self.extras.clone(),
+            self.sort_mode.clone(),
         );
-        self.tags = get_all_tags(&conn);
-        self.stats = get_stats(&conn);
+        move |conn| query_songs(conn, &search, &tag_filter, &extras, &sort_mode)
     }
 
-    fn collect_tag_ids(&self) -> Vec<i64> {
-        self.active_filters
-            .values()
-            .flat_map(|s| s.iter().copied())
-            .collect()
+    fn category_mode(&self, kategorie: &str) -> MatchMode {
+        self.category_modes
+            .get(kategorie)
+            .copied()
+            .unwrap_or(MatchMode::Any)
+    }
+
+    fn tag_filter(&self) -> TagFilter {
+        TagFilter {
+            groups: self
+                .active_filters
+                .iter()
+                .map(|(kategorie, ids)| {
+                    (ids.iter().copied().collect(), self.category_mode(kategorie))
+                })
+                .collect(),
+            mode: self.category_join,
+        }
     }
 
     fn refresh_songs_only(&mut self) {
-        let tag_ids = self.collect_tag_ids();
-        let conn = self.db.lock().unwrap();
-        self.songs = query_songs(
-            &conn,
-            &self.search_text,
-            &tag_ids,
-            self.filter_audio,
-            self.filter_untagged,
-            &self.sort_mode,
+        self.refresh.invalidate();
+        self.reload_song_chain();
+        self.reload_song_arrangements();
+        let query = self.list_query();
+        self.list_loads.song.clear();
+        self.list_loads.songs = Some(self.db.request(move |conn| query(conn)));
+    }
+
+    /// Reload one song after an edit instead of the whole list. Tags and stats
+    /// are marked stale and reloaded once at the start of the next frame, so a
+    /// burst of edits costs one reload.
+    fn refresh_song(&mut self, song_id: i64) {
+        self.refresh.invalidate();
+        self.reload_song_chain();
+        self.reload_song_arrangements();
+        self.meta_stale = true;
+        let (search, tag_filter, extras) = (
+            self.search_text.clone(),
+            self.tag_filter(),
+            self.extras.clone(),
         );
+        let rx = self
+            .db
+            .request(move |conn| query_song(conn, song_id, &search, &tag_filter, &extras));
+        self.list_loads.song.push((song_id, rx));
     }
 
-    fn stop_audio(&mut self) {
-        if let Some(ref mut child) = self.audio_process {
-            let _ = child.kill();
-            let _ = child.wait();
+    /// Put a reloaded song in place. Falls back to a full song query when the
+    /// song newly matches the filters or its position in the sort order changed.
+    fn apply_song(&mut self, song_id: i64, song: Option<Song>) {
+        let index = self.songs.iter().position(|s| s.id == song_id);
+        match (index, song) {
+            (Some(index), Some(song)) => {
+                let old = std::mem::replace(&mut self.songs[index], song);
+                if self.table_sort.is_some() {
+                    self.apply_table_sort();
+                } else if sort_key_changed(&self.sort_mode, &old, &self.songs[index]) {
+                    self.refresh_songs_only();
+                } else if self.songs[index].werk_id.is_some()
+                    && (old.artist != self.songs[index].artist
+                        || old.tonart != self.songs[index].tonart)
+                {
+                    // The other arrangements took over the new artist or key.
+                    self.refresh_songs_only();
+                }
+            }
+            (Some(index), None) => {
+                self.songs.remove(index);
+            }
+            (None, Some(_)) => self.refresh_songs_only(),
+            (None, None) => {}
         }
-        self.audio_process = None;
-        self.audio_playing_song_id = None;
     }
 
-    fn play_audio(&mut self, song_id: i64, audio_pfad: &str) {
-        self.stop_audio();
-        let full_path = self.base_dir.join(audio_pfad);
-        match std::process::Command::new("afplay")
-            .arg(&full_path)
-            .spawn()
-        {
-            Ok(child) => {
-                self.audio_process = Some(child);
-                self.audio_playing_song_id = Some(song_id);
+    /// Open a song file in its app (open_with.rs) and record it in `song_events`.
+    /// The file opens once the song's own app setting is loaded.
+    fn open_song_file(&mut self, rel_path: &str) {
+        let rel = rel_path.to_string();
+        self.opening.push(self.db.request(move |conn| {
+            let song_id = song_id_by_path(conn, &rel);
+            if let Some(song_id) = song_id {
+                log_song_event(conn, song_id, SongEvent::Opened);
             }
-            Err(_) => {
-                let _ = std::process::Command::new("open")
-                    .arg(&full_path)
-                    .spawn();
+            OpenRequest {
+                song_app: song_id.and_then(|id| song_open_with(conn, id)),
+                rel_path: rel,
+                song_id,
             }
-        }
+        }));
     }
 
-    fn check_audio_finished(&mut self) {
-        if let Some(ref mut child) = self.audio_process {
-            match child.try_wait() {
-                Ok(Some(_)) => {
-                    self.audio_process = None;
-                    self.audio_playing_song_id = None;
+    /// Open the files whose app setting has arrived, in click order.
+    fn poll_opening(&mut self) {
+        while let Some(rx) = self.opening.first() {
+            match rx.try_recv() {
+                Ok(request) => {
+                    self.opening.remove(0);
+                    let path = self.base_dir.join(&request.rel_path);
+                    crate::open_with::open(
+                        &path,
+                        crate::open_with::app_for(
+                            &self.config.open_with,
+                            request.song_app.as_deref(),
+                            &path,
+                        ),
+                    );
+                    if let Some(song_id) = request.song_id {
+                        self.refresh_song(song_id);
+                    }
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => break,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.opening.remove(0);
                 }
-                _ => {}
             }
         }
     }
 
+    /// Full scan of the music folder in the background, like the one at
+    /// startup, queueing background jobs for what changed. `poll_scan_status`
+    /// shows the Scan-Bericht, or a toast if nothing changed. While a scan
+    /// runs, its report stands for this one.
+    fn rescan(&mut self) {
+        self.manual_scan = true;
+        crate::scanner::start_scan(
+            self.db.clone(),
+            self.base_dir.clone(),
+            self.scan_options.read().unwrap().clone(),
+            self.scan_status.clone(),
+            self.scan_tx.clone(),
+            |_| {},
+        );
+    }
+
     fn apply_theme(&self, ctx: &egui::Context) {
         let mut visuals = egui::Visuals::dark();
-        visuals.panel_fill = palette::BG_DEEP;
-        visuals.window_fill = palette::BG_SURFACE;
-        visuals.extreme_bg_color = palette::BG_INPUT;
-        visuals.faint_bg_color = palette::BG_CARD;
+        visuals.panel_fill = palette::bg_deep();
+        visuals.window_fill = palette::bg_surface();
+        visuals.extreme_bg_color = palette::bg_input();
+        visuals.faint_bg_color = palette::bg_card();
 
-        visuals.widgets.noninteractive.bg_fill = palette::BG_SURFACE;
+        visuals.widgets.noninteractive.bg_fill = palette::bg_surface();
         visuals.widgets.noninteractive.fg_stroke =
             egui::Stroke::new(1.0, palette::TEXT_SECONDARY);
         visuals.widgets.noninteractive.bg_stroke =
-            egui::Stroke::new(0.5, palette::BORDER_SUBTLE);
+            egui::Stroke::new(0.5, palette::border_subtle());
         visuals.widgets.noninteractive.rounding = egui::Rounding::same(6.0);
 
-        visuals.widgets.inactive.bg_fill = palette::BTN_BG;
+        visuals.widgets.inactive.bg_fill = palette::btn_bg();
         visuals.widgets.inactive.fg_stroke =
             egui::Stroke::new(1.0, palette::TEXT_PRIMARY);
         visuals.widgets.inactive.bg_stroke =
-            egui::Stroke::new(0.5, palette::BORDER_SUBTLE);
+            egui::Stroke::new(0.5, palette::border_subtle());
         visuals.widgets.inactive.rounding = egui::Rounding::same(6.0);
 
-        visuals.widgets.hovered.bg_fill = palette::BTN_HOVER;
+        visuals.widgets.hovered.bg_fill = palette::btn_hover();
         visuals.widgets.hovered.fg_stroke =
             egui::Stroke::new(1.0, palette::TEXT_PRIMARY);
         visuals.widgets.hovered.bg_stroke =
-            egui::Stroke::new(1.0, palette::BORDER_ACTIVE);
+            egui::Stroke::new(1.0, palette::border_active());
         visuals.widgets.hovered.rounding = egui::Rounding::same(6.0);
 
-        visuals.widgets.active.bg_fill = palette::ACCENT_DIM;
+        visuals.widgets.active.bg_fill = palette::accent_dim();
         visuals.widgets.active.fg_stroke =
             egui::Stroke::new(1.0, palette::TEXT_PRIMARY);
         visuals.widgets.active.bg_stroke =
-            egui::Stroke::new(1.0, palette::ACCENT);
+            egui::Stroke::new(1.0, palette::accent());
         visuals.widgets.active.rounding = egui::Rounding::same(6.0);
 
-        visuals.selection.bg_fill = egui::Color32::from_rgba_premultiplied(235, 180, 60, 40);
-        visuals.selection.stroke = egui::Stroke::new(1.0, palette::ACCENT);
+        let accent = palette::accent();
+        visuals.selection.bg_fill =
+            egui::Color32::from_rgba_premultiplied(accent.r(), accent.g(), accent.b(), 40);
+        visuals.selection.stroke = egui::Stroke::new(1.0, palette::accent());
 
         visuals.window_rounding = egui::Rounding::same(10.0);
         visuals.window_shadow = egui::epaint::Shadow {
@@ -294,7 +852,7 @@ impl SongIndexApp {
             spread: 2.0,
             color: egui::Color32::from_black_alpha(100),
         };
-        visuals.window_stroke = egui::Stroke::new(1.0, palette::BORDER_SUBTLE);
+        visuals.window_stroke = egui::Stroke::new(1.0, palette::border_subtle());
         visuals.popup_shadow = egui::epaint::Shadow {
             offset: egui::vec2(0.0, 2.0),
             blur: 8.0,
@@ -330,18 +888,202 @@ impl SongIndexApp {
     }
 }
 
+/// Write `config.toml`. A failure keeps the change for this session only.
+fn save_config(config: &Config) {
+    if let Err(e) = crate::config::save_config(config) {
+        log::warn!("Failed to write config: {e}");
+    }
+}
+
+/// Tag a song from the tag dialog without waiting; the list reload that
+/// follows (`needs_refresh`) queues behind the write.
+fn send_tag(db: &Db, song_id: i64, kategorie: &str, wert: &str) {
+    let (kategorie, wert) = (kategorie.to_string(), wert.to_string());
+    db.send(move |conn| apply_tag(conn, song_id, &kategorie, &wert));
+}
+
 fn styled_small_button(ui: &mut egui::Ui, label: &str) -> egui::Response {
     let btn = egui::Button::new(
         egui::RichText::new(label)
             .size(12.5)
             .color(palette::TEXT_SECONDARY),
     )
-    .fill(palette::BTN_BG)
-    .stroke(egui::Stroke::new(0.5, palette::BORDER_SUBTLE))
+    .fill(palette::btn_bg())
+    .stroke(egui::Stroke::new(0.5, palette::border_subtle()))
     .rounding(5.0);
     ui.add(btn)
 }
 
+fn match_mode_toggle(ui: &mut egui::Ui, mode: MatchMode) -> egui::Response {
+    let btn = egui::Button::new(
+        egui::RichText::new(tr(mode.label()))
+            .size(10.5)
+            .strong()
+            .color(match mode {
+                MatchMode::Any => palette::TEXT_SECONDARY,
+                MatchMode::All => palette::accent(),
+            }),
+    )
+    .fill(palette::btn_bg())
+    .stroke(egui::Stroke::new(0.5, palette::border_subtle()))
+    .rounding(4.0)
+    .small();
+    ui.add(btn)
+}
+
+/// Right-click menu on a song's title in the list, table and grid views.
+fn song_context_menu(resp: &egui::Response, song: &Song, action: &mut Option<SongAction>) {
+    resp.context_menu(|ui| {
+        if ui.button(tr("Datei \u{00F6}ffnen")).clicked() {
+            *action = Some(SongAction::OpenFile(song.dateipfad.clone()));
+            ui.close_menu();
+        }
+        if ui.button(tr(crate::open_with::REVEAL_LABEL)).clicked() {
+            *action = Some(SongAction::Reveal(song.dateipfad.clone()));
+            ui.close_menu();
+        }
+        if ui.button(tr("Bearbeiten")).clicked() {
+            *action = Some(SongAction::Edit(song.id));
+            ui.close_menu();
+        }
+    });
+}
+
+/// Whether an edit moved the song within the SQL sort order.
+fn sort_key_changed(sort: &SortMode, old: &Song, new: &Song) -> bool {
+    old.titel != new.titel
+        || match sort {
+            SortMode::Title | SortMode::Recent => false,
+            SortMode::Artist => old.artist != new.artist,
+            SortMode::Untagged => old.tags.len() != new.tags.len(),
+            SortMode::Rating => old.favorit != new.favorit || old.bewertung != new.bewertung,
+            SortMode::LastOpened => old.zuletzt_geoeffnet != new.zuletzt_geoeffnet,
+            SortMode::MostUsed => old.nutzungen != new.nutzungen,
+            SortMode::Difficulty => {
+                let levels = |song: &Song| {
+                    song.tags
+                        .iter()
+                        .filter(|t| t.kategorie == "schwierigkeit")
+                        .map(|t| t.id)
+                        .collect::<Vec<_>>()
+                };
+                levels(old) != levels(new)
+            }
+        }
+}
+
+/// Key, tempo and capo as one short line, e.g. "G \u{00B7} 92 BPM \u{00B7} Kapo 2".
+fn music_summary(song: &Song) -> Option<String> {
+    let mut parts = Vec::new();
+    if let Some(ref tonart) = song.tonart {
+        parts.push(tonart.clone());
+    }
+    if let Some(bpm) = song.bpm {
+        parts.push(format!("{bpm} BPM"));
+    }
+    if let Some(kapo) = song.kapo.filter(|k| *k > 0) {
+        parts.push(tr_arg("Kapo {}", kapo));
+    }
+    if let Some(jahr) = song.jahr {
+        parts.push(jahr.to_string());
+    }
+    match song.seiten {
+        Some(1) => parts.push(tr("1 Seite").to_string()),
+        Some(seiten) if seiten > 1 => parts.push(tr_arg("{} Seiten", seiten)),
+        _ => {}
+    }
+    (!parts.is_empty()).then(|| parts.join(" \u{00B7} "))
+}
+
+/// Host of a URL without `www.`; empty for schemes without one.
+fn url_host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let host = rest.split(['/', '?', '#']).next().unwrap_or(rest);
+    host.trim_start_matches("www.")
+}
+
+/// Icon for a song link by where it points: lesson video, recording, tab
+/// page, notes, anything else.
+fn link_icon(url: &str) -> &'static str {
+    let host = url_host(url);
+    let on = |domains: &[&str]| {
+        domains
+            .iter()
+            .any(|d| host == *d || host.ends_with(&format!(".{d}")))
+    };
+    if on(&["youtube.com", "youtu.be", "vimeo.com"]) {
+        "\u{1F3AC}"
+    } else if url.starts_with("spotify:")
+        || on(&[
+            "spotify.com",
+            "music.apple.com",
+            "deezer.com",
+            "tidal.com",
+            "soundcloud.com",
+            "bandcamp.com",
+        ])
+    {
+        "\u{1F3B5}"
+    } else if on(&["ultimate-guitar.com", "songsterr.com", "chordify.net"]) {
+        "\u{1F3B8}"
+    } else if url.starts_with("obsidian:")
+        || url.starts_with("notion:")
+        || on(&["notion.so", "docs.google.com", "drive.google.com"])
+    {
+        "\u{1F4DD}"
+    } else {
+        "\u{1F517}"
+    }
+}
+
+/// Heart and 1–5 stars for a song card; laid out right to left. Clicking the
+/// current rating again clears it.
+fn rating_controls(ui: &mut egui::Ui, song: &Song) -> Option<SongAction> {
+    let mut action = None;
+    ui.spacing_mut().item_spacing.x = 1.0;
+    for stars in (1..=5).rev() {
+        let filled = stars <= song.bewertung;
+        let star = egui::Label::new(
+            egui::RichText::new(if filled { "\u{2605}" } else { "\u{2606}" })
+                .size(14.0)
+                .color(if filled {
+                    palette::accent()
+                } else {
+                    palette::TEXT_MUTED
+                }),
+        )
+        .sense(egui::Sense::click());
+        if ui.add(star).on_hover_text(tr_arg("{} von 5", stars)).clicked() {
+            action = Some(SongAction::SetRating {
+                song_id: song.id,
+                bewertung: if stars == song.bewertung { 0 } else { stars },
+            });
+        }
+    }
+    ui.add_space(4.0);
+    let heart = egui::Label::new(
+        egui::RichText::new(if song.favorit { "\u{2665}" } else { "\u{2661}" })
+            .size(15.0)
+            .color(if song.favorit { palette::ACCENT_RED } else { palette::TEXT_MUTED }),
+    )
+    .sense(egui::Sense::click());
+    if ui
+        .add(heart)
+        .on_hover_text(if song.favorit {
+            tr("Favorit entfernen")
+        } else {
+            tr("Als Favorit markieren")
+        })
+        .clicked()
+    {
+        action = Some(SongAction::ToggleFavorite {
+            song_id: song.id,
+            favorit: !song.favorit,
+        });
+    }
+    action
+}
+
 fn stat_badge(ui: &mut egui::Ui, value: &str, label: &str, color: egui::Color32) {
     ui.horizontal(|ui| {
         ui.spacing_mut().item_spacing.x = 3.0;
@@ -360,29 +1102,67 @@ fn stat_badge(ui: &mut egui::Ui, value: &str, label: &str, color: egui::Color32)
 }
 
 impl eframe::App for SongIndexApp {
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.save_ui_state();
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        while self.watcher_rx.try_recv().is_ok() {
-            self.needs_refresh = true;
+        puffin::GlobalProfiler::lock().new_frame();
+        puffin::profile_scope!("Frame");
+        self.track_window(ctx);
+        if let Some(startup) = pending::answer(&mut self.startup) {
+            self.apply_startup(startup);
+        }
+        if self.startup.is_some() {
+            self.apply_theme(ctx);
+            egui::CentralPanel::default().show(ctx, |ui| {
+                ui.centered_and_justified(|ui| ui.spinner());
+            });
+            return;
+        }
+        self.poll_background_refresh();
+        self.poll_scan_status();
+        let jobs_running = self.jobs.progress().is_some();
+        if self.jobs_running && !jobs_running {
+            self.refresh_songs_only();
+        }
+        self.jobs_running = jobs_running;
+        if self.meta_stale {
+            self.meta_stale = false;
+            let locale = self.config.locale;
+            self.list_loads.meta = Some(self.db.request(move |conn| {
+                (
+                    get_all_tags(conn),
+                    difficulty_levels(conn),
+                    get_stats(conn),
+                    Formatter::load(conn, locale),
+                )
+            }));
         }
         if self.needs_refresh {
             self.needs_refresh = false;
             self.refresh_data();
         }
+        self.poll_list_loads();
+        self.poll_opening();
 
         self.check_audio_finished();
-        if self.audio_playing_song_id.is_some() {
-            ctx.request_repaint_after(std::time::Duration::from_millis(500));
+
+        if ctx.input(|i| i.viewport().close_requested()) {
+            self.stop_practice();
         }
 
         self.apply_theme(ctx);
+        self.handle_type_select(ctx);
+        self.handle_open_key(ctx);
 
         // ── Header ──
         egui::TopBottomPanel::top("header")
             .frame(
                 egui::Frame::none()
-                    .fill(palette::BG_HEADER)
+                    .fill(palette::bg_header())
                     .inner_margin(egui::Margin::symmetric(16.0, 12.0))
-                    .stroke(egui::Stroke::new(0.5, palette::BORDER_SUBTLE)),
+                    .stroke(egui::Stroke::new(0.5, palette::border_subtle())),
             )
             .show(ctx, |ui| {
                 ui.horizontal(|ui| {
@@ -396,7 +1176,7 @@ impl eframe::App for SongIndexApp {
                     ui.label(
                         egui::RichText::new("\u{266B}")
                             .size(18.0)
-                            .color(palette::ACCENT_DIM),
+                            .color(palette::accent_dim()),
                     );
 
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
@@ -407,52 +1187,93 @@ impl eframe::App for SongIndexApp {
                         )
                         .fill(egui::Color32::TRANSPARENT)
                         .stroke(egui::Stroke::NONE);
-                        if ui.add(gear).on_hover_text("Einstellungen").clicked() {
+                        if ui.add(gear).on_hover_text(tr("Einstellungen")).clicked() {
                             self.show_settings = !self.show_settings;
                         }
+                        let stats_btn = egui::Button::new(
+                            egui::RichText::new(tr("Statistik"))
+                                .size(13.0)
+                                .color(palette::TEXT_MUTED),
+                        )
+                        .fill(egui::Color32::TRANSPARENT)
+                        .stroke(egui::Stroke::NONE);
+                        if ui.add(stats_btn).clicked() {
+                            self.open_stats();
+                        }
+                        if let Some((done, total)) = self.jobs.progress() {
+                            ui.add_space(8.0);
+                            ui.label(
+                                egui::RichText::new(format!("{done}/{total}"))
+                                    .size(12.0)
+                                    .color(palette::TEXT_MUTED),
+                            )
+                            .on_hover_text(tr(
+                                "Hintergrundjobs: Vorschaubilder, PDF-Text, Pr\u{00FC}fsummen",
+                            ));
+                            ui.add(egui::Spinner::new().size(12.0).color(palette::TEXT_MUTED));
+                        }
+                        self.show_scan_progress(ui);
+                        self.show_watcher_status(ui);
 
                         ui.add_space(8.0);
                         stat_badge(
                             ui,
-                            &self.stats.untagged_songs.to_string(),
-                            "ohne Tags",
+                            &self.format.number(self.stats.practice_seconds_week / 60),
+                            tr("min ge\u{00FC}bt diese Woche"),
                             palette::TEXT_MUTED,
                         );
                         ui.add_space(6.0);
                         ui.label(
                             egui::RichText::new("\u{00B7}")
                                 .size(16.0)
-                                .color(palette::BORDER_SUBTLE),
+                                .color(palette::border_subtle()),
                         );
                         ui.add_space(6.0);
                         stat_badge(
                             ui,
-                            &self.stats.songs_with_audio.to_string(),
-                            "mit Audio",
+                            &self.format.number(self.stats.untagged_songs),
+                            tr("ohne Tags"),
+                            palette::TEXT_MUTED,
+                        );
+                        ui.add_space(6.0);
+                        ui.label(
+                            egui::RichText::new("\u{00B7}")
+                                .size(16.0)
+                                .color(palette::border_subtle()),
+                        );
+                        ui.add_space(6.0);
+                        stat_badge(
+                            ui,
+                            &self.format.number(self.stats.songs_with_audio),
+                            tr("mit Audio"),
                             palette::AUDIO_GREEN,
                         );
                         ui.add_space(6.0);
                         ui.label(
                             egui::RichText::new("\u{00B7}")
                                 .size(16.0)
-                                .color(palette::BORDER_SUBTLE),
+                                .color(palette::border_subtle()),
                         );
                         ui.add_space(6.0);
                         stat_badge(
                             ui,
-                            &self.stats.total_songs.to_string(),
+                            &self.format.number(self.stats.total_songs),
                             "Songs",
-                            palette::ACCENT,
+                            palette::accent(),
                         );
                     });
                 });
             });
 
+        self.show_player_bar(ctx);
+        self.show_practice_bar(ctx);
+        self.show_detail_panel(ctx);
+
         // ── Central Panel ──
         egui::CentralPanel::default()
             .frame(
                 egui::Frame::none()
-                    .fill(palette::BG_DEEP)
+                    .fill(palette::bg_deep())
                     .inner_margin(egui::Margin::symmetric(16.0, 12.0)),
             )
             .show(ctx, |ui| {
@@ -467,17 +1288,18 @@ impl eframe::App for SongIndexApp {
                             .color(palette::TEXT_MUTED),
                     );
 
-                    let search_width = ui.available_width() - 80.0;
+                    let search_width = ui.available_width() - 160.0;
                     let response = ui.add_sized(
                         [search_width, 28.0],
                         egui::TextEdit::singleline(&mut self.search_text)
                             .hint_text(
-                                egui::RichText::new("Suche nach Titel oder Artist...")
+                                egui::RichText::new(tr("Suche nach Titel oder Artist..."))
                                     .color(palette::TEXT_MUTED),
                             )
                             .text_color(palette::TEXT_PRIMARY)
                             .margin(egui::Margin::symmetric(8.0, 4.0)),
-                    );
+                    )
+                    .on_hover_text(tr(SEARCH_SYNTAX_HELP));
                     if response.changed() {
                         search_changed = true;
                     }
@@ -487,14 +1309,28 @@ impl eframe::App for SongIndexApp {
                             .size(13.0)
                             .color(palette::TEXT_SECONDARY),
                     )
-                    .fill(palette::BTN_BG)
+                    .fill(palette::btn_bg())
                     .rounding(6.0);
                     if ui.add(rescan_btn).clicked() {
-                        let conn = self.db.lock().unwrap();
-                        crate::scanner::scan_directory(&conn, &self.base_dir);
-                        drop(conn);
-                        self.refresh_data();
+                        self.rescan();
+                        self.check_library_size();
                     }
+
+                    ui.menu_button(
+                        egui::RichText::new("+ Chart")
+                            .size(13.0)
+                            .color(palette::TEXT_SECONDARY),
+                        |ui| {
+                            if ui.button(tr("Neues Chart aus Vorlage\u{2026}")).clicked() {
+                                self.open_new_chart();
+                                ui.close_menu();
+                            }
+                            if ui.button(tr("Aus Zwischenablage importieren\u{2026}")).clicked() {
+                                self.open_clipboard_import();
+                                ui.close_menu();
+                            }
+                        },
+                    );
                 });
 
                 if search_changed {
@@ -508,8 +1344,13 @@ impl eframe::App for SongIndexApp {
 
                 // Accordion header
                 let active_count: usize = self.active_filters.values().map(|s| s.len()).sum::<usize>()
-                    + if self.filter_audio { 1 } else { 0 }
-                    + if self.filter_untagged { 1 } else { 0 };
+                    + if self.extras.has_audio { 1 } else { 0 }
+                    + if self.extras.untagged { 1 } else { 0 }
+                    + if self.extras.favorites { 1 } else { 0 }
+                    + if self.extras.bpm.is_some() { 1 } else { 0 }
+                    + if self.extras.kapo_max.is_some() { 1 } else { 0 }
+                    + if self.extras.schwierigkeit.is_some() { 1 } else { 0 }
+                    + if self.extras.smart_list.is_some() { 1 } else { 0 };
 
                 ui.horizontal(|ui| {
                     let arrow = if self.filters_open { "\u{25BE}" } else { "\u{25B8}" };
@@ -523,7 +1364,7 @@ impl eframe::App for SongIndexApp {
                         egui::RichText::new(&header_text)
                             .size(13.0)
                             .color(if active_count > 0 {
-                                palette::ACCENT
+                                palette::accent()
                             } else {
                                 palette::TEXT_SECONDARY
                             }),
@@ -545,6 +1386,7 @@ impl eframe::App for SongIndexApp {
                             if group.tags.is_empty() {
                                 continue;
                             }
+                            let mode = self.category_mode(cat_name);
                             ui.horizontal_wrapped(|ui| {
                                 ui.spacing_mut().item_spacing = egui::vec2(6.0, 4.0);
                                 ui.label(
@@ -555,19 +1397,27 @@ impl eframe::App for SongIndexApp {
                                     .size(12.5)
                                     .color(palette::TEXT_MUTED),
                                 );
+                                if match_mode_toggle(ui, mode)
+                                    .on_hover_text(tr("Mehrere Tags dieser Kategorie: mindestens einer (ODER) oder alle (UND)"))
+                                    .clicked()
+                                {
+                                    self.category_modes
+                                        .insert(cat_name.to_string(), mode.toggled());
+                                    filter_changed = true;
+                                }
                                 ui.add_space(2.0);
 
                                 for tag in &group.tags {
                                     let is_active = self
                                         .active_filters
                                         .get(*cat_name)
-                                        .map_or(false, |s| s.contains(&tag.id));
+                                        .is_some_and(|s| s.contains(&tag.id));
 
                                     let text = format!("{} ({})", tag.wert, tag.count);
                                     let label = if is_active {
                                         egui::RichText::new(&text)
                                             .size(12.5)
-                                            .color(palette::BG_DEEP)
+                                            .color(palette::bg_deep())
                                             .strong()
                                     } else {
                                         egui::RichText::new(&text)
@@ -601,48 +1451,163 @@ impl eframe::App for SongIndexApp {
                     ui.horizontal(|ui| {
                         ui.spacing_mut().item_spacing = egui::vec2(6.0, 4.0);
                         ui.label(
-                            egui::RichText::new("Extras:")
+                            egui::RichText::new(tr("Extras:"))
                                 .size(12.5)
                                 .color(palette::TEXT_MUTED),
                         );
                         ui.add_space(2.0);
 
-                        let audio_label = if self.filter_audio {
-                            egui::RichText::new("Nur mit Audio")
+                        let audio_label = if self.extras.has_audio {
+                            egui::RichText::new(tr("Nur mit Audio"))
+                                .size(12.5)
+                                .color(palette::bg_deep())
+                                .strong()
+                        } else {
+                            egui::RichText::new(tr("Nur mit Audio"))
+                                .size(12.5)
+                                .color(palette::TEXT_SECONDARY)
+                        };
+                        if ui
+                            .selectable_label(self.extras.has_audio, audio_label)
+                            .clicked()
+                        {
+                            self.extras.has_audio = !self.extras.has_audio;
+                            filter_changed = true;
+                        }
+
+                        let untagged_label = if self.extras.untagged {
+                            egui::RichText::new(tr("Ohne Tags"))
                                 .size(12.5)
-                                .color(palette::BG_DEEP)
+                                .color(palette::bg_deep())
                                 .strong()
                         } else {
-                            egui::RichText::new("Nur mit Audio")
+                            egui::RichText::new(tr("Ohne Tags"))
                                 .size(12.5)
                                 .color(palette::TEXT_SECONDARY)
                         };
                         if ui
-                            .selectable_label(self.filter_audio, audio_label)
+                            .selectable_label(self.extras.untagged, untagged_label)
                             .clicked()
                         {
-                            self.filter_audio = !self.filter_audio;
+                            self.extras.untagged = !self.extras.untagged;
                             filter_changed = true;
                         }
 
-                        let untagged_label = if self.filter_untagged {
-                            egui::RichText::new("Ohne Tags")
+                        let favorites_label = if self.extras.favorites {
+                            egui::RichText::new(tr("Nur Favoriten"))
                                 .size(12.5)
-                                .color(palette::BG_DEEP)
+                                .color(palette::bg_deep())
                                 .strong()
                         } else {
-                            egui::RichText::new("Ohne Tags")
+                            egui::RichText::new(tr("Nur Favoriten"))
                                 .size(12.5)
                                 .color(palette::TEXT_SECONDARY)
                         };
                         if ui
-                            .selectable_label(self.filter_untagged, untagged_label)
+                            .selectable_label(self.extras.favorites, favorites_label)
                             .clicked()
                         {
-                            self.filter_untagged = !self.filter_untagged;
+                            self.extras.favorites = !self.extras.favorites;
+                            filter_changed = true;
+                        }
+
+                        ui.add_space(12.0);
+                        ui.label(
+                            egui::RichText::new(tr("Kategorien verkn\u{00FC}pfen:"))
+                                .size(12.5)
+                                .color(palette::TEXT_MUTED),
+                        );
+                        if match_mode_toggle(ui, self.category_join)
+                            .on_hover_text(tr("Songs m\u{00FC}ssen die Filter aller Kategorien erf\u{00FC}llen (UND) oder mindestens einer (ODER)"))
+                            .clicked()
+                        {
+                            self.category_join = self.category_join.toggled();
                             filter_changed = true;
                         }
                     });
+
+                    // Value ranges
+                    ui.horizontal(|ui| {
+                        ui.spacing_mut().item_spacing = egui::vec2(6.0, 4.0);
+                        ui.label(
+                            egui::RichText::new(tr("Werte:"))
+                                .size(12.5)
+                                .color(palette::TEXT_MUTED),
+                        );
+                        ui.add_space(2.0);
+
+                        let mut bpm_on = self.extras.bpm.is_some();
+                        if ui.checkbox(&mut bpm_on, "BPM").changed() {
+                            self.extras.bpm = bpm_on.then_some((60, 120));
+                            filter_changed = true;
+                        }
+                        if let Some((ref mut min, ref mut max)) = self.extras.bpm {
+                            filter_changed |= ui
+                                .add(egui::DragValue::new(min).range(20..=*max))
+                                .changed();
+                            ui.label("\u{2013}");
+                            filter_changed |= ui
+                                .add(egui::DragValue::new(max).range(*min..=300))
+                                .changed();
+                        }
+
+                        ui.add_space(12.0);
+                        let mut kapo_on = self.extras.kapo_max.is_some();
+                        if ui.checkbox(&mut kapo_on, tr("Kapo bis")).changed() {
+                            self.extras.kapo_max = kapo_on.then_some(2);
+                            filter_changed = true;
+                        }
+                        if let Some(ref mut max) = self.extras.kapo_max {
+                            filter_changed |= ui
+                                .add(egui::DragValue::new(max).range(0..=12))
+                                .changed();
+                        }
+
+                        // Positions on the scale; stored ranks may have gaps.
+                        let levels: Vec<&DifficultyLevel> =
+                            self.difficulty_levels.iter().filter(|l| l.rang.is_some()).collect();
+                        if levels.len() >= 2 {
+                            ui.add_space(12.0);
+                            let mut difficulty_on = self.extras.schwierigkeit.is_some();
+                            if ui.checkbox(&mut difficulty_on, tr("Schwierigkeit")).changed() {
+                                self.extras.schwierigkeit = if difficulty_on {
+                                    levels[0].rang.zip(levels[levels.len() - 1].rang)
+                                } else {
+                                    None
+                                };
+                                filter_changed = true;
+                            }
+                            if let Some((min, max)) = self.extras.schwierigkeit {
+                                let position = |rang: i64| {
+                                    levels
+                                        .iter()
+                                        .position(|l| l.rang >= Some(rang))
+                                        .unwrap_or(levels.len() - 1)
+                                };
+                                let (mut from, mut to) = (position(min), position(max));
+                                ui.spacing_mut().slider_width = 70.0;
+                                let mut moved = ui
+                                    .add(egui::Slider::new(&mut from, 0..=to).show_value(false))
+                                    .changed();
+                                ui.label(&levels[from].wert);
+                                ui.label("\u{2013}");
+                                moved |= ui
+                                    .add(
+                                        egui::Slider::new(&mut to, from..=levels.len() - 1)
+                                            .show_value(false),
+                                    )
+                                    .changed();
+                                ui.label(&levels[to].wert);
+                                if moved {
+                                    self.extras.schwierigkeit =
+                                        levels[from].rang.zip(levels[to].rang);
+                                    filter_changed = true;
+                                }
+                            }
+                        }
+                    });
+
+                    filter_changed |= self.smart_list_row(ui);
                 }
 
                 if filter_changed {
@@ -655,14 +1620,61 @@ impl eframe::App for SongIndexApp {
                 let mut sort_changed = false;
                 ui.horizontal(|ui| {
                     ui.label(
-                        egui::RichText::new(format!("{} Songs gefunden", self.songs.len()))
+                        egui::RichText::new(tr_arg("{} Songs gefunden", self.songs.len()))
                             .size(14.0)
                             .color(palette::TEXT_SECONDARY),
                     );
+                    ui.add_space(8.0);
+                    if styled_small_button(ui, tr("\u{00DC}be-Session\u{2026}")).clicked() {
+                        self.open_session_planner();
+                    }
+                    if styled_small_button(ui, tr("Aufw\u{00E4}rmen\u{2026}")).clicked() {
+                        self.open_warmup();
+                    }
+                    if styled_small_button(ui, tr("Metronom\u{2026}"))
+                        .on_hover_text(tr("\u{00DC}bernimmt das Tempo des ausgew\u{00E4}hlten Songs"))
+                        .clicked()
+                    {
+                        self.open_metronome();
+                    }
+                    let review_label = if self.stats.review_due > 0 {
+                        format!("{} ({})", tr("Wiederholen"), self.stats.review_due)
+                    } else {
+                        tr("Wiederholen").to_string()
+                    };
+                    if styled_small_button(ui, &review_label).clicked() {
+                        self.open_review();
+                    }
+                    if styled_small_button(ui, tr("Als N\u{00E4}chstes\u{2026}"))
+                        .on_hover_text(tr("Songs, deren Lernpfad bis hierher als \u{201E}Kann ich\u{201C} markiert ist"))
+                        .clicked()
+                    {
+                        self.open_next_songs();
+                    }
+                    if styled_small_button(ui, tr("Tags vergeben\u{2026}")).clicked() {
+                        self.open_triage();
+                    }
+                    if styled_small_button(ui, tr("Als HTML exportieren\u{2026}")).clicked() {
+                        self.open_html_export();
+                    }
+                    if self.songs.iter().any(|s| s.dateityp == "pdf")
+                        && styled_small_button(ui, tr("Als PDF-Mappe exportieren\u{2026}"))
+                            .on_hover_text(tr("PDFs der Liste zu einer Datei zusammenf\u{00FC}gen, z.B. f\u{00FC}r einen Auftritt"))
+                            .clicked()
+                    {
+                        self.open_pdf_export();
+                    }
+                    if self.songs.iter().any(|s| s.has_audio)
+                        && styled_small_button(ui, tr("\u{25B6} Alle abspielen"))
+                            .on_hover_text(tr("Audio aller Songs der Liste nacheinander abspielen"))
+                            .clicked()
+                    {
+                        self.play_all();
+                    }
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        let current_label = self.sort_mode.label();
+                        let current_label = tr(self.sort_mode.label());
                         egui::ComboBox::from_label(
-                            egui::RichText::new("Sortierung")
+                            egui::RichText::new(tr("Sortierung"))
                                 .size(12.5)
                                 .color(palette::TEXT_MUTED),
                         )
@@ -677,7 +1689,7 @@ impl eframe::App for SongIndexApp {
                                     .selectable_value(
                                         &mut self.sort_mode,
                                         mode.clone(),
-                                        mode.label(),
+                                        tr(mode.label()),
                                     )
                                     .changed()
                                 {
@@ -685,10 +1697,24 @@ impl eframe::App for SongIndexApp {
                                 }
                             }
                         });
+                        ui.add_space(8.0);
+                        for mode in [ViewMode::Grid, ViewMode::Table, ViewMode::List] {
+                            let selected = self.view_mode == mode;
+                            if ui
+                                .selectable_label(
+                                    selected,
+                                    egui::RichText::new(tr(mode.label())).size(12.5),
+                                )
+                                .clicked()
+                            {
+                                self.view_mode = mode;
+                            }
+                        }
                     });
                 });
 
                 if sort_changed {
+                    self.table_sort = None;
                     self.refresh_songs_only();
                 }
 
@@ -701,190 +1727,59 @@ impl eframe::App for SongIndexApp {
                     egui::vec2(rect.width(), 1.0),
                 );
                 ui.painter()
-                    .rect_filled(sep_rect, 0.0, palette::BORDER_SUBTLE);
+                    .rect_filled(sep_rect, 0.0, palette::border_subtle());
                 ui.add_space(6.0);
 
                 // ── Song list ──
                 let mut action: Option<SongAction> = None;
-
-                egui::ScrollArea::vertical()
-                    .auto_shrink([false, false])
-                    .show(ui, |ui| {
-                        if self.songs.is_empty() {
-                            ui.add_space(40.0);
-                            ui.vertical_centered(|ui| {
-                                ui.label(
-                                    egui::RichText::new("Keine Songs gefunden.")
-                                        .size(16.0)
-                                        .color(palette::TEXT_MUTED),
-                                );
-                            });
-                            return;
+                self.update_scroll_memory();
+
+                {
+                    puffin::profile_scope!("Liste rendern");
+                    if self.view_mode == ViewMode::Grid {
+                        self.show_song_grid(ui, &mut action);
+                    } else if self.view_mode == ViewMode::Table {
+                        if let Some(column) = self.show_song_table(ui, &mut action) {
+                            self.toggle_table_sort(column);
                         }
-
-                        for song in &self.songs {
-                            egui::Frame::none()
-                                .fill(palette::BG_CARD)
-                                .inner_margin(egui::Margin::symmetric(14.0, 10.0))
-                                .rounding(8.0)
-                                .stroke(egui::Stroke::new(0.5, palette::BORDER_SUBTLE))
-                                .show(ui, |ui: &mut egui::Ui| {
-                                    // Title row
-                                    ui.horizontal(|ui: &mut egui::Ui| {
-                                        ui.label(
-                                            egui::RichText::new(&song.titel)
-                                                .size(16.0)
-                                                .strong()
-                                                .color(palette::TEXT_PRIMARY),
-                                        );
-                                        if let Some(ref artist) = song.artist {
-                                            ui.label(
-                                                egui::RichText::new(format!("\u{2014} {artist}"))
-                                                    .size(14.5)
-                                                    .color(palette::TEXT_SECONDARY),
-                                            );
-                                        }
-                                        if song.has_audio {
-                                            ui.with_layout(
-                                                egui::Layout::right_to_left(egui::Align::Center),
-                                                |ui: &mut egui::Ui| {
-                                                    let is_playing = self.audio_playing_song_id == Some(song.id);
-                                                    let (label, bg_color) = if is_playing {
-                                                        ("\u{25A0} Stop", palette::ACCENT_RED)
-                                                    } else {
-                                                        ("\u{25B6} Audio", palette::TAG_STIMMUNG)
-                                                    };
-                                                    let btn = egui::Button::new(
-                                                        egui::RichText::new(label)
-                                                            .size(11.5)
-                                                            .color(egui::Color32::WHITE),
-                                                    )
-                                                    .fill(bg_color)
-                                                    .rounding(4.0);
-                                                    if ui.add(btn).clicked() {
-                                                        if let Some(ref ap) = song.audio_pfad {
-                                                            action = Some(SongAction::ToggleAudio {
-                                                                song_id: song.id,
-                                                                audio_pfad: ap.clone(),
-                                                            });
-                                                        }
-                                                    }
-                                                },
-                                            );
-                                        }
-                                    });
-
-                                    // Tags
-                                    if !song.tags.is_empty() {
-                                        ui.add_space(2.0);
-                                        ui.horizontal_wrapped(|ui: &mut egui::Ui| {
-                                            ui.spacing_mut().item_spacing = egui::vec2(4.0, 4.0);
-                                            for tag in &song.tags {
-                                                let color = tag_color(&tag.kategorie);
-                                                let text = egui::RichText::new(&tag.wert)
-                                                    .size(11.5)
-                                                    .color(egui::Color32::WHITE);
-
-                                                let button = egui::Button::new(text)
-                                                    .fill(color)
-                                                    .rounding(10.0)
-                                                    .stroke(egui::Stroke::NONE);
-
-                                                let resp = ui.add(button);
-                                                if resp.clicked() {
-                                                    action =
-                                                        Some(SongAction::ConfirmRemoveTag {
-                                                            song_id: song.id,
-                                                            tag_id: tag.id,
-                                                            tag_wert: tag.wert.clone(),
-                                                        });
-                                                }
-                                                if resp.hovered() {
-                                                    resp.on_hover_text(if tag.auto_generated {
-                                                        "Automatisch \u{2014} Klick zum Entfernen"
-                                                    } else {
-                                                        "Manuell \u{2014} Klick zum Entfernen"
-                                                    });
-                                                }
-                                            }
-                                        });
-                                    }
-
-                                    ui.add_space(2.0);
-
-                                    // Path + actions
-                                    ui.horizontal(|ui: &mut egui::Ui| {
-                                        ui.label(
-                                            egui::RichText::new(&song.dateipfad)
-                                                .size(11.5)
-                                                .color(palette::TEXT_MUTED),
-                                        );
-                                        ui.with_layout(
-                                            egui::Layout::right_to_left(egui::Align::Center),
-                                            |ui: &mut egui::Ui| {
-                                                ui.spacing_mut().item_spacing.x = 4.0;
-                                                if styled_small_button(ui, "Datei \u{00F6}ffnen")
-                                                    .clicked()
-                                                {
-                                                    action = Some(SongAction::OpenFile(
-                                                        song.dateipfad.clone(),
-                                                    ));
-                                                }
-                                                if styled_small_button(ui, "Bearbeiten").clicked()
-                                                {
-                                                    action = Some(SongAction::Edit {
-                                                        song_id: song.id,
-                                                        titel: song.titel.clone(),
-                                                        artist: song
-                                                            .artist
-                                                            .clone()
-                                                            .unwrap_or_default(),
-                                                    });
-                                                }
-                                                if styled_small_button(ui, "+ Tag").clicked() {
-                                                    action = Some(SongAction::OpenTagModal {
-                                                        song_id: song.id,
-                                                        song_titel: song.titel.clone(),
-                                                    });
-                                                }
-                                            },
-                                        );
-                                    });
-                                });
-                            ui.add_space(3.0);
+                    } else {
+                        if let Some(letter) = self.show_song_list(ui, &mut action) {
+                            self.jump_to_letter = Some(letter);
+                            ui.ctx().request_repaint();
+                        } else {
+                            self.jump_to_letter = None;
                         }
-                    });
+                    }
+                }
+                self.scroll_to_selected = false;
 
                 // Process actions
                 if let Some(act) = action {
                     match act {
-                        SongAction::OpenFile(rel_path) => {
-                            let full_path = self.base_dir.join(&rel_path);
-                            let _ = std::process::Command::new("open")
-                                .arg(&full_path)
-                                .spawn();
+                        SongAction::Select(song_id) => {
+                            self.selected_song_id = Some(song_id);
                         }
+                        SongAction::Open(song_id) => self.open_song(song_id),
+                        SongAction::OpenFile(rel_path) => self.open_song_file(&rel_path),
                         SongAction::OpenTagModal {
                             song_id,
                             song_titel,
                         } => {
+                            let quick_picks = pending::Loaded::new(
+                                self.db.request(|conn| quick_pick_tags(conn, QUICK_PICKS)),
+                            );
                             self.tag_modal = Some(TagModalState {
                                 song_id,
                                 song_titel,
                                 kategorie_idx: 0,
                                 wert: String::new(),
+                                quick_picks,
                             });
                         }
-                        SongAction::Edit {
-                            song_id,
-                            titel,
-                            artist,
-                        } => {
-                            self.edit_modal = Some(EditModalState {
-                                song_id,
-                                titel,
-                                artist,
-                            });
+                        SongAction::Edit(song_id) => {
+                            if let Some(song) = self.songs.iter().find(|s| s.id == song_id) {
+                                self.edit_modal = Some(EditModalState::from_song(song));
+                            }
                         }
                         SongAction::ConfirmRemoveTag {
                             song_id,
@@ -901,12 +1796,39 @@ impl eframe::App for SongIndexApp {
                             song_id,
                             audio_pfad,
                         } => {
-                            if self.audio_playing_song_id == Some(song_id) {
+                            if self.playing_song_id() == Some(song_id) {
                                 self.stop_audio();
                             } else {
                                 self.play_audio(song_id, &audio_pfad);
                             }
                         }
+                        SongAction::OpenCompanion { song_id, file } => {
+                            self.open_companion(song_id, &file)
+                        }
+                        SongAction::SetRating { song_id, bewertung } => {
+                            self.db
+                                .send(move |conn| set_rating(conn, song_id, bewertung));
+                            self.refresh_song(song_id);
+                        }
+                        SongAction::ToggleFavorite { song_id, favorit } => {
+                            self.db.send(move |conn| set_favorite(conn, song_id, favorit));
+                            self.refresh_song(song_id);
+                        }
+                        SongAction::OpenUrl(url) => crate::open_with::open_url(&url),
+                        SongAction::Reveal(rel_path) => {
+                            crate::open_with::reveal(&self.base_dir.join(rel_path))
+                        }
+                        SongAction::TogglePractice { song_id, titel } => {
+                            if self
+                                .practice_timer
+                                .as_ref()
+                                .is_some_and(|t| t.song_id == song_id)
+                            {
+                                self.stop_practice();
+                            } else {
+                                self.start_practice(song_id, titel);
+                            }
+                        }
                     }
                 }
             });
@@ -915,18 +1837,18 @@ impl eframe::App for SongIndexApp {
         if self.show_settings {
             let mut open = true;
             egui::Window::new(
-                egui::RichText::new("Einstellungen")
+                egui::RichText::new(tr("Einstellungen"))
                     .size(16.0)
                     .color(palette::TEXT_PRIMARY),
             )
             .open(&mut open)
             .collapsible(false)
             .resizable(false)
-            .fixed_size([450.0, 120.0])
+            .fixed_size([450.0, 280.0])
             .show(ctx, |ui| {
                 ui.horizontal(|ui| {
                     ui.label(
-                        egui::RichText::new("Musikordner:")
+                        egui::RichText::new(tr("Musikordner:"))
                             .color(palette::TEXT_SECONDARY),
                     );
                     ui.label(
@@ -936,50 +1858,415 @@ impl eframe::App for SongIndexApp {
                     );
                 });
                 ui.add_space(8.0);
-                if ui.button("Ordner \u{00E4}ndern").clicked() {
+                if ui.button(tr("Ordner \u{00E4}ndern")).clicked() {
                     if let Some(new_dir) = rfd::FileDialog::new()
-                        .set_title("Musikordner ausw\u{00E4}hlen")
+                        .set_title(tr("Musikordner ausw\u{00E4}hlen"))
                         .set_directory(&self.base_dir)
                         .pick_folder()
                     {
-                        save_config(&Config {
-                            music_dir: new_dir.clone(),
-                        });
+                        self.config.music_dir = new_dir.clone();
+                        save_config(&self.config);
                         self.base_dir = new_dir;
-                        let conn = self.db.lock().unwrap();
-                        crate::scanner::scan_directory(&conn, &self.base_dir);
-                        drop(conn);
-                        self.refresh_data();
+                        self.thumbnails = None;
+                        self.rescan();
+                    }
+                }
+                ui.add_space(8.0);
+                if ui
+                    .checkbox(
+                        &mut self.config.audio_entries,
+                        tr("Audiodateien ohne Chart als eigene Eintr\u{00E4}ge"),
+                    )
+                    .on_hover_text(tr(
+                        "Backing-Tracks, die zu keinem Song geh\u{00F6}ren, erscheinen \
+                         mit Typ \u{201E}Audio\u{201C} in der Liste.",
+                    ))
+                    .changed()
+                {
+                    save_config(&self.config);
+                    *self.scan_options.write().unwrap() = ScanOptions::from(&self.config);
+                    self.rescan();
+                }
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(tr("Automatisch neu scannen:"))
+                            .color(palette::TEXT_SECONDARY),
+                    );
+                    let before = self.config.rescan;
+                    let label = match self.config.rescan {
+                        RescanSchedule::Aus => "Nie",
+                        RescanSchedule::Intervall { .. } => "Alle N Stunden",
+                        RescanSchedule::Nachts { .. } => "T\u{00E4}glich ab",
+                    };
+                    egui::ComboBox::from_id_salt("rescan_schedule")
+                        .selected_text(tr(label))
+                        .show_ui(ui, |ui| {
+                            let rescan = &mut self.config.rescan;
+                            ui.selectable_value(rescan, RescanSchedule::Aus, tr("Nie"));
+                            if ui
+                                .selectable_label(
+                                    matches!(rescan, RescanSchedule::Intervall { .. }),
+                                    tr("Alle N Stunden"),
+                                )
+                                .clicked()
+                            {
+                                *rescan = RescanSchedule::Intervall { stunden: 24 };
+                            }
+                            if ui
+                                .selectable_label(
+                                    matches!(rescan, RescanSchedule::Nachts { .. }),
+                                    tr("T\u{00E4}glich ab"),
+                                )
+                                .clicked()
+                            {
+                                *rescan = RescanSchedule::Nachts { stunde: 3 };
+                            }
+                        });
+                    match self.config.rescan {
+                        RescanSchedule::Aus => {}
+                        RescanSchedule::Intervall { ref mut stunden } => {
+                            ui.add(egui::DragValue::new(stunden).range(1..=168).suffix(" h"));
+                        }
+                        RescanSchedule::Nachts { ref mut stunde } => {
+                            ui.add(
+                                egui::DragValue::new(stunde)
+                                    .range(0..=23)
+                                    .suffix(tr(" Uhr")),
+                            );
+                        }
+                    }
+                    if self.config.rescan != before {
+                        save_config(&self.config);
                     }
+                })
+                .response
+                .on_hover_text(tr(
+                    "F\u{00E4}ngt \u{00C4}nderungen auf, die der Datei-W\u{00E4}chter \
+                     w\u{00E4}hrend des Ruhezustands verpasst hat.",
+                ));
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(tr("Sprache:")).color(palette::TEXT_SECONDARY));
+                    let before = self.config.language;
+                    egui::ComboBox::from_id_salt("language")
+                        .selected_text(self.config.language.label())
+                        .show_ui(ui, |ui| {
+                            for language in Language::ALL {
+                                ui.selectable_value(
+                                    &mut self.config.language,
+                                    language,
+                                    language.label(),
+                                );
+                            }
+                        });
+                    if self.config.language != before {
+                        save_config(&self.config);
+                        crate::i18n::set_language(self.config.language);
+                    }
+                });
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(tr("Datum und Zahlen:"))
+                            .color(palette::TEXT_SECONDARY),
+                    );
+                    let before = self.config.locale;
+                    egui::ComboBox::from_id_salt("locale")
+                        .selected_text(self.config.locale.label())
+                        .show_ui(ui, |ui| {
+                            for locale in Locale::ALL {
+                                ui.selectable_value(
+                                    &mut self.config.locale,
+                                    locale,
+                                    locale.label(),
+                                );
+                            }
+                        });
+                    if self.config.locale != before {
+                        save_config(&self.config);
+                        // Reloaded with the tags at the start of the next frame.
+                        self.meta_stale = true;
+                    }
+                });
+                ui.add_space(8.0);
+                ui.label(
+                    egui::RichText::new(tr("Doppelklick und Enter:"))
+                        .color(palette::TEXT_SECONDARY),
+                );
+                let before = self.config.open_actions;
+                egui::Grid::new("open_actions").show(ui, |ui| {
+                    let actions = &mut self.config.open_actions;
+                    for (view, action) in [
+                        (tr("Liste"), &mut actions.liste),
+                        (tr("Tabelle"), &mut actions.tabelle),
+                        (tr("Raster"), &mut actions.raster),
+                    ] {
+                        ui.label(egui::RichText::new(view).color(palette::TEXT_MUTED));
+                        egui::ComboBox::from_id_salt(("open_action", view))
+                            .selected_text(tr(action.label()))
+                            .show_ui(ui, |ui| {
+                                for option in OpenAction::ALL {
+                                    ui.selectable_value(action, option, tr(option.label()));
+                                }
+                            });
+                        ui.end_row();
+                    }
+                });
+                if self.config.open_actions != before {
+                    save_config(&self.config);
                 }
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(tr("Speicher f\u{00FC}r Vorschaubilder:"))
+                            .color(palette::TEXT_SECONDARY),
+                    );
+                    let thumbs = ui.add(
+                        egui::DragValue::new(&mut self.config.thumbnail_cache_mb)
+                            .range(16..=2048)
+                            .suffix(" MB"),
+                    );
+                    ui.label(
+                        egui::RichText::new("Charts:").color(palette::TEXT_SECONDARY),
+                    );
+                    let charts = ui.add(
+                        egui::DragValue::new(&mut self.config.chart_cache_mb)
+                            .range(1..=512)
+                            .suffix(" MB"),
+                    );
+                    if thumbs.changed() {
+                        if let Some(ref mut cache) = self.thumbnails {
+                            cache.set_limit(self.config.thumbnail_cache_bytes());
+                        }
+                    }
+                    if charts.changed() {
+                        self.chart_cache.set_limit(self.config.chart_cache_bytes());
+                    }
+                    if thumbs.changed() || charts.changed() {
+                        save_config(&self.config);
+                    }
+                });
+                ui.add_space(8.0);
+                ui.separator();
+                ui.add_space(4.0);
+                ui.horizontal_wrapped(|ui| {
+                    if ui.button(tr("Tags verwalten\u{2026}")).clicked() {
+                        self.tag_manager = Some(Default::default());
+                    }
+                    if ui.button(tr("CSV importieren\u{2026}")).clicked() {
+                        self.open_csv_import();
+                    }
+                    if ui.button(tr("Zeitmaschine\u{2026}")).clicked() {
+                        self.open_snapshots();
+                    }
+                    if ui.button(tr("Cloud-Backup\u{2026}")).clicked() {
+                        self.open_backup_settings();
+                    }
+                    if ui.button(tr("\u{00D6}ffentliche Seite\u{2026}")).clicked() {
+                        self.open_website();
+                    }
+                    if ui.button("Obsidian\u{2026}").clicked() {
+                        self.open_obsidian();
+                    }
+                    if ui.button(tr("Im WLAN freigeben\u{2026}")).clicked() {
+                        self.open_lan();
+                    }
+                    if ui.button(tr("\u{00D6}ffnen mit\u{2026}")).clicked() {
+                        self.open_open_with();
+                    }
+                    if ui.button(tr("Papierkorb\u{2026}")).clicked() {
+                        self.open_trash();
+                    }
+                    if ui.button(tr("Entfernte Songs\u{2026}")).clicked() {
+                        self.open_removal_log();
+                    }
+                    if ui.button(tr("Ignoriert\u{2026}")).clicked() {
+                        self.open_ignored();
+                    }
+                    if ui.button(tr("Ausschl\u{00FC}sse\u{2026}")).clicked() {
+                        self.open_ignore_patterns();
+                    }
+                    if ui.button(tr("Auto-Tag-Regeln\u{2026}")).clicked() {
+                        self.open_auto_tag_rules();
+                    }
+                    if ui.button(tr("Artist-Ordner\u{2026}")).clicked() {
+                        self.open_artist_folders();
+                    }
+                    if ui.button(tr("Zusammengeh\u{00F6}rige Dateien\u{2026}")).clicked() {
+                        self.open_companion_groups();
+                    }
+                    if ui.button(tr("Farben\u{2026}")).clicked() {
+                        self.open_theme();
+                    }
+                    if ui.button(tr("Speicherort\u{2026}")).clicked() {
+                        self.open_storage();
+                    }
+                    if ui.button(tr("Scan-Vorschau\u{2026}")).clicked() {
+                        self.open_scan_preview();
+                    }
+                    if ui.button(tr("Wartung\u{2026}")).clicked() {
+                        self.open_maintenance();
+                    }
+                    if ui.button(tr("Protokoll anzeigen\u{2026}")).clicked() {
+                        self.open_log_viewer();
+                    }
+                    #[cfg(feature = "encryption")]
+                    if ui.button(tr("Verschl\u{00FC}sselung\u{2026}")).clicked() {
+                        self.open_encryption();
+                    }
+                });
             });
             if !open {
                 self.show_settings = false;
             }
         }
 
+        self.show_import_modal(ctx);
+        self.show_new_chart_modal(ctx);
+        self.show_clipboard_modal(ctx);
+        self.show_tag_manager(ctx);
+        self.show_difficulty(ctx);
+        self.show_snapshot_window(ctx);
+        self.show_backup_window(ctx);
+        self.show_session_window(ctx);
+        self.show_warmup_window(ctx);
+        self.show_metronome(ctx);
+        self.show_review_window(ctx);
+        self.show_next_songs(ctx);
+        self.show_stats_window(ctx);
+        self.show_coverage_window(ctx);
+        self.show_tag_packs(ctx);
+        self.show_chart_viewer(ctx);
+        self.show_image_viewer(ctx);
+        self.show_html_export(ctx);
+        self.show_pdf_export(ctx);
+        self.show_triage(ctx);
+        self.show_trash_window(ctx);
+        self.show_removal_log(ctx);
+        self.show_ignored_window(ctx);
+        self.show_ignore_patterns(ctx);
+        self.show_auto_tag_rules(ctx);
+        self.show_artist_folders(ctx);
+        self.show_companion_groups(ctx);
+        self.show_large_library(ctx);
+        self.show_website_window(ctx);
+        self.show_obsidian_window(ctx);
+        self.show_lan_window(ctx);
+        self.show_open_with_window(ctx);
+        self.show_musicbrainz(ctx);
+        self.show_theme(ctx);
+        self.show_storage(ctx);
+        self.show_maintenance(ctx);
+        self.show_scan_report(ctx);
+        self.show_scan_preview(ctx);
+        self.show_log_viewer(ctx);
+        self.show_smart_list_editor(ctx);
+        self.show_wake_notice(ctx);
+        self.show_toasts(ctx);
+        // Before the cache overlay, which would also take Shift+F12 as F12.
+        self.show_profiler(ctx);
+        self.show_cache_overlay(ctx);
+        #[cfg(feature = "encryption")]
+        self.show_encryption_modal(ctx);
+
         // ── Tag modal ──
         let mut close_tag_modal = false;
         if let Some(ref mut modal) = self.tag_modal {
             let mut open = true;
             egui::Window::new(
-                egui::RichText::new(format!("Tag hinzuf\u{00FC}gen \u{2014} {}", modal.song_titel))
-                    .size(15.0)
-                    .color(palette::TEXT_PRIMARY),
+                egui::RichText::new(format!(
+                    "{} \u{2014} {}",
+                    tr("Tag hinzuf\u{00FC}gen"),
+                    modal.song_titel
+                ))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
             )
             .open(&mut open)
             .collapsible(false)
             .resizable(false)
-            .fixed_size([380.0, 320.0])
+            .fixed_size([380.0, 390.0])
             .show(ctx, |ui| {
                 let categories =
                     ["instrument", "schwierigkeit", "stil", "technik", "stimmung", "kapo"];
-                let labels =
-                    ["Instrument", "Schwierigkeit", "Stil", "Technik", "Stimmung", "Kapo"];
+                let labels = [
+                    "Instrument",
+                    tr("Schwierigkeit"),
+                    tr("Stil"),
+                    tr("Technik"),
+                    tr("Stimmung"),
+                    tr("Kapo"),
+                ];
+
+                let song_tag_ids: HashSet<i64> = self
+                    .songs
+                    .iter()
+                    .find(|s| s.id == modal.song_id)
+                    .map(|s| s.tags.iter().map(|t| t.id).collect())
+                    .unwrap_or_default();
+                // Until the quick picks are loaded the modal shows none.
+                let (all_recent, all_frequent) = match modal.quick_picks.get() {
+                    Some((recent, frequent)) => (&recent[..], &frequent[..]),
+                    None => (&[][..], &[][..]),
+                };
+                let recent: Vec<_> = all_recent
+                    .iter()
+                    .filter(|t| !song_tag_ids.contains(&t.id))
+                    .collect();
+                let frequent: Vec<_> = all_frequent
+                    .iter()
+                    .filter(|t| {
+                        !song_tag_ids.contains(&t.id) && !recent.iter().any(|r| r.id == t.id)
+                    })
+                    .collect();
+                let mut picked = None;
+                for (label, picks) in [(tr("Zuletzt:"), &recent), (tr("H\u{00E4}ufig:"), &frequent)]
+                {
+                    if picks.is_empty() {
+                        continue;
+                    }
+                    ui.horizontal_wrapped(|ui| {
+                        ui.spacing_mut().item_spacing = egui::vec2(4.0, 4.0);
+                        ui.label(
+                            egui::RichText::new(label)
+                                .size(12.5)
+                                .color(palette::TEXT_MUTED),
+                        );
+                        for tag in picks.iter() {
+                            let btn = egui::Button::new(
+                                egui::RichText::new(&tag.wert)
+                                    .size(11.5)
+                                    .color(egui::Color32::WHITE),
+                            )
+                            .fill(tag_color(&tag.kategorie))
+                            .rounding(10.0)
+                            .stroke(egui::Stroke::NONE);
+                            if ui
+                                .add(btn)
+                                .on_hover_text(category_label(&tag.kategorie))
+                                .clicked()
+                            {
+                                picked = Some((tag.kategorie.clone(), tag.wert.clone()));
+                            }
+                        }
+                    });
+                }
+                if let Some((kategorie, wert)) = picked {
+                    let song_id = modal.song_id;
+                    self.db
+                        .send(move |conn| apply_tag(conn, song_id, &kategorie, &wert));
+                    self.needs_refresh = true;
+                    close_tag_modal = true;
+                }
+                if !recent.is_empty() || !frequent.is_empty() {
+                    ui.add_space(4.0);
+                    ui.separator();
+                }
 
                 ui.horizontal(|ui| {
                     ui.label(
-                        egui::RichText::new("Kategorie:")
+                        egui::RichText::new(tr("Kategorie:"))
                             .color(palette::TEXT_SECONDARY),
                     );
                     egui::ComboBox::from_id_salt("tag_kategorie")
@@ -995,45 +2282,40 @@ impl eframe::App for SongIndexApp {
 
                 ui.horizontal(|ui| {
                     ui.label(
-                        egui::RichText::new("Wert:")
+                        egui::RichText::new(tr("Wert:"))
                             .color(palette::TEXT_SECONDARY),
                     );
                     let response = ui.text_edit_singleline(&mut modal.wert);
                     if response.lost_focus()
                         && ui.input(|i| i.key_pressed(egui::Key::Enter))
+                        && !modal.wert.trim().is_empty()
                     {
-                        if !modal.wert.trim().is_empty() {
-                            let conn = self.db.lock().unwrap();
-                            add_tag_to_song(
-                                &conn,
-                                modal.song_id,
-                                categories[modal.kategorie_idx],
-                                modal.wert.trim(),
-                            );
-                            drop(conn);
-                            self.needs_refresh = true;
-                            close_tag_modal = true;
-                        }
+                        send_tag(
+                            &self.db,
+                            modal.song_id,
+                            categories[modal.kategorie_idx],
+                            modal.wert.trim(),
+                        );
+                        self.needs_refresh = true;
+                        close_tag_modal = true;
                     }
                 });
 
                 ui.add_space(4.0);
 
                 let add_btn = egui::Button::new(
-                    egui::RichText::new("Hinzuf\u{00FC}gen")
+                    egui::RichText::new(tr("Hinzuf\u{00FC}gen"))
                         .color(palette::TEXT_PRIMARY),
                 )
-                .fill(palette::ACCENT_DIM)
+                .fill(palette::accent_dim())
                 .rounding(6.0);
                 if ui.add(add_btn).clicked() && !modal.wert.trim().is_empty() {
-                    let conn = self.db.lock().unwrap();
-                    add_tag_to_song(
-                        &conn,
+                    send_tag(
+                        &self.db,
                         modal.song_id,
                         categories[modal.kategorie_idx],
                         modal.wert.trim(),
                     );
-                    drop(conn);
                     self.needs_refresh = true;
                     close_tag_modal = true;
                 }
@@ -1042,20 +2324,12 @@ impl eframe::App for SongIndexApp {
                 ui.separator();
                 ui.add_space(2.0);
                 ui.label(
-                    egui::RichText::new("Vorhandene Tags:")
+                    egui::RichText::new(tr("Vorhandene Tags:"))
                         .size(12.5)
                         .color(palette::TEXT_MUTED),
                 );
                 ui.add_space(4.0);
 
-                let song_tag_ids: HashSet<i64> = {
-                    if let Some(song) = self.songs.iter().find(|s| s.id == modal.song_id) {
-                        song.tags.iter().map(|t| t.id).collect()
-                    } else {
-                        HashSet::new()
-                    }
-                };
-
                 egui::ScrollArea::vertical()
                     .max_height(180.0)
                     .show(ui, |ui| {
@@ -1078,14 +2352,12 @@ impl eframe::App for SongIndexApp {
                                     .stroke(egui::Stroke::NONE);
 
                                     if ui.add(btn).clicked() {
-                                        let conn = self.db.lock().unwrap();
-                                        add_tag_to_song(
-                                            &conn,
+                                        send_tag(
+                                            &self.db,
                                             modal.song_id,
                                             &group.kategorie,
                                             &tag.wert,
                                         );
-                                        drop(conn);
                                         added = true;
                                     }
                                 }
@@ -1103,10 +2375,11 @@ impl eframe::App for SongIndexApp {
             }
         }
         if close_tag_modal {
-            self.tag_modal = None;
-            if self.needs_refresh {
-                self.refresh_data();
-                self.needs_refresh = false;
+            if let Some(modal) = self.tag_modal.take() {
+                if self.needs_refresh {
+                    self.needs_refresh = false;
+                    self.refresh_song(modal.song_id);
+                }
             }
         }
 
@@ -1116,18 +2389,18 @@ impl eframe::App for SongIndexApp {
         if let Some(ref mut modal) = self.edit_modal {
             let mut open = true;
             egui::Window::new(
-                egui::RichText::new("Song bearbeiten")
+                egui::RichText::new(tr("Song bearbeiten"))
                     .size(15.0)
                     .color(palette::TEXT_PRIMARY),
             )
             .open(&mut open)
             .collapsible(false)
             .resizable(false)
-            .fixed_size([380.0, 160.0])
+            .fixed_size([420.0, 470.0])
             .show(ctx, |ui| {
                 ui.horizontal(|ui| {
                     ui.label(
-                        egui::RichText::new("Titel:")
+                        egui::RichText::new(tr("Titel:"))
                             .color(palette::TEXT_SECONDARY),
                     );
                     ui.text_edit_singleline(&mut modal.titel);
@@ -1140,19 +2413,116 @@ impl eframe::App for SongIndexApp {
                     );
                     ui.text_edit_singleline(&mut modal.artist);
                 });
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(tr("Tonart:"))
+                            .color(palette::TEXT_SECONDARY),
+                    );
+                    ui.add(egui::TextEdit::singleline(&mut modal.tonart).desired_width(50.0));
+                    ui.add_space(8.0);
+                    ui.label(
+                        egui::RichText::new("BPM:")
+                            .color(palette::TEXT_SECONDARY),
+                    );
+                    ui.add(egui::TextEdit::singleline(&mut modal.bpm).desired_width(50.0));
+                    ui.add_space(8.0);
+                    ui.label(
+                        egui::RichText::new(tr("Kapo:"))
+                            .color(palette::TEXT_SECONDARY),
+                    );
+                    ui.add(egui::TextEdit::singleline(&mut modal.kapo).desired_width(40.0));
+                    ui.add_space(8.0);
+                    ui.label(
+                        egui::RichText::new(tr("Jahr:"))
+                            .color(palette::TEXT_SECONDARY),
+                    );
+                    ui.add(egui::TextEdit::singleline(&mut modal.jahr).desired_width(50.0));
+                });
+                ui.add_space(4.0);
+                let field = ui
+                    .horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new("Tags:")
+                                .color(palette::TEXT_SECONDARY),
+                        );
+                        ui.add(
+                            egui::TextEdit::singleline(&mut modal.hashtags)
+                                .desired_width(f32::INFINITY)
+                                .hint_text(tr("#stil/Blues #kapo/2 #anf\u{00E4}nger")),
+                        )
+                    })
+                    .inner;
+                let completions = hashtags::completions(&modal.hashtags, &self.tags);
+                if !completions.is_empty() {
+                    ui.horizontal_wrapped(|ui| {
+                        ui.spacing_mut().item_spacing = egui::vec2(4.0, 4.0);
+                        for completion in &completions {
+                            if styled_small_button(ui, completion).clicked() {
+                                modal.hashtags = hashtags::complete(&modal.hashtags, completion);
+                                let end = modal.hashtags.chars().count();
+                                if let Some(mut state) = egui::TextEdit::load_state(ctx, field.id) {
+                                    state.cursor.set_char_range(Some(egui::text::CCursorRange::one(
+                                        egui::text::CCursor::new(end),
+                                    )));
+                                    state.store(ctx, field.id);
+                                }
+                                field.request_focus();
+                            }
+                        }
+                    });
+                }
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(tr("\u{00D6}ffnen mit:"))
+                            .color(palette::TEXT_SECONDARY),
+                    );
+                    ui.add(
+                        egui::TextEdit::singleline(&mut modal.oeffnen_mit)
+                            .desired_width(220.0)
+                            .hint_text(tr("leer = nach Dateiendung")),
+                    );
+                    if ui.small_button(tr("W\u{00E4}hlen\u{2026}")).clicked() {
+                        if let Some(app) = crate::open_with::pick_app() {
+                            modal.oeffnen_mit = app;
+                        }
+                    }
+                });
+                ui.add_space(4.0);
+                ui.checkbox(&mut modal.kann_ich, tr("Kann ich (Repertoire)"));
+                ui.checkbox(
+                    &mut modal.privat,
+                    tr("Privat (nicht auf der \u{00F6}ffentlichen Seite)"),
+                );
+                ui.add_space(6.0);
+                ui.label(
+                    egui::RichText::new(tr("Notizen (Markdown):"))
+                        .color(palette::TEXT_SECONDARY),
+                );
+                egui::ScrollArea::vertical()
+                    .max_height(150.0)
+                    .show(ui, |ui| {
+                        ui.add(
+                            egui::TextEdit::multiline(&mut modal.notizen)
+                                .desired_rows(6)
+                                .desired_width(f32::INFINITY)
+                                .hint_text(tr("z.B. ## Knifflige Takte\n- T. 12: Barr\u{00E9} vorbereiten")),
+                        );
+                    });
                 ui.add_space(10.0);
                 ui.horizontal(|ui| {
                     let save_btn = egui::Button::new(
-                        egui::RichText::new("Speichern")
+                        egui::RichText::new(tr("Speichern"))
                             .color(palette::TEXT_PRIMARY),
                     )
-                    .fill(palette::ACCENT_DIM)
+                    .fill(palette::accent_dim())
                     .rounding(6.0);
                     if ui.add(save_btn).clicked() {
                         save_edit = true;
                     }
                     ui.add_space(4.0);
-                    if ui.button("Abbrechen").clicked() {
+                    if ui.button(tr("Abbrechen")).clicked() {
                         close_edit_modal = true;
                     }
                 });
@@ -1163,11 +2533,46 @@ impl eframe::App for SongIndexApp {
             }
         }
         if save_edit {
-            if let Some(modal) = &self.edit_modal {
-                let conn = self.db.lock().unwrap();
-                update_song(&conn, modal.song_id, &modal.titel, &modal.artist);
-                drop(conn);
-                self.refresh_data();
+            if let Some(modal) = self.edit_modal.take() {
+                let mut tonart = modal.tonart.trim().to_string();
+                let mut bpm = modal.bpm.trim().to_string();
+                let mut kapo = modal.kapo.trim().to_string();
+                let mut tags = Vec::new();
+                // Key, tempo and capo are columns, not tags.
+                for hashtag in hashtags::parse(&modal.hashtags) {
+                    match hashtag.kategorie.as_deref() {
+                        Some("tonart") => tonart = hashtag.wert,
+                        Some("bpm") => bpm = hashtag.wert,
+                        Some("kapo") => kapo = hashtag.wert,
+                        _ => tags.push(hashtags::resolve(&self.tags, &hashtag)),
+                    }
+                }
+                let song_id = modal.song_id;
+                let review_interval_days = self.config.review_interval_days;
+                self.db.send(move |conn| {
+                    update_song(conn, song_id, &modal.titel, &modal.artist);
+                    update_song_notes(conn, song_id, &modal.notizen);
+                    for (kategorie, wert) in tags {
+                        apply_tag(conn, song_id, &kategorie, &wert);
+                    }
+                    update_song_music(
+                        conn,
+                        song_id,
+                        (!tonart.is_empty()).then_some(tonart.as_str()),
+                        bpm.parse().ok(),
+                        kapo.parse().ok(),
+                    );
+                    crate::repertoire::set_repertoire(
+                        conn,
+                        song_id,
+                        modal.kann_ich,
+                        review_interval_days,
+                    );
+                    set_song_year(conn, song_id, modal.jahr.trim().parse().ok());
+                    set_private(conn, song_id, modal.privat);
+                    set_song_open_with(conn, song_id, &modal.oeffnen_mit);
+                });
+                self.refresh_song(song_id);
             }
             self.edit_modal = None;
         } else if close_edit_modal {
@@ -1180,7 +2585,7 @@ impl eframe::App for SongIndexApp {
         if let Some(ref confirm) = self.confirm_remove {
             let mut open = true;
             egui::Window::new(
-                egui::RichText::new("Tag entfernen?")
+                egui::RichText::new(tr("Tag entfernen?"))
                     .size(15.0)
                     .color(palette::ACCENT_RED),
             )
@@ -1190,16 +2595,16 @@ impl eframe::App for SongIndexApp {
             .fixed_size([320.0, 90.0])
             .show(ctx, |ui| {
                 ui.label(
-                    egui::RichText::new(format!(
+                    egui::RichText::new(tr_arg(
                         "Tag \u{201E}{}\u{201C} entfernen?",
-                        confirm.tag_wert
+                        &confirm.tag_wert,
                     ))
                     .color(palette::TEXT_PRIMARY),
                 );
                 ui.add_space(10.0);
                 ui.horizontal(|ui| {
                     let remove_btn = egui::Button::new(
-                        egui::RichText::new("Entfernen")
+                        egui::RichText::new(tr("Entfernen"))
                             .color(egui::Color32::WHITE),
                     )
                     .fill(palette::ACCENT_RED)
@@ -1208,7 +2613,7 @@ impl eframe::App for SongIndexApp {
                         do_remove = true;
                     }
                     ui.add_space(4.0);
-                    if ui.button("Abbrechen").clicked() {
+                    if ui.button(tr("Abbrechen")).clicked() {
                         close_confirm = true;
                     }
                 });
@@ -1219,11 +2624,12 @@ impl eframe::App for SongIndexApp {
             }
         }
         if do_remove {
-            if let Some(confirm) = &self.confirm_remove {
-                let conn = self.db.lock().unwrap();
-                remove_tag_from_song(&conn, confirm.song_id, confirm.tag_id);
-                drop(conn);
-                self.refresh_data();
+            if let Some((song_id, tag_id)) =
+                self.confirm_remove.as_ref().map(|c| (c.song_id, c.tag_id))
+            {
+                self.db
+                    .send(move |conn| remove_tag_from_song(conn, song_id, tag_id));
+                self.refresh_song(song_id);
             }
             self.confirm_remove = None;
         } else if close_confirm {
@@ -1232,10 +2638,37 @@ impl eframe::App for SongIndexApp {
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
+enum ViewMode {
+    List,
+    Table,
+    Grid,
+}
+
+impl ViewMode {
+    fn label(&self) -> &'static str {
+        match self {
+            ViewMode::List => "\u{2630} Liste",
+            ViewMode::Table => "\u{25A6} Tabelle",
+            ViewMode::Grid => "\u{25A3} Raster",
+        }
+    }
+}
+
 enum SongAction {
+    Select(i64),
+    /// The view's configured double-click action (`Config::open_actions`).
+    Open(i64),
     OpenFile(String),
     OpenTagModal { song_id: i64, song_titel: String },
-    Edit { song_id: i64, titel: String, artist: String },
+    Edit(i64),
     ConfirmRemoveTag { song_id: i64, tag_id: i64, tag_wert: String },
     ToggleAudio { song_id: i64, audio_pfad: String },
+    OpenCompanion { song_id: i64, file: crate::companions::Companion },
+    TogglePractice { song_id: i64, titel: String },
+    SetRating { song_id: i64, bewertung: i64 },
+    ToggleFavorite { song_id: i64, favorit: bool },
+    OpenUrl(String),
+    /// Show the file (relative path) in the file manager.
+    Reveal(String),
 }
@@ -0,0 +1,255 @@
+use super::pending::{answer, Loaded};
+use super::{palette, styled_small_button, SongIndexApp};
+use crate::arrangements::{
+    add_arrangement, arrangements, remove_arrangement, suggestions, Arrangement,
+};
+use crate::db::{query_songs, ExtraFilters, SortMode, TagFilter};
+use crate::i18n::tr;
+use eframe::egui;
+use std::sync::mpsc::Receiver;
+
+/// Matches listed under the search field of the form.
+const MAX_MATCHES: usize = 8;
+
+/// The other arrangements of the selected song and the songs that look
+/// like further ones, reloaded with the list.
+pub(super) struct SongArrangements {
+    fassungen: Vec<Arrangement>,
+    vorschlaege: Vec<Arrangement>,
+}
+
+/// The "+ Fassung" search form in the detail panel.
+pub(super) struct ArrangementForm {
+    song_id: i64,
+    query: String,
+    searched: Option<String>,
+    matches: Loaded<Vec<(i64, String, Option<String>)>>,
+    /// Put the cursor in the search field on the next frame.
+    focus: bool,
+}
+
+/// Something clicked in the arrangements section.
+pub(super) enum ArrangementAction {
+    Select(i64),
+    NewForm,
+    Add(i64),
+    Detach,
+}
+
+fn arrangement_chip(ui: &mut egui::Ui, text: &str) -> egui::Response {
+    let chip = egui::Button::new(
+        egui::RichText::new(text)
+            .size(11.5)
+            .color(palette::TEXT_PRIMARY),
+    )
+    .fill(palette::bg_input())
+    .rounding(10.0)
+    .stroke(egui::Stroke::new(0.5, palette::border_subtle()));
+    ui.add(chip)
+}
+
+/// Chips for the arrangements of the selected song's piece, suggestions by
+/// title and the form to add one.
+pub(super) fn arrangements_section(
+    ui: &mut egui::Ui,
+    song_id: i64,
+    loaded: &SongArrangements,
+    form: &mut Option<ArrangementForm>,
+    action: &mut Option<ArrangementAction>,
+) {
+    ui.label(
+        egui::RichText::new(tr("Fassungen"))
+            .size(13.0)
+            .strong()
+            .color(palette::TEXT_SECONDARY),
+    );
+    ui.add_space(2.0);
+    if !loaded.fassungen.is_empty() {
+        ui.horizontal_wrapped(|ui| {
+            ui.spacing_mut().item_spacing = egui::vec2(4.0, 4.0);
+            for other in &loaded.fassungen {
+                if other.id == song_id {
+                    ui.label(
+                        egui::RichText::new(&other.titel)
+                            .size(11.5)
+                            .strong()
+                            .color(palette::accent()),
+                    );
+                } else if arrangement_chip(ui, &other.titel).clicked() {
+                    *action = Some(ArrangementAction::Select(other.id));
+                }
+            }
+        });
+    }
+    if !loaded.vorschlaege.is_empty() {
+        ui.horizontal_wrapped(|ui| {
+            ui.spacing_mut().item_spacing = egui::vec2(4.0, 4.0);
+            ui.label(
+                egui::RichText::new(tr("\u{00C4}hnlich:"))
+                    .size(11.5)
+                    .color(palette::TEXT_MUTED),
+            );
+            for other in &loaded.vorschlaege {
+                if arrangement_chip(ui, &format!("+ {}", other.titel))
+                    .on_hover_text(tr("Als Fassung dazunehmen"))
+                    .clicked()
+                {
+                    *action = Some(ArrangementAction::Add(other.id));
+                }
+            }
+        });
+    }
+
+    let mut cancel = false;
+    match form.as_mut().filter(|f| f.song_id == song_id) {
+        None => {
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing.x = 4.0;
+                if ui
+                    .small_button(tr("+ Fassung"))
+                    .on_hover_text(tr("Ein anderes Arrangement desselben Songs"))
+                    .clicked()
+                {
+                    *action = Some(ArrangementAction::NewForm);
+                }
+                if !loaded.fassungen.is_empty()
+                    && ui
+                        .small_button(tr("Aus Gruppe l\u{00F6}sen"))
+                        .on_hover_text(tr("Wieder als eigenst\u{00E4}ndiger Song f\u{00FC}hren"))
+                        .clicked()
+                {
+                    *action = Some(ArrangementAction::Detach);
+                }
+            });
+        }
+        Some(form) => {
+            let resp = ui.add(
+                egui::TextEdit::singleline(&mut form.query)
+                    .desired_width(f32::INFINITY)
+                    .hint_text(tr("Fassung: Song suchen\u{2026}")),
+            );
+            if std::mem::take(&mut form.focus) {
+                resp.request_focus();
+            }
+            for (id, match_titel, artist) in form.matches.get().map_or(&[][..], |m| &m[..]) {
+                let label = match artist {
+                    Some(artist) => format!("{match_titel} \u{2014} {artist}"),
+                    None => match_titel.clone(),
+                };
+                if ui
+                    .selectable_label(false, egui::RichText::new(label).size(12.5))
+                    .clicked()
+                {
+                    *action = Some(ArrangementAction::Add(*id));
+                }
+            }
+            if styled_small_button(ui, tr("Abbrechen")).clicked() {
+                cancel = true;
+            }
+        }
+    }
+    if cancel {
+        *form = None;
+    }
+}
+
+impl ArrangementForm {
+    pub(super) fn song_id(&self) -> i64 {
+        self.song_id
+    }
+}
+
+impl SongIndexApp {
+    /// Load the arrangements of the selected song into `song_arrangements`,
+    /// once per selection and after every change to the list.
+    pub(super) fn load_song_arrangements(&mut self, song_id: i64) {
+        if self
+            .song_arrangements
+            .as_ref()
+            .is_none_or(|(id, _)| *id != song_id)
+        {
+            let loaded = Loaded::new(self.request_song_arrangements(song_id));
+            self.song_arrangements = Some((song_id, loaded));
+        }
+    }
+
+    /// Reload `song_arrangements` after a change; the old ones stay up
+    /// until the new ones are there.
+    pub(super) fn reload_song_arrangements(&mut self) {
+        if let Some(song_id) = self.song_arrangements.as_ref().map(|(id, _)| *id) {
+            let rx = self.request_song_arrangements(song_id);
+            if let Some((_, loaded)) = self.song_arrangements.as_mut() {
+                loaded.reload(rx);
+            }
+        }
+    }
+
+    fn request_song_arrangements(&self, song_id: i64) -> Receiver<SongArrangements> {
+        self.db.request(move |conn| SongArrangements {
+            fassungen: arrangements(conn, song_id),
+            vorschlaege: suggestions(conn, song_id),
+        })
+    }
+
+    /// Search the form's matches when its text changed. Also expands the
+    /// piece an arrangement was just added to.
+    pub(super) fn update_arrangement_form(&mut self) {
+        if let Some(Some(werk_id)) = answer(&mut self.adding_arrangement) {
+            self.expanded_works.insert(werk_id);
+        }
+        let Some(ref mut form) = self.arrangement_form else {
+            return;
+        };
+        if form.searched.as_deref() == Some(form.query.as_str()) {
+            return;
+        }
+        let query = form.query.trim().to_string();
+        if query.is_empty() {
+            form.matches = Loaded::default();
+        } else {
+            let song_id = form.song_id;
+            form.matches.reload(self.db.request(move |conn| {
+                query_songs(
+                    conn,
+                    &query,
+                    &TagFilter::none(),
+                    &ExtraFilters::default(),
+                    &SortMode::Title,
+                )
+                .into_iter()
+                .filter(|s| s.id != song_id)
+                .take(MAX_MATCHES)
+                .map(|s| (s.id, s.titel, s.artist))
+                .collect()
+            }));
+        }
+        form.searched = Some(form.query.clone());
+    }
+
+    pub(super) fn apply_arrangement_action(&mut self, song_id: i64, action: ArrangementAction) {
+        match action {
+            ArrangementAction::Select(id) => self.show_in_list(id),
+            ArrangementAction::NewForm => {
+                self.arrangement_form = Some(ArrangementForm {
+                    song_id,
+                    query: String::new(),
+                    searched: None,
+                    matches: Loaded::default(),
+                    focus: true,
+                });
+            }
+            ArrangementAction::Add(other) => {
+                self.adding_arrangement = Some(
+                    self.db
+                        .request(move |conn| add_arrangement(conn, song_id, other)),
+                );
+                self.arrangement_form = None;
+                self.refresh_data();
+            }
+            ArrangementAction::Detach => {
+                self.db.send(move |conn| remove_arrangement(conn, song_id));
+                self.refresh_data();
+            }
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use super::pending::answer;
+use super::{palette, SongIndexApp};
+use crate::artist_folders::{self, ArtistSuggestion};
+use crate::i18n::{tr, tr_arg};
+use eframe::egui;
+use std::sync::mpsc::Receiver;
+
+pub(super) struct ArtistFoldersState {
+    suggestions: Vec<ArtistSuggestion>,
+    /// Per suggestion: whether to apply it.
+    selected: Vec<bool>,
+    /// The suggestions, until they are loaded.
+    loading: Option<Receiver<Vec<ArtistSuggestion>>>,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_artist_folders(&mut self) {
+        let rules = self.config.auto_tag_rules.clone();
+        let loading = self
+            .db
+            .request(move |conn| artist_folders::suggest(conn, &rules));
+        self.artist_folders = Some(ArtistFoldersState {
+            suggestions: Vec::new(),
+            selected: Vec::new(),
+            loading: Some(loading),
+        });
+    }
+
+    pub(super) fn show_artist_folders(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.artist_folders else {
+            return;
+        };
+        if let Some(suggestions) = answer(&mut state.loading) {
+            state.selected = vec![true; suggestions.len()];
+            state.suggestions = suggestions;
+        }
+
+        let mut apply = false;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Artist-Ordner"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([520.0, 420.0])
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(tr(
+                    "Ordner mit mehreren PDFs ohne \u{201E}Artist - Titel\u{201C} im Dateinamen \
+                     sind vermutlich nach dem Artist benannt. Der Ordnername wird als Artist \
+                     f\u{00FC}r die Songs darin ohne Artist vorgeschlagen.",
+                ))
+                .size(12.5)
+                .color(palette::TEXT_SECONDARY),
+            );
+            ui.add_space(4.0);
+            if state.loading.is_some() {
+                ui.spinner();
+                return;
+            }
+            if state.suggestions.is_empty() {
+                ui.label(
+                    egui::RichText::new(tr("Keine Vorschl\u{00E4}ge.")).color(palette::TEXT_MUTED),
+                );
+                return;
+            }
+            ui.separator();
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, true])
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    for (i, suggestion) in state.suggestions.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut state.selected[i], "");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut suggestion.artist)
+                                    .desired_width(180.0),
+                            );
+                            let count = match suggestion.songs.len() {
+                                1 => tr("1 Song").to_string(),
+                                n => tr_arg("{} Songs", n),
+                            };
+                            ui.label(
+                                egui::RichText::new(count)
+                                    .size(12.0)
+                                    .color(palette::TEXT_MUTED),
+                            );
+                        });
+                        egui::CollapsingHeader::new(
+                            egui::RichText::new(&suggestion.ordner)
+                                .size(11.5)
+                                .color(palette::TEXT_MUTED),
+                        )
+                        .id_salt(("artist_folder", i))
+                        .show(ui, |ui| {
+                            for (_, titel) in &suggestion.songs {
+                                ui.label(
+                                    egui::RichText::new(titel)
+                                        .size(12.0)
+                                        .color(palette::TEXT_SECONDARY),
+                                );
+                            }
+                        });
+                        ui.add_space(2.0);
+                    }
+                });
+
+            ui.add_space(8.0);
+            let count = state.selected.iter().filter(|s| **s).count();
+            let apply_btn = egui::Button::new(
+                egui::RichText::new(tr("Artist \u{00FC}bernehmen")).color(palette::TEXT_PRIMARY),
+            )
+            .fill(palette::accent_dim())
+            .rounding(6.0);
+            if ui.add_enabled(count > 0, apply_btn).clicked() {
+                apply = true;
+            }
+        });
+
+        if apply {
+            let chosen: Vec<(String, Vec<i64>)> = state
+                .suggestions
+                .iter()
+                .zip(&state.selected)
+                .filter(|(s, selected)| **selected && !s.artist.trim().is_empty())
+                .map(|(s, _)| {
+                    let ids = s.songs.iter().map(|(id, _)| *id).collect();
+                    (s.artist.trim().to_string(), ids)
+                })
+                .collect();
+            self.db.send(move |conn| {
+                for (artist, ids) in &chosen {
+                    artist_folders::apply(conn, artist, ids);
+                }
+            });
+            self.artist_folders = None;
+            self.refresh_data();
+        } else if !open {
+            self.artist_folders = None;
+        }
+    }
+}
@@ -0,0 +1,409 @@
+use super::pending::answer;
+use super::{palette, save_config, SongIndexApp};
+use crate::autotag::{self, RetagChange, RuleMerge, TaggedSong};
+use crate::config::{default_auto_tag_rules, AutoTagRule, RuleScope};
+use crate::i18n::{tr, tr_arg};
+use crate::scanner::ScanOptions;
+use eframe::egui;
+use std::sync::mpsc::Receiver;
+
+pub(super) struct AutoTagRulesState {
+    rules: Vec<AutoTagRule>,
+    /// Library as loaded when the editor opened; the preview runs against it.
+    songs: Vec<TaggedSong>,
+    /// The library, until it is loaded; the preview waits for it.
+    loading: Option<Receiver<Vec<TaggedSong>>>,
+    changes: Vec<RetagChange>,
+    /// How an import or the default rules join the list.
+    merge: RuleMerge,
+    message: Option<String>,
+}
+
+impl AutoTagRulesState {
+    fn update_preview(&mut self) {
+        self.changes = autotag::preview(&self.songs, &self.rules);
+    }
+}
+
+impl SongIndexApp {
+    pub(super) fn open_auto_tag_rules(&mut self) {
+        let loading = self.db.request(|conn| autotag::load_songs(conn));
+        self.auto_tag_rules = Some(AutoTagRulesState {
+            rules: self.config.auto_tag_rules.clone(),
+            songs: Vec::new(),
+            loading: Some(loading),
+            changes: Vec::new(),
+            merge: RuleMerge::default(),
+            message: None,
+        });
+    }
+
+    pub(super) fn show_auto_tag_rules(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.auto_tag_rules else {
+            return;
+        };
+        if let Some(songs) = answer(&mut state.loading) {
+            state.songs = songs;
+            state.update_preview();
+        }
+
+        let mut save = false;
+        let mut retag = false;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Auto-Tag-Regeln"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([900.0, 540.0])
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(tr(
+                    "Enth\u{00E4}lt der Pfad eines neuen Songs das Muster, bekommt er den Tag. \
+                     Ohne \u{201E}A=a\u{201C} z\u{00E4}hlt die Gro\u{00DF}-/Kleinschreibung. Die Regeln werden von oben \
+                     nach unten gepr\u{00FC}ft.",
+                ))
+                .size(12.5)
+                .color(palette::TEXT_SECONDARY),
+            );
+            ui.add_space(4.0);
+
+            let mut changed = false;
+            let mut remove = None;
+            let mut move_up = None;
+            egui::ScrollArea::both()
+                .id_salt("auto_tag_rules")
+                .max_height(220.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("auto_tag_rules_grid")
+                        .num_columns(11)
+                        .spacing([6.0, 4.0])
+                        .show(ui, |ui| {
+                            ui.label("");
+                            for label in [
+                                "Muster",
+                                "A=a",
+                                "Wort",
+                                "Suchen in",
+                                "Nur unter",
+                                "Kategorie",
+                                "Wert",
+                                "Ersetzen",
+                                "Stopp",
+                            ] {
+                                ui.label(
+                                    egui::RichText::new(tr(label))
+                                        .size(12.0)
+                                        .color(palette::TEXT_MUTED),
+                                );
+                            }
+                            ui.end_row();
+                            let count = state.rules.len();
+                            for (i, rule) in state.rules.iter_mut().enumerate() {
+                                ui.horizontal(|ui| {
+                                    ui.spacing_mut().item_spacing.x = 2.0;
+                                    if ui
+                                        .add_enabled(i > 0, egui::Button::new("\u{2191}").small())
+                                        .on_hover_text(tr("Fr\u{00FC}her pr\u{00FC}fen"))
+                                        .clicked()
+                                    {
+                                        move_up = Some(i);
+                                    }
+                                    if ui
+                                        .add_enabled(
+                                            i + 1 < count,
+                                            egui::Button::new("\u{2193}").small(),
+                                        )
+                                        .on_hover_text(tr("Sp\u{00E4}ter pr\u{00FC}fen"))
+                                        .clicked()
+                                    {
+                                        move_up = Some(i + 1);
+                                    }
+                                });
+                                changed |= ui
+                                    .add(
+                                        egui::TextEdit::singleline(&mut rule.pattern)
+                                            .desired_width(160.0),
+                                    )
+                                    .changed();
+                                changed |= ui
+                                    .checkbox(&mut rule.ohne_gross_klein, "")
+                                    .on_hover_text(tr("Gro\u{00DF}-/Kleinschreibung egal"))
+                                    .changed();
+                                changed |= ui
+                                    .checkbox(&mut rule.ganzes_wort, "")
+                                    .on_hover_text(tr(
+                                        "Nur als ganzes Wort: \u{201E}Blues\u{201C} passt \
+                                         nicht auf \u{201E}Bluestone\u{201C}",
+                                    ))
+                                    .changed();
+                                changed |= scope_edit(ui, i, rule);
+                                changed |= ui
+                                    .add(
+                                        egui::TextEdit::singleline(&mut rule.unter)
+                                            .desired_width(130.0)
+                                            .hint_text(tr("Ordner")),
+                                    )
+                                    .on_hover_text(tr(
+                                        "Nur Songs in diesem Ordner (relativ zum Musikordner)",
+                                    ))
+                                    .changed();
+                                for (text, width) in
+                                    [(&mut rule.kategorie, 100.0), (&mut rule.wert, 120.0)]
+                                {
+                                    changed |= ui
+                                        .add(egui::TextEdit::singleline(text).desired_width(width))
+                                        .changed();
+                                }
+                                changed |= ui
+                                    .checkbox(&mut rule.ersetzen, "")
+                                    .on_hover_text(tr(
+                                        "Ersetzt Tags dieser Kategorie aus fr\u{00FC}heren Regeln, \
+                                         beim Neu-Taggen auch von Hand gesetzte",
+                                    ))
+                                    .changed();
+                                changed |= ui
+                                    .checkbox(&mut rule.stoppen, "")
+                                    .on_hover_text(tr(
+                                        "Trifft die Regel zu, werden die folgenden \
+                                         nicht mehr gepr\u{00FC}ft",
+                                    ))
+                                    .changed();
+                                if ui
+                                    .small_button("\u{2715}")
+                                    .on_hover_text(tr("Regel l\u{00F6}schen"))
+                                    .clicked()
+                                {
+                                    remove = Some(i);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+            if let Some(i) = remove {
+                state.rules.remove(i);
+                changed = true;
+            }
+            if let Some(i) = move_up {
+                state.rules.swap(i - 1, i);
+                changed = true;
+            }
+            ui.horizontal(|ui| {
+                if ui.button(tr("+ Regel")).clicked() {
+                    state.rules.push(AutoTagRule {
+                        ebene: 1,
+                        ..Default::default()
+                    });
+                }
+                ui.separator();
+                if ui
+                    .button(tr("Exportieren\u{2026}"))
+                    .on_hover_text(tr(
+                        "Die Regeln als TOML- oder JSON-Datei speichern, z.B. zum Weitergeben",
+                    ))
+                    .clicked()
+                {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .set_title(tr("Auto-Tag-Regeln exportieren"))
+                        .set_file_name(tr("auto-tag-regeln.toml"))
+                        .add_filter("TOML", &["toml"])
+                        .add_filter("JSON", &["json"])
+                        .save_file()
+                    {
+                        let rules: Vec<AutoTagRule> = state
+                            .rules
+                            .iter()
+                            .filter(|r| !r.pattern.is_empty())
+                            .cloned()
+                            .collect();
+                        state.message = Some(match autotag::export_rules(&rules, &path) {
+                            Ok(n) => tr_arg("{} Regeln exportiert", n),
+                            Err(e) => tr_arg("Export fehlgeschlagen: {}", e),
+                        });
+                    }
+                }
+                let mut imported = None;
+                if ui
+                    .button(tr("Importieren\u{2026}"))
+                    .on_hover_text(tr("Regeln aus einer exportierten Datei \u{00FC}bernehmen"))
+                    .clicked()
+                {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .set_title(tr("Auto-Tag-Regeln importieren"))
+                        .add_filter(tr("Regeln"), &["toml", "json"])
+                        .pick_file()
+                    {
+                        match autotag::read_rules(&path) {
+                            Ok(rules) => imported = Some(rules),
+                            Err(e) => state.message = Some(tr_arg("Import fehlgeschlagen: {}", e)),
+                        }
+                    }
+                }
+                if ui
+                    .button(tr("Standardregeln"))
+                    .on_hover_text(tr(
+                        "Die mitgelieferten Ordner-Regeln (E-Gitarre, Blues, Kinderlieder \u{2026}) \
+                         \u{00FC}bernehmen",
+                    ))
+                    .clicked()
+                {
+                    imported = Some(default_auto_tag_rules());
+                }
+                egui::ComboBox::from_id_salt("auto_tag_merge")
+                    .selected_text(tr(state.merge.label()))
+                    .width(150.0)
+                    .show_ui(ui, |ui| {
+                        for mode in RuleMerge::ALL {
+                            ui.selectable_value(&mut state.merge, mode, tr(mode.label()));
+                        }
+                    })
+                    .response
+                    .on_hover_text(tr(
+                        "Wie Import und Standardregeln zur Liste kommen. Doppelt ist eine Regel \
+                         mit gleichem Muster, Bereich und Tag.",
+                    ));
+                if let Some(rules) = imported {
+                    let n = autotag::merge_rules(&mut state.rules, rules, state.merge);
+                    state.message = Some(tr_arg(
+                        "{} Regeln \u{00FC}bernommen \u{2013} \u{201E}Speichern\u{201C} \
+                         macht sie wirksam",
+                        n,
+                    ));
+                    changed = true;
+                }
+            });
+            if let Some(ref message) = state.message {
+                ui.label(egui::RichText::new(message).color(palette::accent()));
+            }
+            if changed {
+                state.update_preview();
+            }
+
+            ui.add_space(6.0);
+            ui.separator();
+            if state.loading.is_some() {
+                ui.spinner();
+                return;
+            }
+            let summary = match state.changes.len() {
+                0 => tr("Neu taggen w\u{00FC}rde nichts \u{00E4}ndern").to_string(),
+                1 => tr("Neu taggen w\u{00FC}rde 1 Song \u{00E4}ndern:").to_string(),
+                n => tr_arg("Neu taggen w\u{00FC}rde {} Songs \u{00E4}ndern:", n),
+            };
+            ui.label(
+                egui::RichText::new(summary)
+                    .size(12.5)
+                    .color(palette::TEXT_SECONDARY),
+            );
+            egui::ScrollArea::vertical()
+                .id_salt("auto_tag_preview")
+                .max_height(180.0)
+                .auto_shrink([false, true])
+                .show(ui, |ui| {
+                    for change in &state.changes {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label(
+                                egui::RichText::new(&change.titel)
+                                    .size(12.5)
+                                    .color(palette::TEXT_PRIMARY),
+                            )
+                            .on_hover_text(&change.dateipfad);
+                            for (kategorie, wert) in &change.gained {
+                                ui.label(
+                                    egui::RichText::new(format!("+ {kategorie}:{wert}"))
+                                        .size(12.0)
+                                        .color(palette::AUDIO_GREEN),
+                                );
+                            }
+                            for (kategorie, wert) in &change.lost {
+                                ui.label(
+                                    egui::RichText::new(format!("\u{2212} {kategorie}:{wert}"))
+                                        .size(12.0)
+                                        .strikethrough()
+                                        .color(palette::ACCENT_RED),
+                                );
+                            }
+                        });
+                    }
+                });
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                if ui.button(tr("Speichern")).clicked() {
+                    save = true;
+                }
+                let retag_btn = egui::Button::new(
+                    egui::RichText::new(tr("Speichern und neu taggen")).color(palette::TEXT_PRIMARY),
+                )
+                .fill(palette::accent_dim())
+                .rounding(6.0);
+                if ui
+                    .add_enabled(!state.changes.is_empty(), retag_btn)
+                    .clicked()
+                {
+                    retag = true;
+                }
+            });
+            ui.label(
+                egui::RichText::new(tr(
+                    "Speichern gilt f\u{00FC}r neu gefundene Songs. Neu taggen \u{00E4}ndert nur \
+                     Tags, die eine Regel vergeben hat; von Hand gesetzte bleiben, au\u{00DF}er \
+                     eine Regel mit \u{201E}Ersetzen\u{201C} trifft zu.",
+                ))
+                .size(11.5)
+                .color(palette::TEXT_MUTED),
+            );
+        });
+
+        if save || retag {
+            self.config.auto_tag_rules = state
+                .rules
+                .iter()
+                .filter(|r| !r.pattern.is_empty())
+                .cloned()
+                .collect();
+            save_config(&self.config);
+            *self.scan_options.write().unwrap() = ScanOptions::from(&self.config);
+            if retag {
+                let changes = std::mem::take(&mut state.changes);
+                self.db.send(move |conn| autotag::apply(conn, &changes));
+                self.refresh_data();
+            }
+            self.auto_tag_rules = None;
+        } else if !open {
+            self.auto_tag_rules = None;
+        }
+    }
+}
+
+/// Scope dropdown, plus the folder depth for `RuleScope::Ebene`.
+fn scope_edit(ui: &mut egui::Ui, i: usize, rule: &mut AutoTagRule) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        let before = rule.bereich;
+        egui::ComboBox::from_id_salt(("auto_tag_scope", i))
+            .selected_text(tr(rule.bereich.label()))
+            .width(120.0)
+            .show_ui(ui, |ui| {
+                for scope in RuleScope::ALL {
+                    ui.selectable_value(&mut rule.bereich, scope, tr(scope.label()));
+                }
+            })
+            .response
+            .on_hover_text(tr(
+                "Ganzer Pfad: auch im Dateinamen. Ein Ordner: irgendein Ordnername. \
+                 Direkter Ordner: nur der Ordner der Datei. Ordnerebene: nur der Ordner \
+                 in dieser Tiefe (1 = oberste Ebene).",
+            ));
+        changed |= before != rule.bereich;
+        if rule.bereich == RuleScope::Ebene {
+            changed |= ui
+                .add(egui::DragValue::new(&mut rule.ebene).range(1..=12))
+                .changed();
+        }
+    });
+    changed
+}
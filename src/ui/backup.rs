@@ -0,0 +1,246 @@
+use super::{palette, save_config, SongIndexApp};
+use crate::backup::{decrypt, load_state, run_backup, unix_now};
+use crate::config::{BackupConfig, BackupTarget};
+use crate::i18n::{tr, tr_arg, tr_args};
+use crate::keychain;
+use eframe::egui;
+use std::sync::mpsc::Receiver;
+
+pub(super) struct BackupWindowState {
+    draft: BackupConfig,
+    /// Keychain entries being edited, written back on save.
+    passphrase: String,
+    /// WebDAV password or S3 secret key of the draft's target.
+    secret: String,
+    running: Option<Receiver<Result<String, String>>>,
+    message: Option<(bool, String)>,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_backup_settings(&mut self) {
+        let draft = self.config.backup.clone();
+        self.backup_window = Some(BackupWindowState {
+            passphrase: keychain::get(&draft.passphrase_ref).unwrap_or_default(),
+            secret: keychain::get(draft.target.secret_ref()).unwrap_or_default(),
+            draft,
+            running: None,
+            message: None,
+        });
+    }
+
+    pub(super) fn show_backup_window(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.backup_window else {
+            return;
+        };
+
+        if let Some(ref rx) = state.running {
+            match rx.try_recv() {
+                Ok(Ok(name)) => {
+                    state.message = Some((true, tr_arg("Hochgeladen als {}", name)));
+                    state.running = None;
+                }
+                Ok(Err(e)) => {
+                    state.message = Some((false, e));
+                    state.running = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {
+                    ctx.request_repaint_after(std::time::Duration::from_millis(200));
+                }
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => state.running = None,
+            }
+        }
+
+        let mut save = false;
+        let mut backup_now = false;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Cloud-Backup"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(460.0)
+        .show(ctx, |ui| {
+            let BackupWindowState {
+                draft,
+                passphrase,
+                secret,
+                ..
+            } = state;
+            ui.checkbox(&mut draft.enabled, tr("Automatisch sichern"));
+            ui.add_space(6.0);
+
+            ui.horizontal(|ui| {
+                let is_webdav = matches!(draft.target, BackupTarget::WebDav { .. });
+                let before = draft.target.secret_ref().to_string();
+                if ui.selectable_label(is_webdav, "WebDAV").clicked() && !is_webdav {
+                    draft.target = BackupTarget::webdav();
+                }
+                if ui.selectable_label(!is_webdav, tr("S3-kompatibel")).clicked() && is_webdav {
+                    draft.target = BackupTarget::s3();
+                }
+                if draft.target.secret_ref() != before {
+                    *secret = keychain::get(draft.target.secret_ref()).unwrap_or_default();
+                }
+            });
+            ui.add_space(4.0);
+
+            egui::Grid::new("backup_grid")
+                .num_columns(2)
+                .spacing([8.0, 6.0])
+                .show(ui, |ui| {
+                    let row = |ui: &mut egui::Ui, label: &str, value: &mut String, secret| {
+                        ui.label(egui::RichText::new(tr(label)).color(palette::TEXT_SECONDARY));
+                        ui.add(
+                            egui::TextEdit::singleline(value)
+                                .password(secret)
+                                .desired_width(300.0),
+                        );
+                        ui.end_row();
+                    };
+                    match &mut draft.target {
+                        BackupTarget::WebDav { url, username, .. } => {
+                            row(ui, "URL:", url, false);
+                            row(ui, "Benutzer:", username, false);
+                            row(ui, "Passwort:", secret, true);
+                        }
+                        BackupTarget::S3 {
+                            endpoint,
+                            bucket,
+                            region,
+                            access_key,
+                            ..
+                        } => {
+                            row(ui, "Endpoint:", endpoint, false);
+                            row(ui, "Bucket:", bucket, false);
+                            row(ui, "Region:", region, false);
+                            row(ui, "Access Key:", access_key, false);
+                            row(ui, "Secret Key:", secret, true);
+                        }
+                    }
+                    row(ui, "Verschl\u{00FC}sselung:", passphrase, true);
+
+                    ui.label(egui::RichText::new(tr("Intervall:")).color(palette::TEXT_SECONDARY));
+                    ui.add(
+                        egui::DragValue::new(&mut draft.interval_hours)
+                            .range(1..=24 * 30)
+                            .suffix(" h"),
+                    );
+                    ui.end_row();
+                });
+
+            ui.add_space(4.0);
+            ui.label(
+                egui::RichText::new(tr(
+                    "Ohne das Verschl\u{00FC}sselungspasswort lassen sich die Sicherungen nicht wiederherstellen.",
+                ))
+                .size(11.5)
+                .color(palette::TEXT_MUTED),
+            );
+
+            ui.add_space(6.0);
+            let status = load_state();
+            let last = match (&status.last_file, status.last_success) {
+                (Some(name), Some(when)) => tr_args(
+                    "Letzte Sicherung {}: {}",
+                    &[
+                        &self.format.seconds_ago(unix_now().saturating_sub(when)),
+                        name,
+                    ],
+                ),
+                (Some(name), None) => tr_arg("Letzte Sicherung: {}", name),
+                (None, _) => tr("Noch keine Sicherung hochgeladen.").to_string(),
+            };
+            ui.label(egui::RichText::new(last).size(12.5).color(palette::TEXT_SECONDARY));
+            if let Some((ok, ref msg)) = state.message {
+                let color = if ok { palette::AUDIO_GREEN } else { palette::ACCENT_RED };
+                ui.label(egui::RichText::new(msg).size(12.5).color(color));
+            } else if let Some(ref err) = status.last_error {
+                ui.label(
+                    egui::RichText::new(tr_arg("Letzter Fehler: {}", err))
+                        .size(12.5)
+                        .color(palette::ACCENT_RED),
+                );
+            }
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                let save_btn = egui::Button::new(
+                    egui::RichText::new(tr("Speichern")).color(palette::TEXT_PRIMARY),
+                )
+                .fill(palette::accent_dim())
+                .rounding(6.0);
+                if ui.add(save_btn).clicked() {
+                    save = true;
+                }
+                ui.add_space(4.0);
+                if ui
+                    .add_enabled(state.running.is_none(), egui::Button::new(tr("Jetzt sichern")))
+                    .clicked()
+                {
+                    backup_now = true;
+                }
+                if state.running.is_some() {
+                    ui.spinner();
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button(tr("Backup entschl\u{00FC}sseln\u{2026}")).clicked() {
+                        state.message = decrypt_file(&state.passphrase);
+                    }
+                });
+            });
+        });
+
+        if save || backup_now {
+            self.config.backup = state.draft.clone();
+            save_config(&self.config);
+            let stored = keychain::set(&state.draft.passphrase_ref, &state.passphrase)
+                .and_then(|()| keychain::set(state.draft.target.secret_ref(), &state.secret));
+            if let Err(e) = stored {
+                state.message = Some((false, tr_arg("Schl\u{00FC}sselbund: {}", e)));
+            } else if save {
+                state.message = Some((true, tr("Gespeichert.").to_string()));
+            }
+        }
+        if backup_now {
+            let (tx, rx) = std::sync::mpsc::channel();
+            let db = self.db.clone();
+            let cfg = state.draft.clone();
+            std::thread::spawn(move || {
+                tx.send(run_backup(&db, &cfg)).ok();
+            });
+            state.running = Some(rx);
+            state.message = None;
+        }
+
+        if !open {
+            self.backup_window = None;
+        }
+    }
+}
+
+/// Decrypt a downloaded backup into a plain SQLite file chosen by the user.
+fn decrypt_file(passphrase: &str) -> Option<(bool, String)> {
+    let source = rfd::FileDialog::new()
+        .set_title(tr("Sicherung ausw\u{00E4}hlen"))
+        .add_filter("Songindex-Backup", &["enc"])
+        .pick_file()?;
+    let data = match std::fs::read(&source) {
+        Ok(data) => data,
+        Err(e) => return Some((false, e.to_string())),
+    };
+    let plain = match decrypt(&data, passphrase) {
+        Ok(plain) => plain,
+        Err(e) => return Some((false, e)),
+    };
+    let target = rfd::FileDialog::new()
+        .set_title(tr("Entschl\u{00FC}sselte Datenbank speichern"))
+        .set_file_name("songindex.db")
+        .save_file()?;
+    Some(match std::fs::write(&target, plain) {
+        Ok(()) => (true, tr_arg("Gespeichert unter {}", target.display())),
+        Err(e) => (false, e.to_string()),
+    })
+}
@@ -0,0 +1,92 @@
+use super::{palette, SongIndexApp};
+use crate::i18n::tr;
+use crate::lru::CacheStats;
+use eframe::egui;
+
+const MB: f64 = 1024.0 * 1024.0;
+
+impl SongIndexApp {
+    /// Debug overlay (F12) with the fill level and hit rate of each in-memory
+    /// cache plus the texture memory egui currently holds.
+    pub(super) fn show_cache_overlay(&mut self, ctx: &egui::Context) {
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::F12)) {
+            self.cache_overlay = !self.cache_overlay;
+        }
+        if !self.cache_overlay {
+            return;
+        }
+
+        let thumbnails = self.thumbnails.as_ref().map(|t| t.stats());
+        let charts = self.chart_cache.stats();
+        let (textures, texture_bytes) = {
+            let manager = ctx.tex_manager();
+            let manager = manager.read();
+            let bytes: usize = manager.allocated().map(|(_, meta)| meta.bytes_used()).sum();
+            (manager.num_allocated(), bytes)
+        };
+
+        egui::Area::new(egui::Id::new("cache_overlay"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-12.0, -12.0])
+            .order(egui::Order::Foreground)
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(palette::bg_card())
+                    .stroke(egui::Stroke::new(0.5, palette::border_subtle()))
+                    .rounding(6.0)
+                    .inner_margin(egui::Margin::same(10.0))
+                    .show(ui, |ui| {
+                        ui.label(
+                            egui::RichText::new(tr("Caches"))
+                                .size(13.0)
+                                .strong()
+                                .color(palette::TEXT_PRIMARY),
+                        );
+                        egui::Grid::new("cache_overlay_grid")
+                            .num_columns(4)
+                            .spacing([14.0, 2.0])
+                            .show(ui, |ui| {
+                                for header in ["", "Eintr\u{00E4}ge", "Speicher", "Trefferquote"] {
+                                    ui.label(small(tr(header), palette::TEXT_MUTED));
+                                }
+                                ui.end_row();
+                                cache_row(ui, tr("Vorschaubilder"), thumbnails.unwrap_or_default());
+                                cache_row(ui, "Charts", charts);
+                                ui.label(small(tr("Texturen"), palette::TEXT_SECONDARY));
+                                ui.label(small(textures.to_string(), palette::TEXT_PRIMARY));
+                                ui.label(small(
+                                    format!("{:.1} MB", texture_bytes as f64 / MB),
+                                    palette::TEXT_PRIMARY,
+                                ));
+                                ui.label(small("\u{2013}", palette::TEXT_MUTED));
+                                ui.end_row();
+                            });
+                    });
+            });
+    }
+}
+
+fn small(text: impl Into<String>, color: egui::Color32) -> egui::RichText {
+    egui::RichText::new(text)
+        .size(11.5)
+        .monospace()
+        .color(color)
+}
+
+fn cache_row(ui: &mut egui::Ui, name: &str, stats: CacheStats) {
+    ui.label(small(name, palette::TEXT_SECONDARY));
+    ui.label(small(stats.entries.to_string(), palette::TEXT_PRIMARY));
+    ui.label(small(
+        format!(
+            "{:.1} / {:.0} MB",
+            stats.bytes as f64 / MB,
+            stats.limit as f64 / MB
+        ),
+        palette::TEXT_PRIMARY,
+    ));
+    let rate = stats
+        .hit_rate()
+        .map_or_else(|| "\u{2013}".to_string(), |r| format!("{:.0} %", r * 100.0));
+    ui.label(small(rate, palette::TEXT_PRIMARY));
+    ui.end_row();
+}
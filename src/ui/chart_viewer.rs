@@ -0,0 +1,187 @@
+use super::{music_summary, palette, SongIndexApp};
+use crate::chordpro::{parse_chart, plain_chart, ChartLine};
+use crate::filetype::FileType;
+use crate::i18n::tr_arg;
+use crate::lru::LruCache;
+use eframe::egui;
+use std::rc::Rc;
+use std::time::SystemTime;
+
+pub(super) struct ChartViewerState {
+    titel: String,
+    subtitle: Option<String>,
+    lines: Result<Rc<Vec<ChartLine>>, String>,
+}
+
+/// Parsed charts by `dateipfad`, with the file's modification time at parse time.
+pub(super) type ChartCache = LruCache<String, (SystemTime, Rc<Vec<ChartLine>>)>;
+
+const CHART_FONT_SIZE: f32 = 13.5;
+
+impl SongIndexApp {
+    pub(super) fn open_chart_viewer(&mut self, song_id: i64) {
+        let Some(song) = self.songs.iter().find(|s| s.id == song_id) else {
+            return;
+        };
+        let subtitle = match (&song.artist, music_summary(song)) {
+            (Some(artist), Some(music)) => Some(format!("{artist} \u{00B7} {music}")),
+            (Some(artist), None) => Some(artist.clone()),
+            (None, music) => music,
+        };
+        let path = self.base_dir.join(&song.dateipfad);
+        let mtime = std::fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        let cached = self
+            .chart_cache
+            .get(&song.dateipfad)
+            .filter(|(parsed, _)| *parsed == mtime)
+            .map(|(_, lines)| lines.clone());
+        let lines = match cached {
+            Some(lines) => Ok(lines),
+            None => std::fs::read_to_string(&path)
+                .map(|text| {
+                    let lines = Rc::new(if song.dateityp == FileType::ChordPro.as_str() {
+                        parse_chart(&text)
+                    } else {
+                        plain_chart(&text)
+                    });
+                    self.chart_cache.insert(
+                        song.dateipfad.clone(),
+                        (mtime, lines.clone()),
+                        chart_bytes(&lines),
+                    );
+                    lines
+                })
+                .map_err(|e| tr_arg("Datei konnte nicht gelesen werden: {}", e)),
+        };
+        self.chart_viewer = Some(ChartViewerState {
+            titel: song.titel.clone(),
+            subtitle,
+            lines,
+        });
+    }
+
+    pub(super) fn show_chart_viewer(&mut self, ctx: &egui::Context) {
+        let Some(ref state) = self.chart_viewer else {
+            return;
+        };
+
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(&state.titel)
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .id(egui::Id::new("chart_viewer"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([560.0, 640.0])
+        .show(ctx, |ui| {
+            if let Some(ref subtitle) = state.subtitle {
+                ui.label(egui::RichText::new(subtitle).color(palette::TEXT_SECONDARY));
+                ui.add_space(6.0);
+            }
+            let lines = match state.lines {
+                Ok(ref lines) => lines,
+                Err(ref e) => {
+                    ui.label(egui::RichText::new(e).color(palette::TEXT_MUTED));
+                    return;
+                }
+            };
+            egui::ScrollArea::both()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    ui.spacing_mut().item_spacing.y = 0.0;
+                    for line in lines.iter() {
+                        show_chart_line(ui, line);
+                    }
+                });
+        });
+
+        if !open {
+            self.chart_viewer = None;
+        }
+    }
+}
+
+/// Rough heap size of a parsed chart.
+fn chart_bytes(lines: &[ChartLine]) -> usize {
+    let line_bytes = |line: &ChartLine| match line {
+        ChartLine::Lyrics(segments) => segments
+            .iter()
+            .map(|(chord, text)| chord.as_ref().map_or(0, String::len) + text.len() + 48)
+            .sum(),
+        ChartLine::Section(text) | ChartLine::Comment(text) | ChartLine::Verbatim(text) => {
+            text.len()
+        }
+        ChartLine::Blank => 0,
+    };
+    lines
+        .iter()
+        .map(|line| line_bytes(line) + std::mem::size_of::<ChartLine>())
+        .sum()
+}
+
+fn mono(text: impl Into<String>, color: egui::Color32) -> egui::RichText {
+    egui::RichText::new(text)
+        .font(egui::FontId::monospace(CHART_FONT_SIZE))
+        .color(color)
+}
+
+fn show_chart_line(ui: &mut egui::Ui, line: &ChartLine) {
+    match line {
+        ChartLine::Lyrics(segments) => {
+            let (chords, lyrics) = chords_over_lyrics(segments);
+            if !chords.is_empty() {
+                ui.label(mono(chords, palette::accent()).strong());
+            }
+            if !lyrics.trim().is_empty() {
+                ui.label(mono(lyrics, palette::TEXT_PRIMARY));
+            }
+        }
+        ChartLine::Section(label) => {
+            ui.add_space(6.0);
+            ui.label(
+                egui::RichText::new(label)
+                    .size(13.0)
+                    .strong()
+                    .color(palette::TEXT_SECONDARY),
+            );
+            ui.add_space(2.0);
+        }
+        ChartLine::Comment(text) => {
+            ui.label(
+                egui::RichText::new(text)
+                    .size(13.0)
+                    .italics()
+                    .color(palette::TEXT_MUTED),
+            );
+        }
+        ChartLine::Verbatim(text) => {
+            ui.label(mono(text, palette::TEXT_PRIMARY));
+        }
+        ChartLine::Blank => ui.add_space(CHART_FONT_SIZE),
+    }
+}
+
+/// Lay out chord segments as two monospace lines. A chord that would collide
+/// with the previous one pushes its lyrics to the right.
+fn chords_over_lyrics(segments: &[(Option<String>, String)]) -> (String, String) {
+    let mut chords = String::new();
+    let mut lyrics = String::new();
+    for (chord, text) in segments {
+        if let Some(chord) = chord {
+            let chord_len = chords.chars().count();
+            let min_col = if chord_len == 0 { 0 } else { chord_len + 1 };
+            let lyric_len = lyrics.chars().count();
+            lyrics.extend(std::iter::repeat_n(' ', min_col.saturating_sub(lyric_len)));
+            let col = lyrics.chars().count();
+            chords.extend(std::iter::repeat_n(' ', col - chord_len));
+            chords.push_str(chord);
+        }
+        lyrics.push_str(text);
+    }
+    (chords, lyrics)
+}
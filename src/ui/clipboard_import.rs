@@ -0,0 +1,142 @@
+use super::new_chart::target_folder_row;
+use super::{palette, SongIndexApp};
+use crate::chordpro::build_document;
+use crate::i18n::{tr, tr_arg};
+use crate::scanner::add_single_file;
+use crate::templates::chart_filename;
+use eframe::egui;
+use std::path::PathBuf;
+
+pub(super) struct ClipboardModalState {
+    text: String,
+    titel: String,
+    artist: String,
+    target_dir: PathBuf,
+    error: Option<String>,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_clipboard_import(&mut self) {
+        let text = arboard::Clipboard::new()
+            .and_then(|mut cb| cb.get_text())
+            .unwrap_or_default();
+        self.clipboard_modal = Some(ClipboardModalState {
+            text,
+            titel: String::new(),
+            artist: String::new(),
+            target_dir: self.base_dir.clone(),
+            error: None,
+        });
+    }
+
+    pub(super) fn show_clipboard_modal(&mut self, ctx: &egui::Context) {
+        let mut close = false;
+        let mut save = false;
+        let base_dir = self.base_dir.clone();
+        let Some(ref mut modal) = self.clipboard_modal else {
+            return;
+        };
+
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Aus Zwischenablage importieren"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([520.0, 460.0])
+        .show(ctx, |ui| {
+            egui::Grid::new("clipboard_grid")
+                .num_columns(2)
+                .spacing([8.0, 6.0])
+                .show(ui, |ui| {
+                    ui.label(egui::RichText::new(tr("Titel:")).color(palette::TEXT_SECONDARY));
+                    ui.text_edit_singleline(&mut modal.titel);
+                    ui.end_row();
+
+                    ui.label(egui::RichText::new("Artist:").color(palette::TEXT_SECONDARY));
+                    ui.text_edit_singleline(&mut modal.artist);
+                    ui.end_row();
+
+                    ui.label(egui::RichText::new(tr("Ordner:")).color(palette::TEXT_SECONDARY));
+                    target_folder_row(ui, &base_dir, &mut modal.target_dir, &mut modal.error);
+                    ui.end_row();
+                });
+
+            ui.add_space(6.0);
+            ui.horizontal(|ui| {
+                ui.label(
+                    egui::RichText::new(tr("Akkorde / Text:"))
+                        .size(12.5)
+                        .color(palette::TEXT_MUTED),
+                );
+                if ui.small_button(tr("Erneut einf\u{00FC}gen")).clicked() {
+                    if let Ok(text) = arboard::Clipboard::new().and_then(|mut cb| cb.get_text()) {
+                        modal.text = text;
+                    }
+                }
+            });
+            egui::ScrollArea::vertical()
+                .max_height(260.0)
+                .show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut modal.text)
+                            .font(egui::TextStyle::Monospace)
+                            .desired_width(f32::INFINITY)
+                            .desired_rows(12),
+                    );
+                });
+
+            if let Some(ref err) = modal.error {
+                ui.label(egui::RichText::new(err).color(palette::ACCENT_RED));
+            }
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                let save_btn = egui::Button::new(
+                    egui::RichText::new(tr("Speichern")).color(palette::TEXT_PRIMARY),
+                )
+                .fill(palette::accent_dim())
+                .rounding(6.0);
+                let can_save = !modal.titel.trim().is_empty() && !modal.text.trim().is_empty();
+                if ui.add_enabled(can_save, save_btn).clicked() {
+                    save = true;
+                }
+                ui.add_space(4.0);
+                if ui.button(tr("Abbrechen")).clicked() {
+                    close = true;
+                }
+            });
+        });
+
+        if save {
+            let path = modal
+                .target_dir
+                .join(chart_filename(&modal.titel, &modal.artist, "cho"));
+            if path.exists() {
+                modal.error = Some(tr_arg(
+                    "\u{201E}{}\u{201C} existiert bereits.",
+                    path.file_name().unwrap_or_default().to_string_lossy(),
+                ));
+            } else {
+                let doc = build_document(&modal.titel, &modal.artist, &modal.text);
+                match std::fs::write(&path, doc) {
+                    Ok(()) => {
+                        let options = self.scan_options.read().unwrap().clone();
+                        self.db
+                            .send(move |conn| add_single_file(conn, &base_dir, &path, &options));
+                        self.refresh_data();
+                        close = true;
+                    }
+                    Err(e) => modal.error = Some(e.to_string()),
+                }
+            }
+        }
+
+        if !open || close {
+            self.clipboard_modal = None;
+        }
+    }
+}
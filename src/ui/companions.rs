@@ -0,0 +1,477 @@
+use super::pending::answer;
+use super::{palette, SongIndexApp};
+use crate::companions::{
+    add_companions, remove_companion, set_role, suggest, Candidate, Companion, Group, Role,
+};
+use crate::db::{log_song_event, Song, SongEvent};
+use crate::i18n::{tr, tr_arg};
+use crate::scanner::{add_single_file, nfc};
+use eframe::egui;
+use std::sync::mpsc::Receiver;
+
+/// The "Dateien" section's state beyond the song's own companions.
+pub(super) struct CompanionPanel {
+    song_id: i64,
+    /// What "Passende suchen" found, once clicked.
+    suggestions: Option<Vec<Candidate>>,
+    /// The matcher's answer while "Passende suchen" runs.
+    searching: Option<Receiver<Vec<Candidate>>>,
+    error: Option<String>,
+}
+
+/// Something clicked in the "Dateien" section.
+pub(super) enum CompanionAction {
+    Open(Companion),
+    SetRole(String, Role),
+    Detach(String),
+    Pick,
+    Suggest,
+    Attach(String, Role),
+}
+
+/// "Zusammengehörige Dateien": what the matcher groups across the library.
+pub(super) struct CompanionGroupsState {
+    groups: Vec<Group>,
+    /// Per group and file: whether to attach it.
+    selected: Vec<Vec<bool>>,
+    /// The groups, until the matcher is done.
+    loading: Option<Receiver<Vec<Group>>>,
+}
+
+fn file_name(rel_path: &str) -> &str {
+    rel_path.rsplit('/').next().unwrap_or(rel_path)
+}
+
+fn role_combo(ui: &mut egui::Ui, id_salt: impl std::hash::Hash, rolle: &mut Role) -> bool {
+    let mut changed = false;
+    egui::ComboBox::from_id_salt(id_salt)
+        .selected_text(tr(rolle.label()))
+        .width(80.0)
+        .show_ui(ui, |ui| {
+            for role in Role::ALL {
+                changed |= ui.selectable_value(rolle, role, tr(role.label())).changed();
+            }
+        });
+    changed
+}
+
+/// Chip for a companion on a song card; audio shows ▶ or ■ like the card's
+/// audio button.
+pub(super) fn companion_chip(ui: &mut egui::Ui, file: &Companion, playing: bool) -> egui::Response {
+    let label = match file.rolle {
+        Role::Audio if playing => format!("\u{25A0} {}", tr(file.rolle.label())),
+        Role::Audio => format!("\u{25B6} {}", tr(file.rolle.label())),
+        role => tr(role.label()).to_string(),
+    };
+    let chip = egui::Button::new(
+        egui::RichText::new(label)
+            .size(11.5)
+            .color(palette::TEXT_SECONDARY),
+    )
+    .fill(palette::bg_input())
+    .rounding(10.0)
+    .stroke(egui::Stroke::new(0.5, palette::border_subtle()));
+    ui.add(chip).on_hover_text(&file.dateipfad)
+}
+
+/// The selected song's companions with their roles, and the ways to add
+/// more: a file from the music folder or the matcher's suggestions.
+pub(super) fn companion_section(
+    ui: &mut egui::Ui,
+    song: &Song,
+    panel: Option<&CompanionPanel>,
+    playing: Option<&str>,
+    action: &mut Option<CompanionAction>,
+) {
+    ui.label(
+        egui::RichText::new(tr("Dateien"))
+            .size(13.0)
+            .strong()
+            .color(palette::TEXT_SECONDARY),
+    );
+    ui.add_space(2.0);
+    for file in &song.dateien {
+        ui.horizontal(|ui| {
+            ui.spacing_mut().item_spacing.x = 4.0;
+            let mut rolle = file.rolle;
+            if role_combo(ui, ("companion_role", &file.dateipfad), &mut rolle) {
+                *action = Some(CompanionAction::SetRole(file.dateipfad.clone(), rolle));
+            }
+            let open = match file.rolle {
+                Role::Audio if playing == Some(file.dateipfad.as_str()) => "\u{25A0}",
+                Role::Audio => "\u{25B6}",
+                _ => tr("\u{00D6}ffnen"),
+            };
+            if ui.small_button(open).clicked() {
+                *action = Some(CompanionAction::Open(file.clone()));
+            }
+            if ui
+                .small_button("\u{00D7}")
+                .on_hover_text(tr("Vom Song l\u{00F6}sen \u{2014} wieder ein eigener Song"))
+                .clicked()
+            {
+                *action = Some(CompanionAction::Detach(file.dateipfad.clone()));
+            }
+            ui.add(
+                egui::Label::new(
+                    egui::RichText::new(file_name(&file.dateipfad))
+                        .size(12.0)
+                        .color(palette::TEXT_PRIMARY),
+                )
+                .truncate(),
+            )
+            .on_hover_text(&file.dateipfad);
+        });
+    }
+    ui.horizontal(|ui| {
+        ui.spacing_mut().item_spacing.x = 4.0;
+        if ui
+            .small_button(tr("+ Datei\u{2026}"))
+            .on_hover_text(tr(
+                "Tab, Liedtext oder Aufnahme aus dem Musikordner dazulegen",
+            ))
+            .clicked()
+        {
+            *action = Some(CompanionAction::Pick);
+        }
+        if ui
+            .small_button(tr("Passende suchen"))
+            .on_hover_text(tr("Dateien mit gleichem Titel in anderen Ordnern"))
+            .clicked()
+        {
+            *action = Some(CompanionAction::Suggest);
+        }
+    });
+    let Some(panel) = panel else {
+        return;
+    };
+    if panel.searching.is_some() {
+        ui.spinner();
+    } else if let Some(ref suggestions) = panel.suggestions {
+        if suggestions.is_empty() {
+            ui.label(
+                egui::RichText::new(tr("Nichts Passendes gefunden."))
+                    .size(12.0)
+                    .color(palette::TEXT_MUTED),
+            );
+        }
+        for candidate in suggestions {
+            let label = format!(
+                "+ {} \u{00B7} {}",
+                tr(candidate.rolle.label()),
+                file_name(&candidate.dateipfad)
+            );
+            if ui
+                .selectable_label(false, egui::RichText::new(label).size(12.0))
+                .on_hover_text(&candidate.dateipfad)
+                .clicked()
+            {
+                *action = Some(CompanionAction::Attach(
+                    candidate.dateipfad.clone(),
+                    candidate.rolle,
+                ));
+            }
+        }
+    }
+    if let Some(ref error) = panel.error {
+        ui.label(
+            egui::RichText::new(error)
+                .size(12.0)
+                .color(palette::ACCENT_RED),
+        );
+    }
+}
+
+impl SongIndexApp {
+    /// The companion panel state if it belongs to `song_id`.
+    pub(super) fn companion_panel(&self, song_id: i64) -> Option<&CompanionPanel> {
+        self.companion_panel
+            .as_ref()
+            .filter(|p| p.song_id == song_id)
+    }
+
+    fn companion_panel_mut(&mut self, song_id: i64) -> &mut CompanionPanel {
+        if self
+            .companion_panel
+            .as_ref()
+            .is_none_or(|p| p.song_id != song_id)
+        {
+            self.companion_panel = None;
+        }
+        self.companion_panel.get_or_insert(CompanionPanel {
+            song_id,
+            suggestions: None,
+            searching: None,
+            error: None,
+        })
+    }
+
+    /// Take the matcher's suggestions once "Passende suchen" has them.
+    pub(super) fn poll_companion_suggestions(&mut self) {
+        if let Some(ref mut panel) = self.companion_panel {
+            if let Some(suggestions) = answer(&mut panel.searching) {
+                panel.suggestions = Some(suggestions);
+            }
+        }
+    }
+
+    /// Play or stop a companion recording, or open any other companion in
+    /// the app for its extension (the song's own app is for its main file).
+    pub(super) fn open_companion(&mut self, song_id: i64, file: &Companion) {
+        if file.rolle == Role::Audio {
+            if self.playing_audio() == Some(file.dateipfad.as_str()) {
+                self.stop_audio();
+            } else {
+                self.play_audio(song_id, &file.dateipfad);
+            }
+            return;
+        }
+        let path = self.base_dir.join(&file.dateipfad);
+        crate::open_with::open(
+            &path,
+            crate::open_with::app_for(&self.config.open_with, None, &path),
+        );
+        self.db
+            .send(move |conn| log_song_event(conn, song_id, SongEvent::Opened));
+        self.refresh_song(song_id);
+    }
+
+    pub(super) fn apply_companion_action(&mut self, song_id: i64, action: CompanionAction) {
+        match action {
+            CompanionAction::Open(file) => self.open_companion(song_id, &file),
+            CompanionAction::SetRole(rel_path, rolle) => {
+                self.db.send(move |conn| set_role(conn, &rel_path, rolle));
+                self.refresh_song(song_id);
+            }
+            CompanionAction::Detach(rel_path) => {
+                let base_dir = self.base_dir.clone();
+                let options = self.scan_options.read().unwrap().clone();
+                self.db.send(move |conn| {
+                    remove_companion(conn, &rel_path);
+                    add_single_file(conn, &base_dir, &base_dir.join(&rel_path), &options);
+                });
+                self.refresh_data();
+            }
+            CompanionAction::Pick => {
+                let Some(path) = rfd::FileDialog::new()
+                    .set_directory(&self.base_dir)
+                    .pick_file()
+                else {
+                    return;
+                };
+                match path.strip_prefix(&self.base_dir) {
+                    Ok(rel) => {
+                        let rel_path = nfc(rel.to_string_lossy());
+                        let rolle = Role::guess(&rel_path);
+                        self.attach_companion(song_id, rel_path, rolle);
+                    }
+                    Err(_) => {
+                        self.companion_panel_mut(song_id).error =
+                            Some(tr("Die Datei liegt nicht im Musikordner.").to_string());
+                    }
+                }
+            }
+            CompanionAction::Suggest => {
+                let searching = self.suggest_groups(move |groups| suggestions_for(groups, song_id));
+                let panel = self.companion_panel_mut(song_id);
+                panel.searching = Some(searching);
+                panel.error = None;
+            }
+            CompanionAction::Attach(rel_path, rolle) => {
+                self.attach_companion(song_id, rel_path, rolle);
+            }
+        }
+    }
+
+    fn attach_companion(&mut self, song_id: i64, rel_path: String, rolle: Role) {
+        let file = [(rel_path.clone(), rolle)];
+        self.db
+            .send(move |conn| add_companions(conn, song_id, &file));
+        let panel = self.companion_panel_mut(song_id);
+        panel.error = None;
+        if let Some(ref mut suggestions) = panel.suggestions {
+            suggestions.retain(|c| c.dateipfad != rel_path);
+        }
+        // A merged song leaves the list.
+        self.refresh_data();
+    }
+
+    /// Request what the matcher would group in the whole library, passed
+    /// through `then` on the worker.
+    fn suggest_groups<T: Send + 'static>(
+        &self,
+        then: impl FnOnce(Vec<Group>) -> T + Send + 'static,
+    ) -> Receiver<T> {
+        let base_dir = self.base_dir.clone();
+        let options = self.scan_options.read().unwrap().clone();
+        self.db
+            .request(move |conn| then(suggest(conn, &base_dir, &options)))
+    }
+
+    pub(super) fn open_companion_groups(&mut self) {
+        let loading = self.suggest_groups(|groups| groups);
+        self.companion_groups = Some(CompanionGroupsState {
+            groups: Vec::new(),
+            selected: Vec::new(),
+            loading: Some(loading),
+        });
+    }
+
+    pub(super) fn show_companion_groups(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.companion_groups else {
+            return;
+        };
+        if let Some(groups) = answer(&mut state.loading) {
+            state.selected = groups.iter().map(|g| vec![true; g.dateien.len()]).collect();
+            state.groups = groups;
+        }
+
+        let mut apply = false;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Zusammengeh\u{00F6}rige Dateien"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([560.0, 440.0])
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(tr(
+                    "Dateien mit gleichem Titel in verschiedenen Ordnern, z.\u{00A0}B. Noten, \
+                     Tab und Aufnahme eines Songs. Angehakte Dateien werden dem Song oben \
+                     zugeordnet; waren sie eigene Songs, gehen deren Tags, Links und Notizen \
+                     mit.",
+                ))
+                .size(12.5)
+                .color(palette::TEXT_SECONDARY),
+            );
+            ui.add_space(4.0);
+            if state.loading.is_some() {
+                ui.spinner();
+                return;
+            }
+            if state.groups.is_empty() {
+                ui.label(
+                    egui::RichText::new(tr("Keine Vorschl\u{00E4}ge.")).color(palette::TEXT_MUTED),
+                );
+                return;
+            }
+            ui.separator();
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, true])
+                .max_height(320.0)
+                .show(ui, |ui| {
+                    for (i, group) in state.groups.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                egui::RichText::new(&group.titel)
+                                    .strong()
+                                    .color(palette::TEXT_PRIMARY),
+                            );
+                            if let Some(ref artist) = group.artist {
+                                ui.label(
+                                    egui::RichText::new(format!("\u{2014} {artist}"))
+                                        .color(palette::TEXT_SECONDARY),
+                                );
+                            }
+                        });
+                        ui.label(
+                            egui::RichText::new(&group.dateipfad)
+                                .size(11.5)
+                                .color(palette::TEXT_MUTED),
+                        );
+                        for (j, candidate) in group.dateien.iter_mut().enumerate() {
+                            ui.horizontal(|ui| {
+                                ui.checkbox(&mut state.selected[i][j], "");
+                                role_combo(ui, ("group_role", i, j), &mut candidate.rolle);
+                                let path = ui.label(
+                                    egui::RichText::new(&candidate.dateipfad)
+                                        .size(11.5)
+                                        .color(palette::TEXT_SECONDARY),
+                                );
+                                if candidate.song_id.is_some() {
+                                    path.on_hover_text(tr(
+                                        "Bisher ein eigener Song; er wird mit diesem \
+                                         zusammengelegt.",
+                                    ));
+                                }
+                            });
+                        }
+                        ui.add_space(6.0);
+                    }
+                });
+
+            ui.add_space(8.0);
+            let count: usize = state
+                .selected
+                .iter()
+                .map(|s| s.iter().filter(|s| **s).count())
+                .sum();
+            let apply_btn = egui::Button::new(
+                egui::RichText::new(tr_arg("{} Dateien zuordnen", count))
+                    .color(palette::TEXT_PRIMARY),
+            )
+            .fill(palette::accent_dim())
+            .rounding(6.0);
+            if ui.add_enabled(count > 0, apply_btn).clicked() {
+                apply = true;
+            }
+        });
+
+        if apply {
+            let groups: Vec<(i64, Vec<(String, Role)>)> = state
+                .groups
+                .iter()
+                .zip(&state.selected)
+                .filter_map(|(group, selected)| {
+                    let files: Vec<(String, Role)> = group
+                        .dateien
+                        .iter()
+                        .zip(selected)
+                        .filter(|(_, selected)| **selected)
+                        .map(|(c, _)| (c.dateipfad.clone(), c.rolle))
+                        .collect();
+                    (!files.is_empty()).then_some((group.song_id, files))
+                })
+                .collect();
+            self.db.send(move |conn| {
+                for (song_id, files) in groups {
+                    add_companions(conn, song_id, &files);
+                }
+            });
+            self.companion_groups = None;
+            self.refresh_data();
+        } else if !open {
+            self.companion_groups = None;
+        }
+    }
+}
+
+/// The files of the matcher's group around `song_id`, other than its own. If
+/// the song is not the group's main file, the main file is among them.
+fn suggestions_for(groups: Vec<Group>, song_id: i64) -> Vec<Candidate> {
+    for group in groups {
+        if group.song_id == song_id {
+            return group.dateien;
+        }
+        if group.dateien.iter().any(|c| c.song_id == Some(song_id)) {
+            let main = Candidate {
+                rolle: Role::guess(&group.dateipfad),
+                dateipfad: group.dateipfad,
+                song_id: Some(group.song_id),
+            };
+            return std::iter::once(main)
+                .chain(
+                    group
+                        .dateien
+                        .into_iter()
+                        .filter(|c| c.song_id != Some(song_id)),
+                )
+                .collect();
+        }
+    }
+    Vec::new()
+}
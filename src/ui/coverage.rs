@@ -0,0 +1,240 @@
+use super::pending::answer;
+use super::{palette, SongIndexApp};
+use crate::curriculum::{
+    coverage, curriculum_path, load_curriculum, unknown_levels, CoverageRow, Curriculum,
+};
+use crate::i18n::{tr, tr_arg, tr_args};
+use crate::pdf::{write_report, ReportLine};
+use eframe::egui;
+use std::path::Path;
+use std::sync::mpsc::Receiver;
+
+pub(super) struct CoverageWindowState {
+    curriculum: Result<Curriculum, String>,
+    rows: Vec<CoverageRow>,
+    unknown_levels: Vec<String>,
+    /// Rows and unknown levels, until they are counted.
+    loading: Option<Receiver<(Vec<CoverageRow>, Vec<String>)>>,
+    message: Option<String>,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_coverage(&mut self) {
+        let curriculum = load_curriculum();
+        let loading = curriculum.as_ref().ok().map(|c| {
+            let c = c.clone();
+            self.db
+                .request(move |conn| (coverage(conn, &c), unknown_levels(conn, &c)))
+        });
+        self.coverage_window = Some(CoverageWindowState {
+            curriculum,
+            rows: Vec::new(),
+            unknown_levels: Vec::new(),
+            loading,
+            message: None,
+        });
+    }
+
+    pub(super) fn show_coverage_window(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.coverage_window else {
+            return;
+        };
+        if let Some((rows, unknown)) = answer(&mut state.loading) {
+            state.rows = rows;
+            state.unknown_levels = unknown;
+        }
+
+        let mut reload = false;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Lehrplan-Abdeckung"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([560.0, 420.0])
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button(tr("Lehrplan bearbeiten\u{2026}")).clicked() {
+                    crate::open_with::open_by_extension(
+                        &curriculum_path(),
+                        &self.config.open_with,
+                    );
+                }
+                if ui.button(tr("Neu laden")).clicked() {
+                    reload = true;
+                }
+                if let Ok(ref curriculum) = state.curriculum {
+                    if ui
+                        .add_enabled(
+                            state.loading.is_none(),
+                            egui::Button::new(tr("Als PDF exportieren\u{2026}")),
+                        )
+                        .clicked()
+                    {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .set_title(tr("Abdeckungsbericht speichern"))
+                            .set_file_name(tr("Lehrplan-Abdeckung.pdf"))
+                            .add_filter("PDF", &["pdf"])
+                            .save_file()
+                        {
+                            state.message =
+                                Some(match export_pdf(&path, curriculum, &state.rows) {
+                                    Ok(()) => tr_arg("Gespeichert unter {}", path.display()),
+                                    Err(e) => e,
+                                });
+                        }
+                    }
+                }
+            });
+            ui.separator();
+
+            let curriculum = match state.curriculum {
+                Ok(ref c) => c,
+                Err(ref e) => {
+                    ui.label(
+                        egui::RichText::new(tr_arg("lehrplan.toml ist fehlerhaft: {}", e))
+                            .color(palette::ACCENT_RED),
+                    );
+                    return;
+                }
+            };
+            if state.loading.is_some() {
+                ui.spinner();
+                return;
+            }
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                egui::Grid::new("coverage_grid")
+                    .striped(true)
+                    .spacing([16.0, 6.0])
+                    .show(ui, |ui| {
+                        ui.label(
+                            egui::RichText::new(tr("Fertigkeit"))
+                                .strong()
+                                .color(palette::TEXT_SECONDARY),
+                        );
+                        for stufe in &curriculum.stufen {
+                            ui.label(
+                                egui::RichText::new(stufe)
+                                    .strong()
+                                    .color(palette::TEXT_SECONDARY),
+                            );
+                        }
+                        ui.end_row();
+                        for row in &state.rows {
+                            ui.label(egui::RichText::new(&row.skill).color(palette::TEXT_PRIMARY));
+                            for &count in &row.counts {
+                                let color = if count == 0 {
+                                    palette::ACCENT_RED
+                                } else if count < curriculum.min_songs {
+                                    palette::TAG_SCHWIERIGKEIT
+                                } else {
+                                    palette::AUDIO_GREEN
+                                };
+                                ui.label(
+                                    egui::RichText::new(count.to_string()).strong().color(color),
+                                );
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+
+            let gaps = state
+                .rows
+                .iter()
+                .flat_map(|r| r.counts.iter())
+                .filter(|&&c| c < curriculum.min_songs)
+                .count();
+            ui.add_space(6.0);
+            ui.label(
+                egui::RichText::new(tr_args(
+                    "{} L\u{00FC}cken (weniger als {} Songs).",
+                    &[&(gaps as i64), &curriculum.min_songs],
+                ))
+                .color(palette::TEXT_SECONDARY),
+            );
+            if !state.unknown_levels.is_empty() {
+                ui.label(
+                    egui::RichText::new(tr_arg(
+                        "Nicht im Lehrplan: Schwierigkeit {}",
+                        state.unknown_levels.join(", "),
+                    ))
+                    .size(12.0)
+                    .color(palette::TEXT_MUTED),
+                );
+            }
+            if let Some(ref msg) = state.message {
+                ui.label(
+                    egui::RichText::new(msg)
+                        .size(12.5)
+                        .color(palette::TEXT_MUTED),
+                );
+            }
+        });
+
+        if reload {
+            self.open_coverage();
+        } else if !open {
+            self.coverage_window = None;
+        }
+    }
+}
+
+fn export_pdf(path: &Path, curriculum: &Curriculum, rows: &[CoverageRow]) -> Result<(), String> {
+    const SKILL_WIDTH: f32 = 170.0;
+    const LEVEL_WIDTH: f32 = 90.0;
+
+    let mut lines = vec![
+        ReportLine::text(tr("Lehrplan-Abdeckung"), 18.0, true),
+        ReportLine::text(
+            tr_arg(
+                "Rot: weniger als {} Songs pro Fertigkeit und Stufe",
+                curriculum.min_songs,
+            ),
+            9.0,
+            false,
+        ),
+        ReportLine::blank(),
+    ];
+    let mut header = vec![(0.0, tr("Fertigkeit").to_string())];
+    for (i, stufe) in curriculum.stufen.iter().enumerate() {
+        header.push((SKILL_WIDTH + i as f32 * LEVEL_WIDTH, stufe.clone()));
+    }
+    lines.push(ReportLine::row(header, true, false));
+
+    for row in rows {
+        let mut cells = vec![(0.0, row.skill.clone())];
+        for (i, count) in row.counts.iter().enumerate() {
+            cells.push((SKILL_WIDTH + i as f32 * LEVEL_WIDTH, count.to_string()));
+        }
+        let gap = row.counts.iter().any(|&c| c < curriculum.min_songs);
+        lines.push(ReportLine::row(cells, false, gap));
+    }
+
+    lines.push(ReportLine::blank());
+    lines.push(ReportLine::text(tr("Einkaufsliste"), 13.0, true));
+    for row in rows {
+        for (stufe, &count) in curriculum.stufen.iter().zip(&row.counts) {
+            if count < curriculum.min_songs {
+                lines.push(ReportLine::text(
+                    tr_args(
+                        "\u{2022} {} ({}): {} weitere Songs",
+                        &[
+                            &row.skill,
+                            stufe,
+                            &(curriculum.min_songs - count).to_string(),
+                        ],
+                    ),
+                    10.0,
+                    false,
+                ));
+            }
+        }
+    }
+
+    write_report(path, &lines)
+}
@@ -0,0 +1,419 @@
+use super::arrangements::arrangements_section;
+use super::companions::companion_section;
+use super::learning_path::learning_path_section;
+use super::markdown::show_markdown;
+use super::musicbrainz::MusicBrainzState;
+use super::transpose::transpose_tool;
+use super::{
+    link_icon, music_summary, palette, styled_small_button, tag_color, url_host, EditModalState,
+    SongIndexApp,
+};
+use crate::db::{add_song_link, remove_song_link, set_ignored};
+use crate::filetype::FileType;
+use crate::i18n::{tr, tr_arg};
+use eframe::egui;
+
+/// The "+ Link" form under the link chips.
+pub(super) struct LinkForm {
+    song_id: i64,
+    label: String,
+    url: String,
+}
+
+/// Label for a link added without one: the app for known schemes and hosts,
+/// otherwise the host name.
+fn default_link_label(url: &str) -> String {
+    let host = url_host(url);
+    let label = if url.starts_with("obsidian:") {
+        "Obsidian"
+    } else if url.starts_with("notion:") || host.ends_with("notion.so") {
+        "Notion"
+    } else if host.contains("drive.google") || host.contains("docs.google") {
+        "Google Drive"
+    } else if host.ends_with("youtube.com") || host == "youtu.be" {
+        "YouTube"
+    } else if url.starts_with("spotify:") || host.ends_with("spotify.com") {
+        "Spotify"
+    } else if host.ends_with("ultimate-guitar.com") {
+        "Ultimate Guitar"
+    } else if host.ends_with("songsterr.com") {
+        "Songsterr"
+    } else {
+        host
+    };
+    label.to_string()
+}
+
+impl SongIndexApp {
+    /// Side panel with the selected song's details and rendered notes.
+    pub(super) fn show_detail_panel(&mut self, ctx: &egui::Context) {
+        puffin::profile_scope!("Detailansicht");
+        let Some(index) = self.selected_index() else {
+            return;
+        };
+        let song_id = self.songs[index].id;
+        if self
+            .link_form
+            .as_ref()
+            .is_some_and(|f| f.song_id != song_id)
+        {
+            self.link_form = None;
+        }
+        if self
+            .path_form
+            .as_ref()
+            .is_some_and(|f| f.song_id() != song_id)
+        {
+            self.path_form = None;
+        }
+        if self.companion_panel(song_id).is_none() {
+            self.companion_panel = None;
+        }
+        self.poll_companion_suggestions();
+        if self
+            .arrangement_form
+            .as_ref()
+            .is_some_and(|f| f.song_id() != song_id)
+        {
+            self.arrangement_form = None;
+        }
+        self.load_song_chain(song_id);
+        self.update_path_form();
+        self.load_song_arrangements(song_id);
+        self.update_arrangement_form();
+
+        let mut close = false;
+        let mut edit = false;
+        let mut open_file = false;
+        let mut reveal = false;
+        let mut view_chart = false;
+        let mut ignore = false;
+        let mut lookup = false;
+        let mut open_url = None;
+        let mut add_link = false;
+        let mut remove_link = None;
+        let mut path_action = None;
+        let mut companion_action = None;
+        let mut arrangement_action = None;
+        let song = &self.songs[index];
+        let playing = self.playing_audio().map(str::to_owned);
+        let companion_panel = self.companion_panel.as_ref();
+        let transpose = &mut self.transpose;
+        let links = &song.links;
+        let link_form = &mut self.link_form;
+        let path_form = &mut self.path_form;
+        let song_chain = self.song_chain.as_mut().and_then(|(_, path)| path.get());
+        let arrangement_form = &mut self.arrangement_form;
+        let song_arrangements = self
+            .song_arrangements
+            .as_mut()
+            .and_then(|(_, loaded)| loaded.get());
+        let format = self.format;
+        egui::SidePanel::right("song_detail")
+            .resizable(true)
+            .default_width(300.0)
+            .width_range(220.0..=520.0)
+            .frame(
+                egui::Frame::none()
+                    .fill(palette::bg_surface())
+                    .inner_margin(egui::Margin::symmetric(14.0, 12.0))
+                    .stroke(egui::Stroke::new(0.5, palette::border_subtle())),
+            )
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(&song.titel)
+                            .size(17.0)
+                            .strong()
+                            .color(palette::TEXT_PRIMARY),
+                    );
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
+                        if ui
+                            .small_button("\u{2715}")
+                            .on_hover_text(tr("Schlie\u{00DF}en"))
+                            .clicked()
+                        {
+                            close = true;
+                        }
+                    });
+                });
+                if let Some(ref artist) = song.artist {
+                    ui.label(
+                        egui::RichText::new(artist)
+                            .size(14.0)
+                            .color(palette::TEXT_SECONDARY),
+                    );
+                }
+                if let Some(music) = music_summary(song) {
+                    ui.label(
+                        egui::RichText::new(music)
+                            .size(13.0)
+                            .color(palette::TEXT_SECONDARY),
+                    );
+                }
+                transpose_tool(ui, transpose, song);
+                ui.label(
+                    egui::RichText::new(&song.dateipfad)
+                        .size(11.5)
+                        .color(palette::TEXT_MUTED),
+                );
+                let mut dates = tr_arg(
+                    "Hinzugef\u{00FC}gt {}",
+                    format.relative(&song.created_at)
+                );
+                if let Some(days) = song.zuletzt_geuebt {
+                    dates.push_str(&tr_arg(
+                        " \u{00B7} zuletzt ge\u{00FC}bt {}",
+                        format.days_ago(days)
+                    ));
+                }
+                ui.label(
+                    egui::RichText::new(dates)
+                        .size(11.5)
+                        .color(palette::TEXT_MUTED),
+                )
+                .on_hover_text(tr_arg(
+                    "Hinzugef\u{00FC}gt am {}",
+                    format.date(&song.created_at)
+                ));
+
+                if !song.tags.is_empty() {
+                    ui.add_space(6.0);
+                    ui.horizontal_wrapped(|ui| {
+                        ui.spacing_mut().item_spacing = egui::vec2(4.0, 4.0);
+                        for tag in &song.tags {
+                            egui::Frame::none()
+                                .fill(tag_color(&tag.kategorie))
+                                .rounding(10.0)
+                                .inner_margin(egui::Margin::symmetric(7.0, 2.0))
+                                .show(ui, |ui| {
+                                    ui.label(
+                                        egui::RichText::new(&tag.wert)
+                                            .size(11.5)
+                                            .color(egui::Color32::WHITE),
+                                    );
+                                });
+                        }
+                    });
+                }
+
+                ui.add_space(6.0);
+                ui.horizontal_wrapped(|ui| {
+                    ui.spacing_mut().item_spacing = egui::vec2(4.0, 4.0);
+                    for link in links {
+                        let chip = egui::Button::new(
+                            egui::RichText::new(format!("{} {}", link_icon(&link.url), link.label))
+                                .size(11.5)
+                                .color(palette::TEXT_PRIMARY),
+                        )
+                        .fill(palette::bg_input())
+                        .rounding(10.0)
+                        .stroke(egui::Stroke::new(0.5, palette::accent_dim()));
+                        let resp = ui.add(chip).on_hover_text(&link.url);
+                        if resp.clicked() {
+                            open_url = Some(link.url.clone());
+                        }
+                        resp.context_menu(|ui| {
+                            if ui.button(tr("Link entfernen")).clicked() {
+                                remove_link = Some(link.id);
+                                ui.close_menu();
+                            }
+                        });
+                    }
+                    if link_form.is_none()
+                        && ui
+                            .small_button("+ Link")
+                            .on_hover_text(
+                                tr("Link zu Lernvideo, Aufnahme, Tab-Seite, Unterrichtsplan oder Notiz"),
+                            )
+                            .clicked()
+                    {
+                        *link_form = Some(LinkForm {
+                            song_id: song.id,
+                            label: String::new(),
+                            url: String::new(),
+                        });
+                    }
+                });
+                if let Some(form) = link_form.as_mut().filter(|f| f.song_id == song.id) {
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut form.label)
+                                .desired_width(80.0)
+                                .hint_text(tr("Bezeichnung")),
+                        );
+                        let url = ui.add(
+                            egui::TextEdit::singleline(&mut form.url)
+                                .desired_width(f32::INFINITY)
+                                .hint_text(tr("obsidian://\u{2026} oder https://\u{2026}")),
+                        );
+                        if url.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                            add_link = true;
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        if styled_small_button(ui, tr("Hinzuf\u{00FC}gen")).clicked() {
+                            add_link = true;
+                        }
+                        if styled_small_button(ui, tr("Abbrechen")).clicked() {
+                            *link_form = None;
+                        }
+                    });
+                }
+
+                ui.add_space(6.0);
+                companion_section(
+                    ui,
+                    song,
+                    companion_panel,
+                    playing.as_deref(),
+                    &mut companion_action,
+                );
+
+                if let Some(loaded) = song_arrangements {
+                    ui.add_space(6.0);
+                    arrangements_section(
+                        ui,
+                        song.id,
+                        loaded,
+                        arrangement_form,
+                        &mut arrangement_action,
+                    );
+                }
+
+                if let Some(path) = song_chain {
+                    ui.add_space(6.0);
+                    learning_path_section(
+                        ui,
+                        song.id,
+                        &song.titel,
+                        path,
+                        path_form,
+                        &mut path_action,
+                    );
+                }
+
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    ui.spacing_mut().item_spacing.x = 4.0;
+                    if styled_small_button(ui, tr("Datei \u{00F6}ffnen")).clicked() {
+                        open_file = true;
+                    }
+                    if styled_small_button(ui, "\u{1F4C2}")
+                        .on_hover_text(tr(crate::open_with::REVEAL_LABEL))
+                        .clicked()
+                    {
+                        reveal = true;
+                    }
+                    if styled_small_button(ui, tr("Bearbeiten")).clicked() {
+                        edit = true;
+                    }
+                    let viewable = [FileType::ChordPro, FileType::Text, FileType::Image]
+                        .iter()
+                        .any(|t| t.as_str() == song.dateityp);
+                    if viewable && styled_small_button(ui, tr("Anzeigen")).clicked()
+                    {
+                        view_chart = true;
+                    }
+                    if styled_small_button(ui, "MusicBrainz\u{2026}")
+                        .on_hover_text(tr("Artist, Jahr, Tonart und Stil online nachschlagen"))
+                        .clicked()
+                    {
+                        lookup = true;
+                    }
+                    if styled_small_button(ui, tr("Ignorieren"))
+                        .on_hover_text(tr("Kein Song \u{2014} aus Liste und Statistik ausblenden"))
+                        .clicked()
+                    {
+                        ignore = true;
+                    }
+                });
+
+                ui.add_space(8.0);
+                ui.separator();
+                ui.label(
+                    egui::RichText::new(tr("Notizen"))
+                        .size(13.0)
+                        .strong()
+                        .color(palette::TEXT_SECONDARY),
+                );
+                ui.add_space(4.0);
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| match song.notizen {
+                        Some(ref notizen) => show_markdown(ui, notizen),
+                        None => {
+                            ui.label(
+                                egui::RichText::new(
+                                    tr("Keine Notizen. \u{00DC}ber \u{201E}Bearbeiten\u{201C} hinzuf\u{00FC}gen."),
+                                )
+                                .size(12.5)
+                                .color(palette::TEXT_MUTED),
+                            );
+                        }
+                    });
+            });
+
+        let open_path = open_file.then(|| song.dateipfad.clone());
+        if edit {
+            self.edit_modal = Some(EditModalState::from_song(song));
+        }
+        if reveal {
+            crate::open_with::reveal(&self.base_dir.join(&song.dateipfad));
+        }
+        if lookup {
+            self.musicbrainz = Some(MusicBrainzState::new(song));
+        }
+        if view_chart {
+            let song_id = song.id;
+            if song.dateityp == FileType::Image.as_str() {
+                self.open_image_viewer(song_id);
+            } else {
+                self.open_chart_viewer(song_id);
+            }
+        }
+        if let Some(rel_path) = open_path {
+            self.open_song_file(&rel_path);
+        }
+        if let Some(url) = open_url {
+            crate::open_with::open_url(&url);
+        }
+        if add_link || remove_link.is_some() {
+            let form = self.link_form.take().filter(|_| add_link);
+            self.db.send(move |conn| {
+                if let Some(form) = form {
+                    let url = form.url.trim();
+                    if !url.is_empty() {
+                        let label = match form.label.trim() {
+                            "" => default_link_label(url),
+                            label => label.to_string(),
+                        };
+                        add_song_link(conn, form.song_id, &label, url);
+                    }
+                }
+                if let Some(id) = remove_link {
+                    remove_song_link(conn, id);
+                }
+            });
+            self.refresh_song(song_id);
+        }
+        if let Some(action) = companion_action {
+            self.apply_companion_action(song_id, action);
+        }
+        if let Some(action) = arrangement_action {
+            self.apply_arrangement_action(song_id, action);
+        }
+        if let Some(action) = path_action {
+            self.apply_path_action(song_id, action);
+        }
+        if ignore {
+            let song_id = self.songs[index].id;
+            self.db.send(move |conn| set_ignored(conn, song_id, true));
+            self.selected_song_id = None;
+            self.refresh_data();
+        }
+        if close {
+            self.selected_song_id = None;
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use super::{palette, SongIndexApp};
+use crate::db::set_difficulty_order;
+use crate::i18n::tr;
+use eframe::egui;
+
+impl SongIndexApp {
+    pub(super) fn open_difficulty(&mut self) {
+        self.difficulty_window = true;
+    }
+
+    /// "Stufen": order of the `schwierigkeit` values for the difficulty sort
+    /// and filter. Every change is stored right away.
+    pub(super) fn show_difficulty(&mut self, ctx: &egui::Context) {
+        if !self.difficulty_window {
+            return;
+        }
+
+        let mut ranked: Vec<i64> = self
+            .difficulty_levels
+            .iter()
+            .filter(|l| l.rang.is_some())
+            .map(|l| l.id)
+            .collect();
+        let mut changed = false;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Schwierigkeitsstufen"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(380.0)
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(tr(
+                    "Vom leichtesten zum schwersten. Die Reihenfolge gilt f\u{00FC}r die \
+                     Sortierung \u{201E}Schwierigkeit\u{201C} und den Filter; Werte ohne \
+                     Stufe kommen dort zuletzt.",
+                ))
+                .size(12.5)
+                .color(palette::TEXT_SECONDARY),
+            );
+            ui.add_space(6.0);
+            if self.difficulty_levels.is_empty() {
+                ui.label(
+                    egui::RichText::new(tr("Noch keine Werte in der Kategorie Schwierigkeit."))
+                        .color(palette::TEXT_MUTED),
+                );
+                return;
+            }
+
+            egui::Grid::new("difficulty_levels")
+                .num_columns(3)
+                .spacing([10.0, 4.0])
+                .show(ui, |ui| {
+                    let count = ranked.len();
+                    for (i, level) in self
+                        .difficulty_levels
+                        .iter()
+                        .filter(|l| l.rang.is_some())
+                        .enumerate()
+                    {
+                        ui.label(
+                            egui::RichText::new(format!("{}.", i + 1)).color(palette::TEXT_MUTED),
+                        );
+                        ui.label(&level.wert);
+                        ui.horizontal(|ui| {
+                            ui.spacing_mut().item_spacing.x = 4.0;
+                            if ui
+                                .add_enabled(i > 0, egui::Button::new("\u{2191}").small())
+                                .clicked()
+                            {
+                                ranked.swap(i, i - 1);
+                                changed = true;
+                            }
+                            if ui
+                                .add_enabled(i + 1 < count, egui::Button::new("\u{2193}").small())
+                                .clicked()
+                            {
+                                ranked.swap(i, i + 1);
+                                changed = true;
+                            }
+                            if ui
+                                .small_button("\u{00D7}")
+                                .on_hover_text(tr("Aus der Skala nehmen"))
+                                .clicked()
+                            {
+                                ranked.retain(|id| *id != level.id);
+                                changed = true;
+                            }
+                        });
+                        ui.end_row();
+                    }
+                    for level in self.difficulty_levels.iter().filter(|l| l.rang.is_none()) {
+                        ui.label(egui::RichText::new("\u{2013}").color(palette::TEXT_MUTED));
+                        ui.label(egui::RichText::new(&level.wert).color(palette::TEXT_MUTED));
+                        if ui
+                            .small_button(tr("Einordnen"))
+                            .on_hover_text(tr("Als schwerste Stufe anh\u{00E4}ngen"))
+                            .clicked()
+                        {
+                            ranked.push(level.id);
+                            changed = true;
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+
+        if changed {
+            self.db
+                .send(move |conn| set_difficulty_order(conn, &ranked));
+            self.refresh_data();
+        }
+        if !open {
+            self.difficulty_window = false;
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use super::pending::answer;
+use super::{palette, SongIndexApp};
+use crate::encryption::{
+    encrypt_database, forget_passphrase, is_encrypted, store_passphrase, stored_passphrase,
+};
+use crate::i18n::tr;
+use eframe::egui;
+use std::sync::mpsc::Receiver;
+
+pub(super) struct EncryptionModalState {
+    encrypted: bool,
+    in_keychain: bool,
+    passphrase: String,
+    confirm: String,
+    remember: bool,
+    error: Option<String>,
+    /// The database is being rewritten encrypted.
+    encrypting: Option<Receiver<Result<(), String>>>,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_encryption(&mut self) {
+        self.encryption_modal = Some(EncryptionModalState {
+            encrypted: is_encrypted(&self.config.database_path()),
+            in_keychain: stored_passphrase().is_some(),
+            passphrase: String::new(),
+            confirm: String::new(),
+            remember: true,
+            error: None,
+            encrypting: None,
+        });
+    }
+
+    pub(super) fn show_encryption_modal(&mut self, ctx: &egui::Context) {
+        let Some(ref mut modal) = self.encryption_modal else {
+            return;
+        };
+        match answer(&mut modal.encrypting) {
+            Some(Ok(())) => {
+                if modal.remember {
+                    if let Err(e) = store_passphrase(&modal.passphrase) {
+                        modal.error = Some(e);
+                    }
+                }
+                modal.encrypted = true;
+                modal.in_keychain = modal.remember && modal.error.is_none();
+                modal.passphrase.clear();
+                modal.confirm.clear();
+            }
+            Some(Err(e)) => modal.error = Some(e),
+            None => {}
+        }
+
+        let mut encrypt = false;
+        let mut close = false;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Bibliothek verschl\u{00FC}sseln"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .fixed_size([380.0, 200.0])
+        .show(ctx, |ui| {
+            if modal.encrypted {
+                ui.label(
+                    egui::RichText::new(tr("Die Datenbank ist mit SQLCipher verschl\u{00FC}sselt."))
+                        .color(palette::AUDIO_GREEN),
+                );
+                if let Some(ref err) = modal.error {
+                    ui.label(egui::RichText::new(err).color(palette::ACCENT_RED));
+                }
+                ui.add_space(8.0);
+                if modal.in_keychain {
+                    ui.label(
+                        egui::RichText::new(
+                            tr("Das Passwort liegt im Schl\u{00FC}sselbund; beim Start wird nicht gefragt."),
+                        )
+                        .size(12.5)
+                        .color(palette::TEXT_MUTED),
+                    );
+                    if ui.button(tr("Aus Schl\u{00FC}sselbund entfernen")).clicked() {
+                        forget_passphrase();
+                        modal.in_keychain = false;
+                    }
+                } else {
+                    ui.label(
+                        egui::RichText::new(tr("Das Passwort wird bei jedem Start abgefragt."))
+                            .size(12.5)
+                            .color(palette::TEXT_MUTED),
+                    );
+                }
+                return;
+            }
+
+            ui.label(
+                egui::RichText::new(
+                    tr("Ohne das Passwort l\u{00E4}sst sich die Bibliothek nicht mehr \u{00F6}ffnen."),
+                )
+                .size(12.5)
+                .color(palette::TEXT_MUTED),
+            );
+            ui.add_space(6.0);
+            egui::Grid::new("encryption_grid")
+                .num_columns(2)
+                .spacing([8.0, 6.0])
+                .show(ui, |ui| {
+                    ui.label(egui::RichText::new(tr("Passwort:")).color(palette::TEXT_SECONDARY));
+                    ui.add(egui::TextEdit::singleline(&mut modal.passphrase).password(true));
+                    ui.end_row();
+
+                    ui.label(egui::RichText::new(tr("Wiederholen:")).color(palette::TEXT_SECONDARY));
+                    ui.add(egui::TextEdit::singleline(&mut modal.confirm).password(true));
+                    ui.end_row();
+                });
+            ui.checkbox(&mut modal.remember, tr("Im Schl\u{00FC}sselbund speichern"));
+
+            if let Some(ref err) = modal.error {
+                ui.label(egui::RichText::new(err).color(palette::ACCENT_RED));
+            }
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                let btn = egui::Button::new(
+                    egui::RichText::new(tr("Verschl\u{00FC}sseln")).color(palette::TEXT_PRIMARY),
+                )
+                .fill(palette::accent_dim())
+                .rounding(6.0);
+                let valid = !modal.passphrase.is_empty() && modal.passphrase == modal.confirm;
+                if ui
+                    .add_enabled(valid && modal.encrypting.is_none(), btn)
+                    .clicked()
+                {
+                    encrypt = true;
+                }
+                if modal.encrypting.is_some() {
+                    ui.spinner();
+                }
+                ui.add_space(4.0);
+                if ui.button(tr("Abbrechen")).clicked() {
+                    close = true;
+                }
+            });
+        });
+
+        if encrypt {
+            let db_path = self.config.database_path();
+            let passphrase = modal.passphrase.clone();
+            modal.error = None;
+            modal.encrypting = Some(
+                self.db
+                    .request(move |conn| encrypt_database(conn, &db_path, &passphrase)),
+            );
+        }
+
+        if !open || close {
+            self.encryption_modal = None;
+        }
+    }
+}
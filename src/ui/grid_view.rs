@@ -0,0 +1,133 @@
+use super::{palette, song_context_menu, SongAction, SongIndexApp};
+use crate::i18n::tr;
+use crate::thumbnails::ThumbnailCache;
+use eframe::egui;
+
+const TILE_WIDTH: f32 = 150.0;
+const THUMB_HEIGHT: f32 = 190.0;
+const TILE_SPACING: f32 = 10.0;
+
+impl SongIndexApp {
+    /// Render the songs as a gallery of first-page thumbnails with the title underneath.
+    pub(super) fn show_song_grid(&mut self, ui: &mut egui::Ui, action: &mut Option<SongAction>) {
+        let scroll_target = if self.scroll_to_selected {
+            self.selected_index()
+        } else {
+            None
+        };
+        let cache = self.thumbnails.get_or_insert_with(|| {
+            ThumbnailCache::new(
+                self.base_dir.clone(),
+                self.jobs.clone(),
+                self.config.thumbnail_cache_bytes(),
+                ui.ctx().clone(),
+            )
+        });
+
+        if self.songs.is_empty() {
+            ui.add_space(40.0);
+            ui.vertical_centered(|ui| {
+                if self.list_loading() {
+                    ui.spinner();
+                    return;
+                }
+                ui.label(
+                    egui::RichText::new(tr("Keine Songs gefunden."))
+                        .size(16.0)
+                        .color(palette::TEXT_MUTED),
+                );
+            });
+            return;
+        }
+
+        let columns = ((ui.available_width() + TILE_SPACING) / (TILE_WIDTH + TILE_SPACING))
+            .floor()
+            .max(1.0) as usize;
+        let rows = self.songs.len().div_ceil(columns);
+        let row_height = THUMB_HEIGHT + 44.0;
+
+        let mut scroll = egui::ScrollArea::vertical().auto_shrink([false, false]);
+        if let Some(offset) = self.scroll_memory.pending_restore() {
+            scroll = scroll.vertical_scroll_offset(offset);
+        }
+        if let Some(index) = scroll_target {
+            let row = (index / columns) as f32;
+            scroll =
+                scroll.vertical_scroll_offset(row * (row_height + ui.spacing().item_spacing.y));
+        }
+        let output = scroll.show_rows(ui, row_height, rows, |ui, range| {
+            for row in range {
+                ui.horizontal(|ui| {
+                    ui.spacing_mut().item_spacing.x = TILE_SPACING;
+                    for song in self.songs.iter().skip(row * columns).take(columns) {
+                        let selected = self.selected_song_id == Some(song.id);
+                        let resp = egui::Frame::none()
+                            .fill(palette::bg_card())
+                            .inner_margin(egui::Margin::same(6.0))
+                            .rounding(8.0)
+                            .stroke(if selected {
+                                egui::Stroke::new(1.5, palette::accent())
+                            } else {
+                                egui::Stroke::new(0.5, palette::border_subtle())
+                            })
+                            .show(ui, |ui| {
+                                ui.set_width(TILE_WIDTH - 12.0);
+                                let size = egui::vec2(TILE_WIDTH - 12.0, THUMB_HEIGHT);
+                                match cache.get(&song.dateipfad) {
+                                    Some(path) => {
+                                        ui.add_sized(
+                                            size,
+                                            egui::Image::new(format!("file://{}", path.display()))
+                                                .maintain_aspect_ratio(true)
+                                                .rounding(4.0),
+                                        );
+                                    }
+                                    None => {
+                                        let (rect, _) =
+                                            ui.allocate_exact_size(size, egui::Sense::hover());
+                                        ui.painter().rect_filled(rect, 4.0, palette::bg_input());
+                                        ui.painter().text(
+                                            rect.center(),
+                                            egui::Align2::CENTER_CENTER,
+                                            "\u{266B}",
+                                            egui::FontId::proportional(32.0),
+                                            palette::TEXT_MUTED,
+                                        );
+                                    }
+                                }
+                                ui.add(
+                                    egui::Label::new(
+                                        egui::RichText::new(&song.titel)
+                                            .size(12.5)
+                                            .color(palette::TEXT_PRIMARY),
+                                    )
+                                    .truncate(),
+                                );
+                                if let Some(ref artist) = song.artist {
+                                    ui.add(
+                                        egui::Label::new(
+                                            egui::RichText::new(artist)
+                                                .size(11.5)
+                                                .color(palette::TEXT_SECONDARY),
+                                        )
+                                        .truncate(),
+                                    );
+                                }
+                            })
+                            .response
+                            .interact(egui::Sense::click())
+                            .on_hover_text(&song.dateipfad);
+                        if resp.double_clicked() {
+                            *action = Some(SongAction::Open(song.id));
+                        } else if resp.clicked() {
+                            *action = Some(SongAction::Select(song.id));
+                        }
+                        song_context_menu(&resp, song, action);
+                    }
+                });
+                ui.add_space(TILE_SPACING);
+            }
+        });
+        self.scroll_memory.record(output.state.offset.y);
+    }
+}
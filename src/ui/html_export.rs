@@ -0,0 +1,118 @@
+use super::{palette, SongIndexApp};
+use crate::html::{export_repertoire, Links};
+use crate::i18n::{tr, tr_arg};
+use eframe::egui;
+
+pub(super) struct HtmlExportState {
+    titel: String,
+    /// Light, numbered layout without the search box.
+    print: bool,
+    links: bool,
+    /// Empty: links relative to the page, for a copy in the music folder.
+    link_base: String,
+    message: Option<String>,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_html_export(&mut self) {
+        self.html_export = Some(HtmlExportState {
+            titel: "Repertoire".to_string(),
+            print: false,
+            links: false,
+            link_base: String::new(),
+            message: None,
+        });
+    }
+
+    pub(super) fn show_html_export(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.html_export else {
+            return;
+        };
+
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Als HTML exportieren"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .fixed_size([420.0, 240.0])
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(tr_arg(
+                    "Exportiert die aktuelle Liste ({} Songs) mit Artist, Tonart und Tags.",
+                    self.songs.len(),
+                ))
+                .color(palette::TEXT_SECONDARY),
+            );
+            ui.add_space(6.0);
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(tr("Titel:")).color(palette::TEXT_SECONDARY));
+                ui.text_edit_singleline(&mut state.titel);
+            });
+            ui.checkbox(&mut state.print, tr("Zum Drucken oder Verschicken"))
+                .on_hover_text(tr("Helles Layout mit Nummern, ohne Suchfeld"));
+            ui.checkbox(&mut state.links, tr("Titel mit den Dateien verlinken"));
+            if state.links {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(tr("Basis-URL:")).color(palette::TEXT_SECONDARY));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut state.link_base)
+                            .hint_text(tr("leer = relativ zum Musikordner")),
+                    );
+                });
+            }
+            ui.add_space(8.0);
+
+            let save = egui::Button::new(
+                egui::RichText::new(tr("Speichern\u{2026}")).color(palette::TEXT_PRIMARY),
+            )
+            .fill(palette::accent_dim())
+            .rounding(6.0);
+            if ui.add(save).clicked() {
+                let mut dialog = rfd::FileDialog::new()
+                    .set_title(tr("Repertoire-Seite speichern"))
+                    .set_file_name(format!("{}.html", state.titel.trim()))
+                    .add_filter("HTML", &["html"]);
+                if state.links && state.link_base.trim().is_empty() {
+                    dialog = dialog.set_directory(&self.base_dir);
+                }
+                if let Some(path) = dialog.save_file() {
+                    let links = match (state.links, state.link_base.trim()) {
+                        (false, _) => Links::None,
+                        (true, "") => Links::Relative,
+                        (true, base) => Links::Base(base.to_string()),
+                    };
+                    let stand = self.format.today();
+                    state.message = Some(
+                        match export_repertoire(
+                            &path,
+                            state.titel.trim(),
+                            &stand,
+                            &self.songs,
+                            &links,
+                            state.print,
+                        ) {
+                            Ok(()) => tr_arg("Gespeichert unter {}", path.display()),
+                            Err(e) => e,
+                        },
+                    );
+                }
+            }
+            if let Some(ref message) = state.message {
+                ui.add_space(4.0);
+                ui.label(
+                    egui::RichText::new(message)
+                        .size(12.5)
+                        .color(palette::TEXT_MUTED),
+                );
+            }
+        });
+
+        if !open {
+            self.html_export = None;
+        }
+    }
+}
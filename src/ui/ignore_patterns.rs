@@ -0,0 +1,150 @@
+use super::pending::answer;
+use super::{palette, save_config, SongIndexApp};
+use crate::db::query_rows;
+use crate::i18n::{tr, tr_arg};
+use crate::scanner::{ignore_set, ScanOptions};
+use eframe::egui;
+use std::sync::mpsc::Receiver;
+
+pub(super) struct IgnorePatternsState {
+    /// One pattern per line.
+    text: String,
+    errors: Vec<String>,
+    /// Indexed songs, to show how many the patterns would hide.
+    paths: Vec<String>,
+    /// The indexed paths, until they are loaded.
+    loading: Option<Receiver<Vec<String>>>,
+    affected: usize,
+}
+
+impl IgnorePatternsState {
+    fn validate(&mut self) {
+        let (set, errors) = ignore_set(&self.patterns());
+        self.errors = errors;
+        self.affected = self
+            .paths
+            .iter()
+            .filter(|p| set.is_match(p.as_str()))
+            .count();
+    }
+
+    fn patterns(&self) -> Vec<String> {
+        self.text
+            .lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect()
+    }
+}
+
+impl SongIndexApp {
+    pub(super) fn open_ignore_patterns(&mut self) {
+        let loading = self.db.request(|conn| {
+            query_rows(
+                conn,
+                "SELECT dateipfad FROM songs WHERE deleted_at IS NULL",
+                [],
+                |row| row.get(0),
+            )
+        });
+        let mut state = IgnorePatternsState {
+            text: self.config.ignore_patterns.join("\n"),
+            errors: Vec::new(),
+            paths: Vec::new(),
+            loading: Some(loading),
+            affected: 0,
+        };
+        state.validate();
+        self.ignore_patterns = Some(state);
+    }
+
+    pub(super) fn show_ignore_patterns(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.ignore_patterns else {
+            return;
+        };
+        if let Some(paths) = answer(&mut state.loading) {
+            state.paths = paths;
+            state.validate();
+        }
+
+        let mut apply = false;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Ausschl\u{00FC}sse"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .fixed_size([420.0, 320.0])
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(tr(
+                    "Ein Muster pro Zeile, relativ zum Musikordner. * passt auf beliebige \
+                     Zeichen (auch in Unterordnern), ** auf beliebig viele Ordner.",
+                ))
+                .size(12.5)
+                .color(palette::TEXT_SECONDARY),
+            );
+            ui.add_space(4.0);
+            let edit = ui.add(
+                egui::TextEdit::multiline(&mut state.text)
+                    .desired_rows(8)
+                    .desired_width(f32::INFINITY)
+                    .code_editor()
+                    .hint_text(tr("**/Archiv/**\n*_old.pdf")),
+            );
+            if edit.changed() {
+                state.validate();
+            }
+            for error in &state.errors {
+                ui.label(
+                    egui::RichText::new(error)
+                        .size(12.0)
+                        .color(palette::ACCENT_RED),
+                );
+            }
+            if state.loading.is_some() {
+                ui.spinner();
+            } else {
+                ui.label(
+                    egui::RichText::new(tr_arg(
+                        "{} indizierte Songs passen auf die Muster",
+                        state.affected,
+                    ))
+                    .size(12.5)
+                    .color(palette::TEXT_MUTED),
+                );
+            }
+            ui.add_space(8.0);
+            let apply_btn = egui::Button::new(
+                egui::RichText::new(tr("\u{00DC}bernehmen und neu scannen"))
+                    .color(palette::TEXT_PRIMARY),
+            )
+            .fill(palette::accent_dim())
+            .rounding(6.0);
+            if ui.add(apply_btn).clicked() {
+                apply = true;
+            }
+            ui.label(
+                egui::RichText::new(tr(
+                    "Ausgeschlossene Songs landen im Papierkorb und kommen mit ihren Tags \
+                     zur\u{00FC}ck, sobald das Muster entfernt wird.",
+                ))
+                .size(11.5)
+                .color(palette::TEXT_MUTED),
+            );
+        });
+
+        if apply {
+            self.config.ignore_patterns = state.patterns();
+            save_config(&self.config);
+            *self.scan_options.write().unwrap() = ScanOptions::from(&self.config);
+            self.ignore_patterns = None;
+            self.rescan();
+        } else if !open {
+            self.ignore_patterns = None;
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use super::pending::Loaded;
+use super::{palette, styled_small_button, SongIndexApp};
+use crate::db::{ignored_songs, set_ignored};
+use crate::i18n::tr;
+use eframe::egui;
+
+pub(super) struct IgnoredWindowState {
+    /// (id, titel, dateipfad)
+    songs: Loaded<Vec<(i64, String, String)>>,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_ignored(&mut self) {
+        self.ignored_window = Some(IgnoredWindowState {
+            songs: Loaded::new(self.db.request(|conn| ignored_songs(conn))),
+        });
+    }
+
+    pub(super) fn show_ignored_window(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.ignored_window else {
+            return;
+        };
+
+        let mut unignore = None;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Ignorierte Dateien"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([480.0, 340.0])
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(tr(
+                    "Dateien, die keine Songs sind. Sie tauchen weder in der Liste noch in \
+                     der Statistik auf, bleiben aber beim Scan erkannt.",
+                ))
+                .size(12.5)
+                .color(palette::TEXT_SECONDARY),
+            );
+            ui.add_space(4.0);
+            let Some(songs) = state.songs.get() else {
+                ui.spinner();
+                return;
+            };
+            if songs.is_empty() {
+                ui.label(
+                    egui::RichText::new(tr("Keine ignorierten Dateien."))
+                        .color(palette::TEXT_MUTED),
+                );
+                return;
+            }
+            ui.separator();
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    for (id, titel, dateipfad) in songs.iter() {
+                        ui.horizontal(|ui| {
+                            ui.vertical(|ui| {
+                                ui.label(egui::RichText::new(titel).color(palette::TEXT_PRIMARY));
+                                ui.label(
+                                    egui::RichText::new(dateipfad)
+                                        .size(11.5)
+                                        .color(palette::TEXT_MUTED),
+                                );
+                            });
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    if styled_small_button(ui, tr("Wieder anzeigen")).clicked() {
+                                        unignore = Some(*id);
+                                    }
+                                },
+                            );
+                        });
+                        ui.add_space(2.0);
+                    }
+                });
+        });
+
+        if let Some(id) = unignore {
+            state.songs.reload(self.db.request(move |conn| {
+                set_ignored(conn, id, false);
+                ignored_songs(conn)
+            }));
+            self.refresh_data();
+        }
+        if !open {
+            self.ignored_window = None;
+        }
+    }
+}
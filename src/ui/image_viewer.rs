@@ -0,0 +1,119 @@
+use super::{palette, SongIndexApp};
+use crate::i18n::tr;
+use crate::jobs::{JobKind, Priority};
+use crate::thumbnails::{is_fresh, preview_path, preview_uri};
+use eframe::egui;
+
+pub(super) struct ImageViewerState {
+    titel: String,
+    dateipfad: String,
+    /// Fit the image to the window width instead of showing it at full size.
+    fit: bool,
+    /// Conversion job already queued, so a failed one is not retried every frame.
+    requested: bool,
+}
+
+/// What the viewer can show for the current image.
+enum Shown {
+    Ready(String),
+    Converting,
+    Failed,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_image_viewer(&mut self, song_id: i64) {
+        let Some(song) = self.songs.iter().find(|s| s.id == song_id) else {
+            return;
+        };
+        self.image_viewer = Some(ImageViewerState {
+            titel: song.titel.clone(),
+            dateipfad: song.dateipfad.clone(),
+            fit: true,
+            requested: false,
+        });
+    }
+
+    pub(super) fn show_image_viewer(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.image_viewer else {
+            return;
+        };
+
+        // egui decodes PNG itself; JPEG and HEIC scans are shown through a
+        // converted copy rendered by the job queue.
+        let source = self.base_dir.join(&state.dateipfad);
+        let is_png = source
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("png"));
+        let shown = if is_png {
+            Shown::Ready(format!("file://{}", source.display()))
+        } else if is_fresh(&preview_path(&state.dateipfad), &source) {
+            Shown::Ready(preview_uri(&state.dateipfad))
+        } else if !state.requested {
+            self.jobs.push(
+                JobKind::Preview,
+                &state.dateipfad,
+                source,
+                Priority::Visible,
+            );
+            state.requested = true;
+            Shown::Converting
+        } else if self.jobs.is_pending(JobKind::Preview, &state.dateipfad) {
+            Shown::Converting
+        } else {
+            Shown::Failed
+        };
+
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(&state.titel)
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .id(egui::Id::new("image_viewer"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([560.0, 720.0])
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut state.fit, true, tr("Einpassen"));
+                ui.selectable_value(&mut state.fit, false, "100 %");
+            });
+            ui.add_space(4.0);
+            let uri = match shown {
+                Shown::Ready(uri) => uri,
+                Shown::Converting => {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(
+                            egui::RichText::new(tr("Bild wird vorbereitet\u{2026}"))
+                                .color(palette::TEXT_MUTED),
+                        );
+                    });
+                    return;
+                }
+                Shown::Failed => {
+                    ui.label(
+                        egui::RichText::new(tr("Bild konnte nicht umgewandelt werden."))
+                            .color(palette::TEXT_MUTED),
+                    );
+                    return;
+                }
+            };
+            egui::ScrollArea::both()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    let image = egui::Image::new(uri).fit_to_original_size(1.0);
+                    if state.fit {
+                        ui.add(image.max_width(ui.available_width()));
+                    } else {
+                        ui.add(image);
+                    }
+                });
+        });
+
+        if !open {
+            self.image_viewer = None;
+        }
+    }
+}
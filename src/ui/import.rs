@@ -0,0 +1,237 @@
+use super::pending::Loaded;
+use super::{category_label, palette, SongIndexApp, CATEGORY_LABELS};
+use crate::i18n::{tr, tr_arg, tr_args};
+use crate::importer::{guess_target, import_rows, read_csv, ColumnTarget, CsvData, ImportResult};
+use eframe::egui;
+use std::path::PathBuf;
+
+pub(super) struct ImportModalState {
+    path: PathBuf,
+    data: CsvData,
+    mapping: Vec<ColumnTarget>,
+    error: Option<String>,
+    /// Set once the import ran; loading until it is done.
+    result: Option<Loaded<ImportResult>>,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_csv_import(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .set_title(tr("CSV-Datei ausw\u{00E4}hlen"))
+            .add_filter("CSV", &["csv", "txt"])
+            .pick_file()
+        else {
+            return;
+        };
+
+        self.import_modal = Some(match read_csv(&path) {
+            Ok(data) => {
+                let mapping = data.headers.iter().map(|h| guess_target(h)).collect();
+                ImportModalState {
+                    path,
+                    data,
+                    mapping,
+                    error: None,
+                    result: None,
+                }
+            }
+            Err(e) => ImportModalState {
+                path,
+                data: CsvData {
+                    headers: Vec::new(),
+                    rows: Vec::new(),
+                },
+                mapping: Vec::new(),
+                error: Some(e),
+                result: None,
+            },
+        });
+    }
+
+    pub(super) fn show_import_modal(&mut self, ctx: &egui::Context) {
+        let mut close = false;
+        let mut run_import = false;
+        let Some(ref mut modal) = self.import_modal else {
+            return;
+        };
+
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("CSV importieren"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([560.0, 420.0])
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(modal.path.display().to_string())
+                    .size(12.0)
+                    .color(palette::TEXT_MUTED),
+            );
+            ui.add_space(6.0);
+
+            if let Some(ref err) = modal.error {
+                ui.label(
+                    egui::RichText::new(tr_arg("Datei konnte nicht gelesen werden: {}", err))
+                        .color(palette::ACCENT_RED),
+                );
+                return;
+            }
+
+            if let Some(result) = modal.result.as_mut() {
+                let Some(result) = result.get() else {
+                    ui.spinner();
+                    return;
+                };
+                ui.label(
+                    egui::RichText::new(tr_args(
+                        "{} Songs zugeordnet, {} Tags gesetzt.",
+                        &[&result.matched, &result.tags_added],
+                    ))
+                    .color(palette::TEXT_PRIMARY),
+                );
+                if !result.unmatched.is_empty() {
+                    ui.add_space(4.0);
+                    egui::CollapsingHeader::new(tr_arg(
+                        "{} Zeilen ohne passenden Song",
+                        result.unmatched.len(),
+                    ))
+                    .show(ui, |ui| {
+                        egui::ScrollArea::vertical()
+                            .max_height(240.0)
+                            .show(ui, |ui| {
+                                for label in &result.unmatched {
+                                    ui.label(
+                                        egui::RichText::new(label)
+                                            .size(12.5)
+                                            .color(palette::TEXT_SECONDARY),
+                                    );
+                                }
+                            });
+                    });
+                }
+                ui.add_space(8.0);
+                if ui.button(tr("Schliessen")).clicked() {
+                    close = true;
+                }
+                return;
+            }
+
+            ui.label(
+                egui::RichText::new(tr_arg(
+                    "{} Zeilen gefunden. Spalten zuordnen:",
+                    modal.data.rows.len(),
+                ))
+                .color(palette::TEXT_SECONDARY),
+            );
+            ui.add_space(4.0);
+
+            let mut targets = vec![
+                ColumnTarget::Ignore,
+                ColumnTarget::Titel,
+                ColumnTarget::Artist,
+                ColumnTarget::Dateiname,
+            ];
+            targets.extend(
+                CATEGORY_LABELS
+                    .iter()
+                    .filter(|(k, _)| *k != "artist")
+                    .map(|(k, _)| ColumnTarget::Tag(k.to_string())),
+            );
+
+            egui::ScrollArea::vertical()
+                .max_height(280.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("csv_mapping")
+                        .num_columns(3)
+                        .spacing([12.0, 6.0])
+                        .show(ui, |ui| {
+                            for (i, header) in modal.data.headers.iter().enumerate() {
+                                ui.label(egui::RichText::new(header).color(palette::TEXT_PRIMARY));
+                                let sample = modal
+                                    .data
+                                    .rows
+                                    .first()
+                                    .and_then(|r| r.get(i))
+                                    .map(|s| s.as_str())
+                                    .unwrap_or_default();
+                                ui.label(
+                                    egui::RichText::new(sample)
+                                        .size(12.0)
+                                        .color(palette::TEXT_MUTED),
+                                );
+                                egui::ComboBox::from_id_salt(("csv_target", i))
+                                    .selected_text(target_label(&modal.mapping[i]))
+                                    .show_ui(ui, |ui| {
+                                        for target in &targets {
+                                            ui.selectable_value(
+                                                &mut modal.mapping[i],
+                                                target.clone(),
+                                                target_label(target),
+                                            );
+                                        }
+                                    });
+                                ui.end_row();
+                            }
+                        });
+                });
+
+            let can_import = modal
+                .mapping
+                .iter()
+                .any(|t| matches!(t, ColumnTarget::Titel | ColumnTarget::Dateiname));
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                let import_btn = egui::Button::new(
+                    egui::RichText::new(tr("Importieren")).color(palette::TEXT_PRIMARY),
+                )
+                .fill(palette::accent_dim())
+                .rounding(6.0);
+                if ui.add_enabled(can_import, import_btn).clicked() {
+                    run_import = true;
+                }
+                ui.add_space(4.0);
+                if ui.button(tr("Abbrechen")).clicked() {
+                    close = true;
+                }
+                if !can_import {
+                    ui.label(
+                        egui::RichText::new(tr("Titel- oder Dateiname-Spalte n\u{00F6}tig"))
+                            .size(12.0)
+                            .color(palette::TEXT_MUTED),
+                    );
+                }
+            });
+        });
+
+        if run_import {
+            if let Some(ref mut modal) = self.import_modal {
+                let (data, mapping) = (modal.data.clone(), modal.mapping.clone());
+                modal.result = Some(Loaded::new(
+                    self.db
+                        .request(move |conn| import_rows(conn, &data, &mapping)),
+                ));
+            }
+            // Queued after the import, so it sees the new tags.
+            self.refresh_data();
+        }
+
+        if !open || close {
+            self.import_modal = None;
+        }
+    }
+}
+
+fn target_label(target: &ColumnTarget) -> String {
+    match target {
+        ColumnTarget::Ignore => tr("Ignorieren").to_string(),
+        ColumnTarget::Titel => tr("Titel").to_string(),
+        ColumnTarget::Artist => "Artist".to_string(),
+        ColumnTarget::Dateiname => tr("Dateiname").to_string(),
+        ColumnTarget::Tag(kategorie) => format!("Tag: {}", category_label(kategorie)),
+    }
+}
@@ -0,0 +1,110 @@
+use super::{palette, save_config, SongIndexApp};
+use crate::i18n::tr;
+use crate::lan::LanServer;
+use eframe::egui;
+
+pub(super) struct LanWindowState {
+    message: Option<String>,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_lan(&mut self) {
+        self.lan_window = Some(LanWindowState { message: None });
+    }
+
+    /// Start or stop the server to match `config.lan.aktiv`.
+    pub(super) fn apply_lan(&mut self) -> Result<(), String> {
+        self.lan_server = None;
+        if self.config.lan.aktiv {
+            self.lan_server = Some(LanServer::start(
+                self.db.clone(),
+                self.base_dir.clone(),
+                self.config.lan.port,
+            )?);
+        }
+        Ok(())
+    }
+
+    pub(super) fn show_lan_window(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.lan_window else {
+            return;
+        };
+
+        let mut toggled = false;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Im WLAN freigeben"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .fixed_size([420.0, 220.0])
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(tr(
+                    "Sch\u{00FC}ler im selben WLAN k\u{00F6}nnen die Bibliothek im Browser \
+                     durchsuchen und PDFs und Audio \u{00F6}ffnen, ohne etwas zu installieren. \
+                     Nur lesen; als privat markierte Songs, Notizen und Bewertungen bleiben \
+                     drau\u{00DF}en.",
+                ))
+                .size(12.5)
+                .color(palette::TEXT_SECONDARY),
+            );
+            ui.add_space(6.0);
+            ui.horizontal(|ui| {
+                toggled = ui
+                    .checkbox(&mut self.config.lan.aktiv, tr("Freigabe an"))
+                    .changed();
+                ui.label(egui::RichText::new("Port:").color(palette::TEXT_SECONDARY));
+                ui.add_enabled(
+                    !self.config.lan.aktiv,
+                    egui::DragValue::new(&mut self.config.lan.port).range(1024..=65535),
+                );
+            });
+            if let Some(ref server) = self.lan_server {
+                ui.add_space(6.0);
+                let url = server.url();
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(&url)
+                            .size(16.0)
+                            .strong()
+                            .color(palette::accent()),
+                    );
+                    if ui.small_button(tr("Kopieren")).clicked() {
+                        ui.ctx().copy_text(url.clone());
+                    }
+                });
+                ui.label(
+                    egui::RichText::new(tr("Diese Adresse im Browser des Handys eingeben."))
+                        .size(11.5)
+                        .color(palette::TEXT_MUTED),
+                );
+            }
+            if let Some(ref message) = state.message {
+                ui.add_space(4.0);
+                ui.label(
+                    egui::RichText::new(message)
+                        .size(12.5)
+                        .color(palette::TEXT_MUTED),
+                );
+            }
+        });
+
+        if toggled {
+            let result = self.apply_lan();
+            if result.is_err() {
+                self.config.lan.aktiv = false;
+            }
+            save_config(&self.config);
+            if let Some(ref mut state) = self.lan_window {
+                state.message = result.err();
+            }
+        }
+        if !open {
+            self.lan_window = None;
+        }
+    }
+}
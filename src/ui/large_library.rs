@@ -0,0 +1,125 @@
+use super::pending::Loaded;
+use super::{palette, save_config, styled_small_button, SongIndexApp};
+use crate::i18n::{tr, tr_arg};
+use crate::scanner::{largest_folders, ScanOptions};
+use eframe::egui;
+
+/// Folders offered for exclusion.
+const FOLDERS_SHOWN: usize = 12;
+
+pub(super) struct LargeLibraryState {
+    songs: i64,
+    /// (folder, songs in it), largest first.
+    folders: Loaded<Vec<(String, usize)>>,
+}
+
+impl SongIndexApp {
+    /// Open the warning if the library has grown past `scan_warn_limit`.
+    pub(super) fn check_library_size(&mut self) {
+        let limit = self.config.scan_warn_limit;
+        if limit == 0 || self.stats.total_songs <= limit as i64 {
+            return;
+        }
+        self.large_library = Some(LargeLibraryState {
+            songs: self.stats.total_songs,
+            folders: Loaded::new(self.db.request(|conn| largest_folders(conn, FOLDERS_SHOWN))),
+        });
+    }
+
+    /// Add an ignore pattern for `folder` and scan again, which moves its
+    /// songs to the Papierkorb. Also offered in the scan report.
+    pub(super) fn exclude_folder(&mut self, folder: &str) {
+        self.config
+            .ignore_patterns
+            .push(format!("{}/**", globset::escape(folder)));
+        save_config(&self.config);
+        *self.scan_options.write().unwrap() = ScanOptions::from(&self.config);
+        self.rescan();
+    }
+
+    pub(super) fn show_large_library(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.large_library else {
+            return;
+        };
+
+        let mut exclude = None;
+        let mut silence = false;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Gro\u{00DF}e Bibliothek"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([480.0, 380.0])
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(tr_arg(
+                    "Der Scan hat {} Songs gefunden. Ist ein ganzes Notenarchiv dabei, das \
+                     nicht in den Index geh\u{00F6}rt, l\u{00E4}sst es sich hier ausschlie\u{00DF}en.",
+                    self.format.number(state.songs),
+                ))
+                .size(12.5)
+                .color(palette::TEXT_SECONDARY),
+            );
+            ui.add_space(4.0);
+            ui.separator();
+            let Some(folders) = state.folders.get() else {
+                ui.spinner();
+                return;
+            };
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, true])
+                .max_height(240.0)
+                .show(ui, |ui| {
+                    for (folder, count) in folders.iter() {
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new(folder).color(palette::TEXT_PRIMARY));
+                            ui.label(
+                                egui::RichText::new(tr_arg(
+                                    "{} Songs",
+                                    self.format.number(*count as i64),
+                                ))
+                                    .size(12.0)
+                                    .color(palette::TEXT_MUTED),
+                            );
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    if styled_small_button(ui, tr("Ausschlie\u{00DF}en")).clicked() {
+                                        exclude = Some(folder.clone());
+                                    }
+                                },
+                            );
+                        });
+                    }
+                });
+            ui.add_space(8.0);
+            if ui.button(tr("Nicht mehr warnen")).clicked() {
+                silence = true;
+            }
+            ui.label(
+                egui::RichText::new(
+                    tr("Ausschlie\u{00DF}en legt ein Muster unter \u{201E}Ausschl\u{00FC}sse\u{2026}\u{201C} \
+                     an und scannt neu; die Songs landen im Papierkorb."),
+                )
+                .size(11.5)
+                .color(palette::TEXT_MUTED),
+            );
+        });
+
+        if let Some(folder) = exclude {
+            self.large_library = None;
+            self.exclude_folder(&folder);
+            self.check_library_size();
+        } else if silence {
+            self.config.scan_warn_limit = 0;
+            save_config(&self.config);
+            self.large_library = None;
+        } else if !open {
+            self.large_library = None;
+        }
+    }
+}
@@ -0,0 +1,440 @@
+use super::pending::{answer, Loaded};
+use super::{palette, styled_small_button, SongIndexApp};
+use crate::db::{query_songs, ExtraFilters, SortMode, TagFilter};
+use crate::i18n::{tr, tr_arg};
+use crate::progression::{
+    add_prerequisite, chain, next_songs, remove_prerequisite, Chain, NextSong, PathSong,
+};
+use crate::repertoire::set_repertoire;
+use eframe::egui;
+use std::sync::mpsc::Receiver;
+
+/// Matches listed under the search field of the link form.
+const MAX_MATCHES: usize = 8;
+
+/// The "+ Davor" / "+ Danach" form in the detail panel.
+pub(super) struct PathForm {
+    song_id: i64,
+    /// Link a song after the selected one instead of before it.
+    danach: bool,
+    query: String,
+    searched: Option<String>,
+    matches: Loaded<Vec<(i64, String, Option<String>)>>,
+    error: Option<String>,
+    /// Whether the link being added could be made, until it is known.
+    linking: Option<Receiver<bool>>,
+    /// Put the cursor in the search field on the next frame.
+    focus: bool,
+}
+
+/// Something clicked in the learning path section.
+pub(super) enum PathAction {
+    Select(i64),
+    NewForm { danach: bool },
+    Link(i64),
+    Unlink { song_id: i64, vorher_id: i64 },
+}
+
+/// "Als Nächstes": songs whose prerequisites are all "kann ich".
+pub(super) struct NextSongsState {
+    songs: Loaded<Vec<NextSong>>,
+}
+
+enum NextAction {
+    Open(String),
+    Show(i64),
+    Learned(i64),
+}
+
+fn path_chip(ui: &mut egui::Ui, song: &PathSong) -> egui::Response {
+    let text = if song.kann_ich {
+        egui::RichText::new(format!("\u{2713} {}", song.titel)).color(palette::AUDIO_GREEN)
+    } else {
+        egui::RichText::new(&song.titel).color(palette::TEXT_PRIMARY)
+    };
+    let chip = egui::Button::new(text.size(11.5))
+        .fill(palette::bg_input())
+        .rounding(10.0)
+        .stroke(egui::Stroke::new(0.5, palette::border_subtle()));
+    let status = if song.kann_ich {
+        tr("Kann ich")
+    } else {
+        tr("Noch nicht gelernt")
+    };
+    let hover = match song.artist {
+        Some(ref artist) => format!("{artist} \u{00B7} {status}"),
+        None => status.to_string(),
+    };
+    ui.add(chip).on_hover_text(hover)
+}
+
+/// The chain around the selected song, as chips from the first song to the
+/// last with the selected one in the middle, and the form to add links.
+pub(super) fn learning_path_section(
+    ui: &mut egui::Ui,
+    song_id: i64,
+    titel: &str,
+    path: &Chain,
+    form: &mut Option<PathForm>,
+    action: &mut Option<PathAction>,
+) {
+    ui.label(
+        egui::RichText::new(tr("Lernpfad"))
+            .size(13.0)
+            .strong()
+            .color(palette::TEXT_SECONDARY),
+    );
+    ui.add_space(2.0);
+    if !path.is_empty() {
+        ui.horizontal_wrapped(|ui| {
+            ui.spacing_mut().item_spacing = egui::vec2(4.0, 4.0);
+            let arrow = |ui: &mut egui::Ui| {
+                ui.label(egui::RichText::new("\u{2192}").color(palette::TEXT_MUTED));
+            };
+            for group in &path.vorher {
+                for other in group {
+                    let resp = path_chip(ui, other);
+                    if resp.clicked() {
+                        *action = Some(PathAction::Select(other.id));
+                    }
+                    if other.direkt {
+                        resp.context_menu(|ui| {
+                            if ui.button(tr("Verkn\u{00FC}pfung entfernen")).clicked() {
+                                *action = Some(PathAction::Unlink {
+                                    song_id,
+                                    vorher_id: other.id,
+                                });
+                                ui.close_menu();
+                            }
+                        });
+                    }
+                }
+                arrow(ui);
+            }
+            ui.label(
+                egui::RichText::new(titel)
+                    .size(11.5)
+                    .strong()
+                    .color(palette::accent()),
+            );
+            for group in &path.danach {
+                arrow(ui);
+                for other in group {
+                    let resp = path_chip(ui, other);
+                    if resp.clicked() {
+                        *action = Some(PathAction::Select(other.id));
+                    }
+                    if other.direkt {
+                        resp.context_menu(|ui| {
+                            if ui.button(tr("Verkn\u{00FC}pfung entfernen")).clicked() {
+                                *action = Some(PathAction::Unlink {
+                                    song_id: other.id,
+                                    vorher_id: song_id,
+                                });
+                                ui.close_menu();
+                            }
+                        });
+                    }
+                }
+            }
+        });
+    }
+
+    let mut cancel = false;
+    match form.as_mut().filter(|f| f.song_id == song_id) {
+        None => {
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing.x = 4.0;
+                if ui
+                    .small_button(tr("+ Davor"))
+                    .on_hover_text(tr("Einen Song, der vorher gelernt werden sollte"))
+                    .clicked()
+                {
+                    *action = Some(PathAction::NewForm { danach: false });
+                }
+                if ui
+                    .small_button(tr("+ Danach"))
+                    .on_hover_text(tr("Einen Song, der auf diesen aufbaut"))
+                    .clicked()
+                {
+                    *action = Some(PathAction::NewForm { danach: true });
+                }
+            });
+        }
+        Some(form) => {
+            ui.horizontal(|ui| {
+                let hint = if form.danach {
+                    tr("Danach lernen: Song suchen\u{2026}")
+                } else {
+                    tr("Davor lernen: Song suchen\u{2026}")
+                };
+                let resp = ui.add(
+                    egui::TextEdit::singleline(&mut form.query)
+                        .desired_width(f32::INFINITY)
+                        .hint_text(hint),
+                );
+                if std::mem::take(&mut form.focus) {
+                    resp.request_focus();
+                }
+            });
+            for (id, match_titel, artist) in form.matches.get().map_or(&[][..], |m| &m[..]) {
+                let label = match artist {
+                    Some(artist) => format!("{match_titel} \u{2014} {artist}"),
+                    None => match_titel.clone(),
+                };
+                if ui
+                    .selectable_label(false, egui::RichText::new(label).size(12.5))
+                    .clicked()
+                {
+                    *action = Some(PathAction::Link(*id));
+                }
+            }
+            if let Some(ref error) = form.error {
+                ui.label(
+                    egui::RichText::new(error)
+                        .size(12.0)
+                        .color(palette::ACCENT_RED),
+                );
+            }
+            if styled_small_button(ui, tr("Abbrechen")).clicked() {
+                cancel = true;
+            }
+        }
+    }
+    if cancel {
+        *form = None;
+    }
+}
+
+impl PathForm {
+    pub(super) fn song_id(&self) -> i64 {
+        self.song_id
+    }
+}
+
+impl SongIndexApp {
+    /// Load the chain of the selected song into `song_chain`, once per
+    /// selection and after every change to the list.
+    pub(super) fn load_song_chain(&mut self, song_id: i64) {
+        if self
+            .song_chain
+            .as_ref()
+            .is_none_or(|(id, _)| *id != song_id)
+        {
+            let path = self.db.request(move |conn| chain(conn, song_id));
+            self.song_chain = Some((song_id, Loaded::new(path)));
+        }
+    }
+
+    /// Reload `song_chain` after a change; the old chain stays up until the
+    /// new one is there.
+    pub(super) fn reload_song_chain(&mut self) {
+        if let Some((song_id, path)) = self.song_chain.as_mut() {
+            let song_id = *song_id;
+            path.reload(self.db.request(move |conn| chain(conn, song_id)));
+        }
+    }
+
+    /// Search the link form's matches when its text changed.
+    pub(super) fn update_path_form(&mut self) {
+        let Some(ref mut form) = self.path_form else {
+            return;
+        };
+        match answer(&mut form.linking) {
+            Some(true) => {
+                self.path_form = None;
+                self.reload_song_chain();
+                return;
+            }
+            Some(false) => {
+                form.error = Some(
+                    tr("Geht nicht: der Song liegt schon auf der anderen Seite des Pfads.")
+                        .to_string(),
+                );
+            }
+            None => {}
+        }
+        if form.searched.as_deref() == Some(form.query.as_str()) {
+            return;
+        }
+        let query = form.query.trim().to_string();
+        if query.is_empty() {
+            form.matches = Loaded::default();
+        } else {
+            let song_id = form.song_id;
+            form.matches.reload(self.db.request(move |conn| {
+                query_songs(
+                    conn,
+                    &query,
+                    &TagFilter::none(),
+                    &ExtraFilters::default(),
+                    &SortMode::Title,
+                )
+                .into_iter()
+                .filter(|s| s.id != song_id)
+                .take(MAX_MATCHES)
+                .map(|s| (s.id, s.titel, s.artist))
+                .collect()
+            }));
+        }
+        form.searched = Some(form.query.clone());
+    }
+
+    pub(super) fn apply_path_action(&mut self, song_id: i64, action: PathAction) {
+        match action {
+            PathAction::Select(id) => self.show_in_list(id),
+            PathAction::NewForm { danach } => {
+                self.path_form = Some(PathForm {
+                    song_id,
+                    danach,
+                    query: String::new(),
+                    searched: None,
+                    matches: Loaded::default(),
+                    error: None,
+                    linking: None,
+                    focus: true,
+                });
+            }
+            PathAction::Link(other) => {
+                let Some(danach) = self.path_form.as_ref().map(|f| f.danach) else {
+                    return;
+                };
+                let (song, vorher) = if danach {
+                    (other, song_id)
+                } else {
+                    (song_id, other)
+                };
+                let linking = self
+                    .db
+                    .request(move |conn| add_prerequisite(conn, song, vorher));
+                if let Some(ref mut form) = self.path_form {
+                    form.error = None;
+                    form.linking = Some(linking);
+                }
+            }
+            PathAction::Unlink { song_id, vorher_id } => {
+                self.db
+                    .send(move |conn| remove_prerequisite(conn, song_id, vorher_id));
+                self.reload_song_chain();
+            }
+        }
+    }
+
+    /// Select a song, first clearing search and filters if they hide it.
+    pub(super) fn show_in_list(&mut self, song_id: i64) {
+        if !self.songs.iter().any(|s| s.id == song_id) {
+            self.search_text.clear();
+            self.active_filters.clear();
+            self.extras = ExtraFilters::default();
+            self.refresh_songs_only();
+        }
+        self.selected_song_id = Some(song_id);
+        self.scroll_to_selected = true;
+    }
+
+    pub(super) fn open_next_songs(&mut self) {
+        let songs = self.db.request(|conn| next_songs(conn));
+        match self.next_songs_window.as_mut() {
+            Some(state) => state.songs.reload(songs),
+            None => {
+                self.next_songs_window = Some(NextSongsState {
+                    songs: Loaded::new(songs),
+                })
+            }
+        }
+    }
+
+    pub(super) fn show_next_songs(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.next_songs_window else {
+            return;
+        };
+
+        let mut action = None;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Als N\u{00E4}chstes"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([460.0, 360.0])
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(tr(
+                    "Songs, bei denen alles davor im Lernpfad als \u{201E}Kann ich\u{201C} \
+                     markiert ist, leichteste zuerst.",
+                ))
+                .size(12.5)
+                .color(palette::TEXT_SECONDARY),
+            );
+            ui.separator();
+            let Some(songs) = state.songs.get() else {
+                ui.spinner();
+                return;
+            };
+            if songs.is_empty() {
+                ui.label(
+                    egui::RichText::new(tr(
+                        "Gerade nichts bereit. Lernpfade entstehen im Detailbereich eines \
+                         Songs mit \u{201E}+ Davor\u{201C} und \u{201E}+ Danach\u{201C}.",
+                    ))
+                    .color(palette::TEXT_MUTED),
+                );
+                return;
+            }
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for song in songs.iter() {
+                    ui.horizontal(|ui| {
+                        ui.vertical(|ui| {
+                            ui.label(egui::RichText::new(&song.titel).color(palette::TEXT_PRIMARY));
+                            let mut sub = tr_arg("nach {}", song.nach.join(", "));
+                            if let Some(ref artist) = song.artist {
+                                sub = format!("{artist} \u{00B7} {sub}");
+                            }
+                            ui.label(
+                                egui::RichText::new(sub)
+                                    .size(12.0)
+                                    .color(palette::TEXT_SECONDARY),
+                            );
+                        });
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.spacing_mut().item_spacing.x = 4.0;
+                            if styled_small_button(ui, tr("\u{2713} Kann ich"))
+                                .on_hover_text(tr("Ins Repertoire aufnehmen"))
+                                .clicked()
+                            {
+                                action = Some(NextAction::Learned(song.id));
+                            }
+                            if styled_small_button(ui, tr("Zeigen")).clicked() {
+                                action = Some(NextAction::Show(song.id));
+                            }
+                            if styled_small_button(ui, tr("\u{00D6}ffnen")).clicked() {
+                                action = Some(NextAction::Open(song.dateipfad.clone()));
+                            }
+                        });
+                    });
+                    ui.add_space(4.0);
+                }
+            });
+        });
+
+        match action {
+            Some(NextAction::Open(rel_path)) => self.open_song_file(&rel_path),
+            Some(NextAction::Show(song_id)) => self.show_in_list(song_id),
+            Some(NextAction::Learned(song_id)) => {
+                let review_interval_days = self.config.review_interval_days;
+                self.db.send(move |conn| {
+                    set_repertoire(conn, song_id, true, review_interval_days);
+                });
+                self.open_next_songs();
+                self.refresh_song(song_id);
+            }
+            None => {}
+        }
+        if !open {
+            self.next_songs_window = None;
+        }
+    }
+}
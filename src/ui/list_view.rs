@@ -0,0 +1,579 @@
+use super::companions::companion_chip;
+use super::{
+    link_icon, music_summary, palette, rating_controls, song_context_menu, styled_small_button,
+    tag_color, SongAction, SongIndexApp,
+};
+use crate::db::{Song, SortMode};
+use crate::filetype::FileType;
+use crate::i18n::{tr, tr_arg};
+use eframe::egui;
+use std::collections::HashMap;
+
+const INDEX_LETTERS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ#";
+const INDEX_WIDTH: f32 = 18.0;
+const STICKY_HEIGHT: f32 = 22.0;
+/// Height reserved for a card that has not been laid out yet.
+const CARD_HEIGHT_ESTIMATE: f32 = 90.0;
+
+/// Section a song belongs to: its first letter with umlauts folded, `#` for
+/// everything else (digits, punctuation, missing artist).
+fn section_letter(song: &Song, by_artist: bool) -> char {
+    let source = if by_artist {
+        song.artist.as_deref().unwrap_or("")
+    } else {
+        &song.titel
+    };
+    match source
+        .trim_start()
+        .chars()
+        .next()
+        .map(|c| c.to_ascii_uppercase())
+    {
+        Some('\u{00C4}' | '\u{00E4}') => 'A',
+        Some('\u{00D6}' | '\u{00F6}') => 'O',
+        Some('\u{00DC}' | '\u{00FC}') => 'U',
+        Some(c) if c.is_ascii_alphabetic() => c,
+        _ => '#',
+    }
+}
+
+impl SongIndexApp {
+    /// Alphabetical sections are shown when the list is ordered by title or
+    /// artist; returns whether the sections follow the artist.
+    fn alpha_sections(&self) -> Option<bool> {
+        if self.table_sort.is_some() {
+            return None;
+        }
+        match self.sort_mode {
+            SortMode::Title => Some(false),
+            SortMode::Artist => Some(true),
+            _ => None,
+        }
+    }
+
+    /// Render the songs as cards with tags, path and action buttons. When
+    /// sorted alphabetically, adds letter headers (the current one sticks to the
+    /// top) and a letter index on the right. Returns a clicked index letter.
+    pub(super) fn show_song_list(
+        &mut self,
+        ui: &mut egui::Ui,
+        action: &mut Option<SongAction>,
+    ) -> Option<char> {
+        let sections = self.alpha_sections().filter(|_| !self.songs.is_empty());
+        let Some(by_artist) = sections else {
+            self.show_song_cards(ui, action, None);
+            return None;
+        };
+
+        let mut present: Vec<char> = Vec::new();
+        for song in &self.songs {
+            let letter = section_letter(song, by_artist);
+            if !present.contains(&letter) {
+                present.push(letter);
+            }
+        }
+
+        let mut jump = None;
+        ui.horizontal_top(|ui| {
+            ui.allocate_ui(
+                egui::vec2(ui.available_width() - INDEX_WIDTH, ui.available_height()),
+                |ui| self.show_song_cards(ui, action, Some(by_artist)),
+            );
+            jump = letter_index(ui, &present);
+        });
+        jump
+    }
+
+    fn show_song_cards(
+        &mut self,
+        ui: &mut egui::Ui,
+        action: &mut Option<SongAction>,
+        sections: Option<bool>,
+    ) {
+        let mut scroll = egui::ScrollArea::vertical()
+            .id_salt("song_list")
+            .auto_shrink([false, false]);
+        if let Some(offset) = self.scroll_memory.pending_restore() {
+            scroll = scroll.vertical_scroll_offset(offset);
+        }
+        let output = scroll.show(ui, |ui| {
+            if self.songs.is_empty() {
+                ui.add_space(40.0);
+                ui.vertical_centered(|ui| {
+                    if self.list_loading() {
+                        ui.spinner();
+                        return;
+                    }
+                    ui.label(
+                        egui::RichText::new(tr("Keine Songs gefunden."))
+                            .size(16.0)
+                            .color(palette::TEXT_MUTED),
+                    );
+                });
+                return;
+            }
+
+            let groups = self.work_groups();
+            let mut toggle = None;
+            let mut current: Option<char> = None;
+            let mut headers: Vec<(char, f32)> = Vec::new();
+            for (i, song) in self.songs.iter().enumerate() {
+                // Arrangements are listed together under their piece, at the
+                // place of the first one.
+                let members = song.werk_id.and_then(|w| groups.get(&w));
+                if members.is_some_and(|m| m[0] != i) {
+                    continue;
+                }
+                if let Some(by_artist) = sections {
+                    let letter = section_letter(song, by_artist);
+                    if current != Some(letter) {
+                        current = Some(letter);
+                        let header = ui.label(
+                            egui::RichText::new(letter.to_string())
+                                .size(13.0)
+                                .strong()
+                                .color(palette::accent()),
+                        );
+                        headers.push((letter, header.rect.top()));
+                        if self.jump_to_letter == Some(letter) {
+                            header.scroll_to_me(Some(egui::Align::TOP));
+                        }
+                    }
+                }
+
+                match members {
+                    Some(members) => {
+                        let werk_id = song.werk_id.unwrap_or_default();
+                        if self.scroll_to_selected
+                            && members
+                                .iter()
+                                .any(|&m| self.selected_song_id == Some(self.songs[m].id))
+                        {
+                            self.expanded_works.insert(werk_id);
+                        }
+                        let expanded = self.expanded_works.contains(&werk_id);
+                        let titel = self.works.get(&werk_id).map_or(&song.titel, |w| &w.titel);
+                        if work_header(ui, titel, song, members.len(), expanded).clicked() {
+                            toggle = Some(werk_id);
+                        }
+                        if expanded {
+                            let mut heights = Vec::new();
+                            ui.indent(("werk", werk_id), |ui| {
+                                for &m in members {
+                                    let member = &self.songs[m];
+                                    if let Some(height) = self.song_card(ui, member, action) {
+                                        heights.push((member.id, height));
+                                    }
+                                }
+                            });
+                            self.card_heights.extend(heights);
+                        }
+                    }
+                    None => {
+                        if let Some(height) = self.song_card(ui, song, action) {
+                            self.card_heights.insert(song.id, height);
+                        }
+                    }
+                }
+            }
+
+            if let Some(werk_id) = toggle {
+                if !self.expanded_works.remove(&werk_id) {
+                    self.expanded_works.insert(werk_id);
+                }
+            }
+
+            // Sticky header: the section whose header has scrolled out of view.
+            let clip = ui.clip_rect();
+            if let Some((letter, _)) = headers.iter().rev().find(|(_, y)| *y < clip.top()) {
+                let band =
+                    egui::Rect::from_min_size(clip.min, egui::vec2(clip.width(), STICKY_HEIGHT));
+                let painter = ui.painter_at(clip);
+                painter.rect_filled(band, 0.0, palette::bg_deep());
+                painter.text(
+                    band.left_center(),
+                    egui::Align2::LEFT_CENTER,
+                    letter.to_string(),
+                    egui::FontId::proportional(13.0),
+                    palette::accent(),
+                );
+            }
+        });
+        self.scroll_memory.record(output.state.offset.y);
+    }
+
+    /// Positions in `songs` of the arrangements of each piece that has more
+    /// than one in the current list.
+    fn work_groups(&self) -> HashMap<i64, Vec<usize>> {
+        let mut groups: HashMap<i64, Vec<usize>> = HashMap::new();
+        for (i, song) in self.songs.iter().enumerate() {
+            if let Some(werk_id) = song.werk_id {
+                groups.entry(werk_id).or_default().push(i);
+            }
+        }
+        groups.retain(|_, members| members.len() > 1);
+        groups
+    }
+
+    /// One song card, or the space it takes while out of view. Returns the
+    /// measured height when the card was laid out.
+    fn song_card(
+        &self,
+        ui: &mut egui::Ui,
+        song: &Song,
+        action: &mut Option<SongAction>,
+    ) -> Option<f32> {
+        let selected = self.selected_song_id == Some(song.id);
+        // Cards out of view only reserve their last measured height;
+        // laying out every card each frame is too slow for large
+        // libraries.
+        let height = self
+            .card_heights
+            .get(&song.id)
+            .copied()
+            .unwrap_or(CARD_HEIGHT_ESTIMATE);
+        let slot =
+            egui::Rect::from_min_size(ui.cursor().min, egui::vec2(ui.available_width(), height));
+        if !ui.is_rect_visible(slot) {
+            let (rect, _) = ui.allocate_exact_size(slot.size(), egui::Sense::hover());
+            if selected && self.scroll_to_selected {
+                ui.scroll_to_rect(rect, Some(egui::Align::Center));
+            }
+            ui.add_space(3.0);
+            return None;
+        }
+        let card = egui::Frame::none()
+            .fill(palette::bg_card())
+            .inner_margin(egui::Margin::symmetric(14.0, 10.0))
+            .rounding(8.0)
+            .stroke(if selected {
+                egui::Stroke::new(1.5, palette::accent())
+            } else {
+                egui::Stroke::new(0.5, palette::border_subtle())
+            })
+            .show(ui, |ui: &mut egui::Ui| {
+                // Title row
+                ui.horizontal(|ui: &mut egui::Ui| {
+                    let title = egui::Label::new(
+                        egui::RichText::new(&song.titel)
+                            .size(16.0)
+                            .strong()
+                            .color(palette::TEXT_PRIMARY),
+                    )
+                    .sense(egui::Sense::click());
+                    let resp = ui.add(title);
+                    if resp.double_clicked() {
+                        *action = Some(SongAction::Open(song.id));
+                    } else if resp.clicked() {
+                        *action = Some(SongAction::Select(song.id));
+                    }
+                    song_context_menu(&resp, song, action);
+                    if let Some(ref artist) = song.artist {
+                        ui.label(
+                            egui::RichText::new(format!("\u{2014} {artist}"))
+                                .size(14.5)
+                                .color(palette::TEXT_SECONDARY),
+                        );
+                    }
+                    if let Some(music) = music_summary(song) {
+                        ui.label(
+                            egui::RichText::new(music)
+                                .size(12.5)
+                                .color(palette::TEXT_MUTED),
+                        );
+                    }
+                    if let Some(file_type) = FileType::from_stored(&song.dateityp) {
+                        file_type_badge(ui, file_type);
+                    }
+                    for link in &song.links {
+                        let icon =
+                            egui::Label::new(egui::RichText::new(link_icon(&link.url)).size(13.0))
+                                .sense(egui::Sense::click());
+                        if ui
+                            .add(icon)
+                            .on_hover_text(format!("{}\n{}", link.label, link.url))
+                            .clicked()
+                        {
+                            *action = Some(SongAction::OpenUrl(link.url.clone()));
+                        }
+                    }
+                    if song.kann_ich {
+                        ui.label(
+                            egui::RichText::new("\u{2605}")
+                                .size(14.0)
+                                .color(palette::accent()),
+                        )
+                        .on_hover_text(tr("Repertoire (kann ich)"));
+                    }
+                    if song.has_audio {
+                        ui.with_layout(
+                            egui::Layout::right_to_left(egui::Align::Center),
+                            |ui: &mut egui::Ui| {
+                                let is_playing = self.playing_song_id() == Some(song.id);
+                                let (label, bg_color) = if is_playing {
+                                    ("\u{25A0} Stop", palette::ACCENT_RED)
+                                } else {
+                                    ("\u{25B6} Audio", palette::TAG_STIMMUNG)
+                                };
+                                let btn = egui::Button::new(
+                                    egui::RichText::new(label)
+                                        .size(11.5)
+                                        .color(egui::Color32::WHITE),
+                                )
+                                .fill(bg_color)
+                                .rounding(4.0);
+                                if ui.add(btn).clicked() {
+                                    if let Some(ref ap) = song.audio_pfad {
+                                        *action = Some(SongAction::ToggleAudio {
+                                            song_id: song.id,
+                                            audio_pfad: ap.clone(),
+                                        });
+                                    }
+                                }
+                            },
+                        );
+                    }
+                });
+
+                // Tags
+                if !song.tags.is_empty() {
+                    ui.add_space(2.0);
+                    ui.horizontal_wrapped(|ui: &mut egui::Ui| {
+                        ui.spacing_mut().item_spacing = egui::vec2(4.0, 4.0);
+                        for tag in &song.tags {
+                            let color = tag_color(&tag.kategorie);
+                            let text = egui::RichText::new(&tag.wert)
+                                .size(11.5)
+                                .color(egui::Color32::WHITE);
+
+                            let button = egui::Button::new(text)
+                                .fill(color)
+                                .rounding(10.0)
+                                .stroke(egui::Stroke::NONE);
+
+                            let resp = ui.add(button);
+                            if resp.clicked() {
+                                *action = Some(SongAction::ConfirmRemoveTag {
+                                    song_id: song.id,
+                                    tag_id: tag.id,
+                                    tag_wert: tag.wert.clone(),
+                                });
+                            }
+                            if resp.hovered() {
+                                resp.on_hover_text(if tag.auto_generated {
+                                    tr("Automatisch \u{2014} Klick zum Entfernen")
+                                } else {
+                                    tr("Manuell \u{2014} Klick zum Entfernen")
+                                });
+                            }
+                        }
+                    });
+                }
+
+                // Companion files
+                if !song.dateien.is_empty() {
+                    ui.add_space(2.0);
+                    ui.horizontal_wrapped(|ui: &mut egui::Ui| {
+                        ui.spacing_mut().item_spacing = egui::vec2(4.0, 4.0);
+                        for file in &song.dateien {
+                            let playing = self.playing_audio() == Some(file.dateipfad.as_str());
+                            if companion_chip(ui, file, playing).clicked() {
+                                *action = Some(SongAction::OpenCompanion {
+                                    song_id: song.id,
+                                    file: file.clone(),
+                                });
+                            }
+                        }
+                    });
+                }
+
+                ui.add_space(2.0);
+
+                // Path + actions
+                ui.horizontal(|ui: &mut egui::Ui| {
+                    ui.label(
+                        egui::RichText::new(&song.dateipfad)
+                            .size(11.5)
+                            .color(palette::TEXT_MUTED),
+                    );
+                    if self.sort_mode == SortMode::Recent {
+                        ui.label(
+                            egui::RichText::new(tr_arg(
+                                "\u{00B7} hinzugef\u{00FC}gt {}",
+                                self.format.relative(&song.created_at),
+                            ))
+                            .size(11.5)
+                            .color(palette::TEXT_MUTED),
+                        )
+                        .on_hover_text(self.format.date(&song.created_at));
+                    }
+                    if let Some(days) = song.zuletzt_geoeffnet {
+                        ui.label(
+                            egui::RichText::new(tr_arg(
+                                "\u{00B7} zuletzt ge\u{00F6}ffnet {}",
+                                self.format.days_ago(days),
+                            ))
+                            .size(11.5)
+                            .color(palette::TEXT_MUTED),
+                        )
+                        .on_hover_text(tr_arg("{}\u{00D7} verwendet", song.nutzungen));
+                    }
+                    ui.with_layout(
+                        egui::Layout::right_to_left(egui::Align::Center),
+                        |ui: &mut egui::Ui| {
+                            ui.spacing_mut().item_spacing.x = 4.0;
+                            if styled_small_button(ui, tr("Datei \u{00F6}ffnen")).clicked() {
+                                *action = Some(SongAction::OpenFile(song.dateipfad.clone()));
+                            }
+                            if styled_small_button(ui, tr("Bearbeiten")).clicked() {
+                                *action = Some(SongAction::Edit(song.id));
+                            }
+                            if styled_small_button(ui, "+ Tag").clicked() {
+                                *action = Some(SongAction::OpenTagModal {
+                                    song_id: song.id,
+                                    song_titel: song.titel.clone(),
+                                });
+                            }
+                            let practicing = self
+                                .practice_timer
+                                .as_ref()
+                                .is_some_and(|t| t.song_id == song.id);
+                            let label = if practicing {
+                                "\u{23F1} Stop"
+                            } else {
+                                tr("\u{23F1} \u{00DC}ben")
+                            };
+                            if styled_small_button(ui, label).clicked() {
+                                *action = Some(SongAction::TogglePractice {
+                                    song_id: song.id,
+                                    titel: song.titel.clone(),
+                                });
+                            }
+                            ui.add_space(8.0);
+                            if let Some(a) = rating_controls(ui, song) {
+                                *action = Some(a);
+                            }
+                        },
+                    );
+                });
+            });
+        if selected && self.scroll_to_selected {
+            card.response.scroll_to_me(Some(egui::Align::Center));
+        }
+        ui.add_space(3.0);
+        Some(card.response.rect.height())
+    }
+}
+
+/// Collapsible row standing for the arrangements of one piece; `first` is
+/// the first of them in the list, which carries the shared artist and key.
+fn work_header(
+    ui: &mut egui::Ui,
+    titel: &str,
+    first: &Song,
+    count: usize,
+    expanded: bool,
+) -> egui::Response {
+    let frame = egui::Frame::none()
+        .fill(palette::bg_card())
+        .inner_margin(egui::Margin::symmetric(14.0, 7.0))
+        .rounding(8.0)
+        .stroke(egui::Stroke::new(0.5, palette::border_subtle()))
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                let arrow = if expanded { "\u{25BE}" } else { "\u{25B8}" };
+                ui.label(egui::RichText::new(arrow).color(palette::TEXT_MUTED));
+                ui.label(
+                    egui::RichText::new(titel)
+                        .size(16.0)
+                        .strong()
+                        .color(palette::TEXT_PRIMARY),
+                );
+                if let Some(ref artist) = first.artist {
+                    ui.label(
+                        egui::RichText::new(format!("\u{2014} {artist}"))
+                            .size(14.5)
+                            .color(palette::TEXT_SECONDARY),
+                    );
+                }
+                if let Some(ref tonart) = first.tonart {
+                    ui.label(
+                        egui::RichText::new(tonart)
+                            .size(12.5)
+                            .color(palette::TEXT_MUTED),
+                    );
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(
+                        egui::RichText::new(tr_arg("{} Fassungen", count))
+                            .size(12.0)
+                            .color(palette::TEXT_MUTED),
+                    );
+                });
+            });
+        });
+    ui.add_space(3.0);
+    ui.interact(
+        frame.response.rect,
+        ui.id().with(("werk_header", first.id)),
+        egui::Sense::click(),
+    )
+    .on_hover_text(if expanded {
+        tr("Fassungen zuklappen")
+    } else {
+        tr("Fassungen zeigen")
+    })
+}
+
+/// Vertical A–Z strip. Letters without songs are dimmed; clicking one jumps to
+/// the next letter that has songs.
+fn letter_index(ui: &mut egui::Ui, present: &[char]) -> Option<char> {
+    let count = INDEX_LETTERS.chars().count() as f32;
+    let row_height = (ui.available_height() / count).clamp(8.0, 16.0);
+    let font = egui::FontId::proportional((row_height - 3.0).clamp(7.0, 11.0));
+
+    let mut clicked = None;
+    ui.vertical(|ui| {
+        ui.spacing_mut().item_spacing.y = 0.0;
+        for (i, letter) in INDEX_LETTERS.chars().enumerate() {
+            let (rect, resp) =
+                ui.allocate_exact_size(egui::vec2(INDEX_WIDTH, row_height), egui::Sense::click());
+            let color = if !present.contains(&letter) {
+                palette::border_active()
+            } else if resp.hovered() {
+                palette::accent()
+            } else {
+                palette::TEXT_SECONDARY
+            };
+            ui.painter().text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                letter,
+                font.clone(),
+                color,
+            );
+            if resp.clicked() {
+                clicked = INDEX_LETTERS
+                    .chars()
+                    .skip(i)
+                    .find(|l| present.contains(l))
+                    .or_else(|| present.last().copied());
+            }
+        }
+    });
+    clicked
+}
+
+fn file_type_badge(ui: &mut egui::Ui, file_type: FileType) {
+    egui::Frame::none()
+        .stroke(egui::Stroke::new(0.5, palette::border_subtle()))
+        .rounding(4.0)
+        .inner_margin(egui::Margin::symmetric(5.0, 1.0))
+        .show(ui, |ui| {
+            ui.label(
+                egui::RichText::new(tr(file_type.label()))
+                    .size(10.5)
+                    .color(palette::TEXT_MUTED),
+            );
+        });
+}
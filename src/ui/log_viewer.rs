@@ -0,0 +1,185 @@
+use super::{palette, styled_small_button, SongIndexApp};
+use crate::i18n::tr;
+use crate::logging::{log_path, read_lines, LogLine};
+use crate::open_with::{reveal, REVEAL_LABEL};
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+/// Messages read from the log files; older ones are left out.
+const MAX_LINES: usize = 5000;
+/// How often the open window looks for new messages.
+const POLL: Duration = Duration::from_secs(1);
+
+/// The "Protokoll" window: the log file with a level and text filter, new
+/// messages appear while it is open.
+pub(super) struct LogViewer {
+    lines: Vec<LogLine>,
+    /// Least severe level shown.
+    min_level: log::Level,
+    query: String,
+    /// Size of the log file when it was read; a different size means new
+    /// messages or a rotation.
+    read_len: u64,
+    checked: Instant,
+}
+
+fn log_len() -> u64 {
+    std::fs::metadata(log_path()).map(|m| m.len()).unwrap_or(0)
+}
+
+fn level_color(level: log::Level) -> egui::Color32 {
+    match level {
+        log::Level::Error => palette::ACCENT_RED,
+        log::Level::Warn => palette::accent(),
+        _ => palette::TEXT_MUTED,
+    }
+}
+
+impl LogViewer {
+    fn reload(&mut self) {
+        self.read_len = log_len();
+        self.lines = read_lines(MAX_LINES);
+        self.checked = Instant::now();
+    }
+
+    fn matches(&self, line: &LogLine) -> bool {
+        let query = self.query.trim().to_lowercase();
+        line.level <= self.min_level
+            && (query.is_empty()
+                || line.text.to_lowercase().contains(&query)
+                || line.module.to_lowercase().contains(&query))
+    }
+}
+
+impl SongIndexApp {
+    pub(super) fn open_log_viewer(&mut self) {
+        let mut viewer = LogViewer {
+            lines: Vec::new(),
+            min_level: log::Level::Info,
+            query: String::new(),
+            read_len: 0,
+            checked: Instant::now(),
+        };
+        viewer.reload();
+        self.log_viewer = Some(viewer);
+    }
+
+    pub(super) fn show_log_viewer(&mut self, ctx: &egui::Context) {
+        let Some(ref mut viewer) = self.log_viewer else {
+            return;
+        };
+        if viewer.checked.elapsed() >= POLL {
+            viewer.checked = Instant::now();
+            if log_len() != viewer.read_len {
+                viewer.reload();
+            }
+        }
+        ctx.request_repaint_after(POLL);
+
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Protokoll"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([720.0, 440.0])
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(tr(
+                    "Was Scan, \u{00DC}berwachung, Player und Hintergrundaufgaben gemeldet haben, \
+                     neueste unten. Zeiten in UTC.",
+                ))
+                .size(12.5)
+                .color(palette::TEXT_SECONDARY),
+            );
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_salt("log_level")
+                    .selected_text(tr(match viewer.min_level {
+                        log::Level::Error => "Nur Fehler",
+                        log::Level::Warn => "Warnungen und Fehler",
+                        _ => "Alles",
+                    }))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut viewer.min_level, log::Level::Info, tr("Alles"));
+                        ui.selectable_value(
+                            &mut viewer.min_level,
+                            log::Level::Warn,
+                            tr("Warnungen und Fehler"),
+                        );
+                        ui.selectable_value(
+                            &mut viewer.min_level,
+                            log::Level::Error,
+                            tr("Nur Fehler"),
+                        );
+                    });
+                ui.add(
+                    egui::TextEdit::singleline(&mut viewer.query)
+                        .desired_width(200.0)
+                        .hint_text(tr("Filtern, z.\u{202F}B. Watcher")),
+                );
+                if styled_small_button(ui, tr("Aktualisieren")).clicked() {
+                    viewer.reload();
+                }
+                if styled_small_button(ui, REVEAL_LABEL).clicked() {
+                    reveal(&log_path());
+                }
+            });
+            ui.separator();
+
+            let shown: Vec<&LogLine> = viewer.lines.iter().filter(|l| viewer.matches(l)).collect();
+            if shown.is_empty() {
+                ui.label(
+                    egui::RichText::new(tr("Keine Eintr\u{00E4}ge.")).color(palette::TEXT_MUTED),
+                );
+                return;
+            }
+            let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .stick_to_bottom(true)
+                .show_rows(ui, row_height, shown.len(), |ui, range| {
+                    for line in &shown[range] {
+                        ui.horizontal(|ui| {
+                            ui.spacing_mut().item_spacing.x = 8.0;
+                            ui.label(
+                                egui::RichText::new(&line.zeit)
+                                    .monospace()
+                                    .color(palette::TEXT_MUTED),
+                            );
+                            ui.label(
+                                egui::RichText::new(format!("{:<5}", line.level))
+                                    .monospace()
+                                    .color(level_color(line.level)),
+                            );
+                            ui.label(
+                                egui::RichText::new(&line.module)
+                                    .monospace()
+                                    .color(palette::TEXT_SECONDARY),
+                            );
+                            // Messages spanning lines show their first one;
+                            // the hover has all of it.
+                            let first = line.text.lines().next().unwrap_or_default();
+                            let resp = ui.add(
+                                egui::Label::new(
+                                    egui::RichText::new(first)
+                                        .monospace()
+                                        .color(palette::TEXT_PRIMARY),
+                                )
+                                .truncate(),
+                            );
+                            if first.len() < line.text.len() {
+                                resp.on_hover_text(&line.text);
+                            }
+                        });
+                    }
+                });
+        });
+        if !open {
+            self.log_viewer = None;
+        }
+    }
+}
@@ -0,0 +1,275 @@
+use super::pending::{answer, Loaded};
+use super::{palette, SongIndexApp};
+use crate::i18n::{tr, tr_arg, tr_args};
+use crate::maintenance::{
+    checkpoint, integrity_check, orphan_report, remove_orphans, size, vacuum, OrphanReport, Size,
+};
+use eframe::egui;
+use std::sync::mpsc::Receiver;
+
+const MB: f64 = 1024.0 * 1024.0;
+/// Integrity problems listed before the rest is only counted.
+const MAX_PROBLEMS: usize = 20;
+
+/// "Wartung": checks and cleanups on the library file, each with its result
+/// (or error) listed in the window.
+pub(super) struct MaintenanceState {
+    size: Loaded<Option<Size>>,
+    /// Last report from "Verwaiste Einträge suchen".
+    orphans: Option<OrphanReport>,
+    results: Vec<Result<String, String>>,
+    /// The running action; the buttons wait for it.
+    running: Option<Receiver<(Action, ActionResult)>>,
+}
+
+/// Result of an action, and the orphan report it produced.
+type ActionResult = (Result<String, String>, Option<OrphanReport>);
+
+#[derive(Clone, Copy)]
+enum Action {
+    Vacuum,
+    Checkpoint,
+    Integrity,
+    FindOrphans,
+    RemoveOrphans,
+}
+
+fn mb(bytes: i64) -> String {
+    format!("{:.1}\u{00A0}MB", bytes as f64 / MB)
+}
+
+impl SongIndexApp {
+    pub(super) fn open_maintenance(&mut self) {
+        let size = self.db.request(|conn| size(conn).ok());
+        self.maintenance_window = Some(MaintenanceState {
+            size: Loaded::new(size),
+            orphans: None,
+            results: Vec::new(),
+            running: None,
+        });
+    }
+
+    pub(super) fn show_maintenance(&mut self, ctx: &egui::Context) {
+        if let Some((action, outcome)) = self
+            .maintenance_window
+            .as_mut()
+            .and_then(|state| answer(&mut state.running))
+        {
+            self.finish_maintenance(action, outcome);
+        }
+        let Some(ref mut state) = self.maintenance_window else {
+            return;
+        };
+
+        let mut action = None;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Wartung"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_width(440.0)
+        .show(ctx, |ui| {
+            if let Some(Some(size)) = state.size.get() {
+                ui.label(
+                    egui::RichText::new(tr_args(
+                        "Bibliothek: {}, davon ungenutzt {}",
+                        &[&mb(size.total), &mb(size.free)],
+                    ))
+                    .color(palette::TEXT_SECONDARY),
+                );
+                ui.add_space(4.0);
+            }
+            let idle = state.running.is_none();
+            ui.horizontal_wrapped(|ui| {
+                for (label, hover, kind) in [
+                    (
+                        "Komprimieren",
+                        "VACUUM: schreibt die Datei ohne ungenutzten Platz neu. Dauert bei \
+                         gro\u{00DF}en Bibliotheken einige Sekunden.",
+                        Action::Vacuum,
+                    ),
+                    (
+                        "WAL zur\u{00FC}ckschreiben",
+                        "Schreibt das Write-Ahead-Log in die Datei zur\u{00FC}ck und leert es, \
+                         z.\u{00A0}B. vor dem Kopieren der Datei von Hand.",
+                        Action::Checkpoint,
+                    ),
+                    (
+                        "Integrit\u{00E4}t pr\u{00FC}fen",
+                        "PRAGMA integrity_check: sucht besch\u{00E4}digte Seiten und Indizes.",
+                        Action::Integrity,
+                    ),
+                    (
+                        "Verwaiste Eintr\u{00E4}ge suchen",
+                        "Tag-Zuordnungen, \u{00DC}bungszeiten, Links und Ereignisse ohne \
+                         Song oder Tag sowie Tags ohne Songs.",
+                        Action::FindOrphans,
+                    ),
+                ] {
+                    if ui
+                        .add_enabled(idle, egui::Button::new(tr(label)))
+                        .on_hover_text(tr(hover))
+                        .clicked()
+                    {
+                        action = Some(kind);
+                    }
+                }
+                if !idle {
+                    ui.spinner();
+                }
+            });
+
+            if let Some(ref report) = state.orphans {
+                ui.add_space(6.0);
+                ui.separator();
+                if report.is_empty() {
+                    ui.label(
+                        egui::RichText::new(tr("Keine verwaisten Eintr\u{00E4}ge."))
+                            .color(palette::TEXT_MUTED),
+                    );
+                } else {
+                    egui::Grid::new("maintenance_orphans")
+                        .num_columns(2)
+                        .spacing([10.0, 2.0])
+                        .show(ui, |ui| {
+                            for (table, count) in &report.dangling {
+                                ui.label(egui::RichText::new(table).monospace());
+                                ui.label(tr_arg("{} ohne Song oder Tag", count));
+                                ui.end_row();
+                            }
+                            if report.unused_tags > 0 {
+                                ui.label(egui::RichText::new("tags").monospace());
+                                ui.label(tr_arg("{} ohne Songs", report.unused_tags));
+                                ui.end_row();
+                            }
+                        });
+                    ui.add_space(4.0);
+                    let btn = egui::Button::new(
+                        egui::RichText::new(tr("Entfernen")).color(palette::TEXT_PRIMARY),
+                    )
+                    .fill(palette::accent_dim())
+                    .rounding(6.0);
+                    if ui.add_enabled(idle, btn).clicked() {
+                        action = Some(Action::RemoveOrphans);
+                    }
+                }
+            }
+
+            if !state.results.is_empty() {
+                ui.add_space(6.0);
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .id_salt("maintenance_results")
+                    .max_height(200.0)
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        for result in &state.results {
+                            match result {
+                                Ok(text) => ui.label(
+                                    egui::RichText::new(text).color(palette::TEXT_SECONDARY),
+                                ),
+                                Err(text) => {
+                                    ui.label(egui::RichText::new(text).color(palette::ACCENT_RED))
+                                }
+                            };
+                        }
+                    });
+            }
+        });
+
+        if let Some(action) = action {
+            self.run_maintenance(action);
+        }
+        if !open {
+            self.maintenance_window = None;
+        }
+    }
+
+    fn run_maintenance(&mut self, action: Action) {
+        let Some(ref mut state) = self.maintenance_window else {
+            return;
+        };
+        state.running = Some(self.db.request(move |conn| {
+            let mut orphans = None;
+            let result = match action {
+                Action::Vacuum => vacuum(conn)
+                    .map(|(before, after)| {
+                        tr_args("Komprimiert: {} \u{2192} {}", &[&mb(before), &mb(after)])
+                    })
+                    .map_err(|e| tr_arg("Komprimieren fehlgeschlagen: {}", e)),
+                Action::Checkpoint => match checkpoint(conn) {
+                    Ok(c) if c.log < 0 => Ok(tr("Die Bibliothek nutzt kein WAL.").to_string()),
+                    Ok(c) if c.busy => Err(tr_args(
+                        "WAL nur teilweise zur\u{00FC}ckgeschrieben ({} von {} Seiten); ein \
+                     Hintergrundjob liest gerade. Sp\u{00E4}ter nochmal versuchen.",
+                        &[&c.written, &c.log],
+                    )),
+                    Ok(c) => Ok(tr_arg(
+                        "WAL zur\u{00FC}ckgeschrieben: {} Seiten.",
+                        c.written,
+                    )),
+                    Err(e) => Err(tr_arg("Checkpoint fehlgeschlagen: {}", e)),
+                },
+                Action::Integrity => match integrity_check(conn) {
+                    Ok(problems) if problems.is_empty() => {
+                        Ok(tr("Integrit\u{00E4}tspr\u{00FC}fung: keine Fehler.").to_string())
+                    }
+                    Ok(problems) => {
+                        let mut text = tr_arg(
+                            "Integrit\u{00E4}tspr\u{00FC}fung: {} Fehler. Ein Backup aus der \
+                         Zeitmaschine oder dem Cloud-Backup zur\u{00FC}ckspielen.",
+                            problems.len(),
+                        );
+                        for problem in problems.iter().take(MAX_PROBLEMS) {
+                            text.push_str(&format!("\n\u{2022} {problem}"));
+                        }
+                        if problems.len() > MAX_PROBLEMS {
+                            text.push_str(&tr_arg(
+                                "\n\u{2026} und {} weitere",
+                                problems.len() - MAX_PROBLEMS,
+                            ));
+                        }
+                        Err(text)
+                    }
+                    Err(e) => Err(tr_arg(
+                        "Integrit\u{00E4}tspr\u{00FC}fung fehlgeschlagen: {}",
+                        e,
+                    )),
+                },
+                Action::FindOrphans => match orphan_report(conn) {
+                    Ok(report) => {
+                        let count = report.dangling.iter().map(|(_, n)| n).sum::<i64>()
+                            + report.unused_tags;
+                        orphans = Some(report);
+                        Ok(tr_arg("{} verwaiste Eintr\u{00E4}ge gefunden.", count))
+                    }
+                    Err(e) => Err(tr_arg("Suche fehlgeschlagen: {}", e)),
+                },
+                Action::RemoveOrphans => remove_orphans(conn)
+                    .map(|n| tr_arg("{} verwaiste Eintr\u{00E4}ge entfernt.", n))
+                    .map_err(|e| tr_arg("Entfernen fehlgeschlagen: {}", e)),
+            };
+            (action, (result, orphans))
+        }));
+        state.size.reload(self.db.request(|conn| size(conn).ok()));
+    }
+
+    fn finish_maintenance(&mut self, action: Action, (result, orphans): ActionResult) {
+        if let Err(ref e) = result {
+            log::warn!("{e}");
+        }
+        if matches!(action, Action::RemoveOrphans) && result.is_ok() {
+            self.refresh_data();
+        }
+        if let Some(ref mut state) = self.maintenance_window {
+            if orphans.is_some() || matches!(action, Action::RemoveOrphans) {
+                state.orphans = orphans;
+            }
+            state.results.push(result);
+        }
+    }
+}
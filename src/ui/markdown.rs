@@ -0,0 +1,109 @@
+use super::palette;
+use eframe::egui;
+use egui::text::{LayoutJob, TextFormat};
+
+/// Render a small subset of Markdown for song notes: `#` headings, `-`/`*`
+/// and numbered lists, `**bold**`, `*italic*` and `` `code` ``. Everything
+/// else is shown as plain text.
+pub(super) fn show_markdown(ui: &mut egui::Ui, text: &str) {
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() {
+            ui.add_space(4.0);
+            continue;
+        }
+
+        let hashes = trimmed.chars().take_while(|c| *c == '#').count();
+        if (1..=3).contains(&hashes) && trimmed[hashes..].starts_with(' ') {
+            let size = [17.0, 15.0, 14.0][hashes - 1];
+            ui.add_space(2.0);
+            ui.label(inline(
+                &trimmed[hashes + 1..],
+                size,
+                palette::TEXT_PRIMARY,
+                true,
+            ));
+            continue;
+        }
+
+        let indent = (line.len() - trimmed.len()) as f32 * 4.0;
+        if let Some(item) = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+        {
+            list_item(ui, indent, "\u{2022}", item);
+            continue;
+        }
+        let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+        if digits > 0 && trimmed[digits..].starts_with(". ") {
+            list_item(ui, indent, &trimmed[..digits + 1], &trimmed[digits + 2..]);
+            continue;
+        }
+
+        ui.label(inline(trimmed, 13.0, palette::TEXT_SECONDARY, false));
+    }
+}
+
+fn list_item(ui: &mut egui::Ui, indent: f32, marker: &str, text: &str) {
+    ui.horizontal_wrapped(|ui| {
+        ui.add_space(indent);
+        ui.label(
+            egui::RichText::new(marker)
+                .size(13.0)
+                .color(palette::TEXT_MUTED),
+        );
+        ui.label(inline(text, 13.0, palette::TEXT_SECONDARY, false));
+    });
+}
+
+fn inline(text: &str, size: f32, color: egui::Color32, bold: bool) -> LayoutJob {
+    let mut job = LayoutJob::default();
+    let mut strong = bold;
+    let mut italics = false;
+    let mut code = false;
+    let mut current = String::new();
+
+    let flush = |job: &mut LayoutJob, current: &mut String, strong, italics, code| {
+        if current.is_empty() {
+            return;
+        }
+        let format = if code {
+            TextFormat {
+                font_id: egui::FontId::monospace(size - 1.0),
+                color: palette::accent(),
+                background: palette::bg_input(),
+                ..Default::default()
+            }
+        } else {
+            TextFormat {
+                font_id: egui::FontId::proportional(size),
+                color: if strong { palette::TEXT_PRIMARY } else { color },
+                italics,
+                ..Default::default()
+            }
+        };
+        job.append(&std::mem::take(current), 0.0, format);
+    };
+
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '`' => {
+                flush(&mut job, &mut current, strong, italics, code);
+                code = !code;
+            }
+            '*' if !code && chars.peek() == Some(&'*') => {
+                chars.next();
+                flush(&mut job, &mut current, strong, italics, code);
+                strong = !strong;
+            }
+            '*' if !code => {
+                flush(&mut job, &mut current, strong, italics, code);
+                italics = !italics;
+            }
+            c => current.push(c),
+        }
+    }
+    flush(&mut job, &mut current, strong, italics, code);
+    job
+}
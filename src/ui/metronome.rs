@@ -0,0 +1,213 @@
+use super::{palette, SongIndexApp};
+use crate::i18n::{tr, tr_arg};
+use crate::metronome::{Metronome, MetronomeControls, MAX_BPM, MIN_BPM};
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+/// Time signatures offered, as (label, beats per bar).
+const TAKTARTEN: &[(&str, u32)] = &[
+    ("2/4", 2),
+    ("3/4", 3),
+    ("4/4", 4),
+    ("5/4", 5),
+    ("6/8", 6),
+    ("7/8", 7),
+];
+
+/// Taps further apart than this start a new measurement.
+const TAP_RESET: Duration = Duration::from_secs(2);
+
+pub(super) struct MetronomeState {
+    bpm: u32,
+    takt: usize,
+    accent: bool,
+    taps: Vec<Instant>,
+    running: Option<Metronome>,
+    error: Option<String>,
+}
+
+impl MetronomeState {
+    fn beats(&self) -> u32 {
+        TAKTARTEN[self.takt].1
+    }
+
+    /// Average of the last few tap intervals.
+    fn tap(&mut self) {
+        let now = Instant::now();
+        if self
+            .taps
+            .last()
+            .is_some_and(|last| now.duration_since(*last) > TAP_RESET)
+        {
+            self.taps.clear();
+        }
+        self.taps.push(now);
+        if self.taps.len() > 5 {
+            self.taps.remove(0);
+        }
+        if let [first, .., last] = self.taps[..] {
+            let interval = last.duration_since(first).as_secs_f32() / (self.taps.len() - 1) as f32;
+            self.bpm = ((60.0 / interval).round() as u32).clamp(MIN_BPM, MAX_BPM);
+        }
+    }
+}
+
+impl SongIndexApp {
+    pub(super) fn open_metronome(&mut self) {
+        if self.metronome.is_some() {
+            return;
+        }
+        self.metronome = Some(MetronomeState {
+            bpm: self.selected_song_bpm().unwrap_or(80),
+            takt: 2,
+            accent: true,
+            taps: Vec::new(),
+            running: None,
+            error: None,
+        });
+    }
+
+    fn selected_song_bpm(&self) -> Option<u32> {
+        let id = self.selected_song_id?;
+        let bpm = self.songs.iter().find(|s| s.id == id)?.bpm?;
+        u32::try_from(bpm)
+            .ok()
+            .filter(|b| (MIN_BPM..=MAX_BPM).contains(b))
+    }
+
+    pub(super) fn show_metronome(&mut self, ctx: &egui::Context) {
+        let song_bpm = self.selected_song_bpm();
+        let Some(ref mut state) = self.metronome else {
+            return;
+        };
+        if state.running.is_some() {
+            ctx.request_repaint_after(Duration::from_millis(30));
+        }
+
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Metronom"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(300.0)
+        .show(ctx, |ui| {
+            let mut changed = false;
+            ui.horizontal(|ui| {
+                changed |= ui
+                    .add(
+                        egui::DragValue::new(&mut state.bpm)
+                            .range(MIN_BPM..=MAX_BPM)
+                            .speed(0.5)
+                            .suffix(" BPM"),
+                    )
+                    .changed();
+                for (label, delta) in [("\u{2212}", -1i32), ("+", 1)] {
+                    if ui.small_button(label).clicked() {
+                        state.bpm = state
+                            .bpm
+                            .saturating_add_signed(delta)
+                            .clamp(MIN_BPM, MAX_BPM);
+                        changed = true;
+                    }
+                }
+                if ui
+                    .button("Tap")
+                    .on_hover_text(tr("Im Takt klicken, um das Tempo zu messen"))
+                    .clicked()
+                {
+                    state.tap();
+                    changed = true;
+                }
+            });
+            ui.spacing_mut().slider_width = 260.0;
+            changed |= ui
+                .add(egui::Slider::new(&mut state.bpm, MIN_BPM..=MAX_BPM).show_value(false))
+                .changed();
+            if let Some(bpm) = song_bpm {
+                if ui
+                    .button(tr_arg("Vom Song \u{00FC}bernehmen ({} BPM)", bpm))
+                    .clicked()
+                {
+                    state.bpm = bpm;
+                    changed = true;
+                }
+            }
+
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(tr("Takt")).color(palette::TEXT_SECONDARY));
+                egui::ComboBox::from_id_salt("metronome_takt")
+                    .selected_text(TAKTARTEN[state.takt].0)
+                    .width(60.0)
+                    .show_ui(ui, |ui| {
+                        for (i, (label, _)) in TAKTARTEN.iter().enumerate() {
+                            changed |= ui.selectable_value(&mut state.takt, i, *label).changed();
+                        }
+                    });
+                changed |= ui
+                    .checkbox(&mut state.accent, tr("Betonung auf 1"))
+                    .changed();
+            });
+
+            ui.add_space(8.0);
+            let beat = state.running.as_ref().map(|m| m.controls.beat_in_bar());
+            ui.horizontal(|ui| {
+                for i in 0..state.beats() {
+                    let (rect, _) =
+                        ui.allocate_exact_size(egui::vec2(18.0, 18.0), egui::Sense::hover());
+                    let color = match beat {
+                        Some(b) if b == i && i == 0 && state.accent => palette::ACCENT_RED,
+                        Some(b) if b == i => palette::accent(),
+                        _ => palette::border_active(),
+                    };
+                    ui.painter().circle_filled(rect.center(), 7.0, color);
+                }
+            });
+
+            ui.add_space(8.0);
+            let (label, fill) = if state.running.is_some() {
+                ("\u{25A0} Stopp", palette::ACCENT_RED)
+            } else {
+                ("\u{25B6} Start", palette::accent_dim())
+            };
+            let toggle =
+                egui::Button::new(egui::RichText::new(tr(label)).color(palette::TEXT_PRIMARY))
+                    .fill(fill)
+                    .rounding(6.0);
+            // A click while running drops (stops) the metronome.
+            if ui.add(toggle).clicked() && state.running.take().is_none() {
+                let controls = MetronomeControls::new(state.bpm, state.beats(), state.accent);
+                match Metronome::start(controls) {
+                    Ok(metronome) => {
+                        state.running = Some(metronome);
+                        state.error = None;
+                    }
+                    Err(e) => state.error = Some(e),
+                }
+            }
+            if let Some(ref error) = state.error {
+                ui.label(
+                    egui::RichText::new(error)
+                        .size(12.0)
+                        .color(palette::ACCENT_RED),
+                );
+            }
+
+            if changed {
+                if let Some(ref metronome) = state.running {
+                    metronome.controls.set_bpm(state.bpm);
+                    metronome.controls.set_beats(state.beats());
+                    metronome.controls.set_accent(state.accent);
+                }
+            }
+        });
+
+        if !open {
+            self.metronome = None;
+        }
+    }
+}
@@ -0,0 +1,360 @@
+use super::{palette, SongIndexApp};
+use crate::db::{apply_tag, set_song_year, update_song, update_song_music, Song};
+use crate::i18n::tr;
+use crate::musicbrainz::{details, genre_tag, search, Candidate, Details};
+use eframe::egui;
+use std::sync::mpsc::{Receiver, TryRecvError};
+
+/// Lookup for one song: search, pick a hit, then choose what to take over.
+pub(super) struct MusicBrainzState {
+    song: Song,
+    titel: String,
+    artist: String,
+    searching: Option<Receiver<Result<Vec<Candidate>, String>>>,
+    candidates: Vec<Candidate>,
+    selected: Option<usize>,
+    loading: Option<Receiver<Result<Details, String>>>,
+    details: Option<Details>,
+    take_artist: bool,
+    take_jahr: bool,
+    take_tonart: bool,
+    take_stil: bool,
+    /// Suggested `stil` values, from the genres MusicBrainz lists.
+    stile: Vec<String>,
+    stil: String,
+    error: Option<String>,
+}
+
+impl MusicBrainzState {
+    /// Starts the search right away.
+    pub(super) fn new(song: &Song) -> Self {
+        let mut state = MusicBrainzState {
+            song: song.clone(),
+            titel: song.titel.clone(),
+            artist: song.artist.clone().unwrap_or_default(),
+            searching: None,
+            candidates: Vec::new(),
+            selected: None,
+            loading: None,
+            details: None,
+            take_artist: false,
+            take_jahr: false,
+            take_tonart: false,
+            take_stil: false,
+            stile: Vec::new(),
+            stil: String::new(),
+            error: None,
+        };
+        state.start_search();
+        state
+    }
+
+    fn start_search(&mut self) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let (titel, artist) = (self.titel.clone(), self.artist.clone());
+        std::thread::spawn(move || {
+            tx.send(search(&titel, &artist)).ok();
+        });
+        self.searching = Some(rx);
+        self.candidates.clear();
+        self.select(None);
+    }
+
+    fn select(&mut self, index: Option<usize>) {
+        self.selected = index;
+        self.details = None;
+        self.loading = None;
+        self.stile.clear();
+        self.error = None;
+        let Some(candidate) = index.and_then(|i| self.candidates.get(i)).cloned() else {
+            return;
+        };
+        self.take_artist = self.song.artist.as_deref() != Some(candidate.artist.as_str());
+        self.take_jahr = candidate.jahr.is_some() && self.song.jahr != candidate.jahr;
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            tx.send(details(&candidate)).ok();
+        });
+        self.loading = Some(rx);
+    }
+}
+
+impl SongIndexApp {
+    pub(super) fn show_musicbrainz(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.musicbrainz else {
+            return;
+        };
+
+        if let Some(ref rx) = state.searching {
+            match rx.try_recv() {
+                Ok(result) => {
+                    state.searching = None;
+                    match result {
+                        Ok(candidates) => {
+                            state.candidates = candidates;
+                            if !state.candidates.is_empty() {
+                                state.select(Some(0));
+                            }
+                        }
+                        Err(e) => state.error = Some(e),
+                    }
+                }
+                Err(TryRecvError::Empty) => {
+                    ctx.request_repaint_after(std::time::Duration::from_millis(200));
+                }
+                Err(TryRecvError::Disconnected) => state.searching = None,
+            }
+        }
+        if let Some(ref rx) = state.loading {
+            match rx.try_recv() {
+                Ok(result) => {
+                    state.loading = None;
+                    match result {
+                        Ok(details) => {
+                            let existing: Vec<String> = self
+                                .tags
+                                .iter()
+                                .filter(|g| g.kategorie == "stil")
+                                .flat_map(|g| g.tags.iter().map(|t| t.wert.clone()))
+                                .collect();
+                            state.stile = details
+                                .genres
+                                .iter()
+                                .map(|g| genre_tag(g, &existing))
+                                .collect();
+                            state.stil = state.stile.first().cloned().unwrap_or_default();
+                            let has_stil = state.song.tags.iter().any(|t| {
+                                t.kategorie == "stil" && t.wert.eq_ignore_ascii_case(&state.stil)
+                            });
+                            state.take_stil = !state.stil.is_empty() && !has_stil;
+                            state.take_tonart =
+                                details.tonart.is_some() && state.song.tonart.is_none();
+                            state.details = Some(details);
+                        }
+                        Err(e) => state.error = Some(e),
+                    }
+                }
+                Err(TryRecvError::Empty) => {
+                    ctx.request_repaint_after(std::time::Duration::from_millis(200));
+                }
+                Err(TryRecvError::Disconnected) => state.loading = None,
+            }
+        }
+
+        let mut apply = false;
+        let mut select = None;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new("MusicBrainz")
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([520.0, 440.0])
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(tr(
+                    "Sucht den Song online bei musicbrainz.org. \u{00DC}bernommen wird nur, \
+                     was unten angehakt ist.",
+                ))
+                .size(12.5)
+                .color(palette::TEXT_SECONDARY),
+            );
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut state.titel)
+                        .desired_width(180.0)
+                        .hint_text(tr("Titel")),
+                );
+                ui.add(
+                    egui::TextEdit::singleline(&mut state.artist)
+                        .desired_width(150.0)
+                        .hint_text("Artist"),
+                );
+                if ui
+                    .add_enabled(
+                        state.searching.is_none() && !state.titel.trim().is_empty(),
+                        egui::Button::new(tr("Suchen")),
+                    )
+                    .clicked()
+                {
+                    state.start_search();
+                }
+                if state.searching.is_some() {
+                    ui.spinner();
+                }
+            });
+            if let Some(ref error) = state.error {
+                ui.label(egui::RichText::new(error).color(palette::ACCENT_RED));
+            }
+            ui.separator();
+
+            if state.candidates.is_empty() {
+                if state.searching.is_none() && state.error.is_none() {
+                    ui.label(
+                        egui::RichText::new(tr("Nichts gefunden.")).color(palette::TEXT_MUTED),
+                    );
+                }
+                return;
+            }
+            egui::ScrollArea::vertical()
+                .id_salt("musicbrainz_candidates")
+                .max_height(140.0)
+                .show(ui, |ui| {
+                    for (i, candidate) in state.candidates.iter().enumerate() {
+                        let jahr = candidate
+                            .jahr
+                            .map(|j| format!(" ({j})"))
+                            .unwrap_or_default();
+                        let label = format!(
+                            "{} \u{2014} {}{jahr} \u{00B7} {}\u{00A0}%",
+                            candidate.titel, candidate.artist, candidate.score
+                        );
+                        if ui
+                            .selectable_label(state.selected == Some(i), label)
+                            .clicked()
+                        {
+                            select = Some(i);
+                        }
+                    }
+                });
+            let Some(candidate) = state.selected.and_then(|i| state.candidates.get(i)) else {
+                return;
+            };
+            ui.separator();
+
+            let current = |value: Option<String>| value.unwrap_or_else(|| "\u{2013}".to_string());
+            egui::Grid::new("musicbrainz_review")
+                .num_columns(3)
+                .spacing([10.0, 4.0])
+                .show(ui, |ui| {
+                    for label in ["", "Bisher", "MusicBrainz"] {
+                        ui.label(
+                            egui::RichText::new(tr(label))
+                                .size(12.0)
+                                .color(palette::TEXT_MUTED),
+                        );
+                    }
+                    ui.end_row();
+
+                    ui.checkbox(&mut state.take_artist, "Artist");
+                    ui.label(current(state.song.artist.clone()));
+                    ui.label(&candidate.artist);
+                    ui.end_row();
+
+                    ui.add_enabled(
+                        candidate.jahr.is_some(),
+                        egui::Checkbox::new(&mut state.take_jahr, tr("Jahr")),
+                    );
+                    ui.label(current(state.song.jahr.map(|j| j.to_string())));
+                    ui.label(current(candidate.jahr.map(|j| j.to_string())));
+                    ui.end_row();
+
+                    let Some(ref details) = state.details else {
+                        if state.loading.is_some() {
+                            ui.label("");
+                            ui.label(
+                                egui::RichText::new(tr("Tonart und Stil werden geladen\u{2026}"))
+                                    .color(palette::TEXT_MUTED),
+                            );
+                            ui.spinner();
+                            ui.end_row();
+                        }
+                        return;
+                    };
+                    ui.add_enabled(
+                        details.tonart.is_some(),
+                        egui::Checkbox::new(&mut state.take_tonart, tr("Tonart")),
+                    );
+                    ui.label(current(state.song.tonart.clone()));
+                    ui.label(current(details.tonart.clone()));
+                    ui.end_row();
+
+                    ui.add_enabled(
+                        !state.stile.is_empty(),
+                        egui::Checkbox::new(&mut state.take_stil, tr("Stil-Tag")),
+                    );
+                    let stile: Vec<&str> = state
+                        .song
+                        .tags
+                        .iter()
+                        .filter(|t| t.kategorie == "stil")
+                        .map(|t| t.wert.as_str())
+                        .collect();
+                    ui.label(current((!stile.is_empty()).then(|| stile.join(", "))));
+                    if state.stile.is_empty() {
+                        ui.label(current(None));
+                    } else {
+                        egui::ComboBox::from_id_salt("musicbrainz_stil")
+                            .selected_text(&state.stil)
+                            .show_ui(ui, |ui| {
+                                for stil in &state.stile {
+                                    ui.selectable_value(&mut state.stil, stil.clone(), stil);
+                                }
+                            })
+                            .response
+                            .on_hover_text(tr("Genres bei MusicBrainz, meistgenanntes zuerst"));
+                    }
+                    ui.end_row();
+                });
+
+            ui.add_space(8.0);
+            let anything = state.take_artist
+                || state.take_jahr
+                || (state.details.is_some() && (state.take_tonart || state.take_stil));
+            let apply_btn = egui::Button::new(
+                egui::RichText::new(tr("\u{00DC}bernehmen")).color(palette::TEXT_PRIMARY),
+            )
+            .fill(palette::accent_dim())
+            .rounding(6.0);
+            if ui.add_enabled(anything, apply_btn).clicked() {
+                apply = true;
+            }
+        });
+
+        if select.is_some() && select != state.selected {
+            state.select(select);
+        }
+        if apply {
+            let song_id = state.song.id;
+            if let Some(candidate) = state.selected.and_then(|i| state.candidates.get(i)) {
+                let song = state.song.clone();
+                let candidate = candidate.clone();
+                let details = state.details.clone();
+                let take_artist = state.take_artist;
+                let take_jahr = state.take_jahr;
+                let take_tonart = state.take_tonart;
+                let stil = Some(state.stil.clone()).filter(|s| state.take_stil && !s.is_empty());
+                self.db.send(move |conn| {
+                    if take_artist {
+                        update_song(conn, song.id, &song.titel, &candidate.artist);
+                    }
+                    if take_jahr && candidate.jahr.is_some() {
+                        set_song_year(conn, song.id, candidate.jahr);
+                    }
+                    if let Some(details) = details {
+                        if take_tonart && details.tonart.is_some() {
+                            update_song_music(
+                                conn,
+                                song.id,
+                                details.tonart.as_deref(),
+                                song.bpm,
+                                song.kapo,
+                            );
+                        }
+                        if let Some(stil) = stil {
+                            apply_tag(conn, song.id, "stil", &stil);
+                        }
+                    }
+                });
+            }
+            self.refresh_song(song_id);
+            self.musicbrainz = None;
+        } else if !open {
+            self.musicbrainz = None;
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use super::{palette, SongIndexApp};
+use crate::i18n::{tr, tr_arg};
+use crate::scanner::add_single_file;
+use crate::templates::{chart_filename, create_chart, ensure_template_dir, list_templates};
+use eframe::egui;
+use std::path::{Path, PathBuf};
+
+pub(super) struct NewChartModalState {
+    templates: Vec<PathBuf>,
+    template_idx: usize,
+    titel: String,
+    artist: String,
+    target_dir: PathBuf,
+    error: Option<String>,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_new_chart(&mut self) {
+        let template_dir = self.config.template_dir();
+        ensure_template_dir(&template_dir);
+        self.new_chart_modal = Some(NewChartModalState {
+            templates: list_templates(&template_dir),
+            template_idx: 0,
+            titel: String::new(),
+            artist: String::new(),
+            target_dir: self.base_dir.clone(),
+            error: None,
+        });
+    }
+
+    pub(super) fn show_new_chart_modal(&mut self, ctx: &egui::Context) {
+        let mut close = false;
+        let mut create = false;
+        let base_dir = self.base_dir.clone();
+        let template_dir = self.config.template_dir();
+        let Some(ref mut modal) = self.new_chart_modal else {
+            return;
+        };
+
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Neues Chart anlegen"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .fixed_size([420.0, 240.0])
+        .show(ctx, |ui| {
+            if modal.templates.is_empty() {
+                ui.label(
+                    egui::RichText::new(tr_arg("Keine Vorlagen in {}", template_dir.display()))
+                        .color(palette::TEXT_MUTED),
+                );
+                return;
+            }
+
+            egui::Grid::new("new_chart_grid")
+                .num_columns(2)
+                .spacing([8.0, 6.0])
+                .show(ui, |ui| {
+                    ui.label(egui::RichText::new(tr("Vorlage:")).color(palette::TEXT_SECONDARY));
+                    let name = |p: &PathBuf| {
+                        p.file_name()
+                            .unwrap_or_default()
+                            .to_string_lossy()
+                            .to_string()
+                    };
+                    egui::ComboBox::from_id_salt("chart_template")
+                        .selected_text(name(&modal.templates[modal.template_idx]))
+                        .show_ui(ui, |ui| {
+                            for (i, t) in modal.templates.iter().enumerate() {
+                                ui.selectable_value(&mut modal.template_idx, i, name(t));
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label(egui::RichText::new(tr("Titel:")).color(palette::TEXT_SECONDARY));
+                    ui.text_edit_singleline(&mut modal.titel);
+                    ui.end_row();
+
+                    ui.label(egui::RichText::new("Artist:").color(palette::TEXT_SECONDARY));
+                    ui.text_edit_singleline(&mut modal.artist);
+                    ui.end_row();
+
+                    ui.label(egui::RichText::new(tr("Ordner:")).color(palette::TEXT_SECONDARY));
+                    target_folder_row(ui, &base_dir, &mut modal.target_dir, &mut modal.error);
+                    ui.end_row();
+                });
+
+            let ext = modal.templates[modal.template_idx]
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            if !modal.titel.trim().is_empty() {
+                ui.add_space(4.0);
+                ui.label(
+                    egui::RichText::new(chart_filename(&modal.titel, &modal.artist, &ext))
+                        .size(12.5)
+                        .color(palette::TEXT_MUTED),
+                );
+            }
+
+            if let Some(ref err) = modal.error {
+                ui.label(egui::RichText::new(err).color(palette::ACCENT_RED));
+            }
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                let create_btn = egui::Button::new(
+                    egui::RichText::new(tr("Anlegen")).color(palette::TEXT_PRIMARY),
+                )
+                .fill(palette::accent_dim())
+                .rounding(6.0);
+                if ui
+                    .add_enabled(!modal.titel.trim().is_empty(), create_btn)
+                    .clicked()
+                {
+                    create = true;
+                }
+                ui.add_space(4.0);
+                if ui.button(tr("Abbrechen")).clicked() {
+                    close = true;
+                }
+            });
+        });
+
+        if create {
+            match create_chart(
+                &modal.templates[modal.template_idx],
+                &modal.target_dir,
+                &modal.titel,
+                &modal.artist,
+            ) {
+                Ok(path) => {
+                    let options = self.scan_options.read().unwrap().clone();
+                    self.db
+                        .send(move |conn| add_single_file(conn, &base_dir, &path, &options));
+                    self.refresh_data();
+                    close = true;
+                }
+                Err(e) => modal.error = Some(e),
+            }
+        }
+
+        if !open || close {
+            self.new_chart_modal = None;
+        }
+    }
+}
+
+/// Shows the target folder relative to the music folder with a button to change it.
+/// Folders outside the music folder are rejected since they would not be indexed.
+pub(super) fn target_folder_row(
+    ui: &mut egui::Ui,
+    base_dir: &Path,
+    target_dir: &mut PathBuf,
+    error: &mut Option<String>,
+) {
+    ui.horizontal(|ui| {
+        let rel = target_dir
+            .strip_prefix(base_dir)
+            .map(|r| r.display().to_string())
+            .unwrap_or_default();
+        ui.label(
+            egui::RichText::new(if rel.is_empty() { "/".to_string() } else { rel })
+                .size(12.5)
+                .color(palette::TEXT_MUTED),
+        );
+        if ui.small_button(tr("\u{00C4}ndern")).clicked() {
+            if let Some(dir) = rfd::FileDialog::new()
+                .set_title(tr("Zielordner ausw\u{00E4}hlen"))
+                .set_directory(&*target_dir)
+                .pick_folder()
+            {
+                if dir.starts_with(base_dir) {
+                    *target_dir = dir;
+                    *error = None;
+                } else {
+                    *error = Some(tr("Der Ordner muss im Musikordner liegen.").to_string());
+                }
+            }
+        }
+    });
+}
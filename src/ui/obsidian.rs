@@ -0,0 +1,120 @@
+use super::{palette, save_config, SongIndexApp};
+use crate::config::ObsidianConfig;
+use crate::i18n::{tr, tr_args};
+use crate::obsidian::sync;
+use eframe::egui;
+
+pub(super) struct ObsidianWindowState {
+    obsidian: ObsidianConfig,
+    message: Option<String>,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_obsidian(&mut self) {
+        self.obsidian_window = Some(ObsidianWindowState {
+            obsidian: self.config.obsidian.clone(),
+            message: None,
+        });
+    }
+
+    pub(super) fn show_obsidian_window(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.obsidian_window else {
+            return;
+        };
+
+        let mut changed = false;
+        let mut export = false;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Obsidian-Export"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .fixed_size([420.0, 240.0])
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(
+                    tr("Eine Markdown-Notiz pro Song mit Tags, Tonart und Links im Frontmatter \
+                     und einem Link auf die Noten \u{2013} zum Verlinken aus deinem \
+                     Unterrichts-Vault. Die Notizen geh\u{00F6}ren Songindex und werden \
+                     beim Abgleich \u{00FC}berschrieben."),
+                )
+                .size(12.5)
+                .color(palette::TEXT_SECONDARY),
+            );
+            ui.add_space(6.0);
+            let obsidian = &mut state.obsidian;
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(tr("Ordner:")).color(palette::TEXT_SECONDARY));
+                let folder = obsidian
+                    .ordner
+                    .as_ref()
+                    .map_or("\u{2013}".to_string(), |p| p.display().to_string());
+                ui.label(
+                    egui::RichText::new(folder)
+                        .size(12.5)
+                        .color(palette::TEXT_MUTED),
+                );
+                if ui.button(tr("W\u{00E4}hlen\u{2026}")).clicked() {
+                    if let Some(dir) = rfd::FileDialog::new()
+                        .set_title(tr("Ordner im Obsidian-Vault"))
+                        .pick_folder()
+                    {
+                        obsidian.ordner = Some(dir);
+                        changed = true;
+                    }
+                }
+            });
+            changed |= ui
+                .checkbox(
+                    &mut obsidian.aktuell_halten,
+                    tr("Automatisch aktuell halten"),
+                )
+                .on_hover_text(tr("Gleicht die Notizen im Hintergrund ab, sobald sich die Bibliothek \u{00E4}ndert."))
+                .changed();
+
+            ui.add_space(8.0);
+            let export_btn = egui::Button::new(
+                egui::RichText::new(tr("Jetzt exportieren")).color(palette::TEXT_PRIMARY),
+            )
+            .fill(palette::accent_dim())
+            .rounding(6.0);
+            if ui
+                .add_enabled(obsidian.ordner.is_some(), export_btn)
+                .clicked()
+            {
+                export = true;
+            }
+            if let Some(ref message) = state.message {
+                ui.add_space(4.0);
+                ui.label(
+                    egui::RichText::new(message)
+                        .size(12.5)
+                        .color(palette::TEXT_MUTED),
+                );
+            }
+        });
+
+        if export {
+            if let Some(ref dir) = state.obsidian.ordner {
+                state.message = Some(match sync(&self.db, &self.base_dir, dir) {
+                    Ok(summary) => tr_args(
+                        "{} Songs \u{00B7} {} Notizen geschrieben, {} entfernt",
+                        &[&summary.total, &summary.written, &summary.removed],
+                    ),
+                    Err(e) => e,
+                });
+            }
+        }
+        if changed || export {
+            self.config.obsidian = state.obsidian.clone();
+            save_config(&self.config);
+        }
+        if !open {
+            self.obsidian_window = None;
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use super::type_select::list_has_keyboard;
+use super::{SongIndexApp, ViewMode};
+use crate::config::OpenAction;
+use eframe::egui;
+
+impl SongIndexApp {
+    /// The configured double-click/Enter action of the current view.
+    fn open_action(&self) -> OpenAction {
+        let actions = &self.config.open_actions;
+        match self.view_mode {
+            ViewMode::List => actions.liste,
+            ViewMode::Table => actions.tabelle,
+            ViewMode::Grid => actions.raster,
+        }
+    }
+
+    /// Enter while the list has the keyboard runs the default action on the
+    /// selected song.
+    pub(super) fn handle_open_key(&mut self, ctx: &egui::Context) {
+        if !list_has_keyboard(ctx) {
+            return;
+        }
+        let Some(song_id) = self.selected_song_id else {
+            return;
+        };
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Enter)) {
+            self.open_song(song_id);
+        }
+    }
+
+    /// Double-click on a song or Enter on the selection.
+    pub(super) fn open_song(&mut self, song_id: i64) {
+        let Some(song) = self.songs.iter().find(|s| s.id == song_id) else {
+            return;
+        };
+        self.selected_song_id = Some(song_id);
+        match (self.open_action(), song.audio_pfad.clone()) {
+            (OpenAction::Audio, Some(audio_pfad)) => {
+                if self.playing_song_id() == Some(song_id) {
+                    self.stop_audio();
+                } else {
+                    self.play_audio(song_id, &audio_pfad);
+                }
+            }
+            (OpenAction::Details, _) => {}
+            (OpenAction::Datei | OpenAction::Audio, _) => {
+                let dateipfad = song.dateipfad.clone();
+                self.open_song_file(&dateipfad);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::wake::notice_area;
+
+    /// Whether the list has the keyboard in the second of two frames that
+    /// show `ui`, once its areas count as visible.
+    fn list_has_keyboard_with(ui: impl Fn(&egui::Context)) -> bool {
+        let ctx = egui::Context::default();
+        let mut has_keyboard = false;
+        for _ in 0..2 {
+            let _ = ctx.run(egui::RawInput::default(), |ctx| {
+                ui(ctx);
+                has_keyboard = list_has_keyboard(ctx);
+            });
+        }
+        has_keyboard
+    }
+
+    #[test]
+    fn enter_reaches_the_list_while_the_wake_notice_shows() {
+        assert!(list_has_keyboard_with(|_| {}));
+        assert!(list_has_keyboard_with(|ctx| {
+            notice_area().show(ctx, |ui| ui.label("Nach dem Ruhezustand abgeglichen"));
+        }));
+        assert!(!list_has_keyboard_with(|ctx| {
+            egui::Window::new("Tags vergeben").show(ctx, |ui| ui.label("Tags"));
+        }));
+    }
+}
@@ -0,0 +1,118 @@
+use super::{palette, save_config, SongIndexApp};
+use crate::i18n::tr;
+use crate::open_with::pick_app;
+use eframe::egui;
+
+pub(super) struct OpenWithState {
+    /// Extension and app, in the order shown.
+    rows: Vec<(String, String)>,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_open_with(&mut self) {
+        self.open_with_window = Some(OpenWithState {
+            rows: self
+                .config
+                .open_with
+                .iter()
+                .map(|(ext, app)| (ext.clone(), app.clone()))
+                .collect(),
+        });
+    }
+
+    pub(super) fn show_open_with_window(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.open_with_window else {
+            return;
+        };
+
+        let mut save = false;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("\u{00D6}ffnen mit"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .fixed_size([460.0, 300.0])
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(tr(
+                    "Welches Programm eine Dateiendung \u{00F6}ffnet, z.\u{00A0}B. .gp in \
+                     Guitar Pro und .pdf in forScore. Ohne Eintrag gilt die Vorgabe von \
+                     macOS; einzelne Songs lassen sich unter \u{201E}Bearbeiten\u{201C} \
+                     abweichend einstellen.",
+                ))
+                .size(12.5)
+                .color(palette::TEXT_SECONDARY),
+            );
+            ui.add_space(6.0);
+            let mut remove = None;
+            egui::ScrollArea::vertical()
+                .max_height(170.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("open_with_grid")
+                        .num_columns(4)
+                        .spacing([6.0, 4.0])
+                        .show(ui, |ui| {
+                            for (i, (ext, app)) in state.rows.iter_mut().enumerate() {
+                                ui.add(
+                                    egui::TextEdit::singleline(ext)
+                                        .desired_width(60.0)
+                                        .hint_text("pdf"),
+                                );
+                                ui.add(
+                                    egui::TextEdit::singleline(app)
+                                        .desired_width(230.0)
+                                        .hint_text("Guitar Pro 8"),
+                                );
+                                if ui.small_button(tr("W\u{00E4}hlen\u{2026}")).clicked() {
+                                    if let Some(picked) = pick_app() {
+                                        *app = picked;
+                                    }
+                                }
+                                if ui.small_button("\u{2715}").clicked() {
+                                    remove = Some(i);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+            if let Some(i) = remove {
+                state.rows.remove(i);
+            }
+            if ui.small_button(tr("+ Zuordnung")).clicked() {
+                state.rows.push((String::new(), String::new()));
+            }
+            ui.add_space(8.0);
+            let save_btn = egui::Button::new(
+                egui::RichText::new(tr("Speichern")).color(palette::TEXT_PRIMARY),
+            )
+            .fill(palette::accent_dim())
+            .rounding(6.0);
+            if ui.add(save_btn).clicked() {
+                save = true;
+            }
+        });
+
+        if save {
+            // Stored without the dot and lowercase, as `Path::extension` compares.
+            self.config.open_with = state
+                .rows
+                .iter()
+                .map(|(ext, app)| {
+                    (
+                        ext.trim().trim_start_matches('.').to_lowercase(),
+                        app.trim().to_string(),
+                    )
+                })
+                .filter(|(ext, app)| !ext.is_empty() && !app.is_empty())
+                .collect();
+            save_config(&self.config);
+            self.open_with_window = None;
+        } else if !open {
+            self.open_with_window = None;
+        }
+    }
+}
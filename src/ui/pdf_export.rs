@@ -0,0 +1,168 @@
+use super::{palette, SongIndexApp};
+use crate::i18n::{tr, tr_arg, tr_args};
+use crate::pdf::{merge, ReportLine};
+use eframe::egui;
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, TryRecvError};
+
+pub(super) struct PdfExportState {
+    titel: String,
+    cover: bool,
+    message: Option<String>,
+    /// Message of the merge running on its own thread; loading and
+    /// renumbering every PDF takes a while for a long setlist.
+    merging: Option<Receiver<String>>,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_pdf_export(&mut self) {
+        self.pdf_export = Some(PdfExportState {
+            titel: "Setlist".to_string(),
+            cover: true,
+            message: None,
+            merging: None,
+        });
+    }
+
+    pub(super) fn show_pdf_export(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.pdf_export else {
+            return;
+        };
+        if let Some(ref rx) = state.merging {
+            match rx.try_recv() {
+                Ok(message) => {
+                    state.message = Some(message);
+                    state.merging = None;
+                }
+                Err(TryRecvError::Empty) => {
+                    ctx.request_repaint_after(std::time::Duration::from_millis(200));
+                }
+                Err(TryRecvError::Disconnected) => state.merging = None,
+            }
+        }
+
+        let pdfs: Vec<_> = self.songs.iter().filter(|s| s.dateityp == "pdf").collect();
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Als PDF-Mappe exportieren"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .fixed_size([420.0, 200.0])
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(tr_arg(
+                    "F\u{00FC}gt die PDFs der aktuellen Liste ({} Songs) in dieser Reihenfolge \
+                     zu einer Datei zusammen.",
+                    pdfs.len(),
+                ))
+                .color(palette::TEXT_SECONDARY),
+            );
+            ui.add_space(6.0);
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(tr("Titel:")).color(palette::TEXT_SECONDARY));
+                ui.text_edit_singleline(&mut state.titel);
+            });
+            ui.checkbox(&mut state.cover, tr("Deckblatt mit Songs und Tonarten"));
+            ui.add_space(8.0);
+
+            let save = egui::Button::new(
+                egui::RichText::new(tr("Speichern\u{2026}")).color(palette::TEXT_PRIMARY),
+            )
+            .fill(palette::accent_dim())
+            .rounding(6.0);
+            let idle = state.merging.is_none();
+            if ui.add_enabled(!pdfs.is_empty() && idle, save).clicked() {
+                if let Some(path) = rfd::FileDialog::new()
+                    .set_title(tr("PDF-Mappe speichern"))
+                    .set_file_name(format!("{}.pdf", state.titel.trim()))
+                    .add_filter("PDF", &["pdf"])
+                    .save_file()
+                {
+                    let cover = state.cover.then(|| {
+                        let mut lines = vec![
+                            ReportLine::text(state.titel.trim(), 20.0, true),
+                            ReportLine::blank(),
+                            ReportLine::row(
+                                vec![
+                                    (0.0, tr("Nr.").to_string()),
+                                    (30.0, tr("Titel").to_string()),
+                                    (260.0, "Artist".to_string()),
+                                    (410.0, tr("Tonart").to_string()),
+                                ],
+                                true,
+                                false,
+                            ),
+                        ];
+                        for (i, song) in pdfs.iter().enumerate() {
+                            let tonart = match (&song.tonart, song.kapo) {
+                                (Some(t), Some(k)) if k > 0 => {
+                                    format!("{t} ({})", tr_arg("Kapo {}", k))
+                                }
+                                (Some(t), _) => t.clone(),
+                                (None, _) => String::new(),
+                            };
+                            lines.push(ReportLine::row(
+                                vec![
+                                    (0.0, format!("{}", i + 1)),
+                                    (30.0, song.titel.clone()),
+                                    (260.0, song.artist.clone().unwrap_or_default()),
+                                    (410.0, tonart),
+                                ],
+                                false,
+                                false,
+                            ));
+                        }
+                        lines
+                    });
+                    let files: Vec<PathBuf> = pdfs
+                        .iter()
+                        .map(|s| self.base_dir.join(&s.dateipfad))
+                        .collect();
+                    let (tx, rx) = std::sync::mpsc::channel();
+                    std::thread::spawn(move || {
+                        let message = match merge(&path, cover.as_deref(), &files) {
+                            Ok(skipped) if skipped.is_empty() => {
+                                tr_arg("Gespeichert unter {}", path.display())
+                            }
+                            Ok(skipped) => tr_args(
+                                "Gespeichert unter {}. {} Dateien nicht lesbar (fehlt, \
+                                 besch\u{00E4}digt oder verschl\u{00FC}sselt) und ausgelassen.",
+                                &[&path.display(), &skipped.len()],
+                            ),
+                            Err(e) => e,
+                        };
+                        tx.send(message).ok();
+                    });
+                    state.merging = Some(rx);
+                    state.message = None;
+                }
+            }
+            if !idle {
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(
+                        egui::RichText::new(tr("PDFs werden zusammengef\u{00FC}gt\u{2026}"))
+                            .size(12.5)
+                            .color(palette::TEXT_MUTED),
+                    );
+                });
+            } else if let Some(ref message) = state.message {
+                ui.add_space(4.0);
+                ui.label(
+                    egui::RichText::new(message)
+                        .size(12.5)
+                        .color(palette::TEXT_MUTED),
+                );
+            }
+        });
+
+        if !open {
+            self.pdf_export = None;
+        }
+    }
+}
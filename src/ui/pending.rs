@@ -0,0 +1,57 @@
+use std::sync::mpsc::{Receiver, TryRecvError};
+
+/// Data a window shows, loaded on the database worker so no frame waits for
+/// it. A reload keeps showing the last answer until the next one is there.
+pub(super) struct Loaded<T> {
+    value: Option<T>,
+    next: Option<Receiver<T>>,
+}
+
+impl<T> Default for Loaded<T> {
+    /// Nothing loaded or requested.
+    fn default() -> Self {
+        Self {
+            value: None,
+            next: None,
+        }
+    }
+}
+
+impl<T> Loaded<T> {
+    pub(super) fn new(rx: Receiver<T>) -> Self {
+        Self {
+            value: None,
+            next: Some(rx),
+        }
+    }
+
+    /// Replace the value with the answer of `rx` once it arrives.
+    pub(super) fn reload(&mut self, rx: Receiver<T>) {
+        self.next = Some(rx);
+    }
+
+    /// The latest answer; None until the first one arrived.
+    pub(super) fn get(&mut self) -> Option<&mut T> {
+        if let Some(value) = answer(&mut self.next) {
+            self.value = Some(value);
+        }
+        self.value.as_mut()
+    }
+}
+
+/// The answer of a request once it has arrived; `pending` is cleared then.
+/// A request that failed (the worker logs it) clears it without an answer.
+pub(super) fn answer<T>(pending: &mut Option<Receiver<T>>) -> Option<T> {
+    let rx = pending.as_ref()?;
+    match rx.try_recv() {
+        Ok(value) => {
+            *pending = None;
+            Some(value)
+        }
+        Err(TryRecvError::Empty) => None,
+        Err(TryRecvError::Disconnected) => {
+            *pending = None;
+            None
+        }
+    }
+}
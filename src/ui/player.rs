@@ -0,0 +1,638 @@
+use super::pending::answer;
+use super::practice::format_duration;
+use super::{palette, save_config, SongIndexApp};
+use crate::db::{
+    log_song_event, set_song_gain_db, set_song_loop, set_song_semitones, song_gain_db, song_loop,
+    song_semitones, SongEvent,
+};
+use crate::i18n::tr;
+use crate::jobs::{JobKind, Priority};
+use crate::player::Player;
+use crate::stretch::{MAX_SEMITONES, MAX_TEMPO, MIN_TEMPO};
+use crate::thumbnails::is_fresh;
+use crate::transpose::Key;
+use crate::waveform::{load_waveform, waveform_path};
+use eframe::egui;
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+const GAIN_HELP: &str = "Pegel dieses Songs, z.\u{00A0}B. f\u{00FC}r zu laute Backing-Tracks";
+
+/// A drag ended, or the value changed by a click or keyboard. Saving only
+/// then avoids a write per frame while dragging.
+fn edit_finished(response: &egui::Response) -> bool {
+    response.drag_stopped() || response.changed() && !response.dragged()
+}
+
+pub(super) struct NowPlaying {
+    pub(super) song_id: i64,
+    titel: String,
+    audio_pfad: String,
+    player: Player,
+    /// Peaks for the seek bar; `None` until the job has rendered them.
+    waveform: Option<Vec<f32>>,
+    waveform_pending: bool,
+    /// Per-song gain in dB, stored in `songs.pegel_db`.
+    gain_db: f32,
+    /// Per-song pitch shift, stored in `songs.halbtoene`.
+    semitones: i32,
+    /// The song's key, to show what the shift transposes it to.
+    key: Option<Key>,
+    /// Slider position while the knob is dragged; seeking happens on release.
+    scrub: Option<f32>,
+    /// A–B loop markers in seconds; the loop runs once both are set.
+    loop_a: Option<f32>,
+    loop_b: Option<f32>,
+    /// Markers are saved with the song (`schleife_von`/`schleife_bis`).
+    loop_saved: bool,
+}
+
+impl NowPlaying {
+    fn loop_range(&self) -> Option<(f32, f32)> {
+        self.loop_a.zip(self.loop_b).filter(|(a, b)| a < b)
+    }
+
+    fn apply_loop(&self) {
+        self.player.set_loop(
+            self.loop_range()
+                .map(|(a, b)| (Duration::from_secs_f32(a), Duration::from_secs_f32(b))),
+        );
+    }
+}
+
+/// Gain in dB, saved A–B loop and pitch shift of a song.
+type SongSettings = (f32, Option<(f32, f32)>, i32);
+
+/// A song that starts playing once its stored settings are loaded.
+pub(super) struct StartingPlayback {
+    song_id: i64,
+    audio_pfad: String,
+    /// Direction to go on in if the file cannot be played; `None` outside a
+    /// queue, where it opens in the default app instead.
+    queue_direction: Option<isize>,
+    settings: Option<Receiver<SongSettings>>,
+}
+
+/// "Alle abspielen": the songs with audio from the list as it was when the
+/// queue started, played one after another.
+pub(super) struct PlayQueue {
+    /// `(song id, audio_pfad)`
+    songs: Vec<(i64, String)>,
+    idx: usize,
+}
+
+impl SongIndexApp {
+    pub(super) fn playing_song_id(&self) -> Option<i64> {
+        self.now_playing.as_ref().map(|n| n.song_id)
+    }
+
+    /// The file playing right now; a companion recording is not the song's
+    /// `audio_pfad`.
+    pub(super) fn playing_audio(&self) -> Option<&str> {
+        self.now_playing.as_ref().map(|n| n.audio_pfad.as_str())
+    }
+
+    /// Play a song's audio in the player bar. Formats rodio cannot decode
+    /// (and builds without audio output) open in the default app instead.
+    /// Ends a running queue.
+    pub(super) fn play_audio(&mut self, song_id: i64, audio_pfad: &str) {
+        self.stop_audio();
+        self.load_playback(song_id, audio_pfad, None);
+    }
+
+    /// Queue the audio of every song in the current list and start the first.
+    pub(super) fn play_all(&mut self) {
+        let songs: Vec<(i64, String)> = self
+            .songs
+            .iter()
+            .filter(|s| s.has_audio)
+            .filter_map(|s| Some((s.id, s.audio_pfad.clone()?)))
+            .collect();
+        self.stop_audio();
+        if !songs.is_empty() {
+            self.play_queue = Some(PlayQueue { songs, idx: 0 });
+            self.play_queue_from(0, 1);
+        }
+    }
+
+    /// Play queue entry `idx`, or the next one in `direction` (±1) if a file
+    /// cannot be played. Runs off either end: the queue is done.
+    fn play_queue_from(&mut self, idx: usize, direction: isize) {
+        self.now_playing = None;
+        let Some((song_id, audio_pfad)) = self
+            .play_queue
+            .as_ref()
+            .and_then(|q| q.songs.get(idx).cloned())
+        else {
+            self.play_queue = None;
+            return;
+        };
+        self.play_queue.as_mut().unwrap().idx = idx;
+        self.load_playback(song_id, &audio_pfad, Some(direction));
+    }
+
+    /// Record the play and load the song's settings; `poll_playback` starts
+    /// it once they are there.
+    fn load_playback(&mut self, song_id: i64, audio_pfad: &str, queue_direction: Option<isize>) {
+        let settings = self.db.request(move |conn| {
+            log_song_event(conn, song_id, SongEvent::Played);
+            (
+                song_gain_db(conn, song_id),
+                song_loop(conn, song_id),
+                song_semitones(conn, song_id),
+            )
+        });
+        self.starting_playback = Some(StartingPlayback {
+            song_id,
+            audio_pfad: audio_pfad.to_string(),
+            queue_direction,
+            settings: Some(settings),
+        });
+    }
+
+    /// Start the song whose settings have arrived. If it cannot be played, a
+    /// queue goes on with the next one; a single song opens in its app.
+    fn poll_playback(&mut self) {
+        let Some(starting) = self.starting_playback.as_mut() else {
+            return;
+        };
+        let Some(settings) = answer(&mut starting.settings) else {
+            if starting.settings.is_none() {
+                self.starting_playback = None;
+            }
+            return;
+        };
+        let starting = self.starting_playback.take().unwrap();
+        self.refresh_song(starting.song_id);
+        let Err(e) = self.start_playback(starting.song_id, &starting.audio_pfad, settings) else {
+            return;
+        };
+        log::warn!("Player: {e}");
+        match starting.queue_direction {
+            Some(direction) => {
+                let next = self
+                    .play_queue
+                    .as_ref()
+                    .and_then(|q| q.idx.checked_add_signed(direction));
+                match next {
+                    Some(next) => self.play_queue_from(next, direction),
+                    None => self.play_queue = None,
+                }
+            }
+            None => {
+                let full_path = self.base_dir.join(&starting.audio_pfad);
+                crate::open_with::open_by_extension(&full_path, &self.config.open_with);
+            }
+        }
+    }
+
+    fn start_playback(
+        &mut self,
+        song_id: i64,
+        audio_pfad: &str,
+        (gain_db, saved_loop, semitones): SongSettings,
+    ) -> Result<(), String> {
+        let full_path = self.base_dir.join(audio_pfad);
+        let player = Player::open(
+            &full_path,
+            self.config.volume,
+            gain_db,
+            self.playback_tempo,
+            semitones,
+        )?;
+        let song = self.songs.iter().find(|s| s.id == song_id);
+        let titel = song
+            .map(|s| s.titel.clone())
+            .unwrap_or_else(|| audio_pfad.to_string());
+        let key = song.and_then(|s| s.tonart.as_deref()).and_then(Key::parse);
+        let cache = waveform_path(audio_pfad);
+        let waveform = is_fresh(&cache, &full_path)
+            .then(|| load_waveform(&cache))
+            .flatten();
+        if waveform.is_none() {
+            self.jobs.push(
+                JobKind::Waveform,
+                audio_pfad,
+                full_path.clone(),
+                Priority::Visible,
+            );
+        }
+        let now = NowPlaying {
+            song_id,
+            titel,
+            audio_pfad: audio_pfad.to_string(),
+            waveform_pending: waveform.is_none(),
+            waveform,
+            player,
+            gain_db,
+            semitones,
+            key,
+            scrub: None,
+            loop_a: saved_loop.map(|(a, _)| a),
+            loop_b: saved_loop.map(|(_, b)| b),
+            loop_saved: saved_loop.is_some(),
+        };
+        now.apply_loop();
+        self.now_playing = Some(now);
+        Ok(())
+    }
+
+    pub(super) fn stop_audio(&mut self) {
+        self.now_playing = None;
+        self.starting_playback = None;
+        self.play_queue = None;
+    }
+
+    pub(super) fn check_audio_finished(&mut self) {
+        self.poll_playback();
+        if self
+            .now_playing
+            .as_ref()
+            .is_some_and(|n| n.player.is_finished())
+        {
+            match self.play_queue.as_ref().map(|q| q.idx) {
+                Some(idx) => self.play_queue_from(idx + 1, 1),
+                None => self.now_playing = None,
+            }
+        }
+    }
+
+    /// Bottom bar while audio plays: pause/resume, stop, previous/next in a
+    /// queue, elapsed/total time,
+    /// a seek slider, A–B loop markers, tempo, the song's pitch shift, the
+    /// global volume and the song's gain.
+    pub(super) fn show_player_bar(&mut self, ctx: &egui::Context) {
+        let Some(ref mut now) = self.now_playing else {
+            return;
+        };
+        if !now.player.is_paused() {
+            ctx.request_repaint_after(Duration::from_millis(250));
+        }
+        if now.waveform_pending && !self.jobs.is_pending(JobKind::Waveform, &now.audio_pfad) {
+            now.waveform_pending = false;
+            now.waveform = load_waveform(&waveform_path(&now.audio_pfad));
+        }
+
+        let mut stop = false;
+        let mut skip: Option<isize> = None;
+        let queue_pos = self.play_queue.as_ref().map(|q| (q.idx, q.songs.len()));
+        let mut volume_changed = false;
+        let mut gain_changed = false;
+        let mut semitones_changed = false;
+        let mut loop_changed = false;
+        let volume = &mut self.config.volume;
+        let tempo = &mut self.playback_tempo;
+        egui::TopBottomPanel::bottom("player_bar")
+            .frame(
+                egui::Frame::none()
+                    .fill(palette::bg_header())
+                    .inner_margin(egui::Margin::symmetric(16.0, 8.0))
+                    .stroke(egui::Stroke::new(0.5, palette::border_subtle())),
+            )
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let (icon, hover) = if now.player.is_paused() {
+                        ("\u{25B6}", tr("Weiter"))
+                    } else {
+                        ("\u{23F8}", "Pause")
+                    };
+                    let pause = egui::Button::new(
+                        egui::RichText::new(icon)
+                            .size(13.0)
+                            .color(egui::Color32::WHITE),
+                    )
+                    .fill(palette::TAG_STIMMUNG)
+                    .rounding(4.0);
+                    if ui.add(pause).on_hover_text(hover).clicked() {
+                        now.player.toggle_pause();
+                    }
+                    let stop_btn = egui::Button::new(
+                        egui::RichText::new("\u{25A0}")
+                            .size(13.0)
+                            .color(egui::Color32::WHITE),
+                    )
+                    .fill(palette::ACCENT_RED)
+                    .rounding(4.0);
+                    if ui.add(stop_btn).on_hover_text("Stop").clicked() {
+                        stop = true;
+                    }
+                    if let Some((idx, len)) = queue_pos {
+                        if ui
+                            .add_enabled(idx > 0, egui::Button::new("\u{23EE}"))
+                            .on_hover_text(tr("Vorheriger Song"))
+                            .clicked()
+                        {
+                            skip = Some(-1);
+                        }
+                        if ui
+                            .add_enabled(idx + 1 < len, egui::Button::new("\u{23ED}"))
+                            .on_hover_text(tr("N\u{00E4}chster Song"))
+                            .clicked()
+                        {
+                            skip = Some(1);
+                        }
+                        ui.label(
+                            egui::RichText::new(format!("{}/{len}", idx + 1))
+                                .size(12.0)
+                                .monospace()
+                                .color(palette::TEXT_MUTED),
+                        );
+                    }
+                    ui.label(
+                        egui::RichText::new(&now.titel)
+                            .size(14.0)
+                            .strong()
+                            .color(palette::TEXT_PRIMARY),
+                    );
+
+                    let position = now.scrub.unwrap_or(now.player.position().as_secs_f32());
+                    ui.label(
+                        egui::RichText::new(format_duration(position as i64))
+                            .size(13.0)
+                            .monospace()
+                            .color(palette::accent()),
+                    );
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let gain = ui
+                            .add(
+                                egui::DragValue::new(&mut now.gain_db)
+                                    .range(-24.0..=12.0)
+                                    .speed(0.2)
+                                    .fixed_decimals(1)
+                                    .suffix(" dB"),
+                            )
+                            .on_hover_text(tr(GAIN_HELP));
+                        let slider = ui
+                            .add(egui::Slider::new(volume, 0.0..=1.0).show_value(false))
+                            .on_hover_text(tr("Lautst\u{00E4}rke"));
+                        ui.label(
+                            egui::RichText::new("\u{1F50A}")
+                                .size(13.0)
+                                .color(palette::TEXT_MUTED),
+                        );
+                        if gain.changed() || slider.changed() {
+                            now.player.set_volume(*volume, now.gain_db);
+                        }
+                        ui.add_space(8.0);
+                        let pitch = ui
+                            .add(
+                                egui::DragValue::new(&mut now.semitones)
+                                    .range(-MAX_SEMITONES..=MAX_SEMITONES)
+                                    .speed(0.1)
+                                    .custom_formatter(|v, _| match v as i32 {
+                                        0 => "\u{00B1}0".to_string(),
+                                        v => format!("{v:+}"),
+                                    }),
+                            )
+                            .on_hover_text(pitch_help(now.key, now.semitones));
+                        if pitch.double_clicked() {
+                            now.semitones = 0;
+                        }
+                        if pitch.changed() || pitch.double_clicked() {
+                            now.player.set_semitones(now.semitones);
+                        }
+                        semitones_changed = edit_finished(&pitch) || pitch.double_clicked();
+                        ui.label(
+                            egui::RichText::new(tr("Halbt\u{00F6}ne"))
+                                .size(12.0)
+                                .color(palette::TEXT_MUTED),
+                        );
+                        ui.add_space(8.0);
+                        let tempo_edit = ui
+                            .add(
+                                egui::DragValue::new(tempo)
+                                    .range(MIN_TEMPO..=MAX_TEMPO)
+                                    .speed(0.01)
+                                    .custom_formatter(|v, _| format!("{:.0} %", v * 100.0))
+                                    .custom_parser(|s| {
+                                        let s = s.trim().trim_end_matches('%').trim();
+                                        s.parse::<f64>().ok().map(|v| v / 100.0)
+                                    }),
+                            )
+                            .on_hover_text(tr("Tempo \u{2013} die Tonh\u{00F6}he bleibt gleich. \
+                                 Doppelklick setzt auf 100\u{00A0}% zur\u{00FC}ck."));
+                        if tempo_edit.double_clicked() {
+                            *tempo = 1.0;
+                        }
+                        if tempo_edit.changed() || tempo_edit.double_clicked() {
+                            now.player.set_tempo(*tempo);
+                        }
+                        ui.label(
+                            egui::RichText::new("Tempo")
+                                .size(12.0)
+                                .color(palette::TEXT_MUTED),
+                        );
+                        ui.add_space(8.0);
+                        loop_changed = loop_controls(ui, now, position);
+                        volume_changed = edit_finished(&slider);
+                        gain_changed = edit_finished(&gain);
+
+                        let Some(total) = now.player.total() else {
+                            return;
+                        };
+                        let total = total.as_secs_f32();
+                        ui.add_space(8.0);
+                        ui.label(
+                            egui::RichText::new(format_duration(total as i64))
+                                .size(13.0)
+                                .monospace()
+                                .color(palette::TEXT_MUTED),
+                        );
+                        let mut value = position;
+                        let seek = if let Some(peaks) = &now.waveform {
+                            waveform_bar(ui, peaks, &mut value, total, now.loop_range())
+                        } else {
+                            ui.spacing_mut().slider_width = ui.available_width();
+                            let seek = ui.add(
+                                egui::Slider::new(&mut value, 0.0..=total.max(0.1))
+                                    .show_value(false),
+                            );
+                            if let Some((a, b)) = now.loop_range() {
+                                let x =
+                                    |t: f32| egui::lerp(seek.rect.x_range(), t / total.max(0.1));
+                                let band = egui::Rect::from_x_y_ranges(
+                                    x(a)..=x(b),
+                                    seek.rect.center().y - 3.0..=seek.rect.center().y + 3.0,
+                                );
+                                ui.painter().rect_filled(
+                                    band,
+                                    2.0,
+                                    palette::accent().gamma_multiply(0.35),
+                                );
+                            }
+                            seek
+                        };
+                        if seek.dragged() {
+                            now.scrub = Some(value);
+                        } else if edit_finished(&seek) {
+                            now.player.seek(Duration::from_secs_f32(value));
+                            now.scrub = None;
+                        }
+                    });
+                });
+            });
+
+        if gain_changed {
+            let (song_id, gain_db) = (now.song_id, now.gain_db);
+            self.db
+                .send(move |conn| set_song_gain_db(conn, song_id, gain_db));
+        }
+        if semitones_changed {
+            let (song_id, semitones) = (now.song_id, now.semitones);
+            self.db
+                .send(move |conn| set_song_semitones(conn, song_id, semitones));
+        }
+        if loop_changed {
+            now.apply_loop();
+            // Unsaved markers clear what was stored; the loop keeps running.
+            let saved = now.loop_range().filter(|_| now.loop_saved);
+            let song_id = now.song_id;
+            self.db
+                .send(move |conn| set_song_loop(conn, song_id, saved));
+        }
+        if volume_changed {
+            save_config(&self.config);
+        }
+        if stop {
+            self.stop_audio();
+        } else if let (Some(direction), Some((idx, _))) = (skip, queue_pos) {
+            self.play_queue_from(idx.saturating_add_signed(direction), direction);
+        }
+    }
+}
+
+/// Hover text of the pitch shift, with the key the song then sounds in.
+fn pitch_help(key: Option<Key>, semitones: i32) -> String {
+    let mut help = tr(
+        "Tonh\u{00F6}he in Halbt\u{00F6}nen \u{2013} das Tempo bleibt gleich. \
+         Wird mit dem Song gespeichert, Doppelklick setzt zur\u{00FC}ck.",
+    )
+    .to_string();
+    if let Some(key) = key.filter(|_| semitones != 0) {
+        help.push_str(&format!(
+            "\n{} \u{2192} {}",
+            key.name(),
+            key.transposed(i64::from(semitones)).name()
+        ));
+    }
+    help
+}
+
+/// "A", "B", the loop range, clear and "Merken". Returns whether the markers
+/// or the saved state changed. Laid out right to left like the rest of the
+/// bar's right side.
+fn loop_controls(ui: &mut egui::Ui, now: &mut NowPlaying, position: f32) -> bool {
+    let mut changed = false;
+    let saved =
+        egui::SelectableLabel::new(now.loop_saved, egui::RichText::new(tr("Merken")).size(12.0));
+    if ui
+        .add(saved)
+        .on_hover_text(tr("Schleife mit dem Song speichern"))
+        .clicked()
+    {
+        now.loop_saved = !now.loop_saved;
+        changed = true;
+    }
+    if (now.loop_a.is_some() || now.loop_b.is_some())
+        && ui
+            .small_button("\u{2715}")
+            .on_hover_text(tr("Schleife aufheben"))
+            .clicked()
+    {
+        now.loop_a = None;
+        now.loop_b = None;
+        changed = true;
+    }
+    let range = match (now.loop_a, now.loop_b) {
+        (Some(a), Some(b)) => format!(
+            "{}\u{2013}{}",
+            format_duration(a as i64),
+            format_duration(b as i64)
+        ),
+        (Some(a), None) => format!("{}\u{2013}", format_duration(a as i64)),
+        _ => String::new(),
+    };
+    if !range.is_empty() {
+        ui.label(
+            egui::RichText::new(range)
+                .size(12.0)
+                .monospace()
+                .color(palette::TEXT_MUTED),
+        );
+    }
+    let b = ui
+        .add_enabled(
+            now.loop_a.is_some_and(|a| position > a),
+            egui::Button::new(egui::RichText::new("B").size(12.0)),
+        )
+        .on_hover_text(tr("Schleifenende an der aktuellen Stelle"));
+    if b.clicked() {
+        now.loop_b = Some(position);
+        changed = true;
+    }
+    if ui
+        .button(egui::RichText::new("A").size(12.0))
+        .on_hover_text(tr("Schleifenanfang an der aktuellen Stelle"))
+        .clicked()
+    {
+        now.loop_a = Some(position);
+        if now.loop_b.is_some_and(|b| b <= position) {
+            now.loop_b = None;
+        }
+        changed = true;
+    }
+    changed
+}
+
+/// Peaks as the seek bar: the played part in the accent color, the A–B loop
+/// shaded between its markers. Clicking or dragging moves `value` like the
+/// slider it replaces.
+fn waveform_bar(
+    ui: &mut egui::Ui,
+    peaks: &[f32],
+    value: &mut f32,
+    total: f32,
+    loop_range: Option<(f32, f32)>,
+) -> egui::Response {
+    let total = total.max(0.1);
+    let size = egui::vec2(ui.available_width(), 26.0);
+    let (rect, mut response) = ui.allocate_exact_size(size, egui::Sense::click_and_drag());
+    if let Some(pointer) = response.interact_pointer_pos() {
+        *value = egui::remap_clamp(pointer.x, rect.x_range(), 0.0..=total);
+        response.mark_changed();
+    }
+
+    let x = |t: f32| egui::lerp(rect.x_range(), (t / total).clamp(0.0, 1.0));
+    let painter = ui.painter_at(rect);
+    if let Some((a, b)) = loop_range {
+        painter.rect_filled(
+            egui::Rect::from_x_y_ranges(x(a)..=x(b), rect.y_range()),
+            2.0,
+            palette::accent().gamma_multiply(0.15),
+        );
+        for t in [a, b] {
+            painter.vline(
+                x(t),
+                rect.y_range(),
+                egui::Stroke::new(1.0, palette::accent_dim()),
+            );
+        }
+    }
+    let played = x(*value);
+    let step = rect.width() / peaks.len() as f32;
+    for (i, peak) in peaks.iter().enumerate() {
+        let px = rect.left() + (i as f32 + 0.5) * step;
+        let half = (peak * rect.height() / 2.0).max(0.5);
+        let color = if px <= played {
+            palette::accent()
+        } else {
+            palette::TEXT_MUTED
+        };
+        painter.vline(
+            px,
+            rect.center().y - half..=rect.center().y + half,
+            egui::Stroke::new((step * 0.7).max(1.0), color),
+        );
+    }
+    response
+}
@@ -0,0 +1,130 @@
+use super::pending::Loaded;
+use super::{palette, SongIndexApp};
+use crate::db::{get_practice_seconds, log_practice};
+use crate::i18n::{tr, tr_arg};
+use eframe::egui;
+use std::time::Instant;
+
+/// Sessions shorter than this are treated as accidental clicks and not logged.
+const MIN_PRACTICE_SECONDS: u64 = 30;
+
+pub(super) struct PracticeTimer {
+    pub(super) song_id: i64,
+    titel: String,
+    started: Instant,
+    /// Practice time logged before this session.
+    total_before: Loaded<i64>,
+}
+
+pub(super) fn format_duration(seconds: i64) -> String {
+    if seconds >= 3600 {
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            (seconds / 60) % 60,
+            seconds % 60
+        )
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+impl PracticeTimer {
+    pub(super) fn elapsed_seconds(&self) -> i64 {
+        self.started.elapsed().as_secs() as i64
+    }
+}
+
+impl SongIndexApp {
+    pub(super) fn start_practice(&mut self, song_id: i64, titel: String) {
+        self.stop_practice();
+        let total_before = Loaded::new(
+            self.db
+                .request(move |conn| get_practice_seconds(conn, song_id)),
+        );
+        self.practice_timer = Some(PracticeTimer {
+            song_id,
+            titel,
+            started: Instant::now(),
+            total_before,
+        });
+    }
+
+    /// Stop the running timer and add the elapsed time to the practice log.
+    pub(super) fn stop_practice(&mut self) {
+        let Some(timer) = self.practice_timer.take() else {
+            return;
+        };
+        let seconds = timer.started.elapsed().as_secs();
+        if seconds >= MIN_PRACTICE_SECONDS {
+            self.db
+                .send(move |conn| log_practice(conn, timer.song_id, seconds as i64));
+            // The week's total in the header comes with the stats.
+            self.meta_stale = true;
+        }
+    }
+
+    pub(super) fn show_practice_bar(&mut self, ctx: &egui::Context) {
+        let Some(ref mut timer) = self.practice_timer else {
+            return;
+        };
+        ctx.request_repaint_after(std::time::Duration::from_millis(500));
+
+        let elapsed = timer.started.elapsed().as_secs() as i64;
+        let mut stop = false;
+        egui::TopBottomPanel::bottom("practice_bar")
+            .frame(
+                egui::Frame::none()
+                    .fill(palette::bg_header())
+                    .inner_margin(egui::Margin::symmetric(16.0, 8.0))
+                    .stroke(egui::Stroke::new(0.5, palette::border_subtle())),
+            )
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(tr("\u{23F1} \u{00DC}ben:"))
+                            .size(13.0)
+                            .color(palette::TEXT_MUTED),
+                    );
+                    ui.label(
+                        egui::RichText::new(&timer.titel)
+                            .size(14.0)
+                            .strong()
+                            .color(palette::TEXT_PRIMARY),
+                    );
+                    ui.label(
+                        egui::RichText::new(format_duration(elapsed))
+                            .size(14.0)
+                            .monospace()
+                            .color(palette::accent()),
+                    );
+                    if let Some(total_before) = timer.total_before.get() {
+                        ui.label(
+                            egui::RichText::new(tr_arg(
+                                "(insgesamt {})",
+                                format_duration(*total_before + elapsed),
+                            ))
+                            .size(12.0)
+                            .color(palette::TEXT_MUTED),
+                        );
+                    }
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let btn = egui::Button::new(
+                            egui::RichText::new("\u{25A0} Stop")
+                                .size(12.0)
+                                .color(egui::Color32::WHITE),
+                        )
+                        .fill(palette::ACCENT_RED)
+                        .rounding(4.0);
+                        if ui.add(btn).clicked() {
+                            stop = true;
+                        }
+                    });
+                });
+            });
+
+        if stop {
+            self.stop_practice();
+        }
+    }
+}
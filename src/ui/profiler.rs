@@ -0,0 +1,204 @@
+use super::{palette, SongIndexApp};
+use crate::i18n::tr;
+use eframe::egui;
+use puffin::{FrameView, GlobalFrameView, Reader, ScopeCollection, Stream};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Frames kept for the frame time graph and the scope table.
+const FRAME_HISTORY: usize = 240;
+/// Frame budget at 60 Hz, drawn as a reference line in the graph.
+const FRAME_BUDGET_MS: f32 = 1000.0 / 60.0;
+
+/// Timings of one named scope over the kept frames, in nanoseconds.
+#[derive(Default)]
+struct ScopeStats {
+    calls: u64,
+    last: i64,
+    total: i64,
+    max: i64,
+}
+
+impl SongIndexApp {
+    /// Hidden developer mode (Shift+F12): frame times and the cost of the
+    /// instrumented scopes (list rendering, DB queries, scans). puffin only
+    /// records while the window is open.
+    pub(super) fn show_profiler(&mut self, ctx: &egui::Context) {
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::SHIFT, egui::Key::F12)) {
+            self.profiler = match self.profiler {
+                Some(_) => None,
+                None => Some(frame_view()),
+            };
+            puffin::set_scopes_on(self.profiler.is_some());
+        }
+        let Some(ref view) = self.profiler else {
+            return;
+        };
+        // Keep measuring while idle, otherwise the numbers freeze.
+        ctx.request_repaint_after(Duration::from_millis(250));
+
+        let (scopes, ms) = collect(&view.lock());
+        let mut reset = false;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new("Profiler")
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .id(egui::Id::new("profiler"))
+        .open(&mut open)
+        .collapsible(true)
+        .resizable(false)
+        .default_pos([20.0, 80.0])
+        .show(ctx, |ui| {
+            let average = ms.iter().sum::<f32>() / ms.len().max(1) as f32;
+            let max = ms.iter().copied().fold(0.0, f32::max);
+            ui.label(
+                egui::RichText::new(format!(
+                    "Frame: \u{00D8} {average:.2} ms \u{00B7} max {max:.2} ms \u{00B7} {} Songs",
+                    self.songs.len()
+                ))
+                .color(palette::TEXT_SECONDARY),
+            );
+            frame_graph(ui, &ms);
+
+            ui.add_space(6.0);
+            egui::Grid::new("profiler_scopes")
+                .num_columns(5)
+                .spacing([14.0, 2.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    for header in ["Bereich", "Aufrufe", "zuletzt", "\u{00D8}", "max"] {
+                        ui.label(
+                            egui::RichText::new(header)
+                                .size(11.5)
+                                .color(palette::TEXT_MUTED),
+                        );
+                    }
+                    ui.end_row();
+                    for (name, stats) in &scopes {
+                        ui.label(
+                            egui::RichText::new(name)
+                                .size(12.0)
+                                .color(palette::TEXT_SECONDARY),
+                        );
+                        ui.label(mono(stats.calls.to_string()));
+                        ui.label(mono(format!("{:.2} ms", millis(stats.last))));
+                        ui.label(mono(format!(
+                            "{:.2} ms",
+                            millis(stats.total / stats.calls as i64)
+                        )));
+                        ui.label(mono(format!("{:.2} ms", millis(stats.max))));
+                        ui.end_row();
+                    }
+                });
+
+            ui.add_space(6.0);
+            if ui.button(tr("Zur\u{00FC}cksetzen")).clicked() {
+                reset = true;
+            }
+        });
+
+        if !open {
+            self.profiler = None;
+            puffin::set_scopes_on(false);
+        } else if reset {
+            self.profiler = Some(frame_view());
+        }
+    }
+}
+
+/// A fresh sink on the global profiler; dropping it unregisters it.
+fn frame_view() -> GlobalFrameView {
+    let view = GlobalFrameView::default();
+    view.lock().set_max_recent(FRAME_HISTORY);
+    view
+}
+
+/// All scopes by name, and the `Frame` scope durations in milliseconds,
+/// oldest first.
+fn collect(view: &FrameView) -> (BTreeMap<String, ScopeStats>, Vec<f32>) {
+    let mut scopes = BTreeMap::new();
+    let mut frames = Vec::new();
+    for frame in view.recent_frames().filter_map(|f| f.unpacked().ok()) {
+        for info in frame.thread_streams.values() {
+            add_scopes(
+                view.scope_collection(),
+                &info.stream,
+                0,
+                &mut scopes,
+                &mut frames,
+            );
+        }
+    }
+    (scopes, frames)
+}
+
+/// Adds the scopes starting at `offset` and their children.
+fn add_scopes(
+    names: &ScopeCollection,
+    stream: &Stream,
+    offset: u64,
+    scopes: &mut BTreeMap<String, ScopeStats>,
+    frames: &mut Vec<f32>,
+) {
+    let Ok(reader) = Reader::with_offset(stream, offset) else {
+        return;
+    };
+    for scope in reader.map_while(Result::ok) {
+        let Some(details) = names.fetch_by_id(&scope.id) else {
+            continue;
+        };
+        let duration = scope.record.duration_ns;
+        if details.name() == "Frame" {
+            frames.push(millis(duration));
+        }
+        let stats = scopes.entry(details.name().to_string()).or_default();
+        stats.calls += 1;
+        stats.last = duration;
+        stats.total += duration;
+        stats.max = stats.max.max(duration);
+        add_scopes(names, stream, scope.child_begin_position, scopes, frames);
+    }
+}
+
+fn millis(ns: i64) -> f32 {
+    ns as f32 / 1_000_000.0
+}
+
+fn mono(text: String) -> egui::RichText {
+    egui::RichText::new(text)
+        .size(11.5)
+        .monospace()
+        .color(palette::TEXT_PRIMARY)
+}
+
+/// Bar per frame, scaled so the 60 Hz budget sits at half height.
+fn frame_graph(ui: &mut egui::Ui, ms: &[f32]) {
+    let (rect, _) = ui.allocate_exact_size(
+        egui::vec2(FRAME_HISTORY as f32 * 1.5, 60.0),
+        egui::Sense::hover(),
+    );
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 4.0, palette::bg_input());
+    let scale = rect.height() / (FRAME_BUDGET_MS * 2.0);
+    let budget_y = rect.bottom() - FRAME_BUDGET_MS * scale;
+    painter.hline(
+        rect.x_range(),
+        budget_y,
+        egui::Stroke::new(0.5, palette::TEXT_MUTED),
+    );
+    for (i, value) in ms.iter().enumerate() {
+        let x = rect.left() + i as f32 * 1.5;
+        let top = (rect.bottom() - value * scale).max(rect.top());
+        let color = if *value > FRAME_BUDGET_MS {
+            palette::ACCENT_RED
+        } else {
+            palette::accent()
+        };
+        painter.line_segment(
+            [egui::pos2(x, rect.bottom()), egui::pos2(x, top)],
+            egui::Stroke::new(1.0, color),
+        );
+    }
+}
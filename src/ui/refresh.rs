@@ -0,0 +1,227 @@
+use super::pending::answer;
+use super::SongIndexApp;
+use crate::arrangements::Work;
+use crate::db::{
+    difficulty_levels, get_all_tags, get_stats, query_songs, song_exists, DifficultyLevel,
+    SmartList, Song, Stats, TagGroup,
+};
+use crate::format::Formatter;
+use crate::scanner::Changed;
+use std::collections::HashMap;
+use std::sync::mpsc::{Receiver, TryRecvError};
+
+/// What [`ListData`] has, queried off the UI thread after a watcher event.
+struct RefreshResult {
+    generation: u64,
+    songs: Vec<Song>,
+    tags: Vec<TagGroup>,
+    difficulty_levels: Vec<DifficultyLevel>,
+    works: HashMap<i64, Work>,
+    stats: Stats,
+    format: Formatter,
+    /// The song an open dialog worked on, if it no longer exists.
+    removed_song: Option<i64>,
+}
+
+/// Watcher-triggered refreshes run on the database worker and are swapped in
+/// whole once done. Any synchronous refresh bumps `generation`, so results
+/// computed for an older filter state (or before a local edit) are dropped.
+#[derive(Default)]
+pub(super) struct BackgroundRefresh {
+    generation: u64,
+    running: Option<Receiver<RefreshResult>>,
+    /// A watcher event is waiting for the next query.
+    pending: bool,
+    /// Result held back while a modal is open.
+    parked: Option<RefreshResult>,
+}
+
+impl BackgroundRefresh {
+    /// Drop in-flight and parked results; the watcher change they carried is
+    /// queried again.
+    pub(super) fn invalidate(&mut self) {
+        self.generation += 1;
+        self.pending |= self.running.is_some() || self.parked.take().is_some();
+    }
+}
+
+/// Everything `refresh_data` reloads.
+pub(super) struct ListData {
+    pub(super) songs: Vec<Song>,
+    pub(super) tags: Vec<TagGroup>,
+    pub(super) difficulty_levels: Vec<DifficultyLevel>,
+    pub(super) works: HashMap<i64, Work>,
+    pub(super) stats: Stats,
+    pub(super) format: Formatter,
+}
+
+/// What a reload after a single-song edit brings besides the song.
+pub(super) type MetaData = (Vec<TagGroup>, Vec<DifficultyLevel>, Stats, Formatter);
+
+/// Reloads of the list requested from the window. They run on the database
+/// worker, which answers in order, and are applied in the frame their answer
+/// arrives; a wider reload drops the narrower ones requested before it.
+#[derive(Default)]
+pub(super) struct ListLoads {
+    pub(super) full: Option<Receiver<ListData>>,
+    pub(super) songs: Option<Receiver<Vec<Song>>>,
+    pub(super) song: Vec<(i64, Receiver<Option<Song>>)>,
+    pub(super) meta: Option<Receiver<MetaData>>,
+    /// Song to select and scroll to once the list is there, if it is in it.
+    pub(super) select: Option<i64>,
+    /// Smart lists after a change, with the id of a saved one.
+    pub(super) smart_lists: Option<Receiver<(Option<i64>, Vec<SmartList>)>>,
+    /// Filter by the saved smart list once it is there.
+    pub(super) show_saved_smart_list: bool,
+}
+
+impl SongIndexApp {
+    /// Apply reloads whose answers have arrived.
+    pub(super) fn poll_list_loads(&mut self) {
+        let mut list_changed = false;
+        if let Some(data) = answer(&mut self.list_loads.full) {
+            self.songs = data.songs;
+            self.tags = data.tags;
+            self.difficulty_levels = data.difficulty_levels;
+            self.works = data.works;
+            self.stats = data.stats;
+            self.format = data.format;
+            list_changed = true;
+        }
+        if let Some(songs) = answer(&mut self.list_loads.songs) {
+            self.songs = songs;
+            list_changed = true;
+        }
+        if list_changed {
+            self.apply_table_sort();
+            if let Some(id) = self.list_loads.select.take() {
+                if self.songs.iter().any(|s| s.id == id) {
+                    self.selected_song_id = Some(id);
+                    self.scroll_to_selected = true;
+                }
+            }
+        }
+        while let Some((song_id, rx)) = self.list_loads.song.first() {
+            match rx.try_recv() {
+                Ok(song) => {
+                    let song_id = *song_id;
+                    self.list_loads.song.remove(0);
+                    self.apply_song(song_id, song);
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.list_loads.song.remove(0);
+                }
+            }
+        }
+        if let Some((saved, smart_lists)) = answer(&mut self.list_loads.smart_lists) {
+            self.smart_lists = smart_lists;
+            if std::mem::take(&mut self.list_loads.show_saved_smart_list) {
+                self.extras.smart_list = saved;
+                self.refresh_songs_only();
+            }
+        }
+        if let Some((tags, difficulty_levels, stats, format)) = answer(&mut self.list_loads.meta) {
+            self.tags = tags;
+            self.difficulty_levels = difficulty_levels;
+            self.stats = stats;
+            self.format = format;
+        }
+    }
+
+    /// Whether the list is being reloaded, e.g. to say so instead of "no songs".
+    pub(super) fn list_loading(&self) -> bool {
+        self.list_loads.full.is_some() || self.list_loads.songs.is_some()
+    }
+
+    /// Song the open tag/edit/confirm dialog belongs to.
+    fn modal_song_id(&self) -> Option<i64> {
+        self.tag_modal
+            .as_ref()
+            .map(|m| m.song_id)
+            .or(self.edit_modal.as_ref().map(|m| m.song_id))
+            .or(self.confirm_remove.as_ref().map(|c| c.song_id))
+    }
+
+    /// Drain watcher events, start a background query and apply finished ones.
+    /// Changed files get their background jobs.
+    pub(super) fn poll_background_refresh(&mut self) {
+        let mut changed = Vec::new();
+        let mut library = false;
+        while let Ok(event) = self.watcher_rx.try_recv() {
+            self.refresh.pending = true;
+            match event {
+                Changed::Files(paths) => changed.extend(paths),
+                Changed::Library => library = true,
+            }
+        }
+        if library {
+            self.jobs.enqueue_library(&self.db, self.base_dir.clone());
+        } else {
+            self.jobs
+                .enqueue_files(&self.db, self.base_dir.clone(), changed);
+        }
+
+        if let Some(ref rx) = self.refresh.running {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.refresh.running = None;
+                    if result.generation == self.refresh.generation {
+                        self.refresh.parked = Some(result);
+                    }
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => self.refresh.running = None,
+            }
+        }
+        if self.refresh.pending && self.refresh.running.is_none() {
+            self.refresh.pending = false;
+            self.spawn_refresh();
+        }
+
+        // While a dialog is open the list stays as it is, unless the file of
+        // the song being edited has disappeared.
+        let Some(ref result) = self.refresh.parked else {
+            return;
+        };
+        let modal_song = self.modal_song_id();
+        if modal_song.is_some() && modal_song != result.removed_song {
+            return;
+        }
+        if let Some(result) = self.refresh.parked.take() {
+            if modal_song.is_some() {
+                self.tag_modal = None;
+                self.edit_modal = None;
+                self.confirm_remove = None;
+            }
+            self.songs = result.songs;
+            self.tags = result.tags;
+            self.difficulty_levels = result.difficulty_levels;
+            self.works = result.works;
+            self.stats = result.stats;
+            self.format = result.format;
+            self.apply_table_sort();
+        }
+    }
+
+    fn spawn_refresh(&mut self) {
+        let generation = self.refresh.generation;
+        let search = self.search_text.clone();
+        let tag_filter = self.tag_filter();
+        let extras = self.extras.clone();
+        let sort_mode = self.sort_mode.clone();
+        let focus = self.modal_song_id();
+        let locale = self.config.locale;
+        let rx = self.db.request(move |conn| RefreshResult {
+            generation,
+            songs: query_songs(conn, &search, &tag_filter, &extras, &sort_mode),
+            tags: get_all_tags(conn),
+            difficulty_levels: difficulty_levels(conn),
+            works: crate::arrangements::works(conn),
+            stats: get_stats(conn),
+            format: Formatter::load(conn, locale),
+            removed_song: focus.filter(|id| !song_exists(conn, *id)),
+        });
+        self.refresh.running = Some(rx);
+    }
+}
@@ -0,0 +1,180 @@
+use super::pending::{answer, Loaded};
+use super::{palette, styled_small_button, SongIndexApp};
+use crate::i18n::{tr, tr_args};
+use crate::removal_log::{clear_returned, entries, restore, RemovedSong, Restored};
+use eframe::egui;
+use std::sync::mpsc::Receiver;
+
+pub(super) struct RemovalLogState {
+    entries: Loaded<Vec<RemovedSong>>,
+    message: Option<String>,
+    /// Message of a running restore, if it has one.
+    restoring: Option<Receiver<Option<String>>>,
+}
+
+enum RemovalLogAction {
+    Restore(i64),
+    ClearReturned,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_removal_log(&mut self) {
+        self.removal_log = Some(RemovalLogState {
+            entries: Loaded::new(self.db.request(|conn| entries(conn))),
+            message: None,
+            restoring: None,
+        });
+    }
+
+    pub(super) fn show_removal_log(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.removal_log else {
+            return;
+        };
+        if let Some(message) = answer(&mut state.restoring) {
+            state.message = message;
+        }
+
+        let mut action = None;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Entfernte Songs"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([540.0, 400.0])
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(tr(
+                    "Jeder Song, den der Scan wegen einer fehlenden Datei entfernt hat, mit \
+                     seinen Tags \u{2013} auch nachdem der Papierkorb geleert wurde. \
+                     \u{201E}Wiederherstellen\u{201C} holt Titel, Artist, Notizen und Tags \
+                     zur\u{00FC}ck, sobald die Datei wieder am selben Ort liegt.",
+                ))
+                .size(12.5)
+                .color(palette::TEXT_SECONDARY),
+            );
+            if let Some(ref message) = state.message {
+                ui.add_space(4.0);
+                ui.label(egui::RichText::new(message).color(palette::accent()));
+            }
+            ui.add_space(4.0);
+            let Some(entries) = state.entries.get() else {
+                ui.spinner();
+                return;
+            };
+            if entries.is_empty() {
+                ui.label(
+                    egui::RichText::new(tr("Noch keine Songs entfernt."))
+                        .color(palette::TEXT_MUTED),
+                );
+                return;
+            }
+            if entries.iter().any(|e| e.zurueck)
+                && ui.button(tr("Zur\u{00FC}ckgekehrte ausblenden")).clicked()
+            {
+                action = Some(RemovalLogAction::ClearReturned);
+            }
+            ui.separator();
+
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    for entry in entries.iter() {
+                        ui.horizontal(|ui| {
+                            ui.vertical(|ui| {
+                                let title = match entry.artist {
+                                    Some(ref artist) => {
+                                        format!("{} \u{2014} {artist}", entry.titel)
+                                    }
+                                    None => entry.titel.clone(),
+                                };
+                                ui.label(egui::RichText::new(title).color(palette::TEXT_PRIMARY));
+                                let tags: Vec<String> = entry
+                                    .tags
+                                    .iter()
+                                    .map(|(kategorie, wert)| format!("{kategorie}: {wert}"))
+                                    .collect();
+                                ui.label(
+                                    egui::RichText::new(tr_args(
+                                        "{} \u{00B7} {} Tags \u{00B7} entfernt {}",
+                                        &[
+                                            &entry.dateipfad,
+                                            &entry.tags.len(),
+                                            &self.format.relative(&entry.zeitpunkt),
+                                        ],
+                                    ))
+                                    .size(11.5)
+                                    .color(palette::TEXT_MUTED),
+                                )
+                                .on_hover_text(
+                                    if tags.is_empty() {
+                                        self.format.date(&entry.zeitpunkt)
+                                    } else {
+                                        tags.join("\n")
+                                    },
+                                );
+                            });
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    let status = if entry.zurueck {
+                                        Some("wieder da")
+                                    } else if entry.wartet {
+                                        Some("wartet auf die Datei")
+                                    } else {
+                                        None
+                                    };
+                                    match status {
+                                        Some(status) => {
+                                            ui.label(
+                                                egui::RichText::new(tr(status))
+                                                    .size(11.5)
+                                                    .color(palette::TEXT_MUTED),
+                                            );
+                                        }
+                                        None => {
+                                            if styled_small_button(ui, tr("Wiederherstellen"))
+                                                .clicked()
+                                            {
+                                                action = Some(RemovalLogAction::Restore(entry.id));
+                                            }
+                                        }
+                                    }
+                                },
+                            );
+                        });
+                        ui.add_space(2.0);
+                    }
+                });
+        });
+
+        if let Some(action) = action {
+            let base_dir = self.base_dir.clone();
+            let options = self.scan_options.read().unwrap().clone();
+            state.restoring = Some(self.db.request(move |conn| {
+                match action {
+                    RemovalLogAction::Restore(id) => match restore(conn, &base_dir, &options, id) {
+                        Restored::Now => Some(tr("Wiederhergestellt.").to_string()),
+                        Restored::WhenFileReturns => Some(
+                            tr("Die Datei fehlt noch; der Song kommt mit seinen Tags zur\u{00FC}ck, \
+                                sobald sie wieder da ist.")
+                            .to_string(),
+                        ),
+                    },
+                    RemovalLogAction::ClearReturned => {
+                        clear_returned(conn);
+                        None
+                    }
+                }
+            }));
+            state.entries.reload(self.db.request(|conn| entries(conn)));
+            self.refresh_data();
+        }
+        if !open {
+            self.removal_log = None;
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use super::pending::Loaded;
+use super::{palette, save_config, styled_small_button, SongIndexApp};
+use crate::i18n::{tr, tr_args};
+use crate::repertoire::{mark_reviewed, repertoire_songs, review_queue, RepertoireSong};
+use eframe::egui;
+
+pub(super) struct ReviewWindowState {
+    show_all: bool,
+    /// Due songs and the whole repertoire, least fresh first.
+    songs: Loaded<(Vec<RepertoireSong>, Vec<RepertoireSong>)>,
+}
+
+enum ReviewAction {
+    Open(String),
+    Practice(i64, String),
+    Reviewed { song_id: i64, confident: bool },
+}
+
+fn freshness_color(freshness: f64) -> egui::Color32 {
+    if freshness >= 0.5 {
+        palette::AUDIO_GREEN
+    } else if freshness >= 0.25 {
+        palette::TAG_SCHWIERIGKEIT
+    } else {
+        palette::ACCENT_RED
+    }
+}
+
+impl SongIndexApp {
+    pub(super) fn open_review(&mut self) {
+        self.review_window = Some(ReviewWindowState {
+            show_all: false,
+            songs: Loaded::new(self.db.request(|conn| load_review(conn))),
+        });
+    }
+
+    pub(super) fn show_review_window(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.review_window else {
+            return;
+        };
+
+        let mut action: Option<ReviewAction> = None;
+        let mut interval_changed = false;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Wiederholen"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([480.0, 380.0])
+        .show(ctx, |ui| {
+            let loaded = state.songs.get();
+            let (due_count, all_count) = match loaded {
+                Some((ref due, ref all)) => (format!(" ({})", due.len()), format!(" ({})", all.len())),
+                None => (String::new(), String::new()),
+            };
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut state.show_all, false, format!("{}{due_count}", tr("F\u{00E4}llig")));
+                ui.selectable_value(&mut state.show_all, true, format!("{}{all_count}", tr("Ganzes Repertoire")));
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui
+                        .add(
+                            egui::DragValue::new(&mut self.config.review_interval_days)
+                                .range(1..=90)
+                                .suffix(tr(" Tage")),
+                        )
+                        .on_hover_text(tr("Erstes Wiederholungsintervall f\u{00FC}r neue \u{201E}Kann ich\u{201C}-Songs"))
+                        .changed()
+                    {
+                        interval_changed = true;
+                    }
+                    ui.label(egui::RichText::new(tr("Intervall:")).size(12.5).color(palette::TEXT_MUTED));
+                });
+            });
+            ui.separator();
+
+            let Some((due, all)) = loaded else {
+                ui.spinner();
+                return;
+            };
+            let songs = if state.show_all { all } else { due };
+            if songs.is_empty() {
+                ui.label(
+                    egui::RichText::new(tr(if state.show_all {
+                        "Noch keine Songs als \u{201E}Kann ich\u{201C} markiert (Bearbeiten)."
+                    } else {
+                        "Nichts f\u{00E4}llig \u{2014} das Repertoire sitzt."
+                    }))
+                    .color(palette::TEXT_MUTED),
+                );
+                return;
+            }
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for song in songs {
+                    ui.horizontal(|ui| {
+                        let freshness = song.freshness();
+                        ui.label(
+                            egui::RichText::new(format!("{:>3.0}%", freshness * 100.0))
+                                .monospace()
+                                .size(12.0)
+                                .color(freshness_color(freshness)),
+                        )
+                        .on_hover_text(tr_args(
+                            "Zuletzt {}, Intervall {} Tage",
+                            &[&self.format.days_ago(song.days_since as i64), &song.interval_days],
+                        ));
+                        ui.vertical(|ui| {
+                            ui.label(egui::RichText::new(&song.titel).color(palette::TEXT_PRIMARY));
+                            if let Some(ref artist) = song.artist {
+                                ui.label(
+                                    egui::RichText::new(artist)
+                                        .size(12.0)
+                                        .color(palette::TEXT_SECONDARY),
+                                );
+                            }
+                        });
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.spacing_mut().item_spacing.x = 4.0;
+                            if styled_small_button(ui, tr("Wackelt")).on_hover_text(tr("Intervall zur\u{00FC}cksetzen")).clicked() {
+                                action = Some(ReviewAction::Reviewed { song_id: song.id, confident: false });
+                            }
+                            if styled_small_button(ui, tr("\u{2713} Sitzt")).on_hover_text(tr("Intervall verdoppeln")).clicked() {
+                                action = Some(ReviewAction::Reviewed { song_id: song.id, confident: true });
+                            }
+                            if styled_small_button(ui, tr("\u{23F1} \u{00DC}ben")).clicked() {
+                                action = Some(ReviewAction::Practice(song.id, song.titel.clone()));
+                            }
+                            if styled_small_button(ui, tr("\u{00D6}ffnen")).clicked() {
+                                action = Some(ReviewAction::Open(song.dateipfad.clone()));
+                            }
+                        });
+                    });
+                    ui.add_space(4.0);
+                }
+            });
+        });
+
+        if interval_changed {
+            save_config(&self.config);
+        }
+        match action {
+            Some(ReviewAction::Open(rel_path)) => self.open_song_file(&rel_path),
+            Some(ReviewAction::Practice(song_id, titel)) => self.start_practice(song_id, titel),
+            Some(ReviewAction::Reviewed { song_id, confident }) => {
+                let review_interval_days = self.config.review_interval_days;
+                self.db.send(move |conn| {
+                    mark_reviewed(conn, song_id, confident, review_interval_days);
+                });
+                state
+                    .songs
+                    .reload(self.db.request(|conn| load_review(conn)));
+                self.needs_refresh = true;
+            }
+            None => {}
+        }
+        if !open {
+            self.review_window = None;
+        }
+    }
+}
+
+/// Due songs and the whole repertoire, least fresh first.
+fn load_review(conn: &rusqlite::Connection) -> (Vec<RepertoireSong>, Vec<RepertoireSong>) {
+    let mut all = repertoire_songs(conn);
+    all.sort_by(|a, b| a.freshness().total_cmp(&b.freshness()));
+    (review_queue(conn), all)
+}
@@ -0,0 +1,302 @@
+use super::{palette, SongIndexApp};
+use crate::autotag::Tag;
+use crate::i18n::{tr, tr_args};
+use crate::scanner::{preview_scan, ScanPreview};
+use eframe::egui;
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, TryRecvError};
+
+/// Height of one list before it scrolls.
+const LIST_HEIGHT: f32 = 180.0;
+
+/// "Scan-Vorschau": what a scan of the music folder, or of another folder
+/// put in its place, would change. Nothing is written.
+pub(super) struct ScanPreviewState {
+    dir: PathBuf,
+    running: Option<Receiver<ScanPreview>>,
+    preview: Option<ScanPreview>,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_scan_preview(&mut self) {
+        let mut state = ScanPreviewState {
+            dir: self.base_dir.clone(),
+            running: None,
+            preview: None,
+        };
+        self.start_scan_preview(&mut state);
+        self.scan_preview = Some(state);
+    }
+
+    fn start_scan_preview(&self, state: &mut ScanPreviewState) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let db = self.db.clone();
+        let dir = state.dir.clone();
+        let options = self.scan_options.read().unwrap().clone();
+        std::thread::spawn(move || {
+            tx.send(preview_scan(&db, &dir, &options)).ok();
+        });
+        state.running = Some(rx);
+        state.preview = None;
+    }
+
+    pub(super) fn show_scan_preview(&mut self, ctx: &egui::Context) {
+        let Some(mut state) = self.scan_preview.take() else {
+            return;
+        };
+
+        if let Some(ref rx) = state.running {
+            match rx.try_recv() {
+                Ok(preview) => {
+                    state.running = None;
+                    state.preview = Some(preview);
+                }
+                Err(TryRecvError::Empty) => {
+                    ctx.request_repaint_after(std::time::Duration::from_millis(200));
+                }
+                Err(TryRecvError::Disconnected) => state.running = None,
+            }
+        }
+
+        let mut pick_dir = false;
+        let mut show = None;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Scan-Vorschau"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_width(560.0)
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(tr(
+                    "Zeigt, was ein Scan dieses Ordners an der Bibliothek \u{00E4}ndern w\u{00FC}rde. \
+                     Es wird nichts gespeichert.",
+                ))
+                .size(12.0)
+                .color(palette::TEXT_MUTED),
+            );
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.label(
+                    egui::RichText::new(state.dir.display().to_string())
+                        .size(13.0)
+                        .color(palette::TEXT_SECONDARY),
+                );
+                if ui
+                    .add_enabled(state.running.is_none(), egui::Button::new(tr("Anderer Ordner\u{2026}")))
+                    .clicked()
+                {
+                    pick_dir = true;
+                }
+            });
+            ui.add_space(6.0);
+
+            let Some(ref preview) = state.preview else {
+                if state.running.is_some() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(tr("Ordner wird durchsucht\u{2026}"));
+                    });
+                }
+                return;
+            };
+            if preview.is_empty() {
+                ui.label(
+                    egui::RichText::new(tr("Ein Scan w\u{00FC}rde nichts \u{00E4}ndern."))
+                        .size(13.0)
+                        .color(palette::TEXT_PRIMARY),
+                );
+                return;
+            }
+            ui.label(
+                egui::RichText::new(tr_args(
+                    "{} neu \u{00B7} {} zur\u{00FC}ck \u{00B7} {} entfernt \u{00B7} {} verschoben",
+                    &[
+                        &preview.added.len(),
+                        &preview.restored.len(),
+                        &preview.removed.len(),
+                        &preview.moved.len(),
+                    ],
+                ))
+                .size(13.0)
+                .color(palette::TEXT_PRIMARY),
+            );
+            if !preview.removed.is_empty() || !preview.moved.is_empty() {
+                ui.add_space(4.0);
+                ui.label(
+                    egui::RichText::new(tr_args(
+                        "{} von {} Songs k\u{00E4}men mit Tags und Notizen in den Papierkorb.",
+                        &[
+                            &(preview.removed.len() + preview.moved.len()),
+                            &preview.known,
+                        ],
+                    ))
+                    .size(12.5)
+                    .color(palette::ACCENT_RED),
+                );
+            }
+            ui.add_space(6.0);
+
+            list_section(ui, tr("Neu"), "added", &preview.added, |(path, tags)| {
+                (path.clone(), tag_list(tags))
+            });
+            list_section(
+                ui,
+                tr("Zur\u{00FC}ck aus dem Papierkorb"),
+                "restored",
+                &preview.restored,
+                |song| (song.dateipfad.clone(), String::new()),
+            );
+            if !preview.removed.is_empty() {
+                egui::CollapsingHeader::new(format!(
+                    "{} ({})",
+                    tr("Entfernt"),
+                    preview.removed.len()
+                ))
+                    .id_salt(("scan_preview", "removed"))
+                    .show(ui, |ui| {
+                        egui::ScrollArea::vertical()
+                            .id_salt(("scan_preview_list", "removed"))
+                            .max_height(LIST_HEIGHT)
+                            .show_rows(ui, 18.0, preview.removed.len(), |ui, rows| {
+                                for song in &preview.removed[rows] {
+                                    if ui
+                                        .selectable_label(
+                                            false,
+                                            egui::RichText::new(&song.dateipfad).size(12.0),
+                                        )
+                                        .on_hover_text(tr("In der Liste zeigen"))
+                                        .clicked()
+                                    {
+                                        show = Some(song.id);
+                                    }
+                                }
+                            });
+                    });
+            }
+            if !preview.moved.is_empty() {
+                egui::CollapsingHeader::new(format!(
+                    "{} ({})",
+                    tr("Verschoben"),
+                    preview.moved.len()
+                ))
+                    .id_salt(("scan_preview", "moved"))
+                    .show(ui, |ui| {
+                        ui.label(
+                            egui::RichText::new(tr(
+                                "Gleicher Dateiname in einem anderen Ordner. Der neue Eintrag \
+                                 bekommt nur die Tags der Regeln.",
+                            ))
+                            .size(11.5)
+                            .color(palette::TEXT_MUTED),
+                        );
+                        egui::ScrollArea::vertical()
+                            .id_salt(("scan_preview_list", "moved"))
+                            .max_height(LIST_HEIGHT)
+                            .show(ui, |ui| {
+                                for moved in &preview.moved {
+                                    if ui
+                                        .selectable_label(
+                                            false,
+                                            egui::RichText::new(format!(
+                                                "{} \u{2192} {}",
+                                                moved.from.dateipfad, moved.to
+                                            ))
+                                            .size(12.0),
+                                        )
+                                        .on_hover_text(tr("In der Liste zeigen"))
+                                        .clicked()
+                                    {
+                                        show = Some(moved.from.id);
+                                    }
+                                    if !moved.gained.is_empty() {
+                                        ui.label(
+                                            egui::RichText::new(format!(
+                                                "   + {}",
+                                                tag_list(&moved.gained)
+                                            ))
+                                            .size(11.5)
+                                            .color(palette::TEXT_SECONDARY),
+                                        );
+                                    }
+                                    if !moved.lost.is_empty() {
+                                        ui.label(
+                                            egui::RichText::new(format!(
+                                                "   \u{2212} {}",
+                                                tag_list(&moved.lost)
+                                            ))
+                                            .size(11.5)
+                                            .color(palette::ACCENT_RED),
+                                        );
+                                    }
+                                }
+                            });
+                    });
+            }
+        });
+
+        if pick_dir {
+            if let Some(dir) = rfd::FileDialog::new()
+                .set_title(tr("Ordner f\u{00FC}r die Vorschau ausw\u{00E4}hlen"))
+                .set_directory(&state.dir)
+                .pick_folder()
+            {
+                state.dir = dir;
+                self.start_scan_preview(&mut state);
+            }
+        }
+        if let Some(id) = show {
+            self.show_in_list(id);
+        }
+        if open {
+            self.scan_preview = Some(state);
+        }
+    }
+}
+
+/// "kategorie: wert" pairs, comma separated.
+fn tag_list(tags: &[Tag]) -> String {
+    tags.iter()
+        .map(|(kategorie, wert)| format!("{kategorie}: {wert}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A collapsible list of paths, each with a muted note after it.
+fn list_section<T>(
+    ui: &mut egui::Ui,
+    title: &str,
+    id: &str,
+    items: &[T],
+    line: impl Fn(&T) -> (String, String),
+) {
+    if items.is_empty() {
+        return;
+    }
+    egui::CollapsingHeader::new(format!("{title} ({})", items.len()))
+        .id_salt(("scan_preview", id))
+        .show(ui, |ui| {
+            egui::ScrollArea::vertical()
+                .id_salt(("scan_preview_list", id))
+                .max_height(LIST_HEIGHT)
+                .show_rows(ui, 18.0, items.len(), |ui, rows| {
+                    for item in &items[rows] {
+                        let (path, note) = line(item);
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new(path).size(12.0));
+                            if !note.is_empty() {
+                                ui.label(
+                                    egui::RichText::new(note)
+                                        .size(11.5)
+                                        .color(palette::TEXT_MUTED),
+                                );
+                            }
+                        });
+                    }
+                });
+        });
+}
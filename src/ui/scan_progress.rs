@@ -0,0 +1,47 @@
+use super::{notify, palette, Level, SongIndexApp};
+use crate::i18n::{tr, tr_arg};
+use eframe::egui;
+use std::time::Duration;
+
+impl SongIndexApp {
+    /// Take the report of a scan that finished in the background, reload the
+    /// list and run the size check.
+    pub(super) fn poll_scan_status(&mut self) {
+        let Some(report) = self.scan_status.lock().unwrap().report.take() else {
+            return;
+        };
+        self.refresh_data();
+        self.check_library_size();
+        if !report.is_empty() {
+            self.open_scan_report(report);
+        } else if self.manual_scan {
+            notify(Level::Info, tr("Scan abgeschlossen, keine \u{00C4}nderungen"));
+        }
+        self.manual_scan = false;
+    }
+
+    /// "Scanne… N Dateien" in the header while a scan runs.
+    /// Its batches arrive like watcher events, so the list fills meanwhile.
+    pub(super) fn show_scan_progress(&self, ui: &mut egui::Ui) {
+        let progress = self.scan_status.lock().unwrap();
+        if !progress.running {
+            return;
+        }
+        ui.ctx().request_repaint_after(Duration::from_millis(250));
+
+        ui.add_space(8.0);
+        ui.label(
+            egui::RichText::new(tr_arg(
+                "Scanne\u{2026} {} Dateien",
+                self.format.number(progress.files as i64),
+            ))
+            .size(12.0)
+            .color(palette::TEXT_MUTED),
+        )
+        .on_hover_text(tr_arg(
+            "{} neue Songs bisher; die Liste f\u{00FC}llt sich, w\u{00E4}hrend der Scan l\u{00E4}uft",
+            self.format.number(progress.added as i64),
+        ));
+        ui.add(egui::Spinner::new().size(12.0).color(palette::TEXT_MUTED));
+    }
+}
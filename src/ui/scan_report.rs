@@ -0,0 +1,222 @@
+use super::{palette, styled_small_button, SongIndexApp};
+use crate::i18n::{tr, tr_arg, tr_args};
+use crate::scanner::{largest_folders_of, ReportedSong, ScanReport};
+use eframe::egui;
+
+/// Warn when at least this share of the songs disappeared in one scan.
+const LARGE_LOSS: f32 = 0.2;
+/// Height of one list before it scrolls.
+const LIST_HEIGHT: f32 = 180.0;
+/// Folders with new songs offered for exclusion.
+const FOLDERS_SHOWN: usize = 8;
+
+pub(super) struct ScanReportState {
+    report: ScanReport,
+    /// Folders the new songs are in, as (folder, new songs), largest first.
+    folders: Vec<(String, usize)>,
+}
+
+/// Something clicked in the report.
+enum ReportAction {
+    Show(i64),
+    OpenTrash,
+    Exclude(String),
+}
+
+impl SongIndexApp {
+    /// Show what a scan changed. The first scan of a library is left out:
+    /// everything in it is new.
+    pub(super) fn open_scan_report(&mut self, report: ScanReport) {
+        if report.known == 0 {
+            return;
+        }
+        let folders = largest_folders_of(
+            report.added.iter().map(|song| song.dateipfad.as_str()),
+            FOLDERS_SHOWN,
+        );
+        self.scan_report = Some(ScanReportState { report, folders });
+    }
+
+    pub(super) fn show_scan_report(&mut self, ctx: &egui::Context) {
+        let Some(ref state) = self.scan_report else {
+            return;
+        };
+        let report = &state.report;
+
+        let mut action = None;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Scan-Bericht"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_width(520.0)
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(tr_args(
+                    "{} neu \u{00B7} {} entfernt \u{00B7} {} verschoben \u{00B7} {} mit neuem Audio",
+                    &[
+                        &report.added.len(),
+                        &report.removed.len(),
+                        &report.moved.len(),
+                        &report.audio.len(),
+                    ],
+                ))
+                .size(13.0)
+                .color(palette::TEXT_PRIMARY),
+            );
+            let lost = report.removed.len() + report.moved.len();
+            if lost as f32 >= report.known as f32 * LARGE_LOSS {
+                ui.add_space(4.0);
+                ui.label(
+                    egui::RichText::new(tr_args(
+                        "\u{26A0} {} von {} Songs sind nicht mehr an ihrem Platz. Wurde ein \
+                         Ordner umbenannt oder ein Laufwerk getrennt? Die alten Eintr\u{00E4}ge \
+                         liegen mit Tags und Notizen im Papierkorb.",
+                        &[&lost, &report.known],
+                    ))
+                    .size(12.5)
+                    .color(palette::ACCENT_RED),
+                );
+            }
+            ui.add_space(6.0);
+
+            song_section(ui, tr("Neu"), "added", &report.added, &mut action, false);
+            if !state.folders.is_empty() {
+                egui::CollapsingHeader::new(tr("Ordner mit neuen Songs"))
+                    .id_salt("scan_report_folders")
+                    .show(ui, |ui| {
+                        for (folder, count) in &state.folders {
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    egui::RichText::new(folder)
+                                        .size(12.0)
+                                        .color(palette::TEXT_PRIMARY),
+                                );
+                                ui.label(
+                                    egui::RichText::new(tr_arg("{} neu", count))
+                                        .size(12.0)
+                                        .color(palette::TEXT_MUTED),
+                                );
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| {
+                                        if styled_small_button(ui, tr("Ausschlie\u{00DF}en"))
+                                            .on_hover_text(tr(
+                                                "Ausschlie\u{00DF}en legt ein Muster unter \u{201E}Ausschl\u{00FC}sse\u{2026}\u{201C} \
+                                                 an und scannt neu; die Songs landen im Papierkorb.",
+                                            ))
+                                            .clicked()
+                                        {
+                                            action = Some(ReportAction::Exclude(folder.clone()));
+                                        }
+                                    },
+                                );
+                            });
+                        }
+                    });
+            }
+            song_section(ui, tr("Entfernt"), "removed", &report.removed, &mut action, true);
+            if !report.moved.is_empty() {
+                egui::CollapsingHeader::new(format!(
+                    "{} ({})",
+                    tr("Verschoben"),
+                    report.moved.len()
+                ))
+                    .id_salt("scan_report_moved")
+                    .show(ui, |ui| {
+                        ui.label(
+                            egui::RichText::new(tr(
+                                "Gleicher Dateiname in einem anderen Ordner. Der neue Eintrag \
+                                 beginnt ohne Tags; der alte liegt im Papierkorb.",
+                            ))
+                            .size(11.5)
+                            .color(palette::TEXT_MUTED),
+                        );
+                        egui::ScrollArea::vertical()
+                            .id_salt("scan_report_moved_list")
+                            .max_height(LIST_HEIGHT)
+                            .show_rows(ui, 18.0, report.moved.len(), |ui, rows| {
+                                for moved in &report.moved[rows] {
+                                    let text = format!(
+                                        "{} \u{2192} {}",
+                                        moved.from.dateipfad, moved.to.dateipfad
+                                    );
+                                    if ui
+                                        .selectable_label(false, egui::RichText::new(text).size(12.0))
+                                        .on_hover_text(tr("In der Liste zeigen"))
+                                        .clicked()
+                                    {
+                                        action = Some(ReportAction::Show(moved.to.id));
+                                    }
+                                }
+                            });
+                    });
+            }
+            song_section(ui, tr("Neues Audio"), "audio", &report.audio, &mut action, false);
+        });
+
+        match action {
+            Some(ReportAction::Show(id)) => self.show_in_list(id),
+            Some(ReportAction::OpenTrash) => self.open_trash(),
+            Some(ReportAction::Exclude(folder)) => {
+                if let Some(ref mut state) = self.scan_report {
+                    state.folders.retain(|(f, _)| *f != folder);
+                }
+                self.exclude_folder(&folder);
+            }
+            None => {}
+        }
+        if !open {
+            self.scan_report = None;
+        }
+    }
+}
+
+/// A collapsible list of songs; a click shows the song in the list, or the
+/// Papierkorb for removed ones.
+fn song_section(
+    ui: &mut egui::Ui,
+    title: &str,
+    id: &str,
+    songs: &[ReportedSong],
+    action: &mut Option<ReportAction>,
+    removed: bool,
+) {
+    if songs.is_empty() {
+        return;
+    }
+    egui::CollapsingHeader::new(format!("{title} ({})", songs.len()))
+        .id_salt(("scan_report", id))
+        .show(ui, |ui| {
+            egui::ScrollArea::vertical()
+                .id_salt(("scan_report_list", id))
+                .max_height(LIST_HEIGHT)
+                .show_rows(ui, 18.0, songs.len(), |ui, rows| {
+                    for song in &songs[rows] {
+                        let hover = if removed {
+                            tr("Papierkorb \u{00F6}ffnen")
+                        } else {
+                            tr("In der Liste zeigen")
+                        };
+                        if ui
+                            .selectable_label(
+                                false,
+                                egui::RichText::new(&song.dateipfad).size(12.0),
+                            )
+                            .on_hover_text(hover)
+                            .clicked()
+                        {
+                            *action = Some(if removed {
+                                ReportAction::OpenTrash
+                            } else {
+                                ReportAction::Show(song.id)
+                            });
+                        }
+                    }
+                });
+        });
+}
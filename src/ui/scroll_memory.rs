@@ -0,0 +1,73 @@
+use super::SongIndexApp;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// Scroll offset per view and filter state, so switching back to an earlier
+/// filter combination returns to the same place in the list.
+#[derive(Default)]
+pub(super) struct ScrollMemory {
+    offsets: HashMap<u64, f32>,
+    current: Option<u64>,
+    restore: Option<f32>,
+}
+
+impl ScrollMemory {
+    /// Switch to `key`; if it differs from the last frame, its remembered offset
+    /// (or the top) is applied by the next view that renders.
+    fn begin(&mut self, key: u64) {
+        if self.current != Some(key) {
+            self.current = Some(key);
+            self.restore = Some(self.offsets.get(&key).copied().unwrap_or(0.0));
+        }
+    }
+
+    pub(super) fn pending_restore(&self) -> Option<f32> {
+        self.restore
+    }
+
+    pub(super) fn record(&mut self, offset: f32) {
+        self.restore = None;
+        if let Some(key) = self.current {
+            self.offsets.insert(key, offset);
+        }
+    }
+}
+
+impl SongIndexApp {
+    fn scroll_key(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.view_mode.hash(&mut hasher);
+        self.search_text.hash(&mut hasher);
+        self.sort_mode.hash(&mut hasher);
+        self.table_sort.hash(&mut hasher);
+        self.category_join.hash(&mut hasher);
+
+        let mut filters: Vec<(&String, Vec<i64>)> = self
+            .active_filters
+            .iter()
+            .map(|(kategorie, ids)| {
+                let mut ids: Vec<i64> = ids.iter().copied().collect();
+                ids.sort_unstable();
+                (kategorie, ids)
+            })
+            .collect();
+        filters.sort();
+        for (kategorie, ids) in filters {
+            kategorie.hash(&mut hasher);
+            ids.hash(&mut hasher);
+            self.category_mode(kategorie).hash(&mut hasher);
+        }
+
+        let extras = &self.extras;
+        (extras.has_audio, extras.untagged, extras.favorites).hash(&mut hasher);
+        (extras.bpm, extras.kapo_max).hash(&mut hasher);
+        (extras.schwierigkeit, extras.smart_list).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Called once per frame before the song view is drawn.
+    pub(super) fn update_scroll_memory(&mut self) {
+        let key = self.scroll_key();
+        self.scroll_memory.begin(key);
+    }
+}
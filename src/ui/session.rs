@@ -0,0 +1,307 @@
+use super::practice::format_duration;
+use super::{palette, SongIndexApp};
+use crate::db::get_practice_seconds;
+use crate::i18n::{tr, tr_arg, tr_args};
+use eframe::egui;
+use std::sync::mpsc::{Receiver, TryRecvError};
+
+struct SessionSong {
+    id: i64,
+    titel: String,
+    audio_pfad: Option<String>,
+}
+
+enum SessionPhase {
+    Setup,
+    /// Practice times of the list's songs are loading to pick the least
+    /// practiced ones; `songs` holds the whole list meanwhile.
+    Picking {
+        practiced: Receiver<Vec<i64>>,
+    },
+    Running {
+        idx: usize,
+    },
+    Done {
+        practiced_seconds: i64,
+    },
+}
+
+pub(super) struct SessionState {
+    song_count: usize,
+    minutes: u32,
+    least_practiced_first: bool,
+    songs: Vec<SessionSong>,
+    phase: SessionPhase,
+    practiced_seconds: i64,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_session_planner(&mut self) {
+        self.session = Some(SessionState {
+            song_count: self.songs.len().min(5),
+            minutes: 10,
+            least_practiced_first: false,
+            songs: Vec::new(),
+            phase: SessionPhase::Setup,
+            practiced_seconds: 0,
+        });
+    }
+
+    /// Pick the songs for the session from the current (filtered) song list.
+    fn start_session(&mut self) {
+        let Some(ref mut session) = self.session else {
+            return;
+        };
+        session.songs = self
+            .songs
+            .iter()
+            .map(|s| SessionSong {
+                id: s.id,
+                titel: s.titel.clone(),
+                audio_pfad: s.audio_pfad.clone().filter(|_| s.has_audio),
+            })
+            .collect();
+        if session.least_practiced_first {
+            let ids: Vec<i64> = session.songs.iter().map(|s| s.id).collect();
+            let practiced = self.db.request(move |conn| {
+                ids.iter()
+                    .map(|id| get_practice_seconds(conn, *id))
+                    .collect()
+            });
+            session.phase = SessionPhase::Picking { practiced };
+        } else {
+            self.pick_session_songs(Vec::new());
+        }
+    }
+
+    /// Keep the first songs of the list, least `practiced` first (missing
+    /// times count as 0), and start the session.
+    fn pick_session_songs(&mut self, practiced: Vec<i64>) {
+        let Some(ref mut session) = self.session else {
+            return;
+        };
+        let mut candidates: Vec<(i64, SessionSong)> = practiced
+            .into_iter()
+            .chain(std::iter::repeat(0))
+            .zip(std::mem::take(&mut session.songs))
+            .collect();
+        // Stable sort keeps the list order among equally practiced songs.
+        candidates.sort_by_key(|(practiced, _)| *practiced);
+        session.songs = candidates
+            .into_iter()
+            .take(session.song_count)
+            .map(|(_, s)| s)
+            .collect();
+        session.practiced_seconds = 0;
+        session.phase = SessionPhase::Running { idx: 0 };
+        self.begin_session_song(0);
+    }
+
+    fn begin_session_song(&mut self, idx: usize) {
+        let Some(song) = self.session.as_ref().and_then(|s| s.songs.get(idx)) else {
+            return;
+        };
+        let (id, titel, audio) = (song.id, song.titel.clone(), song.audio_pfad.clone());
+        self.start_practice(id, titel);
+        match audio {
+            Some(audio_pfad) => self.play_audio(id, &audio_pfad),
+            None => self.stop_audio(),
+        }
+    }
+
+    /// Log the current song and move on, or finish the session after the last one.
+    fn advance_session(&mut self) {
+        let elapsed = self
+            .practice_timer
+            .as_ref()
+            .map(|t| t.elapsed_seconds())
+            .unwrap_or(0);
+        self.stop_practice();
+        self.stop_audio();
+
+        let Some(ref mut session) = self.session else {
+            return;
+        };
+        session.practiced_seconds += elapsed;
+        let SessionPhase::Running { idx } = session.phase else {
+            return;
+        };
+        if idx + 1 < session.songs.len() {
+            session.phase = SessionPhase::Running { idx: idx + 1 };
+            self.begin_session_song(idx + 1);
+        } else {
+            session.phase = SessionPhase::Done {
+                practiced_seconds: session.practiced_seconds,
+            };
+        }
+    }
+
+    pub(super) fn show_session_window(&mut self, ctx: &egui::Context) {
+        let picked = match self.session.as_ref().map(|s| &s.phase) {
+            Some(SessionPhase::Picking { practiced }) => match practiced.try_recv() {
+                Ok(practiced) => Some(practiced),
+                Err(TryRecvError::Empty) => None,
+                Err(TryRecvError::Disconnected) => Some(Vec::new()),
+            },
+            _ => None,
+        };
+        if let Some(practiced) = picked {
+            self.pick_session_songs(practiced);
+        }
+        let Some(ref mut session) = self.session else {
+            return;
+        };
+
+        // The practice bar's Stop button ends the session as well.
+        if let SessionPhase::Running { .. } = session.phase {
+            if self.practice_timer.is_none() {
+                session.phase = SessionPhase::Done {
+                    practiced_seconds: session.practiced_seconds,
+                };
+            }
+        }
+
+        let elapsed = self
+            .practice_timer
+            .as_ref()
+            .map(|t| t.elapsed_seconds())
+            .unwrap_or(0);
+        let slot = i64::from(session.minutes) * 60;
+        let mut start = false;
+        let mut advance = false;
+        let mut abort = false;
+        if let SessionPhase::Running { .. } = session.phase {
+            if elapsed >= slot {
+                advance = true;
+            }
+        }
+
+        let filtered = self.songs.len();
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("\u{00DC}be-Session"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .fixed_size([360.0, 180.0])
+        .show(ctx, |ui| match session.phase {
+            SessionPhase::Setup => {
+                ui.label(
+                    egui::RichText::new(tr_arg("Aus der aktuellen Liste ({} Songs):", filtered))
+                        .size(12.5)
+                        .color(palette::TEXT_MUTED),
+                );
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::DragValue::new(&mut session.song_count)
+                            .range(1..=filtered.max(1))
+                            .suffix(tr(" Songs")),
+                    );
+                    ui.label("\u{00D7}");
+                    ui.add(
+                        egui::DragValue::new(&mut session.minutes)
+                            .range(1..=120)
+                            .suffix(" min"),
+                    );
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "= {} min",
+                            session.song_count.min(filtered) as u32 * session.minutes
+                        ))
+                        .color(palette::TEXT_SECONDARY),
+                    );
+                });
+                ui.checkbox(
+                    &mut session.least_practiced_first,
+                    tr("Am wenigsten ge\u{00FC}bte Songs zuerst"),
+                );
+                ui.add_space(8.0);
+                let btn = egui::Button::new(
+                    egui::RichText::new(tr("\u{25B6} Starten")).color(palette::TEXT_PRIMARY),
+                )
+                .fill(palette::accent_dim())
+                .rounding(6.0);
+                if ui.add_enabled(filtered > 0, btn).clicked() {
+                    start = true;
+                }
+            }
+            SessionPhase::Picking { .. } => {
+                ui.centered_and_justified(|ui| ui.spinner());
+            }
+            SessionPhase::Running { idx } => {
+                let song = &session.songs[idx];
+                ui.label(
+                    egui::RichText::new(tr_args(
+                        "Song {} von {}",
+                        &[&(idx + 1), &session.songs.len()],
+                    ))
+                    .size(12.5)
+                    .color(palette::TEXT_MUTED),
+                );
+                ui.label(
+                    egui::RichText::new(&song.titel)
+                        .size(16.0)
+                        .strong()
+                        .color(palette::TEXT_PRIMARY),
+                );
+                ui.add_space(4.0);
+                ui.add(
+                    egui::ProgressBar::new(elapsed as f32 / slot as f32)
+                        .text(tr_arg("noch {}", format_duration((slot - elapsed).max(0)))),
+                );
+                if let Some(next) = session.songs.get(idx + 1) {
+                    ui.label(
+                        egui::RichText::new(tr_arg("Als N\u{00E4}chstes: {}", &next.titel))
+                            .size(12.5)
+                            .color(palette::TEXT_SECONDARY),
+                    );
+                }
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button(tr("\u{23ED} Weiter")).clicked() {
+                        advance = true;
+                    }
+                    if ui.button(tr("Beenden")).clicked() {
+                        abort = true;
+                    }
+                });
+            }
+            SessionPhase::Done { practiced_seconds } => {
+                ui.label(
+                    egui::RichText::new(tr("Session beendet."))
+                        .size(15.0)
+                        .color(palette::AUDIO_GREEN),
+                );
+                ui.label(
+                    egui::RichText::new(tr_arg(
+                        "{} ge\u{00FC}bt, im \u{00DC}be-Log eingetragen.",
+                        format_duration(practiced_seconds),
+                    ))
+                    .color(palette::TEXT_SECONDARY),
+                );
+            }
+        });
+
+        if start {
+            self.start_session();
+        } else if advance {
+            self.advance_session();
+        } else if abort || !open {
+            if let Some(SessionPhase::Running { .. }) = self.session.as_ref().map(|s| &s.phase) {
+                self.stop_practice();
+                self.stop_audio();
+            }
+            if !open {
+                self.session = None;
+            } else if let Some(ref mut session) = self.session {
+                session.phase = SessionPhase::Done {
+                    practiced_seconds: session.practiced_seconds + elapsed,
+                };
+            }
+        }
+    }
+}
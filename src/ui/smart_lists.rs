@@ -0,0 +1,271 @@
+use super::pending::Loaded;
+use super::{palette, tr, tr_arg, SongIndexApp, SEARCH_SYNTAX_HELP};
+use crate::db::{
+    delete_smart_list, query_songs, save_smart_list, smart_lists, ExtraFilters, SortMode, TagFilter,
+};
+use eframe::egui;
+
+/// Create or edit one smart list: a name and a rule in the search syntax.
+pub(super) struct SmartListEditor {
+    id: Option<i64>,
+    name: String,
+    regel: String,
+    /// Songs the rule matches right now, and the rule they were counted for.
+    count: Option<Loaded<usize>>,
+    counted: Option<String>,
+    confirm_delete: bool,
+}
+
+enum Edit {
+    Save,
+    Delete,
+}
+
+impl SongIndexApp {
+    /// The "Listen:" row in the filter accordion. Returns whether the list
+    /// filter changed.
+    pub(super) fn smart_list_row(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        let mut edit = None;
+        let mut delete = None;
+        ui.horizontal_wrapped(|ui| {
+            ui.spacing_mut().item_spacing = egui::vec2(6.0, 4.0);
+            ui.label(
+                egui::RichText::new(tr("Listen:"))
+                    .size(12.5)
+                    .color(palette::TEXT_MUTED),
+            );
+            ui.add_space(2.0);
+            for list in &self.smart_lists {
+                let is_active = self.extras.smart_list == Some(list.id);
+                let label = if is_active {
+                    egui::RichText::new(&list.name)
+                        .size(12.5)
+                        .color(palette::bg_deep())
+                        .strong()
+                } else {
+                    egui::RichText::new(&list.name)
+                        .size(12.5)
+                        .color(palette::TEXT_SECONDARY)
+                };
+                let response = ui
+                    .selectable_label(is_active, label)
+                    .on_hover_text(egui::RichText::new(&list.regel).monospace());
+                if response.clicked() {
+                    self.extras.smart_list = (!is_active).then_some(list.id);
+                    changed = true;
+                }
+                response.context_menu(|ui| {
+                    if ui.button(tr("Bearbeiten\u{2026}")).clicked() {
+                        edit = Some(list.id);
+                        ui.close_menu();
+                    }
+                    if ui.button(tr("L\u{00F6}schen")).clicked() {
+                        delete = Some(list.id);
+                        ui.close_menu();
+                    }
+                });
+            }
+            if ui
+                .small_button("+")
+                .on_hover_text(tr("Neue smarte Liste aus der aktuellen Suche"))
+                .clicked()
+            {
+                self.smart_list_editor = Some(SmartListEditor {
+                    id: None,
+                    name: String::new(),
+                    regel: self.search_text.trim().to_string(),
+                    count: None,
+                    counted: None,
+                    confirm_delete: false,
+                });
+            }
+        });
+        if let Some(id) = edit {
+            self.open_smart_list_editor(id);
+        }
+        if let Some(id) = delete {
+            changed |= self.remove_smart_list(id);
+        }
+        changed
+    }
+
+    fn open_smart_list_editor(&mut self, id: i64) {
+        let Some(list) = self.smart_lists.iter().find(|l| l.id == id) else {
+            return;
+        };
+        self.smart_list_editor = Some(SmartListEditor {
+            id: Some(list.id),
+            name: list.name.clone(),
+            regel: list.regel.clone(),
+            count: None,
+            counted: None,
+            confirm_delete: false,
+        });
+    }
+
+    /// Delete a list; returns whether it was the active one.
+    fn remove_smart_list(&mut self, id: i64) -> bool {
+        self.smart_lists.retain(|l| l.id != id);
+        self.list_loads.smart_lists = Some(self.db.request(move |conn| {
+            delete_smart_list(conn, id);
+            (None, smart_lists(conn))
+        }));
+        let was_active = self.extras.smart_list == Some(id);
+        if was_active {
+            self.extras.smart_list = None;
+        }
+        was_active
+    }
+
+    pub(super) fn show_smart_list_editor(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.smart_list_editor else {
+            return;
+        };
+
+        if state.counted.as_deref() != Some(state.regel.as_str()) {
+            let regel = state.regel.clone();
+            let count = self.db.request(move |conn| {
+                query_songs(
+                    conn,
+                    &regel,
+                    &TagFilter::none(),
+                    &ExtraFilters::default(),
+                    &SortMode::Title,
+                )
+                .len()
+            });
+            // The last count stays up while the new one is loading.
+            match state.count.as_mut() {
+                Some(loaded) => loaded.reload(count),
+                None => state.count = Some(Loaded::new(count)),
+            }
+            state.counted = Some(state.regel.clone());
+        }
+
+        let mut edit = None;
+        let mut open = true;
+        let title = tr(if state.id.is_some() {
+            "Smarte Liste bearbeiten"
+        } else {
+            "Neue smarte Liste"
+        });
+        egui::Window::new(
+            egui::RichText::new(title)
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(460.0)
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(tr(
+                    "Eine gespeicherte Suche, die als eigene Liste erscheint und sich mit \
+                     der Bibliothek aktualisiert. Zum Beispiel: \
+                     schwierigkeit:Anf\u{00E4}nger has:audio -gespielt:30",
+                ))
+                .size(12.5)
+                .color(palette::TEXT_SECONDARY),
+            );
+            ui.add_space(6.0);
+            egui::Grid::new("smart_list_grid")
+                .num_columns(2)
+                .spacing([10.0, 6.0])
+                .show(ui, |ui| {
+                    ui.label(egui::RichText::new("Name").color(palette::TEXT_SECONDARY));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut state.name)
+                            .desired_width(320.0)
+                            .hint_text(tr("z.\u{00A0}B. Wieder mal spielen")),
+                    );
+                    ui.end_row();
+
+                    ui.label(egui::RichText::new(tr("Regel")).color(palette::TEXT_SECONDARY));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut state.regel)
+                            .desired_width(320.0)
+                            .font(egui::TextStyle::Monospace),
+                    )
+                    .on_hover_text(tr(SEARCH_SYNTAX_HELP));
+                    ui.end_row();
+                });
+            ui.add_space(4.0);
+            match state.count.as_mut().and_then(Loaded::get) {
+                Some(count) => {
+                    ui.label(
+                        egui::RichText::new(tr_arg("Passt gerade auf {} Songs.", count))
+                            .color(palette::TEXT_MUTED),
+                    );
+                }
+                None => {
+                    ui.spinner();
+                }
+            }
+            ui.add_space(8.0);
+
+            ui.horizontal(|ui| {
+                let valid = !state.name.trim().is_empty() && !state.regel.trim().is_empty();
+                let save_btn = egui::Button::new(
+                    egui::RichText::new(tr("Speichern")).color(palette::TEXT_PRIMARY),
+                )
+                .fill(palette::accent_dim())
+                .rounding(6.0);
+                if ui.add_enabled(valid, save_btn).clicked() {
+                    edit = Some(Edit::Save);
+                }
+                if state.id.is_some() {
+                    if state.confirm_delete {
+                        ui.label(
+                            egui::RichText::new(tr("Wirklich l\u{00F6}schen?"))
+                                .color(palette::ACCENT_RED),
+                        );
+                        if ui.button(tr("Ja")).clicked() {
+                            edit = Some(Edit::Delete);
+                        }
+                        if ui.button(tr("Nein")).clicked() {
+                            state.confirm_delete = false;
+                        }
+                    } else if ui.button(tr("L\u{00F6}schen")).clicked() {
+                        state.confirm_delete = true;
+                    }
+                }
+            });
+        });
+
+        match edit {
+            Some(Edit::Save) => {
+                let (list_id, name, regel) = (
+                    state.id,
+                    state.name.trim().to_string(),
+                    state.regel.trim().to_string(),
+                );
+                self.list_loads.smart_lists = Some(self.db.request(move |conn| {
+                    (
+                        save_smart_list(conn, list_id, &name, &regel),
+                        smart_lists(conn),
+                    )
+                }));
+                // A new list is shown once saved; an edited one only if active.
+                if state.id.is_none() || self.extras.smart_list == state.id {
+                    if state.id.is_none() && self.search_text.trim() == state.regel.trim() {
+                        self.search_text.clear();
+                    }
+                    self.list_loads.show_saved_smart_list = true;
+                }
+                self.smart_list_editor = None;
+            }
+            Some(Edit::Delete) => {
+                if let Some(id) = state.id {
+                    if self.remove_smart_list(id) {
+                        self.refresh_songs_only();
+                    }
+                }
+                self.smart_list_editor = None;
+            }
+            None if !open => self.smart_list_editor = None,
+            None => {}
+        }
+    }
+}
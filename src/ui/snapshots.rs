@@ -0,0 +1,345 @@
+use super::pending::{answer, Loaded};
+use super::{category_label, palette, tag_color, SongIndexApp};
+use crate::i18n::{tr, tr_arg, tr_args};
+use crate::snapshot::{
+    category_changes, current_song, list_snapshots, load_snapshot, restore_category, restore_song,
+    Snapshot, SongSnapshot,
+};
+use eframe::egui;
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+
+#[derive(PartialEq)]
+enum RestoreMode {
+    Song,
+    Kategorie,
+}
+
+pub(super) struct SnapshotWindowState {
+    dates: Vec<String>,
+    date_idx: usize,
+    /// Shared with the database worker for comparing and restoring.
+    snapshot: Option<Arc<Snapshot>>,
+    mode: RestoreMode,
+    song_filter: String,
+    selected_song: Option<usize>,
+    /// The selected song as it is now; `None` inside once it left the index.
+    current: Option<Loaded<Option<SongSnapshot>>>,
+    kategorie: Option<String>,
+    category_changes: Option<Loaded<usize>>,
+    message: Option<String>,
+    /// Message of a running category restore.
+    restoring: Option<Receiver<String>>,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_snapshots(&mut self) {
+        let dates = list_snapshots();
+        let snapshot = dates.first().and_then(|d| load_snapshot(d)).map(Arc::new);
+        self.snapshot_window = Some(SnapshotWindowState {
+            dates,
+            date_idx: 0,
+            snapshot,
+            mode: RestoreMode::Song,
+            song_filter: String::new(),
+            selected_song: None,
+            current: None,
+            kategorie: None,
+            category_changes: None,
+            message: None,
+            restoring: None,
+        });
+    }
+
+    pub(super) fn show_snapshot_window(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.snapshot_window else {
+            return;
+        };
+        if let Some(message) = answer(&mut state.restoring) {
+            state.message = Some(message);
+        }
+
+        let mut restore = false;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Zeitmaschine"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([560.0, 460.0])
+        .show(ctx, |ui| {
+            if state.dates.is_empty() {
+                ui.label(
+                    egui::RichText::new(tr("Noch keine Snapshots vorhanden."))
+                        .color(palette::TEXT_MUTED),
+                );
+                return;
+            }
+
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(tr("Stand vom:")).color(palette::TEXT_SECONDARY));
+                let before = state.date_idx;
+                let label =
+                    |d: &str| format!("{} ({})", self.format.date(d), self.format.relative(d));
+                egui::ComboBox::from_id_salt("snapshot_date")
+                    .selected_text(label(&state.dates[state.date_idx]))
+                    .show_ui(ui, |ui| {
+                        for (i, d) in state.dates.iter().enumerate() {
+                            ui.selectable_value(&mut state.date_idx, i, label(d));
+                        }
+                    });
+                if before != state.date_idx {
+                    state.snapshot = load_snapshot(&state.dates[state.date_idx]).map(Arc::new);
+                    state.selected_song = None;
+                    state.current = None;
+                    state.category_changes = None;
+                    state.message = None;
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut state.mode, RestoreMode::Song, tr("Einzelner Song"));
+                ui.selectable_value(&mut state.mode, RestoreMode::Kategorie, tr("Tag-Kategorie"));
+            });
+            ui.separator();
+
+            let Some(ref snapshot) = state.snapshot else {
+                ui.label(
+                    egui::RichText::new(tr("Snapshot konnte nicht gelesen werden."))
+                        .color(palette::ACCENT_RED),
+                );
+                return;
+            };
+
+            match state.mode {
+                RestoreMode::Song => {
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new(tr("Suche:")).color(palette::TEXT_SECONDARY));
+                        ui.text_edit_singleline(&mut state.song_filter);
+                    });
+                    let filter = state.song_filter.to_lowercase();
+                    egui::ScrollArea::vertical()
+                        .id_salt("snapshot_songs")
+                        .max_height(160.0)
+                        .show(ui, |ui| {
+                            for (i, song) in snapshot
+                                .songs
+                                .iter()
+                                .enumerate()
+                                .filter(|(_, s)| {
+                                    filter.is_empty()
+                                        || s.titel.to_lowercase().contains(&filter)
+                                        || s.dateipfad.to_lowercase().contains(&filter)
+                                })
+                                .take(200)
+                            {
+                                if ui
+                                    .selectable_label(
+                                        state.selected_song == Some(i),
+                                        egui::RichText::new(&song.dateipfad).size(12.5),
+                                    )
+                                    .clicked()
+                                {
+                                    state.selected_song = Some(i);
+                                    state.current = None;
+                                    state.message = None;
+                                }
+                            }
+                        });
+
+                    if let Some(song) = state.selected_song.and_then(|i| snapshot.songs.get(i)) {
+                        ui.separator();
+                        let current = state.current.get_or_insert_with(|| {
+                            let dateipfad = song.dateipfad.clone();
+                            Loaded::new(self.db.request(move |conn| current_song(conn, &dateipfad)))
+                        });
+                        match current.get() {
+                            None => {
+                                ui.spinner();
+                            }
+                            Some(Some(current)) => {
+                                ui.columns(2, |cols| {
+                                    song_preview(&mut cols[0], tr("Aktuell"), current);
+                                    song_preview(&mut cols[1], &snapshot.datum, song);
+                                });
+                                ui.add_space(6.0);
+                                if ui.button(tr("Diesen Song wiederherstellen")).clicked() {
+                                    restore = true;
+                                }
+                            }
+                            Some(None) => {
+                                ui.label(
+                                    egui::RichText::new(tr("Die Datei ist nicht mehr im Index."))
+                                        .color(palette::TEXT_MUTED),
+                                );
+                            }
+                        }
+                    }
+                }
+                RestoreMode::Kategorie => {
+                    let mut categories: Vec<&str> = snapshot
+                        .songs
+                        .iter()
+                        .flat_map(|s| s.tags.iter().map(|t| t.kategorie.as_str()))
+                        .collect();
+                    categories.sort();
+                    categories.dedup();
+
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new(tr("Kategorie:")).color(palette::TEXT_SECONDARY),
+                        );
+                        let before = state.kategorie.clone();
+                        egui::ComboBox::from_id_salt("snapshot_kategorie")
+                            .selected_text(
+                                state
+                                    .kategorie
+                                    .as_deref()
+                                    .map(category_label)
+                                    .unwrap_or(tr("w\u{00E4}hlen")),
+                            )
+                            .show_ui(ui, |ui| {
+                                for k in &categories {
+                                    ui.selectable_value(
+                                        &mut state.kategorie,
+                                        Some(k.to_string()),
+                                        category_label(k),
+                                    );
+                                }
+                            });
+                        if before != state.kategorie {
+                            state.category_changes = None;
+                            state.message = None;
+                        }
+                    });
+
+                    if let Some(ref kategorie) = state.kategorie {
+                        let changes =
+                            state.category_changes.get_or_insert_with(|| {
+                                let (snapshot, kategorie) = (snapshot.clone(), kategorie.clone());
+                                Loaded::new(self.db.request(move |conn| {
+                                    category_changes(conn, &snapshot, &kategorie)
+                                }))
+                            });
+                        let Some(&mut changes) = changes.get() else {
+                            ui.spinner();
+                            return;
+                        };
+                        ui.add_space(4.0);
+                        ui.label(
+                            egui::RichText::new(tr_args(
+                                "{} Songs haben heute andere \u{201E}{}\u{201C}-Tags.",
+                                &[&changes, &category_label(kategorie)],
+                            ))
+                            .color(palette::TEXT_SECONDARY),
+                        );
+                        ui.add_space(6.0);
+                        if ui
+                            .add_enabled(
+                                changes > 0 && state.restoring.is_none(),
+                                egui::Button::new(tr("Kategorie wiederherstellen")),
+                            )
+                            .clicked()
+                        {
+                            restore = true;
+                        }
+                    }
+                }
+            }
+
+            if let Some(ref msg) = state.message {
+                ui.add_space(6.0);
+                ui.label(egui::RichText::new(msg).color(palette::AUDIO_GREEN));
+            }
+        });
+
+        if restore {
+            if let Some(snapshot) = state.snapshot.clone() {
+                match state.mode {
+                    RestoreMode::Song => {
+                        if let Some(song) = state.selected_song.and_then(|i| snapshot.songs.get(i))
+                        {
+                            state.message = Some(tr_args(
+                                "\u{201E}{}\u{201C} auf Stand {} zur\u{00FC}ckgesetzt.",
+                                &[&song.titel, &snapshot.datum],
+                            ));
+                            let song = song.clone();
+                            self.db.send(move |conn| restore_song(conn, &song));
+                            state.current = None;
+                        }
+                    }
+                    RestoreMode::Kategorie => {
+                        if let Some(kategorie) = state.kategorie.clone() {
+                            state.restoring = Some(self.db.request(move |conn| {
+                                let n = restore_category(conn, &snapshot, &kategorie);
+                                tr_args(
+                                    "{} bei {} Songs auf Stand {} zur\u{00FC}ckgesetzt.",
+                                    &[&category_label(&kategorie), &n, &snapshot.datum],
+                                )
+                            }));
+                            state.category_changes = None;
+                        }
+                    }
+                }
+            }
+            self.needs_refresh = true;
+        }
+
+        if !open {
+            self.snapshot_window = None;
+        }
+    }
+}
+
+fn song_preview(ui: &mut egui::Ui, heading: &str, song: &SongSnapshot) {
+    ui.label(
+        egui::RichText::new(heading)
+            .size(12.5)
+            .strong()
+            .color(palette::TEXT_MUTED),
+    );
+    ui.label(egui::RichText::new(&song.titel).color(palette::TEXT_PRIMARY));
+    if let Some(ref artist) = song.artist {
+        ui.label(egui::RichText::new(artist).color(palette::TEXT_SECONDARY));
+    }
+    if let Some(ref details) = song.details {
+        let mut parts = Vec::new();
+        if let Some(ref tonart) = details.tonart {
+            parts.push(tonart.clone());
+        }
+        if let Some(kapo) = details.kapo {
+            parts.push(tr_arg("Kapo {}", kapo));
+        }
+        if let Some(bpm) = details.bpm {
+            parts.push(format!("{bpm} BPM"));
+        }
+        if !parts.is_empty() {
+            ui.label(
+                egui::RichText::new(parts.join(" \u{00B7} "))
+                    .size(12.0)
+                    .color(palette::TEXT_SECONDARY),
+            );
+        }
+        if let Some(ref notizen) = details.notizen {
+            ui.label(
+                egui::RichText::new(notizen)
+                    .size(12.0)
+                    .italics()
+                    .color(palette::TEXT_MUTED),
+            );
+        }
+    }
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().item_spacing = egui::vec2(4.0, 4.0);
+        for tag in &song.tags {
+            ui.label(
+                egui::RichText::new(&tag.wert)
+                    .size(11.5)
+                    .color(tag_color(&tag.kategorie)),
+            );
+        }
+    });
+}
@@ -0,0 +1,471 @@
+use super::pending::Loaded;
+use super::{category_label, palette, tag_color, SongIndexApp, CATEGORY_LABELS};
+use crate::db::{audio_coverage, most_used_tags, never_opened_songs, songs_added_per_month};
+use crate::i18n::{tr, tr_arg, tr_args};
+use crate::stats::{tag_cooccurrence, Cooccurrence};
+use eframe::egui;
+use egui_plot::{uniform_grid_spacer, Bar, BarChart, Plot};
+
+const CELL_SIZE: egui::Vec2 = egui::vec2(46.0, 26.0);
+/// Values per bar chart; the rest of a long category is left out.
+const MAX_BARS: usize = 15;
+const BAR_HEIGHT: f32 = 20.0;
+/// Axis labels longer than this are cut off with an ellipsis.
+const MAX_LABEL_CHARS: usize = 18;
+
+#[derive(Clone, Copy, PartialEq)]
+enum StatsTab {
+    Overview,
+    Combinations,
+}
+
+/// Aggregates for the "\u{00DC}bersicht" tab, loaded when it is first shown.
+struct Overview {
+    per_month: Vec<(String, i64)>,
+    audio: Vec<(String, i64, i64)>,
+    top_tags: Vec<(String, String, i64)>,
+    never_opened: Vec<(String, Option<String>, String)>,
+}
+
+pub(super) struct StatsWindowState {
+    tab: StatsTab,
+    overview: Option<Loaded<Overview>>,
+    /// Category of the "Songs pro Wert" chart.
+    chart_kategorie: String,
+    row_kategorie: String,
+    col_kategorie: String,
+    matrix: Option<Loaded<Cooccurrence>>,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_stats(&mut self) {
+        self.stats_window = Some(StatsWindowState {
+            tab: StatsTab::Overview,
+            overview: None,
+            chart_kategorie: "stil".to_string(),
+            row_kategorie: "stil".to_string(),
+            col_kategorie: "schwierigkeit".to_string(),
+            matrix: None,
+        });
+    }
+
+    pub(super) fn show_stats_window(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.stats_window else {
+            return;
+        };
+        match state.tab {
+            StatsTab::Overview if state.overview.is_none() => {
+                state.overview = Some(Loaded::new(self.db.request(load_overview)));
+            }
+            StatsTab::Combinations if state.matrix.is_none() => {
+                let (rows, cols) = (state.row_kategorie.clone(), state.col_kategorie.clone());
+                state.matrix = Some(Loaded::new(
+                    self.db
+                        .request(move |conn| tag_cooccurrence(conn, &rows, &cols)),
+                ));
+            }
+            _ => {}
+        }
+
+        let tags = &self.tags;
+        let mut open = true;
+        let mut open_coverage = false;
+        let mut open_song = None;
+        egui::Window::new(
+            egui::RichText::new(tr("Statistik"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([620.0, 560.0])
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut state.tab, StatsTab::Overview, tr("\u{00DC}bersicht"));
+                ui.selectable_value(
+                    &mut state.tab,
+                    StatsTab::Combinations,
+                    tr("Tag-Kombinationen"),
+                );
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button(tr("Lehrplan-Abdeckung\u{2026}")).clicked() {
+                        open_coverage = true;
+                    }
+                    if state.tab == StatsTab::Overview
+                        && ui
+                            .small_button("\u{21BB}")
+                            .on_hover_text(tr("Neu berechnen"))
+                            .clicked()
+                    {
+                        if let Some(overview) = state.overview.as_mut() {
+                            overview.reload(self.db.request(load_overview));
+                        }
+                    }
+                });
+            });
+            ui.separator();
+
+            if state.tab == StatsTab::Overview {
+                let Some(overview) = state.overview.as_mut().and_then(Loaded::get) else {
+                    ui.spinner();
+                    return;
+                };
+                egui::ScrollArea::vertical()
+                    .id_salt("stats_overview")
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            section_title(ui, tr("Songs pro Wert"));
+                            category_combo(ui, "stats_chart", &mut state.chart_kategorie);
+                        });
+                        let values = tags
+                            .iter()
+                            .find(|g| g.kategorie == state.chart_kategorie)
+                            .map(|g| g.tags.as_slice())
+                            .unwrap_or_default();
+                        let color = tag_color(&state.chart_kategorie);
+                        let bars: Vec<Bar> = values
+                            .iter()
+                            .filter(|t| t.count > 0)
+                            .take(MAX_BARS)
+                            .map(|t| {
+                                Bar::new(0.0, t.count as f64)
+                                    .name(tr_args("{}: {} Songs", &[&t.wert, &t.count]))
+                                    .fill(color)
+                            })
+                            .collect();
+                        let labels: Vec<&str> = values
+                            .iter()
+                            .filter(|t| t.count > 0)
+                            .take(MAX_BARS)
+                            .map(|t| t.wert.as_str())
+                            .collect();
+                        horizontal_bars(ui, "stats_values", &labels, vec![bars]);
+
+                        section_title(ui, tr("Neu pro Monat"));
+                        month_chart(ui, &overview.per_month);
+
+                        section_title(ui, tr("Audio-Abdeckung pro Stil"));
+                        let audio: Vec<_> = overview.audio.iter().take(MAX_BARS).collect();
+                        let with: Vec<Bar> = audio
+                            .iter()
+                            .map(|(wert, total, with)| {
+                                Bar::new(0.0, *with as f64)
+                                    .name(tr_args("{}: {} von {} mit Audio", &[wert, with, total]))
+                                    .fill(palette::AUDIO_GREEN)
+                            })
+                            .collect();
+                        let without: Vec<Bar> = audio
+                            .iter()
+                            .map(|(wert, total, with)| {
+                                Bar::new(0.0, (total - with) as f64)
+                                    .name(tr_args(
+                                        "{}: {} von {} ohne Audio",
+                                        &[wert, &(total - with), total],
+                                    ))
+                                    .fill(palette::border_active())
+                            })
+                            .collect();
+                        let labels: Vec<&str> =
+                            audio.iter().map(|(wert, _, _)| wert.as_str()).collect();
+                        horizontal_bars(ui, "stats_audio", &labels, vec![with, without]);
+
+                        section_title(ui, tr("H\u{00E4}ufigste Tags"));
+                        let bars: Vec<Bar> = overview
+                            .top_tags
+                            .iter()
+                            .map(|(kategorie, wert, count)| {
+                                Bar::new(0.0, *count as f64)
+                                    .name(tr_args(
+                                        "{}: {}, {} Songs",
+                                        &[&category_label(kategorie), wert, count],
+                                    ))
+                                    .fill(tag_color(kategorie))
+                            })
+                            .collect();
+                        let labels: Vec<&str> = overview
+                            .top_tags
+                            .iter()
+                            .map(|(_, wert, _)| wert.as_str())
+                            .collect();
+                        horizontal_bars(ui, "stats_top_tags", &labels, vec![bars]);
+
+                        section_title(
+                            ui,
+                            &tr_arg("Nie ge\u{00F6}ffnet ({})", overview.never_opened.len()),
+                        );
+                        ui.label(
+                            egui::RichText::new(tr(
+                                "Z\u{00E4}hlt nur \u{00D6}ffnen aus Songindex heraus. Klick \
+                                 \u{00F6}ffnet den Song.",
+                            ))
+                            .size(11.5)
+                            .color(palette::TEXT_MUTED),
+                        );
+                        egui::ScrollArea::vertical()
+                            .id_salt("stats_never_opened")
+                            .max_height(180.0)
+                            .show(ui, |ui| {
+                                for (titel, artist, dateipfad) in &overview.never_opened {
+                                    ui.horizontal(|ui| {
+                                        if ui.link(titel).clicked() {
+                                            open_song = Some(dateipfad.clone());
+                                        }
+                                        if let Some(artist) = artist {
+                                            ui.label(
+                                                egui::RichText::new(artist)
+                                                    .color(palette::TEXT_MUTED),
+                                            );
+                                        }
+                                    });
+                                }
+                            });
+                    });
+                return;
+            }
+
+            ui.horizontal(|ui| {
+                let mut changed = false;
+                changed |= category_combo(ui, "stats_rows", &mut state.row_kategorie);
+                ui.label(egui::RichText::new("\u{00D7}").color(palette::TEXT_MUTED));
+                changed |= category_combo(ui, "stats_cols", &mut state.col_kategorie);
+                if changed {
+                    state.matrix = None;
+                }
+            });
+            ui.add_space(6.0);
+
+            let Some(matrix) = state.matrix.as_mut().and_then(Loaded::get) else {
+                ui.spinner();
+                return;
+            };
+            if matrix.rows.is_empty() || matrix.columns.is_empty() {
+                ui.label(
+                    egui::RichText::new(tr("F\u{00FC}r diese Kategorien gibt es noch keine Tags."))
+                        .color(palette::TEXT_MUTED),
+                );
+                return;
+            }
+            egui::ScrollArea::both().show(ui, |ui| {
+                heatmap(ui, matrix);
+            });
+            ui.add_space(4.0);
+            ui.label(
+                egui::RichText::new(tr("Rot umrandet: Kombinationen ohne einen einzigen Song."))
+                    .size(11.5)
+                    .color(palette::TEXT_MUTED),
+            );
+        });
+
+        if let Some(dateipfad) = open_song {
+            self.open_song_file(&dateipfad);
+            if let Some(overview) = self
+                .stats_window
+                .as_mut()
+                .and_then(|state| state.overview.as_mut())
+                .and_then(Loaded::get)
+            {
+                overview.never_opened.retain(|(_, _, p)| *p != dateipfad);
+            }
+        }
+        if open_coverage {
+            self.open_coverage();
+        }
+        if !open {
+            self.stats_window = None;
+        }
+    }
+}
+
+fn section_title(ui: &mut egui::Ui, title: &str) {
+    ui.add_space(8.0);
+    ui.label(
+        egui::RichText::new(title)
+            .size(14.0)
+            .strong()
+            .color(palette::TEXT_PRIMARY),
+    );
+}
+
+fn short_label(label: &str) -> String {
+    if label.chars().count() > MAX_LABEL_CHARS {
+        let cut: String = label.chars().take(MAX_LABEL_CHARS - 1).collect();
+        format!("{cut}\u{2026}")
+    } else {
+        label.to_string()
+    }
+}
+
+/// One row per label, the first on top. `series` are stacked left to right;
+/// each bar's name is its hover text.
+fn horizontal_bars(ui: &mut egui::Ui, id: &str, labels: &[&str], series: Vec<Vec<Bar>>) {
+    if labels.is_empty() {
+        ui.label(egui::RichText::new(tr("Noch keine Daten.")).color(palette::TEXT_MUTED));
+        return;
+    }
+    let rows = labels.len();
+    let mut charts: Vec<BarChart> = Vec::new();
+    for bars in series {
+        let bars = bars
+            .into_iter()
+            .enumerate()
+            .map(|(i, bar)| Bar {
+                argument: (rows - 1 - i) as f64,
+                ..bar
+            })
+            .collect();
+        let chart = BarChart::new(bars)
+            .horizontal()
+            .width(0.7)
+            .element_formatter(Box::new(|bar, _| bar.name.clone()));
+        let below: Vec<&BarChart> = charts.iter().collect();
+        let chart = chart.stack_on(&below);
+        charts.push(chart);
+    }
+    let labels: Vec<String> = labels.iter().map(|l| short_label(l)).collect();
+    chart_plot(id)
+        .height(rows as f32 * BAR_HEIGHT + 30.0)
+        .y_axis_min_width(110.0)
+        .y_grid_spacer(uniform_grid_spacer(|_| [1.0, 1.0, 1.0]))
+        .y_axis_formatter(move |mark, _| axis_label(&labels, rows - 1, mark.value))
+        .include_x(0.0)
+        .show(ui, |plot| {
+            for chart in charts {
+                plot.bar_chart(chart);
+            }
+        });
+}
+
+fn month_chart(ui: &mut egui::Ui, months: &[(String, i64)]) {
+    if months.is_empty() {
+        ui.label(egui::RichText::new(tr("Noch keine Daten.")).color(palette::TEXT_MUTED));
+        return;
+    }
+    let bars = months
+        .iter()
+        .enumerate()
+        .map(|(i, (monat, count))| {
+            Bar::new(i as f64, *count as f64)
+                .name(tr_args("{}: {} Songs", &[monat, count]))
+                .fill(palette::accent())
+        })
+        .collect();
+    let chart = BarChart::new(bars)
+        .width(0.8)
+        .element_formatter(Box::new(|bar, _| bar.name.clone()));
+    let labels: Vec<String> = months.iter().map(|(m, _)| m.clone()).collect();
+    chart_plot("stats_months")
+        .height(180.0)
+        .x_axis_formatter(move |mark, _| axis_label(&labels, 0, mark.value))
+        .include_y(0.0)
+        .show(ui, |plot| plot.bar_chart(chart));
+}
+
+/// The label for an axis mark on a whole number; `flip` counts from the top.
+fn axis_label(labels: &[String], flip: usize, value: f64) -> String {
+    if value.fract() != 0.0 || value < 0.0 {
+        return String::new();
+    }
+    let index = value as usize;
+    let index = if flip > 0 {
+        flip.checked_sub(index)
+    } else {
+        Some(index)
+    };
+    index
+        .and_then(|i| labels.get(i))
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// A plot that stays put: no panning, zooming or box selection.
+fn chart_plot(id: &str) -> Plot<'static> {
+    Plot::new(id)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .allow_boxed_zoom(false)
+        .allow_double_click_reset(false)
+        .show_x(false)
+        .show_y(false)
+}
+
+fn category_combo(ui: &mut egui::Ui, id: &str, kategorie: &mut String) -> bool {
+    let before = kategorie.clone();
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(category_label(kategorie))
+        .show_ui(ui, |ui| {
+            for (k, label) in CATEGORY_LABELS {
+                ui.selectable_value(kategorie, k.to_string(), *label);
+            }
+        });
+    *kategorie != before
+}
+
+fn load_overview(conn: &mut rusqlite::Connection) -> Overview {
+    Overview {
+        per_month: songs_added_per_month(conn),
+        audio: audio_coverage(conn, "stil"),
+        top_tags: most_used_tags(conn, MAX_BARS),
+        never_opened: never_opened_songs(conn),
+    }
+}
+
+fn heatmap(ui: &mut egui::Ui, matrix: &Cooccurrence) {
+    let max = matrix.max().max(1) as f32;
+    egui::Grid::new("cooccurrence_grid")
+        .spacing([2.0, 2.0])
+        .show(ui, |ui| {
+            ui.label("");
+            for col in &matrix.columns {
+                ui.add_sized(
+                    [CELL_SIZE.x, CELL_SIZE.y],
+                    egui::Label::new(
+                        egui::RichText::new(col)
+                            .size(11.0)
+                            .color(palette::TEXT_SECONDARY),
+                    )
+                    .truncate(),
+                )
+                .on_hover_text(col);
+            }
+            ui.end_row();
+
+            for (r, row) in matrix.rows.iter().enumerate() {
+                ui.label(
+                    egui::RichText::new(row)
+                        .size(12.0)
+                        .color(palette::TEXT_SECONDARY),
+                );
+                for (c, col) in matrix.columns.iter().enumerate() {
+                    let count = matrix.counts[r][c];
+                    let (rect, resp) = ui.allocate_exact_size(CELL_SIZE, egui::Sense::hover());
+                    let t = count as f32 / max;
+                    let fill = lerp_color(palette::bg_card(), palette::accent(), t.sqrt());
+                    ui.painter().rect_filled(rect, 3.0, fill);
+                    if count == 0 {
+                        ui.painter().rect_stroke(
+                            rect.shrink(0.5),
+                            3.0,
+                            egui::Stroke::new(1.0, palette::ACCENT_RED),
+                        );
+                    } else {
+                        ui.painter().text(
+                            rect.center(),
+                            egui::Align2::CENTER_CENTER,
+                            count.to_string(),
+                            egui::FontId::proportional(12.0),
+                            palette::TEXT_PRIMARY,
+                        );
+                    }
+                    resp.on_hover_text(tr_args("{} \u{00D7} {}: {} Songs", &[row, col, &count]));
+                }
+                ui.end_row();
+            }
+        });
+}
+
+fn lerp_color(a: egui::Color32, b: egui::Color32, t: f32) -> egui::Color32 {
+    let mix = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * t).round() as u8;
+    egui::Color32::from_rgb(mix(a.r(), b.r()), mix(a.g(), b.g()), mix(a.b(), b.b()))
+}
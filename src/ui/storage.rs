@@ -0,0 +1,248 @@
+use super::pending::{answer, Loaded};
+use super::{palette, save_config, SongIndexApp};
+use crate::config::{data_dir, portable_dir, DATABASE_FILE};
+use crate::i18n::{tr, tr_arg};
+use crate::open_with::{open_folder, reveal, REVEAL_LABEL};
+use eframe::egui;
+use rusqlite::params;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
+
+/// "Speicherort": where config and library live. A different library is
+/// only opened on the next start.
+pub(super) struct StorageState {
+    /// File of the open library.
+    open_path: Loaded<Option<PathBuf>>,
+    error: Option<String>,
+    /// Target and result of a running move.
+    copying: Option<Receiver<(PathBuf, Result<(), String>)>>,
+}
+
+enum Change {
+    /// Copy the open library there and quit.
+    Move(PathBuf),
+    /// Use another existing library from the next start on.
+    Open(PathBuf),
+    Default,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_storage(&mut self) {
+        let open_path = self.db.request(|conn| conn.path().map(PathBuf::from));
+        self.storage_window = Some(StorageState {
+            open_path: Loaded::new(open_path),
+            error: None,
+            copying: None,
+        });
+    }
+
+    pub(super) fn show_storage(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.storage_window else {
+            return;
+        };
+        if let Some((path, result)) = answer(&mut state.copying) {
+            match result {
+                Ok(()) => {
+                    self.set_database(&path);
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                }
+                Err(e) => state.error = Some(e),
+            }
+            return;
+        }
+
+        let data_dir = data_dir();
+        let open_path = state.open_path.get().cloned().flatten();
+        let copying = state.copying.is_some();
+        let next_path = self.config.database_path();
+        let demo = self.config.demo;
+        let mut change = None;
+        let mut quit = false;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Speicherort"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(460.0)
+        .show(ctx, |ui| {
+            let intro = if portable_dir().is_some() {
+                tr(
+                    "Portabel: Einstellungen, Bibliothek und Caches liegen im Ordner \
+                    \u{201E}songindex-daten\u{201C} neben dem Programm.",
+                )
+            } else {
+                tr(
+                    "Einstellungen und Caches liegen im Datenordner. F\u{00FC}r den Betrieb \
+                    vom USB-Stick legt \u{201E}songindex portable\u{201C} im Terminal den \
+                    Ordner \u{201E}songindex-daten\u{201C} neben dem Programm an.",
+                )
+            };
+            ui.label(
+                egui::RichText::new(intro)
+                    .size(12.5)
+                    .color(palette::TEXT_SECONDARY),
+            );
+            ui.add_space(6.0);
+            egui::Grid::new("storage_grid")
+                .num_columns(3)
+                .spacing([10.0, 6.0])
+                .show(ui, |ui| {
+                    ui.label(egui::RichText::new(tr("Datenordner")).color(palette::TEXT_SECONDARY));
+                    ui.label(egui::RichText::new(data_dir.display().to_string()).monospace());
+                    if ui.small_button(tr(REVEAL_LABEL)).clicked() {
+                        open_folder(&data_dir);
+                    }
+                    ui.end_row();
+
+                    ui.label(egui::RichText::new(tr("Bibliothek")).color(palette::TEXT_SECONDARY));
+                    match open_path {
+                        Some(ref path) => {
+                            ui.label(egui::RichText::new(path.display().to_string()).monospace());
+                            if ui.small_button(tr(REVEAL_LABEL)).clicked() {
+                                reveal(path);
+                            }
+                        }
+                        None => {
+                            ui.label("\u{2013}");
+                        }
+                    }
+                    ui.end_row();
+                });
+            ui.add_space(8.0);
+
+            if demo {
+                ui.label(
+                    egui::RichText::new(tr("In der Demo-Bibliothek nicht verf\u{00FC}gbar."))
+                        .color(palette::TEXT_MUTED),
+                );
+                return;
+            }
+            if copying {
+                ui.spinner();
+                return;
+            }
+            ui.horizontal(|ui| {
+                if ui
+                    .button(tr("Verschieben\u{2026}"))
+                    .on_hover_text(tr(
+                        "Kopiert die Bibliothek an den neuen Ort und beendet Songindex; \
+                         beim n\u{00E4}chsten Start wird die Kopie ge\u{00F6}ffnet. Die \
+                         alte Datei bleibt liegen.",
+                    ))
+                    .clicked()
+                {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .set_title(tr("Bibliothek verschieben"))
+                        .set_file_name(DATABASE_FILE)
+                        .add_filter(tr("Songindex-Bibliothek"), &["db"])
+                        .save_file()
+                    {
+                        change = Some(Change::Move(path));
+                    }
+                }
+                if ui
+                    .button(tr("Andere \u{00F6}ffnen\u{2026}"))
+                    .on_hover_text(tr(
+                        "Eine vorhandene Bibliothek ab dem n\u{00E4}chsten Start verwenden.",
+                    ))
+                    .clicked()
+                {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .set_title(tr("Bibliothek \u{00F6}ffnen"))
+                        .add_filter(tr("Songindex-Bibliothek"), &["db"])
+                        .pick_file()
+                    {
+                        change = Some(Change::Open(path));
+                    }
+                }
+                if ui
+                    .add_enabled(
+                        self.config.database.is_some(),
+                        egui::Button::new(tr("Standardort")),
+                    )
+                    .on_hover_text(tr_arg(
+                        "Ab dem n\u{00E4}chsten Start wieder {} im Datenordner verwenden.",
+                        DATABASE_FILE,
+                    ))
+                    .clicked()
+                {
+                    change = Some(Change::Default);
+                }
+            });
+            if open_path.as_ref().is_some_and(|path| *path != next_path) {
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(tr_arg(
+                            "Ab dem n\u{00E4}chsten Start: {}",
+                            next_path.display(),
+                        ))
+                        .color(palette::accent()),
+                    );
+                    if ui.small_button(tr("Jetzt beenden")).clicked() {
+                        quit = true;
+                    }
+                });
+            }
+            if let Some(ref error) = state.error {
+                ui.label(egui::RichText::new(error).color(palette::ACCENT_RED));
+            }
+        });
+
+        match change {
+            Some(Change::Move(path)) => self.copy_library(path),
+            Some(Change::Open(path)) => self.set_database(&path),
+            Some(Change::Default) => {
+                self.config.database = None;
+                save_config(&self.config);
+            }
+            None => {}
+        }
+        if quit {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        }
+        if !open {
+            self.storage_window = None;
+        }
+    }
+
+    /// Start a consistent copy of the open library at `target`, which must
+    /// not exist yet; on success the next start opens it and Songindex quits.
+    fn copy_library(&mut self, target: PathBuf) {
+        let Some(ref mut state) = self.storage_window else {
+            return;
+        };
+        if target.exists() {
+            state.error = Some(tr_arg(
+                "{} gibt es schon; bitte einen anderen Namen w\u{00E4}hlen.",
+                target.display(),
+            ));
+            return;
+        }
+        state.error = None;
+        // Written before the copy so the next start still reopens here.
+        self.save_ui_state();
+        let Some(ref mut state) = self.storage_window else {
+            return;
+        };
+        state.copying = Some(self.db.request(move |conn| {
+            let result = conn
+                .execute("VACUUM INTO ?1", params![target.to_string_lossy()])
+                .map(|_| ())
+                .map_err(|e| tr_arg("Kopieren fehlgeschlagen: {}", e));
+            (target, result)
+        }));
+    }
+
+    /// Store `path` in the config, relative to the data dir if it is inside
+    /// so a portable folder can move.
+    fn set_database(&mut self, path: &Path) {
+        let relative = path.strip_prefix(data_dir()).unwrap_or(path);
+        self.config.database = (relative != Path::new(DATABASE_FILE)).then(|| relative.into());
+        save_config(&self.config);
+    }
+}
@@ -0,0 +1,181 @@
+use super::{palette, song_context_menu, tag_color, SongAction, SongIndexApp};
+use crate::i18n::tr;
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+
+#[derive(Clone, Copy, PartialEq, Hash)]
+pub(super) enum TableColumn {
+    Titel,
+    Artist,
+    Tags,
+    Audio,
+    Hinzugefuegt,
+}
+
+impl TableColumn {
+    fn label(&self) -> &'static str {
+        match self {
+            TableColumn::Titel => "Titel",
+            TableColumn::Artist => "Artist",
+            TableColumn::Tags => "Tags",
+            TableColumn::Audio => "Audio",
+            TableColumn::Hinzugefuegt => "Hinzugef\u{00FC}gt",
+        }
+    }
+
+    fn all() -> [TableColumn; 5] {
+        [
+            TableColumn::Titel,
+            TableColumn::Artist,
+            TableColumn::Tags,
+            TableColumn::Audio,
+            TableColumn::Hinzugefuegt,
+        ]
+    }
+}
+
+impl SongIndexApp {
+    /// Clicking the active column flips the direction, any other column sorts ascending.
+    pub(super) fn toggle_table_sort(&mut self, column: TableColumn) {
+        self.table_sort = match self.table_sort {
+            Some((current, ascending)) if current == column => Some((column, !ascending)),
+            _ => Some((column, true)),
+        };
+        self.apply_table_sort();
+    }
+
+    /// Re-sort the loaded songs by the table's header sort; the SQL sort order
+    /// stays in effect while no header has been clicked.
+    pub(super) fn apply_table_sort(&mut self) {
+        let Some((column, ascending)) = self.table_sort else {
+            return;
+        };
+        self.songs.sort_by(|a, b| {
+            let ord = match column {
+                TableColumn::Titel => a.titel.to_lowercase().cmp(&b.titel.to_lowercase()),
+                TableColumn::Artist => a
+                    .artist
+                    .as_deref()
+                    .unwrap_or("")
+                    .to_lowercase()
+                    .cmp(&b.artist.as_deref().unwrap_or("").to_lowercase()),
+                TableColumn::Tags => a.tags.len().cmp(&b.tags.len()),
+                TableColumn::Audio => a.has_audio.cmp(&b.has_audio),
+                TableColumn::Hinzugefuegt => a.created_at.cmp(&b.created_at),
+            };
+            if ascending {
+                ord
+            } else {
+                ord.reverse()
+            }
+        });
+    }
+
+    /// Render the songs as a resizable table. Returns the header that was clicked.
+    pub(super) fn show_song_table(
+        &mut self,
+        ui: &mut egui::Ui,
+        action: &mut Option<SongAction>,
+    ) -> Option<TableColumn> {
+        let mut clicked = None;
+        let mut table = TableBuilder::new(ui);
+        if let Some(offset) = self.scroll_memory.pending_restore() {
+            table = table.vertical_scroll_offset(offset);
+        }
+        if self.scroll_to_selected {
+            if let Some(index) = self.selected_index() {
+                table = table.scroll_to_row(index, Some(egui::Align::Center));
+            }
+        }
+        let output = table
+            .striped(true)
+            .resizable(true)
+            .auto_shrink([false, false])
+            .sense(egui::Sense::click())
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .column(Column::initial(220.0).at_least(80.0).clip(true))
+            .column(Column::initial(150.0).at_least(60.0).clip(true))
+            .column(Column::initial(220.0).at_least(60.0).clip(true))
+            .column(Column::initial(70.0).at_least(50.0))
+            .column(Column::remainder().at_least(80.0))
+            .header(24.0, |mut header| {
+                for column in TableColumn::all() {
+                    header.col(|ui| {
+                        let arrow = match self.table_sort {
+                            Some((c, true)) if c == column => " \u{25B2}",
+                            Some((c, false)) if c == column => " \u{25BC}",
+                            _ => "",
+                        };
+                        let text = egui::RichText::new(format!("{}{arrow}", tr(column.label())))
+                            .size(12.5)
+                            .strong()
+                            .color(palette::TEXT_SECONDARY);
+                        if ui
+                            .add(egui::Label::new(text).sense(egui::Sense::click()))
+                            .clicked()
+                        {
+                            clicked = Some(column);
+                        }
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(26.0, self.songs.len(), |mut row| {
+                    let song = &self.songs[row.index()];
+                    row.set_selected(self.selected_song_id == Some(song.id));
+                    row.col(|ui| {
+                        let title = egui::RichText::new(&song.titel).color(palette::TEXT_PRIMARY);
+                        let resp = ui
+                            .add(egui::Label::new(title).sense(egui::Sense::click()))
+                            .on_hover_text(&song.dateipfad);
+                        if resp.double_clicked() {
+                            *action = Some(SongAction::Open(song.id));
+                        } else if resp.clicked() {
+                            *action = Some(SongAction::Select(song.id));
+                        }
+                        song_context_menu(&resp, song, action);
+                    });
+                    row.col(|ui| {
+                        if let Some(ref artist) = song.artist {
+                            ui.label(egui::RichText::new(artist).color(palette::TEXT_SECONDARY));
+                        }
+                    });
+                    row.col(|ui| {
+                        ui.spacing_mut().item_spacing.x = 6.0;
+                        for tag in &song.tags {
+                            ui.label(
+                                egui::RichText::new(&tag.wert)
+                                    .size(11.5)
+                                    .color(tag_color(&tag.kategorie)),
+                            );
+                        }
+                    });
+                    row.col(|ui| {
+                        if let (true, Some(ref ap)) = (song.has_audio, &song.audio_pfad) {
+                            let playing = self.playing_song_id() == Some(song.id);
+                            let label = if playing { "\u{25A0}" } else { "\u{25B6}" };
+                            if ui.small_button(label).clicked() {
+                                *action = Some(SongAction::ToggleAudio {
+                                    song_id: song.id,
+                                    audio_pfad: ap.clone(),
+                                });
+                            }
+                        }
+                    });
+                    row.col(|ui| {
+                        ui.label(
+                            egui::RichText::new(self.format.date(&song.created_at))
+                                .size(12.0)
+                                .color(palette::TEXT_MUTED),
+                        )
+                        .on_hover_text(self.format.relative(&song.created_at));
+                    });
+                    if action.is_none() && row.response().clicked() {
+                        *action = Some(SongAction::Select(song.id));
+                    }
+                });
+            });
+        self.scroll_memory.record(output.state.offset.y);
+        clicked
+    }
+}
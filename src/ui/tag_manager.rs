@@ -0,0 +1,374 @@
+use super::pending::answer;
+use super::{category_label, palette, save_config, tag_color, SongIndexApp, CATEGORY_LABELS};
+use crate::db::{delete_tag, merge_tags, rename_tag, set_tag_category};
+use crate::i18n::{tr, tr_arg, tr_args};
+use crate::taxonomy::{apply_taxonomy, export_taxonomy, read_taxonomy, Taxonomy};
+use eframe::egui;
+use std::sync::mpsc::Receiver;
+
+#[derive(Default)]
+pub(super) struct TagManagerState {
+    filter: String,
+    renaming: Option<(i64, String)>,
+    merging: Option<(i64, Option<i64>)>,
+    confirm_delete: Option<(i64, String, i64)>,
+    message: Option<String>,
+    /// Message of a running import or export.
+    pending_message: Option<Receiver<String>>,
+}
+
+enum TagManagerAction {
+    Rename(i64, String),
+    Merge { from: i64, into: i64 },
+    SetCategory(i64, String),
+    Delete(i64),
+}
+
+impl SongIndexApp {
+    pub(super) fn show_tag_manager(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.tag_manager else {
+            return;
+        };
+        if let Some(message) = answer(&mut state.pending_message) {
+            state.message = Some(message);
+        }
+
+        let mut categories: Vec<String> =
+            CATEGORY_LABELS.iter().map(|(k, _)| k.to_string()).collect();
+        for group in &self.tags {
+            if !categories.contains(&group.kategorie) {
+                categories.push(group.kategorie.clone());
+            }
+        }
+
+        let mut action: Option<TagManagerAction> = None;
+        let mut imported = None;
+        let mut open_packs = false;
+        let mut open_difficulty = false;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Tags verwalten"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([620.0, 480.0])
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(tr("Filter:")).color(palette::TEXT_SECONDARY));
+                ui.text_edit_singleline(&mut state.filter);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button(tr("Vorlagen\u{2026}")).clicked() {
+                        open_packs = true;
+                    }
+                    if ui
+                        .button(tr("Stufen\u{2026}"))
+                        .on_hover_text(tr(
+                            "Reihenfolge der Schwierigkeitswerte f\u{00FC}r Sortierung und Filter",
+                        ))
+                        .clicked()
+                    {
+                        open_difficulty = true;
+                    }
+                    if ui
+                        .button(tr("Schema importieren\u{2026}"))
+                        .on_hover_text(tr(
+                            "Kategorien, Werte und Farben aus einer exportierten Datei \u{00FC}bernehmen",
+                        ))
+                        .clicked()
+                    {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .set_title(tr("Tag-Schema importieren"))
+                            .add_filter("JSON", &["json"])
+                            .pick_file()
+                        {
+                            match read_taxonomy(&path) {
+                                Ok(taxonomy) => imported = Some(taxonomy),
+                                Err(e) => {
+                                    state.message = Some(tr_arg("Import fehlgeschlagen: {}", e));
+                                }
+                            }
+                        }
+                    }
+                    if ui
+                        .button(tr("Schema exportieren\u{2026}"))
+                        .on_hover_text(tr(
+                            "Kategorien, Werte und Farben ohne Songs speichern, z.B. zum Weitergeben",
+                        ))
+                        .clicked()
+                    {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .set_title(tr("Tag-Schema exportieren"))
+                            .set_file_name("tag-schema.json")
+                            .add_filter("JSON", &["json"])
+                            .save_file()
+                        {
+                            let farben = self.config.theme.tags.clone();
+                            state.pending_message = Some(self.db.request(move |conn| {
+                                match export_taxonomy(conn, &path, &farben) {
+                                    Ok(n) => tr_arg("{} Tags exportiert", n),
+                                    Err(e) => tr_arg("Export fehlgeschlagen: {}", e),
+                                }
+                            }));
+                        }
+                    }
+                });
+            });
+            if let Some(ref msg) = state.message {
+                ui.label(egui::RichText::new(msg).size(12.0).color(palette::TEXT_MUTED));
+            }
+            ui.add_space(6.0);
+
+            let filter = state.filter.to_lowercase();
+            egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                for group in &self.tags {
+                    let tags: Vec<_> = group
+                        .tags
+                        .iter()
+                        .filter(|t| filter.is_empty() || t.wert.to_lowercase().contains(&filter))
+                        .collect();
+                    if tags.is_empty() {
+                        continue;
+                    }
+
+                    ui.add_space(4.0);
+                    ui.label(
+                        egui::RichText::new(category_label(&group.kategorie))
+                            .size(13.0)
+                            .strong()
+                            .color(palette::TEXT_SECONDARY),
+                    );
+
+                    egui::Grid::new(("tag_manager", &group.kategorie))
+                        .num_columns(3)
+                        .spacing([10.0, 4.0])
+                        .show(ui, |ui| {
+                            for tag in tags {
+                                match state.renaming {
+                                    Some((id, ref mut wert)) if id == tag.id => {
+                                        let resp = ui.text_edit_singleline(wert);
+                                        let submit = resp.lost_focus()
+                                            && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                                        if (submit || ui.small_button("OK").clicked())
+                                            && !wert.trim().is_empty()
+                                        {
+                                            action = Some(TagManagerAction::Rename(
+                                                id,
+                                                wert.trim().to_string(),
+                                            ));
+                                        }
+                                    }
+                                    _ => {
+                                        ui.label(
+                                            egui::RichText::new(&tag.wert)
+                                                .size(12.5)
+                                                .color(tag_color(&group.kategorie)),
+                                        );
+                                        ui.label(
+                                            egui::RichText::new(tag.count.to_string())
+                                                .size(12.0)
+                                                .color(palette::TEXT_MUTED),
+                                        );
+                                    }
+                                }
+
+                                ui.horizontal(|ui| {
+                                    ui.spacing_mut().item_spacing.x = 4.0;
+                                    if ui.small_button(tr("Umbenennen")).clicked() {
+                                        state.renaming = Some((tag.id, tag.wert.clone()));
+                                        state.merging = None;
+                                    }
+
+                                    egui::ComboBox::from_id_salt(("tag_category", tag.id))
+                                        .width(110.0)
+                                        .selected_text(category_label(&group.kategorie))
+                                        .show_ui(ui, |ui| {
+                                            for k in &categories {
+                                                if ui
+                                                    .selectable_label(
+                                                        *k == group.kategorie,
+                                                        category_label(k),
+                                                    )
+                                                    .clicked()
+                                                    && *k != group.kategorie
+                                                {
+                                                    action = Some(TagManagerAction::SetCategory(
+                                                        tag.id,
+                                                        k.clone(),
+                                                    ));
+                                                }
+                                            }
+                                        });
+
+                                    match state.merging {
+                                        Some((id, ref mut target)) if id == tag.id => {
+                                            let selected = target
+                                                .and_then(|t| group.tags.iter().find(|o| o.id == t))
+                                                .map(|o| o.wert.clone())
+                                                .unwrap_or_else(|| tr("Ziel w\u{00E4}hlen").to_string());
+                                            egui::ComboBox::from_id_salt(("tag_merge", tag.id))
+                                                .width(120.0)
+                                                .selected_text(selected)
+                                                .show_ui(ui, |ui| {
+                                                    for other in
+                                                        group.tags.iter().filter(|o| o.id != tag.id)
+                                                    {
+                                                        ui.selectable_value(
+                                                            target,
+                                                            Some(other.id),
+                                                            &other.wert,
+                                                        );
+                                                    }
+                                                });
+                                            if let Some(into) = *target {
+                                                if ui.small_button("OK").clicked() {
+                                                    action = Some(TagManagerAction::Merge {
+                                                        from: tag.id,
+                                                        into,
+                                                    });
+                                                }
+                                            }
+                                        }
+                                        _ => {
+                                            if ui
+                                                .small_button(tr("Zusammenf\u{00FC}hren"))
+                                                .on_hover_text(tr(
+                                                    "Alle Songs dieses Tags einem anderen Tag zuweisen",
+                                                ))
+                                                .clicked()
+                                            {
+                                                state.merging = Some((tag.id, None));
+                                                state.renaming = None;
+                                            }
+                                        }
+                                    }
+
+                                    if ui
+                                        .small_button(
+                                            egui::RichText::new(tr("L\u{00F6}schen"))
+                                                .color(palette::ACCENT_RED),
+                                        )
+                                        .clicked()
+                                    {
+                                        state.confirm_delete =
+                                            Some((tag.id, tag.wert.clone(), tag.count));
+                                    }
+                                });
+                                ui.end_row();
+                            }
+                        });
+                }
+            });
+        });
+
+        if let Some((id, ref wert, count)) = state.confirm_delete {
+            let mut confirm_open = true;
+            let mut close_confirm = false;
+            egui::Window::new(
+                egui::RichText::new(tr("Tag l\u{00F6}schen?"))
+                    .size(15.0)
+                    .color(palette::ACCENT_RED),
+            )
+            .open(&mut confirm_open)
+            .collapsible(false)
+            .resizable(false)
+            .fixed_size([320.0, 90.0])
+            .show(ctx, |ui| {
+                ui.label(
+                    egui::RichText::new(tr_args(
+                        "Tag \u{201E}{}\u{201C} bei {} Songs entfernen und l\u{00F6}schen?",
+                        &[wert, &count],
+                    ))
+                    .color(palette::TEXT_PRIMARY),
+                );
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    let remove_btn = egui::Button::new(
+                        egui::RichText::new(tr("L\u{00F6}schen")).color(egui::Color32::WHITE),
+                    )
+                    .fill(palette::ACCENT_RED)
+                    .rounding(6.0);
+                    if ui.add(remove_btn).clicked() {
+                        action = Some(TagManagerAction::Delete(id));
+                    }
+                    ui.add_space(4.0);
+                    if ui.button(tr("Abbrechen")).clicked() {
+                        close_confirm = true;
+                    }
+                });
+            });
+            if !confirm_open || close_confirm {
+                state.confirm_delete = None;
+            }
+        }
+
+        if let Some(action) = action {
+            state.renaming = None;
+            state.merging = None;
+            state.confirm_delete = None;
+
+            let changed_id = match action {
+                TagManagerAction::Rename(id, _)
+                | TagManagerAction::Merge { from: id, .. }
+                | TagManagerAction::SetCategory(id, _)
+                | TagManagerAction::Delete(id) => id,
+            };
+            self.db.send(move |conn| match action {
+                TagManagerAction::Rename(id, wert) => rename_tag(conn, id, &wert),
+                TagManagerAction::Merge { from, into } => merge_tags(conn, from, into),
+                TagManagerAction::SetCategory(id, kategorie) => {
+                    set_tag_category(conn, id, &kategorie)
+                }
+                TagManagerAction::Delete(id) => delete_tag(conn, id),
+            });
+
+            // The tag may have moved category or disappeared; drop it from the filters.
+            for set in self.active_filters.values_mut() {
+                set.remove(&changed_id);
+            }
+            self.active_filters.retain(|_, s| !s.is_empty());
+            self.refresh_data();
+        }
+
+        if let Some(taxonomy) = imported {
+            self.import_tag_colors(&taxonomy);
+            let message = self.db.request(move |conn| {
+                tr_arg("{} neue Tags importiert", apply_taxonomy(conn, &taxonomy))
+            });
+            if let Some(ref mut state) = self.tag_manager {
+                state.pending_message = Some(message);
+            }
+            // Queued after the import, so it sees the new tags.
+            self.refresh_data();
+        }
+        if open_packs {
+            self.open_tag_packs();
+        }
+        if open_difficulty {
+            self.open_difficulty();
+        }
+        if !open {
+            self.tag_manager = None;
+        }
+    }
+
+    /// Take over the colors of an imported scheme; they replace the own
+    /// colors of the same categories.
+    fn import_tag_colors(&mut self, taxonomy: &Taxonomy) {
+        let mut changed = false;
+        for kategorie in &taxonomy.kategorien {
+            let Some(color) = kategorie.farbe.as_deref().and_then(palette::parse) else {
+                continue;
+            };
+            let name = kategorie.name.trim().to_lowercase();
+            self.config.theme.tags.insert(name, palette::to_hex(color));
+            changed = true;
+        }
+        if changed {
+            palette::load(&self.config.theme);
+            save_config(&self.config);
+        }
+    }
+}
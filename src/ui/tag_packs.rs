@@ -0,0 +1,126 @@
+use super::pending::answer;
+use super::{category_label, palette, save_config, SongIndexApp};
+use crate::i18n::{tr, tr_arg};
+use crate::taxonomy::{apply_taxonomy, STARTER_PACKS};
+use eframe::egui;
+use std::sync::mpsc::Receiver;
+
+pub(super) struct TagPacksState {
+    selected: Vec<bool>,
+    message: Option<String>,
+    /// Tags created by the running "Übernehmen".
+    applying: Option<Receiver<usize>>,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_tag_packs(&mut self) {
+        self.tag_packs = Some(TagPacksState {
+            selected: vec![false; STARTER_PACKS.len()],
+            message: None,
+            applying: None,
+        });
+    }
+
+    pub(super) fn show_tag_packs(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.tag_packs else {
+            return;
+        };
+        let mut applied = false;
+        if let Some(created) = answer(&mut state.applying) {
+            state.message = Some(tr_arg("{} neue Tags angelegt", created));
+            applied = true;
+        }
+
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Tag-Vorlagen"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(460.0)
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(
+                    tr("Statt mit einer leeren Tag-Liste zu beginnen, k\u{00F6}nnen Kategorien und Werte aus einer Vorlage \u{00FC}bernommen werden. Songs werden dabei nicht ver\u{00E4}ndert."),
+                )
+                .color(palette::TEXT_SECONDARY),
+            );
+            ui.add_space(8.0);
+
+            for (pack, selected) in STARTER_PACKS.iter().zip(state.selected.iter_mut()) {
+                ui.checkbox(
+                    selected,
+                    egui::RichText::new(tr(pack.name))
+                        .strong()
+                        .color(palette::TEXT_PRIMARY),
+                );
+                ui.indent(pack.name, |ui| {
+                    ui.label(
+                        egui::RichText::new(tr(pack.beschreibung))
+                            .size(12.0)
+                            .color(palette::TEXT_SECONDARY),
+                    );
+                    for (kategorie, werte) in pack.kategorien {
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "{}: {}",
+                                category_label(kategorie),
+                                werte.join(", ")
+                            ))
+                            .size(11.5)
+                            .color(palette::TEXT_MUTED),
+                        );
+                    }
+                });
+                ui.add_space(6.0);
+            }
+
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                let any_selected = state.selected.iter().any(|s| *s);
+                let apply_btn = egui::Button::new(
+                    egui::RichText::new(tr("\u{00DC}bernehmen")).color(palette::TEXT_PRIMARY),
+                )
+                .fill(palette::accent_dim())
+                .rounding(6.0);
+                if ui
+                    .add_enabled(any_selected && state.applying.is_none(), apply_btn)
+                    .clicked()
+                {
+                    let taxonomies: Vec<_> = STARTER_PACKS
+                        .iter()
+                        .zip(&state.selected)
+                        .filter(|(_, selected)| **selected)
+                        .map(|(pack, _)| pack.taxonomy())
+                        .collect();
+                    state.applying = Some(self.db.request(move |conn| {
+                        taxonomies.iter().map(|t| apply_taxonomy(conn, t)).sum()
+                    }));
+                }
+                if state.applying.is_some() {
+                    ui.spinner();
+                } else if let Some(ref msg) = state.message {
+                    ui.label(
+                        egui::RichText::new(msg)
+                            .size(12.5)
+                            .color(palette::TEXT_MUTED),
+                    );
+                }
+            });
+        });
+
+        if applied {
+            self.refresh_data();
+        }
+        if !open {
+            self.tag_packs = None;
+            if !self.config.tag_packs_offered {
+                self.config.tag_packs_offered = true;
+                save_config(&self.config);
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use super::{category_label, palette, save_config, tag_color, SongIndexApp, CATEGORY_LABELS};
+use crate::i18n::tr;
+use eframe::egui;
+use egui::color_picker::{color_edit_button_srgba, Alpha};
+
+/// "Farben": changes show at once and are written when the window closes.
+pub(super) struct ThemeState {
+    changed: bool,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_theme(&mut self) {
+        self.theme_window = Some(ThemeState { changed: false });
+    }
+
+    pub(super) fn show_theme(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.theme_window else {
+            return;
+        };
+
+        // Built-in categories first, then any others the library uses.
+        let mut kategorien: Vec<String> =
+            CATEGORY_LABELS.iter().map(|(k, _)| k.to_string()).collect();
+        for group in &self.tags {
+            if !kategorien.contains(&group.kategorie) {
+                kategorien.push(group.kategorie.clone());
+            }
+        }
+
+        let theme = &mut self.config.theme;
+        let mut changed = false;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Farben"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(360.0)
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(tr(
+                    "\u{00C4}nderungen wirken sofort. Die Schrift ist hell, der Hintergrund \
+                     sollte also dunkel bleiben; Fl\u{00E4}chen und R\u{00E4}nder sind \
+                     Abstufungen davon.",
+                ))
+                .size(12.5)
+                .color(palette::TEXT_SECONDARY),
+            );
+            ui.add_space(6.0);
+            egui::Grid::new("theme_grid")
+                .num_columns(3)
+                .spacing([10.0, 6.0])
+                .show(ui, |ui| {
+                    for (label, value, current) in [
+                        ("Akzent", &mut theme.akzent, palette::accent()),
+                        ("Hintergrund", &mut theme.hintergrund, palette::bg_deep()),
+                    ] {
+                        ui.label(egui::RichText::new(tr(label)).color(palette::TEXT_SECONDARY));
+                        let mut color = current;
+                        if color_edit_button_srgba(ui, &mut color, Alpha::Opaque).changed() {
+                            *value = Some(palette::to_hex(color));
+                            changed = true;
+                        }
+                        if value.is_some() && ui.small_button(tr("Standard")).clicked() {
+                            *value = None;
+                            changed = true;
+                        }
+                        ui.end_row();
+                    }
+                });
+            ui.add_space(6.0);
+            ui.separator();
+            ui.label(
+                egui::RichText::new("Tags")
+                    .size(13.0)
+                    .strong()
+                    .color(palette::TEXT_SECONDARY),
+            );
+            egui::Grid::new("theme_tags")
+                .num_columns(3)
+                .spacing([10.0, 6.0])
+                .show(ui, |ui| {
+                    for kategorie in &kategorien {
+                        ui.label(
+                            egui::RichText::new(category_label(kategorie))
+                                .color(palette::TEXT_SECONDARY),
+                        );
+                        let mut color = tag_color(kategorie);
+                        if color_edit_button_srgba(ui, &mut color, Alpha::Opaque).changed() {
+                            theme.tags.insert(kategorie.clone(), palette::to_hex(color));
+                            changed = true;
+                        }
+                        if theme.tags.contains_key(kategorie)
+                            && ui.small_button(tr("Standard")).clicked()
+                        {
+                            theme.tags.remove(kategorie);
+                            changed = true;
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+
+        if changed {
+            palette::load(&self.config.theme);
+            state.changed = true;
+        }
+        if !open {
+            if state.changed {
+                save_config(&self.config);
+            }
+            self.theme_window = None;
+        }
+    }
+}
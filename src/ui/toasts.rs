@@ -0,0 +1,181 @@
+use super::{palette, SongIndexApp};
+use eframe::egui;
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// At most this many toasts are on screen; older ones make room.
+const MAX_SHOWN: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Level {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Level {
+    /// How long a toast stays after it was last shown or hovered.
+    fn duration(self) -> Duration {
+        match self {
+            Level::Info => Duration::from_secs(4),
+            Level::Warning => Duration::from_secs(8),
+            Level::Error => Duration::from_secs(15),
+        }
+    }
+
+    fn color(self) -> egui::Color32 {
+        match self {
+            Level::Info => palette::AUDIO_GREEN,
+            Level::Warning => palette::accent(),
+            Level::Error => palette::ACCENT_RED,
+        }
+    }
+}
+
+struct Toast {
+    level: Level,
+    text: String,
+    /// The same message again while it is shown counts up instead of
+    /// stacking.
+    count: usize,
+    since: Instant,
+}
+
+/// Messages from any thread, waiting for the next frame.
+static PENDING: Mutex<Vec<(Level, String)>> = Mutex::new(Vec::new());
+/// Set once the window exists, to wake it up for a new message.
+static CONTEXT: OnceLock<egui::Context> = OnceLock::new();
+
+/// Show a message in the corner of the window. Works from any thread, also
+/// before the window is open (the message waits for it).
+pub(crate) fn notify(level: Level, text: impl Into<String>) {
+    PENDING.lock().unwrap().push((level, text.into()));
+    if let Some(ctx) = CONTEXT.get() {
+        ctx.request_repaint();
+    }
+}
+
+/// Toasts on screen, newest at the bottom.
+#[derive(Default)]
+pub(super) struct Toasts {
+    shown: VecDeque<Toast>,
+}
+
+impl Toasts {
+    pub(super) fn attach(ctx: &egui::Context) {
+        CONTEXT.set(ctx.clone()).ok();
+    }
+
+    fn take_pending(&mut self) {
+        let now = Instant::now();
+        for (level, text) in std::mem::take(&mut *PENDING.lock().unwrap()) {
+            if let Some(toast) = self
+                .shown
+                .iter_mut()
+                .find(|t| t.level == level && t.text == text)
+            {
+                toast.count += 1;
+                toast.since = now;
+                continue;
+            }
+            self.shown.push_back(Toast {
+                level,
+                text,
+                count: 1,
+                since: now,
+            });
+            if self.shown.len() > MAX_SHOWN {
+                self.shown.pop_front();
+            }
+        }
+    }
+}
+
+impl SongIndexApp {
+    /// Bottom right, above the wake notice if that is open. A toast fades
+    /// after its level's time; hovering keeps it, a click closes it.
+    pub(super) fn show_toasts(&mut self, ctx: &egui::Context) {
+        self.toasts.take_pending();
+        let now = Instant::now();
+        self.toasts
+            .shown
+            .retain(|t| now.duration_since(t.since) < t.level.duration());
+        if self.toasts.shown.is_empty() {
+            return;
+        }
+
+        let bottom = if self.wake_notice.is_some() {
+            -60.0
+        } else {
+            -16.0
+        };
+        let mut hovered = Vec::new();
+        let mut closed = None;
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-16.0, bottom])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                ui.set_max_width(380.0);
+                for (i, toast) in self.toasts.shown.iter().enumerate() {
+                    let frame = egui::Frame::popup(ui.style())
+                        .fill(palette::bg_surface())
+                        .stroke(egui::Stroke::new(1.0, toast.level.color()))
+                        .show(ui, |ui| {
+                            ui.horizontal(|ui| {
+                                let icon = match toast.level {
+                                    Level::Info => "\u{2713}",
+                                    Level::Warning | Level::Error => "\u{26A0}",
+                                };
+                                ui.label(egui::RichText::new(icon).color(toast.level.color()));
+                                let text = if toast.count > 1 {
+                                    format!("{} (\u{00D7}{})", toast.text, toast.count)
+                                } else {
+                                    toast.text.clone()
+                                };
+                                ui.add(
+                                    egui::Label::new(
+                                        egui::RichText::new(text)
+                                            .size(12.5)
+                                            .color(palette::TEXT_PRIMARY),
+                                    )
+                                    .wrap(),
+                                );
+                            });
+                        });
+                    let resp = ui.interact(
+                        frame.response.rect,
+                        egui::Id::new(("toast", i)),
+                        egui::Sense::click(),
+                    );
+                    if resp.clicked() {
+                        closed = Some(i);
+                    }
+                    if resp.hovered() {
+                        hovered.push(i);
+                    }
+                    ui.add_space(4.0);
+                }
+            });
+
+        for i in hovered {
+            self.toasts.shown[i].since = now;
+        }
+        if let Some(i) = closed {
+            self.toasts.shown.remove(i);
+        }
+        if let Some(next) = self
+            .toasts
+            .shown
+            .iter()
+            .map(|t| {
+                t.level
+                    .duration()
+                    .saturating_sub(now.duration_since(t.since))
+            })
+            .min()
+        {
+            ctx.request_repaint_after(next);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use super::palette;
+use crate::db::Song;
+use crate::i18n::{tr, tr_arg, tr_args};
+use crate::transpose::{capo_suggestions, sounding_key, Key};
+use eframe::egui;
+
+/// Inputs of the transposition tool; reset when another song is selected.
+#[derive(Default)]
+pub(super) struct TransposeState {
+    song_id: Option<i64>,
+    kapo: i64,
+    target: Option<Key>,
+}
+
+/// Sounding key for any capo position and capo placements for a target key,
+/// based on the song's stored key (as fingered) and capo.
+pub(super) fn transpose_tool(ui: &mut egui::Ui, state: &mut TransposeState, song: &Song) {
+    let Some(played) = song.tonart.as_deref().and_then(Key::parse) else {
+        return;
+    };
+    if state.song_id != Some(song.id) {
+        *state = TransposeState {
+            song_id: Some(song.id),
+            kapo: song.kapo.unwrap_or(0),
+            target: None,
+        };
+    }
+
+    egui::CollapsingHeader::new(
+        egui::RichText::new(tr("Transponieren"))
+            .size(13.0)
+            .color(palette::TEXT_SECONDARY),
+    )
+    .id_salt("transpose_tool")
+    .show(ui, |ui| {
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new(tr("Kapo:")).color(palette::TEXT_SECONDARY));
+            ui.add(egui::DragValue::new(&mut state.kapo).range(0..=11));
+            ui.label(
+                egui::RichText::new(tr_args(
+                    "{} gegriffen klingt in {}",
+                    &[&played.name(), &sounding_key(played, state.kapo).name()],
+                ))
+                .color(palette::TEXT_PRIMARY),
+            );
+        });
+
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new(tr("Zieltonart:")).color(palette::TEXT_SECONDARY));
+            egui::ComboBox::from_id_salt("transpose_target")
+                .selected_text(
+                    state
+                        .target
+                        .map(Key::name)
+                        .unwrap_or_else(|| "\u{2013}".into()),
+                )
+                .show_ui(ui, |ui| {
+                    for key in Key::all(played.minor) {
+                        ui.selectable_value(&mut state.target, Some(key), key.name());
+                    }
+                });
+        });
+
+        let Some(target) = state.target else {
+            return;
+        };
+        let own = played.distance_to(target);
+        ui.label(
+            egui::RichText::new(tr_args(
+                "Mit den Griffen in {}: Kapo {}",
+                &[&played.name(), &own],
+            ))
+            .size(12.5)
+            .color(palette::TEXT_PRIMARY),
+        );
+        for (shape, kapo) in capo_suggestions(target) {
+            if shape == played {
+                continue;
+            }
+            let text = if kapo == 0 {
+                tr_arg("{}-Griffe ohne Kapo", shape.name())
+            } else {
+                tr_args("{}-Griffe, Kapo {}", &[&shape.name(), &kapo])
+            };
+            ui.label(
+                egui::RichText::new(text)
+                    .size(12.5)
+                    .color(palette::TEXT_SECONDARY),
+            );
+        }
+    });
+}
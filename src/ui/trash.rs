@@ -0,0 +1,152 @@
+use super::pending::Loaded;
+use super::{palette, styled_small_button, SongIndexApp};
+use crate::db::{purge_songs, restore_song, trashed_songs, TrashedSong};
+use crate::i18n::{tr, tr_arg, tr_args};
+use eframe::egui;
+
+pub(super) struct TrashWindowState {
+    songs: Loaded<Vec<TrashedSong>>,
+    /// "Papierkorb leeren" was clicked once and waits for confirmation.
+    confirm_empty: bool,
+}
+
+enum TrashAction {
+    Restore(i64),
+    Purge(i64),
+    Empty,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_trash(&mut self) {
+        self.trash_window = Some(TrashWindowState {
+            songs: Loaded::new(self.db.request(|conn| trashed_songs(conn))),
+            confirm_empty: false,
+        });
+    }
+
+    pub(super) fn show_trash_window(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.trash_window else {
+            return;
+        };
+
+        let mut action = None;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Papierkorb"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([520.0, 380.0])
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(tr(
+                    "Songs, deren Datei verschwunden ist. Tags und Notizen bleiben erhalten; \
+                     taucht die Datei wieder auf, ist der Song automatisch zur\u{00FC}ck.",
+                ))
+                .size(12.5)
+                .color(palette::TEXT_SECONDARY),
+            );
+            ui.add_space(4.0);
+            let Some(songs) = state.songs.get() else {
+                ui.spinner();
+                return;
+            };
+            if songs.is_empty() {
+                ui.label(
+                    egui::RichText::new(tr("Der Papierkorb ist leer.")).color(palette::TEXT_MUTED),
+                );
+                return;
+            }
+
+            ui.horizontal(|ui| {
+                if state.confirm_empty {
+                    ui.label(
+                        egui::RichText::new(tr_arg(
+                            "{} Songs endg\u{00FC}ltig l\u{00F6}schen?",
+                            songs.len(),
+                        ))
+                        .color(palette::ACCENT_RED),
+                    );
+                    if ui.button(tr("L\u{00F6}schen")).clicked() {
+                        action = Some(TrashAction::Empty);
+                    }
+                    if ui.button(tr("Abbrechen")).clicked() {
+                        state.confirm_empty = false;
+                    }
+                } else if ui.button(tr("Papierkorb leeren\u{2026}")).clicked() {
+                    state.confirm_empty = true;
+                }
+            });
+            ui.separator();
+
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    for song in songs.iter() {
+                        ui.horizontal(|ui| {
+                            ui.vertical(|ui| {
+                                let title = match song.artist {
+                                    Some(ref artist) => format!("{} \u{2014} {artist}", song.titel),
+                                    None => song.titel.clone(),
+                                };
+                                ui.label(egui::RichText::new(title).color(palette::TEXT_PRIMARY));
+                                ui.label(
+                                    egui::RichText::new(tr_args(
+                                        "{} \u{00B7} {} Tags \u{00B7} entfernt {}",
+                                        &[
+                                            &song.dateipfad,
+                                            &song.tag_count,
+                                            &self.format.relative(&song.deleted_at),
+                                        ],
+                                    ))
+                                    .size(11.5)
+                                    .color(palette::TEXT_MUTED),
+                                )
+                                .on_hover_text(self.format.date(&song.deleted_at));
+                            });
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    if styled_small_button(ui, tr("L\u{00F6}schen"))
+                                        .on_hover_text(tr("Endg\u{00FC}ltig l\u{00F6}schen"))
+                                        .clicked()
+                                    {
+                                        action = Some(TrashAction::Purge(song.id));
+                                    }
+                                    if styled_small_button(ui, tr("Wiederherstellen"))
+                                        .on_hover_text(tr(
+                                            "Fehlt die Datei noch, landet der Song beim \
+                                             n\u{00E4}chsten Scan wieder hier.",
+                                        ))
+                                        .clicked()
+                                    {
+                                        action = Some(TrashAction::Restore(song.id));
+                                    }
+                                },
+                            );
+                        });
+                        ui.add_space(2.0);
+                    }
+                });
+        });
+
+        if let Some(action) = action {
+            state.songs.reload(self.db.request(move |conn| {
+                match action {
+                    TrashAction::Restore(id) => restore_song(conn, id),
+                    TrashAction::Purge(id) => purge_songs(conn, Some(id)),
+                    TrashAction::Empty => purge_songs(conn, None),
+                }
+                trashed_songs(conn)
+            }));
+            state.confirm_empty = false;
+            self.refresh_data();
+        }
+        if !open {
+            self.trash_window = None;
+        }
+    }
+}
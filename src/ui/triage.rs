@@ -0,0 +1,348 @@
+use super::pending::answer;
+use super::{palette, styled_small_button, tag_color, SongIndexApp};
+use crate::db::{
+    apply_tag, get_song_tags, query_songs, remove_tag_from_song, ExtraFilters, Song,
+    SortMode, TagFilter, TagInfo,
+};
+use crate::i18n::{tr, tr_arg, tr_args};
+use crate::thumbnails::ThumbnailCache;
+use eframe::egui;
+use std::sync::mpsc::Receiver;
+
+/// Shortcut keys for the quick tags, most used tag first.
+const KEYS: &[(egui::Key, &str)] = &[
+    (egui::Key::Num1, "1"),
+    (egui::Key::Num2, "2"),
+    (egui::Key::Num3, "3"),
+    (egui::Key::Num4, "4"),
+    (egui::Key::Num5, "5"),
+    (egui::Key::Num6, "6"),
+    (egui::Key::Num7, "7"),
+    (egui::Key::Num8, "8"),
+    (egui::Key::Num9, "9"),
+    (egui::Key::Num0, "0"),
+    (egui::Key::Q, "Q"),
+    (egui::Key::W, "W"),
+    (egui::Key::E, "E"),
+    (egui::Key::R, "R"),
+    (egui::Key::T, "T"),
+    (egui::Key::Z, "Z"),
+    (egui::Key::U, "U"),
+    (egui::Key::I, "I"),
+    (egui::Key::O, "O"),
+    (egui::Key::P, "P"),
+    (egui::Key::A, "A"),
+    (egui::Key::S, "S"),
+    (egui::Key::D, "D"),
+    (egui::Key::F, "F"),
+    (egui::Key::G, "G"),
+    (egui::Key::H, "H"),
+    (egui::Key::J, "J"),
+    (egui::Key::K, "K"),
+    (egui::Key::L, "L"),
+];
+
+const PREVIEW_HEIGHT: f32 = 360.0;
+
+/// Steps through the songs without hand-set tags one at a time.
+pub(super) struct TriageState {
+    /// Untagged songs when the mode was opened; tagging does not reshuffle it.
+    queue: Vec<Song>,
+    /// The queue, until it is loaded.
+    loading_queue: Option<Receiver<Vec<Song>>>,
+    index: usize,
+    /// Most used tags as (kategorie, wert), in key order.
+    picks: Vec<(String, String)>,
+    /// Tags of the current song, reloaded after each change.
+    current_tags: Vec<TagInfo>,
+    /// Reloaded `current_tags`; keys are ignored until they arrive.
+    loading_tags: Option<Receiver<Vec<TagInfo>>>,
+}
+
+enum TriageAction {
+    Toggle(usize),
+    Next,
+    Previous,
+    Open,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_triage(&mut self) {
+        let loading_queue = self.db.request(|conn| {
+            let extras = ExtraFilters {
+                untagged: true,
+                ..Default::default()
+            };
+            query_songs(conn, "", &TagFilter::none(), &extras, &SortMode::Title)
+        });
+
+        let mut picks: Vec<(i64, String, String)> = self
+            .tags
+            .iter()
+            .filter(|g| g.kategorie != "artist")
+            .flat_map(|g| {
+                g.tags
+                    .iter()
+                    .map(|t| (t.count, g.kategorie.clone(), t.wert.clone()))
+            })
+            .collect();
+        picks.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.2.cmp(&b.2)));
+        let picks = picks
+            .into_iter()
+            .take(KEYS.len())
+            .map(|(_, kategorie, wert)| (kategorie, wert))
+            .collect();
+
+        self.triage = Some(TriageState {
+            queue: Vec::new(),
+            loading_queue: Some(loading_queue),
+            index: 0,
+            picks,
+            current_tags: Vec::new(),
+            loading_tags: None,
+        });
+    }
+
+    /// Keyboard-first tagging: digits and letters toggle the most used tags,
+    /// Enter/→ moves on, ← goes back, Esc closes.
+    pub(super) fn show_triage(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.triage else {
+            return;
+        };
+        if let Some(queue) = answer(&mut state.loading_queue) {
+            state.queue = queue;
+            state.loading_tags = state.queue.first().map(|s| {
+                let song_id = s.id;
+                self.db.request(move |conn| get_song_tags(conn, song_id))
+            });
+        }
+        if let Some(tags) = answer(&mut state.loading_tags) {
+            state.current_tags = tags;
+        }
+
+        let mut action = None;
+        let mut open = true;
+        if !ctx.wants_keyboard_input() {
+            ctx.input_mut(|i| {
+                if i.consume_key(egui::Modifiers::NONE, egui::Key::Escape) {
+                    open = false;
+                } else if i.consume_key(egui::Modifiers::NONE, egui::Key::Enter)
+                    || i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowRight)
+                {
+                    action = Some(TriageAction::Next);
+                } else if i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowLeft) {
+                    action = Some(TriageAction::Previous);
+                } else if let Some(n) = KEYS
+                    .iter()
+                    .take(state.picks.len())
+                    .position(|(key, _)| i.consume_key(egui::Modifiers::NONE, *key))
+                {
+                    action = Some(TriageAction::Toggle(n));
+                }
+            });
+        }
+
+        let cache = self.thumbnails.get_or_insert_with(|| {
+            ThumbnailCache::new(
+                self.base_dir.clone(),
+                self.jobs.clone(),
+                self.config.thumbnail_cache_bytes(),
+                ctx.clone(),
+            )
+        });
+
+        egui::Window::new(
+            egui::RichText::new(tr("Tags vergeben"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .fixed_size([640.0, 460.0])
+        .show(ctx, |ui| {
+            if state.loading_queue.is_some() {
+                ui.centered_and_justified(|ui| ui.spinner());
+                return;
+            }
+            let Some(song) = state.queue.get(state.index) else {
+                ui.label(
+                    egui::RichText::new(tr_arg(
+                        "Fertig \u{2014} alle {} Songs durchgesehen.",
+                        state.queue.len(),
+                    ))
+                    .color(palette::TEXT_SECONDARY),
+                );
+                return;
+            };
+
+            ui.horizontal(|ui| {
+                ui.label(
+                    egui::RichText::new(tr_args(
+                        "{} von {}",
+                        &[&(state.index + 1), &state.queue.len()],
+                    ))
+                    .size(12.5)
+                    .color(palette::TEXT_MUTED),
+                );
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if styled_small_button(ui, tr("Weiter \u{2192}")).clicked() {
+                        action = Some(TriageAction::Next);
+                    }
+                    if styled_small_button(ui, tr("\u{2190} Zur\u{00FC}ck")).clicked() {
+                        action = Some(TriageAction::Previous);
+                    }
+                    if styled_small_button(ui, tr("Datei \u{00F6}ffnen")).clicked() {
+                        action = Some(TriageAction::Open);
+                    }
+                });
+            });
+            ui.separator();
+
+            ui.horizontal_top(|ui| {
+                let size = egui::vec2(PREVIEW_HEIGHT * 0.75, PREVIEW_HEIGHT);
+                match cache.get(&song.dateipfad) {
+                    Some(path) => {
+                        ui.add_sized(
+                            size,
+                            egui::Image::new(format!("file://{}", path.display()))
+                                .maintain_aspect_ratio(true)
+                                .rounding(4.0),
+                        );
+                    }
+                    None => {
+                        let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+                        ui.painter().rect_filled(rect, 4.0, palette::bg_input());
+                    }
+                }
+
+                ui.vertical(|ui| {
+                    ui.label(
+                        egui::RichText::new(&song.titel)
+                            .size(17.0)
+                            .strong()
+                            .color(palette::TEXT_PRIMARY),
+                    );
+                    if let Some(ref artist) = song.artist {
+                        ui.label(
+                            egui::RichText::new(artist)
+                                .size(14.0)
+                                .color(palette::TEXT_SECONDARY),
+                        );
+                    }
+                    ui.label(
+                        egui::RichText::new(&song.dateipfad)
+                            .size(11.5)
+                            .color(palette::TEXT_MUTED),
+                    );
+                    ui.add_space(8.0);
+
+                    if state.picks.is_empty() {
+                        ui.label(
+                            egui::RichText::new(tr(
+                                "Noch keine Tags vorhanden \u{2014} lege zuerst ein paar \
+                                 im Tag-Dialog an.",
+                            ))
+                            .color(palette::TEXT_MUTED),
+                        );
+                    }
+                    egui::ScrollArea::vertical()
+                        .max_height(PREVIEW_HEIGHT - 80.0)
+                        .show(ui, |ui| {
+                            for (n, (kategorie, wert)) in state.picks.iter().enumerate() {
+                                let on = state
+                                    .current_tags
+                                    .iter()
+                                    .any(|t| t.kategorie == *kategorie && t.wert == *wert);
+                                ui.horizontal(|ui| {
+                                    ui.label(
+                                        egui::RichText::new(KEYS[n].1)
+                                            .size(12.0)
+                                            .monospace()
+                                            .color(palette::TEXT_MUTED),
+                                    );
+                                    let (fill, text) = if on {
+                                        (tag_color(kategorie), egui::Color32::WHITE)
+                                    } else {
+                                        (palette::bg_input(), palette::TEXT_SECONDARY)
+                                    };
+                                    let chip = egui::Button::new(
+                                        egui::RichText::new(wert).size(12.0).color(text),
+                                    )
+                                    .fill(fill)
+                                    .rounding(10.0)
+                                    .stroke(egui::Stroke::new(0.5, tag_color(kategorie)));
+                                    if ui.add(chip).on_hover_text(kategorie).clicked() {
+                                        action = Some(TriageAction::Toggle(n));
+                                    }
+                                });
+                            }
+                        });
+                });
+            });
+
+            ui.add_space(4.0);
+            ui.label(
+                egui::RichText::new(tr(
+                    "Tasten 1\u{2013}0 und Buchstaben schalten Tags um \u{00B7} Enter/\u{2192} \
+                     weiter \u{00B7} \u{2190} zur\u{00FC}ck \u{00B7} Esc schlie\u{00DF}t",
+                ))
+                .size(11.5)
+                .color(palette::TEXT_MUTED),
+            );
+        });
+
+        let mut changed_song = None;
+        match action {
+            // Whether the tag is set is only known once the tags are there.
+            Some(TriageAction::Toggle(_)) if state.loading_tags.is_some() => {}
+            Some(TriageAction::Toggle(n)) => {
+                if let (Some(song), Some((kategorie, wert))) =
+                    (state.queue.get(state.index), state.picks.get(n))
+                {
+                    let song_id = song.id;
+                    let tag_id = state
+                        .current_tags
+                        .iter()
+                        .find(|t| t.kategorie == *kategorie && t.wert == *wert)
+                        .map(|t| t.id);
+                    let (kategorie, wert) = (kategorie.clone(), wert.clone());
+                    state.loading_tags = Some(self.db.request(move |conn| {
+                        match tag_id {
+                            Some(tag_id) => remove_tag_from_song(conn, song_id, tag_id),
+                            None => apply_tag(conn, song_id, &kategorie, &wert),
+                        }
+                        get_song_tags(conn, song_id)
+                    }));
+                    changed_song = Some(song_id);
+                }
+            }
+            Some(TriageAction::Next) => {
+                state.index = (state.index + 1).min(state.queue.len());
+            }
+            Some(TriageAction::Previous) => {
+                state.index = state.index.saturating_sub(1);
+            }
+            Some(TriageAction::Open) | None => {}
+        }
+        if matches!(action, Some(TriageAction::Next | TriageAction::Previous)) {
+            state.current_tags.clear();
+            state.loading_tags = state.queue.get(state.index).map(|s| {
+                let song_id = s.id;
+                self.db.request(move |conn| get_song_tags(conn, song_id))
+            });
+        }
+        if let Some(TriageAction::Open) = action {
+            if let Some(song) = state.queue.get(state.index) {
+                let rel_path = song.dateipfad.clone();
+                self.open_song_file(&rel_path);
+            }
+        }
+        if let Some(song_id) = changed_song {
+            self.refresh_song(song_id);
+        }
+        if !open {
+            self.triage = None;
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use super::SongIndexApp;
+use crate::db::SortMode;
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+/// Keystrokes further apart than this start a new search, like Finder.
+const TYPE_SELECT_TIMEOUT: Duration = Duration::from_millis(1000);
+
+#[derive(Default)]
+pub(super) struct TypeSelect {
+    buffer: String,
+    last_key: Option<Instant>,
+}
+
+impl SongIndexApp {
+    /// Type-select: letters typed while the list has the keyboard jump to the
+    /// first song whose title (or artist, when sorted by artist) starts with them.
+    /// The search field and filters are left alone.
+    pub(super) fn handle_type_select(&mut self, ctx: &egui::Context) {
+        if !list_has_keyboard(ctx) {
+            return;
+        }
+        let typed: String = ctx.input(|i| {
+            i.events
+                .iter()
+                .filter_map(|e| match e {
+                    egui::Event::Text(t) => Some(t.as_str()),
+                    _ => None,
+                })
+                .collect()
+        });
+        if typed.is_empty() {
+            return;
+        }
+
+        let now = Instant::now();
+        let state = &mut self.type_select;
+        if state
+            .last_key
+            .is_none_or(|t| now.duration_since(t) > TYPE_SELECT_TIMEOUT)
+        {
+            state.buffer.clear();
+        }
+        state.last_key = Some(now);
+        state.buffer.push_str(&typed.to_lowercase());
+        if state.buffer.trim().is_empty() {
+            state.buffer.clear();
+            return;
+        }
+
+        let prefix = state.buffer.as_str();
+        let by_artist = self.sort_mode == SortMode::Artist;
+        let found = self.songs.iter().find(|s| {
+            if by_artist {
+                s.artist
+                    .as_deref()
+                    .is_some_and(|a| a.to_lowercase().starts_with(prefix))
+            } else {
+                s.titel.to_lowercase().starts_with(prefix)
+            }
+        });
+        if let Some(song) = found {
+            self.selected_song_id = Some(song.id);
+            self.scroll_to_selected = true;
+        }
+    }
+
+    pub(super) fn selected_index(&self) -> Option<usize> {
+        let id = self.selected_song_id?;
+        self.songs.iter().position(|s| s.id == id)
+    }
+}
+
+/// Whether keystrokes belong to the song list: no window or modal is open
+/// (they are all `egui::Window`s, e.g. "Tags vergeben" uses letters and Enter
+/// itself) and no widget has keyboard focus. Toasts, the wake notice and the
+/// overlays are foreground areas and don't count.
+pub(super) fn list_has_keyboard(ctx: &egui::Context) -> bool {
+    let window_open = ctx.memory(|m| {
+        m.areas()
+            .visible_layer_ids()
+            .iter()
+            .any(|layer| layer.order == egui::Order::Middle)
+    });
+    !window_open && !ctx.wants_keyboard_input() && ctx.memory(|m| m.focused().is_none())
+}
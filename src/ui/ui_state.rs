@@ -0,0 +1,208 @@
+use super::refresh::MetaData;
+use super::{SongIndexApp, ViewMode};
+use crate::arrangements::{works, Work};
+use crate::config::Locale;
+use crate::db::{
+    difficulty_levels, get_all_tags, get_stats, get_ui_state, set_ui_state, smart_lists,
+    ExtraFilters, MatchMode, SmartList, SortMode,
+};
+use crate::format::Formatter;
+use crate::worker::Db;
+use eframe::egui;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::mpsc::Receiver;
+
+const WINDOW_KEY: &str = "fenster";
+const LIST_KEY: &str = "liste";
+/// Smaller stored sizes (a window dragged almost shut) are not restored.
+const MIN_SIZE: [f32; 2] = [480.0, 360.0];
+
+/// The main window as it was when the app quit.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(super) struct WindowState {
+    /// Outer top-left corner; unknown on Wayland.
+    position: Option<[f32; 2]>,
+    /// Inner size, without the title bar.
+    groesse: [f32; 2],
+    maximiert: bool,
+}
+
+/// Search, sort, view and filters as they were when the app quit.
+#[derive(Serialize, Deserialize)]
+struct ListState {
+    suche: String,
+    sortierung: SortMode,
+    ansicht: ViewMode,
+    filter_offen: bool,
+    /// Selected tag ids per category.
+    filter: BTreeMap<String, Vec<i64>>,
+    modi: BTreeMap<String, MatchMode>,
+    verknuepfung: MatchMode,
+    extras: ExtraFilters,
+    ausgewaehlt: Option<i64>,
+}
+
+/// What the window needs before it can show the list, loaded on the database
+/// worker while the first frames are drawn.
+pub(super) struct Startup {
+    meta: MetaData,
+    works: HashMap<i64, Work>,
+    smart_lists: Vec<SmartList>,
+    list: Option<ListState>,
+    has_own_tags: bool,
+}
+
+/// Load the startup data. The library's background jobs are queued once the
+/// startup scan is done.
+pub(super) fn load_startup(db: &Db, locale: Locale) -> Receiver<Startup> {
+    db.request(move |conn| Startup {
+        meta: (
+            get_all_tags(conn),
+            difficulty_levels(conn),
+            get_stats(conn),
+            Formatter::load(conn, locale),
+        ),
+        works: works(conn),
+        smart_lists: smart_lists(conn),
+        list: load(conn, LIST_KEY),
+        has_own_tags: crate::taxonomy::has_own_tags(conn),
+    })
+}
+
+fn load<T: for<'de> Deserialize<'de>>(conn: &Connection, key: &str) -> Option<T> {
+    let json = get_ui_state(conn, key)?;
+    serde_json::from_str(&json)
+        .map_err(|e| log::warn!("Ignoring stored {key} state: {e}"))
+        .ok()
+}
+
+fn store<T: Serialize>(conn: &Connection, key: &str, value: &T) {
+    match serde_json::to_string(value) {
+        Ok(json) => set_ui_state(conn, key, &json),
+        Err(e) => log::warn!("Failed to store {key} state: {e}"),
+    }
+}
+
+/// `builder` with the size, position and maximized state of the last session.
+pub fn saved_viewport(conn: &Connection, builder: egui::ViewportBuilder) -> egui::ViewportBuilder {
+    let Some(window) = load::<WindowState>(conn, WINDOW_KEY) else {
+        return builder;
+    };
+    let mut builder = builder.with_maximized(window.maximiert);
+    if window.groesse[0] >= MIN_SIZE[0] && window.groesse[1] >= MIN_SIZE[1] {
+        builder = builder.with_inner_size(window.groesse);
+    }
+    if let Some(position) = window.position {
+        builder = builder.with_position(position);
+    }
+    builder
+}
+
+impl SongIndexApp {
+    /// Remember the window geometry every frame; it is stored on exit.
+    pub(super) fn track_window(&mut self, ctx: &egui::Context) {
+        ctx.input(|i| {
+            let viewport = i.viewport();
+            let maximiert = viewport.maximized.unwrap_or(false);
+            // While maximized, keep the size to go back to.
+            if maximiert {
+                if let Some(ref mut window) = self.window_state {
+                    window.maximiert = true;
+                }
+                return;
+            }
+            if let Some(inner) = viewport.inner_rect {
+                self.window_state = Some(WindowState {
+                    position: viewport.outer_rect.map(|r| r.min.into()),
+                    groesse: inner.size().into(),
+                    maximiert: false,
+                });
+            }
+        });
+    }
+
+    /// Take over the startup data and load the list the way it was left.
+    pub(super) fn apply_startup(&mut self, startup: Startup) {
+        (self.tags, self.difficulty_levels, self.stats, self.format) = startup.meta;
+        self.works = startup.works;
+        self.smart_lists = startup.smart_lists;
+        match startup.list {
+            Some(state) => self.restore_list_state(state),
+            None => self.refresh_songs_only(),
+        }
+        if !self.config.tag_packs_offered && !startup.has_own_tags {
+            self.open_tag_packs();
+        }
+    }
+
+    /// Put the list back the way it was left; filters on tags or smart lists
+    /// that no longer exist are dropped.
+    fn restore_list_state(&mut self, state: ListState) {
+        let exists = |kategorie: &str, id: i64| {
+            self.tags
+                .iter()
+                .any(|g| g.kategorie == kategorie && g.tags.iter().any(|t| t.id == id))
+        };
+        self.active_filters.clear();
+        for (kategorie, ids) in state.filter {
+            let ids: HashSet<i64> = ids
+                .into_iter()
+                .filter(|id| exists(&kategorie, *id))
+                .collect();
+            if !ids.is_empty() {
+                self.active_filters.insert(kategorie, ids);
+            }
+        }
+        self.category_modes = state.modi.into_iter().collect();
+        self.category_join = state.verknuepfung;
+        self.search_text = state.suche;
+        self.sort_mode = state.sortierung;
+        self.view_mode = state.ansicht;
+        self.filters_open = state.filter_offen;
+        self.extras = state.extras;
+        if let Some(id) = self.extras.smart_list {
+            if !self.smart_lists.iter().any(|l| l.id == id) {
+                self.extras.smart_list = None;
+            }
+        }
+        self.refresh_songs_only();
+        self.list_loads.select = state.ausgewaehlt;
+    }
+
+    pub(super) fn save_ui_state(&self) {
+        let window = self.window_state;
+        let list = ListState {
+            suche: self.search_text.clone(),
+            sortierung: self.sort_mode.clone(),
+            ansicht: self.view_mode,
+            filter_offen: self.filters_open,
+            filter: self
+                .active_filters
+                .iter()
+                .map(|(kategorie, ids)| {
+                    let mut ids: Vec<i64> = ids.iter().copied().collect();
+                    ids.sort_unstable();
+                    (kategorie.clone(), ids)
+                })
+                .collect(),
+            modi: self
+                .category_modes
+                .iter()
+                .map(|(kategorie, mode)| (kategorie.clone(), *mode))
+                .collect(),
+            verknuepfung: self.category_join,
+            extras: self.extras.clone(),
+            ausgewaehlt: self.selected_song_id,
+        };
+        // `main` waits for the worker after the window closed, so this lands
+        // before the app quits.
+        self.db.send(move |conn| {
+            if let Some(ref window) = window {
+                store(conn, WINDOW_KEY, window);
+            }
+            store(conn, LIST_KEY, &list);
+        });
+    }
+}
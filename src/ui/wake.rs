@@ -0,0 +1,63 @@
+use super::{palette, styled_small_button, SongIndexApp};
+use crate::i18n::tr_arg;
+use eframe::egui;
+
+/// Changes found by the reconciliation after the machine woke up, shown until
+/// dismissed. Several wakes in a row add up.
+#[derive(Default)]
+pub(super) struct WakeNotice {
+    added: usize,
+    removed: usize,
+}
+
+impl SongIndexApp {
+    pub(super) fn show_wake_notice(&mut self, ctx: &egui::Context) {
+        while let Ok(result) = self.wake_rx.try_recv() {
+            let notice = self.wake_notice.get_or_insert_with(Default::default);
+            notice.added += result.added;
+            notice.removed += result.removed;
+        }
+        let Some(ref notice) = self.wake_notice else {
+            return;
+        };
+
+        let mut parts = Vec::new();
+        if notice.added > 0 {
+            parts.push(tr_arg("{} neu", notice.added));
+        }
+        if notice.removed > 0 {
+            parts.push(tr_arg("{} entfernt", notice.removed));
+        }
+        let mut dismiss = false;
+        notice_area().show(ctx, |ui| {
+            egui::Frame::popup(ui.style())
+                .fill(palette::bg_surface())
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new(tr_arg(
+                                "Nach dem Ruhezustand abgeglichen: {}",
+                                parts.join(", "),
+                            ))
+                            .size(12.5)
+                            .color(palette::TEXT_SECONDARY),
+                        );
+                        if styled_small_button(ui, "OK").clicked() {
+                            dismiss = true;
+                        }
+                    });
+                });
+        });
+        if dismiss {
+            self.wake_notice = None;
+        }
+    }
+}
+
+/// Bottom right, in the foreground like the toasts: a window-level area would
+/// take Enter and type-select from the song list while the notice is up.
+pub(super) fn notice_area() -> egui::Area {
+    egui::Area::new(egui::Id::new("wake_notice"))
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-16.0, -16.0])
+        .order(egui::Order::Foreground)
+}
@@ -0,0 +1,166 @@
+use super::pending::answer;
+use super::{palette, save_config, styled_small_button, SongIndexApp};
+use crate::i18n::tr;
+use crate::warmup::{daily_warmup, technik_values, WarmupItem};
+use eframe::egui;
+use std::sync::mpsc::{Receiver, TryRecvError};
+
+pub(super) struct WarmupWindowState {
+    items: Vec<WarmupItem>,
+    all_techniken: Vec<String>,
+    show_options: bool,
+    /// Items for changed options, until they arrive.
+    reload: Option<Receiver<Vec<WarmupItem>>>,
+}
+
+impl WarmupWindowState {
+    pub(super) fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+impl SongIndexApp {
+    /// Load the warm-up; it opens once loaded.
+    pub(super) fn open_warmup(&mut self) {
+        self.warmup_loading = Some((self.load_warmup(), false));
+    }
+
+    /// Like `open_warmup`, but the warm-up stays closed if it has no songs.
+    pub(super) fn open_warmup_on_startup(&mut self) {
+        self.warmup_loading = Some((self.load_warmup(), true));
+    }
+
+    fn load_warmup(&self) -> Receiver<WarmupWindowState> {
+        let techniken = self.config.warmup_techniken.clone();
+        self.db.request(move |conn| WarmupWindowState {
+            items: daily_warmup(conn, &techniken),
+            all_techniken: technik_values(conn),
+            show_options: false,
+            reload: None,
+        })
+    }
+
+    pub(super) fn show_warmup_window(&mut self, ctx: &egui::Context) {
+        if let Some((rx, skip_empty)) = self.warmup_loading.as_ref() {
+            match rx.try_recv() {
+                Ok(state) => {
+                    if !(*skip_empty && state.is_empty()) {
+                        self.warmup_window = Some(state);
+                    }
+                    self.warmup_loading = None;
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => self.warmup_loading = None,
+            }
+        }
+        let Some(ref mut state) = self.warmup_window else {
+            return;
+        };
+        if let Some(items) = answer(&mut state.reload) {
+            state.items = items;
+        }
+
+        let mut open_file: Option<String> = None;
+        let mut practice: Option<(i64, String)> = None;
+        let mut options_changed = false;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("Aufw\u{00E4}rmen heute"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(420.0)
+        .show(ctx, |ui| {
+            if state.items.is_empty() {
+                ui.label(
+                    egui::RichText::new(tr(
+                        "Keine Songs mit \u{201E}Technik\u{201C}-Tags gefunden.",
+                    ))
+                    .color(palette::TEXT_MUTED),
+                );
+            }
+            egui::Grid::new("warmup_grid")
+                .num_columns(3)
+                .spacing([10.0, 6.0])
+                .show(ui, |ui| {
+                    for item in &state.items {
+                        ui.label(
+                            egui::RichText::new(&item.technik)
+                                .size(12.5)
+                                .color(palette::TAG_TECHNIK),
+                        );
+                        ui.vertical(|ui| {
+                            ui.label(egui::RichText::new(&item.titel).color(palette::TEXT_PRIMARY));
+                            if let Some(ref artist) = item.artist {
+                                ui.label(
+                                    egui::RichText::new(artist)
+                                        .size(12.0)
+                                        .color(palette::TEXT_SECONDARY),
+                                );
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            if styled_small_button(ui, tr("\u{00D6}ffnen")).clicked() {
+                                open_file = Some(item.dateipfad.clone());
+                            }
+                            if styled_small_button(ui, tr("\u{23F1} \u{00DC}ben")).clicked() {
+                                practice = Some((item.song_id, item.titel.clone()));
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+
+            ui.add_space(6.0);
+            ui.separator();
+            ui.checkbox(&mut state.show_options, tr("Optionen"));
+            if state.show_options {
+                if ui
+                    .checkbox(
+                        &mut self.config.warmup_on_startup,
+                        tr("Beim Start anzeigen"),
+                    )
+                    .changed()
+                {
+                    options_changed = true;
+                }
+                ui.label(
+                    egui::RichText::new(tr("Techniken (keine Auswahl = alle):"))
+                        .size(12.5)
+                        .color(palette::TEXT_MUTED),
+                );
+                ui.horizontal_wrapped(|ui| {
+                    for technik in &state.all_techniken {
+                        let mut on = self.config.warmup_techniken.contains(technik);
+                        if ui.checkbox(&mut on, technik).changed() {
+                            if on {
+                                self.config.warmup_techniken.push(technik.clone());
+                            } else {
+                                self.config.warmup_techniken.retain(|t| t != technik);
+                            }
+                            options_changed = true;
+                        }
+                    }
+                });
+            }
+        });
+
+        if options_changed {
+            save_config(&self.config);
+            let techniken = self.config.warmup_techniken.clone();
+            state.reload = Some(self.db.request(move |conn| daily_warmup(conn, &techniken)));
+        }
+        if let Some(rel_path) = open_file {
+            self.open_song_file(&rel_path);
+        }
+        if let Some((song_id, titel)) = practice {
+            self.start_practice(song_id, titel);
+        }
+        if !open {
+            self.warmup_window = None;
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use super::{palette, SongIndexApp};
+use crate::i18n::{tr, tr_args};
+use crate::scanner::WatcherHealth;
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+impl SongIndexApp {
+    /// Warning in the header while the file watcher is down; nothing while it
+    /// runs. Changes made meanwhile are picked up once it is back.
+    pub(super) fn show_watcher_status(&self, ui: &mut egui::Ui) {
+        let health = self.watcher_status.lock().unwrap().clone();
+        let WatcherHealth::Down {
+            error,
+            attempts,
+            retry_at,
+        } = health
+        else {
+            return;
+        };
+        ui.ctx().request_repaint_after(Duration::from_secs(1));
+
+        let wait = retry_at.saturating_duration_since(Instant::now()).as_secs();
+        let retry = if wait == 0 {
+            tr("Neuer Versuch l\u{00E4}uft\u{2026}").to_string()
+        } else {
+            tr_args(
+                "Neuer Versuch in {}\u{00A0}s (Versuch {})",
+                &[&wait, &(attempts + 1)],
+            )
+        };
+        ui.add_space(8.0);
+        ui.label(
+            egui::RichText::new(tr("\u{26A0} \u{00DC}berwachung unterbrochen"))
+                .size(12.0)
+                .color(palette::ACCENT_RED),
+        )
+        .on_hover_text(format!(
+            "{}\n{error}\n{retry}",
+            tr("\u{00C4}nderungen im Musikordner werden gerade nicht erkannt.")
+        ));
+    }
+}
@@ -0,0 +1,186 @@
+use super::pending::{answer, Loaded};
+use super::{palette, save_config, SongIndexApp};
+use crate::config::WebsiteConfig;
+use crate::i18n::{tr, tr_arg, tr_args};
+use crate::site::{generate, public_songs, PublicSong};
+use eframe::egui;
+use std::path::PathBuf;
+use std::sync::mpsc::Receiver;
+
+pub(super) struct WebsiteWindowState {
+    website: WebsiteConfig,
+    /// Songs the site would list with the current settings.
+    count: Loaded<usize>,
+    message: Option<String>,
+    /// Songs, date and folder of the site being built.
+    building: Option<Receiver<(Vec<PublicSong>, String, PathBuf)>>,
+}
+
+impl SongIndexApp {
+    pub(super) fn open_website(&mut self) {
+        let website = self.config.website.clone();
+        let query = website.clone();
+        let count = self
+            .db
+            .request(move |conn| public_songs(conn, &query).len());
+        self.website_window = Some(WebsiteWindowState {
+            website,
+            count: Loaded::new(count),
+            message: None,
+            building: None,
+        });
+    }
+
+    pub(super) fn show_website_window(&mut self, ctx: &egui::Context) {
+        let Some(ref mut state) = self.website_window else {
+            return;
+        };
+        if let Some((songs, stand, dir)) = answer(&mut state.building) {
+            let stand = self.format.date(&stand);
+            state.message = Some(match generate(&dir, &state.website, &songs, &stand) {
+                Ok(()) => tr_args(
+                    "{} Songs nach {} geschrieben",
+                    &[&songs.len(), &dir.display()],
+                ),
+                Err(e) => e,
+            });
+        }
+
+        let mut changed = false;
+        let mut build = false;
+        let mut open = true;
+        egui::Window::new(
+            egui::RichText::new(tr("\u{00D6}ffentliche Seite"))
+                .size(15.0)
+                .color(palette::TEXT_PRIMARY),
+        )
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .fixed_size([440.0, 360.0])
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(tr(
+                    "Eine kleine durchsuchbare Website deiner Songs, z.\u{00A0}B. f\u{00FC}r \
+                     neue Sch\u{00FC}ler. Als privat markierte Songs, Dateien, Notizen und \
+                     Bewertungen bleiben drau\u{00DF}en.",
+                ))
+                .size(12.5)
+                .color(palette::TEXT_SECONDARY),
+            );
+            ui.add_space(6.0);
+            let website = &mut state.website;
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(tr("Titel:")).color(palette::TEXT_SECONDARY));
+                ui.text_edit_singleline(&mut website.titel);
+            });
+            ui.label(egui::RichText::new(tr("Einleitung:")).color(palette::TEXT_SECONDARY));
+            ui.add(
+                egui::TextEdit::multiline(&mut website.einleitung)
+                    .desired_rows(2)
+                    .desired_width(f32::INFINITY),
+            );
+            changed |= ui
+                .checkbox(
+                    &mut website.nur_repertoire,
+                    tr("Nur Repertoire (\u{201E}kann ich\u{201C})"),
+                )
+                .changed();
+
+            ui.add_space(4.0);
+            ui.label(
+                egui::RichText::new(tr("Sichtbare Tag-Kategorien:")).color(palette::TEXT_SECONDARY),
+            );
+            ui.horizontal_wrapped(|ui| {
+                for group in &self.tags {
+                    let mut shown = website.kategorien.contains(&group.kategorie);
+                    if ui.checkbox(&mut shown, &group.kategorie).changed() {
+                        if shown {
+                            website.kategorien.push(group.kategorie.clone());
+                        } else {
+                            website.kategorien.retain(|k| *k != group.kategorie);
+                        }
+                    }
+                }
+            });
+
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(tr("Ordner:")).color(palette::TEXT_SECONDARY));
+                let folder = website
+                    .ausgabe
+                    .as_ref()
+                    .map_or("\u{2013}".to_string(), |p| p.display().to_string());
+                ui.label(
+                    egui::RichText::new(folder)
+                        .size(12.5)
+                        .color(palette::TEXT_MUTED),
+                );
+                if ui.button(tr("W\u{00E4}hlen\u{2026}")).clicked() {
+                    if let Some(dir) = rfd::FileDialog::new()
+                        .set_title(tr("Ordner f\u{00FC}r die Website"))
+                        .pick_folder()
+                    {
+                        website.ausgabe = Some(dir);
+                    }
+                }
+            });
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                let build_btn = egui::Button::new(
+                    egui::RichText::new(tr("Seite erzeugen")).color(palette::TEXT_PRIMARY),
+                )
+                .fill(palette::accent_dim())
+                .rounding(6.0);
+                if ui
+                    .add_enabled(website.ausgabe.is_some(), build_btn)
+                    .clicked()
+                {
+                    build = true;
+                }
+                if state.building.is_some() {
+                    ui.spinner();
+                } else if let Some(count) = state.count.get() {
+                    ui.label(
+                        egui::RichText::new(tr_arg("{} Songs \u{00F6}ffentlich", count))
+                            .size(12.5)
+                            .color(palette::TEXT_MUTED),
+                    );
+                }
+            });
+            if let Some(ref message) = state.message {
+                ui.add_space(4.0);
+                ui.label(
+                    egui::RichText::new(message)
+                        .size(12.5)
+                        .color(palette::TEXT_MUTED),
+                );
+            }
+        });
+
+        if changed {
+            let query = state.website.clone();
+            state.count.reload(
+                self.db
+                    .request(move |conn| public_songs(conn, &query).len()),
+            );
+        }
+        if build {
+            if let Some(dir) = state.website.ausgabe.clone() {
+                let query = state.website.clone();
+                state.building = Some(self.db.request(move |conn| {
+                    let stand: String = conn
+                        .query_row("SELECT date('now', 'localtime')", [], |row| row.get(0))
+                        .unwrap_or_default();
+                    (public_songs(conn, &query), stand, dir)
+                }));
+            }
+            self.config.website = state.website.clone();
+            save_config(&self.config);
+        }
+        if !open {
+            self.website_window = None;
+        }
+    }
+}
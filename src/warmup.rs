@@ -0,0 +1,79 @@
+use rusqlite::{params, Connection};
+
+#[derive(Debug, Clone)]
+pub struct WarmupItem {
+    pub technik: String,
+    pub song_id: i64,
+    pub titel: String,
+    pub artist: Option<String>,
+    pub dateipfad: String,
+}
+
+/// All `technik` tag values that are in use, alphabetically.
+pub fn technik_values(conn: &Connection) -> Vec<String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT DISTINCT t.wert FROM tags t JOIN song_tags st ON st.tag_id = t.id
+             WHERE t.kategorie = 'technik' ORDER BY t.wert",
+        )
+        .unwrap();
+    stmt.query_map([], |row| row.get(0))
+        .unwrap()
+        .filter_map(|r| r.ok())
+        .collect()
+}
+
+/// Today's warm-up: one song per technique. The pick rotates by calendar day,
+/// so it stays the same all day and moves on to the next song tomorrow.
+/// An empty `techniken` list means every technique in use.
+pub fn daily_warmup(conn: &Connection, techniken: &[String]) -> Vec<WarmupItem> {
+    let day: i64 = conn
+        .query_row(
+            "SELECT CAST(julianday('now', 'localtime') AS INTEGER)",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+
+    let techniken = if techniken.is_empty() {
+        technik_values(conn)
+    } else {
+        techniken.to_vec()
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT s.id, s.titel, s.artist, s.dateipfad
+             FROM songs s
+             JOIN song_tags st ON st.song_id = s.id
+             JOIN tags t ON t.id = st.tag_id
+             WHERE t.kategorie = 'technik' AND t.wert = ?1 AND s.deleted_at IS NULL AND s.ignoriert = 0
+             ORDER BY s.titel, s.id",
+        )
+        .unwrap();
+
+    techniken
+        .into_iter()
+        .filter_map(|technik| {
+            let songs: Vec<(i64, String, Option<String>, String)> = stmt
+                .query_map(params![technik], |row| {
+                    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+                })
+                .unwrap()
+                .filter_map(|r| r.ok())
+                .collect();
+            if songs.is_empty() {
+                return None;
+            }
+            let (song_id, titel, artist, dateipfad) =
+                songs[day.rem_euclid(songs.len() as i64) as usize].clone();
+            Some(WarmupItem {
+                technik,
+                song_id,
+                titel,
+                artist,
+                dateipfad,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,66 @@
+use crate::config::data_dir;
+use rodio::{Decoder, Source};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+/// Number of peaks stored per file; the player bar draws one bar per peak.
+pub const PEAKS: usize = 600;
+
+/// Cache file for an audio file, keyed by a hash of its relative path.
+pub fn waveform_path(audio_pfad: &str) -> PathBuf {
+    let hash = Sha256::digest(audio_pfad.as_bytes());
+    let name: String = hash[..10].iter().map(|b| format!("{b:02x}")).collect();
+    data_dir().join("wellenformen").join(format!("{name}.bin"))
+}
+
+/// Decode the whole file and write [`PEAKS`] peak levels (0–255, the loudest
+/// sample of each slice) to `target`.
+pub fn render_waveform(source: &Path, target: &Path) -> bool {
+    let Ok(file) = std::fs::File::open(source) else {
+        return false;
+    };
+    let Ok(decoder) = Decoder::try_from(file) else {
+        return false;
+    };
+    let channels = usize::from(decoder.channels()).max(1);
+    // Peaks of short blocks first, since the length is not always known
+    // up front; they are merged into the final slices afterwards.
+    let block = channels * 256;
+    let mut blocks = Vec::new();
+    let (mut peak, mut n) = (0f32, 0);
+    for sample in decoder {
+        peak = peak.max(sample.abs());
+        n += 1;
+        if n == block {
+            blocks.push(peak);
+            (peak, n) = (0.0, 0);
+        }
+    }
+    if n > 0 {
+        blocks.push(peak);
+    }
+    if blocks.is_empty() {
+        return false;
+    }
+
+    let peaks: Vec<u8> = (0..PEAKS)
+        .map(|i| {
+            let from = i * blocks.len() / PEAKS;
+            let to = ((i + 1) * blocks.len() / PEAKS).max(from + 1);
+            let peak = blocks[from.min(blocks.len() - 1)..to.min(blocks.len())]
+                .iter()
+                .fold(0f32, |a, &b| a.max(b));
+            (peak.min(1.0) * 255.0).round() as u8
+        })
+        .collect();
+    let Some(dir) = target.parent() else {
+        return false;
+    };
+    std::fs::create_dir_all(dir).is_ok() && std::fs::write(target, peaks).is_ok()
+}
+
+/// Cached peaks as 0.0–1.0, or `None` if there are none yet.
+pub fn load_waveform(path: &Path) -> Option<Vec<f32>> {
+    let data = std::fs::read(path).ok()?;
+    (data.len() == PEAKS).then(|| data.iter().map(|&b| f32::from(b) / 255.0).collect())
+}